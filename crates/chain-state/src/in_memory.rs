@@ -17,12 +17,19 @@ use reth_primitives_traits::{
 };
 use reth_storage_api::StateProviderBox;
 use reth_trie::{updates::TrieUpdates, HashedPostState};
-use std::{collections::BTreeMap, sync::Arc, time::Instant};
+use std::{
+    collections::{BTreeMap, VecDeque},
+    sync::Arc,
+    time::Instant,
+};
 use tokio::sync::{broadcast, watch};
 
 /// Size of the broadcast channel used to notify canonical state events.
 const CANON_STATE_NOTIFICATION_CHANNEL_SIZE: usize = 256;
 
+/// Maximum number of recently rejected blocks that are kept around for debugging.
+const BAD_BLOCKS_LIMIT: usize = 10;
+
 /// Metrics for the in-memory state.
 #[derive(Metrics)]
 #[metrics(scope = "blockchain_tree.in_mem_state")]
@@ -139,6 +146,8 @@ pub(crate) struct CanonicalInMemoryStateInner<N: NodePrimitives> {
     pub(crate) in_memory_state: InMemoryState<N>,
     /// A broadcast stream that emits events when the canonical chain is updated.
     pub(crate) canon_state_notification_sender: CanonStateNotificationSender<N>,
+    /// The most recent blocks that failed validation, oldest first.
+    pub(crate) bad_blocks: RwLock<VecDeque<SealedBlock<N::Block>>>,
 }
 
 impl<N: NodePrimitives> CanonicalInMemoryStateInner<N> {
@@ -192,6 +201,7 @@ impl<N: NodePrimitives> CanonicalInMemoryState<N> {
                 chain_info_tracker,
                 in_memory_state,
                 canon_state_notification_sender,
+                bad_blocks: Default::default(),
             }),
         }
     }
@@ -216,6 +226,7 @@ impl<N: NodePrimitives> CanonicalInMemoryState<N> {
             chain_info_tracker,
             in_memory_state,
             canon_state_notification_sender,
+            bad_blocks: Default::default(),
         };
 
         Self { inner: Arc::new(inner) }
@@ -503,6 +514,30 @@ impl<N: NodePrimitives> CanonicalInMemoryState<N> {
         self.inner.chain_info_tracker.subscribe_finalized_block()
     }
 
+    /// Records a block that failed validation.
+    ///
+    /// Only the [`BAD_BLOCKS_LIMIT`] most recent bad blocks are kept, older ones are evicted.
+    pub fn insert_bad_block(&self, block: SealedBlock<N::Block>) {
+        let mut bad_blocks = self.inner.bad_blocks.write();
+        if bad_blocks.iter().any(|bad| bad.hash() == block.hash()) {
+            return
+        }
+        if bad_blocks.len() == BAD_BLOCKS_LIMIT {
+            bad_blocks.pop_front();
+        }
+        bad_blocks.push_back(block);
+    }
+
+    /// Returns the recently recorded bad blocks, most recent first.
+    pub fn bad_blocks(&self) -> Vec<SealedBlock<N::Block>> {
+        self.inner.bad_blocks.read().iter().rev().cloned().collect()
+    }
+
+    /// Returns the recorded bad block with the given hash, if any.
+    pub fn bad_block_by_hash(&self, hash: B256) -> Option<SealedBlock<N::Block>> {
+        self.inner.bad_blocks.read().iter().find(|block| block.hash() == hash).cloned()
+    }
+
     /// Attempts to send a new [`CanonStateNotification`] to all active Receiver handles.
     pub fn notify_canon_state(&self, event: CanonStateNotification<N>) {
         self.inner.canon_state_notification_sender.send(event).ok();
@@ -1158,6 +1193,29 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_bad_blocks() {
+        let state: CanonicalInMemoryState = CanonicalInMemoryState::empty();
+        let mut test_block_builder: TestBlockBuilder = TestBlockBuilder::default();
+        let blocks = test_block_builder
+            .get_executed_blocks(0..BAD_BLOCKS_LIMIT as u64 + 2)
+            .map(|block| block.recovered_block().sealed_block().clone())
+            .collect::<Vec<_>>();
+
+        state.insert_bad_block(blocks[0].clone());
+        state.insert_bad_block(blocks[0].clone());
+        assert_eq!(state.bad_blocks(), vec![blocks[0].clone()]);
+
+        for block in &blocks[1..] {
+            state.insert_bad_block(block.clone());
+        }
+        let bad_blocks = state.bad_blocks();
+        assert_eq!(bad_blocks.len(), BAD_BLOCKS_LIMIT);
+        assert_eq!(bad_blocks.first(), blocks.last());
+        assert_eq!(state.bad_block_by_hash(blocks[0].hash()), None);
+        assert_eq!(state.bad_block_by_hash(blocks[2].hash()), Some(blocks[2].clone()));
+    }
+
     #[test]
     fn test_in_memory_state_impl_state_by_hash() {
         let mut state_by_hash = HashMap::default();
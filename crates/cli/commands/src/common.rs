@@ -90,6 +90,9 @@ impl<C: ChainSpecParser> EnvironmentArgs<C> {
         if config.stages.era.folder.is_none() {
             config.stages.era = config.stages.era.with_datadir(data_dir.data_dir());
         }
+        if config.stages.s3.folder.is_none() {
+            config.stages.s3 = config.stages.s3.with_datadir(data_dir.data_dir());
+        }
 
         info!(target: "reth::cli", ?db_path, ?sf_path, "Opening storage");
         let (db, sfp) = match access {
@@ -16,6 +16,7 @@ mod list;
 mod stats;
 /// DB List TUI
 mod tui;
+mod verify;
 
 /// `reth db` command
 #[derive(Debug, Parser)]
@@ -52,6 +53,8 @@ pub enum Subcommands {
     Version,
     /// Returns the full database path
     Path,
+    /// Verifies cross-table invariants for a range of blocks
+    Verify(verify::Command),
 }
 
 /// `db_ro_exec` opens a database in read-only mode, and then execute with the provided command
@@ -153,6 +156,11 @@ impl<C: ChainSpecParser<ChainSpec: EthChainSpec + EthereumHardforks>> Command<C>
             Subcommands::Path => {
                 println!("{}", db_path.display());
             }
+            Subcommands::Verify(command) => {
+                db_ro_exec!(self.env, tool, N, {
+                    command.execute(&tool)?;
+                });
+            }
         }
 
         Ok(())
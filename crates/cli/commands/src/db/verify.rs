@@ -0,0 +1,653 @@
+use crate::common::CliNodeTypes;
+use alloy_consensus::BlockHeader;
+use alloy_primitives::{keccak256, Address, BlockNumber, B256};
+use clap::{Parser, ValueEnum};
+use reth_chainspec::EthereumHardforks;
+use reth_db::DatabaseEnv;
+use reth_db_api::{
+    cursor::{DbCursorRO, DbDupCursorRO},
+    models::BlockNumberAddress,
+    tables,
+    transaction::DbTx,
+};
+use reth_db_common::DbTool;
+use reth_node_builder::NodeTypesWithDBAdapter;
+use reth_primitives_traits::{SealedHeader, SignedTransaction, SignerRecoverable};
+use reth_provider::{
+    providers::ProviderNodeTypes, BlockBodyIndicesProvider, BlockHashReader, BlockNumReader,
+    DBProvider, HeaderProvider, ProviderFactory, PruneCheckpointReader, ReceiptProvider,
+    StageCheckpointReader, TransactionsProvider,
+};
+use reth_prune::PruneSegment;
+use reth_stages::StageId;
+use reth_trie::HashedPostState;
+use serde::Serialize;
+use std::{
+    collections::BTreeSet,
+    ops::RangeInclusive,
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tracing::{info, warn};
+
+/// Interval between progress logs.
+const LOG_INTERVAL: Duration = Duration::from_secs(10);
+
+#[derive(Parser, Debug)]
+/// The arguments for the `reth db verify` command
+pub struct Command {
+    /// The first block of the range to verify.
+    #[arg(long, default_value_t = 0)]
+    from: BlockNumber,
+
+    /// The last block of the range to verify, the highest block of the database by default.
+    #[arg(long)]
+    to: Option<BlockNumber>,
+
+    /// The checks to run, all checks by default.
+    #[arg(long, value_delimiter = ',')]
+    checks: Vec<Check>,
+
+    /// The maximum number of failures that are reported per check.
+    ///
+    /// Failures past this number are only counted.
+    #[arg(long, default_value_t = 100)]
+    max_failures: usize,
+
+    /// Write the report to this file instead of stdout.
+    #[arg(long, value_name = "PATH")]
+    output: Option<PathBuf>,
+}
+
+/// Invariants that `reth db verify` can check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum Check {
+    /// Headers hash to their canonical hash, link to their parent and are indexed in
+    /// `HeaderNumbers`.
+    Headers,
+    /// `BlockBodyIndices` are contiguous and the transactions they point to exist in static
+    /// files.
+    BodyIndices,
+    /// `TransactionSenders` match the senders recovered from the transaction signatures.
+    Senders,
+    /// Every transaction has a receipt.
+    Receipts,
+    /// `TransactionHashNumbers` has an entry for every transaction hash.
+    TransactionLookup,
+    /// The changesets after the last block revert the current state to the state root of the
+    /// last block, and the plain state of every changed account and storage slot matches the
+    /// hashed state.
+    Changesets,
+}
+
+impl Check {
+    /// Returns the stage whose checkpoint bounds the blocks this check applies to.
+    const fn stage(&self) -> StageId {
+        match self {
+            Self::Headers => StageId::Headers,
+            Self::BodyIndices => StageId::Bodies,
+            Self::Senders => StageId::SenderRecovery,
+            Self::Receipts => StageId::Execution,
+            Self::TransactionLookup => StageId::TransactionLookup,
+            Self::Changesets => StageId::Finish,
+        }
+    }
+
+    /// Returns the prune segments that remove the data this check verifies.
+    const fn prune_segments(&self) -> &'static [PruneSegment] {
+        match self {
+            Self::Headers | Self::BodyIndices => &[],
+            Self::Senders => &[PruneSegment::SenderRecovery],
+            Self::Receipts => &[PruneSegment::Receipts, PruneSegment::ContractLogs],
+            Self::TransactionLookup => &[PruneSegment::TransactionLookup],
+            Self::Changesets => &[
+                PruneSegment::AccountHistory,
+                PruneSegment::StorageHistory,
+                PruneSegment::ChangeSets,
+            ],
+        }
+    }
+}
+
+/// Machine-readable result of `reth db verify`.
+#[derive(Debug, Serialize)]
+pub struct VerifyReport {
+    /// The range of blocks that was requested.
+    pub range: RangeInclusive<BlockNumber>,
+    /// Whether all checks passed.
+    pub ok: bool,
+    /// The results per check.
+    pub checks: Vec<CheckReport>,
+}
+
+/// Result of a single check.
+#[derive(Debug, Serialize)]
+pub struct CheckReport {
+    /// The check.
+    pub check: Check,
+    /// The range of blocks that was verified, `None` if no block of the requested range is
+    /// available for this check.
+    pub verified: Option<RangeInclusive<BlockNumber>>,
+    /// Why blocks of the requested range were skipped, e.g. because they are pruned.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub skipped: Vec<String>,
+    /// The total number of failures.
+    pub failure_count: usize,
+    /// The first failures, limited by `--max-failures`.
+    pub failures: Vec<Failure>,
+}
+
+/// A violated invariant.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Failure {
+    /// The block the failure was found in.
+    pub block: BlockNumber,
+    /// Description of the failure.
+    pub message: String,
+}
+
+impl Command {
+    /// Execute `db verify` command
+    pub fn execute<N: CliNodeTypes<ChainSpec: EthereumHardforks>>(
+        self,
+        tool: &DbTool<NodeTypesWithDBAdapter<N, Arc<DatabaseEnv>>>,
+    ) -> eyre::Result<()> {
+        warn!("This command should be run without the node running!");
+
+        let to = match self.to {
+            Some(to) => to,
+            None => tool.provider_factory.provider()?.last_block_number()?,
+        };
+        let checks = if self.checks.is_empty() {
+            Check::value_variants().to_vec()
+        } else {
+            self.checks.into_iter().collect::<BTreeSet<_>>().into_iter().collect()
+        };
+
+        let report = verify(&tool.provider_factory, self.from..=to, &checks, self.max_failures)?;
+
+        let json = serde_json::to_string_pretty(&report)?;
+        match self.output {
+            Some(path) => {
+                reth_fs_util::write(&path, json)?;
+                info!(target: "reth::cli", ?path, "Wrote verification report");
+            }
+            None => println!("{json}"),
+        }
+
+        eyre::ensure!(report.ok, "Database verification failed");
+        Ok(())
+    }
+}
+
+/// Runs the `checks` on the blocks of `range`.
+pub fn verify<N: ProviderNodeTypes>(
+    factory: &ProviderFactory<N>,
+    range: RangeInclusive<BlockNumber>,
+    checks: &[Check],
+    max_failures: usize,
+) -> eyre::Result<VerifyReport> {
+    let provider = factory.provider()?.disable_long_read_transaction_safety();
+    let snap_synced = provider.is_snap_synced()?;
+
+    let mut reports = Vec::with_capacity(checks.len());
+    for check in checks {
+        let mut report = CheckReport {
+            check: *check,
+            verified: None,
+            skipped: Vec::new(),
+            failure_count: 0,
+            failures: Vec::new(),
+        };
+
+        // Only blocks that the responsible stage has processed and that are not pruned are
+        // verified.
+        let stage_checkpoint =
+            provider.get_stage_checkpoint(check.stage())?.unwrap_or_default().block_number;
+        let mut start = *range.start();
+        let mut end = *range.end();
+        if stage_checkpoint < end {
+            report.skipped.push(format!(
+                "blocks after {stage_checkpoint} are not processed by the {} stage",
+                check.stage()
+            ));
+            end = stage_checkpoint;
+        }
+        for segment in check.prune_segments() {
+            if let Some(pruned) = provider
+                .get_prune_checkpoint(*segment)?
+                .and_then(|checkpoint| checkpoint.block_number)
+            {
+                if pruned >= start {
+                    report.skipped.push(format!("blocks up to {pruned} are pruned by {segment}"));
+                    start = pruned + 1;
+                }
+            }
+        }
+        // The state of a snap synced database was downloaded at the pivot block, so there are no
+        // changesets up to it and the plain state only holds what was executed afterwards.
+        if *check == Check::Changesets && snap_synced {
+            let pivot =
+                provider.get_stage_checkpoint(StageId::SnapSync)?.unwrap_or_default().block_number;
+            if pivot >= start {
+                report.skipped.push(format!("blocks up to {pivot} are snap synced"));
+                start = pivot + 1;
+            }
+            report.skipped.push("plain state is incomplete after snap sync".to_string());
+        }
+
+        if start <= end {
+            report.verified = Some(start..=end);
+        }
+        reports.push(report);
+    }
+
+    let mut verifier = Verifier { reports, max_failures };
+    verifier.verify_blocks(&provider)?;
+    if let Some(report) = verifier.report_mut(Check::Changesets) {
+        if let Some(range) = report.verified.clone() {
+            drop(provider);
+            verifier.verify_changesets(factory, range, !snap_synced)?;
+        }
+    }
+
+    let reports = verifier.reports;
+    Ok(VerifyReport {
+        range,
+        ok: reports.iter().all(|report| report.failure_count == 0),
+        checks: reports,
+    })
+}
+
+/// Collects the failures of the checks.
+struct Verifier {
+    reports: Vec<CheckReport>,
+    max_failures: usize,
+}
+
+impl Verifier {
+    fn report_mut(&mut self, check: Check) -> Option<&mut CheckReport> {
+        self.reports.iter_mut().find(|report| report.check == check)
+    }
+
+    /// Returns true if `check` is run for `block`.
+    fn applies(&self, check: Check, block: BlockNumber) -> bool {
+        self.reports.iter().any(|report| {
+            report.check == check && report.verified.as_ref().is_some_and(|r| r.contains(&block))
+        })
+    }
+
+    fn fail(&mut self, check: Check, block: BlockNumber, message: String) {
+        let max_failures = self.max_failures;
+        let Some(report) = self.report_mut(check) else { return };
+        report.failure_count += 1;
+        if report.failures.len() < max_failures {
+            report.failures.push(Failure { block, message });
+        }
+    }
+
+    /// Runs the per-block checks, going through the blocks once.
+    fn verify_blocks<P>(&mut self, provider: &P) -> eyre::Result<()>
+    where
+        P: DBProvider
+            + HeaderProvider
+            + BlockHashReader
+            + BlockBodyIndicesProvider
+            + TransactionsProvider<Transaction: SignedTransaction>
+            + ReceiptProvider,
+    {
+        let Some((start, end)) = self
+            .reports
+            .iter()
+            .filter(|report| report.check != Check::Changesets)
+            .filter_map(|report| report.verified.as_ref())
+            .map(|range| (*range.start(), *range.end()))
+            .reduce(|(start, end), (s, e)| (start.min(s), end.max(e)))
+        else {
+            return Ok(())
+        };
+
+        let tx = provider.tx_ref();
+        let mut parent_hash = None;
+        let mut next_tx_num = None;
+        if start > 0 {
+            parent_hash = provider.block_hash(start - 1)?;
+            next_tx_num =
+                provider.block_body_indices(start - 1)?.map(|indices| indices.next_tx_num());
+        }
+
+        let mut last_log = Instant::now();
+        for block in start..=end {
+            if last_log.elapsed() > LOG_INTERVAL {
+                info!(target: "reth::cli", block, end, "Verifying blocks");
+                last_log = Instant::now();
+            }
+
+            if self.applies(Check::Headers, block) {
+                match provider.header_by_number(block)? {
+                    Some(header) => {
+                        let header = SealedHeader::seal_slow(header);
+                        let hash = header.hash();
+                        if let Some(canonical) = provider.block_hash(block)? {
+                            if canonical != hash {
+                                self.fail(
+                                    Check::Headers,
+                                    block,
+                                    format!(
+                                        "header hashes to {hash}, canonical hash is {canonical}"
+                                    ),
+                                );
+                            }
+                        } else {
+                            self.fail(Check::Headers, block, "missing canonical hash".to_string());
+                        }
+                        if let Some(parent) = parent_hash {
+                            if header.parent_hash() != parent {
+                                self.fail(
+                                    Check::Headers,
+                                    block,
+                                    format!(
+                                        "parent hash {} does not match hash {parent} of the previous block",
+                                        header.parent_hash()
+                                    ),
+                                );
+                            }
+                        }
+                        match tx.get::<tables::HeaderNumbers>(hash)? {
+                            Some(number) if number == block => {}
+                            Some(number) => self.fail(
+                                Check::Headers,
+                                block,
+                                format!("HeaderNumbers maps {hash} to block {number}"),
+                            ),
+                            None => self.fail(
+                                Check::Headers,
+                                block,
+                                format!("HeaderNumbers is missing {hash}"),
+                            ),
+                        }
+                        parent_hash = Some(hash);
+                    }
+                    None => {
+                        self.fail(Check::Headers, block, "missing header".to_string());
+                        parent_hash = None;
+                    }
+                }
+            }
+
+            let tx_checks =
+                [Check::BodyIndices, Check::Senders, Check::Receipts, Check::TransactionLookup];
+            if !tx_checks.iter().any(|check| self.applies(*check, block)) {
+                continue
+            }
+
+            let Some(indices) = provider.block_body_indices(block)? else {
+                self.fail(Check::BodyIndices, block, "missing block body indices".to_string());
+                next_tx_num = None;
+                continue
+            };
+            if let Some(expected) = next_tx_num {
+                if indices.first_tx_num != expected {
+                    self.fail(
+                        Check::BodyIndices,
+                        block,
+                        format!(
+                            "first transaction number is {}, expected {expected}",
+                            indices.first_tx_num
+                        ),
+                    );
+                }
+            }
+            next_tx_num = Some(indices.next_tx_num());
+
+            let tx_range = indices.tx_num_range();
+            let transactions = provider.transactions_by_tx_range(tx_range.clone())?;
+            if self.applies(Check::BodyIndices, block) &&
+                transactions.len() as u64 != indices.tx_count
+            {
+                self.fail(
+                    Check::BodyIndices,
+                    block,
+                    format!(
+                        "found {} of {} transactions {tx_range:?} in static files",
+                        transactions.len(),
+                        indices.tx_count
+                    ),
+                );
+            }
+
+            if self.applies(Check::Receipts, block) {
+                let receipts = provider.receipts_by_tx_range(tx_range.clone())?;
+                if receipts.len() as u64 != indices.tx_count {
+                    self.fail(
+                        Check::Receipts,
+                        block,
+                        format!(
+                            "found {} receipts for {} transactions",
+                            receipts.len(),
+                            indices.tx_count
+                        ),
+                    );
+                }
+            }
+
+            let check_senders = self.applies(Check::Senders, block);
+            let check_lookup = self.applies(Check::TransactionLookup, block);
+            for (tx_num, transaction) in tx_range.zip(&transactions) {
+                if check_senders {
+                    let stored = tx.get::<tables::TransactionSenders>(tx_num)?;
+                    match (transaction.recover_signer(), stored) {
+                        (Ok(recovered), Some(stored)) if recovered == stored => {}
+                        (Ok(recovered), Some(stored)) => self.fail(
+                            Check::Senders,
+                            block,
+                            format!(
+                                "sender of transaction {tx_num} is {stored}, recovered {recovered}"
+                            ),
+                        ),
+                        (_, None) => self.fail(
+                            Check::Senders,
+                            block,
+                            format!("missing sender of transaction {tx_num}"),
+                        ),
+                        (Err(err), Some(_)) => self.fail(
+                            Check::Senders,
+                            block,
+                            format!("failed to recover sender of transaction {tx_num}: {err}"),
+                        ),
+                    }
+                }
+
+                if check_lookup {
+                    let hash = *transaction.tx_hash();
+                    match tx.get::<tables::TransactionHashNumbers>(hash)? {
+                        Some(number) if number == tx_num => {}
+                        Some(number) => self.fail(
+                            Check::TransactionLookup,
+                            block,
+                            format!(
+                                "TransactionHashNumbers maps {hash} to {number}, expected {tx_num}"
+                            ),
+                        ),
+                        None => self.fail(
+                            Check::TransactionLookup,
+                            block,
+                            format!("TransactionHashNumbers is missing {hash}"),
+                        ),
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks that the changesets after the last block of `range` revert the current state to the
+    /// state root of that block, and if `compare_plain_state` is set, that the plain state of the
+    /// accounts and storage slots changed in `range` matches the hashed state.
+    ///
+    /// The plain state is not compared on snap synced databases, whose plain state lacks every
+    /// account and storage slot that wasn't written by the execution after the pivot block.
+    fn verify_changesets<N: ProviderNodeTypes>(
+        &mut self,
+        factory: &ProviderFactory<N>,
+        range: RangeInclusive<BlockNumber>,
+        compare_plain_state: bool,
+    ) -> eyre::Result<()> {
+        let end = *range.end();
+        info!(target: "reth::cli", block = end, "Reverting state with changesets");
+
+        let expected = factory.header_by_number(end)?.map(|header| header.state_root());
+        let state_root =
+            factory.history_by_block_number(end)?.state_root(HashedPostState::default())?;
+        match expected {
+            Some(expected) if expected == state_root => {}
+            Some(expected) => self.fail(
+                Check::Changesets,
+                end,
+                format!("reverted state root is {state_root}, header state root is {expected}"),
+            ),
+            None => self.fail(Check::Changesets, end, "missing header".to_string()),
+        }
+
+        if !compare_plain_state {
+            return Ok(())
+        }
+
+        info!(target: "reth::cli", ?range, "Comparing plain and hashed state of changed accounts");
+        let provider = factory.provider()?.disable_long_read_transaction_safety();
+        let tx = provider.tx_ref();
+
+        let mut accounts = BTreeSet::<(Address, BlockNumber)>::new();
+        for entry in tx.cursor_read::<tables::AccountChangeSets>()?.walk_range(range.clone())? {
+            let (block, change) = entry?;
+            accounts.insert((change.address, block));
+        }
+        let mut last = None;
+        for (address, block) in accounts {
+            if last.replace(address) == Some(address) {
+                continue
+            }
+            let plain = tx.get::<tables::PlainAccountState>(address)?;
+            let hashed = tx.get::<tables::HashedAccounts>(keccak256(address))?;
+            if plain != hashed {
+                self.fail(
+                    Check::Changesets,
+                    block,
+                    format!(
+                        "plain state of account {address} is {plain:?}, hashed state is {hashed:?}"
+                    ),
+                );
+            }
+        }
+
+        let mut slots = BTreeSet::<(Address, B256, BlockNumber)>::new();
+        let storage_range = BlockNumberAddress::range(range);
+        for entry in tx.cursor_dup_read::<tables::StorageChangeSets>()?.walk_range(storage_range)? {
+            let (key, change) = entry?;
+            slots.insert((key.address(), change.key, key.block_number()));
+        }
+        let mut plain_cursor = tx.cursor_dup_read::<tables::PlainStorageState>()?;
+        let mut hashed_cursor = tx.cursor_dup_read::<tables::HashedStorages>()?;
+        let mut last = None;
+        for (address, slot, block) in slots {
+            if last.replace((address, slot)) == Some((address, slot)) {
+                continue
+            }
+            let plain = plain_cursor
+                .seek_by_key_subkey(address, slot)?
+                .filter(|entry| entry.key == slot)
+                .map(|entry| entry.value);
+            let hashed_slot = keccak256(slot);
+            let hashed = hashed_cursor
+                .seek_by_key_subkey(keccak256(address), hashed_slot)?
+                .filter(|entry| entry.key == hashed_slot)
+                .map(|entry| entry.value);
+            if plain != hashed {
+                self.fail(
+                    Check::Changesets,
+                    block,
+                    format!(
+                        "plain state of slot {slot} of {address} is {plain:?}, hashed state is {hashed:?}"
+                    ),
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_db_api::transaction::DbTxMut;
+    use reth_provider::{
+        test_utils::create_test_provider_factory, BlockWriter, StageCheckpointWriter,
+        StorageLocation,
+    };
+    use reth_stages::StageCheckpoint;
+    use reth_testing_utils::generators::{self, random_block_range, BlockRangeParams};
+
+    const BLOCK_CHECKS: [Check; 4] =
+        [Check::Headers, Check::BodyIndices, Check::Senders, Check::TransactionLookup];
+
+    #[test]
+    fn parse_checks() {
+        let cmd =
+            Command::try_parse_from(["reth", "--checks", "headers,senders", "--to", "10"]).unwrap();
+        assert_eq!(cmd.checks, vec![Check::Headers, Check::Senders]);
+        assert_eq!(cmd.to, Some(10));
+    }
+
+    #[test]
+    fn verify_blocks() {
+        let factory = create_test_provider_factory();
+        let mut rng = generators::rng();
+        let blocks = random_block_range(
+            &mut rng,
+            0..=5,
+            BlockRangeParams { parent: Some(B256::ZERO), tx_count: 1..3, ..Default::default() },
+        );
+
+        let provider = factory.provider_rw().unwrap();
+        for block in &blocks {
+            provider
+                .insert_block(block.clone().try_recover().unwrap(), StorageLocation::Database)
+                .unwrap();
+        }
+        for check in BLOCK_CHECKS {
+            provider.save_stage_checkpoint(check.stage(), StageCheckpoint::new(5)).unwrap();
+        }
+        provider.commit().unwrap();
+
+        let report = verify(&factory, 0..=5, &BLOCK_CHECKS, 100).unwrap();
+        assert!(report.ok, "{report:#?}");
+        assert!(report.checks.iter().all(|check| check.verified == Some(0..=5)));
+
+        // Corrupt the database
+        let provider = factory.provider_rw().unwrap();
+        let tx = provider.tx_ref();
+        tx.delete::<tables::HeaderNumbers>(blocks[2].hash(), None).unwrap();
+        let first_tx_num = provider.block_body_indices(4).unwrap().unwrap().first_tx_num;
+        tx.put::<tables::TransactionSenders>(first_tx_num, Address::ZERO).unwrap();
+        provider.commit().unwrap();
+
+        let report = verify(&factory, 0..=5, &BLOCK_CHECKS, 100).unwrap();
+        assert!(!report.ok);
+        let failures = |check| {
+            report.checks.iter().find(|report| report.check == check).unwrap().failures.clone()
+        };
+        assert_eq!(failures(Check::Headers).iter().map(|f| f.block).collect::<Vec<_>>(), vec![2]);
+        assert_eq!(failures(Check::Senders).iter().map(|f| f.block).collect::<Vec<_>>(), vec![4]);
+        assert!(failures(Check::BodyIndices).is_empty());
+        assert!(failures(Check::TransactionLookup).is_empty());
+
+        // Blocks past the stage checkpoint are skipped
+        let report = verify(&factory, 0..=5, &[Check::Receipts], 100).unwrap();
+        assert_eq!(report.checks[0].verified, Some(0..=0));
+        assert_eq!(report.checks[0].skipped.len(), 1);
+    }
+}
@@ -1,20 +1,41 @@
-use crate::common::EnvironmentArgs;
+use crate::{
+    common::EnvironmentArgs,
+    snapshot::manifest::{
+        chunk_name, hash_chunk, read_chunk, write_chunk, SnapshotComponent, SnapshotManifest,
+        CHUNKS_DIR, MANIFEST_FILE,
+    },
+};
+use alloy_chains::Chain;
+use alloy_primitives::B256;
+use backon::{ExponentialBuilder, Retryable};
 use clap::Parser;
 use eyre::Result;
+use futures::{stream, StreamExt};
 use lz4::Decoder;
 use reqwest::Client;
 use reth_chainspec::{EthChainSpec, EthereumHardforks};
 use reth_cli::chainspec::ChainSpecParser;
+use reth_db::{lockfile::StorageLock, mdbx::DatabaseArguments, open_db};
+use reth_db_api::{
+    database::Database,
+    tables,
+    transaction::{DbTx, DbTxMut},
+};
 use reth_fs_util as fs;
+use reth_node_core::dirs::{ChainPath, DataDirPath};
+use reth_prune::{PruneCheckpoint, PruneMode, PruneSegment};
+use reth_stages::StageId;
 use std::{
+    collections::BTreeSet,
+    fs::OpenOptions,
     io::{self, Read, Write},
-    path::Path,
+    path::{Path, PathBuf},
     sync::Arc,
     time::{Duration, Instant},
 };
 use tar::Archive;
 use tokio::task;
-use tracing::info;
+use tracing::{info, warn};
 
 const BYTE_UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
 const MERKLE_BASE_URL: &str = "https://snapshots.merkle.io";
@@ -36,9 +57,24 @@ pub struct DownloadCommand<C: ChainSpecParser> {
         - https://publicnode.com/snapshots (full nodes & testnets)\n\
         \n\
         If no URL is provided, the latest mainnet archive snapshot\n\
-        will be proposed for download from merkle.io"
+        will be proposed for download from merkle.io\n\
+        \n\
+        If the URL points to a `manifest.json` created by `reth snapshot create`,\n\
+        the snapshot is downloaded in verified chunks instead. An interrupted\n\
+        download resumes where it stopped when the command is run again."
     )]
     url: Option<String>,
+
+    /// Components to download from a snapshot manifest, all components by default.
+    ///
+    /// Only the receipts can be left out. They are then marked as pruned up to the block of the
+    /// snapshot, and the node must be run with receipts pruning, e.g. with `--full`.
+    #[arg(long, value_delimiter = ',')]
+    components: Vec<SnapshotComponent>,
+
+    /// Number of chunks to download in parallel from a snapshot manifest.
+    #[arg(long, default_value_t = 8, value_parser = clap::value_parser!(u64).range(1..))]
+    concurrency: u64,
 }
 
 impl<C: ChainSpecParser<ChainSpec: EthChainSpec + EthereumHardforks>> DownloadCommand<C> {
@@ -55,6 +91,37 @@ impl<C: ChainSpecParser<ChainSpec: EthChainSpec + EthereumHardforks>> DownloadCo
             }
         };
 
+        if url.ends_with(MANIFEST_FILE) {
+            info!(target: "reth::cli",
+                chain = %self.env.chain.chain(),
+                dir = ?data_dir.data_dir(),
+                url = %url,
+                "Starting snapshot download"
+            );
+
+            let skipped_receipts = download_snapshot(
+                &url,
+                &data_dir,
+                self.env.chain.chain(),
+                &self.components,
+                self.concurrency as usize,
+            )
+            .await?;
+            info!(target: "reth::cli", "Snapshot downloaded and verified successfully");
+
+            if skipped_receipts {
+                let db_path = data_dir.db();
+                let db_args = self.env.db.database_args();
+                task::spawn_blocking(move || prune_skipped_receipts(&db_path, db_args)).await??;
+            }
+
+            return Ok(())
+        }
+
+        if !self.components.is_empty() {
+            eyre::bail!("--components is only supported for snapshot manifests")
+        }
+
         info!(target: "reth::cli",
             chain = %self.env.chain.chain(),
             dir = ?data_dir.data_dir(),
@@ -178,6 +245,176 @@ async fn stream_and_extract(url: &str, target_dir: &Path) -> Result<()> {
     Ok(())
 }
 
+/// A chunk of a file of a snapshot.
+#[derive(Debug)]
+struct SnapshotChunk {
+    /// File the chunk belongs to.
+    path: PathBuf,
+    /// Offset of the chunk in the file.
+    offset: u64,
+    /// Length of the chunk.
+    len: u64,
+    /// Sha256 hash of the chunk.
+    hash: B256,
+}
+
+/// Downloads the files of the snapshot manifest at `manifest_url` into the datadir.
+///
+/// Chunks that are already in place are verified and skipped, so an interrupted download resumes
+/// where it stopped and a datadir can be moved to a newer snapshot by fetching only the chunks
+/// that changed.
+///
+/// Returns whether the receipts of the snapshot were left out.
+async fn download_snapshot(
+    manifest_url: &str,
+    data_dir: &ChainPath<DataDirPath>,
+    chain: Chain,
+    components: &[SnapshotComponent],
+    concurrency: usize,
+) -> Result<bool> {
+    let client = Client::new();
+    let manifest: SnapshotManifest = serde_json::from_slice(
+        &client.get(manifest_url).send().await?.error_for_status()?.bytes().await?,
+    )?;
+    manifest.validate()?;
+    if manifest.chain != chain {
+        eyre::bail!("snapshot was created for chain {}, expected {chain}", manifest.chain)
+    }
+
+    let mut skipped = manifest.components();
+    skipped.retain(|component| !components.is_empty() && !components.contains(component));
+    if let Some(component) =
+        skipped.iter().find(|component| **component != SnapshotComponent::Receipts)
+    {
+        eyre::bail!(
+            "component {component:?} of the snapshot can't be left out, only receipts are optional"
+        )
+    }
+    let base_url = manifest_url.trim_end_matches(MANIFEST_FILE);
+
+    // Both locks are held until the download is done, so no node can use the datadir in the
+    // meantime.
+    fs::create_dir_all(data_dir.db())?;
+    fs::create_dir_all(data_dir.static_files())?;
+    let _db_lock = StorageLock::try_acquire(&data_dir.db())?;
+    let _static_files_lock = StorageLock::try_acquire(&data_dir.static_files())?;
+
+    let mut paths = BTreeSet::new();
+    let mut chunks = Vec::new();
+    for file in manifest.files_of(components) {
+        let path = file.target(data_dir)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        // Files are resized upfront, so their chunks can be written in any order.
+        OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(false)
+            .open(&path)
+            .and_then(|f| f.set_len(file.size))
+            .map_err(|err| fs::FsPathError::open(err, &path))?;
+
+        chunks.extend(file.chunks.iter().enumerate().map(|(index, hash)| {
+            let (offset, len) = manifest.chunk_bounds(file, index);
+            SnapshotChunk { path: path.clone(), offset, len, hash: *hash }
+        }));
+        paths.insert(path);
+    }
+
+    let total = chunks.len();
+    let (mut done, mut downloaded) = (0, 0);
+    let mut last_logged = Instant::now();
+    let mut results = stream::iter(chunks)
+        .map(|chunk| download_chunk(&client, base_url, chunk))
+        .buffer_unordered(concurrency);
+    while let Some(result) = results.next().await {
+        done += 1;
+        downloaded += result? as usize;
+        if last_logged.elapsed() >= Duration::from_secs(10) || done == total {
+            info!(target: "reth::cli", done, total, downloaded, "Downloading snapshot chunks");
+            last_logged = Instant::now();
+        }
+    }
+
+    for path in paths {
+        fs::open(&path)?.sync_all()?;
+    }
+
+    Ok(skipped.contains(&SnapshotComponent::Receipts))
+}
+
+/// Marks the receipts up to the block of the downloaded snapshot as pruned, since their static
+/// files were not downloaded.
+fn prune_skipped_receipts(db_path: &Path, db_args: DatabaseArguments) -> Result<()> {
+    let db = open_db(db_path, db_args)?;
+    let tx = db.tx_mut()?;
+    let Some(block) = tx
+        .get::<tables::StageCheckpoints>(StageId::Finish.to_string())?
+        .map(|checkpoint| checkpoint.block_number)
+    else {
+        return Ok(())
+    };
+    let tx_number = tx.get::<tables::BlockBodyIndices>(block)?.map(|indices| indices.last_tx_num());
+
+    tx.put::<tables::PruneCheckpoints>(
+        PruneSegment::Receipts,
+        PruneCheckpoint {
+            block_number: Some(block),
+            tx_number,
+            prune_mode: PruneMode::Before(block + 1),
+        },
+    )?;
+    tx.commit()?;
+
+    warn!(target: "reth::cli",
+        block,
+        "Receipts were not downloaded, the node must be run with receipts pruning, e.g. with --full"
+    );
+    Ok(())
+}
+
+/// Downloads the chunk into its file, unless the file already holds it.
+///
+/// Returns whether the chunk was downloaded.
+async fn download_chunk(client: &Client, base_url: &str, chunk: SnapshotChunk) -> Result<bool> {
+    let SnapshotChunk { path, offset, len, hash } = chunk;
+
+    let existing = {
+        let path = path.clone();
+        task::spawn_blocking(move || -> Result<_> {
+            Ok(read_chunk(&mut fs::open(&path)?, offset, len)?)
+        })
+        .await??
+    };
+    if hash_chunk(&existing) == hash {
+        return Ok(false)
+    }
+
+    let url = format!("{base_url}{CHUNKS_DIR}/{}", chunk_name(&hash));
+    let data = (|| async { client.get(&url).send().await?.error_for_status()?.bytes().await })
+        .retry(ExponentialBuilder::default())
+        .notify(
+            |err, _| warn!(target: "reth::cli", %err, %url, "Failed to download chunk, retrying"),
+        )
+        .await?;
+    if data.len() as u64 != len || hash_chunk(&data) != hash {
+        eyre::bail!("chunk {url} does not match its hash")
+    }
+
+    task::spawn_blocking(move || -> Result<()> {
+        let mut file = OpenOptions::new()
+            .write(true)
+            .open(&path)
+            .map_err(|err| fs::FsPathError::open(err, &path))?;
+        Ok(write_chunk(&mut file, offset, &data)?)
+    })
+    .await??;
+
+    Ok(true)
+}
+
 // Builds default URL for latest mainnet archive  snapshot
 async fn get_latest_snapshot_url() -> Result<String> {
     let latest_url = format!("{MERKLE_BASE_URL}/latest.txt");
@@ -30,6 +30,8 @@ pub struct ExportArgs {
     first_block_number: Option<u64>,
     /// Optional last block number to export from the db.
     /// It is by default 8191.
+    /// It must be before the merge, `.era` files for later blocks cannot be
+    /// created since the beacon blocks and states they hold are not in the database.
     #[arg(long, value_name = "last-block-number", verbatim_doc_comment)]
     last_block_number: Option<u64>,
     /// The maximum number of blocks per file, it can help you to decrease the size of the files.
@@ -77,6 +79,14 @@ impl<C: ChainSpecParser<ChainSpec: EthChainSpec + EthereumHardforks>> ExportEraC
 
         export_config.validate()?;
 
+        // Era1 files only hold pre-merge history
+        if self.env.chain.is_paris_active_at_block(export_config.last_block_number) {
+            eyre::bail!(
+                "Block {} is past the merge, only pre-merge blocks can be exported to era1 files",
+                export_config.last_block_number
+            );
+        }
+
         info!(
             target: "reth::cli",
             "Starting ERA1 block export: blocks {}-{} to {}",
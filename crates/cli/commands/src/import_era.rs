@@ -32,6 +32,10 @@ pub struct ImportArgs {
     /// The path to a directory for import.
     ///
     /// The ERA1 files are read from the local directory parsing headers and bodies.
+    /// Post-merge blocks are read from the execution payloads of the `.era` files in the
+    /// directory, which are imported after the ERA1 files.
+    ///
+    /// The `.era` files are verified against the `checksums_era.txt` of the directory.
     #[arg(long, value_name = "IMPORT_ERA_PATH", verbatim_doc_comment)]
     path: Option<PathBuf>,
 
@@ -25,6 +25,7 @@ pub mod p2p;
 pub mod prune;
 pub mod re_execute;
 pub mod recover;
+pub mod snapshot;
 pub mod stage;
 #[cfg(feature = "arbitrary")]
 pub mod test_vectors;
@@ -0,0 +1,214 @@
+//! `reth snapshot create` command.
+
+use super::manifest::{
+    chunk_name, hash_chunk, read_chunk, SnapshotComponent, SnapshotFile, SnapshotManifest,
+    CHUNKS_DIR, DEFAULT_CHUNK_SIZE, MANIFEST_FILE, MAX_CHUNK_SIZE,
+};
+use crate::common::EnvironmentArgs;
+use clap::Parser;
+use reth_chainspec::EthChainSpec;
+use reth_cli::chainspec::ChainSpecParser;
+use reth_db::{lockfile::StorageLock, version::DB_VERSION_FILE_NAME};
+use reth_fs_util as fs;
+use reth_node_core::dirs::{ChainPath, DataDirPath};
+use reth_static_file_types::StaticFileSegment;
+use std::{
+    fs::File,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use tracing::info;
+
+/// Name of the MDBX data file.
+const MDBX_DATA_FILE: &str = "mdbx.dat";
+
+/// `reth snapshot create` command
+///
+/// The node must be stopped while the snapshot is created.
+#[derive(Debug, Parser)]
+pub struct Command<C: ChainSpecParser> {
+    #[command(flatten)]
+    env: EnvironmentArgs<C>,
+
+    /// Directory to write the snapshot to.
+    ///
+    /// Chunks that are already in the directory, e.g. from a previous snapshot, are reused.
+    #[arg(long, short, value_name = "PATH")]
+    output: PathBuf,
+
+    /// Size of the chunks the files are split into, in bytes.
+    #[arg(long, default_value_t = DEFAULT_CHUNK_SIZE, value_parser = clap::value_parser!(u64).range(1..=MAX_CHUNK_SIZE))]
+    chunk_size: u64,
+}
+
+impl<C: ChainSpecParser<ChainSpec: EthChainSpec>> Command<C> {
+    /// Execute `snapshot create` command
+    pub async fn execute(self) -> eyre::Result<()> {
+        let data_dir = self.env.datadir.clone().resolve_datadir(self.env.chain.chain());
+
+        // Both locks are held until the snapshot is created, so no node can modify the datadir in
+        // the meantime.
+        let _db_lock = StorageLock::try_acquire(&data_dir.db())?;
+        let _static_files_lock = StorageLock::try_acquire(&data_dir.static_files())?;
+
+        let chunks_dir = self.output.join(CHUNKS_DIR);
+        fs::create_dir_all(&chunks_dir)?;
+
+        let mut manifest = SnapshotManifest {
+            chain: self.env.chain.chain(),
+            chunk_size: self.chunk_size,
+            files: Vec::new(),
+        };
+        for (component, path) in snapshot_files(&data_dir)? {
+            let file =
+                chunk_file(component, &component.dir(&data_dir), &path, &manifest, &chunks_dir)?;
+            info!(target: "reth::cli", path = %file.path, chunks = file.chunks.len(), "Added file to snapshot");
+            manifest.files.push(file);
+        }
+
+        // The manifest is written last, so an interrupted run never leaves a snapshot behind that
+        // refers to missing chunks.
+        let manifest_path = self.output.join(MANIFEST_FILE);
+        fs::write(&manifest_path, serde_json::to_vec_pretty(&manifest)?)?;
+        info!(target: "reth::cli", path = ?manifest_path, files = manifest.files.len(), "Snapshot created");
+
+        Ok(())
+    }
+}
+
+impl<C: ChainSpecParser> Command<C> {
+    /// Returns the underlying chain being used to run this command
+    pub fn chain_spec(&self) -> Option<&Arc<C::ChainSpec>> {
+        Some(&self.env.chain)
+    }
+}
+
+/// Returns the files of the datadir that make up a snapshot, relative to the directory of their
+/// component.
+///
+/// Static files are recognized by their name, so unrelated files are skipped.
+fn snapshot_files(
+    data_dir: &ChainPath<DataDirPath>,
+) -> eyre::Result<Vec<(SnapshotComponent, PathBuf)>> {
+    let mut files = [MDBX_DATA_FILE, DB_VERSION_FILE_NAME]
+        .into_iter()
+        .map(|name| (SnapshotComponent::Db, PathBuf::from(name)))
+        .collect::<Vec<_>>();
+
+    let mut static_files = Vec::new();
+    for entry in fs::read_dir(data_dir.static_files())? {
+        let entry = entry?;
+        if !entry.file_type()?.is_file() {
+            continue
+        }
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let data_file =
+            name.strip_suffix(".off").or_else(|| name.strip_suffix(".conf")).unwrap_or(&name);
+        if let Some((segment, _)) = StaticFileSegment::parse_filename(data_file) {
+            static_files.push((SnapshotComponent::from(segment), PathBuf::from(name)));
+        }
+    }
+    static_files.sort_unstable();
+    files.extend(static_files);
+
+    Ok(files)
+}
+
+/// Splits the file into chunks, writes every chunk that is missing into `chunks_dir` and returns
+/// the manifest entry of the file.
+fn chunk_file(
+    component: SnapshotComponent,
+    dir: &Path,
+    path: &Path,
+    manifest: &SnapshotManifest,
+    chunks_dir: &Path,
+) -> eyre::Result<SnapshotFile> {
+    let mut file = File::open(dir.join(path))?;
+    let size = file.metadata()?.len();
+    let chunk_count = size.div_ceil(manifest.chunk_size) as usize;
+    let mut snapshot_file = SnapshotFile {
+        path: path.to_string_lossy().into_owned(),
+        component,
+        size,
+        chunks: Vec::with_capacity(chunk_count),
+    };
+
+    for index in 0..chunk_count {
+        let (offset, len) = manifest.chunk_bounds(&snapshot_file, index);
+        let data = read_chunk(&mut file, offset, len)?;
+        let hash = hash_chunk(&data);
+
+        let chunk_path = chunks_dir.join(chunk_name(&hash));
+        if !chunk_path.exists() {
+            // Written to a temporary file first, so a chunk is never stored partially.
+            let tmp_path = chunk_path.with_extension("tmp");
+            fs::write(&tmp_path, &data)?;
+            fs::rename(&tmp_path, &chunk_path)?;
+        }
+        snapshot_file.chunks.push(hash);
+    }
+
+    Ok(snapshot_file)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_chains::Chain;
+    use reth_node_core::{args::DatadirArgs, dirs::PlatformPath};
+    use std::str::FromStr;
+
+    #[test]
+    fn chunks_datadir() {
+        let dir = tempfile::tempdir().unwrap();
+        let data_dir = ChainPath::new(
+            PlatformPath::<DataDirPath>::from_str(dir.path().to_str().unwrap()).unwrap(),
+            Chain::mainnet(),
+            DatadirArgs::default(),
+        );
+        fs::create_dir_all(data_dir.db()).unwrap();
+        fs::create_dir_all(data_dir.static_files()).unwrap();
+        fs::write(data_dir.db().join(MDBX_DATA_FILE), b"0123456789").unwrap();
+        fs::write(data_dir.db().join(DB_VERSION_FILE_NAME), b"2").unwrap();
+        fs::write(data_dir.db().join("mdbx.lck"), b"").unwrap();
+        for name in [
+            "static_file_headers_0_499999",
+            "static_file_headers_0_499999.off",
+            "static_file_headers_0_499999.conf",
+            "static_file_receipts_0_499999",
+            "unrelated",
+        ] {
+            fs::write(data_dir.static_files().join(name), b"01234567").unwrap();
+        }
+
+        let files = snapshot_files(&data_dir).unwrap();
+        assert_eq!(
+            files,
+            vec![
+                (SnapshotComponent::Db, PathBuf::from(MDBX_DATA_FILE)),
+                (SnapshotComponent::Db, PathBuf::from(DB_VERSION_FILE_NAME)),
+                (SnapshotComponent::Headers, PathBuf::from("static_file_headers_0_499999")),
+                (SnapshotComponent::Headers, PathBuf::from("static_file_headers_0_499999.conf")),
+                (SnapshotComponent::Headers, PathBuf::from("static_file_headers_0_499999.off")),
+                (SnapshotComponent::Receipts, PathBuf::from("static_file_receipts_0_499999")),
+            ]
+        );
+
+        let chunks_dir = dir.path().join(CHUNKS_DIR);
+        fs::create_dir_all(&chunks_dir).unwrap();
+        let manifest = SnapshotManifest { chain: Chain::mainnet(), chunk_size: 4, files: vec![] };
+        let file = chunk_file(
+            SnapshotComponent::Db,
+            &data_dir.db(),
+            Path::new(MDBX_DATA_FILE),
+            &manifest,
+            &chunks_dir,
+        )
+        .unwrap();
+        assert_eq!(file.size, 10);
+        assert_eq!(file.chunks, vec![hash_chunk(b"0123"), hash_chunk(b"4567"), hash_chunk(b"89")]);
+        for (hash, data) in file.chunks.iter().zip([&b"0123"[..], b"4567", b"89"]) {
+            assert_eq!(fs::read(chunks_dir.join(chunk_name(hash))).unwrap(), data);
+        }
+    }
+}
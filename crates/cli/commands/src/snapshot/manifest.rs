@@ -0,0 +1,286 @@
+//! Snapshot manifest, shared by `reth snapshot create` and `reth download`.
+//!
+//! A snapshot is a directory that holds a [`MANIFEST_FILE`] and a [`CHUNKS_DIR`]. The manifest
+//! lists every file of the datadir that is part of the snapshot, split into chunks of
+//! [`SnapshotManifest::chunk_size`] bytes. Chunks are stored in the chunks directory under the hex
+//! encoded sha256 hash of their content, so identical chunks of consecutive snapshots are only
+//! stored and downloaded once.
+
+use alloy_chains::Chain;
+use alloy_primitives::{hex::ToHexExt, B256};
+use clap::ValueEnum;
+use reth_node_core::dirs::{ChainPath, DataDirPath};
+use reth_static_file_types::StaticFileSegment;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::BTreeSet,
+    fs::File,
+    io::{Read, Seek, SeekFrom, Write},
+    path::{Component, Path, PathBuf},
+};
+
+/// Name of the manifest file of a snapshot.
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// Name of the directory of a snapshot that holds the chunks.
+pub const CHUNKS_DIR: &str = "chunks";
+
+/// Default size of the chunks files are split into: 64 MiB.
+pub const DEFAULT_CHUNK_SIZE: u64 = 64 * 1024 * 1024;
+
+/// Maximum size of a chunk: 1 GiB.
+///
+/// Chunks are held in memory while they are downloaded and verified.
+pub const MAX_CHUNK_SIZE: u64 = 1024 * 1024 * 1024;
+
+/// Describes the files of a snapshot and the chunks they are made of.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotManifest {
+    /// Chain the snapshot was created for.
+    pub chain: Chain,
+    /// Size of every chunk in bytes, only the last chunk of a file can be smaller.
+    pub chunk_size: u64,
+    /// Files of the snapshot.
+    pub files: Vec<SnapshotFile>,
+}
+
+impl SnapshotManifest {
+    /// Checks that the chunk size is within bounds and that every file has exactly the chunks its
+    /// size requires.
+    ///
+    /// Manifests from untrusted sources must be validated before their chunks are used.
+    pub fn validate(&self) -> eyre::Result<()> {
+        if self.chunk_size == 0 || self.chunk_size > MAX_CHUNK_SIZE {
+            eyre::bail!(
+                "invalid chunk size {} in snapshot manifest, expected 1..={MAX_CHUNK_SIZE}",
+                self.chunk_size
+            )
+        }
+        for file in &self.files {
+            let expected = file.size.div_ceil(self.chunk_size);
+            if file.chunks.len() as u64 != expected {
+                eyre::bail!(
+                    "file {} of snapshot manifest has {} chunks, expected {expected}",
+                    file.path,
+                    file.chunks.len()
+                )
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the components that have files in the snapshot.
+    pub fn components(&self) -> BTreeSet<SnapshotComponent> {
+        self.files.iter().map(|file| file.component).collect()
+    }
+
+    /// Returns the files that belong to any of the given components.
+    ///
+    /// All files are returned if no component is given.
+    pub fn files_of<'a>(
+        &'a self,
+        components: &'a [SnapshotComponent],
+    ) -> impl Iterator<Item = &'a SnapshotFile> + 'a {
+        self.files
+            .iter()
+            .filter(|file| components.is_empty() || components.contains(&file.component))
+    }
+
+    /// Returns the offset and length of the chunk at `index` of the given file.
+    ///
+    /// Chunks past the end of the file are empty, see [`SnapshotManifest::validate`].
+    pub fn chunk_bounds(&self, file: &SnapshotFile, index: usize) -> (u64, u64) {
+        let offset = (index as u64).saturating_mul(self.chunk_size).min(file.size);
+        (offset, (file.size - offset).min(self.chunk_size))
+    }
+}
+
+/// A file of a snapshot.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotFile {
+    /// Path of the file relative to the directory of its component, using `/` as separator.
+    pub path: String,
+    /// Component the file belongs to.
+    pub component: SnapshotComponent,
+    /// Size of the file in bytes.
+    pub size: u64,
+    /// Sha256 hashes of the chunks of the file, in order.
+    pub chunks: Vec<B256>,
+}
+
+impl SnapshotFile {
+    /// Returns the location of the file inside the given datadir.
+    ///
+    /// Fails if the path of the file could point outside of the directory of its component.
+    pub fn target(&self, datadir: &ChainPath<DataDirPath>) -> eyre::Result<PathBuf> {
+        let path = Path::new(&self.path);
+        if path.as_os_str().is_empty() ||
+            !path.components().all(|component| matches!(component, Component::Normal(_)))
+        {
+            eyre::bail!("invalid path in snapshot manifest: {}", self.path)
+        }
+        Ok(self.component.dir(datadir).join(path))
+    }
+}
+
+/// Parts of the datadir a snapshot can be downloaded for.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, ValueEnum,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum SnapshotComponent {
+    /// The MDBX database.
+    Db,
+    /// Static files of the headers segment.
+    Headers,
+    /// Static files of the transactions segment.
+    Transactions,
+    /// Static files of the receipts segment.
+    Receipts,
+    /// Static files of the block meta segment.
+    BlockMeta,
+}
+
+impl SnapshotComponent {
+    /// Returns the directory of the datadir that holds the files of the component.
+    pub fn dir(&self, datadir: &ChainPath<DataDirPath>) -> PathBuf {
+        match self {
+            Self::Db => datadir.db(),
+            Self::Headers | Self::Transactions | Self::Receipts | Self::BlockMeta => {
+                datadir.static_files()
+            }
+        }
+    }
+}
+
+impl From<StaticFileSegment> for SnapshotComponent {
+    fn from(segment: StaticFileSegment) -> Self {
+        match segment {
+            StaticFileSegment::Headers => Self::Headers,
+            StaticFileSegment::Transactions => Self::Transactions,
+            StaticFileSegment::Receipts => Self::Receipts,
+            StaticFileSegment::BlockMeta => Self::BlockMeta,
+        }
+    }
+}
+
+/// Returns the sha256 hash of a chunk.
+pub fn hash_chunk(data: &[u8]) -> B256 {
+    B256::from(<[u8; 32]>::from(Sha256::digest(data)))
+}
+
+/// Returns the name of the chunk with the given hash inside the [`CHUNKS_DIR`].
+pub fn chunk_name(hash: &B256) -> String {
+    hash.encode_hex()
+}
+
+/// Reads `len` bytes at `offset` of the file.
+pub fn read_chunk(file: &mut File, offset: u64, len: u64) -> std::io::Result<Vec<u8>> {
+    let mut data = vec![0; len as usize];
+    file.seek(SeekFrom::Start(offset))?;
+    file.read_exact(&mut data)?;
+    Ok(data)
+}
+
+/// Writes the chunk at `offset` of the file.
+pub fn write_chunk(file: &mut File, offset: u64, data: &[u8]) -> std::io::Result<()> {
+    file.seek(SeekFrom::Start(offset))?;
+    file.write_all(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_node_core::dirs::PlatformPath;
+    use std::str::FromStr;
+
+    fn file(path: &str, component: SnapshotComponent, size: u64) -> SnapshotFile {
+        SnapshotFile { path: path.to_string(), component, size, chunks: Vec::new() }
+    }
+
+    #[test]
+    fn manifest_roundtrip() {
+        let manifest = SnapshotManifest {
+            chain: Chain::mainnet(),
+            chunk_size: 4,
+            files: vec![SnapshotFile {
+                chunks: vec![hash_chunk(b"reth")],
+                ..file("static_file_headers_0_499999", SnapshotComponent::Headers, 4)
+            }],
+        };
+        let json = serde_json::to_string(&manifest).unwrap();
+        assert!(json.contains(r#""component":"headers""#));
+        assert_eq!(serde_json::from_str::<SnapshotManifest>(&json).unwrap(), manifest);
+    }
+
+    #[test]
+    fn chunk_bounds() {
+        let manifest = SnapshotManifest { chain: Chain::mainnet(), chunk_size: 4, files: vec![] };
+        let file = file("mdbx.dat", SnapshotComponent::Db, 10);
+        assert_eq!(manifest.chunk_bounds(&file, 0), (0, 4));
+        assert_eq!(manifest.chunk_bounds(&file, 2), (8, 2));
+        assert_eq!(manifest.chunk_bounds(&file, 3), (10, 0));
+    }
+
+    #[test]
+    fn validate_manifest() {
+        let mut manifest = SnapshotManifest {
+            chain: Chain::mainnet(),
+            chunk_size: 4,
+            files: vec![SnapshotFile {
+                chunks: vec![B256::ZERO; 3],
+                ..file("mdbx.dat", SnapshotComponent::Db, 10)
+            }],
+        };
+        assert!(manifest.validate().is_ok());
+
+        manifest.files[0].chunks.pop();
+        assert!(manifest.validate().is_err());
+        manifest.files[0].chunks.extend([B256::ZERO; 2]);
+        assert!(manifest.validate().is_err());
+
+        manifest.files.clear();
+        manifest.chunk_size = 0;
+        assert!(manifest.validate().is_err());
+        manifest.chunk_size = MAX_CHUNK_SIZE + 1;
+        assert!(manifest.validate().is_err());
+    }
+
+    #[test]
+    fn files_of_components() {
+        let manifest = SnapshotManifest {
+            chain: Chain::mainnet(),
+            chunk_size: 4,
+            files: vec![
+                file("mdbx.dat", SnapshotComponent::Db, 0),
+                file("static_file_receipts_0_499999", SnapshotComponent::Receipts, 0),
+            ],
+        };
+        assert_eq!(manifest.files_of(&[]).count(), 2);
+        let files = manifest.files_of(&[SnapshotComponent::Db]).collect::<Vec<_>>();
+        assert_eq!(files, vec![&manifest.files[0]]);
+    }
+
+    #[test]
+    fn target_stays_in_datadir() {
+        let datadir = ChainPath::new(
+            PlatformPath::<DataDirPath>::from_str("/datadir").unwrap(),
+            Chain::mainnet(),
+            Default::default(),
+        );
+        assert_eq!(
+            file("mdbx.dat", SnapshotComponent::Db, 0).target(&datadir).unwrap(),
+            datadir.db().join("mdbx.dat")
+        );
+        assert_eq!(
+            file("static_file_headers_0_499999", SnapshotComponent::Headers, 0)
+                .target(&datadir)
+                .unwrap(),
+            datadir.static_files().join("static_file_headers_0_499999")
+        );
+        for path in ["", "../mdbx.dat", "/etc/passwd", "db/../../mdbx.dat", "./mdbx.dat"] {
+            assert!(file(path, SnapshotComponent::Db, 0).target(&datadir).is_err(), "{path}");
+        }
+    }
+}
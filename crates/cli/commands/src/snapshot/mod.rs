@@ -0,0 +1,41 @@
+//! `reth snapshot` command.
+
+use clap::{Parser, Subcommand};
+use reth_chainspec::EthChainSpec;
+use reth_cli::chainspec::ChainSpecParser;
+use std::sync::Arc;
+
+mod create;
+pub mod manifest;
+
+/// `reth snapshot` command
+#[derive(Debug, Parser)]
+pub struct Command<C: ChainSpecParser> {
+    #[command(subcommand)]
+    command: Subcommands<C>,
+}
+
+/// `reth snapshot` subcommands
+#[derive(Subcommand, Debug)]
+pub enum Subcommands<C: ChainSpecParser> {
+    /// Create a snapshot of a stopped node that can be downloaded with `reth download`.
+    Create(create::Command<C>),
+}
+
+impl<C: ChainSpecParser<ChainSpec: EthChainSpec>> Command<C> {
+    /// Execute `snapshot` command
+    pub async fn execute(self) -> eyre::Result<()> {
+        match self.command {
+            Subcommands::Create(command) => command.execute().await,
+        }
+    }
+}
+
+impl<C: ChainSpecParser> Command<C> {
+    /// Returns the underlying chain being used to run this command
+    pub fn chain_spec(&self) -> Option<&Arc<C::ChainSpec>> {
+        match &self.command {
+            Subcommands::Create(command) => command.chain_spec(),
+        }
+    }
+}
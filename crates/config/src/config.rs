@@ -1,6 +1,6 @@
 //! Configuration files.
 use reth_network_types::{PeersConfig, SessionsConfig};
-use reth_prune_types::PruneModes;
+use reth_prune_types::{PruneBudget, PruneModes};
 use reth_stages_types::ExecutionStageThresholds;
 use std::{
     path::{Path, PathBuf},
@@ -103,6 +103,8 @@ impl Config {
 pub struct StageConfig {
     /// ERA stage configuration.
     pub era: EraConfig,
+    /// S3 stage configuration.
+    pub s3: S3Config,
     /// Header stage configuration.
     pub headers: HeadersConfig,
     /// Body stage configuration.
@@ -169,6 +171,36 @@ impl EraConfig {
     }
 }
 
+/// S3 stage configuration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct S3Config {
+    /// The base URL of an HTTP or S3 mirror serving static files.
+    ///
+    /// The headers, transactions, block meta and receipts segments are downloaded. The stage is
+    /// disabled if this is not set.
+    pub url: Option<Url>,
+    /// Maximum number of concurrent requests used to download a single file.
+    pub max_concurrent_requests: u64,
+    /// Path to a directory where files downloaded from `url` will be stored until processed.
+    pub folder: Option<PathBuf>,
+}
+
+impl Default for S3Config {
+    fn default() -> Self {
+        Self { url: None, max_concurrent_requests: 8, folder: None }
+    }
+}
+
+impl S3Config {
+    /// Sets `folder` for temporary downloads as a directory called "s3" inside `dir`.
+    pub fn with_datadir(mut self, dir: impl AsRef<Path>) -> Self {
+        self.folder = Some(dir.as_ref().join("s3"));
+        self
+    }
+}
+
 /// Header stage configuration.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -436,11 +468,15 @@ pub struct PruneConfig {
     /// Pruning configuration for every part of the data that can be pruned.
     #[cfg_attr(feature = "serde", serde(alias = "parts"))]
     pub segments: PruneModes,
+    /// Disk budget that the pruner keeps the data directory within, by pruning the segments
+    /// beyond their configured distances.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub budget: Option<PruneBudget>,
 }
 
 impl Default for PruneConfig {
     fn default() -> Self {
-        Self { block_interval: DEFAULT_BLOCK_INTERVAL, segments: PruneModes::none() }
+        Self { block_interval: DEFAULT_BLOCK_INTERVAL, segments: PruneModes::none(), budget: None }
     }
 }
 
@@ -464,8 +500,12 @@ impl PruneConfig {
                     account_history,
                     storage_history,
                     bodies_history,
+                    changesets,
+                    block_meta,
+                    bytecodes,
                     receipts_log_filter,
                 },
+            budget,
         } = other;
 
         // Merge block_interval, only update if it's the default interval
@@ -480,10 +520,15 @@ impl PruneConfig {
         self.segments.account_history = self.segments.account_history.or(account_history);
         self.segments.storage_history = self.segments.storage_history.or(storage_history);
         self.segments.bodies_history = self.segments.bodies_history.or(bodies_history);
+        self.segments.changesets = self.segments.changesets.or(changesets);
+        self.segments.block_meta = self.segments.block_meta.or(block_meta);
+        self.segments.bytecodes = self.segments.bytecodes.or(bytecodes);
 
         if self.segments.receipts_log_filter.0.is_empty() && !receipts_log_filter.0.is_empty() {
             self.segments.receipts_log_filter = receipts_log_filter;
         }
+
+        self.budget = self.budget.take().or(budget);
     }
 }
 
@@ -512,7 +557,7 @@ mod tests {
     use crate::PruneConfig;
     use alloy_primitives::Address;
     use reth_network_peers::TrustedPeer;
-    use reth_prune_types::{PruneMode, PruneModes, ReceiptsLogPruneConfig};
+    use reth_prune_types::{PruneBudget, PruneMode, PruneModes, ReceiptsLogPruneConfig};
     use std::{collections::BTreeMap, path::Path, str::FromStr, time::Duration};
 
     fn with_tempdir(filename: &str, proc: fn(&std::path::Path)) {
@@ -1001,11 +1046,15 @@ receipts = 'full'
                 account_history: None,
                 storage_history: Some(PruneMode::Before(5000)),
                 bodies_history: None,
+                changesets: Some(PruneMode::Distance(20_000)),
+                block_meta: None,
+                bytecodes: None,
                 receipts_log_filter: ReceiptsLogPruneConfig(BTreeMap::from([(
                     Address::random(),
                     PruneMode::Full,
                 )])),
             },
+            budget: None,
         };
 
         let config2 = PruneConfig {
@@ -1017,11 +1066,15 @@ receipts = 'full'
                 account_history: Some(PruneMode::Distance(2000)),
                 storage_history: Some(PruneMode::Distance(3000)),
                 bodies_history: None,
+                changesets: Some(PruneMode::Distance(30_000)),
+                block_meta: None,
+                bytecodes: Some(PruneMode::Before(6000)),
                 receipts_log_filter: ReceiptsLogPruneConfig(BTreeMap::from([
                     (Address::random(), PruneMode::Distance(1000)),
                     (Address::random(), PruneMode::Before(2000)),
                 ])),
             },
+            budget: Some(PruneBudget::new(1000)),
         };
 
         let original_filter = config1.segments.receipts_log_filter.clone();
@@ -1035,7 +1088,10 @@ receipts = 'full'
         assert_eq!(config1.segments.receipts, Some(PruneMode::Distance(1000)));
         assert_eq!(config1.segments.account_history, Some(PruneMode::Distance(2000)));
         assert_eq!(config1.segments.storage_history, Some(PruneMode::Before(5000)));
+        assert_eq!(config1.segments.changesets, Some(PruneMode::Distance(20_000)));
+        assert_eq!(config1.segments.bytecodes, Some(PruneMode::Before(6000)));
         assert_eq!(config1.segments.receipts_log_filter, original_filter);
+        assert_eq!(config1.budget, Some(PruneBudget::new(1000)));
     }
 
     #[test]
@@ -173,7 +173,9 @@ where
                 RpcServerArgs::default()
                     .with_unused_ports()
                     .with_http()
-                    .with_http_api(RpcModuleSelection::All),
+                    .with_http_api(RpcModuleSelection::All)
+                    .with_ws()
+                    .with_ws_api(RpcModuleSelection::All),
             )
             .set_dev(is_dev);
 
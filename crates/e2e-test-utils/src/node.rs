@@ -296,6 +296,12 @@ where
         format!("http://{addr}").parse().unwrap()
     }
 
+    /// Returns the WS RPC URL.
+    pub fn ws_url(&self) -> Url {
+        let addr = self.inner.rpc_server_handle().ws_local_addr().unwrap();
+        format!("ws://{addr}").parse().unwrap()
+    }
+
     /// Returns an RPC client.
     pub fn rpc_client(&self) -> Option<HttpClient> {
         self.inner.rpc_server_handle().http_client()
@@ -230,7 +230,7 @@ where
         if let Some(healthy_node_client) = &self.healthy_node_client {
             // Compare the witness against the healthy node.
             let healthy_node_witness = futures::executor::block_on(async move {
-                DebugApiClient::<()>::debug_execution_witness(
+                DebugApiClient::<(), ()>::debug_execution_witness(
                     healthy_node_client,
                     block.number().into(),
                 )
@@ -4,7 +4,8 @@ use alloy_consensus::BlockHeader;
 use alloy_primitives::{TxHash, B256};
 use alloy_rpc_types_engine::ForkchoiceState;
 use eyre::OptionExt;
-use futures_util::{stream::Fuse, StreamExt};
+use futures_util::{future, stream::Fuse, StreamExt};
+use parking_lot::Mutex;
 use reth_engine_primitives::BeaconConsensusEngineHandle;
 use reth_payload_builder::PayloadBuilderHandle;
 use reth_payload_primitives::{
@@ -15,10 +16,14 @@ use reth_transaction_pool::TransactionPool;
 use std::{
     future::Future,
     pin::Pin,
+    sync::Arc,
     task::{Context, Poll},
     time::{Duration, UNIX_EPOCH},
 };
-use tokio::time::Interval;
+use tokio::{
+    sync::{mpsc, oneshot},
+    time::Interval,
+};
 use tokio_stream::wrappers::ReceiverStream;
 use tracing::error;
 
@@ -30,6 +35,8 @@ pub enum MiningMode {
     Instant(Fuse<ReceiverStream<TxHash>>),
     /// In this mode a block is built at a fixed interval.
     Interval(Interval),
+    /// In this mode blocks are only built when requested through a [`LocalMinerHandle`].
+    Manual,
 }
 
 impl MiningMode {
@@ -44,6 +51,11 @@ impl MiningMode {
         let start = tokio::time::Instant::now() + duration;
         Self::Interval(tokio::time::interval_at(start, duration))
     }
+
+    /// Returns `true` if a block is built for every transaction that reaches the pool.
+    pub const fn is_instant(&self) -> bool {
+        matches!(self, Self::Instant(_))
+    }
 }
 
 impl Future for MiningMode {
@@ -65,10 +77,147 @@ impl Future for MiningMode {
                 }
                 Poll::Pending
             }
+            Self::Manual => Poll::Pending,
         }
     }
 }
 
+/// Errors returned by a [`LocalMinerHandle`].
+#[derive(Debug, thiserror::Error)]
+pub enum LocalMinerError {
+    /// The miner is not running or was never attached to the handle.
+    #[error("local miner is not running")]
+    MinerClosed,
+    /// The requested timestamp is not after the timestamp of the latest mined block.
+    #[error("timestamp {timestamp} is not greater than the latest block timestamp {latest}")]
+    TimestampTooLow {
+        /// The requested timestamp.
+        timestamp: u64,
+        /// The timestamp of the latest mined block.
+        latest: u64,
+    },
+    /// Advancing the chain failed.
+    #[error("failed to mine block: {0}")]
+    Mine(eyre::Report),
+}
+
+/// Commands sent from a [`LocalMinerHandle`] to the [`LocalMiner`].
+#[derive(Debug)]
+enum MinerCommand {
+    Mine {
+        blocks: u64,
+        interval: Option<u64>,
+        tx: oneshot::Sender<Result<Vec<B256>, LocalMinerError>>,
+    },
+    SetMiningMode(MiningMode),
+    IsAutomine(oneshot::Sender<bool>),
+    SetNextBlockTimestamp {
+        timestamp: u64,
+        tx: oneshot::Sender<Result<(), LocalMinerError>>,
+    },
+    IncreaseTime {
+        seconds: u64,
+        tx: oneshot::Sender<i64>,
+    },
+    SetTime {
+        timestamp: u64,
+        tx: oneshot::Sender<u64>,
+    },
+    SetBlockTimestampInterval {
+        interval: Option<u64>,
+        tx: oneshot::Sender<bool>,
+    },
+}
+
+/// A handle to control a running [`LocalMiner`].
+///
+/// The handle can be created before the miner exists, the miner picks up the command channel
+/// via [`LocalMiner::with_handle`].
+#[derive(Debug, Clone)]
+pub struct LocalMinerHandle {
+    to_miner: mpsc::UnboundedSender<MinerCommand>,
+    from_handle: Arc<Mutex<Option<mpsc::UnboundedReceiver<MinerCommand>>>>,
+}
+
+impl LocalMinerHandle {
+    /// Creates a new handle that is not yet attached to a miner.
+    pub fn new() -> Self {
+        let (to_miner, from_handle) = mpsc::unbounded_channel();
+        Self { to_miner, from_handle: Arc::new(Mutex::new(Some(from_handle))) }
+    }
+
+    /// Mines `blocks` blocks right away, regardless of the configured [`MiningMode`].
+    ///
+    /// If `interval` is set, consecutive blocks are `interval` seconds apart. Returns the hashes
+    /// of the mined blocks.
+    pub async fn mine(
+        &self,
+        blocks: u64,
+        interval: Option<u64>,
+    ) -> Result<Vec<B256>, LocalMinerError> {
+        let (tx, rx) = oneshot::channel();
+        self.send(MinerCommand::Mine { blocks, interval, tx })?;
+        rx.await.map_err(|_| LocalMinerError::MinerClosed)?
+    }
+
+    /// Replaces the [`MiningMode`] of the miner.
+    pub fn set_mining_mode(&self, mode: MiningMode) -> Result<(), LocalMinerError> {
+        self.send(MinerCommand::SetMiningMode(mode))
+    }
+
+    /// Returns `true` if the miner is in [`MiningMode::Instant`].
+    pub async fn is_automine(&self) -> Result<bool, LocalMinerError> {
+        let (tx, rx) = oneshot::channel();
+        self.send(MinerCommand::IsAutomine(tx))?;
+        rx.await.map_err(|_| LocalMinerError::MinerClosed)
+    }
+
+    /// Sets the exact timestamp of the next mined block.
+    pub async fn set_next_block_timestamp(&self, timestamp: u64) -> Result<(), LocalMinerError> {
+        let (tx, rx) = oneshot::channel();
+        self.send(MinerCommand::SetNextBlockTimestamp { timestamp, tx })?;
+        rx.await.map_err(|_| LocalMinerError::MinerClosed)?
+    }
+
+    /// Moves the miner's clock forward by `seconds` and returns the total offset from the system
+    /// time.
+    pub async fn increase_time(&self, seconds: u64) -> Result<i64, LocalMinerError> {
+        let (tx, rx) = oneshot::channel();
+        self.send(MinerCommand::IncreaseTime { seconds, tx })?;
+        rx.await.map_err(|_| LocalMinerError::MinerClosed)
+    }
+
+    /// Sets the miner's clock to `timestamp` and returns the new offset from the system time.
+    pub async fn set_time(&self, timestamp: u64) -> Result<u64, LocalMinerError> {
+        let (tx, rx) = oneshot::channel();
+        self.send(MinerCommand::SetTime { timestamp, tx })?;
+        rx.await.map_err(|_| LocalMinerError::MinerClosed)
+    }
+
+    /// Sets a fixed number of seconds between the timestamps of consecutive blocks, or removes it
+    /// if `None`.
+    ///
+    /// Returns `true` if an interval was set before.
+    pub async fn set_block_timestamp_interval(
+        &self,
+        interval: Option<u64>,
+    ) -> Result<bool, LocalMinerError> {
+        let (tx, rx) = oneshot::channel();
+        self.send(MinerCommand::SetBlockTimestampInterval { interval, tx })?;
+        rx.await.map_err(|_| LocalMinerError::MinerClosed)
+    }
+
+    fn send(&self, command: MinerCommand) -> Result<(), LocalMinerError> {
+        self.to_miner.send(command).map_err(|_| LocalMinerError::MinerClosed)
+    }
+}
+
+impl Default for LocalMinerHandle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Local miner advancing the chain/
 #[derive(Debug)]
 pub struct LocalMiner<T: PayloadTypes, B> {
@@ -84,6 +233,14 @@ pub struct LocalMiner<T: PayloadTypes, B> {
     last_timestamp: u64,
     /// Stores latest mined blocks.
     last_block_hashes: Vec<B256>,
+    /// Timestamp override for the next block.
+    next_timestamp: Option<u64>,
+    /// Fixed number of seconds between the timestamps of consecutive blocks.
+    block_timestamp_interval: Option<u64>,
+    /// Offset in seconds applied to the system time.
+    time_offset: i64,
+    /// Commands from a [`LocalMinerHandle`].
+    commands: Option<mpsc::UnboundedReceiver<MinerCommand>>,
 }
 
 impl<T, B> LocalMiner<T, B>
@@ -109,12 +266,25 @@ where
             payload_builder,
             last_timestamp: latest_header.timestamp(),
             last_block_hashes: vec![latest_header.hash()],
+            next_timestamp: None,
+            block_timestamp_interval: None,
+            time_offset: 0,
+            commands: None,
         }
     }
 
+    /// Makes the miner listen to commands from the given [`LocalMinerHandle`].
+    ///
+    /// Only the first miner attached to a handle receives its commands.
+    pub fn with_handle(mut self, handle: &LocalMinerHandle) -> Self {
+        self.commands = handle.from_handle.lock().take();
+        self
+    }
+
     /// Runs the [`LocalMiner`] in a loop, polling the miner and building payloads.
     pub async fn run(mut self) {
         let mut fcu_interval = tokio::time::interval(Duration::from_secs(1));
+        let mut commands = self.commands.take();
         loop {
             tokio::select! {
                 // Wait for the interval or the pool to receive a transaction
@@ -123,6 +293,9 @@ where
                         error!(target: "engine::local", "Error advancing the chain: {:?}", e);
                     }
                 }
+                Some(command) = recv_command(&mut commands) => {
+                    self.on_command(command).await;
+                }
                 // send FCU once in a while
                 _ = fcu_interval.tick() => {
                     if let Err(e) = self.update_forkchoice_state().await {
@@ -133,6 +306,73 @@ where
         }
     }
 
+    /// Handles a command from a [`LocalMinerHandle`].
+    async fn on_command(&mut self, command: MinerCommand) {
+        match command {
+            MinerCommand::Mine { blocks, interval, tx } => {
+                let mut hashes = Vec::with_capacity(blocks as usize);
+                let mut res = Ok(());
+                for i in 0..blocks {
+                    if i > 0 {
+                        if let Some(interval) = interval {
+                            self.next_timestamp = Some(self.last_timestamp + interval);
+                        }
+                    }
+                    match self.advance().await {
+                        Ok(hash) => hashes.push(hash),
+                        Err(err) => {
+                            res = Err(LocalMinerError::Mine(err));
+                            break
+                        }
+                    }
+                }
+                // make the mined blocks canonical right away instead of waiting for the next
+                // forkchoice tick
+                if res.is_ok() && !hashes.is_empty() {
+                    res = self.update_forkchoice_state().await.map_err(LocalMinerError::Mine);
+                }
+                let _ = tx.send(res.map(|_| hashes));
+            }
+            MinerCommand::SetMiningMode(mode) => self.mode = mode,
+            MinerCommand::IsAutomine(tx) => {
+                let _ = tx.send(self.mode.is_instant());
+            }
+            MinerCommand::SetNextBlockTimestamp { timestamp, tx } => {
+                let res = if timestamp > self.last_timestamp {
+                    self.next_timestamp = Some(timestamp);
+                    Ok(())
+                } else {
+                    Err(LocalMinerError::TimestampTooLow { timestamp, latest: self.last_timestamp })
+                };
+                let _ = tx.send(res);
+            }
+            MinerCommand::IncreaseTime { seconds, tx } => {
+                self.time_offset = self.time_offset.saturating_add_unsigned(seconds);
+                let _ = tx.send(self.time_offset);
+            }
+            MinerCommand::SetTime { timestamp, tx } => {
+                let now = now();
+                self.time_offset = (timestamp as i64).saturating_sub(now as i64);
+                let _ = tx.send(timestamp.saturating_sub(now));
+            }
+            MinerCommand::SetBlockTimestampInterval { interval, tx } => {
+                let previous = std::mem::replace(&mut self.block_timestamp_interval, interval);
+                let _ = tx.send(previous.is_some());
+            }
+        }
+    }
+
+    /// Returns the timestamp for the next block.
+    fn next_block_timestamp(&self) -> u64 {
+        if let Some(timestamp) = self.next_timestamp {
+            return timestamp
+        }
+        if let Some(interval) = self.block_timestamp_interval {
+            return self.last_timestamp + interval
+        }
+        std::cmp::max(self.last_timestamp + 1, now().saturating_add_signed(self.time_offset))
+    }
+
     /// Returns current forkchoice state.
     fn forkchoice_state(&self) -> ForkchoiceState {
         ForkchoiceState {
@@ -164,14 +404,10 @@ where
 
     /// Generates payload attributes for a new block, passes them to FCU and inserts built payload
     /// through newPayload.
-    async fn advance(&mut self) -> eyre::Result<()> {
-        let timestamp = std::cmp::max(
-            self.last_timestamp + 1,
-            std::time::SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .expect("cannot be earlier than UNIX_EPOCH")
-                .as_secs(),
-        );
+    ///
+    /// Returns the hash of the new block.
+    async fn advance(&mut self) -> eyre::Result<B256> {
+        let timestamp = self.next_block_timestamp();
 
         let res = self
             .to_engine
@@ -203,14 +439,34 @@ where
             eyre::bail!("Invalid payload")
         }
 
+        let hash = block.hash();
         self.last_timestamp = timestamp;
-        self.last_block_hashes.push(block.hash());
+        self.next_timestamp = None;
+        self.last_block_hashes.push(hash);
         // ensure we keep at most 64 blocks
         if self.last_block_hashes.len() > 64 {
             self.last_block_hashes =
                 self.last_block_hashes.split_off(self.last_block_hashes.len() - 64);
         }
 
-        Ok(())
+        Ok(hash)
     }
 }
+
+/// Receives the next command if the miner is attached to a [`LocalMinerHandle`].
+async fn recv_command(
+    commands: &mut Option<mpsc::UnboundedReceiver<MinerCommand>>,
+) -> Option<MinerCommand> {
+    match commands {
+        Some(commands) => commands.recv().await,
+        None => future::pending().await,
+    }
+}
+
+/// Returns the current system time in seconds.
+fn now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("cannot be earlier than UNIX_EPOCH")
+        .as_secs()
+}
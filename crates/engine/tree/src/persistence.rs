@@ -17,7 +17,7 @@ use std::{
 };
 use thiserror::Error;
 use tokio::sync::oneshot;
-use tracing::{debug, error};
+use tracing::{debug, error, instrument};
 
 /// Writes parts of reth's in memory tree state to the database and static files.
 ///
@@ -121,6 +121,7 @@ where
         Ok(())
     }
 
+    #[instrument(level = "debug", skip(self), target = "engine::persistence")]
     fn on_remove_blocks_above(
         &self,
         new_tip_num: u64,
@@ -139,6 +140,7 @@ where
         Ok(new_tip_hash.map(|hash| BlockNumHash { hash, number: new_tip_num }))
     }
 
+    #[instrument(level = "debug", skip_all, fields(count = blocks.len()), target = "engine::persistence")]
     fn on_save_blocks(
         &self,
         blocks: Vec<ExecutedBlockWithTrieUpdates<N::Primitives>>,
@@ -520,7 +520,7 @@ where
     ///
     /// This returns a [`PayloadStatus`] that represents the outcome of a processed new payload and
     /// returns an error if an internal error occurred.
-    #[instrument(level = "trace", skip_all, fields(block_hash = %payload.block_hash(), block_num = %payload.block_number(),), target = "engine::tree")]
+    #[instrument(level = "debug", skip_all, fields(block_hash = %payload.block_hash(), block_num = %payload.block_number(),), target = "engine::tree")]
     fn on_new_payload(
         &mut self,
         payload: T::ExecutionData,
@@ -831,7 +831,7 @@ where
     /// `engine_forkchoiceUpdated`](https://github.com/ethereum/execution-apis/blob/main/src/engine/paris.md#specification-1).
     ///
     /// Returns an error if an internal error occurred like a database error.
-    #[instrument(level = "trace", skip_all, fields(head = % state.head_block_hash, safe = % state.safe_block_hash,finalized = % state.finalized_block_hash), target = "engine::tree")]
+    #[instrument(level = "debug", skip_all, fields(head = % state.head_block_hash, safe = % state.safe_block_hash,finalized = % state.finalized_block_hash), target = "engine::tree")]
     fn on_forkchoice_updated(
         &mut self,
         state: ForkchoiceState,
@@ -2104,6 +2104,7 @@ where
         }
     }
 
+    #[instrument(level = "debug", skip_all, fields(block_hash = %block.hash(), block_num = %block.number()), target = "engine::tree")]
     fn insert_block_inner(
         &mut self,
         block: RecoveredBlock<N::Block>,
@@ -2305,7 +2306,9 @@ where
             // background task or try to compute it in parallel
             if use_state_root_task {
                 debug!(target: "engine::tree", block=?block_num_hash, "Using sparse trie state root algorithm");
-                match handle.state_root() {
+                let state_root_result = debug_span!(target: "engine::tree", "await_state_root")
+                    .in_scope(|| handle.state_root());
+                match state_root_result {
                     Ok(StateRootComputeOutcome { state_root, trie_updates }) => {
                         let elapsed = execution_finish.elapsed();
                         info!(target: "engine::tree", ?state_root, ?elapsed, "State root task finished");
@@ -2363,7 +2366,8 @@ where
             }
 
             let (root, updates) =
-                ensure_ok!(state_provider.state_root_with_updates(hashed_state.clone()));
+                ensure_ok!(debug_span!(target: "engine::tree", "state_root_fallback")
+                    .in_scope(|| state_provider.state_root_with_updates(hashed_state.clone())));
             (root, updates, root_time.elapsed())
         };
 
@@ -2428,6 +2432,7 @@ where
     }
 
     /// Executes a block with the given state provider
+    #[instrument(level = "debug", skip_all, fields(block = ?block.num_hash()), target = "engine::tree")]
     fn execute_block<S: StateProvider>(
         &mut self,
         state_provider: S,
@@ -2478,6 +2483,7 @@ where
     /// Returns `Err(_)` if error was encountered during computation.
     /// `Err(ProviderError::ConsistentView(_))` can be safely ignored and fallback computation
     /// should be used instead.
+    #[instrument(level = "debug", skip_all, fields(%parent_hash), target = "engine::tree")]
     fn compute_state_root_parallel(
         &self,
         persisting_kind: PersistingKind,
@@ -2623,6 +2629,8 @@ where
 
         // keep track of the invalid header
         self.state.invalid_headers.insert(block.block_with_parent());
+        // keep the full block around so that it can be inspected and traced
+        self.canonical_in_memory_state.insert_bad_block(block.clone());
         self.emit_event(EngineApiEvent::BeaconConsensus(BeaconConsensusEngineEvent::InvalidBlock(
             Box::new(block),
         )));
@@ -40,6 +40,7 @@ use std::{
         Arc,
     },
 };
+use tracing::debug_span;
 
 use super::precompile_cache::PrecompileCacheMap;
 
@@ -203,8 +204,9 @@ where
             self.spawn_caching_with(header, transactions, provider_builder, to_multi_proof.clone());
 
         // spawn multi-proof task
+        let span = debug_span!(target: "engine::root", "multiproof_task");
         self.executor.spawn_blocking(move || {
-            multi_proof_task.run();
+            span.in_scope(|| multi_proof_task.run());
         });
 
         // wire the sparse trie to the state root response receiver
@@ -214,8 +216,9 @@ where
         self.spawn_sparse_trie_task(sparse_trie_rx, proof_task.handle(), state_root_tx);
 
         // spawn the proof task
+        let span = debug_span!(target: "engine::root", "proof_task");
         self.executor.spawn_blocking(move || {
-            if let Err(err) = proof_task.run() {
+            if let Err(err) = span.in_scope(|| proof_task.run()) {
                 // At least log if there is an error at any point
                 tracing::error!(
                     target: "engine::root",
@@ -295,8 +298,9 @@ where
         let to_prewarm_task = prewarm_task.actions_tx();
 
         // spawn pre-warm task
+        let span = debug_span!(target: "engine::caching", "prewarm_task");
         self.executor.spawn_blocking(move || {
-            prewarm_task.run();
+            span.in_scope(|| prewarm_task.run());
         });
         CacheTaskHandle { cache, to_prewarm_task: Some(to_prewarm_task), cache_metrics }
     }
@@ -348,8 +352,9 @@ where
                 sparse_state_trie,
             );
 
+        let span = debug_span!(target: "engine::root", "sparse_trie_task");
         self.executor.spawn_blocking(move || {
-            let (result, trie) = task.run();
+            let (result, trie) = span.in_scope(|| task.run());
             // Send state root computation result
             let _ = state_root_tx.send(result);
 
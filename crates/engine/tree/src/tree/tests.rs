@@ -493,6 +493,26 @@ fn test_disconnected_block() {
     );
 }
 
+#[test]
+fn test_invalid_block_recorded_as_bad_block() {
+    let s = include_str!("../../test-data/holesky/2.rlp");
+    let data = Bytes::from_str(s).unwrap();
+    let block = Block::decode(&mut data.as_ref()).unwrap();
+    let sealed = block.seal_slow();
+
+    let mut test_harness = TestHarness::new(HOLESKY.clone());
+
+    let status = test_harness
+        .tree
+        .on_insert_block_error(InsertBlockError::new(
+            sealed.clone(),
+            ConsensusError::BaseFeeMissing.into(),
+        ))
+        .unwrap();
+    assert!(status.is_invalid());
+    assert_eq!(test_harness.tree.canonical_in_memory_state.bad_blocks(), vec![sealed]);
+}
+
 #[tokio::test]
 async fn test_holesky_payload() {
     let s = include_str!("../../test-data/holesky/1.rlp");
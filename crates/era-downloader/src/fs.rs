@@ -2,22 +2,35 @@ use crate::{EraMeta, BLOCKS_PER_FILE};
 use alloy_primitives::{hex, hex::ToHexExt, BlockNumber};
 use eyre::{eyre, OptionExt};
 use futures_util::{stream, Stream};
+use reth_era::{era_file::EraReader, era_types::SLOTS_PER_HISTORICAL_ROOT};
 use reth_fs_util as fs;
 use sha2::{Digest, Sha256};
 use std::{fmt::Debug, io, io::BufRead, path::Path, str::FromStr};
 
-/// Creates a new ordered asynchronous [`Stream`] of ERA1 files read from `dir`.
+/// Creates a new ordered asynchronous [`Stream`] of ERA1 files read from `dir`, followed by the
+/// consensus layer ERA files of `dir`.
+///
+/// ERA1 files are verified against the `checksums.txt` of `dir`, ERA files against the
+/// `checksums_era.txt` of `dir`, whose lines hold the checksums of the ERA files in the order of
+/// their era numbers, starting with era 0.
+///
+/// Files that end before `start_from` are skipped. The names of ERA files refer to slots rather
+/// than block numbers, so the first block of an ERA file is read to map its slots to blocks.
 pub fn read_dir(
     dir: impl AsRef<Path> + Send + Sync + 'static,
     start_from: BlockNumber,
 ) -> eyre::Result<impl Stream<Item = eyre::Result<EraLocalMeta>> + Send + Sync + 'static + Unpin> {
     let mut checksums = None;
+    let mut era_checksums = None;
+    let mut era_entries = Vec::new();
     let mut entries = fs::read_dir(dir)?
         .filter_map(|entry| {
             (|| {
                 let path = entry?.path();
+                let is_era1 = path.extension() == Some("era1".as_ref());
+                let is_era = path.extension() == Some("era".as_ref());
 
-                if path.extension() == Some("era1".as_ref()) {
+                if is_era1 || is_era {
                     if let Some(last) = path.components().next_back() {
                         let str = last.as_os_str().to_string_lossy().to_string();
                         let parts = str.split('-').collect::<Vec<_>>();
@@ -25,6 +38,11 @@ pub fn read_dir(
                         if parts.len() == 3 {
                             let number = usize::from_str(parts[1])?;
 
+                            if is_era {
+                                era_entries.push((number, path.into_boxed_path()));
+                                return Ok(None);
+                            }
+
                             return Ok(Some((number, path.into_boxed_path())));
                         }
                     }
@@ -34,6 +52,10 @@ pub fn read_dir(
                     let reader = io::BufReader::new(file);
                     let lines = reader.lines();
                     checksums = Some(lines);
+                } else if path.file_name() == Some("checksums_era.txt".as_ref()) {
+                    let file = fs::open(path)?;
+                    let reader = io::BufReader::new(file);
+                    era_checksums = Some(reader.lines().collect::<io::Result<Vec<_>>>()?);
                 }
 
                 Ok(None)
@@ -41,33 +63,75 @@ pub fn read_dir(
             .transpose()
         })
         .collect::<eyre::Result<Vec<_>>>()?;
-    let mut checksums = checksums.ok_or_eyre("Missing file `checksums.txt` in the `dir`")?;
 
-    entries.sort_by(|(left, _), (right, _)| left.cmp(right));
+    // A directory with nothing but ERA files needs no ERA1 checksums
+    if !entries.is_empty() || era_entries.is_empty() {
+        checksums.as_ref().ok_or_eyre("Missing file `checksums.txt` in the `dir`")?;
+    }
+    let era_checksums = if era_entries.is_empty() {
+        Vec::new()
+    } else {
+        era_checksums.ok_or_eyre("Missing file `checksums_era.txt` in the `dir`")?
+    };
+
+    entries.sort_by_key(|(number, _)| *number);
+    era_entries.sort_by_key(|(number, _)| *number);
+
+    let era1_files =
+        entries.into_iter().skip(start_from as usize / BLOCKS_PER_FILE).map(move |(_, path)| {
+            let expected_checksum = checksums
+                .as_mut()
+                .and_then(|checksums| checksums.next())
+                .transpose()?
+                .ok_or_eyre("Got less checksums than ERA files")?;
+            verify_checksum(&path, &expected_checksum)?;
+
+            Ok(EraLocalMeta::new(path))
+        });
+    let era_files = era_entries.into_iter().filter_map(move |(number, path)| {
+        (|| {
+            // Every slot holds at most one block, so the blocks of a file end less than
+            // `SLOTS_PER_HISTORICAL_ROOT` blocks after its first one
+            if start_from > 0 {
+                let first_block =
+                    EraReader::new(fs::open(&path)?).first_execution_block_number()?;
+                if first_block.is_some_and(|first| first + SLOTS_PER_HISTORICAL_ROOT <= start_from)
+                {
+                    return Ok(None)
+                }
+            }
 
-    Ok(stream::iter(entries.into_iter().skip(start_from as usize / BLOCKS_PER_FILE).map(
-        move |(_, path)| {
             let expected_checksum =
-                checksums.next().transpose()?.ok_or_eyre("Got less checksums than ERA files")?;
-            let expected_checksum = hex::decode(expected_checksum)?;
+                era_checksums.get(number).ok_or_eyre("Got less checksums than ERA files")?;
+            verify_checksum(&path, expected_checksum)?;
 
-            let mut hasher = Sha256::new();
-            let mut reader = io::BufReader::new(fs::open(&path)?);
+            Ok(Some(EraLocalMeta::new(path)))
+        })()
+        .transpose()
+    });
 
-            io::copy(&mut reader, &mut hasher)?;
-            let actual_checksum = hasher.finalize().to_vec();
+    Ok(stream::iter(era1_files.chain(era_files)))
+}
 
-            if actual_checksum != expected_checksum {
-                return Err(eyre!(
-                    "Checksum mismatch, got: {}, expected: {}",
-                    actual_checksum.encode_hex(),
-                    expected_checksum.encode_hex()
-                ));
-            }
+/// Checks the sha256 checksum of the file at `path` against the hex encoded `expected_checksum`.
+fn verify_checksum(path: &Path, expected_checksum: &str) -> eyre::Result<()> {
+    let expected_checksum = hex::decode(expected_checksum)?;
 
-            Ok(EraLocalMeta::new(path))
-        },
-    )))
+    let mut hasher = Sha256::new();
+    let mut reader = io::BufReader::new(fs::open(path)?);
+
+    io::copy(&mut reader, &mut hasher)?;
+    let actual_checksum = hasher.finalize().to_vec();
+
+    if actual_checksum != expected_checksum {
+        return Err(eyre!(
+            "Checksum mismatch, got: {}, expected: {}",
+            actual_checksum.encode_hex(),
+            expected_checksum.encode_hex()
+        ));
+    }
+
+    Ok(())
 }
 
 /// Contains information about an ERA file that is on the local file-system and is read-only.
@@ -102,3 +102,64 @@ async fn test_streaming_from_local_directory(
         },
     }
 }
+
+#[test_case::test_case(
+    None,
+    &["mainnet-01000-3f2a2c05.era", "mainnet-00999-54c3ea36.era"],
+    &["mainnet-00999-54c3ea36.era", "mainnet-01000-3f2a2c05.era"];
+    "Reads ERA files without ERA1 checksums"
+)]
+#[test_case::test_case(
+    Some(sha2::Sha256::digest(CONTENTS_0).encode_hex()),
+    &["mainnet-00574-e0b2e16c.era", "mainnet-00000-5ec1ffb8.era1"],
+    &["mainnet-00000-5ec1ffb8.era1", "mainnet-00574-e0b2e16c.era"];
+    "Reads ERA1 files before ERA files"
+)]
+#[tokio::test]
+async fn test_streaming_era_files_from_local_directory(
+    checksums: Option<String>,
+    files: &[&str],
+    expected: &[&str],
+) {
+    let folder = tempfile::tempdir().unwrap();
+    let folder = folder.path().to_owned();
+
+    if let Some(checksums) = checksums {
+        fs::write(folder.join("checksums.txt"), checksums).await.unwrap();
+    }
+    let era_checksums = vec![sha2::Sha256::digest(CONTENTS_0).encode_hex(); 1001].join("\n");
+    fs::write(folder.join("checksums_era.txt"), era_checksums).await.unwrap();
+    for file in files {
+        fs::write(folder.join(file), CONTENTS_0).await.unwrap();
+    }
+
+    let folder = folder.into_boxed_path();
+    let actual =
+        read_dir(folder.clone(), 0).unwrap().map(|meta| meta.unwrap()).collect::<Vec<_>>().await;
+    let expected =
+        expected.iter().map(|file| folder.join(file).into_boxed_path()).collect::<Vec<_>>();
+
+    assert_eq!(actual, expected);
+}
+
+#[tokio::test]
+async fn test_streaming_era_files_verifies_checksums() {
+    let folder = tempfile::tempdir().unwrap();
+    let folder = folder.path().to_owned();
+
+    fs::write(folder.join("mainnet-00001-54c3ea36.era"), CONTENTS_1).await.unwrap();
+    assert_eq!(
+        read_dir(folder.clone(), 0).err().unwrap().to_string(),
+        "Missing file `checksums_era.txt` in the `dir`"
+    );
+
+    let era_checksums = vec![sha2::Sha256::digest(CONTENTS_0).encode_hex(); 2].join("\n");
+    fs::write(folder.join("checksums_era.txt"), era_checksums).await.unwrap();
+    let actual = read_dir(folder, 0).unwrap().next().await.unwrap();
+    assert_eq!(
+        actual.expect_err("should be err").to_string(),
+        "Checksum mismatch, \
+        got: 3e23e8160039594a33894f6564e1b1348bbd7a0088d42c4acb73eeaed59c009d, \
+        expected: ca978112ca1bbdcafac231b39a23dc4da786eff8147c4e72b9807785afee48bb"
+    );
+}
@@ -8,8 +8,11 @@ use reth_db_api::{
     RawKey, RawTable, RawValue,
 };
 use reth_era::{
+    beacon_block::SignedBeaconBlockSsz,
+    consensus_types::CompressedSignedBeaconBlock,
     e2s_types::E2sError,
     era1_file::{BlockTupleIterator, Era1Reader},
+    era_file::EraReader,
     execution_types::BlockTuple,
     DecodeCompressed,
 };
@@ -33,8 +36,9 @@ use std::{
     error::Error,
     fmt::{Display, Formatter},
     io::{Read, Seek},
-    iter::Map,
+    iter::{self, Map},
     ops::RangeBounds,
+    path::Path,
     sync::mpsc,
 };
 use tracing::info;
@@ -145,6 +149,9 @@ where
 
 /// Extracts block headers and bodies from `meta` and appends them using `writer` and `provider`.
 ///
+/// Post-merge blocks are extracted from the execution payloads of the beacon blocks if `meta` is a
+/// `.era` file, see [`is_era_file`].
+///
 /// Adds on to `total_difficulty` and collects hash to height using `hash_collector`.
 ///
 /// Skips all blocks below the [`start_bound`] of `block_numbers` and stops when reaching past the
@@ -173,6 +180,19 @@ where
     P: DBProvider<Tx: DbTxMut> + NodePrimitivesProvider + BlockWriter<Block = B>,
     <P as NodePrimitivesProvider>::Primitives: NodePrimitives<BlockHeader = BH, BlockBody = BB>,
 {
+    if is_era_file(meta.path()) {
+        let iter = open_era(meta)?.iter().filter_map(decode_beacon_block);
+        let iter = iter.chain(
+            iter::once_with(|| match meta.mark_as_processed() {
+                Ok(..) => None,
+                Err(e) => Some(Err(e)),
+            })
+            .flatten(),
+        );
+
+        return process_iter(iter, writer, provider, hash_collector, total_difficulty, block_numbers)
+    }
+
     let reader = open(meta)?;
     let iter =
         reader
@@ -240,6 +260,23 @@ where
     Ok(reader)
 }
 
+/// Opens the consensus layer era file described by `meta`.
+pub fn open_era<Era>(meta: &Era) -> eyre::Result<EraReader<std::fs::File>>
+where
+    Era: EraMeta + ?Sized,
+{
+    let file = fs::open(meta.path())?;
+    let reader = EraReader::new(file);
+
+    Ok(reader)
+}
+
+/// Returns true if `path` is a consensus layer `.era` file, rather than an execution layer `.era1`
+/// file.
+pub fn is_era_file(path: &Path) -> bool {
+    path.extension() == Some("era".as_ref())
+}
+
 /// Extracts a pair of [`FullBlockHeader`] and [`FullBlockBody`] from [`BlockTuple`].
 pub fn decode<BH, BB, E>(block: Result<BlockTuple, E>) -> eyre::Result<(BH, BB)>
 where
@@ -254,6 +291,36 @@ where
     Ok((header, body))
 }
 
+/// Extracts a pair of [`FullBlockHeader`] and [`FullBlockBody`] from the execution payload of a
+/// [`CompressedSignedBeaconBlock`].
+///
+/// Returns `None` for beacon blocks without execution payload, i.e. blocks before the merge, so it
+/// can be used with [`Iterator::filter_map`].
+pub fn decode_beacon_block<BH, BB, E>(
+    block: Result<CompressedSignedBeaconBlock, E>,
+) -> Option<eyre::Result<(BH, BB)>>
+where
+    BH: FullBlockHeader + Value,
+    BB: FullBlockBody<OmmerHeader = BH>,
+    E: From<E2sError> + Error + Send + Sync + 'static,
+{
+    let decode = || {
+        let data = block?.decompress()?;
+        let Some(block) = SignedBeaconBlockSsz::new(&data)?.execution_block::<BB::Transaction>()?
+        else {
+            return Ok(None)
+        };
+
+        // Converted through the RLP encoding, which the header and body types have in common
+        let header = BH::decode(&mut alloy_rlp::encode(&block.header).as_slice())?;
+        let body = BB::decode(&mut alloy_rlp::encode(&block.body).as_slice())?;
+
+        Ok(Some((header, body)))
+    };
+
+    decode().transpose()
+}
+
 /// Extracts block headers and bodies from `iter` and appends them using `writer` and `provider`.
 ///
 /// Adds on to `total_difficulty` and collects hash to height using `hash_collector`.
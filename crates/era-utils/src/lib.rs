@@ -1,6 +1,9 @@
 //! Utilities to store history from downloaded ERA files with storage-api
 //!  and export it to recreate era1 files.
 //!
+//! Besides era1 files, post-merge history is imported from the execution payloads stored in
+//! consensus layer `.era` files.
+//!
 //! The import is downloaded using [`reth_era_downloader`] and parsed using [`reth_era`].
 
 mod history;
@@ -14,5 +17,6 @@ pub use export::{export, ExportConfig};
 
 /// Imports history from ERA files.
 pub use history::{
-    build_index, decode, import, open, process, process_iter, save_stage_checkpoints, ProcessIter,
+    build_index, decode, decode_beacon_block, import, is_era_file, open, open_era, process,
+    process_iter, save_stage_checkpoints, ProcessIter,
 };
@@ -0,0 +1,433 @@
+//! Extraction of execution payloads from ssz-encoded `SignedBeaconBlock`s stored in `.era` files
+//!
+//! The beacon block is not fully decoded, only the offsets leading to the execution payload, the
+//! blob commitments and the execution requests are followed. The fork of a block is detected from
+//! the size of the fixed part of its body, which grows with every fork that adds a field to it.
+//!
+//! See also <https://github.com/ethereum/consensus-specs/blob/dev/specs/deneb/beacon-chain.md#beaconblockbody>
+
+use crate::e2s_types::E2sError;
+use alloy_consensus::Block;
+use alloy_eips::{eip2718::Decodable2718, eip4844::kzg_to_versioned_hash, eip7685::Requests};
+use alloy_primitives::B256;
+use alloy_rpc_types_engine::{
+    CancunPayloadFields, ExecutionPayload, ExecutionPayloadSidecar, PraguePayloadFields,
+};
+use ssz::Decode;
+
+/// Size of the fixed part of a `SignedBeaconBlock`: message offset and signature.
+const SIGNED_BEACON_BLOCK_FIXED_SIZE: usize = 4 + 96;
+
+/// Size of the fixed part of a `BeaconBlock`: slot, proposer index, parent root, state root and
+/// body offset.
+const BEACON_BLOCK_FIXED_SIZE: usize = 8 + 8 + 32 + 32 + 4;
+
+/// Position of the parent root in a `BeaconBlock`.
+const PARENT_ROOT_OFFSET: usize = 16;
+
+/// Position of the offset of the proposer slashings in a `BeaconBlockBody`, which is the first
+/// variable size field of the body of every fork.
+const PROPOSER_SLASHINGS_OFFSET: usize = 200;
+
+/// Position of the offset of the execution payload in a `BeaconBlockBody`.
+const EXECUTION_PAYLOAD_OFFSET: usize = 380;
+
+/// Position of the offset of the blob kzg commitments in a `BeaconBlockBody`.
+const BLOB_KZG_COMMITMENTS_OFFSET: usize = 388;
+
+/// Position of the offset of the execution requests in a `BeaconBlockBody`.
+const EXECUTION_REQUESTS_OFFSET: usize = 392;
+
+/// Size of a kzg commitment.
+const KZG_COMMITMENT_SIZE: usize = 48;
+
+/// Consensus layer fork of a beacon block, as far as it matters for the execution payload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum BeaconBlockFork {
+    /// Phase 0, no execution payload.
+    Phase0,
+    /// Altair, no execution payload.
+    Altair,
+    /// Bellatrix, `ExecutionPayloadV1`.
+    Bellatrix,
+    /// Capella, `ExecutionPayloadV2` with withdrawals.
+    Capella,
+    /// Deneb, `ExecutionPayloadV3` with blob commitments.
+    Deneb,
+    /// Electra or later, `ExecutionPayloadV3` with execution requests.
+    Electra,
+}
+
+impl BeaconBlockFork {
+    /// Detects the fork from the size of the fixed part of a `BeaconBlockBody`.
+    pub const fn from_body_fixed_size(size: usize) -> Option<Self> {
+        Some(match size {
+            220 => Self::Phase0,
+            380 => Self::Altair,
+            384 => Self::Bellatrix,
+            388 => Self::Capella,
+            392 => Self::Deneb,
+            396 => Self::Electra,
+            _ => return None,
+        })
+    }
+
+    /// Returns true if blocks of this fork contain an execution payload.
+    pub const fn has_execution_payload(&self) -> bool {
+        matches!(self, Self::Bellatrix | Self::Capella | Self::Deneb | Self::Electra)
+    }
+}
+
+/// An ssz-encoded `SignedBeaconBlock` of any fork.
+#[derive(Debug, Clone, Copy)]
+pub struct SignedBeaconBlockSsz<'a> {
+    message: &'a [u8],
+    body: &'a [u8],
+    fork: BeaconBlockFork,
+}
+
+impl<'a> SignedBeaconBlockSsz<'a> {
+    /// Splits the ssz-encoded `SignedBeaconBlock` into its parts and detects its fork.
+    pub fn new(data: &'a [u8]) -> Result<Self, E2sError> {
+        let message_offset = read_offset(data, 0)?;
+        if message_offset != SIGNED_BEACON_BLOCK_FIXED_SIZE {
+            return Err(E2sError::Ssz(format!(
+                "Invalid signed beacon block message offset: {message_offset}"
+            )));
+        }
+        let message = &data[message_offset..];
+
+        let body_offset = read_offset(message, BEACON_BLOCK_FIXED_SIZE - 4)?;
+        if body_offset != BEACON_BLOCK_FIXED_SIZE {
+            return Err(E2sError::Ssz(format!("Invalid beacon block body offset: {body_offset}")));
+        }
+        let body = &message[body_offset..];
+
+        let fixed_size = read_offset(body, PROPOSER_SLASHINGS_OFFSET)?;
+        let fork = BeaconBlockFork::from_body_fixed_size(fixed_size).ok_or_else(|| {
+            E2sError::Ssz(format!("Unknown beacon block body with fixed size {fixed_size}"))
+        })?;
+
+        Ok(Self { message, body, fork })
+    }
+
+    /// Returns the fork of the block.
+    pub const fn fork(&self) -> BeaconBlockFork {
+        self.fork
+    }
+
+    /// Returns the slot of the block.
+    pub fn slot(&self) -> u64 {
+        u64::from_le_bytes(self.message[..8].try_into().expect("checked by body offset"))
+    }
+
+    /// Returns the root of the parent beacon block.
+    pub fn parent_root(&self) -> B256 {
+        B256::from_slice(&self.message[PARENT_ROOT_OFFSET..PARENT_ROOT_OFFSET + 32])
+    }
+
+    /// Decodes the execution payload of the block.
+    ///
+    /// Returns `None` for blocks before Bellatrix and for blocks before the merge, whose payload
+    /// is empty.
+    pub fn execution_payload(&self) -> Result<Option<ExecutionPayload>, E2sError> {
+        if !self.fork.has_execution_payload() {
+            return Ok(None)
+        }
+
+        let data = self.field(EXECUTION_PAYLOAD_OFFSET)?;
+        let payload = match self.fork {
+            BeaconBlockFork::Bellatrix => ExecutionPayload::V1(decode_ssz(data)?),
+            BeaconBlockFork::Capella => ExecutionPayload::V2(decode_ssz(data)?),
+            _ => ExecutionPayload::V3(decode_ssz(data)?),
+        };
+
+        Ok((payload.block_hash() != B256::ZERO).then_some(payload))
+    }
+
+    /// Returns the versioned hashes of the blob kzg commitments of the block.
+    pub fn versioned_hashes(&self) -> Result<Vec<B256>, E2sError> {
+        if self.fork < BeaconBlockFork::Deneb {
+            return Ok(Vec::new())
+        }
+
+        let data = self.field(BLOB_KZG_COMMITMENTS_OFFSET)?;
+        if data.len() % KZG_COMMITMENT_SIZE != 0 {
+            return Err(E2sError::Ssz(format!(
+                "Invalid blob kzg commitments length: {}",
+                data.len()
+            )));
+        }
+
+        Ok(data.chunks_exact(KZG_COMMITMENT_SIZE).map(kzg_to_versioned_hash).collect())
+    }
+
+    /// Returns the EIP-7685 execution requests of the block.
+    ///
+    /// Returns `None` for blocks before Electra.
+    pub fn execution_requests(&self) -> Result<Option<Requests>, E2sError> {
+        if self.fork < BeaconBlockFork::Electra {
+            return Ok(None)
+        }
+
+        // `ExecutionRequests` is a container of the deposit, withdrawal and consolidation request
+        // lists. Their elements are fixed size, so the ssz encoding of a list equals the request
+        // data of the matching request type.
+        let data = self.field(EXECUTION_REQUESTS_OFFSET)?;
+        let mut requests = Requests::default();
+        for (request_type, position) in [0, 4, 8].into_iter().enumerate() {
+            let request_data = variable_field(data, position, 12)?;
+            requests.push_request_with_type(request_type as u8, request_data.iter().copied());
+        }
+
+        Ok(Some(requests))
+    }
+
+    /// Extracts the execution layer block of the beacon block.
+    ///
+    /// The hash of the assembled block is checked against the block hash of the payload.
+    ///
+    /// Returns `None` if the beacon block has no execution payload.
+    pub fn execution_block<T: Decodable2718>(&self) -> Result<Option<Block<T>>, E2sError> {
+        let Some(payload) = self.execution_payload()? else { return Ok(None) };
+
+        let sidecar = match self.fork {
+            BeaconBlockFork::Bellatrix | BeaconBlockFork::Capella => {
+                ExecutionPayloadSidecar::none()
+            }
+            _ => {
+                let cancun = CancunPayloadFields::new(self.parent_root(), self.versioned_hashes()?);
+                match self.execution_requests()? {
+                    Some(requests) => {
+                        ExecutionPayloadSidecar::v4(cancun, PraguePayloadFields::new(requests))
+                    }
+                    None => ExecutionPayloadSidecar::v3(cancun),
+                }
+            }
+        };
+
+        let expected = payload.block_hash();
+        let block = payload
+            .try_into_block_with_sidecar::<T>(&sidecar)
+            .map_err(|e| E2sError::Ssz(format!("Invalid execution payload: {e}")))?;
+
+        let hash = block.header.hash_slow();
+        if hash != expected {
+            return Err(E2sError::Ssz(format!(
+                "Execution block hash mismatch: expected {expected}, got {hash}"
+            )));
+        }
+
+        Ok(Some(block))
+    }
+
+    /// Returns the variable size field of the body whose offset is at `position`.
+    fn field(&self, position: usize) -> Result<&'a [u8], E2sError> {
+        let fixed_size = read_offset(self.body, PROPOSER_SLASHINGS_OFFSET)?;
+        variable_field(self.body, position, fixed_size)
+    }
+}
+
+/// Returns the variable size field of the ssz container `data` whose offset is at `position`.
+///
+/// The field ends at the next offset, or at the end of `data` for the last field.
+fn variable_field(data: &[u8], position: usize, fixed_size: usize) -> Result<&[u8], E2sError> {
+    let start = read_offset(data, position)?;
+    let end = if position + 4 < fixed_size { read_offset(data, position + 4)? } else { data.len() };
+
+    data.get(start..end)
+        .ok_or_else(|| E2sError::Ssz(format!("Invalid ssz offsets: {start}..{end}")))
+}
+
+/// Reads the little endian ssz offset at `position`.
+fn read_offset(data: &[u8], position: usize) -> Result<usize, E2sError> {
+    data.get(position..position + 4)
+        .map(|bytes| u32::from_le_bytes(bytes.try_into().expect("length is 4")) as usize)
+        .ok_or_else(|| E2sError::Ssz(format!("Missing ssz offset at {position}")))
+}
+
+/// Decodes an ssz payload.
+fn decode_ssz<T: Decode>(data: &[u8]) -> Result<T, E2sError> {
+    T::from_ssz_bytes(data)
+        .map_err(|e| E2sError::Ssz(format!("Failed to decode execution payload: {e:?}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_consensus::{constants::EMPTY_WITHDRAWALS, BlockBody, Header, EMPTY_OMMER_ROOT_HASH};
+    use alloy_eips::eip4895::Withdrawals;
+    use alloy_primitives::{Bytes, B64, U256};
+    use alloy_rpc_types_engine::{ExecutionPayloadV1, ExecutionPayloadV3};
+    use reth_ethereum_primitives::TransactionSigned;
+    use ssz::Encode;
+
+    /// Builds an ssz-encoded `SignedBeaconBlock` of the given fork, with empty operation lists.
+    fn signed_beacon_block(
+        fork: BeaconBlockFork,
+        parent_root: B256,
+        payload: &[u8],
+        commitments: &[u8],
+        requests: &[u8],
+    ) -> Vec<u8> {
+        let fixed_size = match fork {
+            BeaconBlockFork::Phase0 => 220,
+            BeaconBlockFork::Altair => 380,
+            BeaconBlockFork::Bellatrix => 384,
+            BeaconBlockFork::Capella => 388,
+            BeaconBlockFork::Deneb => 392,
+            BeaconBlockFork::Electra => 396,
+        };
+        let mut fields: Vec<&[u8]> = vec![&[]; 5];
+        fields.extend([payload, &[], commitments, requests]);
+
+        let mut body = vec![0; fixed_size];
+        let mut variable = Vec::new();
+        for (position, field) in (PROPOSER_SLASHINGS_OFFSET..fixed_size)
+            .step_by(4)
+            .filter(|position| !(220..EXECUTION_PAYLOAD_OFFSET).contains(position))
+            .zip(fields)
+        {
+            let offset = (fixed_size + variable.len()) as u32;
+            body[position..position + 4].copy_from_slice(&offset.to_le_bytes());
+            variable.extend_from_slice(field);
+        }
+        body.extend(variable);
+
+        let mut message = Vec::new();
+        message.extend_from_slice(&42u64.to_le_bytes());
+        message.extend_from_slice(&7u64.to_le_bytes());
+        message.extend_from_slice(parent_root.as_slice());
+        message.extend_from_slice(&[0; 32]);
+        message.extend_from_slice(&(BEACON_BLOCK_FIXED_SIZE as u32).to_le_bytes());
+        message.extend(body);
+
+        let mut block = (SIGNED_BEACON_BLOCK_FIXED_SIZE as u32).to_le_bytes().to_vec();
+        block.extend_from_slice(&[0; 96]);
+        block.extend(message);
+        block
+    }
+
+    /// Builds an execution block whose header matches what the payload conversion produces.
+    fn execution_block(header: Header) -> Block<TransactionSigned> {
+        let withdrawals = header.withdrawals_root.map(|_| Withdrawals::default());
+        Block::new(
+            Header {
+                ommers_hash: EMPTY_OMMER_ROOT_HASH,
+                transactions_root: alloy_consensus::EMPTY_ROOT_HASH,
+                number: 1,
+                gas_limit: 30_000_000,
+                timestamp: 12,
+                base_fee_per_gas: Some(7),
+                extra_data: Bytes::from_static(b"reth"),
+                nonce: B64::ZERO,
+                difficulty: U256::ZERO,
+                ..header
+            },
+            BlockBody { transactions: Vec::new(), ommers: Vec::new(), withdrawals },
+        )
+    }
+
+    #[test]
+    fn test_detect_fork() {
+        let data = signed_beacon_block(BeaconBlockFork::Phase0, B256::ZERO, &[], &[], &[]);
+        let block = SignedBeaconBlockSsz::new(&data).unwrap();
+
+        assert_eq!(block.fork(), BeaconBlockFork::Phase0);
+        assert_eq!(block.slot(), 42);
+        assert!(block.execution_block::<TransactionSigned>().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_pre_merge_payload() {
+        let block = execution_block(Header::default());
+        let payload = ExecutionPayloadV1::from_block_unchecked(B256::ZERO, &block).as_ssz_bytes();
+        let data = signed_beacon_block(BeaconBlockFork::Bellatrix, B256::ZERO, &payload, &[], &[]);
+        let block = SignedBeaconBlockSsz::new(&data).unwrap();
+
+        assert_eq!(block.fork(), BeaconBlockFork::Bellatrix);
+        assert!(block.execution_payload().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_extract_deneb_block() {
+        let parent_root = B256::repeat_byte(0x11);
+        let commitment = [0x22; KZG_COMMITMENT_SIZE];
+        let expected = execution_block(Header {
+            withdrawals_root: Some(EMPTY_WITHDRAWALS),
+            blob_gas_used: Some(0),
+            excess_blob_gas: Some(0),
+            parent_beacon_block_root: Some(parent_root),
+            ..Default::default()
+        });
+        let payload =
+            ExecutionPayloadV3::from_block_unchecked(expected.header.hash_slow(), &expected)
+                .as_ssz_bytes();
+        let data =
+            signed_beacon_block(BeaconBlockFork::Deneb, parent_root, &payload, &commitment, &[]);
+        let block = SignedBeaconBlockSsz::new(&data).unwrap();
+
+        assert_eq!(block.fork(), BeaconBlockFork::Deneb);
+        assert_eq!(block.parent_root(), parent_root);
+        assert_eq!(block.versioned_hashes().unwrap(), vec![kzg_to_versioned_hash(&commitment)]);
+        assert_eq!(block.execution_block::<TransactionSigned>().unwrap(), Some(expected));
+    }
+
+    #[test]
+    fn test_extract_electra_block() {
+        let deposit = [0x33; 192];
+        let mut requests = Requests::default();
+        requests.push_request_with_type(0, deposit);
+        let expected = execution_block(Header {
+            withdrawals_root: Some(EMPTY_WITHDRAWALS),
+            blob_gas_used: Some(0),
+            excess_blob_gas: Some(0),
+            parent_beacon_block_root: Some(B256::ZERO),
+            requests_hash: Some(requests.requests_hash()),
+            ..Default::default()
+        });
+        let payload =
+            ExecutionPayloadV3::from_block_unchecked(expected.header.hash_slow(), &expected)
+                .as_ssz_bytes();
+
+        // Deposits, followed by empty withdrawal and consolidation request lists
+        let mut execution_requests = Vec::new();
+        for offset in [12u32, 12 + 192, 12 + 192] {
+            execution_requests.extend_from_slice(&offset.to_le_bytes());
+        }
+        execution_requests.extend_from_slice(&deposit);
+
+        let data = signed_beacon_block(
+            BeaconBlockFork::Electra,
+            B256::ZERO,
+            &payload,
+            &[],
+            &execution_requests,
+        );
+        let block = SignedBeaconBlockSsz::new(&data).unwrap();
+
+        assert_eq!(block.execution_requests().unwrap(), Some(requests));
+        assert_eq!(block.execution_block::<TransactionSigned>().unwrap(), Some(expected));
+    }
+
+    #[test]
+    fn test_block_hash_mismatch() {
+        let expected = execution_block(Header {
+            withdrawals_root: Some(EMPTY_WITHDRAWALS),
+            blob_gas_used: Some(0),
+            excess_blob_gas: Some(0),
+            parent_beacon_block_root: Some(B256::ZERO),
+            ..Default::default()
+        });
+        let payload =
+            ExecutionPayloadV3::from_block_unchecked(expected.header.hash_slow(), &expected)
+                .as_ssz_bytes();
+
+        // The parent root of the beacon block does not match the one the block hash commits to
+        let data =
+            signed_beacon_block(BeaconBlockFork::Deneb, B256::repeat_byte(1), &payload, &[], &[]);
+        let block = SignedBeaconBlockSsz::new(&data).unwrap();
+
+        assert!(block.execution_block::<TransactionSigned>().is_err());
+    }
+}
@@ -0,0 +1,444 @@
+//! Represents a complete Era file
+//!
+//! The structure of an Era file follows the specification:
+//! `Version | block* | era-state | other-entries* | slot-index(block)? | slot-index(state)`
+//!
+//! See also <https://github.com/eth-clients/e2store-format-specs/blob/main/formats/era.md>.
+
+use crate::{
+    beacon_block::SignedBeaconBlockSsz,
+    consensus_types::{
+        self, CompressedBeaconState, CompressedSignedBeaconBlock, COMPRESSED_BEACON_STATE,
+    },
+    e2s_file::{E2StoreReader, E2StoreWriter},
+    e2s_types::{E2sError, Entry, IndexEntry, Version, SLOT_INDEX},
+    era_types::{EraGroup, EraId, SlotIndex, SLOTS_PER_HISTORICAL_ROOT},
+};
+use std::{
+    fs::File,
+    io::{Read, Seek, Write},
+    path::Path,
+};
+
+/// Era file interface
+#[derive(Debug)]
+pub struct EraFile {
+    /// Version record, must be the first record in the file
+    pub version: Version,
+
+    /// Main content group of the Era file
+    pub group: EraGroup,
+
+    /// File identifier
+    pub id: EraId,
+}
+
+impl EraFile {
+    /// Create a new [`EraFile`]
+    pub const fn new(group: EraGroup, id: EraId) -> Self {
+        Self { version: Version, group, id }
+    }
+
+    /// Get the slot of the era state, which is the first slot after the blocks of this file
+    pub const fn state_slot(&self) -> u64 {
+        self.group.state_slot_index.starting_slot
+    }
+}
+
+/// Reader for Era files that builds on top of [`E2StoreReader`]
+#[derive(Debug)]
+pub struct EraReader<R: Read> {
+    reader: E2StoreReader<R>,
+}
+
+/// An iterator of [`CompressedSignedBeaconBlock`] streaming from [`E2StoreReader`].
+#[derive(Debug)]
+pub struct BeaconBlockIterator<R: Read> {
+    reader: E2StoreReader<R>,
+    era_state: Option<CompressedBeaconState>,
+    other_entries: Vec<Entry>,
+    slot_indices: Vec<SlotIndex>,
+}
+
+impl<R: Read> BeaconBlockIterator<R> {
+    const fn new(reader: E2StoreReader<R>) -> Self {
+        Self { reader, era_state: None, other_entries: Vec::new(), slot_indices: Vec::new() }
+    }
+}
+
+impl<R: Read + Seek> Iterator for BeaconBlockIterator<R> {
+    type Item = Result<CompressedSignedBeaconBlock, E2sError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_result().transpose()
+    }
+}
+
+impl<R: Read + Seek> BeaconBlockIterator<R> {
+    fn next_result(&mut self) -> Result<Option<CompressedSignedBeaconBlock>, E2sError> {
+        loop {
+            let Some(entry) = self.reader.read_next_entry()? else {
+                return Ok(None);
+            };
+
+            match entry.entry_type {
+                consensus_types::COMPRESSED_SIGNED_BEACON_BLOCK => {
+                    return Ok(Some(CompressedSignedBeaconBlock::from_entry(&entry)?));
+                }
+                COMPRESSED_BEACON_STATE => {
+                    if self.era_state.is_some() {
+                        return Err(E2sError::Ssz("Multiple era state entries found".to_string()));
+                    }
+                    self.era_state = Some(CompressedBeaconState::from_entry(&entry)?);
+                }
+                SLOT_INDEX => {
+                    if self.slot_indices.len() == 2 {
+                        return Err(E2sError::Ssz(
+                            "More than two slot index entries found".to_string(),
+                        ));
+                    }
+                    self.slot_indices.push(SlotIndex::from_entry(&entry)?);
+                }
+                _ => {
+                    self.other_entries.push(entry);
+                }
+            }
+        }
+    }
+}
+
+impl<R: Read + Seek> EraReader<R> {
+    /// Create a new [`EraReader`]
+    pub fn new(reader: R) -> Self {
+        Self { reader: E2StoreReader::new(reader) }
+    }
+
+    /// Returns an iterator of [`CompressedSignedBeaconBlock`] streaming from `reader`.
+    pub fn iter(self) -> BeaconBlockIterator<R> {
+        BeaconBlockIterator::new(self.reader)
+    }
+
+    /// Returns the execution block number of the first beacon block of the file.
+    ///
+    /// Only the first block is read. Returns `None` if the file has no blocks or its first block
+    /// has no execution payload, i.e. it is from before the merge.
+    pub fn first_execution_block_number(self) -> Result<Option<u64>, E2sError> {
+        let Some(block) = self.iter().next().transpose()? else { return Ok(None) };
+        let data = block.decompress()?;
+
+        Ok(SignedBeaconBlockSsz::new(&data)?.execution_payload()?.map(|p| p.block_number()))
+    }
+
+    /// Reads and parses an Era file from the underlying reader, assembling all components
+    /// into a complete [`EraFile`] with an [`EraId`] that includes the provided network name.
+    pub fn read(mut self, network_name: String) -> Result<EraFile, E2sError> {
+        // Validate version entry
+        let _version_entry = match self.reader.read_version()? {
+            Some(entry) if entry.is_version() => entry,
+            Some(_) => return Err(E2sError::Ssz("First entry is not a Version entry".to_string())),
+            None => return Err(E2sError::Ssz("Empty Era file".to_string())),
+        };
+
+        let mut iter = self.iter();
+        let blocks = (&mut iter).collect::<Result<Vec<_>, _>>()?;
+
+        let BeaconBlockIterator { era_state, other_entries, mut slot_indices, .. } = iter;
+
+        let era_state = era_state
+            .ok_or_else(|| E2sError::Ssz("Era file missing era state entry".to_string()))?;
+
+        // The state slot index is always the last entry, the block slot index is only omitted for
+        // the genesis era
+        let state_slot_index = slot_indices
+            .pop()
+            .ok_or_else(|| E2sError::Ssz("Era file missing state slot index entry".to_string()))?;
+        let mut group = match slot_indices.pop() {
+            Some(slot_index) => {
+                if slot_index.slot_count() != SLOTS_PER_HISTORICAL_ROOT as usize {
+                    return Err(E2sError::Ssz(format!(
+                        "Block slot index must cover {SLOTS_PER_HISTORICAL_ROOT} slots, got {}",
+                        slot_index.slot_count()
+                    )));
+                }
+                EraGroup::with_block_index(blocks, era_state, slot_index, state_slot_index)
+            }
+            None if blocks.is_empty() => EraGroup::new(blocks, era_state, state_slot_index),
+            None => {
+                return Err(E2sError::Ssz("Era file missing block slot index entry".to_string()))
+            }
+        };
+
+        // Add other entries
+        for entry in other_entries {
+            group.add_entry(entry);
+        }
+
+        let id = EraId::new(
+            network_name,
+            group.state_slot_index.starting_slot / SLOTS_PER_HISTORICAL_ROOT,
+        );
+
+        Ok(EraFile::new(group, id))
+    }
+}
+
+impl EraReader<File> {
+    /// Opens and reads an Era file from the given path
+    pub fn open<P: AsRef<Path>>(
+        path: P,
+        network_name: impl Into<String>,
+    ) -> Result<EraFile, E2sError> {
+        let file = File::open(path).map_err(E2sError::Io)?;
+        let reader = Self::new(file);
+        reader.read(network_name.into())
+    }
+}
+
+/// Writer for Era files that builds on top of [`E2StoreWriter`]
+#[derive(Debug)]
+pub struct EraWriter<W: Write> {
+    writer: E2StoreWriter<W>,
+    has_written_version: bool,
+    has_written_era_state: bool,
+    has_written_slot_index: bool,
+    has_written_state_slot_index: bool,
+}
+
+impl<W: Write> EraWriter<W> {
+    /// Create a new [`EraWriter`]
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer: E2StoreWriter::new(writer),
+            has_written_version: false,
+            has_written_era_state: false,
+            has_written_slot_index: false,
+            has_written_state_slot_index: false,
+        }
+    }
+
+    /// Write the version entry
+    pub fn write_version(&mut self) -> Result<(), E2sError> {
+        if self.has_written_version {
+            return Ok(());
+        }
+
+        self.writer.write_version()?;
+        self.has_written_version = true;
+        Ok(())
+    }
+
+    /// Write a complete [`EraFile`] to the underlying writer
+    pub fn write_era_file(&mut self, era_file: &EraFile) -> Result<(), E2sError> {
+        // Write version
+        self.write_version()?;
+
+        if era_file.group.blocks.len() > SLOTS_PER_HISTORICAL_ROOT as usize {
+            return Err(E2sError::Ssz("Era file cannot contain more than 8192 blocks".to_string()));
+        }
+
+        // Write all blocks
+        for block in &era_file.group.blocks {
+            self.write_block(block)?;
+        }
+
+        // Write era state
+        self.write_era_state(&era_file.group.era_state)?;
+
+        // Write other entries
+        for entry in &era_file.group.other_entries {
+            self.writer.write_entry(entry)?;
+        }
+
+        // Write block slot index, omitted for the genesis era
+        if let Some(slot_index) = &era_file.group.slot_index {
+            self.write_slot_index(slot_index)?;
+        }
+
+        // Write state slot index
+        self.write_state_slot_index(&era_file.group.state_slot_index)?;
+
+        // Flush the writer
+        self.writer.flush()?;
+
+        Ok(())
+    }
+
+    /// Write a single signed beacon block
+    pub fn write_block(&mut self, block: &CompressedSignedBeaconBlock) -> Result<(), E2sError> {
+        if !self.has_written_version {
+            self.write_version()?;
+        }
+
+        if self.has_written_era_state {
+            return Err(E2sError::Ssz("Cannot write blocks after era state".to_string()));
+        }
+
+        self.writer.write_entry(&block.to_entry())
+    }
+
+    /// Write the era state
+    pub fn write_era_state(&mut self, era_state: &CompressedBeaconState) -> Result<(), E2sError> {
+        if !self.has_written_version {
+            self.write_version()?;
+        }
+
+        if self.has_written_era_state {
+            return Err(E2sError::Ssz("Era state already written".to_string()));
+        }
+
+        self.writer.write_entry(&era_state.to_entry())?;
+        self.has_written_era_state = true;
+
+        Ok(())
+    }
+
+    /// Write the block slot index
+    pub fn write_slot_index(&mut self, slot_index: &SlotIndex) -> Result<(), E2sError> {
+        if !self.has_written_era_state {
+            return Err(E2sError::Ssz("Cannot write slot index before era state".to_string()));
+        }
+
+        if self.has_written_slot_index {
+            return Err(E2sError::Ssz("Block slot index already written".to_string()));
+        }
+
+        if self.has_written_state_slot_index {
+            return Err(E2sError::Ssz(
+                "Cannot write block slot index after state slot index".to_string(),
+            ));
+        }
+
+        self.writer.write_entry(&slot_index.to_entry())?;
+        self.has_written_slot_index = true;
+
+        Ok(())
+    }
+
+    /// Write the state slot index
+    pub fn write_state_slot_index(&mut self, slot_index: &SlotIndex) -> Result<(), E2sError> {
+        if !self.has_written_era_state {
+            return Err(E2sError::Ssz("Cannot write state slot index before era state".to_string()));
+        }
+
+        if self.has_written_state_slot_index {
+            return Err(E2sError::Ssz("State slot index already written".to_string()));
+        }
+
+        self.writer.write_entry(&slot_index.to_entry())?;
+        self.has_written_state_slot_index = true;
+
+        Ok(())
+    }
+
+    /// Flush any buffered data to the underlying writer
+    pub fn flush(&mut self) -> Result<(), E2sError> {
+        self.writer.flush()
+    }
+}
+
+impl EraWriter<File> {
+    /// Creates a new file at the specified path and writes the [`EraFile`] to it
+    pub fn create<P: AsRef<Path>>(path: P, era_file: &EraFile) -> Result<(), E2sError> {
+        let file = File::create(path).map_err(E2sError::Io)?;
+        let mut writer = Self::new(file);
+        writer.write_era_file(era_file)?;
+        Ok(())
+    }
+
+    /// Creates a new file in the specified directory with a filename derived from the
+    /// [`EraFile`]'s ID using the standardized Era file naming convention
+    pub fn create_with_id<P: AsRef<Path>>(
+        directory: P,
+        era_file: &EraFile,
+    ) -> Result<(), E2sError> {
+        let filename = era_file.id.to_file_name();
+        let path = directory.as_ref().join(filename);
+        Self::create(path, era_file)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+    use tempfile::tempdir;
+
+    // Helper to create a sample Era file for testing
+    fn create_test_era_file(era_number: u64, block_count: usize, network: &str) -> EraFile {
+        let blocks = (0..block_count)
+            .map(|i| CompressedSignedBeaconBlock::new(vec![i as u8; 32]))
+            .collect::<Vec<_>>();
+        let era_state = CompressedBeaconState::new(vec![0xBB; 64]);
+        let state_slot = era_number * SLOTS_PER_HISTORICAL_ROOT;
+        let state_slot_index = SlotIndex::new(state_slot, vec![100]);
+
+        let group = if era_number == 0 {
+            EraGroup::new(blocks, era_state, state_slot_index)
+        } else {
+            let mut offsets = vec![0; SLOTS_PER_HISTORICAL_ROOT as usize];
+            for (i, offset) in offsets.iter_mut().take(block_count).enumerate() {
+                *offset = i as u64 * 40;
+            }
+            let slot_index = SlotIndex::new(state_slot - SLOTS_PER_HISTORICAL_ROOT, offsets);
+            EraGroup::with_block_index(blocks, era_state, slot_index, state_slot_index)
+        };
+
+        EraFile::new(group, EraId::new(network, era_number))
+    }
+
+    #[test]
+    fn test_era_roundtrip_memory() -> Result<(), E2sError> {
+        let era_file = create_test_era_file(600, 3, "testnet");
+
+        // Write to memory buffer
+        let mut buffer = Vec::new();
+        {
+            let mut writer = EraWriter::new(&mut buffer);
+            writer.write_era_file(&era_file)?;
+        }
+
+        // Read back from memory buffer
+        let reader = EraReader::new(Cursor::new(&buffer));
+        let read_era = reader.read("testnet".to_string())?;
+
+        assert_eq!(read_era.id, era_file.id);
+        assert_eq!(read_era.id.era_number, 600);
+        assert_eq!(read_era.state_slot(), 600 * SLOTS_PER_HISTORICAL_ROOT);
+        assert_eq!(read_era.group.blocks.len(), 3);
+        assert_eq!(read_era.group.blocks[2].data, vec![2; 32]);
+        assert_eq!(read_era.group.era_state.data, vec![0xBB; 64]);
+        assert_eq!(read_era.group.slot_index, era_file.group.slot_index);
+        assert_eq!(read_era.group.state_slot_index, era_file.group.state_slot_index);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_era_roundtrip_genesis_file() -> Result<(), E2sError> {
+        let temp_dir = tempdir().expect("Failed to create temp directory");
+
+        let era_file = create_test_era_file(0, 0, "mainnet");
+        EraWriter::create_with_id(temp_dir.path(), &era_file)?;
+
+        let read_era =
+            EraReader::open(temp_dir.path().join("mainnet-00000-00000000.era"), "mainnet")?;
+
+        assert!(read_era.group.is_genesis());
+        assert_eq!(read_era.id.era_number, 0);
+        assert_eq!(read_era.state_slot(), 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_era_writer_order() {
+        let mut writer = EraWriter::new(Vec::new());
+        let slot_index = SlotIndex::new(0, vec![100]);
+
+        assert!(writer.write_state_slot_index(&slot_index).is_err());
+        writer.write_era_state(&CompressedBeaconState::new(vec![1])).unwrap();
+        assert!(writer.write_block(&CompressedSignedBeaconBlock::new(vec![1])).is_err());
+        writer.write_state_slot_index(&slot_index).unwrap();
+        assert!(writer.write_slot_index(&slot_index).is_err());
+    }
+}
@@ -7,6 +7,10 @@ use crate::{
     e2s_types::{Entry, IndexEntry, SLOT_INDEX},
 };
 
+/// Number of slots of an era, blocks of an era are followed by the era state at the next multiple
+/// of this number.
+pub const SLOTS_PER_HISTORICAL_ROOT: u64 = 8192;
+
 /// Era file content group
 ///
 /// Format: `Version | block* | era-state | other-entries* | slot-index(block)? | slot-index(state)`
@@ -122,6 +126,41 @@ impl IndexEntry for SlotIndex {
     }
 }
 
+/// Era file identifier
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EraId {
+    /// Network configuration name
+    pub network_name: String,
+
+    /// Era number, the era state of the file is at slot `era_number * SLOTS_PER_HISTORICAL_ROOT`
+    pub era_number: u64,
+
+    /// Optional hash identifier for this file
+    /// First 4 bytes of the last historical root in the last state in the era file
+    pub hash: Option<[u8; 4]>,
+}
+
+impl EraId {
+    /// Create a new [`EraId`]
+    pub fn new(network_name: impl Into<String>, era_number: u64) -> Self {
+        Self { network_name: network_name.into(), era_number, hash: None }
+    }
+
+    /// Add a hash identifier to [`EraId`]
+    pub const fn with_hash(mut self, hash: [u8; 4]) -> Self {
+        self.hash = Some(hash);
+        self
+    }
+
+    /// Convert to file name following the era file naming:
+    /// `<config-name>-<era-number>-<short-historical-root>.era`
+    /// <https://github.com/eth-clients/e2store-format-specs/blob/main/formats/era.md#file-name>
+    pub fn to_file_name(&self) -> String {
+        let [a, b, c, d] = self.hash.unwrap_or_default();
+        format!("{}-{:05}-{a:02x}{b:02x}{c:02x}{d:02x}.era", self.network_name, self.era_number)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -258,6 +297,15 @@ mod tests {
         assert!(!era_with_index.is_genesis());
     }
 
+    #[test]
+    fn test_era_id_file_name() {
+        assert_eq!(EraId::new("mainnet", 0).to_file_name(), "mainnet-00000-00000000.era");
+        assert_eq!(
+            EraId::new("mainnet", 1337).with_hash([0xde, 0xad, 0xbe, 0xef]).to_file_name(),
+            "mainnet-01337-deadbeef.era"
+        );
+    }
+
     #[test]
     fn test_era_group_add_entries() {
         let blocks = vec![create_beacon_block(10)];
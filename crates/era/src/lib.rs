@@ -12,11 +12,13 @@
 //! - Era format: <https://github.com/eth-clients/e2store-format-specs/blob/main/formats/era.md>
 //! - Era1 format: <https://github.com/eth-clients/e2store-format-specs/blob/main/formats/era1.md>
 
+pub mod beacon_block;
 pub mod consensus_types;
 pub mod e2s_file;
 pub mod e2s_types;
 pub mod era1_file;
 pub mod era1_types;
+pub mod era_file;
 pub mod era_types;
 pub mod execution_types;
 
@@ -10,7 +10,7 @@ use reth_cli_commands::{
     config_cmd, db, download, dump_genesis, export_era, import, import_era, init_cmd, init_state,
     launcher::FnLauncher,
     node::{self, NoArgs},
-    p2p, prune, re_execute, recover, stage,
+    p2p, prune, re_execute, recover, snapshot, stage,
 };
 use reth_cli_runner::CliRunner;
 use reth_db::DatabaseEnv;
@@ -217,6 +217,7 @@ impl<C: ChainSpecParser, Ext: clap::Args + fmt::Debug> Cli<C, Ext> {
             Commands::DumpGenesis(command) => runner.run_blocking_until_ctrl_c(command.execute()),
             Commands::Db(command) => runner.run_blocking_until_ctrl_c(command.execute::<N>()),
             Commands::Download(command) => runner.run_blocking_until_ctrl_c(command.execute::<N>()),
+            Commands::Snapshot(command) => runner.run_blocking_until_ctrl_c(command.execute()),
             Commands::Stage(command) => {
                 runner.run_command_until_exit(|ctx| command.execute::<N, _>(ctx, components))
             }
@@ -274,6 +275,9 @@ pub enum Commands<C: ChainSpecParser, Ext: clap::Args + fmt::Debug> {
     /// Download public node snapshots
     #[command(name = "download")]
     Download(download::DownloadCommand<C>),
+    /// Create snapshots that can be downloaded with `reth download`
+    #[command(name = "snapshot")]
+    Snapshot(snapshot::Command<C>),
     /// Manipulate individual stages.
     #[command(name = "stage")]
     Stage(stage::Command<C>),
@@ -311,6 +315,7 @@ impl<C: ChainSpecParser, Ext: clap::Args + fmt::Debug> Commands<C, Ext> {
             Self::DumpGenesis(cmd) => cmd.chain_spec(),
             Self::Db(cmd) => cmd.chain_spec(),
             Self::Download(cmd) => cmd.chain_spec(),
+            Self::Snapshot(cmd) => cmd.chain_spec(),
             Self::Stage(cmd) => cmd.chain_spec(),
             Self::P2P(cmd) => cmd.chain_spec(),
             #[cfg(feature = "dev")]
@@ -1,6 +1,8 @@
-use alloy_eips::eip2718::Encodable2718;
+use alloy_eips::{eip2718::Encodable2718, BlockNumberOrTag};
 use alloy_genesis::Genesis;
-use alloy_primitives::{b256, hex};
+use alloy_primitives::{b256, hex, Address, Bytes, U256, U64};
+use alloy_provider::{Provider, ProviderBuilder};
+use alloy_rpc_types_eth::Block;
 use futures::StreamExt;
 use reth_chainspec::ChainSpec;
 use reth_node_api::{BlockBody, FullNodeComponents, FullNodePrimitives, NodeTypes};
@@ -8,11 +10,13 @@ use reth_node_builder::{
     rpc::RethRpcAddOns, DebugNodeLauncher, EngineNodeLauncher, FullNode, NodeBuilder, NodeConfig,
     NodeHandle,
 };
-use reth_node_core::args::DevArgs;
+use reth_node_core::args::{DevArgs, RpcServerArgs, TxPoolArgs};
 use reth_node_ethereum::{node::EthereumAddOns, EthereumNode};
 use reth_provider::{providers::BlockchainProvider, CanonStateSubscriptions};
 use reth_rpc_eth_api::helpers::EthTransactions;
+use reth_rpc_server_types::{RethRpcModule, RpcModuleSelection};
 use reth_tasks::TaskManager;
+use reth_transaction_pool::TransactionPool;
 use std::sync::Arc;
 
 #[tokio::test]
@@ -45,6 +49,154 @@ async fn can_run_dev_node() -> eyre::Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn can_control_dev_node_with_anvil_api() -> eyre::Result<()> {
+    reth_tracing::init_test_tracing();
+    let tasks = TaskManager::current();
+    let exec = tasks.executor();
+
+    let node_config =
+        NodeConfig::test()
+            .with_chain(custom_chain())
+            .with_dev(DevArgs { dev: true, ..Default::default() })
+            .with_rpc(RpcServerArgs::default().with_unused_ports().with_http().with_http_api(
+                RpcModuleSelection::from([RethRpcModule::Eth, RethRpcModule::Anvil]),
+            ));
+    let NodeHandle { node, .. } = NodeBuilder::new(node_config.clone())
+        .testing_node(exec.clone())
+        .with_types_and_provider::<EthereumNode, BlockchainProvider<_>>()
+        .with_components(EthereumNode::components())
+        .with_add_ons(EthereumAddOns::default())
+        .launch_with_fn(|builder| {
+            let engine_launcher = EngineNodeLauncher::new(
+                builder.task_executor().clone(),
+                builder.config().datadir(),
+                Default::default(),
+            );
+            let launcher = DebugNodeLauncher::new(engine_launcher);
+            builder.launch_with(launcher)
+        })
+        .await?;
+
+    let addr = node.rpc_server_handle().http_local_addr().unwrap();
+    let provider = ProviderBuilder::new().connect_http(format!("http://{addr}").parse()?);
+
+    // dev nodes mine a block per transaction by default
+    assert!(provider.raw_request::<_, bool>("anvil_getAutomine".into(), ()).await?);
+    provider.raw_request::<_, ()>("anvil_setAutomine".into(), (false,)).await?;
+    assert!(!provider.raw_request::<_, bool>("anvil_getAutomine".into(), ()).await?);
+
+    let start = provider.get_block_number().await?;
+    provider.raw_request::<_, ()>("anvil_mine".into(), (U256::from(3), None::<U256>)).await?;
+    assert_eq!(provider.get_block_number().await?, start + 3);
+
+    // the number of blocks mined by a single call is capped
+    let err = provider
+        .raw_request::<_, ()>("anvil_mine".into(), (U256::from(1_001), None::<U256>))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("can't mine more than"), "{err}");
+    assert_eq!(provider.get_block_number().await?, start + 3);
+
+    let latest = provider.get_block_by_number(BlockNumberOrTag::Latest).await?.unwrap();
+    let timestamp = latest.header.timestamp + 1_000;
+    provider.raw_request::<_, ()>("anvil_setNextBlockTimestamp".into(), (timestamp,)).await?;
+    let mined = provider.raw_request::<_, Vec<Block>>("anvil_mine_detailed".into(), ()).await?;
+    assert_eq!(mined.len(), 1);
+    assert_eq!(mined[0].header.number, start + 4);
+    assert_eq!(mined[0].header.timestamp, timestamp);
+
+    // timestamps can't go backwards
+    let err = provider
+        .raw_request::<_, ()>("anvil_setNextBlockTimestamp".into(), (timestamp,))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("not greater than"), "{err}");
+
+    provider.raw_request::<_, ()>("anvil_setBlockTimestampInterval".into(), (12u64,)).await?;
+    provider.raw_request::<_, ()>("anvil_mine".into(), (U256::from(2), None::<U256>)).await?;
+    let latest = provider.get_block_by_number(BlockNumberOrTag::Latest).await?.unwrap();
+    assert_eq!(latest.header.timestamp, timestamp + 24);
+    assert!(
+        provider.raw_request::<_, bool>("anvil_removeBlockTimestampInterval".into(), ()).await?
+    );
+
+    // transactions that are pending when automine is enabled are mined right away
+    let raw_tx = hex!(
+        "02f876820a28808477359400847735940082520894ab0840c0e43688012c1adb0f5e3fc665188f83d28a029d394a5d630544000080c080a0a044076b7e67b5deecc63f61a8d7913fab86ca365b344b5759d1fe3563b4c39ea019eab979dd000da04dfc72bb0377c092d30fd9e1cab5ae487de49586cc8b0090"
+    );
+    let tx_hash = *provider.send_raw_transaction(&raw_tx).await?.tx_hash();
+    let pending_block = provider.get_block_number().await? + 1;
+    provider.raw_request::<_, ()>("anvil_setAutomine".into(), (true,)).await?;
+    let block = provider.get_block_by_number(pending_block.into()).await?.unwrap();
+    assert_eq!(block.transactions.hashes().collect::<Vec<_>>(), vec![tx_hash]);
+
+    // state can only change through mined blocks
+    let err = provider
+        .raw_request::<_, ()>("anvil_setBalance".into(), (Address::ZERO, U256::from(1)))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("not supported"), "{err}");
+    let err = provider.raw_request::<_, U256>("anvil_snapshot".into(), ()).await.unwrap_err();
+    assert!(err.to_string().contains("not supported"), "{err}");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn can_include_bundles_in_dev_blocks() -> eyre::Result<()> {
+    reth_tracing::init_test_tracing();
+    let tasks = TaskManager::current();
+    let exec = tasks.executor();
+
+    let node_config =
+        NodeConfig::test()
+            .with_chain(custom_chain())
+            .with_dev(DevArgs { dev: true, ..Default::default() })
+            .with_txpool(TxPoolArgs { max_bundles: 10, ..Default::default() })
+            .with_rpc(RpcServerArgs::default().with_unused_ports().with_http().with_http_api(
+                RpcModuleSelection::from([RethRpcModule::Eth, RethRpcModule::Anvil]),
+            ));
+    let NodeHandle { node, .. } = NodeBuilder::new(node_config.clone())
+        .testing_node(exec.clone())
+        .with_types_and_provider::<EthereumNode, BlockchainProvider<_>>()
+        .with_components(EthereumNode::components())
+        .with_add_ons(EthereumAddOns::default())
+        .launch_with_fn(|builder| {
+            let engine_launcher = EngineNodeLauncher::new(
+                builder.task_executor().clone(),
+                builder.config().datadir(),
+                Default::default(),
+            );
+            let launcher = DebugNodeLauncher::new(engine_launcher);
+            builder.launch_with(launcher)
+        })
+        .await?;
+
+    let addr = node.rpc_server_handle().http_local_addr().unwrap();
+    let provider = ProviderBuilder::new().connect_http(format!("http://{addr}").parse()?);
+    provider.raw_request::<_, ()>("anvil_setAutomine".into(), (false,)).await?;
+
+    let raw_tx = hex!(
+        "02f876820a28808477359400847735940082520894ab0840c0e43688012c1adb0f5e3fc665188f83d28a029d394a5d630544000080c080a0a044076b7e67b5deecc63f61a8d7913fab86ca365b344b5759d1fe3563b4c39ea019eab979dd000da04dfc72bb0377c092d30fd9e1cab5ae487de49586cc8b0090"
+    );
+    let tx_hash = b256!("0xb1c6512f4fc202c04355fbda66755e0e344b152e633010e8fd75ecec09b63398");
+
+    let next_block = provider.get_block_number().await? + 1;
+    let bundle =
+        serde_json::json!({ "txs": [Bytes::from(raw_tx)], "blockNumber": U64::from(next_block) });
+    let response: serde_json::Value =
+        provider.raw_request("eth_sendBundle".into(), (bundle,)).await?;
+    assert!(response["bundleHash"].is_string());
+    assert_eq!(node.pool.bundle_pool().unwrap().len(), 1);
+
+    provider.raw_request::<_, ()>("anvil_mine".into(), (U256::from(1), None::<U256>)).await?;
+    let block = provider.get_block_by_number(next_block.into()).await?.unwrap();
+    assert_eq!(block.transactions.hashes().collect::<Vec<_>>(), vec![tx_hash]);
+
+    Ok(())
+}
+
 async fn assert_chain_advances<N, AddOns>(node: FullNode<N, AddOns>)
 where
     N: FullNodeComponents<Provider: CanonStateSubscriptions>,
@@ -3,17 +3,23 @@ use alloy_eips::eip2718::Encodable2718;
 use alloy_primitives::{Address, B256, U256};
 use alloy_provider::{network::EthereumWallet, Provider, ProviderBuilder, SendableTx};
 use alloy_rpc_types_beacon::relay::{
-    BidTrace, BuilderBlockValidationRequestV3, BuilderBlockValidationRequestV4,
-    SignedBidSubmissionV3, SignedBidSubmissionV4,
+    BidTrace, BuilderBlockValidationRequest, BuilderBlockValidationRequestV2,
+    BuilderBlockValidationRequestV3, BuilderBlockValidationRequestV4, SignedBidSubmissionV1,
+    SignedBidSubmissionV2, SignedBidSubmissionV3, SignedBidSubmissionV4,
+};
+use alloy_rpc_types_engine::{
+    BlobsBundleV1, ExecutionPayloadV1, ExecutionPayloadV2, ExecutionPayloadV3, PayloadAttributes,
 };
-use alloy_rpc_types_engine::{BlobsBundleV1, ExecutionPayloadV3};
 use alloy_rpc_types_eth::TransactionRequest;
+use futures::{SinkExt, StreamExt};
 use rand::{rngs::StdRng, Rng, SeedableRng};
 use reth_chainspec::{ChainSpecBuilder, EthChainSpec, MAINNET};
 use reth_e2e_test_utils::setup_engine;
+use reth_ethereum_engine_primitives::EthPayloadBuilderAttributes;
 use reth_node_ethereum::EthereumNode;
 use reth_payload_primitives::BuiltPayload;
 use std::sync::Arc;
+use tokio_tungstenite::tungstenite::{self, Message};
 
 alloy_sol_types::sol! {
     #[sol(rpc, bytecode = "6080604052348015600f57600080fd5b5060405160db38038060db833981016040819052602a91607a565b60005b818110156074576040805143602082015290810182905260009060600160408051601f19818403018152919052805160209091012080555080606d816092565b915050602d565b505060b8565b600060208284031215608b57600080fd5b5051919050565b60006001820160b157634e487b7160e01b600052601160045260246000fd5b5060010190565b60168060c56000396000f3fe6080604052600080fdfea164736f6c6343000810000a")]
@@ -116,6 +122,414 @@ async fn test_fee_history() -> eyre::Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_debug_standard_trace_block_to_file() -> eyre::Result<()> {
+    reth_tracing::init_test_tracing();
+
+    let chain_spec = Arc::new(
+        ChainSpecBuilder::default()
+            .chain(MAINNET.chain)
+            .genesis(serde_json::from_str(include_str!("../assets/genesis.json")).unwrap())
+            .cancun_activated()
+            .build(),
+    );
+
+    let (mut nodes, _tasks, wallet) = setup_engine::<EthereumNode>(
+        1,
+        chain_spec.clone(),
+        false,
+        Default::default(),
+        eth_payload_attributes,
+    )
+    .await?;
+    let mut node = nodes.pop().unwrap();
+    let provider = ProviderBuilder::new()
+        .wallet(EthereumWallet::new(wallet.wallet_gen().swap_remove(0)))
+        .connect_http(node.rpc_url());
+
+    let builder = GasWaster::deploy_builder(&provider, U256::from(10)).send().await?;
+    node.advance_block().await?;
+    let receipt = builder.get_receipt().await?;
+    let block_hash = receipt.block_hash.unwrap();
+
+    let paths: Vec<String> =
+        provider.raw_request("debug_standardTraceBlockToFile".into(), (block_hash,)).await?;
+    assert_eq!(paths.len(), 1);
+    assert!(paths[0].contains("debug_traces"));
+
+    let trace = std::fs::read_to_string(&paths[0])?;
+    let lines = trace
+        .lines()
+        .map(serde_json::from_str::<serde_json::Value>)
+        .collect::<Result<Vec<_>, _>>()?;
+    // every executed opcode is followed by the summary of the transaction
+    let (summary, steps) = lines.split_last().unwrap();
+    assert!(!steps.is_empty());
+    assert!(steps.iter().all(|step| step.get("op").is_some() && step.get("memory").is_none()));
+    assert_eq!(summary["pass"], true);
+
+    let paths: Vec<String> = provider
+        .raw_request(
+            "debug_standardTraceBlockToFile".into(),
+            (
+                block_hash,
+                serde_json::json!({ "enableMemory": true, "txHash": receipt.transaction_hash }),
+            ),
+        )
+        .await?;
+    assert_eq!(paths.len(), 1);
+    let trace = std::fs::read_to_string(&paths[0])?;
+    assert!(trace.lines().next().unwrap().contains("\"memory\""));
+
+    // unknown transactions are rejected
+    assert!(provider
+        .raw_request::<_, Vec<String>>(
+            "debug_standardTraceBlockToFile".into(),
+            (block_hash, serde_json::json!({ "txHash": B256::ZERO })),
+        )
+        .await
+        .is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_debug_trace_chain_subscription() -> eyre::Result<()> {
+    reth_tracing::init_test_tracing();
+
+    let chain_spec = Arc::new(
+        ChainSpecBuilder::default()
+            .chain(MAINNET.chain)
+            .genesis(serde_json::from_str(include_str!("../assets/genesis.json")).unwrap())
+            .cancun_activated()
+            .build(),
+    );
+
+    let (mut nodes, _tasks, wallet) = setup_engine::<EthereumNode>(
+        1,
+        chain_spec.clone(),
+        false,
+        Default::default(),
+        eth_payload_attributes,
+    )
+    .await?;
+    let mut node = nodes.pop().unwrap();
+    let provider = ProviderBuilder::new()
+        .wallet(EthereumWallet::new(wallet.wallet_gen().swap_remove(0)))
+        .connect_http(node.rpc_url());
+
+    for _ in 0..3 {
+        let builder = GasWaster::deploy_builder(&provider, U256::from(10)).send().await?;
+        node.advance_block().await?;
+        builder.get_receipt().await?;
+    }
+
+    let (mut ws, _) = tokio_tungstenite::connect_async(node.ws_url().as_str()).await?;
+
+    // every block is streamed as a separate notification, in block order
+    let subscription =
+        ws_subscribe(&mut ws, serde_json::json!(["0x0", "0x3", { "tracer": "callTracer" }]))
+            .await?;
+    for block in 1..=3u64 {
+        let notification = ws_next(&mut ws).await?;
+        assert_eq!(notification["params"]["subscription"], subscription);
+        let result = &notification["params"]["result"];
+        assert_eq!(result["block"], format!("{block:#x}"));
+        assert_eq!(result["traces"].as_array().unwrap().len(), 1);
+    }
+
+    // a block that fails to be traced ends the subscription with an error
+    let subscription =
+        ws_subscribe(&mut ws, serde_json::json!(["0x0", "0x3", { "tracer": "not a tracer" }]))
+            .await?;
+    let notification = ws_next(&mut ws).await?;
+    assert_eq!(notification["params"]["subscription"], subscription);
+    assert!(notification["params"].get("result").is_none());
+    assert!(notification["params"]["error"]["message"].is_string());
+
+    Ok(())
+}
+
+/// Sends a `debug_traceChain` subscription request and returns the subscription id.
+async fn ws_subscribe<S>(ws: &mut S, params: serde_json::Value) -> eyre::Result<serde_json::Value>
+where
+    S: futures::Sink<Message, Error = tungstenite::Error>
+        + futures::Stream<Item = Result<Message, tungstenite::Error>>
+        + Unpin,
+{
+    let request = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "debug_traceChain",
+        "params": params,
+    });
+    ws.send(Message::text(request.to_string())).await?;
+    let response = ws_next(ws).await?;
+    Ok(response["result"].clone())
+}
+
+/// Returns the next JSON message received over the websocket.
+async fn ws_next<S>(ws: &mut S) -> eyre::Result<serde_json::Value>
+where
+    S: futures::Stream<Item = Result<Message, tungstenite::Error>> + Unpin,
+{
+    loop {
+        let message = ws.next().await.ok_or_else(|| eyre::eyre!("websocket closed"))??;
+        if let Message::Text(text) = message {
+            return Ok(serde_json::from_str(&text)?)
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_ots_search_transactions_pagination() -> eyre::Result<()> {
+    reth_tracing::init_test_tracing();
+
+    let chain_spec = Arc::new(
+        ChainSpecBuilder::default()
+            .chain(MAINNET.chain)
+            .genesis(serde_json::from_str(include_str!("../assets/genesis.json")).unwrap())
+            .cancun_activated()
+            .build(),
+    );
+
+    let (mut nodes, _tasks, wallet) = setup_engine::<EthereumNode>(
+        1,
+        chain_spec.clone(),
+        false,
+        Default::default(),
+        eth_payload_attributes,
+    )
+    .await?;
+    let mut node = nodes.pop().unwrap();
+    let provider = ProviderBuilder::new()
+        .wallet(EthereumWallet::new(wallet.wallet_gen().swap_remove(0)))
+        .connect_http(node.rpc_url());
+
+    // blocks 1..=5 each transfer to the recipient, block 3 additionally transfers elsewhere
+    let recipient = Address::random();
+    for block in 1..=5u64 {
+        let tx = TransactionRequest::default().to(recipient).value(U256::from(block));
+        let mut pending = vec![provider.send_transaction(tx).await?];
+        if block == 3 {
+            let tx = TransactionRequest::default().to(Address::random()).value(U256::from(1));
+            pending.push(provider.send_transaction(tx).await?);
+        }
+        node.advance_block().await?;
+        for tx in pending {
+            assert_eq!(tx.get_receipt().await?.block_number, Some(block));
+        }
+    }
+
+    let search = async |method: &str, block: u64| -> eyre::Result<(Vec<u64>, bool, bool)> {
+        let page: serde_json::Value =
+            provider.raw_request(method.to_string().into(), (recipient, block, 2)).await?;
+        let blocks = page["txs"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|tx| {
+                u64::from_str_radix(
+                    tx["blockNumber"].as_str().unwrap().trim_start_matches("0x"),
+                    16,
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(page["receipts"].as_array().unwrap().len(), blocks.len());
+        Ok((blocks, page["firstPage"].as_bool().unwrap(), page["lastPage"].as_bool().unwrap()))
+    };
+
+    // newest to oldest, two transactions per page
+    assert_eq!(search("ots_searchTransactionsBefore", 0).await?, (vec![5, 4], true, false));
+    assert_eq!(search("ots_searchTransactionsBefore", 4).await?, (vec![3, 2], false, false));
+    assert_eq!(search("ots_searchTransactionsBefore", 2).await?, (vec![1], false, true));
+
+    // oldest to newest, pages are still ordered from newest to oldest
+    assert_eq!(search("ots_searchTransactionsAfter", 0).await?, (vec![2, 1], false, true));
+    assert_eq!(search("ots_searchTransactionsAfter", 2).await?, (vec![4, 3], false, false));
+    assert_eq!(search("ots_searchTransactionsAfter", 4).await?, (vec![5], true, false));
+    assert_eq!(search("ots_searchTransactionsAfter", u64::MAX).await?, (vec![], true, false));
+
+    // an empty page is rejected
+    let empty_page = provider
+        .raw_request::<_, serde_json::Value>(
+            "ots_searchTransactionsBefore".into(),
+            (recipient, 0, 0),
+        )
+        .await;
+    assert!(empty_page.is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_flashbots_validate_v1() -> eyre::Result<()> {
+    reth_tracing::init_test_tracing();
+
+    let chain_spec = Arc::new(
+        ChainSpecBuilder::default()
+            .chain(MAINNET.chain)
+            .genesis(serde_json::from_str(include_str!("../assets/genesis.json")).unwrap())
+            .paris_activated()
+            .build(),
+    );
+
+    // Bellatrix payloads have neither withdrawals nor a parent beacon block root
+    let (mut nodes, _tasks, wallet) = setup_engine::<EthereumNode>(
+        1,
+        chain_spec.clone(),
+        false,
+        Default::default(),
+        |timestamp| {
+            EthPayloadBuilderAttributes::new(
+                B256::ZERO,
+                PayloadAttributes {
+                    timestamp,
+                    prev_randao: B256::ZERO,
+                    suggested_fee_recipient: Address::ZERO,
+                    withdrawals: None,
+                    parent_beacon_block_root: None,
+                },
+            )
+        },
+    )
+    .await?;
+    let mut node = nodes.pop().unwrap();
+    let provider = ProviderBuilder::new()
+        .wallet(EthereumWallet::new(wallet.wallet_gen().swap_remove(0)))
+        .connect_http(node.rpc_url());
+
+    let _ = provider.send_transaction(TransactionRequest::default().to(Address::ZERO)).await?;
+    let payload = node.new_payload().await?;
+
+    let mut request = BuilderBlockValidationRequest {
+        request: SignedBidSubmissionV1 {
+            message: BidTrace {
+                parent_hash: payload.block().parent_hash,
+                block_hash: payload.block().hash(),
+                gas_used: payload.block().gas_used,
+                gas_limit: payload.block().gas_limit,
+                ..Default::default()
+            },
+            execution_payload: ExecutionPayloadV1::from_block_unchecked(
+                payload.block().hash(),
+                &payload.block().clone().into_block(),
+            ),
+            signature: Default::default(),
+        },
+        registered_gas_limit: payload.block().gas_limit,
+    };
+
+    provider
+        .raw_request::<_, ()>("flashbots_validateBuilderSubmissionV1".into(), (&request,))
+        .await
+        .expect("request should validate");
+
+    request.request.execution_payload.state_root = B256::ZERO;
+    assert!(provider
+        .raw_request::<_, ()>("flashbots_validateBuilderSubmissionV1".into(), (&request,))
+        .await
+        .is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_flashbots_validate_v2() -> eyre::Result<()> {
+    reth_tracing::init_test_tracing();
+
+    let chain_spec = Arc::new(
+        ChainSpecBuilder::default()
+            .chain(MAINNET.chain)
+            .genesis(serde_json::from_str(include_str!("../assets/genesis.json")).unwrap())
+            .shanghai_activated()
+            .build(),
+    );
+
+    // Capella payloads don't have a parent beacon block root
+    let (mut nodes, _tasks, wallet) = setup_engine::<EthereumNode>(
+        1,
+        chain_spec.clone(),
+        false,
+        Default::default(),
+        |timestamp| {
+            EthPayloadBuilderAttributes::new(
+                B256::ZERO,
+                PayloadAttributes {
+                    timestamp,
+                    prev_randao: B256::ZERO,
+                    suggested_fee_recipient: Address::ZERO,
+                    withdrawals: Some(vec![]),
+                    parent_beacon_block_root: None,
+                },
+            )
+        },
+    )
+    .await?;
+    let mut node = nodes.pop().unwrap();
+    let provider = ProviderBuilder::new()
+        .wallet(EthereumWallet::new(wallet.wallet_gen().swap_remove(0)))
+        .connect_http(node.rpc_url());
+
+    let _ = provider.send_transaction(TransactionRequest::default().to(Address::ZERO)).await?;
+    let payload = node.new_payload().await?;
+    let block = payload.block().clone().into_block();
+    let message = BidTrace {
+        parent_hash: payload.block().parent_hash,
+        block_hash: payload.block().hash(),
+        gas_used: payload.block().gas_used,
+        gas_limit: payload.block().gas_limit,
+        ..Default::default()
+    };
+
+    let mut request = BuilderBlockValidationRequestV2 {
+        request: SignedBidSubmissionV2 {
+            message: message.clone(),
+            execution_payload: ExecutionPayloadV2::from_block_unchecked(
+                payload.block().hash(),
+                &block,
+            ),
+            signature: Default::default(),
+        },
+        registered_gas_limit: payload.block().gas_limit,
+    };
+
+    provider
+        .raw_request::<_, ()>("flashbots_validateBuilderSubmissionV2".into(), (&request,))
+        .await
+        .expect("request should validate");
+
+    request.registered_gas_limit -= 1;
+    assert!(provider
+        .raw_request::<_, ()>("flashbots_validateBuilderSubmissionV2".into(), (&request,))
+        .await
+        .is_err());
+    request.registered_gas_limit += 1;
+
+    request.request.execution_payload.payload_inner.state_root = B256::ZERO;
+    assert!(provider
+        .raw_request::<_, ()>("flashbots_validateBuilderSubmissionV2".into(), (&request,))
+        .await
+        .is_err());
+
+    // Bellatrix payloads can't carry the withdrawals required after Shanghai
+    let request = BuilderBlockValidationRequest {
+        request: SignedBidSubmissionV1 {
+            message,
+            execution_payload: ExecutionPayloadV1::from_block_unchecked(
+                payload.block().hash(),
+                &block,
+            ),
+            signature: Default::default(),
+        },
+        registered_gas_limit: payload.block().gas_limit,
+    };
+    assert!(provider
+        .raw_request::<_, ()>("flashbots_validateBuilderSubmissionV1".into(), (&request,))
+        .await
+        .is_err());
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_flashbots_validate_v3() -> eyre::Result<()> {
     reth_tracing::init_test_tracing();
@@ -20,21 +20,28 @@ use reth_errors::{BlockExecutionError, BlockValidationError};
 use reth_ethereum_primitives::{EthPrimitives, TransactionSigned};
 use reth_evm::{
     execute::{BlockBuilder, BlockBuilderOutcome},
-    ConfigureEvm, Evm, NextBlockEnvAttributes,
+    system_calls::SystemCaller,
+    ConfigureEvm, Evm, EvmError, NextBlockEnvAttributes,
 };
 use reth_evm_ethereum::EthEvmConfig;
 use reth_payload_builder::{BlobSidecars, EthBuiltPayload, EthPayloadBuilderAttributes};
 use reth_payload_builder_primitives::PayloadBuilderError;
 use reth_payload_primitives::PayloadBuilderAttributes;
 use reth_primitives_traits::transaction::error::InvalidTransactionError;
-use reth_revm::{database::StateProviderDatabase, db::State};
+use reth_revm::{
+    database::StateProviderDatabase,
+    db::{CacheDB, State},
+};
 use reth_storage_api::StateProviderFactory;
 use reth_transaction_pool::{
     error::{Eip4844PoolTransactionError, InvalidPoolTransactionError},
     BestTransactions, BestTransactionsAttributes, PoolTransaction, TransactionPool,
     ValidPoolTransaction,
 };
-use revm::context_interface::Block as _;
+use revm::{
+    context_interface::{result::ResultAndState, Block as _},
+    DatabaseCommit,
+};
 use std::sync::Arc;
 use tracing::{debug, trace, warn};
 
@@ -155,19 +162,16 @@ where
     let mut db =
         State::builder().with_database(cached_reads.as_db_mut(state)).with_bundle_update().build();
 
+    let next_block_attributes = NextBlockEnvAttributes {
+        timestamp: attributes.timestamp(),
+        suggested_fee_recipient: attributes.suggested_fee_recipient(),
+        prev_randao: attributes.prev_randao(),
+        gas_limit: builder_config.gas_limit(parent_header.gas_limit),
+        parent_beacon_block_root: attributes.parent_beacon_block_root(),
+        withdrawals: Some(attributes.withdrawals().clone()),
+    };
     let mut builder = evm_config
-        .builder_for_next_block(
-            &mut db,
-            &parent_header,
-            NextBlockEnvAttributes {
-                timestamp: attributes.timestamp(),
-                suggested_fee_recipient: attributes.suggested_fee_recipient(),
-                prev_randao: attributes.prev_randao(),
-                gas_limit: builder_config.gas_limit(parent_header.gas_limit),
-                parent_beacon_block_root: attributes.parent_beacon_block_root(),
-                withdrawals: Some(attributes.withdrawals().clone()),
-            },
-        )
+        .builder_for_next_block(&mut db, &parent_header, next_block_attributes.clone())
         .map_err(PayloadBuilderError::other)?;
 
     let chain_spec = client.chain_spec();
@@ -188,6 +192,122 @@ where
         PayloadBuilderError::Internal(err.into())
     })?;
 
+    // bundles are included at the top of the block, each bundle is simulated on top of the parent
+    // state, the pre-execution changes and the bundles included before it, so that it is either
+    // included as a whole or not at all
+    if let Some(bundle_pool) = pool.bundle_pool() {
+        let evm_env = evm_config
+            .next_evm_env(parent_header.header(), &next_block_attributes)
+            .map_err(PayloadBuilderError::other)?;
+        let mut sim_db = CacheDB::new(StateProviderDatabase::new(&state_provider));
+
+        // mirror the system calls the builder applied above, bundles may read the beacon root and
+        // block hash history contracts
+        {
+            let mut system_caller = SystemCaller::new(chain_spec.clone());
+            let mut evm = evm_config.evm_with_env(&mut sim_db, evm_env.clone());
+            system_caller
+                .apply_blockhashes_contract_call(parent_header.hash(), &mut evm)
+                .and_then(|_| {
+                    system_caller.apply_beacon_root_contract_call(
+                        attributes.parent_beacon_block_root(),
+                        &mut evm,
+                    )
+                })
+                .map_err(|err| PayloadBuilderError::Internal(err.into()))?;
+        }
+
+        let mut failed_bundles = Vec::new();
+
+        for (bundle_hash, bundle) in
+            bundle_pool.best_bundles(parent_header.number + 1, attributes.timestamp())
+        {
+            // check if the job was cancelled, if so we can exit early
+            if cancel.is_cancelled() {
+                return Ok(BuildOutcome::Cancelled)
+            }
+
+            let mut included = Vec::with_capacity(bundle.transactions.len());
+            let mut bundle_state = Vec::with_capacity(bundle.transactions.len());
+            let mut bundle_gas_used = 0;
+            let mut bundle_failed = false;
+            // the executor rejects transactions whose gas limit exceeds the remaining block gas,
+            // so this is checked against the gas limits and not only the gas used
+            let mut exceeds_gas_limit = false;
+            {
+                let mut evm = evm_config.evm_with_env(CacheDB::new(&sim_db), evm_env.clone());
+                for bundle_tx in &bundle.transactions {
+                    // blob sidecars of bundle transactions are not available
+                    if bundle_tx.tx.is_eip4844() {
+                        bundle_failed = true;
+                        break
+                    }
+
+                    match evm.transact(evm_config.tx_env(&bundle_tx.tx)) {
+                        Ok(ResultAndState { result, state }) => {
+                            if !result.is_success() && !bundle_tx.can_revert {
+                                bundle_failed = true;
+                                break
+                            }
+                            if cumulative_gas_used + bundle_gas_used + bundle_tx.tx.gas_limit() >
+                                block_gas_limit
+                            {
+                                exceeds_gas_limit = true;
+                                break
+                            }
+                            evm.db_mut().commit(state.clone());
+                            bundle_state.push(state);
+                            bundle_gas_used += result.gas_used();
+                            included.push(&bundle_tx.tx);
+                        }
+                        Err(err) if err.is_invalid_tx_err() => {
+                            if !bundle_tx.can_drop {
+                                bundle_failed = true;
+                                break
+                            }
+                        }
+                        // a failing bundle only discards the bundle, not the payload
+                        Err(err) => {
+                            debug!(target: "payload_builder", ?bundle_hash, %err, "failed to simulate bundle");
+                            bundle_failed = true;
+                            break
+                        }
+                    }
+                }
+            }
+
+            if bundle_failed {
+                trace!(target: "payload_builder", ?bundle_hash, "skipping failed bundle");
+                failed_bundles.push(bundle_hash);
+                continue
+            }
+            if exceeds_gas_limit {
+                trace!(target: "payload_builder", ?bundle_hash, bundle_gas_used, "skipping bundle that exceeds the block gas limit");
+                continue
+            }
+
+            for state in bundle_state {
+                sim_db.commit(state);
+            }
+            // the simulation ran on the same state and block env as the builder and checked the
+            // gas limits, so executing the bundle can only fail on database errors, which are
+            // fatal for this attempt like they are for pool transactions
+            for tx in included {
+                let gas_used =
+                    builder.execute_transaction(tx.clone()).map_err(PayloadBuilderError::evm)?;
+                let miner_fee = tx
+                    .effective_tip_per_gas(base_fee)
+                    .expect("fee is always valid; execution succeeded");
+                total_fees += U256::from(miner_fee) * U256::from(gas_used);
+                cumulative_gas_used += gas_used;
+            }
+            trace!(target: "payload_builder", ?bundle_hash, bundle_gas_used, "included bundle");
+        }
+
+        // bundles that reverted or became invalid can't be included anymore
+        bundle_pool.remove_bundles(failed_bundles);
+    }
+
     // initialize empty blob sidecars at first. If cancun is active then this will be populated by
     // blob sidecars if any.
     let mut blob_sidecars = BlobSidecars::Empty;
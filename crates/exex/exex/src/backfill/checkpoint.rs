@@ -0,0 +1,318 @@
+use super::{job::BackfillJobResult, stream::StreamBackfillJob};
+use alloy_consensus::BlockHeader;
+use alloy_primitives::BlockNumber;
+use futures::{Stream, StreamExt};
+use metrics::Gauge;
+use reth_evm::{execute::BlockExecutionError, ConfigureEvm};
+use reth_fs_util::FsPathError;
+use reth_metrics::Metrics;
+use reth_node_api::NodePrimitives;
+use reth_provider::{BlockReader, Chain, StateProviderFactory};
+use reth_tracing::tracing::{debug, info};
+use serde::{Deserialize, Serialize};
+use std::{
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+    pin::Pin,
+    task::{ready, Context, Poll},
+    time::Instant,
+};
+use tokio_util::sync::CancellationToken;
+
+/// Durable progress of a [`CheckpointedBackfillJob`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackfillCheckpoint {
+    /// The first block of the backfilled range.
+    pub start: BlockNumber,
+    /// The highest block that has been processed by the `ExEx`, if any.
+    pub block_number: Option<BlockNumber>,
+}
+
+impl BackfillCheckpoint {
+    /// Returns the path to the checkpoint file of the `ExEx` with the given ID in `directory`.
+    ///
+    /// The ID is escaped, so that it can't name a file outside of `directory`: every character
+    /// other than ASCII alphanumerics, `-`, `_` and non-leading `.` is replaced with `%XX` for each
+    /// of its UTF-8 bytes.
+    pub fn path(directory: &Path, id: &str) -> PathBuf {
+        let mut file_name = String::with_capacity(id.len() + 5);
+        for (i, c) in id.char_indices() {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' || (c == '.' && i > 0) {
+                file_name.push(c);
+            } else {
+                let mut buf = [0; 4];
+                for byte in c.encode_utf8(&mut buf).bytes() {
+                    file_name.push_str(&format!("%{byte:02X}"));
+                }
+            }
+        }
+        file_name.push_str(".json");
+        directory.join(file_name)
+    }
+
+    /// Reads the checkpoint from the file at the given path, if it exists.
+    pub fn read(path: &Path) -> Result<Option<Self>, FsPathError> {
+        if !path.exists() {
+            return Ok(None)
+        }
+        reth_fs_util::read_json_file(path).map(Some)
+    }
+
+    /// Atomically writes the checkpoint to the file at the given path.
+    pub fn write(&self, path: &Path) -> Result<(), FsPathError> {
+        if let Some(parent) = path.parent() {
+            reth_fs_util::create_dir_all(parent)?;
+        }
+        reth_fs_util::atomic_write_file(path, |file| serde_json::to_writer(file, self))
+    }
+
+    /// Returns the range of blocks left to backfill to reach `end`.
+    pub fn remaining(&self, end: BlockNumber) -> RangeInclusive<BlockNumber> {
+        self.block_number.map_or(self.start, |block_number| block_number + 1)..=end
+    }
+
+    /// Returns the number of blocks left to backfill to reach `end`.
+    pub fn remaining_blocks(&self, end: BlockNumber) -> u64 {
+        let remaining = self.remaining(end);
+        (remaining.end() + 1).saturating_sub(*remaining.start())
+    }
+}
+
+/// Metrics for a [`CheckpointedBackfillJob`].
+#[derive(Metrics)]
+#[metrics(scope = "exex.backfill")]
+struct BackfillMetrics {
+    /// The highest block processed by the `ExEx`.
+    checkpoint: Gauge,
+    /// The number of blocks left to backfill.
+    remaining_blocks: Gauge,
+    /// The number of blocks processed per second since the job was started.
+    blocks_per_second: Gauge,
+    /// The estimated number of seconds until the backfill is finished.
+    eta_seconds: Gauge,
+}
+
+/// A backfill job that persists its progress, so that it can be resumed after a restart.
+///
+/// The blocks are executed in parallel in batches by a [`StreamBackfillJob`], so at most
+/// `parallelism` executed batches are held in memory at once. A yielded [`Chain`] is considered
+/// processed by the `ExEx` once the next item is requested from the stream, and only then is the
+/// checkpoint advanced to its tip. If the node stops while a chain is being processed, the chain
+/// is executed and yielded again after the restart.
+///
+/// The stream ends early if the [`CancellationToken`] of the job is cancelled. The job can later
+/// be resumed by creating it again for the same range start.
+#[derive(Debug)]
+pub struct CheckpointedBackfillJob<E: ConfigureEvm, P> {
+    stream: StreamBackfillJob<E, P, Chain<E::Primitives>>,
+    /// The path to the checkpoint file.
+    path: PathBuf,
+    checkpoint: BackfillCheckpoint,
+    /// The last block of the range.
+    end: BlockNumber,
+    /// The tip of the last yielded chain, committed to the checkpoint when the next item is
+    /// requested.
+    pending: Option<BlockNumber>,
+    cancel: CancellationToken,
+    /// The time the job was started and the number of blocks that were left at that time, to
+    /// calculate the throughput.
+    started: (Instant, u64),
+    metrics: BackfillMetrics,
+}
+
+impl<E, P> CheckpointedBackfillJob<E, P>
+where
+    E: ConfigureEvm,
+{
+    /// Creates a new job that resumes the `stream` from the `checkpoint` stored at `path`.
+    pub(super) fn new(
+        id: &str,
+        stream: StreamBackfillJob<E, P, Chain<E::Primitives>>,
+        path: PathBuf,
+        checkpoint: BackfillCheckpoint,
+        end: BlockNumber,
+    ) -> Self {
+        let metrics = BackfillMetrics::new_with_labels(&[("exex", id.to_string())]);
+        let job = Self {
+            stream,
+            path,
+            checkpoint,
+            end,
+            pending: None,
+            cancel: CancellationToken::new(),
+            started: (Instant::now(), checkpoint.remaining_blocks(end)),
+            metrics,
+        };
+        job.update_metrics();
+        job
+    }
+
+    /// Configures the number of batches executed in parallel.
+    pub fn with_parallelism(mut self, parallelism: usize) -> Self {
+        self.stream = self.stream.with_parallelism(parallelism);
+        self
+    }
+
+    /// Configures the number of blocks executed in a single batch.
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.stream = self.stream.with_batch_size(batch_size);
+        self
+    }
+
+    /// Returns the current checkpoint of the job.
+    pub const fn checkpoint(&self) -> BackfillCheckpoint {
+        self.checkpoint
+    }
+
+    /// Returns the token that cancels the job.
+    pub fn cancellation_token(&self) -> CancellationToken {
+        self.cancel.clone()
+    }
+
+    /// Commits the tip of the last yielded chain to the checkpoint.
+    fn commit_pending(&mut self) -> Result<(), FsPathError> {
+        let Some(block_number) = self.pending.take() else { return Ok(()) };
+
+        self.checkpoint.block_number = Some(block_number);
+        self.checkpoint.write(&self.path)?;
+        debug!(target: "exex::backfill", checkpoint = ?self.checkpoint, "Backfill checkpoint saved");
+        self.update_metrics();
+
+        Ok(())
+    }
+
+    fn update_metrics(&self) {
+        let remaining = self.checkpoint.remaining_blocks(self.end);
+        if let Some(block_number) = self.checkpoint.block_number {
+            self.metrics.checkpoint.set(block_number as f64);
+        }
+        self.metrics.remaining_blocks.set(remaining as f64);
+
+        let (started_at, started_remaining) = self.started;
+        let processed = started_remaining - remaining;
+        let elapsed = started_at.elapsed().as_secs_f64();
+        if processed > 0 && elapsed > 0.0 {
+            let blocks_per_second = processed as f64 / elapsed;
+            self.metrics.blocks_per_second.set(blocks_per_second);
+            self.metrics.eta_seconds.set(remaining as f64 / blocks_per_second);
+        }
+    }
+}
+
+impl<E, P> Stream for CheckpointedBackfillJob<E, P>
+where
+    E: ConfigureEvm<Primitives: NodePrimitives<Block = P::Block>> + 'static,
+    P: BlockReader + StateProviderFactory + Clone + Unpin + 'static,
+{
+    type Item = BackfillJobResult<Chain<E::Primitives>>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        // The previous chain was processed, because the next one is requested
+        if let Err(err) = this.commit_pending() {
+            return Poll::Ready(Some(Err(BlockExecutionError::other(err))))
+        }
+
+        if this.cancel.is_cancelled() {
+            info!(target: "exex::backfill", checkpoint = ?this.checkpoint, "Backfill cancelled");
+            return Poll::Ready(None)
+        }
+
+        let Some(result) = ready!(this.stream.poll_next_unpin(cx)) else {
+            info!(target: "exex::backfill", checkpoint = ?this.checkpoint, "Backfill finished");
+            return Poll::Ready(None)
+        };
+
+        if let Ok(chain) = &result {
+            this.pending = Some(chain.tip().number());
+        }
+
+        Poll::Ready(Some(result))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        backfill::test_utils::{blocks_and_execution_outputs, chain_spec},
+        BackfillCheckpoint, BackfillJobFactory,
+    };
+    use alloy_consensus::BlockHeader;
+    use futures::StreamExt;
+    use reth_db_common::init::init_genesis;
+    use reth_evm_ethereum::EthEvmConfig;
+    use reth_primitives_traits::crypto::secp256k1::public_key_to_address;
+    use reth_provider::{
+        providers::BlockchainProvider, test_utils::create_test_provider_factory_with_chain_spec,
+    };
+    use reth_testing_utils::generators;
+    use std::path::Path;
+
+    #[tokio::test]
+    async fn test_resume_and_cancel() -> eyre::Result<()> {
+        reth_tracing::init_test_tracing();
+
+        let key_pair = generators::generate_key(&mut generators::rng());
+        let address = public_key_to_address(key_pair.public_key());
+        let chain_spec = chain_spec(address);
+
+        let executor = EthEvmConfig::ethereum(chain_spec.clone());
+        let provider_factory = create_test_provider_factory_with_chain_spec(chain_spec.clone());
+        init_genesis(&provider_factory)?;
+        let blockchain_db = BlockchainProvider::new(provider_factory.clone())?;
+
+        // Create first 2 blocks
+        blocks_and_execution_outputs(provider_factory, chain_spec, key_pair)?;
+
+        let temp_dir = tempfile::tempdir()?;
+        let factory = BackfillJobFactory::new(executor, blockchain_db).with_stream_parallelism(1);
+        let new_job = || {
+            factory
+                .checkpointed_backfill("test", temp_dir.path(), 1..=2)
+                .map(|job| job.with_batch_size(1))
+        };
+
+        // The checkpoint is advanced only when the next chain is requested
+        let mut job = new_job()?;
+        assert_eq!(job.next().await.unwrap()?.tip().number(), 1);
+        assert_eq!(job.checkpoint().block_number, None);
+        assert_eq!(job.next().await.unwrap()?.tip().number(), 2);
+        assert_eq!(job.checkpoint().block_number, Some(1));
+        drop(job);
+
+        // The unprocessed block is executed again after a restart
+        let mut job = new_job()?;
+        assert_eq!(job.checkpoint(), BackfillCheckpoint { start: 1, block_number: Some(1) });
+        assert_eq!(job.next().await.unwrap()?.tip().number(), 2);
+        assert!(job.next().await.is_none());
+        assert_eq!(job.checkpoint().block_number, Some(2));
+
+        // A cancelled job stops yielding chains
+        let mut job = factory.checkpointed_backfill("cancelled", temp_dir.path(), 1..=2)?;
+        job.cancellation_token().cancel();
+        assert!(job.next().await.is_none());
+
+        // A job for a different range start doesn't resume from the checkpoint
+        let job = factory.checkpointed_backfill("test", temp_dir.path(), 2..=2)?;
+        assert_eq!(job.checkpoint(), BackfillCheckpoint { start: 2, block_number: None });
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_checkpoint_path() {
+        let directory = Path::new("/backfill");
+        assert_eq!(
+            BackfillCheckpoint::path(directory, "my-exex_1.0"),
+            directory.join("my-exex_1.0.json")
+        );
+        assert_eq!(
+            BackfillCheckpoint::path(directory, "../exex"),
+            directory.join("%2E.%2Fexex.json")
+        );
+        assert_eq!(BackfillCheckpoint::path(directory, ".."), directory.join("%2E..json"));
+        assert_eq!(BackfillCheckpoint::path(directory, "a\\b/c"), directory.join("a%5Cb%2Fc.json"));
+        assert_eq!(BackfillCheckpoint::path(directory, "100%"), directory.join("100%25.json"));
+    }
+}
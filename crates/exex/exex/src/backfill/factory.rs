@@ -1,7 +1,9 @@
-use crate::BackfillJob;
-use std::{ops::RangeInclusive, time::Duration};
+use crate::{BackfillCheckpoint, BackfillJob, CheckpointedBackfillJob, StreamBackfillJob};
+use std::{ops::RangeInclusive, path::Path, time::Duration};
 
 use alloy_primitives::BlockNumber;
+use reth_evm::ConfigureEvm;
+use reth_fs_util::FsPathError;
 use reth_node_api::FullNodeComponents;
 use reth_prune_types::PruneModes;
 use reth_stages_api::ExecutionStageThresholds;
@@ -74,6 +76,30 @@ impl<E: Clone, P: Clone> BackfillJobFactory<E, P> {
     }
 }
 
+impl<E: ConfigureEvm + Clone, P: Clone> BackfillJobFactory<E, P> {
+    /// Creates a new backfill job for the given range that persists its progress to the
+    /// checkpoint file of `id` in `directory`, see [`BackfillCheckpoint::path`].
+    ///
+    /// If the checkpoint file contains the progress of a job that started from the same block, the
+    /// job is resumed after the last processed block. Otherwise, the checkpoint is reset to the
+    /// start of the range.
+    pub fn checkpointed_backfill(
+        &self,
+        id: &str,
+        directory: impl AsRef<Path>,
+        range: RangeInclusive<BlockNumber>,
+    ) -> Result<CheckpointedBackfillJob<E, P>, FsPathError> {
+        let path = BackfillCheckpoint::path(directory.as_ref(), id);
+        let checkpoint = BackfillCheckpoint::read(&path)?
+            .filter(|checkpoint| checkpoint.start == *range.start())
+            .unwrap_or_else(|| BackfillCheckpoint { start: *range.start(), block_number: None });
+
+        let stream = StreamBackfillJob::from(self.backfill(checkpoint.remaining(*range.end())))
+            .with_parallelism(self.stream_parallelism);
+        Ok(CheckpointedBackfillJob::new(id, stream, path, checkpoint, *range.end()))
+    }
+}
+
 impl BackfillJobFactory<(), ()> {
     /// Creates a new [`BackfillJobFactory`] from [`FullNodeComponents`].
     pub fn new_from_components<Node: FullNodeComponents>(
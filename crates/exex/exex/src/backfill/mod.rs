@@ -1,9 +1,11 @@
+mod checkpoint;
 mod factory;
 mod job;
 mod stream;
 #[cfg(test)]
 mod test_utils;
 
+pub use checkpoint::{BackfillCheckpoint, CheckpointedBackfillJob};
 pub use factory::BackfillJobFactory;
 pub use job::{BackfillJob, SingleBlockBackfillJob};
 pub use stream::StreamBackfillJob;
@@ -1,18 +1,24 @@
-use crate::{ExExContextDyn, ExExEvent, ExExNotifications, ExExNotificationsStream};
+use crate::{
+    BackfillCheckpoint, BackfillJobFactory, CheckpointedBackfillJob, ExExContextDyn, ExExEvent,
+    ExExNotifications, ExExNotificationsStream,
+};
 use alloy_eips::BlockNumHash;
+use alloy_primitives::BlockNumber;
 use reth_exex_types::ExExHead;
 use reth_node_api::{FullNodeComponents, NodePrimitives, NodeTypes, PrimitivesTy};
 use reth_node_core::node_config::NodeConfig;
 use reth_payload_builder::PayloadBuilderHandle;
-use reth_provider::BlockReader;
+use reth_provider::{BlockReader, DBProvider, DatabaseProviderFactory};
 use reth_tasks::TaskExecutor;
-use std::fmt::Debug;
+use std::{fmt::Debug, ops::RangeInclusive};
 use tokio::sync::mpsc::{error::SendError, UnboundedSender};
 
 /// Captures the context that an `ExEx` has access to.
 ///
 /// This type wraps various node components that the `ExEx` has access to.
 pub struct ExExContext<Node: FullNodeComponents> {
+    /// The ID of the `ExEx`.
+    pub id: String,
     /// The current head of the blockchain at launch.
     pub head: BlockNumHash,
     /// The config of the node
@@ -46,6 +52,7 @@ where
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("ExExContext")
+            .field("id", &self.id)
             .field("head", &self.head)
             .field("config", &self.config)
             .field("reth_config", &self.reth_config)
@@ -129,6 +136,35 @@ where
     ) -> Result<(), SendError<BlockNumHash>> {
         self.events.send(ExExEvent::FinishedHeight(height)).map_err(|_| SendError(height))
     }
+
+    /// Returns a backfill job for the given range that persists its progress under the ID of this
+    /// `ExEx`.
+    ///
+    /// If a previous job of this `ExEx` for a range with the same start was interrupted, the job is
+    /// resumed after the last processed block. See [`CheckpointedBackfillJob`] for details.
+    ///
+    /// The job uses the prune modes and the execution stage thresholds of the node.
+    pub fn checkpointed_backfill(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+    ) -> eyre::Result<CheckpointedBackfillJob<Node::Evm, Node::Provider>> {
+        let directory = self.config.datadir().exex_backfill();
+        let prune_modes = self.provider().database_provider_ro()?.prune_modes_ref().clone();
+        Ok(BackfillJobFactory::new_from_components(self.components.clone())
+            .with_prune_modes(prune_modes)
+            .with_thresholds(self.reth_config.stages.execution.into())
+            .checkpointed_backfill(&self.id, directory, range)?)
+    }
+
+    /// Removes the backfill checkpoint of this `ExEx`, so that the next
+    /// [`Self::checkpointed_backfill`] job starts from the beginning of its range.
+    pub fn reset_backfill_checkpoint(&self) -> eyre::Result<()> {
+        let path = BackfillCheckpoint::path(&self.config.datadir().exex_backfill(), &self.id);
+        if path.exists() {
+            reth_fs_util::remove_file(path)?;
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
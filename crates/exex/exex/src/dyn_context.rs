@@ -15,6 +15,8 @@ use crate::{ExExContext, ExExEvent, ExExNotificationsStream};
 // TODO(0xurb) - add `node` after abstractions
 /// Captures the context that an `ExEx` has access to.
 pub struct ExExContextDyn<N: NodePrimitives = EthPrimitives> {
+    /// The ID of the `ExEx`.
+    pub id: String,
     /// The current head of the blockchain at launch.
     pub head: BlockNumHash,
     /// The config of the node
@@ -41,6 +43,7 @@ pub struct ExExContextDyn<N: NodePrimitives = EthPrimitives> {
 impl<N: NodePrimitives> Debug for ExExContextDyn<N> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("ExExContext")
+            .field("id", &self.id)
             .field("head", &self.head)
             .field("config", &self.config)
             .field("reth_config", &self.reth_config)
@@ -62,6 +65,7 @@ where
         let notifications = Box::new(ctx.notifications) as Box<_>;
 
         Self {
+            id: ctx.id,
             head: ctx.head,
             config,
             reth_config: ctx.reth_config,
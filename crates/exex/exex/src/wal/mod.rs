@@ -2,6 +2,8 @@
 
 mod cache;
 pub use cache::BlockCache;
+mod reader;
+pub use reader::WalReader;
 mod storage;
 use reth_ethereum_primitives::EthPrimitives;
 use reth_node_api::NodePrimitives;
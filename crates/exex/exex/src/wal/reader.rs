@@ -0,0 +1,114 @@
+use std::path::Path;
+
+use crate::wal::{Storage, WalResult};
+use alloy_consensus::BlockHeader;
+use alloy_primitives::BlockNumber;
+use reth_ethereum_primitives::EthPrimitives;
+use reth_exex_types::ExExNotification;
+use reth_node_api::NodePrimitives;
+use reth_tracing::tracing::debug;
+
+/// A read-only cursor over the notifications of a WAL directory.
+///
+/// Unlike [`Wal`](super::Wal), the reader doesn't keep a block cache and doesn't modify the
+/// [`Storage`], so it can be opened by a process other than the node while the node keeps
+/// committing and finalizing notifications.
+///
+/// The reader remembers the last notification it has read, and [`WalReader::read_new`] only
+/// returns the notifications that were committed after it. Notifications that are finalized by the
+/// node before the reader gets to them are skipped.
+#[derive(Debug)]
+pub struct WalReader<N: NodePrimitives = EthPrimitives> {
+    storage: Storage<N>,
+    /// The ID of the next file to read.
+    next_file_id: u32,
+}
+
+impl<N> WalReader<N>
+where
+    N: NodePrimitives,
+{
+    /// Opens the WAL in the given directory for reading.
+    pub fn new(directory: impl AsRef<Path>) -> WalResult<Self> {
+        Ok(Self { storage: Storage::new(directory)?, next_file_id: 0 })
+    }
+
+    /// Reads all notifications in the WAL that contain a committed or reverted block at or above
+    /// `from_block`, and positions the reader after the last notification in the WAL.
+    pub fn read_from(&mut self, from_block: BlockNumber) -> WalResult<Vec<ExExNotification<N>>> {
+        self.next_file_id = 0;
+        let mut notifications = self.read_new()?;
+        notifications.retain(|notification| {
+            notification
+                .committed_chain()
+                .into_iter()
+                .chain(notification.reverted_chain())
+                .any(|chain| chain.tip().number() >= from_block)
+        });
+        Ok(notifications)
+    }
+
+    /// Reads the notifications committed to the WAL since the last read.
+    pub fn read_new(&mut self) -> WalResult<Vec<ExExNotification<N>>> {
+        let Some(files_range) = self.storage.files_range()? else { return Ok(Vec::new()) };
+
+        let mut notifications = Vec::new();
+        for file_id in self.next_file_id.max(*files_range.start())..=*files_range.end() {
+            // The file could have been removed by finalization after we listed the directory
+            if let Some((notification, _)) = self.storage.read_notification(file_id)? {
+                notifications.push(notification);
+            } else {
+                debug!(target: "exex::wal::reader", ?file_id, "Notification was finalized, skipping");
+            }
+        }
+        self.next_file_id = files_range.end() + 1;
+
+        Ok(notifications)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WalReader;
+    use crate::wal::Wal;
+    use alloy_eips::BlockNumHash;
+    use reth_exex_types::ExExNotification;
+    use reth_provider::Chain;
+    use reth_testing_utils::generators::{self, random_block_range, BlockRangeParams};
+    use std::sync::Arc;
+
+    #[test]
+    fn read_from_and_tail() -> eyre::Result<()> {
+        let mut rng = generators::rng();
+
+        let temp_dir = tempfile::tempdir()?;
+        let wal: Wal = Wal::new(&temp_dir)?;
+
+        let blocks = random_block_range(&mut rng, 0..=2, BlockRangeParams::default())
+            .into_iter()
+            .map(|block| block.try_recover())
+            .collect::<Result<Vec<_>, _>>()?;
+        let notifications = blocks
+            .iter()
+            .map(|block| ExExNotification::ChainCommitted {
+                new: Arc::new(Chain::new(vec![block.clone()], Default::default(), None)),
+            })
+            .collect::<Vec<_>>();
+
+        wal.commit(&notifications[0])?;
+        wal.commit(&notifications[1])?;
+
+        let mut reader = WalReader::new(&temp_dir)?;
+        assert_eq!(reader.read_from(1)?, vec![notifications[1].clone()]);
+        assert!(reader.read_new()?.is_empty());
+
+        wal.commit(&notifications[2])?;
+        assert_eq!(reader.read_new()?, vec![notifications[2].clone()]);
+
+        // Finalized notifications are no longer returned
+        wal.finalize(BlockNumHash::new(1, blocks[1].hash()))?;
+        assert_eq!(reader.read_from(0)?, vec![notifications[2].clone()]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,35 @@
+use alloy_eips::BlockNumHash;
+use alloy_primitives::BlockNumber;
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+
+/// API for consumers of ExEx notifications running outside of the node.
+#[cfg_attr(not(any(test, feature = "client")), rpc(server, namespace = "exex"))]
+#[cfg_attr(any(test, feature = "client"), rpc(server, client, namespace = "exex"))]
+pub trait ExExRemoteApi {
+    /// Subscribes the consumer to ExEx notifications.
+    ///
+    /// If `from_block` is set, the notifications in the WAL that contain blocks at or above it are
+    /// replayed before the live notifications. Notifications that were already finalized from the
+    /// WAL can't be replayed.
+    #[subscription(
+        name = "subscribeNotifications",
+        unsubscribe = "unsubscribeNotifications",
+        item = reth_exex::ExExNotification
+    )]
+    async fn subscribe_notifications(
+        &self,
+        consumer: String,
+        from_block: Option<BlockNumber>,
+    ) -> jsonrpsee::core::SubscriptionResult;
+
+    /// Acknowledges that the consumer has processed all blocks up to and including `height`.
+    #[method(name = "finishedHeight")]
+    async fn finished_height(&self, consumer: String, height: BlockNumHash) -> RpcResult<()>;
+
+    /// Removes the consumer, so that its finished height no longer holds back the finalization of
+    /// the WAL.
+    ///
+    /// The consumer is registered again by its next subscription.
+    #[method(name = "removeConsumer")]
+    async fn remove_consumer(&self, consumer: String) -> RpcResult<()>;
+}
@@ -0,0 +1,30 @@
+//! Streams ExEx notifications to consumers running outside of the node.
+//!
+//! [`RemoteExEx`] is installed into the node like any other `ExEx`, and serves the
+//! [`ExExRemoteApi`] over IPC. A remote consumer subscribes from a block number, receives the
+//! committed, reverted and reorged notifications that are still in the ExEx
+//! [WAL](reth_exex::Wal), followed by the live notifications, and acknowledges the height it has
+//! finished processing. The acknowledgements of all consumers are reported to the `ExExManager` as
+//! a single [`ExExEvent::FinishedHeight`](reth_exex::ExExEvent::FinishedHeight), so the WAL is only
+//! finalized up to the lowest height that every consumer has processed.
+//!
+//! ```no_run
+//! # use reth_node_api::FullNodeComponents;
+//! # async fn example<Node: FullNodeComponents>(ctx: reth_exex::ExExContext<Node>) -> eyre::Result<()> {
+//! let exex = reth_exex_remote::launch(ctx, "/tmp/reth-exex.ipc").await?;
+//! exex.await
+//! # }
+//! ```
+#![doc(
+    html_logo_url = "https://raw.githubusercontent.com/paradigmxyz/reth/main/assets/reth-docs.png",
+    html_favicon_url = "https://avatars0.githubusercontent.com/u/97369466?s=256",
+    issue_tracker_base_url = "https://github.com/paradigmxyz/reth/issues/"
+)]
+#![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
+#![cfg_attr(not(test), warn(unused_crate_dependencies))]
+
+mod api;
+pub use api::*;
+
+mod server;
+pub use server::*;
@@ -0,0 +1,491 @@
+use crate::ExExRemoteApiServer;
+use alloy_eips::BlockNumHash;
+use alloy_primitives::{BlockNumber, B256};
+use futures::{Stream, StreamExt};
+use jsonrpsee::{
+    core::{async_trait, RpcResult, SubscriptionResult},
+    types::{error::INTERNAL_ERROR_CODE, ErrorObject},
+    PendingSubscriptionSink, SubscriptionMessage,
+};
+use reth_chainspec::EthChainSpec;
+use reth_exex::{ExExContext, ExExEvent, ExExNotification, WalReader};
+use reth_ipc::server::Builder as IpcServerBuilder;
+use reth_node_api::{FullNodeComponents, NodePrimitives, NodeTypes};
+use reth_tracing::tracing::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    future::Future,
+    path::PathBuf,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tokio::sync::{
+    broadcast::{self, error::RecvError},
+    mpsc::{self, UnboundedReceiver, UnboundedSender},
+};
+
+/// Default number of live notifications buffered for each remote consumer.
+///
+/// A consumer that falls further behind is disconnected and has to resubscribe from the last
+/// height it has finished.
+pub const DEFAULT_REMOTE_EXEX_CAPACITY: usize = 1024;
+
+/// Default time after which a consumer that neither subscribed nor acknowledged a height is
+/// removed, so that it doesn't hold back the finalization of the WAL forever.
+pub const DEFAULT_REMOTE_EXEX_CONSUMER_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Starts an IPC server at `ipc_path` that serves the [`ExExRemoteApi`](crate::ExExRemoteApi),
+/// and returns the `ExEx` that feeds it with the notifications of the node.
+///
+/// The consumers are persisted to `<DATADIR>/exex/remote-consumers.json`.
+pub async fn launch<Node: FullNodeComponents>(
+    ctx: ExExContext<Node>,
+    ipc_path: impl Into<String>,
+) -> eyre::Result<impl Future<Output = eyre::Result<()>>> {
+    let datadir = ctx.config.datadir.clone().resolve_datadir(ctx.config.chain.chain());
+    let (exex, rpc) = RemoteExEx::<<Node::Types as NodeTypes>::Primitives>::new(
+        datadir.exex_wal(),
+        datadir.data_dir().join("exex/remote-consumers.json"),
+        DEFAULT_REMOTE_EXEX_CAPACITY,
+    )?;
+
+    let ipc_path = ipc_path.into();
+    let server = IpcServerBuilder::default().build(ipc_path.clone()).start(rpc.into_rpc()).await?;
+    info!(target: "exex::remote", %ipc_path, "Remote ExEx IPC server started");
+
+    Ok(async move {
+        let result = exex.run(ctx.notifications, ctx.events).await;
+        let _ = server.stop();
+        result
+    })
+}
+
+/// Message sent from the RPC handlers to the [`RemoteExEx`].
+#[derive(Debug)]
+enum ConsumerMessage {
+    /// The consumer subscribed to notifications.
+    Subscribed(String),
+    /// The consumer has processed all blocks up to and including the height.
+    FinishedHeight(String, BlockNumHash),
+    /// The consumer was removed.
+    Removed(String),
+}
+
+/// The persisted state of a consumer.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct ConsumerState {
+    /// The last height acknowledged by the consumer.
+    finished_height: Option<BlockNumHash>,
+    /// The UNIX timestamp of the last subscription or acknowledgement of the consumer.
+    last_seen: u64,
+}
+
+/// An `ExEx` that forwards notifications to remote consumers and reports their finished heights
+/// to the `ExExManager`.
+///
+/// Consumers are tracked from their first subscription until they are removed, or expire after
+/// neither subscribing nor acknowledging a height for the consumer TTL. The consumers are
+/// persisted, so they are still known after a restart. The finished height is only reported once
+/// every known consumer has acknowledged a height, and is the lowest of the acknowledged heights,
+/// so that the WAL keeps the notifications a disconnected consumer needs to catch up. Without any
+/// consumers, the tip of every committed chain is reported instead.
+#[derive(Debug)]
+pub struct RemoteExEx<N: NodePrimitives> {
+    /// Live notifications sent to the subscriptions.
+    notifications: broadcast::Sender<ExExNotification<N>>,
+    /// Messages from the RPC handlers.
+    consumers: UnboundedReceiver<ConsumerMessage>,
+    /// The file the consumers are persisted to.
+    consumers_file: PathBuf,
+    /// The state of each consumer.
+    consumer_states: HashMap<String, ConsumerState>,
+    /// The time after which a consumer that wasn't seen is removed.
+    consumer_ttl: Duration,
+    /// The last finished height reported to the manager.
+    finished_height: Option<BlockNumHash>,
+}
+
+impl<N: NodePrimitives> RemoteExEx<N> {
+    /// Creates a new [`RemoteExEx`] replaying notifications from the WAL in `wal_directory`, and
+    /// loading and persisting its consumers in `consumers_file`.
+    ///
+    /// Returns the `ExEx` and the RPC handler that should be served to the consumers.
+    pub fn new(
+        wal_directory: impl Into<PathBuf>,
+        consumers_file: impl Into<PathBuf>,
+        capacity: usize,
+    ) -> eyre::Result<(Self, ExExRemoteRpc<N>)> {
+        let consumers_file = consumers_file.into();
+        let consumer_states = if consumers_file.exists() {
+            reth_fs_util::read_json_file(&consumers_file)?
+        } else {
+            HashMap::new()
+        };
+        debug!(target: "exex::remote", consumers = consumer_states.len(), "Loaded consumers");
+
+        let (notifications, _) = broadcast::channel(capacity);
+        let (consumers_tx, consumers_rx) = mpsc::unbounded_channel();
+
+        let rpc = ExExRemoteRpc {
+            wal_directory: wal_directory.into(),
+            notifications: notifications.clone(),
+            consumers: consumers_tx,
+        };
+        let exex = Self {
+            notifications,
+            consumers: consumers_rx,
+            consumers_file,
+            consumer_states,
+            consumer_ttl: DEFAULT_REMOTE_EXEX_CONSUMER_TTL,
+            finished_height: None,
+        };
+
+        Ok((exex, rpc))
+    }
+
+    /// Sets the time after which a consumer that neither subscribed nor acknowledged a height is
+    /// removed.
+    pub const fn with_consumer_ttl(mut self, consumer_ttl: Duration) -> Self {
+        self.consumer_ttl = consumer_ttl;
+        self
+    }
+
+    /// Runs the `ExEx` until the notifications stream ends.
+    pub async fn run<S>(
+        mut self,
+        mut notifications: S,
+        events: UnboundedSender<ExExEvent>,
+    ) -> eyre::Result<()>
+    where
+        S: Stream<Item = eyre::Result<ExExNotification<N>>> + Unpin,
+    {
+        loop {
+            let committed_tip = tokio::select! {
+                notification = notifications.next() => {
+                    let Some(notification) = notification.transpose()? else { break };
+                    let committed_tip =
+                        notification.committed_chain().map(|chain| chain.tip().num_hash());
+                    // It's fine if there are no consumers subscribed at the moment
+                    let _ = self.notifications.send(notification);
+                    committed_tip
+                }
+                Some(message) = self.consumers.recv() => {
+                    self.on_consumer_message(message);
+                    None
+                }
+            };
+
+            if let Some(height) = self.update_finished_height(committed_tip) {
+                debug!(target: "exex::remote", ?height, "Reporting finished height");
+                events.send(ExExEvent::FinishedHeight(height))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Updates the state of the consumer that sent the message.
+    fn on_consumer_message(&mut self, message: ConsumerMessage) {
+        let now = unix_timestamp();
+        match message {
+            ConsumerMessage::Subscribed(consumer) => {
+                debug!(target: "exex::remote", %consumer, "Consumer subscribed");
+                self.consumer_states.entry(consumer).or_default().last_seen = now;
+            }
+            ConsumerMessage::FinishedHeight(consumer, height) => {
+                debug!(target: "exex::remote", %consumer, ?height, "Consumer finished height");
+                self.consumer_states.insert(
+                    consumer,
+                    ConsumerState { finished_height: Some(height), last_seen: now },
+                );
+            }
+            ConsumerMessage::Removed(consumer) => {
+                debug!(target: "exex::remote", %consumer, "Consumer removed");
+                if self.consumer_states.remove(&consumer).is_none() {
+                    return
+                }
+            }
+        }
+
+        self.persist_consumers();
+    }
+
+    /// Removes the expired consumers and returns the new finished height if it has changed.
+    ///
+    /// Without any consumers, the finished height is the tip of the committed chain, if any.
+    fn update_finished_height(
+        &mut self,
+        committed_tip: Option<BlockNumHash>,
+    ) -> Option<BlockNumHash> {
+        let expired_before = unix_timestamp().saturating_sub(self.consumer_ttl.as_secs());
+        let consumers = self.consumer_states.len();
+        self.consumer_states.retain(|consumer, state| {
+            let expired = state.last_seen < expired_before;
+            if expired {
+                debug!(target: "exex::remote", %consumer, "Consumer expired");
+            }
+            !expired
+        });
+        if self.consumer_states.len() != consumers {
+            self.persist_consumers();
+        }
+
+        let finished_height = if self.consumer_states.is_empty() {
+            committed_tip?
+        } else {
+            self.consumer_states
+                .values()
+                .map(|state| state.finished_height)
+                .collect::<Option<Vec<_>>>()?
+                .into_iter()
+                .min_by_key(|height| height.number)?
+        };
+        if Some(finished_height) == self.finished_height {
+            return None
+        }
+
+        self.finished_height = Some(finished_height);
+        Some(finished_height)
+    }
+
+    /// Writes the consumers to the consumers file.
+    ///
+    /// A failure is only logged, the consumers are written again on the next change.
+    fn persist_consumers(&self) {
+        let result =
+            self.consumers_file.parent().map(reth_fs_util::create_dir_all).transpose().and_then(
+                |_| reth_fs_util::write_json_file(&self.consumers_file, &self.consumer_states),
+            );
+        if let Err(err) = result {
+            warn!(target: "exex::remote", %err, "Failed to persist consumers");
+        }
+    }
+}
+
+/// The [`ExExRemoteApiServer`] implementation of a [`RemoteExEx`].
+#[derive(Debug, Clone)]
+pub struct ExExRemoteRpc<N: NodePrimitives> {
+    /// The directory of the ExEx WAL.
+    wal_directory: PathBuf,
+    /// Live notifications from the [`RemoteExEx`].
+    notifications: broadcast::Sender<ExExNotification<N>>,
+    /// Messages to the [`RemoteExEx`].
+    consumers: UnboundedSender<ConsumerMessage>,
+}
+
+#[async_trait]
+impl<N: NodePrimitives> ExExRemoteApiServer for ExExRemoteRpc<N> {
+    async fn subscribe_notifications(
+        &self,
+        pending: PendingSubscriptionSink,
+        consumer: String,
+        from_block: Option<BlockNumber>,
+    ) -> SubscriptionResult {
+        // Subscribe to the live notifications before reading the WAL, so that nothing is missed
+        // in between. The notifications that are both in the WAL and in the live stream are
+        // deduplicated below.
+        let mut live = self.notifications.subscribe();
+        let replay = match from_block {
+            Some(from_block) => {
+                let wal_directory = self.wal_directory.clone();
+                tokio::task::spawn_blocking(move || {
+                    WalReader::<N>::new(wal_directory)?.read_from(from_block)
+                })
+                .await??
+            }
+            None => Vec::new(),
+        };
+
+        let _ = self.consumers.send(ConsumerMessage::Subscribed(consumer.clone()));
+        let sink = pending.accept().await?;
+        debug!(target: "exex::remote", %consumer, ?from_block, replayed = replay.len(), "Streaming notifications");
+
+        let mut replayed = HashSet::with_capacity(replay.len());
+        for notification in replay {
+            replayed.insert(notification_key(&notification));
+            let msg = SubscriptionMessage::new(
+                sink.method_name(),
+                sink.subscription_id(),
+                &notification,
+            )?;
+            if sink.send(msg).await.is_err() {
+                return Ok(())
+            }
+        }
+
+        loop {
+            let notification = tokio::select! {
+                _ = sink.closed() => break,
+                notification = live.recv() => match notification {
+                    Ok(notification) => notification,
+                    Err(RecvError::Closed) => break,
+                    Err(RecvError::Lagged(skipped)) => {
+                        return Err(format!(
+                            "consumer lagged behind by {skipped} notifications, resubscribe from the last finished height"
+                        )
+                        .into())
+                    }
+                },
+            };
+
+            if !replayed.is_empty() && replayed.remove(&notification_key(&notification)) {
+                continue
+            }
+
+            let msg = SubscriptionMessage::new(
+                sink.method_name(),
+                sink.subscription_id(),
+                &notification,
+            )?;
+            if sink.send(msg).await.is_err() {
+                break
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn finished_height(&self, consumer: String, height: BlockNumHash) -> RpcResult<()> {
+        self.send(ConsumerMessage::FinishedHeight(consumer, height))
+    }
+
+    async fn remove_consumer(&self, consumer: String) -> RpcResult<()> {
+        self.send(ConsumerMessage::Removed(consumer))
+    }
+}
+
+impl<N: NodePrimitives> ExExRemoteRpc<N> {
+    /// Sends the message to the [`RemoteExEx`].
+    fn send(&self, message: ConsumerMessage) -> RpcResult<()> {
+        self.consumers.send(message).map_err(|_| {
+            ErrorObject::owned(INTERNAL_ERROR_CODE, "remote ExEx is not running", None::<()>)
+        })
+    }
+}
+
+/// Returns the current UNIX timestamp in seconds.
+fn unix_timestamp() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
+
+/// Identifies a notification by the tips of its committed and reverted chains.
+fn notification_key<N: NodePrimitives>(
+    notification: &ExExNotification<N>,
+) -> (Option<B256>, Option<B256>) {
+    (
+        notification.committed_chain().map(|chain| chain.tip().hash()),
+        notification.reverted_chain().map(|chain| chain.tip().hash()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ExExRemoteApiClient;
+    use reth_ethereum_primitives::EthPrimitives;
+    use reth_execution_types::Chain;
+    use reth_exex::Wal;
+    use reth_ipc::client::IpcClientBuilder;
+    use reth_testing_utils::generators::{self, random_block_range, BlockRangeParams};
+    use std::sync::Arc;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn replays_wal_and_reports_finished_height() -> eyre::Result<()> {
+        let mut rng = generators::rng();
+        let temp_dir = tempfile::tempdir()?;
+        let wal_directory = temp_dir.path().join("wal");
+        let ipc_path = temp_dir.path().join("exex.ipc").to_string_lossy().into_owned();
+
+        let blocks = random_block_range(&mut rng, 0..=2, BlockRangeParams::default())
+            .into_iter()
+            .map(|block| block.try_recover())
+            .collect::<Result<Vec<_>, _>>()?;
+        let notifications = blocks
+            .iter()
+            .map(|block| ExExNotification::ChainCommitted {
+                new: Arc::new(Chain::new(vec![block.clone()], Default::default(), None)),
+            })
+            .collect::<Vec<_>>();
+
+        // The first two notifications were committed to the WAL before the consumer subscribed
+        let wal: Wal = Wal::new(&wal_directory)?;
+        wal.commit(&notifications[0])?;
+        wal.commit(&notifications[1])?;
+
+        let consumers_file = temp_dir.path().join("consumers.json");
+        let (exex, rpc) =
+            RemoteExEx::new(&wal_directory, &consumers_file, DEFAULT_REMOTE_EXEX_CAPACITY)?;
+        let _server =
+            IpcServerBuilder::default().build(ipc_path.clone()).start(rpc.into_rpc()).await?;
+
+        let (notifications_tx, notifications_rx) = mpsc::unbounded_channel();
+        let (events_tx, mut events_rx) = mpsc::unbounded_channel();
+        let notifications_stream = unbounded_stream(notifications_rx).map(Ok::<_, eyre::Report>);
+        tokio::spawn(exex.run(notifications_stream, events_tx));
+
+        let client = IpcClientBuilder::default().build(&ipc_path).await?;
+        let mut subscription =
+            ExExRemoteApiClient::subscribe_notifications(&client, "indexer".to_string(), Some(1))
+                .await?;
+        assert_eq!(subscription.next().await.unwrap()?, notifications[1]);
+
+        // The live notification that is already in the WAL is not sent twice
+        notifications_tx.send(notifications[1].clone())?;
+        notifications_tx.send(notifications[2].clone())?;
+        assert_eq!(subscription.next().await.unwrap()?, notifications[2]);
+
+        let height = blocks[2].num_hash();
+        ExExRemoteApiClient::finished_height(&client, "indexer".to_string(), height).await?;
+        assert_eq!(events_rx.recv().await, Some(ExExEvent::FinishedHeight(height)));
+
+        // The consumer is still known after a restart
+        let (exex, _) = RemoteExEx::<EthPrimitives>::new(
+            &wal_directory,
+            &consumers_file,
+            DEFAULT_REMOTE_EXEX_CAPACITY,
+        )?;
+        assert_eq!(
+            exex.consumer_states.get("indexer").and_then(|state| state.finished_height),
+            Some(height)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reports_tip_without_consumers() -> eyre::Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let (mut exex, _) = RemoteExEx::<EthPrimitives>::new(
+            temp_dir.path().join("wal"),
+            temp_dir.path().join("consumers.json"),
+            DEFAULT_REMOTE_EXEX_CAPACITY,
+        )?;
+        let tip = BlockNumHash::new(1, B256::with_last_byte(1));
+
+        assert_eq!(exex.update_finished_height(Some(tip)), Some(tip));
+
+        // A subscribed consumer holds back the finished height until it acknowledges a height
+        exex.on_consumer_message(ConsumerMessage::Subscribed("indexer".to_string()));
+        let tip = BlockNumHash::new(2, B256::with_last_byte(2));
+        assert_eq!(exex.update_finished_height(Some(tip)), None);
+
+        // Once the consumer is removed, the tip is reported again
+        exex.on_consumer_message(ConsumerMessage::Removed("indexer".to_string()));
+        assert_eq!(exex.update_finished_height(Some(tip)), Some(tip));
+
+        // An expired consumer doesn't hold back the finished height
+        exex.on_consumer_message(ConsumerMessage::Subscribed("indexer".to_string()));
+        exex.consumer_states.get_mut("indexer").unwrap().last_seen = 0;
+        let tip = BlockNumHash::new(3, B256::with_last_byte(3));
+        assert_eq!(exex.update_finished_height(Some(tip)), Some(tip));
+        assert!(exex.consumer_states.is_empty());
+
+        Ok(())
+    }
+
+    fn unbounded_stream<T: Send + 'static>(
+        mut rx: mpsc::UnboundedReceiver<T>,
+    ) -> impl Stream<Item = T> + Unpin {
+        Box::pin(futures::stream::poll_fn(move |cx| rx.poll_recv(cx)))
+    }
+}
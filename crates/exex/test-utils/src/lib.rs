@@ -298,6 +298,7 @@ pub async fn test_exex_context_with_chain_spec(
     );
 
     let ctx = ExExContext {
+        id: "test_exex".to_string(),
         head,
         config: NodeConfig::test(),
         reth_config: reth_config::Config::default(),
@@ -112,6 +112,22 @@ impl BanList {
         self.banned_peers.contains_key(peer_id)
     }
 
+    /// Returns until when the peer is banned, if it is banned temporarily.
+    #[inline]
+    pub fn peer_banned_until(&self, peer_id: &PeerId) -> Option<Instant> {
+        self.banned_peers.get(peer_id).copied().flatten()
+    }
+
+    /// Returns an iterator over all peers that are banned indefinitely.
+    pub fn permanently_banned_peers(&self) -> impl Iterator<Item = PeerId> + '_ {
+        self.banned_peers.iter().filter(|(_, until)| until.is_none()).map(|(peer_id, _)| *peer_id)
+    }
+
+    /// Returns an iterator over all ip addresses that are banned indefinitely.
+    pub fn permanently_banned_ips(&self) -> impl Iterator<Item = IpAddr> + '_ {
+        self.banned_ips.iter().filter(|(_, until)| until.is_none()).map(|(ip, _)| *ip)
+    }
+
     /// Unbans the ip address
     pub fn unban_ip(&mut self, ip: &IpAddr) {
         self.banned_ips.remove(ip);
@@ -175,6 +191,17 @@ mod tests {
         assert!(!banlist.is_banned_peer(&peer));
     }
 
+    #[test]
+    fn permanent_bans() {
+        let peer = PeerId::random();
+        let ip = IpAddr::from([1, 1, 1, 1]);
+        let mut banlist = BanList::new([peer], [ip]);
+        banlist.ban_peer_until(PeerId::random(), Instant::now());
+        banlist.ban_ip_until(IpAddr::from([2, 2, 2, 2]), Instant::now());
+        assert_eq!(banlist.permanently_banned_peers().collect::<Vec<_>>(), vec![peer]);
+        assert_eq!(banlist.permanently_banned_ips().collect::<Vec<_>>(), vec![ip]);
+    }
+
     #[test]
     fn can_ban_unban_ip() {
         let ip = IpAddr::from([1, 1, 1, 1]);
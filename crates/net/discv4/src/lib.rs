@@ -378,6 +378,15 @@ impl Discv4 {
         self.send_to_service(cmd);
     }
 
+    /// Sets the external IP address
+    ///
+    /// This will update our [`NodeRecord`]'s address, see also
+    /// [`Discv4Service::set_external_ip_addr`].
+    pub fn set_external_ip_addr(&self, external_ip: IpAddr) {
+        let cmd = Discv4Command::SetExternalIp(external_ip);
+        self.send_to_service(cmd);
+    }
+
     /// Sets the pair in the EIP-868 [`Enr`] of the node.
     ///
     /// If the key already exists, this will update it.
@@ -1764,6 +1773,9 @@ impl Discv4Service {
                             let _ = self.local_eip_868_enr.set_tcp6(port, &self.secret_key);
                         }
                     }
+                    Discv4Command::SetExternalIp(external_ip) => {
+                        self.set_external_ip_addr(external_ip);
+                    }
 
                     Discv4Command::Terminated => {
                         // terminate the service
@@ -2045,6 +2057,7 @@ impl Default for ReceiveCache {
 enum Discv4Command {
     Add(NodeRecord),
     SetTcpPort(u16),
+    SetExternalIp(IpAddr),
     SetEIP868RLPPair { key: Vec<u8>, rlp: Bytes },
     Ban(PeerId, IpAddr),
     BanPeer(PeerId),
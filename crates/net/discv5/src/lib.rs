@@ -123,6 +123,17 @@ impl Discv5 {
         self.set_eip868_in_local_enr(key, buf.into())
     }
 
+    /// Sets the IP address of the local [`Enr`], keeping its UDP and TCP ports.
+    pub fn set_external_ip(&self, ip: IpAddr) {
+        let enr = self.discv5.local_enr();
+        if let Some(port) = enr.udp4().or_else(|| enr.udp6()) {
+            self.discv5.update_local_enr_socket(SocketAddr::new(ip, port), false);
+        }
+        if let Some(port) = enr.tcp4().or_else(|| enr.tcp6()) {
+            self.discv5.update_local_enr_socket(SocketAddr::new(ip, port), true);
+        }
+    }
+
     /// Adds the peer and id to the ban list.
     ///
     /// This will prevent any future inclusion in the table
@@ -77,7 +77,7 @@ pub struct AccountRangeMessage {
 
 /// Request for the storage slots of multiple accounts' storage tries.
 // https://github.com/ethereum/devp2p/blob/master/caps/snap.md#getstorageranges-0x02
-#[derive(Debug, Clone, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+#[derive(Debug, Clone, PartialEq, Eq, RlpEncodable)]
 #[cfg_attr(any(test, feature = "arbitrary"), derive(arbitrary::Arbitrary))]
 #[add_arbitrary_tests(rlp)]
 pub struct GetStorageRangesMessage {
@@ -95,6 +95,45 @@ pub struct GetStorageRangesMessage {
     pub response_bytes: u64,
 }
 
+impl Decodable for GetStorageRangesMessage {
+    fn decode(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+        let header = alloy_rlp::Header::decode(buf)?;
+        if !header.list {
+            return Err(alloy_rlp::Error::UnexpectedString)
+        }
+        let started_len = buf.len();
+
+        let this = Self {
+            request_id: Decodable::decode(buf)?,
+            root_hash: Decodable::decode(buf)?,
+            account_hashes: Decodable::decode(buf)?,
+            // geth leaves the origin and limit empty when requesting entire storage tries
+            starting_hash: decode_optional_hash(buf)?.unwrap_or(B256::ZERO),
+            limit_hash: decode_optional_hash(buf)?.unwrap_or(B256::repeat_byte(0xff)),
+            response_bytes: Decodable::decode(buf)?,
+        };
+
+        let consumed = started_len - buf.len();
+        if consumed != header.payload_length {
+            return Err(alloy_rlp::Error::ListLengthMismatch {
+                expected: header.payload_length,
+                got: consumed,
+            })
+        }
+
+        Ok(this)
+    }
+}
+
+/// Decodes a hash that may be encoded as an empty string.
+fn decode_optional_hash(buf: &mut &[u8]) -> alloy_rlp::Result<Option<B256>> {
+    let bytes = alloy_rlp::Header::decode_bytes(buf, false)?;
+    if bytes.is_empty() {
+        return Ok(None)
+    }
+    B256::try_from(bytes).map(Some).map_err(|_| alloy_rlp::Error::UnexpectedLength)
+}
+
 /// Storage slot data in the response.
 #[derive(Debug, Clone, PartialEq, Eq, RlpEncodable, RlpDecodable)]
 #[cfg_attr(any(test, feature = "arbitrary"), derive(arbitrary::Arbitrary))]
@@ -406,6 +445,42 @@ mod tests {
         }));
     }
 
+    #[test]
+    fn test_get_storage_ranges_empty_origin_and_limit() {
+        // geth encodes the origin and limit of requests for entire storage tries as empty strings
+        #[derive(RlpEncodable)]
+        struct GethGetStorageRanges {
+            request_id: u64,
+            root_hash: B256,
+            account_hashes: Vec<B256>,
+            origin: Bytes,
+            limit: Bytes,
+            response_bytes: u64,
+        }
+
+        let encoded = alloy_rlp::encode(GethGetStorageRanges {
+            request_id: 42,
+            root_hash: b256_from_u64(123),
+            account_hashes: vec![b256_from_u64(456)],
+            origin: Bytes::new(),
+            limit: Bytes::new(),
+            response_bytes: 2048,
+        });
+
+        let decoded = GetStorageRangesMessage::decode(&mut &encoded[..]).unwrap();
+        assert_eq!(
+            decoded,
+            GetStorageRangesMessage {
+                request_id: 42,
+                root_hash: b256_from_u64(123),
+                account_hashes: vec![b256_from_u64(456)],
+                starting_hash: B256::ZERO,
+                limit_hash: B256::repeat_byte(0xff),
+                response_bytes: 2048,
+            }
+        );
+    }
+
     #[test]
     fn test_unknown_message_id() {
         // Create some random data
@@ -1,6 +1,6 @@
 //! A Protocol defines a P2P subprotocol in an `RLPx` connection
 
-use crate::{Capability, EthVersion};
+use crate::{Capability, EthVersion, SnapMessageId};
 
 /// Type that represents a [Capability] and the number of messages it uses.
 ///
@@ -45,6 +45,11 @@ impl Protocol {
         Self::eth(EthVersion::Eth68)
     }
 
+    /// Returns the `snap/1` capability.
+    pub const fn snap_1() -> Self {
+        Self::new(Capability::new_static("snap", 1), SnapMessageId::TrieNodes as u8 + 1)
+    }
+
     /// Consumes the type and returns a tuple of the [Capability] and number of messages.
     #[inline]
     pub(crate) fn split(self) -> (Capability, u8) {
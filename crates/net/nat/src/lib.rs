@@ -1,5 +1,8 @@
 //! Helpers for resolving the external IP.
 //!
+//! The [`upnp`] and [`natpmp`] clients can additionally map ports on the gateway of the local
+//! network, see [`ResolveNatInterval::with_port_mappings`].
+//!
 //! ## Feature Flags
 //!
 //! - `serde` (default): Enable serde support
@@ -12,9 +15,16 @@
 #![cfg_attr(not(test), warn(unused_crate_dependencies))]
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 
+pub mod natpmp;
 pub mod net_if;
+pub mod port_mapping;
+pub mod upnp;
 
 pub use net_if::{NetInterfaceError, DEFAULT_NET_IF_NAME};
+pub use port_mapping::{
+    is_public_ip, Gateway, PortMapper, PortMapping, PortMappingError, PortMappingProtocol,
+    DEFAULT_PORT_MAPPING_LEASE,
+};
 
 use std::{
     fmt,
@@ -22,10 +32,11 @@ use std::{
     net::{AddrParseError, IpAddr},
     pin::Pin,
     str::FromStr,
+    sync::Arc,
     task::{Context, Poll},
     time::Duration,
 };
-use tracing::{debug, error};
+use tracing::debug;
 
 use crate::net_if::resolve_net_if_ip;
 #[cfg(feature = "serde")]
@@ -46,6 +57,8 @@ pub enum NatResolver {
     Any,
     /// Resolve external IP via `UPnP`.
     Upnp,
+    /// Resolve external IP via PCP or NAT-PMP, using the given gateway or the default gateway.
+    NatPmp(Option<IpAddr>),
     /// Resolve external IP via a network request.
     PublicIp,
     /// Use the given [`IpAddr`]
@@ -69,6 +82,14 @@ impl NatResolver {
             _ => None,
         }
     }
+
+    /// Returns whether this resolver maps ports on the gateway of the local network.
+    ///
+    /// Ports are only mapped if `UPnP` or PCP/NAT-PMP is selected explicitly, the default
+    /// [`NatResolver::Any`] never changes the configuration of the gateway.
+    pub const fn maps_ports(self) -> bool {
+        matches!(self, Self::Upnp | Self::NatPmp(_))
+    }
 }
 
 impl fmt::Display for NatResolver {
@@ -76,6 +97,8 @@ impl fmt::Display for NatResolver {
         match self {
             Self::Any => f.write_str("any"),
             Self::Upnp => f.write_str("upnp"),
+            Self::NatPmp(None) => f.write_str("natpmp"),
+            Self::NatPmp(Some(gateway)) => write!(f, "natpmp:{gateway}"),
             Self::PublicIp => f.write_str("publicip"),
             Self::ExternalIp(ip) => write!(f, "extip:{ip}"),
             Self::NetIf => f.write_str("netif"),
@@ -102,10 +125,14 @@ impl FromStr for NatResolver {
         let r = match s {
             "any" => Self::Any,
             "upnp" => Self::Upnp,
+            "natpmp" | "pcp" => Self::NatPmp(None),
             "none" => Self::None,
             "publicip" | "public-ip" => Self::PublicIp,
             "netif" => Self::NetIf,
             s => {
+                if let Some(gateway) = s.strip_prefix("natpmp:") {
+                    return Ok(Self::NatPmp(Some(gateway.parse()?)))
+                }
                 let Some(ip) = s.strip_prefix("extip:") else {
                     return Err(ParseNatResolverError::UnknownVariant(format!(
                         "Unknown Nat Resolver: {s}"
@@ -119,11 +146,15 @@ impl FromStr for NatResolver {
 }
 
 /// With this type you can resolve the external public IP address on an interval basis.
+///
+/// If port mappings are configured, they are requested or renewed on every tick instead, and the
+/// external address of the gateway is returned. The mappings are removed when this is dropped.
 #[must_use = "Does nothing unless polled"]
 pub struct ResolveNatInterval {
     resolver: NatResolver,
     future: Option<Pin<Box<dyn Future<Output = Option<IpAddr>> + Send>>>,
     interval: tokio::time::Interval,
+    port_mapper: Option<Arc<tokio::sync::Mutex<PortMapper>>>,
 }
 
 impl fmt::Debug for ResolveNatInterval {
@@ -132,13 +163,14 @@ impl fmt::Debug for ResolveNatInterval {
             .field("resolver", &self.resolver)
             .field("future", &self.future.as_ref().map(drop))
             .field("interval", &self.interval)
+            .field("port_mapper", &self.port_mapper)
             .finish()
     }
 }
 
 impl ResolveNatInterval {
     fn with_interval(resolver: NatResolver, interval: tokio::time::Interval) -> Self {
-        Self { resolver, future: None, interval }
+        Self { resolver, future: None, interval, port_mapper: None }
     }
 
     /// Creates a new [`ResolveNatInterval`] that attempts to resolve the public IP with interval of
@@ -161,6 +193,22 @@ impl ResolveNatInterval {
         Self::with_interval(resolver, interval)
     }
 
+    /// Maps the given ports on the gateway of the local network on every tick, if the resolver
+    /// supports it. See also [`NatResolver::maps_ports`].
+    pub fn with_port_mappings(mut self, mappings: Vec<PortMapping>) -> Self {
+        if self.resolver.maps_ports() && !mappings.is_empty() {
+            let mapper = PortMapper::new(self.resolver, mappings);
+            self.port_mapper = Some(Arc::new(tokio::sync::Mutex::new(mapper)));
+        }
+        self
+    }
+
+    /// Uses the given [`PortMapper`] to map ports on every tick.
+    pub fn with_port_mapper(mut self, mapper: PortMapper) -> Self {
+        self.port_mapper = Some(Arc::new(tokio::sync::Mutex::new(mapper)));
+        self
+    }
+
     /// Returns the resolver used by this interval
     pub const fn resolver(&self) -> &NatResolver {
         &self.resolver
@@ -180,7 +228,13 @@ impl ResolveNatInterval {
     ///    `None` if the attempt was unsuccessful.
     pub fn poll_tick(&mut self, cx: &mut Context<'_>) -> Poll<Option<IpAddr>> {
         if self.interval.poll_tick(cx).is_ready() {
-            self.future = Some(Box::pin(self.resolver.external_addr()));
+            self.future = Some(match &self.port_mapper {
+                Some(mapper) => {
+                    let mapper = mapper.clone();
+                    Box::pin(async move { mapper.lock().await.map_ports().await })
+                }
+                None => Box::pin(self.resolver.external_addr()),
+            });
         }
 
         if let Some(mut fut) = self.future.take() {
@@ -194,6 +248,16 @@ impl ResolveNatInterval {
     }
 }
 
+impl Drop for ResolveNatInterval {
+    fn drop(&mut self) {
+        let Some(mapper) = self.port_mapper.take() else { return };
+        // removing the mappings is best effort, they expire with their lease otherwise
+        if let Ok(handle) = tokio::runtime::Handle::try_current() {
+            handle.spawn(async move { mapper.lock().await.unmap_ports().await });
+        }
+    }
+}
+
 /// Attempts to produce an IP address with all builtin resolvers (best effort).
 pub async fn external_ip() -> Option<IpAddr> {
     external_addr_with(NatResolver::Any).await
@@ -202,7 +266,8 @@ pub async fn external_ip() -> Option<IpAddr> {
 /// Given a [`NatResolver`] attempts to produce an IP address (best effort).
 pub async fn external_addr_with(resolver: NatResolver) -> Option<IpAddr> {
     match resolver {
-        NatResolver::Any | NatResolver::Upnp | NatResolver::PublicIp => resolve_external_ip().await,
+        NatResolver::Any | NatResolver::PublicIp => resolve_external_ip().await,
+        NatResolver::Upnp | NatResolver::NatPmp(_) => resolve_gateway_external_ip(resolver).await,
         NatResolver::ExternalIp(ip) => Some(ip),
         NatResolver::NetIf => resolve_net_if_ip(DEFAULT_NET_IF_NAME)
             .inspect_err(|err| {
@@ -216,6 +281,21 @@ pub async fn external_addr_with(resolver: NatResolver) -> Option<IpAddr> {
     }
 }
 
+async fn resolve_gateway_external_ip(resolver: NatResolver) -> Option<IpAddr> {
+    let res = match Gateway::discover(resolver).await {
+        Ok(gateway) => gateway.public_external_ip().await,
+        Err(err) => Err(err),
+    };
+    res.inspect_err(|err| {
+        debug!(target: "net::nat",
+            %err,
+            %resolver,
+            "Failed to resolve external IP from gateway"
+        );
+    })
+    .ok()
+}
+
 async fn resolve_external_ip() -> Option<IpAddr> {
     let futures = EXTERNAL_IP_APIS.iter().copied().map(resolve_external_ip_url_res).map(Box::pin);
     futures_util::future::select_ok(futures)
@@ -276,5 +356,39 @@ mod tests {
         let s = "extip:0.0.0.0";
         assert_eq!(ip, s.parse().unwrap());
         assert_eq!(ip.to_string().as_str(), s);
+
+        assert_eq!(NatResolver::NatPmp(None), "natpmp".parse().unwrap());
+        assert_eq!(NatResolver::NatPmp(None).to_string(), "natpmp");
+        let natpmp = NatResolver::NatPmp(Some(IpAddr::V4(Ipv4Addr::new(192, 168, 1, 1))));
+        let s = "natpmp:192.168.1.1";
+        assert_eq!(natpmp, s.parse().unwrap());
+        assert_eq!(natpmp.to_string().as_str(), s);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn resolve_interval_maps_ports() {
+        let igd = upnp::tests::MockIgd::spawn(false).await;
+        let gateway =
+            upnp::IgdGateway::discover_at(igd.ssdp_addr, upnp::DEFAULT_SSDP_TIMEOUT).await.unwrap();
+        let mapper = PortMapper::new(
+            NatResolver::Upnp,
+            vec![PortMapping::tcp(30303), PortMapping::udp(30303)],
+        )
+        .with_gateway(Gateway::Igd(gateway));
+        let mut interval =
+            ResolveNatInterval::interval(NatResolver::Upnp, Duration::from_secs(300))
+                .with_port_mapper(mapper);
+
+        assert_eq!(interval.tick().await, Some("203.0.113.7".parse().unwrap()));
+        assert_eq!(igd.mappings.lock().unwrap().len(), 2);
+
+        drop(interval);
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while !igd.mappings.lock().unwrap().is_empty() {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
     }
 }
@@ -0,0 +1,480 @@
+//! Minimal PCP and NAT-PMP client.
+//!
+//! Port mappings are requested with the PCP `MAP` opcode, see
+//! <https://datatracker.ietf.org/doc/html/rfc6887>. Gateways that only speak NAT-PMP answer PCP
+//! requests with an unsupported version error, in which case the client falls back to NAT-PMP, see
+//! <https://datatracker.ietf.org/doc/html/rfc6886>.
+
+use crate::port_mapping::{local_ip_for, PortMappingError, PortMappingProtocol};
+use std::{
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    time::Duration,
+};
+use tokio::net::UdpSocket;
+use tracing::trace;
+
+/// The port that PCP and NAT-PMP servers listen on.
+pub const NAT_PMP_PORT: u16 = 5351;
+
+/// The NAT-PMP protocol version.
+const NAT_PMP_VERSION: u8 = 0;
+
+/// The PCP protocol version.
+const PCP_VERSION: u8 = 2;
+
+/// The PCP `MAP` opcode.
+const PCP_OPCODE_MAP: u8 = 1;
+
+/// The NAT-PMP public address opcode.
+const NAT_PMP_OPCODE_PUBLIC_ADDRESS: u8 = 0;
+
+/// The bit that is set in the opcode of responses.
+const RESPONSE_BIT: u8 = 0x80;
+
+/// The result code for success, shared by PCP and NAT-PMP.
+const RESULT_SUCCESS: u16 = 0;
+
+/// Number of times a request is sent before giving up.
+///
+/// The first retransmission happens after 250ms, and the delay doubles after each attempt.
+const MAX_ATTEMPTS: u32 = 4;
+
+/// The initial delay before a request is retransmitted.
+const INITIAL_RETRANSMISSION_DELAY: Duration = Duration::from_millis(250);
+
+/// Which protocol a gateway speaks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NatPmpVersion {
+    /// The Port Control Protocol.
+    Pcp,
+    /// NAT Port Mapping Protocol.
+    NatPmp,
+}
+
+/// A port mapping that was granted by the gateway.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NatPmpMapping {
+    /// The external port of the mapping.
+    pub external_port: u16,
+    /// The lifetime of the mapping.
+    pub lifetime: Duration,
+    /// The external IP address of the mapping, only reported by PCP gateways.
+    pub external_ip: Option<IpAddr>,
+}
+
+/// A gateway that manages port mappings via PCP or NAT-PMP.
+#[derive(Debug, Clone)]
+pub struct NatPmpGateway {
+    /// The address of the gateway's PCP/NAT-PMP server
+    gateway: SocketAddr,
+    /// The address of this host in the gateway's network
+    local_ip: IpAddr,
+    /// The protocol the gateway speaks, if known
+    version: Option<NatPmpVersion>,
+    /// The nonce that identifies this client's PCP mappings
+    nonce: [u8; 12],
+}
+
+impl NatPmpGateway {
+    /// Creates a client for the PCP/NAT-PMP server at the given address.
+    pub async fn new(gateway: SocketAddr) -> Result<Self, PortMappingError> {
+        let local_ip = local_ip_for(gateway).await?;
+        Ok(Self { gateway, local_ip, version: None, nonce: rand::random() })
+    }
+
+    /// Creates a client for the PCP/NAT-PMP server of the default gateway.
+    pub async fn default_gateway() -> Result<Self, PortMappingError> {
+        let gateway = default_gateway().ok_or(PortMappingError::NoGateway)?;
+        Self::new(SocketAddr::new(gateway.into(), NAT_PMP_PORT)).await
+    }
+
+    /// Returns the protocol the gateway speaks, if it answered a mapping request before.
+    pub const fn version(&self) -> Option<NatPmpVersion> {
+        self.version
+    }
+
+    /// Returns the external IP address of the gateway.
+    pub async fn external_ip(&self) -> Result<IpAddr, PortMappingError> {
+        let response =
+            self.request(&[NAT_PMP_VERSION, NAT_PMP_OPCODE_PUBLIC_ADDRESS], 12, is_nat_pmp).await?;
+        check_result(u16::from_be_bytes([response[2], response[3]]))?;
+        Ok(IpAddr::V4(Ipv4Addr::new(response[8], response[9], response[10], response[11])))
+    }
+
+    /// Requests a mapping of the port of this host to the same external port.
+    ///
+    /// Mappings must be renewed before their lifetime ends, and are removed if the lifetime is
+    /// zero.
+    pub async fn map_port(
+        &mut self,
+        protocol: PortMappingProtocol,
+        port: u16,
+        lifetime: Duration,
+    ) -> Result<NatPmpMapping, PortMappingError> {
+        let lifetime = u32::try_from(lifetime.as_secs()).unwrap_or(u32::MAX);
+        if self.version != Some(NatPmpVersion::NatPmp) {
+            match self.pcp_map(protocol, port, lifetime).await {
+                Err(PortMappingError::UnsupportedVersion) => {
+                    trace!(target: "net::nat", gateway=%self.gateway, "Falling back to NAT-PMP");
+                }
+                res => {
+                    self.version = Some(NatPmpVersion::Pcp);
+                    return res
+                }
+            }
+        }
+        let res = self.nat_pmp_map(protocol, port, lifetime).await;
+        self.version = Some(NatPmpVersion::NatPmp);
+        res
+    }
+
+    /// Removes the mapping of the port of this host.
+    pub async fn remove_port_mapping(
+        &mut self,
+        protocol: PortMappingProtocol,
+        port: u16,
+    ) -> Result<(), PortMappingError> {
+        self.map_port(protocol, port, Duration::ZERO).await.map(drop)
+    }
+
+    async fn pcp_map(
+        &self,
+        protocol: PortMappingProtocol,
+        port: u16,
+        lifetime: u32,
+    ) -> Result<NatPmpMapping, PortMappingError> {
+        let mut request = [0u8; 60];
+        request[0] = PCP_VERSION;
+        request[1] = PCP_OPCODE_MAP;
+        request[4..8].copy_from_slice(&lifetime.to_be_bytes());
+        request[8..24].copy_from_slice(&to_ipv6(self.local_ip).octets());
+        request[24..36].copy_from_slice(&self.nonce);
+        request[36] = protocol.iana_number();
+        request[40..42].copy_from_slice(&port.to_be_bytes());
+        // a deleted mapping has no external port
+        let suggested_port = if lifetime == 0 { 0 } else { port };
+        request[42..44].copy_from_slice(&suggested_port.to_be_bytes());
+        // the all-zero IPv4-mapped address requests an IPv4 mapping
+        request[44..60].copy_from_slice(&Ipv4Addr::UNSPECIFIED.to_ipv6_mapped().octets());
+
+        let nonce = self.nonce;
+        let response = self
+            .request(&request, 60, |response| {
+                if response[0] == NAT_PMP_VERSION {
+                    return true
+                }
+                response[0] == PCP_VERSION &&
+                    response[1] == RESPONSE_BIT | PCP_OPCODE_MAP &&
+                    response.get(24..36) == Some(&nonce[..])
+            })
+            .await?;
+        if response[0] == NAT_PMP_VERSION {
+            return Err(PortMappingError::UnsupportedVersion)
+        }
+        check_result(u16::from(response[3]))?;
+
+        let external_ip = Ipv6Addr::from(<[u8; 16]>::try_from(&response[44..60]).unwrap());
+        Ok(NatPmpMapping {
+            external_port: u16::from_be_bytes([response[42], response[43]]),
+            lifetime: Duration::from_secs(
+                u32::from_be_bytes(response[4..8].try_into().unwrap()).into(),
+            ),
+            external_ip: Some(external_ip.to_canonical()),
+        })
+    }
+
+    async fn nat_pmp_map(
+        &self,
+        protocol: PortMappingProtocol,
+        port: u16,
+        lifetime: u32,
+    ) -> Result<NatPmpMapping, PortMappingError> {
+        let opcode = match protocol {
+            PortMappingProtocol::Udp => 1,
+            PortMappingProtocol::Tcp => 2,
+        };
+        let mut request = [0u8; 12];
+        request[0] = NAT_PMP_VERSION;
+        request[1] = opcode;
+        request[4..6].copy_from_slice(&port.to_be_bytes());
+        let suggested_port = if lifetime == 0 { 0 } else { port };
+        request[6..8].copy_from_slice(&suggested_port.to_be_bytes());
+        request[8..12].copy_from_slice(&lifetime.to_be_bytes());
+
+        let response = self
+            .request(&request, 16, |response| {
+                is_nat_pmp(response) &&
+                    response[1] == RESPONSE_BIT | opcode &&
+                    response[8..10] == port.to_be_bytes()
+            })
+            .await?;
+        check_result(u16::from_be_bytes([response[2], response[3]]))?;
+
+        Ok(NatPmpMapping {
+            external_port: u16::from_be_bytes([response[10], response[11]]),
+            lifetime: Duration::from_secs(
+                u32::from_be_bytes(response[12..16].try_into().unwrap()).into(),
+            ),
+            external_ip: None,
+        })
+    }
+
+    /// Sends the request until a matching response of at least `min_len` bytes is received.
+    ///
+    /// Responses with an unsupported version error are always returned, regardless of length.
+    async fn request(
+        &self,
+        request: &[u8],
+        min_len: usize,
+        is_response: impl Fn(&[u8]) -> bool,
+    ) -> Result<Vec<u8>, PortMappingError> {
+        let socket = UdpSocket::bind(SocketAddr::new(self.local_ip, 0)).await?;
+        socket.connect(self.gateway).await?;
+
+        let mut delay = INITIAL_RETRANSMISSION_DELAY;
+        let mut buf = [0u8; 1100];
+        for _ in 0..MAX_ATTEMPTS {
+            socket.send(request).await?;
+            let deadline = tokio::time::Instant::now() + delay;
+            while let Ok(len) = tokio::time::timeout_at(deadline, socket.recv(&mut buf)).await {
+                let response = &buf[..len?];
+                if response.len() >= 4 && is_unsupported_version(response) {
+                    return Ok(response.to_vec())
+                }
+                if response.len() >= min_len && is_response(response) {
+                    return Ok(response.to_vec())
+                }
+            }
+            delay *= 2;
+        }
+        Err(PortMappingError::NoGateway)
+    }
+}
+
+/// Returns whether the response is a NAT-PMP response.
+fn is_nat_pmp(response: &[u8]) -> bool {
+    response[0] == NAT_PMP_VERSION
+}
+
+/// Returns whether the response reports that the request's version is not supported.
+///
+/// The result code is at a different offset in PCP and NAT-PMP responses, but it's `1` in both.
+fn is_unsupported_version(response: &[u8]) -> bool {
+    match response[0] {
+        NAT_PMP_VERSION => u16::from_be_bytes([response[2], response[3]]) == 1,
+        PCP_VERSION => response[3] == 1,
+        _ => false,
+    }
+}
+
+/// Maps the result code of a response to an error.
+const fn check_result(code: u16) -> Result<(), PortMappingError> {
+    match code {
+        RESULT_SUCCESS => Ok(()),
+        1 => Err(PortMappingError::UnsupportedVersion),
+        code => Err(PortMappingError::NatPmp(code)),
+    }
+}
+
+/// Returns the IPv6 representation of the address, as used by PCP.
+const fn to_ipv6(ip: IpAddr) -> Ipv6Addr {
+    match ip {
+        IpAddr::V4(ip) => ip.to_ipv6_mapped(),
+        IpAddr::V6(ip) => ip,
+    }
+}
+
+/// Returns the IPv4 address of the default gateway.
+///
+/// This is only supported on Linux, where the routing table is read from `/proc/net/route`.
+pub fn default_gateway() -> Option<Ipv4Addr> {
+    if cfg!(target_os = "linux") {
+        parse_default_gateway(&std::fs::read_to_string("/proc/net/route").ok()?)
+    } else {
+        None
+    }
+}
+
+/// Returns the gateway of the default route in the given `/proc/net/route` table.
+fn parse_default_gateway(routes: &str) -> Option<Ipv4Addr> {
+    routes.lines().skip(1).find_map(|line| {
+        let mut columns = line.split_whitespace();
+        let destination = columns.nth(1)?;
+        let gateway = u32::from_str_radix(columns.next()?, 16).ok()?;
+        // the addresses are in host byte order
+        (destination == "00000000" && gateway != 0).then(|| Ipv4Addr::from(gateway.swap_bytes()))
+    })
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+    };
+
+    /// The port mappings of a [`MockNatPmp`] gateway, by protocol number and internal port.
+    pub(crate) type MockMappings = Arc<Mutex<HashMap<(u8, u16), u32>>>;
+
+    /// The external IP address of the mock gateways.
+    const EXTERNAL_IP: Ipv4Addr = Ipv4Addr::new(203, 0, 113, 9);
+
+    /// A stand-in for a gateway that speaks PCP or NAT-PMP.
+    pub(crate) struct MockNatPmp {
+        /// The address of the mock gateway.
+        pub(crate) addr: SocketAddr,
+        /// The current port mappings.
+        pub(crate) mappings: MockMappings,
+    }
+
+    impl MockNatPmp {
+        /// Spawns a mock gateway that speaks the given protocol.
+        pub(crate) async fn spawn(version: NatPmpVersion) -> Self {
+            let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+            let addr = socket.local_addr().unwrap();
+            let mappings = MockMappings::default();
+
+            let state = mappings.clone();
+            tokio::spawn(async move {
+                let mut buf = [0; 1100];
+                while let Ok((len, from)) = socket.recv_from(&mut buf).await {
+                    let response = match version {
+                        NatPmpVersion::Pcp => pcp_response(&buf[..len], &state),
+                        NatPmpVersion::NatPmp => nat_pmp_response(&buf[..len], &state),
+                    };
+                    socket.send_to(&response, from).await.unwrap();
+                }
+            });
+
+            Self { addr, mappings }
+        }
+    }
+
+    fn pcp_response(request: &[u8], mappings: &MockMappings) -> Vec<u8> {
+        if request[0] != PCP_VERSION {
+            // unsupported version
+            let mut response = vec![0; 24];
+            response[0] = PCP_VERSION;
+            response[1] = request[1] | RESPONSE_BIT;
+            response[3] = 1;
+            return response
+        }
+        assert_eq!(request[1], PCP_OPCODE_MAP);
+        assert_eq!(&request[8..24], &Ipv4Addr::LOCALHOST.to_ipv6_mapped().octets());
+
+        let lifetime = u32::from_be_bytes(request[4..8].try_into().unwrap());
+        let key = (request[36], u16::from_be_bytes([request[40], request[41]]));
+        if lifetime == 0 {
+            mappings.lock().unwrap().remove(&key);
+        } else {
+            mappings.lock().unwrap().insert(key, lifetime);
+        }
+
+        let mut response = vec![0; 60];
+        response[0] = PCP_VERSION;
+        response[1] = RESPONSE_BIT | PCP_OPCODE_MAP;
+        // grant at most an hour
+        response[4..8].copy_from_slice(&lifetime.min(3600).to_be_bytes());
+        response[24..42].copy_from_slice(&request[24..42]);
+        response[42..44].copy_from_slice(&request[42..44]);
+        response[44..60].copy_from_slice(&EXTERNAL_IP.to_ipv6_mapped().octets());
+        response
+    }
+
+    fn nat_pmp_response(request: &[u8], mappings: &MockMappings) -> Vec<u8> {
+        let opcode = request[1];
+        if request[0] != NAT_PMP_VERSION {
+            // unsupported version
+            return vec![NAT_PMP_VERSION, opcode | RESPONSE_BIT, 0, 1, 0, 0, 0, 0]
+        }
+
+        let mut response = vec![NAT_PMP_VERSION, opcode | RESPONSE_BIT, 0, 0, 0, 0, 0, 1];
+        if opcode == NAT_PMP_OPCODE_PUBLIC_ADDRESS {
+            response.extend_from_slice(&EXTERNAL_IP.octets());
+            return response
+        }
+
+        let protocol = if opcode == 1 { 17 } else { 6 };
+        let internal_port = u16::from_be_bytes([request[4], request[5]]);
+        let lifetime = u32::from_be_bytes(request[8..12].try_into().unwrap());
+        if lifetime == 0 {
+            mappings.lock().unwrap().remove(&(protocol, internal_port));
+        } else {
+            mappings.lock().unwrap().insert((protocol, internal_port), lifetime);
+        }
+        response.extend_from_slice(&internal_port.to_be_bytes());
+        // map to a different external port
+        let external_port = if lifetime == 0 { 0 } else { internal_port + 1 };
+        response.extend_from_slice(&external_port.to_be_bytes());
+        response.extend_from_slice(&lifetime.to_be_bytes());
+        response
+    }
+
+    #[tokio::test]
+    async fn pcp_port_mappings() {
+        let mock = MockNatPmp::spawn(NatPmpVersion::Pcp).await;
+        let mut gateway = NatPmpGateway::new(mock.addr).await.unwrap();
+
+        let mapping = gateway
+            .map_port(PortMappingProtocol::Tcp, 30303, Duration::from_secs(7200))
+            .await
+            .unwrap();
+        assert_eq!(gateway.version(), Some(NatPmpVersion::Pcp));
+        assert_eq!(
+            mapping,
+            NatPmpMapping {
+                external_port: 30303,
+                lifetime: Duration::from_secs(3600),
+                external_ip: Some(EXTERNAL_IP.into()),
+            }
+        );
+        assert_eq!(mock.mappings.lock().unwrap().get(&(6, 30303)), Some(&7200));
+
+        gateway.remove_port_mapping(PortMappingProtocol::Tcp, 30303).await.unwrap();
+        assert!(mock.mappings.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn nat_pmp_fallback() {
+        let mock = MockNatPmp::spawn(NatPmpVersion::NatPmp).await;
+        let mut gateway = NatPmpGateway::new(mock.addr).await.unwrap();
+        assert_eq!(gateway.external_ip().await.unwrap(), IpAddr::from(EXTERNAL_IP));
+
+        let mapping = gateway
+            .map_port(PortMappingProtocol::Udp, 30303, Duration::from_secs(7200))
+            .await
+            .unwrap();
+        assert_eq!(gateway.version(), Some(NatPmpVersion::NatPmp));
+        assert_eq!(
+            mapping,
+            NatPmpMapping {
+                external_port: 30304,
+                lifetime: Duration::from_secs(7200),
+                external_ip: None,
+            }
+        );
+        assert_eq!(mock.mappings.lock().unwrap().get(&(17, 30303)), Some(&7200));
+
+        gateway.remove_port_mapping(PortMappingProtocol::Udp, 30303).await.unwrap();
+        assert!(mock.mappings.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn no_gateway() {
+        let silent = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let mut gateway = NatPmpGateway::new(silent.local_addr().unwrap()).await.unwrap();
+        let res = gateway.map_port(PortMappingProtocol::Tcp, 30303, Duration::from_secs(60)).await;
+        assert!(matches!(res, Err(PortMappingError::NoGateway)));
+    }
+
+    #[test]
+    fn parse_routes() {
+        let routes =
+            "Iface\tDestination\tGateway \tFlags\tRefCnt\tUse\tMetric\tMask\t\tMTU\tWindow\tIRTT\n\
+            eth0\t0000A8C0\t00000000\t0001\t0\t0\t0\t00FFFFFF\t0\t0\t0\n\
+            eth0\t00000000\t0101A8C0\t0003\t0\t0\t0\t00000000\t0\t0\t0\n";
+        assert_eq!(parse_default_gateway(routes), Some(Ipv4Addr::new(192, 168, 1, 1)));
+        assert_eq!(parse_default_gateway(routes.lines().next().unwrap()), None);
+    }
+}
@@ -0,0 +1,444 @@
+//! Port mappings on the gateway of the local network.
+//!
+//! The [`PortMapper`] maps the ports of this host on a `UPnP` internet gateway device or a
+//! PCP/NAT-PMP gateway, so that peers outside the local network can connect to them.
+
+use crate::{
+    natpmp::NatPmpGateway,
+    upnp::{IgdGateway, DEFAULT_SSDP_TIMEOUT},
+    NatResolver,
+};
+use std::{
+    fmt, io,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    time::Duration,
+};
+use tokio::net::UdpSocket;
+use tracing::{debug, trace, warn};
+
+/// The lease of the port mappings.
+///
+/// Mappings are renewed whenever the external address is resolved, which happens far more often.
+pub const DEFAULT_PORT_MAPPING_LEASE: Duration = Duration::from_secs(2 * 60 * 60);
+
+/// The transport protocol of a port mapping.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PortMappingProtocol {
+    /// TCP, used by `RLPx`.
+    Tcp,
+    /// UDP, used by discovery.
+    Udp,
+}
+
+impl PortMappingProtocol {
+    /// Returns the IANA protocol number.
+    pub const fn iana_number(&self) -> u8 {
+        match self {
+            Self::Tcp => 6,
+            Self::Udp => 17,
+        }
+    }
+}
+
+impl fmt::Display for PortMappingProtocol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tcp => f.write_str("TCP"),
+            Self::Udp => f.write_str("UDP"),
+        }
+    }
+}
+
+/// A port of this host that should be reachable from outside the local network.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PortMapping {
+    /// The transport protocol.
+    pub protocol: PortMappingProtocol,
+    /// The port of this host, the same external port is requested from the gateway.
+    pub port: u16,
+}
+
+impl PortMapping {
+    /// Creates a TCP port mapping.
+    pub const fn tcp(port: u16) -> Self {
+        Self { protocol: PortMappingProtocol::Tcp, port }
+    }
+
+    /// Creates a UDP port mapping.
+    pub const fn udp(port: u16) -> Self {
+        Self { protocol: PortMappingProtocol::Udp, port }
+    }
+}
+
+impl fmt::Display for PortMapping {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.port, self.protocol)
+    }
+}
+
+/// Errors that can occur when talking to a gateway.
+#[derive(Debug, thiserror::Error)]
+pub enum PortMappingError {
+    /// Failed to send or receive a request.
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    /// Failed to send a HTTP request to the `UPnP` gateway.
+    #[error(transparent)]
+    Http(reqwest::Error),
+    /// No gateway answered.
+    #[error("no gateway found")]
+    NoGateway,
+    /// The gateway sent a response that could not be understood.
+    #[error("invalid gateway response: {0}")]
+    InvalidResponse(&'static str),
+    /// The `UPnP` gateway rejected the request with the given error code.
+    #[error("UPnP error code {0}")]
+    Upnp(u16),
+    /// The PCP/NAT-PMP gateway rejected the request with the given result code.
+    #[error("PCP/NAT-PMP result code {0}")]
+    NatPmp(u16),
+    /// The gateway doesn't support the protocol version.
+    #[error("unsupported protocol version")]
+    UnsupportedVersion,
+    /// The external IP address of the gateway is not publicly routable.
+    #[error("external IP address {0} of the gateway is not public")]
+    NonPublicExternalIp(IpAddr),
+    /// The gateway mapped the port to a different external port.
+    #[error("gateway mapped {mapping} to external port {external_port}")]
+    ExternalPortMismatch {
+        /// The requested mapping.
+        mapping: PortMapping,
+        /// The external port assigned by the gateway.
+        external_port: u16,
+    },
+}
+
+/// Returns `false` for addresses that are not reachable from the internet: private, shared
+/// (carrier-grade NAT, `100.64.0.0/10`), loopback, link-local and unspecified addresses.
+pub fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, ..] = ip.octets();
+            let shared = a == 100 && (b & 0b1100_0000) == 64;
+            !(ip.is_private() ||
+                shared ||
+                ip.is_loopback() ||
+                ip.is_link_local() ||
+                ip.is_unspecified() ||
+                ip.is_broadcast())
+        }
+        IpAddr::V6(ip) => {
+            let unique_local = (ip.segments()[0] & 0xfe00) == 0xfc00;
+            let link_local = (ip.segments()[0] & 0xffc0) == 0xfe80;
+            !(unique_local || link_local || ip.is_loopback() || ip.is_unspecified())
+        }
+    }
+}
+
+/// Returns the address of this host that is used to reach the given address.
+pub(crate) async fn local_ip_for(addr: SocketAddr) -> io::Result<IpAddr> {
+    let unspecified: IpAddr =
+        if addr.is_ipv4() { Ipv4Addr::UNSPECIFIED.into() } else { Ipv6Addr::UNSPECIFIED.into() };
+    let socket = UdpSocket::bind((unspecified, 0)).await?;
+    socket.connect(addr).await?;
+    Ok(socket.local_addr()?.ip())
+}
+
+/// A gateway that can map ports.
+#[derive(Debug, Clone)]
+pub enum Gateway {
+    /// A `UPnP` internet gateway device.
+    Igd(IgdGateway),
+    /// A PCP or NAT-PMP gateway.
+    NatPmp(NatPmpGateway),
+}
+
+impl Gateway {
+    /// Discovers a gateway that is supported by the resolver, see [`NatResolver::maps_ports`].
+    pub async fn discover(resolver: NatResolver) -> Result<Self, PortMappingError> {
+        match resolver {
+            NatResolver::Upnp => Ok(Self::Igd(IgdGateway::discover(DEFAULT_SSDP_TIMEOUT).await?)),
+            NatResolver::NatPmp(gateway) => Ok(Self::NatPmp(nat_pmp_gateway(gateway).await?)),
+            _ => Err(PortMappingError::NoGateway),
+        }
+    }
+
+    /// Returns the external IP address of the gateway.
+    pub async fn external_ip(&self) -> Result<IpAddr, PortMappingError> {
+        match self {
+            Self::Igd(gateway) => gateway.external_ip().await,
+            Self::NatPmp(gateway) => gateway.external_ip().await,
+        }
+    }
+
+    /// Returns the external IP address of the gateway, if it is publicly routable.
+    ///
+    /// A gateway behind another NAT, e.g. carrier-grade NAT, reports a private address, which
+    /// peers can't connect to.
+    pub async fn public_external_ip(&self) -> Result<IpAddr, PortMappingError> {
+        let ip = self.external_ip().await?;
+        if !is_public_ip(ip) {
+            return Err(PortMappingError::NonPublicExternalIp(ip))
+        }
+        Ok(ip)
+    }
+
+    /// Maps the port of this host to the same external port for the given lease.
+    ///
+    /// Returns the external IP address if the gateway reported it. The local node record only
+    /// advertises the ports of this host, so if the gateway assigns a different external port, the
+    /// mapping is removed again and [`PortMappingError::ExternalPortMismatch`] is returned.
+    pub async fn add_port_mapping(
+        &mut self,
+        mapping: PortMapping,
+        lease: Duration,
+    ) -> Result<Option<IpAddr>, PortMappingError> {
+        match self {
+            Self::Igd(gateway) => {
+                gateway.add_port_mapping(mapping.protocol, mapping.port, lease).await?;
+                Ok(None)
+            }
+            Self::NatPmp(gateway) => {
+                let mapped = gateway.map_port(mapping.protocol, mapping.port, lease).await?;
+                if mapped.external_port != mapping.port {
+                    if let Err(err) =
+                        gateway.remove_port_mapping(mapping.protocol, mapping.port).await
+                    {
+                        debug!(target: "net::nat", %err, %mapping, "Failed to remove port mapping");
+                    }
+                    return Err(PortMappingError::ExternalPortMismatch {
+                        mapping,
+                        external_port: mapped.external_port,
+                    })
+                }
+                Ok(mapped.external_ip)
+            }
+        }
+    }
+
+    /// Removes the mapping of the port of this host.
+    pub async fn remove_port_mapping(
+        &mut self,
+        mapping: PortMapping,
+    ) -> Result<(), PortMappingError> {
+        match self {
+            Self::Igd(gateway) => gateway.remove_port_mapping(mapping.protocol, mapping.port).await,
+            Self::NatPmp(gateway) => {
+                gateway.remove_port_mapping(mapping.protocol, mapping.port).await
+            }
+        }
+    }
+}
+
+/// Returns the PCP/NAT-PMP client for the given gateway, or the default gateway.
+async fn nat_pmp_gateway(gateway: Option<IpAddr>) -> Result<NatPmpGateway, PortMappingError> {
+    let gateway = match gateway {
+        Some(ip) => NatPmpGateway::new(SocketAddr::new(ip, crate::natpmp::NAT_PMP_PORT)).await?,
+        None => NatPmpGateway::default_gateway().await?,
+    };
+    // probe the gateway, so that unreachable gateways are skipped
+    gateway.external_ip().await?;
+    Ok(gateway)
+}
+
+/// Keeps ports of this host mapped on the gateway of the local network.
+///
+/// The gateway is discovered lazily and rediscovered if a mapping fails.
+#[derive(Debug)]
+pub struct PortMapper {
+    resolver: NatResolver,
+    mappings: Vec<PortMapping>,
+    lease: Duration,
+    gateway: Option<Gateway>,
+}
+
+impl PortMapper {
+    /// Creates a new [`PortMapper`] for the given ports.
+    pub const fn new(resolver: NatResolver, mappings: Vec<PortMapping>) -> Self {
+        Self { resolver, mappings, lease: DEFAULT_PORT_MAPPING_LEASE, gateway: None }
+    }
+
+    /// Uses the given gateway instead of discovering one.
+    pub fn with_gateway(mut self, gateway: Gateway) -> Self {
+        self.gateway = Some(gateway);
+        self
+    }
+
+    /// Sets the lease of the port mappings.
+    pub const fn with_lease(mut self, lease: Duration) -> Self {
+        self.lease = lease;
+        self
+    }
+
+    /// Returns the ports that are mapped.
+    pub fn mappings(&self) -> &[PortMapping] {
+        &self.mappings
+    }
+
+    /// Returns the current gateway, if any.
+    pub const fn gateway(&self) -> Option<&Gateway> {
+        self.gateway.as_ref()
+    }
+
+    /// Requests or renews the port mappings, and returns the external IP address of the gateway.
+    ///
+    /// If the external IP address of the gateway is not public, see [`is_public_ip`], the ports
+    /// are still mapped, but `None` is returned. `None` is also returned if the gateway assigns a
+    /// different external port to any of the ports, which is retried on the next call.
+    pub async fn map_ports(&mut self) -> Option<IpAddr> {
+        let mut gateway = match self.gateway.take() {
+            Some(gateway) => gateway,
+            None => match Gateway::discover(self.resolver).await {
+                Ok(gateway) => {
+                    debug!(target: "net::nat", ?gateway, "Discovered gateway");
+                    gateway
+                }
+                Err(err) => {
+                    debug!(target: "net::nat", %err, resolver=%self.resolver, "Failed to discover gateway");
+                    return None
+                }
+            },
+        };
+
+        let mut external_ip = None;
+        for mapping in &self.mappings {
+            match gateway.add_port_mapping(*mapping, self.lease).await {
+                Ok(ip) => {
+                    trace!(target: "net::nat", %mapping, "Mapped port");
+                    external_ip = external_ip.or(ip);
+                }
+                Err(err @ PortMappingError::ExternalPortMismatch { .. }) => {
+                    // the port may be mapped for another host, which can expire
+                    warn!(target: "net::nat", %err, "Failed to map port to the same external port");
+                    self.gateway = Some(gateway);
+                    return None
+                }
+                Err(err) => {
+                    // the gateway may have been replaced, try to discover it again on the next tick
+                    debug!(target: "net::nat", %err, %mapping, "Failed to map port");
+                    return None
+                }
+            }
+        }
+
+        let external_ip = match external_ip {
+            Some(ip) if is_public_ip(ip) => Ok(ip),
+            Some(ip) => Err(PortMappingError::NonPublicExternalIp(ip)),
+            None => gateway.public_external_ip().await,
+        };
+        self.gateway = Some(gateway);
+        external_ip
+            .inspect_err(|err| {
+                debug!(target: "net::nat", %err, "Failed to get external IP of gateway");
+            })
+            .ok()
+    }
+
+    /// Removes the port mappings from the gateway, if any.
+    pub async fn unmap_ports(&mut self) {
+        let Some(gateway) = &mut self.gateway else { return };
+        for mapping in &self.mappings {
+            if let Err(err) = gateway.remove_port_mapping(*mapping).await {
+                debug!(target: "net::nat", %err, %mapping, "Failed to remove port mapping");
+            }
+        }
+        self.gateway = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        natpmp::{tests::MockNatPmp, NatPmpVersion},
+        upnp::tests::MockIgd,
+    };
+
+    #[tokio::test]
+    async fn map_ports_on_igd() {
+        let igd = MockIgd::spawn(false).await;
+        let gateway = IgdGateway::discover_at(igd.ssdp_addr, DEFAULT_SSDP_TIMEOUT).await.unwrap();
+        let mut mapper = PortMapper::new(
+            NatResolver::Upnp,
+            vec![PortMapping::tcp(30303), PortMapping::udp(30303)],
+        )
+        .with_gateway(Gateway::Igd(gateway));
+
+        assert_eq!(mapper.map_ports().await, Some("203.0.113.7".parse().unwrap()));
+        assert_eq!(igd.mappings.lock().unwrap().len(), 2);
+        // renewing keeps the mappings
+        assert_eq!(mapper.map_ports().await, Some("203.0.113.7".parse().unwrap()));
+        assert_eq!(igd.mappings.lock().unwrap().len(), 2);
+
+        mapper.unmap_ports().await;
+        assert!(igd.mappings.lock().unwrap().is_empty());
+        assert!(mapper.gateway().is_none());
+    }
+
+    #[tokio::test]
+    async fn map_ports_on_pcp() {
+        let mock = MockNatPmp::spawn(NatPmpVersion::Pcp).await;
+        let gateway = NatPmpGateway::new(mock.addr).await.unwrap();
+        let mut mapper = PortMapper::new(
+            NatResolver::NatPmp(Some(mock.addr.ip())),
+            vec![PortMapping::tcp(30303), PortMapping::udp(30303), PortMapping::udp(9200)],
+        )
+        .with_gateway(Gateway::NatPmp(gateway));
+
+        assert_eq!(mapper.map_ports().await, Some("203.0.113.9".parse().unwrap()));
+        assert_eq!(mock.mappings.lock().unwrap().len(), 3);
+
+        mapper.unmap_ports().await;
+        assert!(mock.mappings.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn remove_mapping_to_different_external_port() {
+        // the mock NAT-PMP gateway maps every port to the next external port
+        let mock = MockNatPmp::spawn(NatPmpVersion::NatPmp).await;
+        let mut gateway = Gateway::NatPmp(NatPmpGateway::new(mock.addr).await.unwrap());
+
+        let res = gateway.add_port_mapping(PortMapping::tcp(30303), Duration::from_secs(60)).await;
+        assert!(matches!(
+            res,
+            Err(PortMappingError::ExternalPortMismatch { mapping, external_port: 30304 })
+                if mapping == PortMapping::tcp(30303)
+        ));
+        assert!(mock.mappings.lock().unwrap().is_empty());
+
+        // the gateway is kept to retry on the next tick
+        let mut mapper = PortMapper::new(
+            NatResolver::NatPmp(Some(mock.addr.ip())),
+            vec![PortMapping::udp(9200)],
+        )
+        .with_gateway(gateway);
+        assert_eq!(mapper.map_ports().await, None);
+        assert!(mapper.gateway().is_some());
+        assert!(mock.mappings.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn discover_without_port_mapping_resolver() {
+        let gateway = Gateway::discover(NatResolver::PublicIp).await;
+        assert!(matches!(gateway, Err(PortMappingError::NoGateway)));
+    }
+
+    #[test]
+    fn public_ip() {
+        assert!(is_public_ip("203.0.113.7".parse().unwrap()));
+        assert!(is_public_ip("2001:db8::1".parse().unwrap()));
+        assert!(!is_public_ip("192.168.1.2".parse().unwrap()));
+        assert!(!is_public_ip("10.0.0.1".parse().unwrap()));
+        assert!(!is_public_ip("100.64.0.1".parse().unwrap()));
+        assert!(!is_public_ip("100.127.255.254".parse().unwrap()));
+        assert!(is_public_ip("100.128.0.1".parse().unwrap()));
+        assert!(!is_public_ip("fd00::1".parse().unwrap()));
+        assert!(!is_public_ip("fe80::1".parse().unwrap()));
+    }
+
+    #[test]
+    fn display_port_mapping() {
+        assert_eq!(PortMapping::tcp(30303).to_string(), "30303/TCP");
+        assert_eq!(PortMapping::udp(9200).to_string(), "9200/UDP");
+    }
+}
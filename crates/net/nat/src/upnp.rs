@@ -0,0 +1,508 @@
+//! Minimal `UPnP` Internet Gateway Device client.
+//!
+//! Discovers the gateway via SSDP and manages port mappings with the `WANIPConnection` or
+//! `WANPPPConnection` SOAP services, see also
+//! <https://upnp.org/specs/gw/UPnP-gw-WANIPConnection-v2-Service.pdf>
+
+use crate::port_mapping::{local_ip_for, PortMappingError, PortMappingProtocol};
+use reqwest::Url;
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    time::Duration,
+};
+use tokio::{net::UdpSocket, time::Instant};
+use tracing::trace;
+
+/// The SSDP multicast address.
+pub const SSDP_MULTICAST_ADDR: SocketAddr =
+    SocketAddr::new(IpAddr::V4(Ipv4Addr::new(239, 255, 255, 250)), 1900);
+
+/// How long to wait for gateways to answer the SSDP search.
+pub const DEFAULT_SSDP_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// The search target of internet gateway devices.
+const IGD_SEARCH_TARGET: &str = "urn:schemas-upnp-org:device:InternetGatewayDevice:1";
+
+/// Services that can manage port mappings, in order of preference.
+const WAN_CONNECTION_SERVICES: [&str; 3] = [
+    "urn:schemas-upnp-org:service:WANIPConnection:2",
+    "urn:schemas-upnp-org:service:WANIPConnection:1",
+    "urn:schemas-upnp-org:service:WANPPPConnection:1",
+];
+
+/// Description of the port mappings created by reth.
+const PORT_MAPPING_DESCRIPTION: &str = "reth";
+
+/// The `OnlyPermanentLeasesSupported` error code of `AddPortMapping`.
+const ONLY_PERMANENT_LEASES_SUPPORTED: u16 = 725;
+
+/// An internet gateway device that was discovered via SSDP.
+#[derive(Debug, Clone)]
+pub struct IgdGateway {
+    /// The URL of the WAN connection service's control endpoint
+    control_url: Url,
+    /// The type of the WAN connection service
+    service_type: String,
+    /// The address of this host in the gateway's network
+    local_ip: IpAddr,
+    client: reqwest::Client,
+}
+
+impl IgdGateway {
+    /// Discovers the internet gateway device of the local network.
+    pub async fn discover(timeout: Duration) -> Result<Self, PortMappingError> {
+        Self::discover_at(SSDP_MULTICAST_ADDR, timeout).await
+    }
+
+    /// Sends the SSDP search to the given address, and returns the first gateway that answers and
+    /// provides a WAN connection service.
+    pub async fn discover_at(
+        ssdp_addr: SocketAddr,
+        timeout: Duration,
+    ) -> Result<Self, PortMappingError> {
+        let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).await?;
+        let search = format!(
+            "M-SEARCH * HTTP/1.1\r\n\
+             HOST: {SSDP_MULTICAST_ADDR}\r\n\
+             ST: {IGD_SEARCH_TARGET}\r\n\
+             MAN: \"ssdp:discover\"\r\n\
+             MX: {}\r\n\r\n",
+            timeout.as_secs().max(1)
+        );
+        socket.send_to(search.as_bytes(), ssdp_addr).await?;
+
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .map_err(PortMappingError::Http)?;
+        let deadline = Instant::now() + timeout;
+        let mut buf = [0; 2048];
+        loop {
+            let (len, from) = tokio::time::timeout_at(deadline, socket.recv_from(&mut buf))
+                .await
+                .map_err(|_| PortMappingError::NoGateway)??;
+            let Some(location) = ssdp_location(&buf[..len]) else { continue };
+            trace!(target: "net::nat", %from, %location, "Discovered UPnP device");
+
+            match Self::from_description(client.clone(), location).await {
+                Ok(gateway) => return Ok(gateway),
+                Err(err) => {
+                    trace!(target: "net::nat", %from, %err, "Skipping UPnP device");
+                }
+            }
+        }
+    }
+
+    /// Fetches the device description and looks up the WAN connection service.
+    async fn from_description(
+        client: reqwest::Client,
+        location: Url,
+    ) -> Result<Self, PortMappingError> {
+        let description = client
+            .get(location.clone())
+            .send()
+            .await
+            .and_then(|res| res.error_for_status())
+            .map_err(PortMappingError::Http)?
+            .text()
+            .await
+            .map_err(PortMappingError::Http)?;
+
+        let services = xml_elements(&description, "service")
+            .filter_map(|service| {
+                Some((xml_element(service, "serviceType")?, xml_element(service, "controlURL")?))
+            })
+            .collect::<Vec<_>>();
+        let (service_type, control_url) = WAN_CONNECTION_SERVICES
+            .iter()
+            .find_map(|wanted| services.iter().find(|(ty, _)| ty.trim() == *wanted))
+            .ok_or(PortMappingError::InvalidResponse("no WAN connection service"))?;
+
+        let base = match xml_element(&description, "URLBase") {
+            Some(base) if !base.trim().is_empty() => base
+                .trim()
+                .parse()
+                .map_err(|_| PortMappingError::InvalidResponse("invalid URLBase"))?,
+            _ => location,
+        };
+        let control_url = base
+            .join(control_url.trim())
+            .map_err(|_| PortMappingError::InvalidResponse("invalid controlURL"))?;
+        let host = control_url
+            .socket_addrs(|| Some(80))
+            .ok()
+            .and_then(|addrs| addrs.into_iter().next())
+            .ok_or(PortMappingError::InvalidResponse("invalid controlURL"))?;
+        let local_ip = local_ip_for(host).await?;
+
+        Ok(Self { control_url, service_type: service_type.trim().to_string(), local_ip, client })
+    }
+
+    /// Returns the address of this host in the gateway's network.
+    pub const fn local_ip(&self) -> IpAddr {
+        self.local_ip
+    }
+
+    /// Returns the external IP address of the gateway.
+    pub async fn external_ip(&self) -> Result<IpAddr, PortMappingError> {
+        let response = self.call("GetExternalIPAddress", "").await?;
+        xml_element(&response, "NewExternalIPAddress")
+            .and_then(|ip| ip.trim().parse().ok())
+            .ok_or(PortMappingError::InvalidResponse("invalid external IP address"))
+    }
+
+    /// Maps the external port of the gateway to the same port of this host.
+    ///
+    /// Falls back to a permanent mapping if the gateway doesn't support leases.
+    pub async fn add_port_mapping(
+        &self,
+        protocol: PortMappingProtocol,
+        port: u16,
+        lease: Duration,
+    ) -> Result<(), PortMappingError> {
+        match self.try_add_port_mapping(protocol, port, lease.as_secs()).await {
+            Err(PortMappingError::Upnp(ONLY_PERMANENT_LEASES_SUPPORTED)) => {
+                self.try_add_port_mapping(protocol, port, 0).await
+            }
+            res => res,
+        }
+    }
+
+    async fn try_add_port_mapping(
+        &self,
+        protocol: PortMappingProtocol,
+        port: u16,
+        lease_secs: u64,
+    ) -> Result<(), PortMappingError> {
+        let args = format!(
+            "<NewRemoteHost></NewRemoteHost>\
+             <NewExternalPort>{port}</NewExternalPort>\
+             <NewProtocol>{protocol}</NewProtocol>\
+             <NewInternalPort>{port}</NewInternalPort>\
+             <NewInternalClient>{}</NewInternalClient>\
+             <NewEnabled>1</NewEnabled>\
+             <NewPortMappingDescription>{PORT_MAPPING_DESCRIPTION}</NewPortMappingDescription>\
+             <NewLeaseDuration>{lease_secs}</NewLeaseDuration>",
+            self.local_ip
+        );
+        self.call("AddPortMapping", &args).await.map(drop)
+    }
+
+    /// Removes the mapping of the external port of the gateway.
+    pub async fn remove_port_mapping(
+        &self,
+        protocol: PortMappingProtocol,
+        port: u16,
+    ) -> Result<(), PortMappingError> {
+        let args = format!(
+            "<NewRemoteHost></NewRemoteHost>\
+             <NewExternalPort>{port}</NewExternalPort>\
+             <NewProtocol>{protocol}</NewProtocol>"
+        );
+        self.call("DeletePortMapping", &args).await.map(drop)
+    }
+
+    /// Invokes the action of the WAN connection service, and returns the response body.
+    async fn call(&self, action: &str, args: &str) -> Result<String, PortMappingError> {
+        let service_type = &self.service_type;
+        let body = format!(
+            "<?xml version=\"1.0\"?>\
+             <s:Envelope xmlns:s=\"http://schemas.xmlsoap.org/soap/envelope/\" \
+             s:encodingStyle=\"http://schemas.xmlsoap.org/soap/encoding/\">\
+             <s:Body><u:{action} xmlns:u=\"{service_type}\">{args}</u:{action}></s:Body>\
+             </s:Envelope>"
+        );
+        let response = self
+            .client
+            .post(self.control_url.clone())
+            .header("Content-Type", "text/xml; charset=\"utf-8\"")
+            .header("SOAPAction", format!("\"{service_type}#{action}\""))
+            .body(body)
+            .send()
+            .await
+            .map_err(PortMappingError::Http)?;
+        let status = response.status();
+        let text = response.text().await.map_err(PortMappingError::Http)?;
+        if !status.is_success() {
+            let code = xml_element(&text, "errorCode").and_then(|code| code.trim().parse().ok());
+            return Err(code
+                .map_or(PortMappingError::InvalidResponse("SOAP request failed"), |code| {
+                    PortMappingError::Upnp(code)
+                }))
+        }
+        Ok(text)
+    }
+}
+
+/// Returns the `LOCATION` header of an SSDP response.
+fn ssdp_location(response: &[u8]) -> Option<Url> {
+    let response = std::str::from_utf8(response).ok()?;
+    let mut lines = response.lines();
+    if !lines.next()?.starts_with("HTTP/1.1 200") {
+        return None
+    }
+    lines.find_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        name.trim().eq_ignore_ascii_case("location").then(|| value.trim().parse().ok())?
+    })
+}
+
+/// Returns the content of the first element with the given name.
+fn xml_element<'a>(xml: &'a str, name: &'a str) -> Option<&'a str> {
+    xml_elements(xml, name).next()
+}
+
+/// Returns the contents of all elements with the given name, ignoring namespace prefixes.
+fn xml_elements<'a>(xml: &'a str, name: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+    let mut rest = xml;
+    std::iter::from_fn(move || loop {
+        let start = rest.find('<')?;
+        rest = &rest[start + 1..];
+        let end = rest.find('>')?;
+        let tag = &rest[..end];
+        rest = &rest[end + 1..];
+
+        let tag_name = tag.split_whitespace().next().unwrap_or_default();
+        let local_name = tag_name.rsplit(':').next().unwrap_or_default();
+        if local_name != name || tag.starts_with('/') || tag.ends_with('/') {
+            continue
+        }
+
+        let close = format!("</{tag_name}>");
+        let content_end = rest.find(&close)?;
+        let content = &rest[..content_end];
+        rest = &rest[content_end + close.len()..];
+        return Some(content)
+    })
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+    };
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    /// The port mappings of a [`MockIgd`], by protocol and external port.
+    pub(crate) type MockMappings = Arc<Mutex<HashMap<(String, u16), (IpAddr, u64)>>>;
+
+    /// A stand-in for an internet gateway device that answers SSDP searches and SOAP requests.
+    pub(crate) struct MockIgd {
+        /// The address to send SSDP searches to.
+        pub(crate) ssdp_addr: SocketAddr,
+        /// The current port mappings.
+        pub(crate) mappings: MockMappings,
+    }
+
+    impl MockIgd {
+        /// Spawns the mock gateway, `permanent_only` gateways reject leases.
+        pub(crate) async fn spawn(permanent_only: bool) -> Self {
+            let http = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let http_addr = http.local_addr().unwrap();
+            let ssdp = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+            let ssdp_addr = ssdp.local_addr().unwrap();
+            let mappings = MockMappings::default();
+
+            tokio::spawn(async move {
+                let mut buf = [0; 2048];
+                while let Ok((len, from)) = ssdp.recv_from(&mut buf).await {
+                    let search = String::from_utf8_lossy(&buf[..len]);
+                    assert!(search.starts_with("M-SEARCH * HTTP/1.1"));
+                    assert!(search.contains(IGD_SEARCH_TARGET));
+                    let response = format!(
+                        "HTTP/1.1 200 OK\r\nST: {IGD_SEARCH_TARGET}\r\n\
+                         Location: http://{http_addr}/rootDesc.xml\r\n\r\n"
+                    );
+                    ssdp.send_to(response.as_bytes(), from).await.unwrap();
+                }
+            });
+
+            let state = mappings.clone();
+            tokio::spawn(async move {
+                while let Ok((mut stream, _)) = http.accept().await {
+                    let state = state.clone();
+                    tokio::spawn(async move {
+                        let request = read_http_request(&mut stream).await;
+                        let (status, body) = handle_request(&request, &state, permanent_only);
+                        let response = format!(
+                            "HTTP/1.1 {status}\r\ncontent-type: text/xml\r\n\
+                             content-length: {}\r\nconnection: close\r\n\r\n{body}",
+                            body.len()
+                        );
+                        stream.write_all(response.as_bytes()).await.unwrap();
+                    });
+                }
+            });
+
+            Self { ssdp_addr, mappings }
+        }
+    }
+
+    async fn read_http_request(stream: &mut tokio::net::TcpStream) -> String {
+        let mut request = Vec::new();
+        let mut buf = [0; 4096];
+        loop {
+            let len = stream.read(&mut buf).await.unwrap();
+            request.extend_from_slice(&buf[..len]);
+            let text = String::from_utf8_lossy(&request);
+            if let Some(header_end) = text.find("\r\n\r\n") {
+                let content_length = text[..header_end]
+                    .lines()
+                    .find_map(|line| {
+                        let (name, value) = line.split_once(':')?;
+                        name.eq_ignore_ascii_case("content-length").then(|| value.trim().parse())
+                    })
+                    .transpose()
+                    .unwrap()
+                    .unwrap_or(0);
+                if request.len() >= header_end + 4 + content_length {
+                    return text.into_owned()
+                }
+            }
+        }
+    }
+
+    fn handle_request(
+        request: &str,
+        mappings: &MockMappings,
+        permanent_only: bool,
+    ) -> (&'static str, String) {
+        if request.starts_with("GET /rootDesc.xml") {
+            let description = "<?xml version=\"1.0\"?>\
+                <root xmlns=\"urn:schemas-upnp-org:device-1-0\"><device>\
+                <deviceType>urn:schemas-upnp-org:device:InternetGatewayDevice:1</deviceType>\
+                <serviceList><service>\
+                <serviceType>urn:schemas-upnp-org:service:Layer3Forwarding:1</serviceType>\
+                <controlURL>/ctl/L3F</controlURL></service></serviceList>\
+                <deviceList><device><deviceList><device><serviceList><service>\
+                <serviceType>urn:schemas-upnp-org:service:WANIPConnection:1</serviceType>\
+                <controlURL>/ctl/IPConn</controlURL></service></serviceList>\
+                </device></deviceList></device></deviceList></device></root>";
+            return ("200 OK", description.to_string())
+        }
+        assert!(request.starts_with("POST /ctl/IPConn"));
+
+        let action = request
+            .lines()
+            .find_map(|line| {
+                let (name, value) = line.split_once(':')?;
+                name.eq_ignore_ascii_case("soapaction").then_some(value)
+            })
+            .and_then(|action| action.trim().trim_matches('"').split_once('#'))
+            .map(|(_, action)| action.to_string())
+            .unwrap();
+        let arg = |name| xml_element(request, name).unwrap().to_string();
+        let response = |action: &str, args: &str| {
+            format!(
+                "<?xml version=\"1.0\"?><s:Envelope xmlns:s=\"http://schemas.xmlsoap.org/soap/envelope/\">\
+                 <s:Body><u:{action}Response xmlns:u=\"urn:schemas-upnp-org:service:WANIPConnection:1\">\
+                 {args}</u:{action}Response></s:Body></s:Envelope>"
+            )
+        };
+        let error = |code: u16| {
+            format!(
+                "<?xml version=\"1.0\"?><s:Envelope xmlns:s=\"http://schemas.xmlsoap.org/soap/envelope/\">\
+                 <s:Body><s:Fault><faultcode>s:Client</faultcode><faultstring>UPnPError</faultstring>\
+                 <detail><UPnPError xmlns=\"urn:schemas-upnp-org:control-1-0\">\
+                 <errorCode>{code}</errorCode></UPnPError></detail></s:Fault></s:Body></s:Envelope>"
+            )
+        };
+
+        match action.as_str() {
+            "GetExternalIPAddress" => (
+                "200 OK",
+                response(&action, "<NewExternalIPAddress>203.0.113.7</NewExternalIPAddress>"),
+            ),
+            "AddPortMapping" => {
+                let lease: u64 = arg("NewLeaseDuration").parse().unwrap();
+                if permanent_only && lease != 0 {
+                    return ("500 Internal Server Error", error(ONLY_PERMANENT_LEASES_SUPPORTED))
+                }
+                assert_eq!(arg("NewInternalPort"), arg("NewExternalPort"));
+                mappings.lock().unwrap().insert(
+                    (arg("NewProtocol"), arg("NewExternalPort").parse().unwrap()),
+                    (arg("NewInternalClient").parse().unwrap(), lease),
+                );
+                ("200 OK", response(&action, ""))
+            }
+            "DeletePortMapping" => {
+                let key = (arg("NewProtocol"), arg("NewExternalPort").parse().unwrap());
+                if mappings.lock().unwrap().remove(&key).is_none() {
+                    return ("500 Internal Server Error", error(714))
+                }
+                ("200 OK", response(&action, ""))
+            }
+            _ => ("500 Internal Server Error", error(401)),
+        }
+    }
+
+    #[tokio::test]
+    async fn igd_port_mappings() {
+        let igd = MockIgd::spawn(false).await;
+        let gateway = IgdGateway::discover_at(igd.ssdp_addr, DEFAULT_SSDP_TIMEOUT).await.unwrap();
+        assert_eq!(gateway.service_type, WAN_CONNECTION_SERVICES[1]);
+        assert_eq!(gateway.local_ip(), IpAddr::from(Ipv4Addr::LOCALHOST));
+        assert_eq!(gateway.external_ip().await.unwrap(), "203.0.113.7".parse::<IpAddr>().unwrap());
+
+        let lease = Duration::from_secs(3600);
+        gateway.add_port_mapping(PortMappingProtocol::Tcp, 30303, lease).await.unwrap();
+        gateway.add_port_mapping(PortMappingProtocol::Udp, 30303, lease).await.unwrap();
+        assert_eq!(
+            igd.mappings.lock().unwrap().get(&("TCP".to_string(), 30303)),
+            Some(&(Ipv4Addr::LOCALHOST.into(), 3600))
+        );
+
+        gateway.remove_port_mapping(PortMappingProtocol::Tcp, 30303).await.unwrap();
+        assert_eq!(igd.mappings.lock().unwrap().len(), 1);
+        assert!(matches!(
+            gateway.remove_port_mapping(PortMappingProtocol::Tcp, 30303).await,
+            Err(PortMappingError::Upnp(714))
+        ));
+    }
+
+    #[tokio::test]
+    async fn igd_permanent_leases_only() {
+        let igd = MockIgd::spawn(true).await;
+        let gateway = IgdGateway::discover_at(igd.ssdp_addr, DEFAULT_SSDP_TIMEOUT).await.unwrap();
+        gateway
+            .add_port_mapping(PortMappingProtocol::Udp, 9200, Duration::from_secs(3600))
+            .await
+            .unwrap();
+        assert_eq!(
+            igd.mappings.lock().unwrap().get(&("UDP".to_string(), 9200)),
+            Some(&(Ipv4Addr::LOCALHOST.into(), 0))
+        );
+    }
+
+    #[tokio::test]
+    async fn igd_discovery_times_out() {
+        let silent = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let res =
+            IgdGateway::discover_at(silent.local_addr().unwrap(), Duration::from_millis(100)).await;
+        assert!(matches!(res, Err(PortMappingError::NoGateway)));
+    }
+
+    #[test]
+    fn parse_xml_elements() {
+        let xml = "<a><b>1</b><c/><u:b attr=\"x\">2</u:b><bb>3</bb></a>";
+        assert_eq!(xml_elements(xml, "b").collect::<Vec<_>>(), vec!["1", "2"]);
+        assert_eq!(xml_element(xml, "bb"), Some("3"));
+        assert_eq!(xml_element(xml, "c"), None);
+    }
+
+    #[test]
+    fn parse_ssdp_location() {
+        let response = b"HTTP/1.1 200 OK\r\nCACHE-CONTROL: max-age=120\r\n\
+            location: http://192.168.1.1:5000/rootDesc.xml\r\n\r\n";
+        assert_eq!(
+            ssdp_location(response),
+            Some("http://192.168.1.1:5000/rootDesc.xml".parse().unwrap())
+        );
+        assert_eq!(ssdp_location(b"NOTIFY * HTTP/1.1\r\nLOCATION: http://a/\r\n\r\n"), None);
+    }
+}
@@ -19,7 +19,9 @@ pub mod session;
 /// [`BackoffKind`] definition.
 mod backoff;
 
-pub use peers::reputation::{Reputation, ReputationChangeKind, ReputationChangeWeights};
+pub use peers::reputation::{
+    Reputation, ReputationChangeKind, ReputationChangeWeights, ReputationEvent,
+};
 
 pub use backoff::BackoffKind;
 pub use peers::{
@@ -30,6 +32,10 @@ pub use peers::{
         DEFAULT_REPUTATION,
     },
     state::PeerConnectionState,
-    ConnectionsConfig, Peer, PeersConfig,
+    ConnectionsConfig, Peer, PeerRequestStats, PeersConfig, PersistedBans, PersistedPeer,
 };
 pub use session::{SessionLimits, SessionsConfig};
+
+// Only needed to enable serialization of `PeerId`s.
+#[cfg(feature = "serde")]
+use alloy_primitives as _;
@@ -11,7 +11,9 @@ use reth_net_banlist::BanList;
 use reth_network_peers::{NodeRecord, TrustedPeer};
 use tracing::info;
 
-use crate::{BackoffKind, ReputationChangeWeights};
+#[cfg(feature = "serde")]
+use crate::PersistedBans;
+use crate::{BackoffKind, PersistedPeer, ReputationChangeWeights};
 
 /// Maximum number of available slots for outbound sessions.
 pub const DEFAULT_MAX_COUNT_PEERS_OUTBOUND: u32 = 100;
@@ -118,6 +120,17 @@ impl Default for ConnectionsConfig {
     }
 }
 
+/// An entry of the peers file, see [`PeersConfig::with_basic_nodes_from_file`].
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum PeersFileEntry {
+    /// Plain enode URL, written by older versions.
+    Record(NodeRecord),
+    /// A peer with its state.
+    Peer(PersistedPeer),
+}
+
 /// Config type for initiating a `PeersManager` instance.
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -147,6 +160,9 @@ pub struct PeersConfig {
     /// Basic nodes to connect to.
     #[cfg_attr(feature = "serde", serde(skip))]
     pub basic_nodes: HashSet<NodeRecord>,
+    /// Known peers and their reputation and request statistics from a previous run.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub persisted_peers: Vec<PersistedPeer>,
     /// How long to ban bad peers.
     #[cfg_attr(feature = "serde", serde(with = "humantime_serde"))]
     pub ban_duration: Duration,
@@ -182,6 +198,7 @@ impl Default for PeersConfig {
             trusted_nodes_only: false,
             trusted_nodes_resolution_interval: Duration::from_secs(60 * 60),
             basic_nodes: Default::default(),
+            persisted_peers: Default::default(),
             max_backoff_count: 5,
             incoming_ip_throttle_duration: INBOUND_IP_THROTTLE_DURATION,
         }
@@ -259,6 +276,12 @@ impl PeersConfig {
         self
     }
 
+    /// Known peers and their state from a previous run.
+    pub fn with_persisted_peers(mut self, peers: Vec<PersistedPeer>) -> Self {
+        self.persisted_peers = peers;
+        self
+    }
+
     /// Configures the max allowed backoff count.
     pub const fn with_max_backoff_count(mut self, max_backoff_count: u8) -> Self {
         self.max_backoff_count = max_backoff_count;
@@ -285,7 +308,12 @@ impl PeersConfig {
         self.connection_info.max_outbound + self.connection_info.max_inbound
     }
 
-    /// Read from file nodes available at launch. Ignored if None.
+    /// Read from file nodes available at launch, and their state from a previous run. Ignored if
+    /// None.
+    ///
+    /// The file is a list of [`PersistedPeer`] entries, or of plain enode URLs if it was written
+    /// by an older version. With the `serde` feature, the peers and IPs of the bans file next to
+    /// it are banned indefinitely, see `with_bans_from_file`.
     pub fn with_basic_nodes_from_file(
         self,
         optional_file: Option<impl AsRef<Path>>,
@@ -297,8 +325,46 @@ impl PeersConfig {
             Err(e) => Err(e)?,
         };
         info!(target: "net::peers", file = %file_path.as_ref().display(), "Loading saved peers");
-        let nodes: HashSet<NodeRecord> = serde_json::from_reader(reader)?;
-        Ok(self.with_basic_nodes(nodes))
+
+        #[cfg(feature = "serde")]
+        let peers = serde_json::from_reader::<_, Vec<PeersFileEntry>>(reader)?
+            .into_iter()
+            .map(|entry| match entry {
+                PeersFileEntry::Record(record) => record.into(),
+                PeersFileEntry::Peer(peer) => peer,
+            })
+            .collect();
+        #[cfg(not(feature = "serde"))]
+        let peers = serde_json::from_reader::<_, Vec<NodeRecord>>(reader)?
+            .into_iter()
+            .map(Into::into)
+            .collect();
+
+        let config = self.with_persisted_peers(peers);
+        #[cfg(feature = "serde")]
+        let config = config.with_bans_from_file(PersistedBans::path(file_path.as_ref()))?;
+        Ok(config)
+    }
+
+    /// Bans the peers and IPs that were banned indefinitely in a previous run, see
+    /// [`PersistedBans`]. Ignored if the file doesn't exist.
+    #[cfg(feature = "serde")]
+    pub fn with_bans_from_file(mut self, file_path: impl AsRef<Path>) -> Result<Self, io::Error> {
+        let reader = match std::fs::File::open(file_path.as_ref()) {
+            Ok(file) => io::BufReader::new(file),
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(self),
+            Err(e) => Err(e)?,
+        };
+        info!(target: "net::peers", file = %file_path.as_ref().display(), "Loading saved bans");
+
+        let bans: PersistedBans = serde_json::from_reader(reader)?;
+        for peer_id in bans.peers {
+            self.ban_list.ban_peer(peer_id);
+        }
+        for ip in bans.ips {
+            self.ban_list.ban_ip(ip);
+        }
+        Ok(self)
     }
 
     /// Returns settings for testing
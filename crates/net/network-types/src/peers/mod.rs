@@ -1,13 +1,23 @@
 pub mod addr;
 pub mod config;
 pub mod kind;
+pub mod persisted;
 pub mod reputation;
 pub mod state;
+pub mod stats;
 
 pub use config::{ConnectionsConfig, PeersConfig};
-pub use reputation::{Reputation, ReputationChange, ReputationChangeKind, ReputationChangeWeights};
+pub use persisted::{PersistedBans, PersistedPeer};
+pub use reputation::{
+    Reputation, ReputationChange, ReputationChangeKind, ReputationChangeWeights, ReputationEvent,
+};
+pub use stats::PeerRequestStats;
 
 use alloy_eip2124::ForkId;
+use std::{
+    collections::VecDeque,
+    time::{SystemTime, UNIX_EPOCH},
+};
 use tracing::debug;
 
 use crate::{
@@ -15,6 +25,9 @@ use crate::{
     DEFAULT_REPUTATION,
 };
 
+/// The number of reputation changes that are kept in the [`Peer::reputation_history`].
+pub const MAX_REPUTATION_HISTORY: usize = 32;
+
 /// Tracks info about a single peer.
 #[derive(Debug, Clone)]
 pub struct Peer {
@@ -35,6 +48,10 @@ pub struct Peer {
     /// Counts number of times the peer was backed off due to a severe
     /// [`BackoffKind`](crate::BackoffKind).
     pub severe_backoff_counter: u8,
+    /// The most recent reputation changes, oldest first.
+    pub reputation_history: VecDeque<ReputationEvent>,
+    /// Statistics of the requests that were sent to the peer.
+    pub request_stats: PeerRequestStats,
 }
 
 // === impl Peer ===
@@ -66,6 +83,8 @@ impl Peer {
             kind: Default::default(),
             backed_off: false,
             severe_backoff_counter: 0,
+            reputation_history: VecDeque::new(),
+            request_stats: Default::default(),
         }
     }
 
@@ -91,6 +110,9 @@ impl Peer {
         let previous = self.reputation;
         // we add reputation since negative reputation change decrease total reputation
         self.reputation = previous.saturating_add(reputation);
+        if reputation != 0 {
+            self.record_reputation_change(reputation, kind);
+        }
 
         debug!(target: "net::peers", reputation=%self.reputation, banned=%self.is_banned(), ?kind, "applied reputation change");
 
@@ -110,6 +132,16 @@ impl Peer {
         ReputationChangeOutcome::None
     }
 
+    /// Appends the reputation change to the [`Peer::reputation_history`], dropping the oldest
+    /// change if the history is full.
+    fn record_reputation_change(&mut self, change: i32, kind: ReputationChangeKind) {
+        if self.reputation_history.len() == MAX_REPUTATION_HISTORY {
+            self.reputation_history.pop_front();
+        }
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+        self.reputation_history.push_back(ReputationEvent { timestamp, kind, change });
+    }
+
     /// Returns true if the peer's reputation is below the banned threshold.
     #[inline]
     pub const fn is_banned(&self) -> bool {
@@ -0,0 +1,80 @@
+//! State of a peer that survives restarts.
+
+use crate::{
+    peers::{reputation::ReputationEvent, stats::PeerRequestStats},
+    Reputation, DEFAULT_REPUTATION,
+};
+use alloy_eip2124::ForkId;
+use reth_network_peers::{NodeRecord, PeerId};
+use std::{
+    net::IpAddr,
+    path::{Path, PathBuf},
+};
+
+/// A known peer as it is stored in the peers file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PersistedPeer {
+    /// Where to reach the peer.
+    #[cfg_attr(feature = "serde", serde(rename = "enode"))]
+    pub record: NodeRecord,
+    /// Reputation of the peer.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub reputation: Reputation,
+    /// The most recent reputation changes that were applied to the peer, oldest first.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    pub reputation_history: Vec<ReputationEvent>,
+    /// The [`ForkId`] that the peer announced most recently.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub fork_id: Option<ForkId>,
+    /// Statistics of the requests that were sent to the peer.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub request_stats: PeerRequestStats,
+    /// Seconds since the unix epoch until which the peer is banned, if it is banned.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub banned_until: Option<u64>,
+}
+
+impl PersistedPeer {
+    /// Returns a new entry for a peer without any recorded state.
+    pub const fn new(record: NodeRecord) -> Self {
+        Self {
+            record,
+            reputation: DEFAULT_REPUTATION,
+            reputation_history: Vec::new(),
+            fork_id: None,
+            request_stats: PeerRequestStats {
+                latency: None,
+                successful_responses: 0,
+                failed_responses: 0,
+            },
+            banned_until: None,
+        }
+    }
+}
+
+impl From<NodeRecord> for PersistedPeer {
+    fn from(record: NodeRecord) -> Self {
+        Self::new(record)
+    }
+}
+
+/// Peers and IP addresses that are banned indefinitely, as they are stored in the bans file next
+/// to the peers file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct PersistedBans {
+    /// The banned peers.
+    pub peers: Vec<PeerId>,
+    /// The banned IP addresses.
+    pub ips: Vec<IpAddr>,
+}
+
+impl PersistedBans {
+    /// Returns the path to the bans file that belongs to the given peers file, e.g.
+    /// `known-peers.banned.json` for `known-peers.json`.
+    pub fn path(peers_file: &Path) -> PathBuf {
+        peers_file.with_extension("banned.json")
+    }
+}
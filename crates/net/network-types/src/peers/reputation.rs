@@ -60,6 +60,7 @@ pub type Reputation = i32;
 
 /// Various kinds of reputation changes.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ReputationChangeKind {
     /// Received an unspecific bad message from the peer
     BadMessage,
@@ -212,6 +213,18 @@ impl From<Reputation> for ReputationChange {
     }
 }
 
+/// A reputation change that was applied to a peer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReputationEvent {
+    /// Seconds since the unix epoch at which the change was applied.
+    pub timestamp: u64,
+    /// The kind of the change.
+    pub kind: ReputationChangeKind,
+    /// The applied reputation change.
+    pub change: Reputation,
+}
+
 /// Outcomes when a reputation change is applied to a peer
 #[derive(Debug, Clone, Copy)]
 pub enum ReputationChangeOutcome {
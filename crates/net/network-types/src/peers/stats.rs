@@ -0,0 +1,97 @@
+//! Tracks how fast and how reliably a peer answers requests.
+
+use std::time::Duration;
+
+/// Once a peer answered this many requests, the response counters are halved, so that recent
+/// responses outweigh old ones.
+const MAX_TRACKED_RESPONSES: u64 = 1024;
+
+/// Statistics of the requests that were sent to a peer.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct PeerRequestStats {
+    /// Moving average of the time it took the peer to answer a request successfully.
+    #[cfg_attr(feature = "serde", serde(with = "humantime_serde"))]
+    pub latency: Option<Duration>,
+    /// Number of requests the peer answered successfully.
+    pub successful_responses: u64,
+    /// Number of requests the peer failed to answer, or answered with a bad response.
+    pub failed_responses: u64,
+}
+
+impl PeerRequestStats {
+    /// Records a successful response that took the given time.
+    pub fn on_success(&mut self, latency: Duration) {
+        self.latency = Some(match self.latency {
+            // weigh the new sample with 1/4
+            Some(average) => (average * 3 + latency) / 4,
+            None => latency,
+        });
+        self.successful_responses += 1;
+        self.decay();
+    }
+
+    /// Records a failed or bad response.
+    pub const fn on_failure(&mut self) {
+        self.failed_responses += 1;
+        self.decay();
+    }
+
+    /// Returns the number of recorded responses.
+    pub const fn total_responses(&self) -> u64 {
+        self.successful_responses + self.failed_responses
+    }
+
+    /// Returns the estimated probability that the peer answers the next request successfully.
+    ///
+    /// Peers without recorded responses have an estimated success rate of `0.5`.
+    pub fn success_rate(&self) -> f64 {
+        (self.successful_responses + 1) as f64 / (self.total_responses() + 2) as f64
+    }
+
+    /// Returns the expected time until the peer answers a request successfully, which is the
+    /// average latency weighed by the success rate.
+    ///
+    /// The `fallback` latency is used if no successful response was recorded yet.
+    pub fn expected_response_time(&self, fallback: Duration) -> Duration {
+        self.latency.unwrap_or(fallback).div_f64(self.success_rate())
+    }
+
+    const fn decay(&mut self) {
+        if self.total_responses() > MAX_TRACKED_RESPONSES {
+            self.successful_responses /= 2;
+            self.failed_responses /= 2;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_stats() {
+        let mut stats = PeerRequestStats::default();
+        assert_eq!(stats.success_rate(), 0.5);
+        assert_eq!(
+            stats.expected_response_time(Duration::from_millis(100)),
+            Duration::from_millis(200)
+        );
+
+        stats.on_success(Duration::from_millis(100));
+        stats.on_success(Duration::from_millis(500));
+        assert_eq!(stats.latency, Some(Duration::from_millis(200)));
+        assert_eq!(stats.success_rate(), 0.75);
+
+        stats.on_failure();
+        assert_eq!(stats.total_responses(), 3);
+        assert_eq!(stats.success_rate(), 0.6);
+
+        for _ in 0..MAX_TRACKED_RESPONSES {
+            stats.on_failure();
+        }
+        assert!(stats.total_responses() <= MAX_TRACKED_RESPONSES);
+        assert!(stats.success_rate() < 0.01);
+    }
+}
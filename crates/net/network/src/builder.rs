@@ -4,6 +4,8 @@ use std::fmt::Debug;
 
 use crate::{
     eth_requests::EthRequestHandler,
+    snap_client::SnapFetchClient,
+    snap_requests::{SnapProtocolHandler, SnapRequestHandler},
     transactions::{
         config::{StrictEthAnnouncementFilter, TransactionPropagationKind},
         policy::NetworkPolicies,
@@ -20,6 +22,10 @@ use tokio::sync::mpsc;
 /// 256 requests with malicious 10MB body requests is 2.6GB which can be absorbed by the node.
 pub(crate) const ETH_REQUEST_CHANNEL_CAPACITY: usize = 256;
 
+/// We set the max channel capacity of the `SnapRequestHandler` to 256, the same as for the
+/// `EthRequestHandler`.
+pub(crate) const SNAP_REQUEST_CHANNEL_CAPACITY: usize = 256;
+
 /// A builder that can configure all components of the network.
 #[expect(missing_debug_implementations)]
 pub struct NetworkBuilder<Tx, Eth, N: NetworkPrimitives = EthNetworkPrimitives> {
@@ -72,6 +78,42 @@ impl<Tx, Eth, N: NetworkPrimitives> NetworkBuilder<Tx, Eth, N> {
         NetworkBuilder { network, request_handler, transactions }
     }
 
+    /// Creates a new [`SnapRequestHandler`] and announces the `snap/1` protocol to peers.
+    ///
+    /// The returned handler serves the `snap` requests of all peers and must be spawned.
+    pub fn snap_request_handler<Client>(&mut self, client: Client) -> SnapRequestHandler<Client> {
+        let (tx, rx) = mpsc::channel(SNAP_REQUEST_CHANNEL_CAPACITY);
+        self.network.add_rlpx_sub_protocol(SnapProtocolHandler::new(tx));
+        SnapRequestHandler::new(client, rx)
+    }
+
+    /// Creates a new [`SnapRequestHandler`] and a [`SnapFetchClient`] that share the `snap/1`
+    /// connections, to both serve state to peers and request state from them.
+    ///
+    /// The returned handler serves the `snap` requests of all peers and must be spawned.
+    pub fn snap_request_handler_with_client<Client>(
+        &mut self,
+        client: Client,
+    ) -> (SnapRequestHandler<Client>, SnapFetchClient) {
+        let (tx, rx) = mpsc::channel(SNAP_REQUEST_CHANNEL_CAPACITY);
+        let protocol = SnapProtocolHandler::new(tx);
+        let snap_client = protocol.client().with_peers_handle(self.network.peers_handle());
+        self.network.add_rlpx_sub_protocol(protocol);
+        (SnapRequestHandler::new(client, rx), snap_client)
+    }
+
+    /// Announces the `snap/1` protocol to peers and returns a client to request state from them.
+    ///
+    /// The state is not served to peers. This must not be combined with
+    /// [`NetworkBuilder::snap_request_handler`], use
+    /// [`NetworkBuilder::snap_request_handler_with_client`] to both serve and request state.
+    pub fn snap_client(&mut self) -> SnapFetchClient {
+        let protocol = SnapProtocolHandler::client_only();
+        let client = protocol.client().with_peers_handle(self.network.peers_handle());
+        self.network.add_rlpx_sub_protocol(protocol);
+        client
+    }
+
     /// Creates a new [`TransactionsManager`] and wires it to the network.
     pub fn transactions<Pool: TransactionPool>(
         self,
@@ -12,6 +12,7 @@ use reth_dns_discovery::{
     DnsDiscoveryConfig, DnsDiscoveryHandle, DnsDiscoveryService, DnsNodeRecordUpdate, DnsResolver,
 };
 use reth_ethereum_forks::{EnrForkIdEntry, ForkId};
+use reth_net_nat::{NatResolver, PortMapping, ResolveNatInterval};
 use reth_network_api::{DiscoveredEvent, DiscoveryEvent};
 use reth_network_peers::{NodeRecord, PeerId};
 use reth_network_types::PeerAddr;
@@ -22,6 +23,7 @@ use std::{
     pin::Pin,
     sync::Arc,
     task::{ready, Context, Poll},
+    time::Duration,
 };
 use tokio::{sync::mpsc, task::JoinHandle};
 use tokio_stream::{wrappers::ReceiverStream, Stream};
@@ -32,6 +34,10 @@ use tracing::trace;
 /// Default is 10 000 peers.
 pub const DEFAULT_MAX_CAPACITY_DISCOVERED_PEERS_CACHE: u32 = 10_000;
 
+/// The interval at which the port mappings on the gateway are renewed, if discv4 doesn't configure
+/// one.
+const DEFAULT_PORT_MAPPING_INTERVAL: Duration = Duration::from_secs(60 * 5);
+
 /// An abstraction over the configured discovery protocol.
 ///
 /// Listens for new discovered nodes and emits events for discovered nodes and their
@@ -64,6 +70,26 @@ pub struct Discovery {
     queued_events: VecDeque<DiscoveryEvent>,
     /// List of listeners subscribed to discovery events.
     discovery_listeners: Vec<mpsc::UnboundedSender<DiscoveryEvent>>,
+    /// Maps the `RLPx` and discovery ports on the gateway of the local network and resolves the
+    /// external IP address of the gateway, if the [`NatResolver`] supports it.
+    port_mapping: Option<ResolveNatInterval>,
+}
+
+/// Returns the ports that should be reachable from outside the local network, given the `RLPx`
+/// port and the ports of the enabled discovery services.
+///
+/// Unbound ports are skipped, since they can't be mapped.
+fn port_mappings(
+    tcp_port: u16,
+    discovery_v4_port: Option<u16>,
+    discovery_v5_port: Option<u16>,
+) -> Vec<PortMapping> {
+    let mut mappings = vec![PortMapping::tcp(tcp_port)];
+    mappings.extend(discovery_v4_port.map(PortMapping::udp));
+    mappings.extend(discovery_v5_port.map(PortMapping::udp));
+    mappings.retain(|mapping| mapping.port != 0);
+    mappings.dedup();
+    mappings
 }
 
 impl Discovery {
@@ -71,14 +97,29 @@ impl Discovery {
     ///
     /// This will spawn the [`reth_discv4::Discv4Service`] onto a new task and establish a listener
     /// channel to receive all discovered nodes.
+    ///
+    /// If the [`NatResolver`] maps ports, see [`NatResolver::maps_ports`], the `RLPx` port and the
+    /// ports of the enabled discovery services are mapped on the gateway of the local network, and
+    /// the external IP address of the gateway is set in the local records of discv4 and discv5.
     pub async fn new(
         tcp_addr: SocketAddr,
         discovery_v4_addr: SocketAddr,
         sk: SecretKey,
-        discv4_config: Option<Discv4Config>,
+        mut discv4_config: Option<Discv4Config>,
         discv5_config: Option<reth_discv5::Config>, // contains discv5 listen address
         dns_discovery_config: Option<DnsDiscoveryConfig>,
+        nat: Option<NatResolver>,
     ) -> Result<Self, NetworkError> {
+        let nat = nat.filter(|nat| nat.maps_ports());
+        let mut port_mapping_interval = DEFAULT_PORT_MAPPING_INTERVAL;
+        if let Some(config) = discv4_config.as_mut().filter(|_| nat.is_some()) {
+            // the external IP is resolved together with the port mappings instead
+            port_mapping_interval =
+                config.resolve_external_ip_interval.take().unwrap_or(port_mapping_interval);
+        }
+        let discovery_v5_port =
+            discv5_config.as_ref().map(|config| config.discovery_socket().port());
+
         // setup discv4 with the discovery address and tcp port
         let local_enr =
             NodeRecord::from_secret_key(discovery_v4_addr, &sk).with_tcp_port(tcp_addr.port());
@@ -107,6 +148,13 @@ impl Discovery {
         let ((discv4, discv4_updates, _discv4_service), (discv5, discv5_updates)) =
             tokio::try_join!(discv4_future, discv5_future)?;
 
+        let port_mapping = nat.map(|nat| {
+            let discovery_v4_port = discv4.as_ref().map(|discv4| discv4.node_record().udp_port);
+            ResolveNatInterval::interval(nat, port_mapping_interval).with_port_mappings(
+                port_mappings(tcp_addr.port(), discovery_v4_port, discovery_v5_port),
+            )
+        });
+
         // setup DNS discovery
         let (_dns_discovery, dns_discovery_updates, _dns_disc_service) =
             if let Some(dns_config) = dns_discovery_config {
@@ -134,6 +182,7 @@ impl Discovery {
             _dns_disc_service,
             _dns_discovery,
             dns_discovery_updates,
+            port_mapping,
         })
     }
 
@@ -157,6 +206,16 @@ impl Discovery {
         // todo: update discv5 enr
     }
 
+    /// Sets the external IP address in the local records of discv4 and discv5.
+    fn set_external_ip(&self, ip: IpAddr) {
+        if let Some(discv4) = &self.discv4 {
+            discv4.set_external_ip_addr(ip)
+        }
+        if let Some(discv5) = &self.discv5 {
+            discv5.set_external_ip(ip)
+        }
+    }
+
     /// Bans the [`IpAddr`] in the discovery service.
     pub(crate) fn ban_ip(&self, ip: IpAddr) {
         if let Some(discv4) = &self.discv4 {
@@ -256,6 +315,15 @@ impl Discovery {
                 return Poll::Ready(event)
             }
 
+            // advertise the external IP address of the gateway once the ports are mapped
+            while let Some(Poll::Ready(ip)) =
+                self.port_mapping.as_mut().map(|interval| interval.poll_tick(cx))
+            {
+                if let Some(ip) = ip {
+                    self.set_external_ip(ip);
+                }
+            }
+
             // drain the discv4 update stream
             while let Some(Poll::Ready(Some(update))) =
                 self.discv4_updates.as_mut().map(|updates| updates.poll_next_unpin(cx))
@@ -332,6 +400,7 @@ impl Discovery {
             dns_discovery_updates: None,
             _dns_disc_service: None,
             discovery_listeners: Default::default(),
+            port_mapping: None,
         }
     }
 }
@@ -342,6 +411,28 @@ mod tests {
     use secp256k1::SECP256K1;
     use std::net::{Ipv4Addr, SocketAddrV4};
 
+    #[test]
+    fn test_port_mappings() {
+        let mappings = port_mappings(30303, Some(30303), Some(9200));
+        assert_eq!(
+            mappings,
+            vec![PortMapping::tcp(30303), PortMapping::udp(30303), PortMapping::udp(9200)]
+        );
+
+        // only the ports of the enabled discovery services are mapped
+        assert_eq!(
+            port_mappings(30303, None, Some(9200)),
+            vec![PortMapping::tcp(30303), PortMapping::udp(9200)]
+        );
+
+        // unbound and duplicate ports are skipped
+        assert_eq!(port_mappings(30303, Some(0), None), vec![PortMapping::tcp(30303)]);
+        assert_eq!(
+            port_mappings(30303, Some(30303), Some(30303)),
+            vec![PortMapping::tcp(30303), PortMapping::udp(30303)]
+        );
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn test_discovery_setup() {
         let (secret_key, _) = SECP256K1.generate_keypair(&mut rand_08::thread_rng());
@@ -353,6 +444,7 @@ mod tests {
             Default::default(),
             None,
             Default::default(),
+            None,
         )
         .await
         .unwrap();
@@ -383,6 +475,7 @@ mod tests {
             Some(discv4_config),
             Some(discv5_config),
             None,
+            None,
         )
         .await
         .expect("should build discv5 with discv4 downgrade")
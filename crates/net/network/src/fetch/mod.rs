@@ -15,7 +15,7 @@ use reth_network_p2p::{
     priority::Priority,
 };
 use reth_network_peers::PeerId;
-use reth_network_types::ReputationChangeKind;
+use reth_network_types::{PeerRequestStats, ReputationChangeKind};
 use std::{
     collections::{HashMap, VecDeque},
     ops::RangeInclusive,
@@ -24,6 +24,7 @@ use std::{
         Arc,
     },
     task::{Context, Poll},
+    time::{Duration, Instant},
 };
 use tokio::sync::{mpsc, mpsc::UnboundedSender, oneshot};
 use tokio_stream::wrappers::UnboundedReceiverStream;
@@ -75,6 +76,9 @@ impl<N: NetworkPrimitives> StateFetcher<N> {
     }
 
     /// Invoked when connected to a new peer.
+    ///
+    /// The `request_stats` are the statistics that were recorded for the peer in previous
+    /// sessions.
     pub(crate) fn new_active_peer(
         &mut self,
         peer_id: PeerId,
@@ -82,6 +86,7 @@ impl<N: NetworkPrimitives> StateFetcher<N> {
         best_number: u64,
         timeout: Arc<AtomicU64>,
         range_info: Option<BlockRangeInfo>,
+        request_stats: PeerRequestStats,
     ) {
         self.peers.insert(
             peer_id,
@@ -92,6 +97,7 @@ impl<N: NetworkPrimitives> StateFetcher<N> {
                 timeout,
                 last_response_likely_bad: false,
                 range_info,
+                request_stats,
             },
         );
     }
@@ -102,14 +108,22 @@ impl<N: NetworkPrimitives> StateFetcher<N> {
     /// Invoked when an active session was closed.
     ///
     /// This cancels also inflight request and sends an error to the receiver.
-    pub(crate) fn on_session_closed(&mut self, peer: &PeerId) {
-        self.peers.remove(peer);
+    ///
+    /// Returns the request statistics that were recorded for the peer.
+    pub(crate) fn on_session_closed(&mut self, peer: &PeerId) -> Option<PeerRequestStats> {
+        let stats = self.peers.remove(peer).map(|peer| peer.request_stats);
         if let Some(req) = self.inflight_headers_requests.remove(peer) {
             let _ = req.response.send(Err(RequestError::ConnectionDropped));
         }
         if let Some(req) = self.inflight_bodies_requests.remove(peer) {
             let _ = req.response.send(Err(RequestError::ConnectionDropped));
         }
+        stats
+    }
+
+    /// Returns the request statistics of the given active peer.
+    pub(crate) fn request_stats(&self, peer_id: &PeerId) -> Option<PeerRequestStats> {
+        self.peers.get(peer_id).map(|peer| peer.request_stats)
     }
 
     /// Updates the block information for the peer.
@@ -134,8 +148,8 @@ impl<N: NetworkPrimitives> StateFetcher<N> {
     }
 
     /// Returns the _next_ idle peer that's ready to accept a request,
-    /// prioritizing those with the lowest timeout/latency and those that recently responded with
-    /// adequate data.
+    /// prioritizing those that recently responded with adequate data and those with the lowest
+    /// expected response time, see [`PeerRequestStats::expected_response_time`].
     fn next_best_peer(&self) -> Option<PeerId> {
         let mut idle = self.peers.iter().filter(|(_, peer)| peer.state.is_idle());
 
@@ -148,8 +162,8 @@ impl<N: NetworkPrimitives> StateFetcher<N> {
                 continue
             }
 
-            // replace best peer if this peer has better rtt
-            if maybe_better.1.timeout() < best_peer.1.timeout() &&
+            // replace best peer if this peer is expected to answer faster and more reliably
+            if maybe_better.1.expected_response_time() < best_peer.1.expected_response_time() &&
                 !maybe_better.1.last_response_likely_bad
             {
                 best_peer = maybe_better;
@@ -226,7 +240,8 @@ impl<N: NetworkPrimitives> StateFetcher<N> {
 
         match req {
             DownloadRequest::GetBlockHeaders { request, response, .. } => {
-                let inflight = Request { request: request.clone(), response };
+                let inflight =
+                    Request { request: request.clone(), response, sent_at: Instant::now() };
                 self.inflight_headers_requests.insert(peer_id, inflight);
                 let HeadersRequest { start, limit, direction } = request;
                 BlockRequest::GetBlockHeaders(GetBlockHeaders {
@@ -237,7 +252,8 @@ impl<N: NetworkPrimitives> StateFetcher<N> {
                 })
             }
             DownloadRequest::GetBlockBodies { request, response, .. } => {
-                let inflight = Request { request: request.clone(), response };
+                let inflight =
+                    Request { request: request.clone(), response, sent_at: Instant::now() };
                 self.inflight_bodies_requests.insert(peer_id, inflight);
                 BlockRequest::GetBlockBodies(GetBlockBodies(request))
             }
@@ -270,6 +286,7 @@ impl<N: NetworkPrimitives> StateFetcher<N> {
 
         let is_likely_bad_response =
             resp.as_ref().is_some_and(|r| res.is_likely_bad_headers_response(&r.request));
+        let sent_at = resp.as_ref().map(|r| r.sent_at);
 
         if let Some(resp) = resp {
             // delegate the response
@@ -279,6 +296,7 @@ impl<N: NetworkPrimitives> StateFetcher<N> {
         if let Some(peer) = self.peers.get_mut(&peer_id) {
             // update the peer's response state
             peer.last_response_likely_bad = is_likely_bad_response;
+            peer.on_response(sent_at, !is_error && !is_likely_bad_response);
 
             // If the peer is still ready to accept new requests, we try to send a followup
             // request immediately.
@@ -301,12 +319,15 @@ impl<N: NetworkPrimitives> StateFetcher<N> {
     ) -> Option<BlockResponseOutcome> {
         let is_likely_bad_response = res.as_ref().map_or(true, |bodies| bodies.is_empty());
 
+        let mut sent_at = None;
         if let Some(resp) = self.inflight_bodies_requests.remove(&peer_id) {
+            sent_at = Some(resp.sent_at);
             let _ = resp.response.send(res.map(|b| (peer_id, b).into()));
         }
         if let Some(peer) = self.peers.get_mut(&peer_id) {
             // update the peer's response state
             peer.last_response_likely_bad = is_likely_bad_response;
+            peer.on_response(sent_at, !is_likely_bad_response);
 
             if peer.state.on_request_finished() && !is_likely_bad_response {
                 return self.followup_request(peer_id)
@@ -353,12 +374,33 @@ struct Peer {
     /// Tracks the range info for the peer.
     #[allow(dead_code)]
     range_info: Option<BlockRangeInfo>,
+    /// Tracks how fast and how reliably the peer answered requests, including previous sessions.
+    request_stats: PeerRequestStats,
 }
 
 impl Peer {
     fn timeout(&self) -> u64 {
         self.timeout.load(Ordering::Relaxed)
     }
+
+    /// Returns the expected time until the peer answers a request successfully.
+    ///
+    /// Falls back to the current timeout if the peer never answered a request successfully.
+    fn expected_response_time(&self) -> Duration {
+        self.request_stats.expected_response_time(Duration::from_millis(self.timeout()))
+    }
+
+    /// Records the outcome of a request that was sent at the given time.
+    ///
+    /// Responses to requests that were not tracked are ignored.
+    fn on_response(&mut self, sent_at: Option<Instant>, success: bool) {
+        let Some(sent_at) = sent_at else { return };
+        if success {
+            self.request_stats.on_success(sent_at.elapsed());
+        } else {
+            self.request_stats.on_failure();
+        }
+    }
 }
 
 /// Tracks the state of an individual peer
@@ -404,6 +446,8 @@ struct Request<Req, Resp> {
     // TODO: this can be attached to the response in error case
     request: Req,
     response: oneshot::Sender<Resp>,
+    /// When the request was sent to the peer.
+    sent_at: Instant,
 }
 
 /// Requests that can be sent to the Syncer from a [`FetchClient`]
@@ -511,8 +555,22 @@ mod tests {
         // Add a few random peers
         let peer1 = B512::random();
         let peer2 = B512::random();
-        fetcher.new_active_peer(peer1, B256::random(), 1, Arc::new(AtomicU64::new(1)), None);
-        fetcher.new_active_peer(peer2, B256::random(), 2, Arc::new(AtomicU64::new(1)), None);
+        fetcher.new_active_peer(
+            peer1,
+            B256::random(),
+            1,
+            Arc::new(AtomicU64::new(1)),
+            None,
+            Default::default(),
+        );
+        fetcher.new_active_peer(
+            peer2,
+            B256::random(),
+            2,
+            Arc::new(AtomicU64::new(1)),
+            None,
+            Default::default(),
+        );
 
         let first_peer = fetcher.next_best_peer().unwrap();
         assert!(first_peer == peer1 || first_peer == peer2);
@@ -539,9 +597,24 @@ mod tests {
 
         let peer2_timeout = Arc::new(AtomicU64::new(300));
 
-        fetcher.new_active_peer(peer1, B256::random(), 1, Arc::new(AtomicU64::new(30)), None);
-        fetcher.new_active_peer(peer2, B256::random(), 2, Arc::clone(&peer2_timeout), None);
-        fetcher.new_active_peer(peer3, B256::random(), 3, Arc::new(AtomicU64::new(50)), None);
+        let stats = PeerRequestStats::default();
+        fetcher.new_active_peer(
+            peer1,
+            B256::random(),
+            1,
+            Arc::new(AtomicU64::new(30)),
+            None,
+            stats,
+        );
+        fetcher.new_active_peer(peer2, B256::random(), 2, Arc::clone(&peer2_timeout), None, stats);
+        fetcher.new_active_peer(
+            peer3,
+            B256::random(),
+            3,
+            Arc::new(AtomicU64::new(50)),
+            None,
+            stats,
+        );
 
         // Must always get peer1 (lowest timeout)
         assert_eq!(fetcher.next_best_peer(), Some(peer1));
@@ -553,6 +626,47 @@ mod tests {
         assert_eq!(fetcher.next_best_peer(), Some(peer2));
     }
 
+    #[tokio::test]
+    async fn test_peer_prioritization_request_stats() {
+        let manager = PeersManager::new(PeersConfig::default());
+        let mut fetcher =
+            StateFetcher::<EthNetworkPrimitives>::new(manager.handle(), Default::default());
+        let fast = B512::random();
+        let unreliable = B512::random();
+        let slow = B512::random();
+
+        let mut fast_stats = PeerRequestStats::default();
+        let mut unreliable_stats = PeerRequestStats::default();
+        let mut slow_stats = PeerRequestStats::default();
+        for _ in 0..10 {
+            fast_stats.on_success(Duration::from_millis(100));
+            unreliable_stats.on_success(Duration::from_millis(50));
+            unreliable_stats.on_failure();
+            unreliable_stats.on_failure();
+            slow_stats.on_success(Duration::from_millis(500));
+        }
+
+        // all peers share the same timeout, so only the recorded stats decide
+        let timeout = Arc::new(AtomicU64::new(1000));
+        fetcher.new_active_peer(fast, B256::random(), 1, Arc::clone(&timeout), None, fast_stats);
+        fetcher.new_active_peer(
+            unreliable,
+            B256::random(),
+            1,
+            Arc::clone(&timeout),
+            None,
+            unreliable_stats,
+        );
+        fetcher.new_active_peer(slow, B256::random(), 1, Arc::clone(&timeout), None, slow_stats);
+
+        assert_eq!(fetcher.next_best_peer(), Some(fast));
+
+        // stats are handed back when the session is closed, so they survive reconnects
+        assert_eq!(fetcher.on_session_closed(&fast), Some(fast_stats));
+        assert_eq!(fetcher.next_best_peer(), Some(unreliable));
+        assert_eq!(fetcher.request_stats(&unreliable), Some(unreliable_stats));
+    }
+
     #[tokio::test]
     async fn test_on_block_headers_response() {
         let manager = PeersManager::new(PeersConfig::default());
@@ -600,6 +714,7 @@ mod tests {
                     direction: Default::default(),
                 },
                 response: tx,
+                sent_at: Instant::now(),
             };
             let header = Header { number: 0, ..Default::default() };
             (req, header)
@@ -611,6 +726,7 @@ mod tests {
             Default::default(),
             Default::default(),
             None,
+            Default::default(),
         );
 
         let (req, header) = request_pair();
@@ -619,6 +735,7 @@ mod tests {
         let outcome = fetcher.on_block_headers_response(peer_id, Ok(vec![header]));
         assert!(outcome.is_none());
         assert!(fetcher.peers[&peer_id].state.is_idle());
+        assert_eq!(fetcher.peers[&peer_id].request_stats.successful_responses, 1);
 
         let outcome =
             fetcher.on_block_headers_response(peer_id, Err(RequestError::Timeout)).unwrap();
@@ -129,6 +129,8 @@ pub mod import;
 pub mod message;
 pub mod peers;
 pub mod protocol;
+pub mod snap_client;
+pub mod snap_requests;
 pub mod transactions;
 
 mod budget;
@@ -47,7 +47,7 @@ use reth_network_api::{
     EthProtocolInfo, NetworkEvent, NetworkStatus, PeerInfo, PeerRequest,
 };
 use reth_network_peers::{NodeRecord, PeerId};
-use reth_network_types::ReputationChangeKind;
+use reth_network_types::{PersistedBans, PersistedPeer, ReputationChangeKind};
 use reth_storage_api::BlockNumReader;
 use reth_tasks::shutdown::GracefulShutdown;
 use reth_tokio_util::EventSender;
@@ -286,6 +286,7 @@ impl<N: NetworkPrimitives> NetworkManager<N> {
             discovery_v4_config,
             discovery_v5_config,
             dns_discovery_config,
+            nat,
         )
         .await?;
         // need to retrieve the addr here since provided port could be `0`
@@ -424,12 +425,36 @@ impl<N: NetworkPrimitives> NetworkManager<N> {
         self.swarm.state().peers().handle()
     }
 
+    /// Returns the state of all peers in the peer set that should survive a restart, see
+    /// [`PersistedPeer`].
+    pub fn persisted_peers(&self) -> Vec<PersistedPeer> {
+        self.swarm.state().persisted_peers().collect()
+    }
+
+    /// Returns the peers and IPs that are banned indefinitely, see [`PersistedBans`].
+    pub fn persisted_bans(&self) -> PersistedBans {
+        self.swarm.state().peers().persisted_bans()
+    }
+
     /// Collect the peers from the [`NetworkManager`] and write them to the given
     /// `persistent_peers_file`.
+    ///
+    /// With the `serde` feature the peers are written together with their reputation, request
+    /// statistics and last seen fork id, and the indefinitely banned peers and IPs are written to
+    /// the [`PersistedBans::path`] next to it. Both are restored by
+    /// [`PeersConfig::with_basic_nodes_from_file`](reth_network_types::PeersConfig::with_basic_nodes_from_file).
     pub fn write_peers_to_file(&self, persistent_peers_file: &Path) -> Result<(), FsPathError> {
+        #[cfg(feature = "serde")]
+        let known_peers = self.persisted_peers();
+        #[cfg(not(feature = "serde"))]
         let known_peers = self.all_peers().collect::<Vec<_>>();
         persistent_peers_file.parent().map(fs::create_dir_all).transpose()?;
         reth_fs_util::write_json_file(persistent_peers_file, &known_peers)?;
+        #[cfg(feature = "serde")]
+        reth_fs_util::write_json_file(
+            &PersistedBans::path(persistent_peers_file),
+            &self.persisted_bans(),
+        )?;
         Ok(())
     }
 
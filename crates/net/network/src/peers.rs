@@ -18,9 +18,11 @@ use reth_network_types::{
     peers::{
         config::PeerBackoffDurations,
         reputation::{DEFAULT_REPUTATION, MAX_TRUSTED_PEER_REPUTATION_CHANGE},
+        MAX_REPUTATION_HISTORY,
     },
-    ConnectionsConfig, Peer, PeerAddr, PeerConnectionState, PeerKind, PeersConfig,
-    ReputationChangeKind, ReputationChangeOutcome, ReputationChangeWeights,
+    ConnectionsConfig, Peer, PeerAddr, PeerConnectionState, PeerKind, PeerRequestStats,
+    PeersConfig, PersistedBans, PersistedPeer, ReputationChangeKind, ReputationChangeOutcome,
+    ReputationChangeWeights,
 };
 use std::{
     collections::{hash_map::Entry, HashMap, HashSet, VecDeque},
@@ -28,7 +30,7 @@ use std::{
     io::{self},
     net::{IpAddr, SocketAddr},
     task::{Context, Poll},
-    time::Duration,
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 use thiserror::Error;
 use tokio::{
@@ -99,13 +101,14 @@ impl PeersManager {
             refill_slots_interval,
             connection_info,
             reputation_weights,
-            ban_list,
+            mut ban_list,
             ban_duration,
             backoff_durations,
             trusted_nodes,
             trusted_nodes_only,
             trusted_nodes_resolution_interval,
             basic_nodes,
+            persisted_peers,
             max_backoff_count,
             incoming_ip_throttle_duration,
         } = config;
@@ -115,7 +118,8 @@ impl PeersManager {
         // We use half of the interval to decrease the max duration to `150%` in worst case
         let unban_interval = ban_duration.min(backoff_durations.low) / 2;
 
-        let mut peers = HashMap::with_capacity(trusted_nodes.len() + basic_nodes.len());
+        let mut peers =
+            HashMap::with_capacity(trusted_nodes.len() + basic_nodes.len() + persisted_peers.len());
         let mut trusted_peer_ids = HashSet::with_capacity(trusted_nodes.len());
 
         for trusted_peer in &trusted_nodes {
@@ -138,6 +142,33 @@ impl PeersManager {
             });
         }
 
+        let unix_now = unix_timestamp();
+        for persisted in persisted_peers {
+            let NodeRecord { address, tcp_port, udp_port, id } = persisted.record;
+            let peer = peers.entry(id).or_insert_with(|| {
+                Peer::new(PeerAddr::new_with_ports(address, tcp_port, Some(udp_port)))
+            });
+            peer.reputation = persisted.reputation;
+            let skip = persisted.reputation_history.len().saturating_sub(MAX_REPUTATION_HISTORY);
+            peer.reputation_history = persisted.reputation_history.into_iter().skip(skip).collect();
+            peer.request_stats = persisted.request_stats;
+            peer.fork_id = peer.fork_id.or(persisted.fork_id);
+
+            match persisted.banned_until.filter(|until| *until > unix_now) {
+                Some(until) => {
+                    // the ban is still active, keep the peer out of circulation for the
+                    // remaining time
+                    let remaining = Duration::from_secs(until - unix_now);
+                    ban_list.ban_peer_until(id, std::time::Instant::now() + remaining);
+                }
+                None if peer.is_banned() => {
+                    // the ban expired while the node was offline
+                    peer.reputation = DEFAULT_REPUTATION;
+                }
+                None => {}
+            }
+        }
+
         Self {
             peers,
             trusted_peer_ids,
@@ -187,6 +218,51 @@ impl PeersManager {
         })
     }
 
+    /// Returns an iterator over the state of all peers that should survive a restart.
+    pub(crate) fn persisted_peers(&self) -> impl Iterator<Item = PersistedPeer> + '_ {
+        let now = std::time::Instant::now();
+        let unix_now = unix_timestamp();
+        self.iter_peers().map(move |record| {
+            let peer = &self.peers[&record.id];
+            let banned_until = self
+                .ban_list
+                .peer_banned_until(&record.id)
+                // round up, so that short bans are not lifted
+                .map(|until| {
+                    unix_now + until.saturating_duration_since(now).as_secs_f64().ceil() as u64
+                });
+            PersistedPeer {
+                record,
+                reputation: peer.reputation,
+                reputation_history: peer.reputation_history.iter().copied().collect(),
+                fork_id: peer.fork_id,
+                request_stats: peer.request_stats,
+                banned_until,
+            }
+        })
+    }
+
+    /// Returns the peers and IPs that are banned indefinitely, which aren't covered by
+    /// [`Self::persisted_peers`].
+    pub(crate) fn persisted_bans(&self) -> PersistedBans {
+        PersistedBans {
+            peers: self.ban_list.permanently_banned_peers().collect(),
+            ips: self.ban_list.permanently_banned_ips().collect(),
+        }
+    }
+
+    /// Returns the request statistics of the given peer.
+    pub(crate) fn peer_request_stats(&self, peer_id: &PeerId) -> Option<PeerRequestStats> {
+        self.peers.get(peer_id).map(|peer| peer.request_stats)
+    }
+
+    /// Stores the request statistics of the given peer, e.g. when its session was closed.
+    pub(crate) fn set_peer_request_stats(&mut self, peer_id: &PeerId, stats: PeerRequestStats) {
+        if let Some(peer) = self.peers.get_mut(peer_id) {
+            peer.request_stats = stats;
+        }
+    }
+
     /// Returns the `NodeRecord` and `PeerKind` for the given peer id
     pub(crate) fn peer_by_id(&self, peer_id: PeerId) -> Option<(NodeRecord, PeerKind)> {
         self.peers.get(&peer_id).map(|v| {
@@ -704,10 +780,11 @@ impl PeersManager {
         }
     }
 
-    /// Called as follow-up for a discovered peer.
+    /// Called as follow-up for a discovered peer, and when a session with the peer was
+    /// established.
     ///
     /// The [`ForkId`] is retrieved from an ENR record that the peer announces over the discovery
-    /// protocol
+    /// protocol, or from the peer's `Status` message.
     pub(crate) fn set_discovered_fork_id(&mut self, peer_id: PeerId, fork_id: ForkId) {
         if let Some(peer) = self.peers.get_mut(&peer_id) {
             trace!(target: "net::peers", ?peer_id, ?fork_id, "set discovered fork id");
@@ -1202,6 +1279,11 @@ impl Display for InboundConnectionError {
     }
 }
 
+/// Returns the current unix timestamp in seconds.
+fn unix_timestamp() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
+
 #[cfg(test)]
 mod tests {
     use alloy_primitives::B512;
@@ -1213,7 +1295,8 @@ mod tests {
     use reth_network_api::Direction;
     use reth_network_peers::{PeerId, TrustedPeer};
     use reth_network_types::{
-        peers::reputation::DEFAULT_REPUTATION, BackoffKind, Peer, ReputationChangeKind,
+        peers::reputation::DEFAULT_REPUTATION, BackoffKind, Peer, PeerRequestStats, PersistedBans,
+        ReputationChangeKind,
     };
     use std::{
         future::{poll_fn, Future},
@@ -2895,4 +2978,86 @@ mod tests {
         let updated_peer = manager.peers.get(&peer_id).unwrap();
         assert_eq!(updated_peer.addr.tcp().ip(), updated_ip);
     }
+
+    #[tokio::test]
+    async fn test_restore_persisted_peers() {
+        let banned = PeerId::random();
+        let good = PeerId::random();
+        let socket_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)), 8008);
+        let config = PeersConfig::test().with_ban_duration(Duration::from_secs(60));
+        let mut peers = PeersManager::new(config);
+        peers.add_peer(banned, PeerAddr::from_tcp(socket_addr), None);
+        peers.add_peer(good, PeerAddr::from_tcp(socket_addr), None);
+
+        peers.apply_reputation_change(&banned, ReputationChangeKind::BadProtocol);
+        assert!(peers.ban_list.is_banned_peer(&banned));
+
+        let mut stats = PeerRequestStats::default();
+        stats.on_success(Duration::from_millis(100));
+        peers.set_peer_request_stats(&good, stats);
+
+        let persisted = peers.persisted_peers().collect::<Vec<_>>();
+        let banned_entry = persisted.iter().find(|peer| peer.record.id == banned).unwrap();
+        assert!(banned_entry.banned_until.is_some());
+        assert_eq!(banned_entry.reputation_history.len(), 1);
+        assert_eq!(banned_entry.reputation_history[0].kind, ReputationChangeKind::BadProtocol);
+
+        // the ban and the recorded state survive a restart
+        let restored =
+            PeersManager::new(PeersConfig::test().with_persisted_peers(persisted.clone()));
+        assert!(restored.ban_list.is_banned_peer(&banned));
+        let restored_banned = restored.peers.get(&banned).unwrap();
+        assert!(restored_banned.is_banned());
+        assert_eq!(restored_banned.reputation_history.len(), 1);
+        assert_eq!(restored.peer_request_stats(&good), Some(stats));
+        assert!(!restored.ban_list.is_banned_peer(&good));
+
+        // an expired ban is lifted
+        let expired = persisted
+            .into_iter()
+            .map(|mut peer| {
+                peer.banned_until = peer.banned_until.map(|_| 1);
+                peer
+            })
+            .collect();
+        let restored = PeersManager::new(PeersConfig::test().with_persisted_peers(expired));
+        assert!(!restored.ban_list.is_banned_peer(&banned));
+        assert_eq!(restored.peers.get(&banned).unwrap().reputation, DEFAULT_REPUTATION);
+    }
+
+    #[tokio::test]
+    async fn test_persisted_bans() {
+        let banned = PeerId::random();
+        let banned_ip = IpAddr::V4(Ipv4Addr::new(1, 1, 1, 1));
+        let config = PeersConfig::test().with_ban_list(BanList::new([banned], [banned_ip]));
+        let mut peers = PeersManager::new(config);
+
+        // temporary bans are persisted with the peer instead
+        let socket_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)), 8008);
+        let temporarily_banned = PeerId::random();
+        peers.add_peer(temporarily_banned, PeerAddr::from_tcp(socket_addr), None);
+        peers.apply_reputation_change(&temporarily_banned, ReputationChangeKind::BadProtocol);
+        assert!(peers.ban_list.is_banned_peer(&temporarily_banned));
+
+        let bans = peers.persisted_bans();
+        assert_eq!(bans, PersistedBans { peers: vec![banned], ips: vec![banned_ip] });
+
+        // the bans are restored from the file next to the peers file
+        #[cfg(feature = "serde")]
+        {
+            let dir = tempfile::tempdir().unwrap();
+            let peers_file = dir.path().join("known-peers.json");
+            reth_fs_util::write_json_file(
+                &peers_file,
+                &peers.persisted_peers().collect::<Vec<_>>(),
+            )
+            .unwrap();
+            reth_fs_util::write_json_file(&PersistedBans::path(&peers_file), &bans).unwrap();
+
+            let config = PeersConfig::test().with_basic_nodes_from_file(Some(&peers_file)).unwrap();
+            let restored = PeersManager::new(config);
+            assert!(restored.ban_list.is_banned_peer(&banned));
+            assert!(restored.ban_list.is_banned_ip(&banned_ip));
+        }
+    }
 }
@@ -0,0 +1,201 @@
+//! State snapshot requests over the `snap/1` protocol.
+//!
+//! The [`SnapFetchClient`] sends requests to the peers that have an active `snap/1` connection,
+//! see [`SnapProtocolHandler`](crate::snap_requests::SnapProtocolHandler).
+
+use parking_lot::Mutex;
+use reth_eth_wire_types::snap::{
+    GetAccountRangeMessage, GetByteCodesMessage, GetStorageRangesMessage, GetTrieNodesMessage,
+    SnapProtocolMessage,
+};
+use reth_network_api::test_utils::PeersHandle;
+use reth_network_p2p::{
+    download::DownloadClient,
+    error::{PeerRequestResult, RequestError},
+    priority::Priority,
+    snap::client::{SnapClient, SnapResponse},
+};
+use reth_network_peers::{PeerId, WithPeerId};
+use reth_network_types::ReputationChangeKind;
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use tokio::sync::{mpsc, oneshot, Notify};
+
+/// Time to wait for the response to a `snap` request.
+pub const SNAP_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A request of the local node that is sent over a peer's `snap/1` connection.
+#[derive(Debug)]
+pub(crate) struct SnapPeerRequest {
+    /// The ID of the request, used to match the response.
+    pub(crate) request_id: u64,
+    /// The request message.
+    pub(crate) request: SnapProtocolMessage,
+    /// The channel sender for the response.
+    pub(crate) response: oneshot::Sender<SnapResponse>,
+}
+
+/// The peers with an active `snap/1` connection.
+#[derive(Debug, Default)]
+pub(crate) struct SnapPeers {
+    /// The peers and the channels to their connections.
+    peers: Mutex<Vec<(PeerId, mpsc::UnboundedSender<SnapPeerRequest>)>>,
+    /// Notifies the pending requests that a peer connected.
+    peer_added: Notify,
+}
+
+impl SnapPeers {
+    /// Registers a new connection of the peer.
+    pub(crate) fn add(&self, peer_id: PeerId, requests: mpsc::UnboundedSender<SnapPeerRequest>) {
+        self.peers.lock().push((peer_id, requests));
+        self.peer_added.notify_waiters();
+    }
+
+    /// Removes the connection with the given channel.
+    pub(crate) fn remove(&self, requests: &mpsc::UnboundedSender<SnapPeerRequest>) {
+        self.peers.lock().retain(|(_, peer_requests)| !peer_requests.same_channel(requests));
+    }
+
+    fn len(&self) -> usize {
+        self.peers.lock().len()
+    }
+
+    /// Returns the peer at the given index, wrapping around the number of peers.
+    fn get(&self, index: usize) -> Option<(PeerId, mpsc::UnboundedSender<SnapPeerRequest>)> {
+        let peers = self.peers.lock();
+        (!peers.is_empty()).then(|| peers[index % peers.len()].clone())
+    }
+}
+
+/// The future returned by the [`SnapClient`] implementation of [`SnapFetchClient`].
+pub type SnapResponseFut =
+    Pin<Box<dyn Future<Output = PeerRequestResult<SnapResponse>> + Send + Sync>>;
+
+/// Front-end API for requesting state snapshots from the network.
+///
+/// Requests are distributed over all peers with a `snap/1` connection in a round-robin fashion.
+/// If there are no such peers, the requests wait until one connects. The request IDs of the
+/// messages are replaced with IDs that are unique for this client, and the priority of the
+/// requests is ignored.
+#[derive(Debug, Clone)]
+pub struct SnapFetchClient {
+    /// The peers with a `snap/1` connection.
+    peers: Arc<SnapPeers>,
+    /// The handle to the peers, used to penalize them.
+    peers_handle: Option<PeersHandle>,
+    /// The ID of the next request.
+    next_request_id: Arc<AtomicU64>,
+    /// The index of the peer for the next request.
+    next_peer: Arc<AtomicUsize>,
+}
+
+impl SnapFetchClient {
+    /// Creates a new client for the given peers.
+    pub(crate) fn new(peers: Arc<SnapPeers>) -> Self {
+        Self {
+            peers,
+            peers_handle: None,
+            next_request_id: Default::default(),
+            next_peer: Default::default(),
+        }
+    }
+
+    /// Sets the handle to the peers, so that peers that send bad responses are penalized.
+    pub fn with_peers_handle(mut self, peers_handle: PeersHandle) -> Self {
+        self.peers_handle = Some(peers_handle);
+        self
+    }
+
+    /// Sends the request built with a new request ID to the next peer.
+    fn send_request(&self, request: impl FnOnce(u64) -> SnapProtocolMessage) -> SnapResponseFut {
+        let request_id = self.next_request_id.fetch_add(1, Ordering::Relaxed);
+        let request = request(request_id);
+        let peers = self.peers.clone();
+        let next_peer = self.next_peer.clone();
+
+        Box::pin(async move {
+            let (peer_id, to_peer) = loop {
+                // register for the notification before checking, so that no peer is missed
+                let peer_added = peers.peer_added.notified();
+                if let Some(peer) = peers.get(next_peer.fetch_add(1, Ordering::Relaxed)) {
+                    break peer
+                }
+                peer_added.await;
+            };
+
+            let (response, rx) = oneshot::channel();
+            to_peer
+                .send(SnapPeerRequest { request_id, request, response })
+                .map_err(|_| RequestError::ConnectionDropped)?;
+            let response = tokio::time::timeout(SNAP_REQUEST_TIMEOUT, rx)
+                .await
+                .map_err(|_| RequestError::Timeout)?
+                .map_err(|_| RequestError::ConnectionDropped)?;
+
+            Ok(WithPeerId::new(peer_id, response))
+        })
+    }
+}
+
+impl DownloadClient for SnapFetchClient {
+    fn report_bad_message(&self, peer_id: PeerId) {
+        if let Some(peers_handle) = &self.peers_handle {
+            peers_handle.reputation_change(peer_id, ReputationChangeKind::BadMessage);
+        }
+    }
+
+    fn num_connected_peers(&self) -> usize {
+        self.peers.len()
+    }
+}
+
+impl SnapClient for SnapFetchClient {
+    type Output = SnapResponseFut;
+
+    fn get_account_range_with_priority(
+        &self,
+        request: GetAccountRangeMessage,
+        _priority: Priority,
+    ) -> Self::Output {
+        self.send_request(|request_id| {
+            SnapProtocolMessage::GetAccountRange(GetAccountRangeMessage { request_id, ..request })
+        })
+    }
+
+    fn get_storage_ranges_with_priority(
+        &self,
+        request: GetStorageRangesMessage,
+        _priority: Priority,
+    ) -> Self::Output {
+        self.send_request(|request_id| {
+            SnapProtocolMessage::GetStorageRanges(GetStorageRangesMessage { request_id, ..request })
+        })
+    }
+
+    fn get_byte_codes_with_priority(
+        &self,
+        request: GetByteCodesMessage,
+        _priority: Priority,
+    ) -> Self::Output {
+        self.send_request(|request_id| {
+            SnapProtocolMessage::GetByteCodes(GetByteCodesMessage { request_id, ..request })
+        })
+    }
+
+    fn get_trie_nodes_with_priority(
+        &self,
+        request: GetTrieNodesMessage,
+        _priority: Priority,
+    ) -> Self::Output {
+        self.send_request(|request_id| {
+            SnapProtocolMessage::GetTrieNodes(GetTrieNodesMessage { request_id, ..request })
+        })
+    }
+}
@@ -0,0 +1,1077 @@
+//! State snapshot serving for the `snap/1` protocol.
+//!
+//! The `snap` protocol is an `RLPx` satellite protocol that runs next to `eth`. The
+//! [`SnapProtocolHandler`] announces `snap/1` to peers and forwards their requests to the
+//! [`SnapRequestHandler`], which answers them from the hashed state tables and attaches range
+//! proofs generated by the trie. The same connections carry the requests of the local node, see
+//! [`SnapFetchClient`].
+//!
+//! See also <https://github.com/ethereum/devp2p/blob/master/caps/snap.md>
+
+use crate::{
+    budget::DEFAULT_BUDGET_TRY_DRAIN_DOWNLOADERS,
+    eth_requests::SOFT_RESPONSE_LIMIT,
+    metered_poll_nested_stream_with_budget,
+    metrics::SnapRequestHandlerMetrics,
+    protocol::{ConnectionHandler, OnNotSupported, ProtocolHandler},
+    snap_client::{SnapFetchClient, SnapPeerRequest, SnapPeers},
+};
+use alloy_consensus::{
+    constants::{EMPTY_ROOT_HASH, KECCAK_EMPTY},
+    BlockHeader,
+};
+use alloy_primitives::{bytes::BytesMut, BlockNumber, Bytes, B256, U256};
+use alloy_rlp::{RlpDecodable, RlpEncodable};
+use futures::{
+    future::{self, BoxFuture},
+    stream::FuturesUnordered,
+    FutureExt, Stream, StreamExt,
+};
+use reth_eth_wire::{
+    capability::SharedCapabilities, multiplex::ProtocolConnection, protocol::Protocol,
+};
+use reth_eth_wire_types::snap::{
+    AccountData, AccountRangeMessage, ByteCodesMessage, GetAccountRangeMessage,
+    GetByteCodesMessage, GetStorageRangesMessage, GetTrieNodesMessage, SnapProtocolMessage,
+    StorageData, StorageRangesMessage, TrieNodesMessage,
+};
+use reth_network_api::{Direction, PeerId};
+use reth_network_p2p::snap::client::SnapResponse;
+use reth_primitives_traits::Account;
+use reth_storage_api::{
+    errors::provider::ProviderResult, HashedStateRangeReader, HeaderProvider, StateProviderFactory,
+};
+use reth_trie_common::{MultiProofTargets, Nibbles, TrieInput};
+use std::{
+    collections::{BTreeMap, HashMap},
+    future::Future,
+    net::SocketAddr,
+    pin::Pin,
+    sync::Arc,
+    task::{ready, Context, Poll},
+    time::Duration,
+};
+use tokio::sync::{
+    mpsc::{self, Receiver, UnboundedReceiver, UnboundedSender},
+    oneshot,
+};
+use tokio_stream::wrappers::ReceiverStream;
+use tracing::{debug, trace};
+
+// Limits: <https://github.com/ethereum/go-ethereum/blob/v1.15.11/eth/protocols/snap/handler.go#L37-L63>
+
+/// Number of most recent blocks whose state is served.
+///
+/// Requests for any other state root are answered with an empty response.
+pub const SNAP_SERVE_STATE_HISTORY: u64 = 128;
+
+/// Maximum number of bytecodes to serve.
+///
+/// Used to limit lookups.
+pub const MAX_CODE_LOOKUPS: usize = 1024;
+
+/// Maximum number of trie nodes to serve.
+///
+/// Used to limit lookups.
+pub const MAX_TRIE_NODE_LOOKUPS: usize = 1024;
+
+/// Maximum number of requests of a single peer that can be in flight at the same time.
+///
+/// Requests that exceed this limit are dropped.
+pub const MAX_PENDING_SNAP_REQUESTS_PER_PEER: usize = 32;
+
+/// Number of hashed accounts or storage slots that are read from the database at once.
+const STATE_RANGE_BATCH_SIZE: usize = 1024;
+
+/// Manages `snap` related requests on top of the p2p network.
+///
+/// This can be spawned to another task and is supposed to be run as background service.
+#[derive(Debug)]
+#[must_use = "Manager does nothing unless polled."]
+pub struct SnapRequestHandler<C> {
+    /// The client type that can interact with the chain.
+    client: C,
+    /// Incoming requests from the peers' `snap` connections.
+    incoming_requests: ReceiverStream<IncomingSnapRequest>,
+    /// The most recently served state root and the block it belongs to.
+    recent_state: Option<(B256, BlockNumber)>,
+    /// Metrics for the snap request handler.
+    metrics: SnapRequestHandlerMetrics,
+}
+
+// === impl SnapRequestHandler ===
+
+impl<C> SnapRequestHandler<C> {
+    /// Create a new instance
+    pub fn new(client: C, incoming: Receiver<IncomingSnapRequest>) -> Self {
+        Self {
+            client,
+            incoming_requests: ReceiverStream::new(incoming),
+            recent_state: None,
+            metrics: Default::default(),
+        }
+    }
+}
+
+impl<C> SnapRequestHandler<C>
+where
+    C: HeaderProvider + StateProviderFactory + HashedStateRangeReader,
+{
+    /// Returns the number of the recent canonical block with the given state root.
+    fn state_block(&mut self, root: B256) -> ProviderResult<Option<BlockNumber>> {
+        let best = self.client.best_block_number()?;
+        let oldest = best.saturating_sub(SNAP_SERVE_STATE_HISTORY - 1);
+
+        if let Some((recent_root, number)) = self.recent_state {
+            if recent_root == root &&
+                number >= oldest &&
+                self.client.header_by_number(number)?.is_some_and(|h| h.state_root() == root)
+            {
+                return Ok(Some(number))
+            }
+        }
+
+        for number in (oldest..=best).rev() {
+            if self.client.header_by_number(number)?.is_some_and(|h| h.state_root() == root) {
+                self.recent_state = Some((root, number));
+                return Ok(Some(number))
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Returns the accounts of the requested range and the proofs for its boundaries.
+    fn get_account_range_response(
+        &mut self,
+        request: GetAccountRangeMessage,
+    ) -> ProviderResult<AccountRangeMessage> {
+        let GetAccountRangeMessage { request_id, root_hash, starting_hash, limit_hash, .. } =
+            request;
+        let mut response =
+            AccountRangeMessage { request_id, accounts: Vec::new(), proof: Vec::new() };
+
+        let Some(block) = self.state_block(root_hash)? else { return Ok(response) };
+        let response_bytes = response_limit(request.response_bytes);
+
+        // the slim account body includes the storage root, which is not part of the hashed state
+        let mut bodies = Vec::new();
+        let mut total_bytes = 0;
+        let (accounts, proof) =
+            self.client.hashed_accounts_range_proof(block, starting_hash, &mut |dump| {
+                let body = SlimAccount::new(&dump.account, dump.storage_root).rlp();
+                total_bytes += B256::len_bytes() + body.len();
+                bodies.push(body);
+                dump.hashed_address >= limit_hash || total_bytes > response_bytes
+            })?;
+
+        let mut targets = vec![starting_hash];
+        targets.extend(accounts.last().map(|dump| dump.hashed_address));
+        response.proof = proof_nodes(targets.iter().flat_map(|target| {
+            proof.account_subtree.matching_nodes_sorted(&Nibbles::unpack(target))
+        }));
+        response.accounts = accounts
+            .into_iter()
+            .zip(bodies)
+            .map(|(dump, body)| AccountData { hash: dump.hashed_address, body })
+            .collect();
+
+        Ok(response)
+    }
+
+    /// Returns the storage slots of the requested accounts.
+    ///
+    /// The starting and limit hashes only apply to the first account. If that range does not
+    /// start at the beginning of the storage trie or the response is cut off in the middle of an
+    /// account, the proofs for the boundaries of that account's range are attached and no further
+    /// accounts are served.
+    fn get_storage_ranges_response(
+        &mut self,
+        request: GetStorageRangesMessage,
+    ) -> ProviderResult<StorageRangesMessage> {
+        let GetStorageRangesMessage {
+            request_id,
+            root_hash,
+            account_hashes,
+            starting_hash,
+            limit_hash,
+            ..
+        } = request;
+        let mut response =
+            StorageRangesMessage { request_id, slots: Vec::new(), proof: Vec::new() };
+
+        let Some(block) = self.state_block(root_hash)? else { return Ok(response) };
+        let response_bytes = response_limit(request.response_bytes);
+        // allow some slack to finish small storage tries instead of proving a partial range
+        let hard_limit = response_bytes + response_bytes / 10;
+
+        let mut total_bytes = 0;
+        for (idx, hashed_address) in account_hashes.into_iter().enumerate() {
+            if total_bytes >= response_bytes {
+                break
+            }
+
+            let (origin, limit) = if idx == 0 {
+                (starting_hash, limit_hash)
+            } else {
+                (B256::ZERO, B256::repeat_byte(0xff))
+            };
+
+            let mut slots = Vec::new();
+            let mut aborted = false;
+            let mut next = Some(origin);
+            'range: while let Some(start) = next {
+                let storage = self.client.hashed_storage_range(
+                    block,
+                    hashed_address,
+                    start,
+                    STATE_RANGE_BATCH_SIZE,
+                )?;
+                next = next_batch_start(&storage, |(hash, _)| *hash);
+
+                for (hash, value) in storage {
+                    if total_bytes >= hard_limit {
+                        aborted = true;
+                        break 'range
+                    }
+
+                    let data = Bytes::from(alloy_rlp::encode(value));
+                    total_bytes += B256::len_bytes() + data.len();
+                    slots.push(StorageData { hash, data });
+
+                    if hash >= limit {
+                        break 'range
+                    }
+                }
+            }
+
+            let last = slots.last().map(|slot| slot.hash);
+            if !slots.is_empty() {
+                response.slots.push(slots);
+            }
+
+            if !origin.is_zero() || (aborted && last.is_some()) {
+                let mut targets = vec![origin];
+                targets.extend(last);
+                let proof = self.client.history_by_block_number(block)?.multiproof(
+                    TrieInput::default(),
+                    MultiProofTargets::account_with_slots(hashed_address, targets.clone()),
+                )?;
+                response.proof = proof_nodes(
+                    proof
+                        .storage_proof_nodes(hashed_address, targets)
+                        .into_iter()
+                        .flat_map(|(_, nodes)| nodes),
+                );
+                break
+            }
+        }
+
+        Ok(response)
+    }
+
+    /// Returns the requested bytecodes.
+    fn get_byte_codes_response(
+        &self,
+        request: GetByteCodesMessage,
+    ) -> ProviderResult<ByteCodesMessage> {
+        let GetByteCodesMessage { request_id, hashes, response_bytes } = request;
+        let response_bytes = response_limit(response_bytes);
+        let state = self.client.latest()?;
+
+        let mut codes = Vec::new();
+        let mut total_bytes = 0;
+        for hash in hashes.into_iter().take(MAX_CODE_LOOKUPS) {
+            if hash == KECCAK_EMPTY {
+                codes.push(Bytes::new());
+            } else if let Some(code) = state.bytecode_by_hash(&hash)? {
+                let code = code.original_bytes();
+                total_bytes += code.len();
+                codes.push(code);
+            }
+
+            if total_bytes > response_bytes {
+                break
+            }
+        }
+
+        Ok(ByteCodesMessage { request_id, codes })
+    }
+
+    /// Returns the requested account and storage trie nodes.
+    ///
+    /// Nodes that don't exist are returned as empty entries.
+    fn get_trie_nodes_response(
+        &mut self,
+        request: GetTrieNodesMessage,
+    ) -> ProviderResult<TrieNodesMessage> {
+        let GetTrieNodesMessage { request_id, root_hash, paths, response_bytes } = request;
+        let mut response = TrieNodesMessage { request_id, nodes: Vec::new() };
+
+        let Some(block) = self.state_block(root_hash)? else { return Ok(response) };
+        let response_bytes = response_limit(response_bytes);
+
+        let mut lookups = Vec::new();
+        for path in paths {
+            if path.slot_paths.is_empty() {
+                lookups.push((None, compact_to_nibbles(&path.account_path)));
+            } else {
+                // storage trie nodes are keyed by the full hashed address
+                let Ok(hashed_address) = B256::try_from(path.account_path.as_ref()) else {
+                    continue
+                };
+                lookups.extend(
+                    path.slot_paths
+                        .iter()
+                        .map(|slot_path| (Some(hashed_address), compact_to_nibbles(slot_path))),
+                );
+            }
+        }
+        lookups.truncate(MAX_TRIE_NODE_LOOKUPS);
+
+        let mut targets = MultiProofTargets::default();
+        for (hashed_address, path) in &lookups {
+            let Some(path) = path else { continue };
+            match hashed_address {
+                Some(hashed_address) => {
+                    targets.entry(*hashed_address).or_default().insert(pad_path_to_key(path));
+                }
+                None => {
+                    targets.entry(pad_path_to_key(path)).or_default();
+                }
+            }
+        }
+        let proof = self
+            .client
+            .history_by_block_number(block)?
+            .multiproof(TrieInput::default(), targets)?;
+
+        let mut total_bytes = 0;
+        for (hashed_address, path) in lookups {
+            let node = path.and_then(|path| match hashed_address {
+                Some(hashed_address) => {
+                    proof.storages.get(&hashed_address)?.subtree.get(&path).cloned()
+                }
+                None => proof.account_subtree.get(&path).cloned(),
+            });
+            let node = node.unwrap_or_default();
+            total_bytes += node.len();
+            response.nodes.push(node);
+
+            if total_bytes > response_bytes {
+                break
+            }
+        }
+
+        Ok(response)
+    }
+
+    fn on_account_range_request(
+        &mut self,
+        peer_id: PeerId,
+        request: GetAccountRangeMessage,
+        response: oneshot::Sender<AccountRangeMessage>,
+    ) {
+        self.metrics.snap_account_range_requests_received_total.increment(1);
+
+        let request_id = request.request_id;
+        let accounts = self.get_account_range_response(request).unwrap_or_else(|err| {
+            debug!(target: "net::snap", %peer_id, %err, "Failed to serve account range");
+            AccountRangeMessage { request_id, accounts: Vec::new(), proof: Vec::new() }
+        });
+
+        let _ = response.send(accounts);
+    }
+
+    fn on_storage_ranges_request(
+        &mut self,
+        peer_id: PeerId,
+        request: GetStorageRangesMessage,
+        response: oneshot::Sender<StorageRangesMessage>,
+    ) {
+        self.metrics.snap_storage_ranges_requests_received_total.increment(1);
+
+        let request_id = request.request_id;
+        let slots = self.get_storage_ranges_response(request).unwrap_or_else(|err| {
+            debug!(target: "net::snap", %peer_id, %err, "Failed to serve storage ranges");
+            StorageRangesMessage { request_id, slots: Vec::new(), proof: Vec::new() }
+        });
+
+        let _ = response.send(slots);
+    }
+
+    fn on_byte_codes_request(
+        &self,
+        peer_id: PeerId,
+        request: GetByteCodesMessage,
+        response: oneshot::Sender<ByteCodesMessage>,
+    ) {
+        self.metrics.snap_byte_codes_requests_received_total.increment(1);
+
+        let request_id = request.request_id;
+        let codes = self.get_byte_codes_response(request).unwrap_or_else(|err| {
+            debug!(target: "net::snap", %peer_id, %err, "Failed to serve bytecodes");
+            ByteCodesMessage { request_id, codes: Vec::new() }
+        });
+
+        let _ = response.send(codes);
+    }
+
+    fn on_trie_nodes_request(
+        &mut self,
+        peer_id: PeerId,
+        request: GetTrieNodesMessage,
+        response: oneshot::Sender<TrieNodesMessage>,
+    ) {
+        self.metrics.snap_trie_nodes_requests_received_total.increment(1);
+
+        let request_id = request.request_id;
+        let nodes = self.get_trie_nodes_response(request).unwrap_or_else(|err| {
+            debug!(target: "net::snap", %peer_id, %err, "Failed to serve trie nodes");
+            TrieNodesMessage { request_id, nodes: Vec::new() }
+        });
+
+        let _ = response.send(nodes);
+    }
+}
+
+/// An endless future.
+///
+/// This should be spawned or used as part of `tokio::select!`.
+impl<C> Future for SnapRequestHandler<C>
+where
+    C: HeaderProvider + StateProviderFactory + HashedStateRangeReader + Unpin,
+{
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+
+        let mut acc = Duration::ZERO;
+        let maybe_more_incoming_requests = metered_poll_nested_stream_with_budget!(
+            acc,
+            "net::snap",
+            "Incoming snap requests stream",
+            DEFAULT_BUDGET_TRY_DRAIN_DOWNLOADERS,
+            this.incoming_requests.poll_next_unpin(cx),
+            |incoming| {
+                match incoming {
+                    IncomingSnapRequest::GetAccountRange { peer_id, request, response } => {
+                        this.on_account_range_request(peer_id, request, response)
+                    }
+                    IncomingSnapRequest::GetStorageRanges { peer_id, request, response } => {
+                        this.on_storage_ranges_request(peer_id, request, response)
+                    }
+                    IncomingSnapRequest::GetByteCodes { peer_id, request, response } => {
+                        this.on_byte_codes_request(peer_id, request, response)
+                    }
+                    IncomingSnapRequest::GetTrieNodes { peer_id, request, response } => {
+                        this.on_trie_nodes_request(peer_id, request, response)
+                    }
+                }
+            },
+        );
+
+        this.metrics.acc_duration_poll_snap_req_handler.set(acc.as_secs_f64());
+
+        // stream is fully drained and import futures pending
+        if maybe_more_incoming_requests {
+            // make sure we're woken up again
+            cx.waker().wake_by_ref();
+        }
+
+        Poll::Pending
+    }
+}
+
+/// All `snap` requests delegated by the peers' `snap` connections.
+#[derive(Debug)]
+pub enum IncomingSnapRequest {
+    /// Request a range of accounts from the peer.
+    ///
+    /// The response should be sent through the channel.
+    GetAccountRange {
+        /// The ID of the peer that sent the request.
+        peer_id: PeerId,
+        /// The requested account range.
+        request: GetAccountRangeMessage,
+        /// The channel sender for the response containing the accounts.
+        response: oneshot::Sender<AccountRangeMessage>,
+    },
+    /// Request storage ranges of accounts from the peer.
+    ///
+    /// The response should be sent through the channel.
+    GetStorageRanges {
+        /// The ID of the peer that sent the request.
+        peer_id: PeerId,
+        /// The requested storage ranges.
+        request: GetStorageRangesMessage,
+        /// The channel sender for the response containing the storage slots.
+        response: oneshot::Sender<StorageRangesMessage>,
+    },
+    /// Request contract bytecodes from the peer.
+    ///
+    /// The response should be sent through the channel.
+    GetByteCodes {
+        /// The ID of the peer that sent the request.
+        peer_id: PeerId,
+        /// The requested bytecode hashes.
+        request: GetByteCodesMessage,
+        /// The channel sender for the response containing the bytecodes.
+        response: oneshot::Sender<ByteCodesMessage>,
+    },
+    /// Request trie nodes from the peer.
+    ///
+    /// The response should be sent through the channel.
+    GetTrieNodes {
+        /// The ID of the peer that sent the request.
+        peer_id: PeerId,
+        /// The requested trie node paths.
+        request: GetTrieNodesMessage,
+        /// The channel sender for the response containing the trie nodes.
+        response: oneshot::Sender<TrieNodesMessage>,
+    },
+}
+
+/// The [`ProtocolHandler`] that announces `snap/1`, serves it with the [`SnapRequestHandler`] and
+/// sends the requests of the [`SnapFetchClient`].
+#[derive(Debug, Clone, Default)]
+pub struct SnapProtocolHandler {
+    /// Sender half of the [`SnapRequestHandler`]'s channel, if the state is served to peers.
+    to_request_handler: Option<mpsc::Sender<IncomingSnapRequest>>,
+    /// The peers with an active `snap/1` connection.
+    peers: Arc<SnapPeers>,
+}
+
+impl SnapProtocolHandler {
+    /// Creates a new protocol handler that forwards requests to the given channel.
+    pub fn new(to_request_handler: mpsc::Sender<IncomingSnapRequest>) -> Self {
+        Self { to_request_handler: Some(to_request_handler), peers: Default::default() }
+    }
+
+    /// Creates a new protocol handler that doesn't serve the state to peers.
+    ///
+    /// The requests of peers are answered with empty responses.
+    pub fn client_only() -> Self {
+        Self::default()
+    }
+
+    /// Returns a client that sends requests over the connections of this handler.
+    pub fn client(&self) -> SnapFetchClient {
+        SnapFetchClient::new(self.peers.clone())
+    }
+
+    fn connection_handler(&self) -> SnapConnectionHandler {
+        SnapConnectionHandler {
+            to_request_handler: self.to_request_handler.clone(),
+            peers: self.peers.clone(),
+        }
+    }
+}
+
+impl ProtocolHandler for SnapProtocolHandler {
+    type ConnectionHandler = SnapConnectionHandler;
+
+    fn on_incoming(&self, _socket_addr: SocketAddr) -> Option<Self::ConnectionHandler> {
+        Some(self.connection_handler())
+    }
+
+    fn on_outgoing(
+        &self,
+        _socket_addr: SocketAddr,
+        _peer_id: PeerId,
+    ) -> Option<Self::ConnectionHandler> {
+        Some(self.connection_handler())
+    }
+}
+
+/// The [`ConnectionHandler`] for a single `snap/1` connection.
+#[derive(Debug)]
+pub struct SnapConnectionHandler {
+    /// Sender half of the [`SnapRequestHandler`]'s channel, if the state is served to peers.
+    to_request_handler: Option<mpsc::Sender<IncomingSnapRequest>>,
+    /// The peers with an active `snap/1` connection.
+    peers: Arc<SnapPeers>,
+}
+
+impl ConnectionHandler for SnapConnectionHandler {
+    type Connection = SnapConnection;
+
+    fn protocol(&self) -> Protocol {
+        Protocol::snap_1()
+    }
+
+    fn on_unsupported_by_peer(
+        self,
+        _supported: &SharedCapabilities,
+        _direction: Direction,
+        _peer_id: PeerId,
+    ) -> OnNotSupported {
+        OnNotSupported::KeepAlive
+    }
+
+    fn into_connection(
+        self,
+        _direction: Direction,
+        peer_id: PeerId,
+        conn: ProtocolConnection,
+    ) -> Self::Connection {
+        let (to_peer, requests) = mpsc::unbounded_channel();
+        self.peers.add(peer_id, to_peer.clone());
+
+        SnapConnection {
+            peer_id,
+            conn,
+            to_request_handler: self.to_request_handler,
+            pending_responses: FuturesUnordered::new(),
+            peers: self.peers,
+            to_peer,
+            requests,
+            inflight_requests: HashMap::new(),
+        }
+    }
+}
+
+/// A `snap/1` connection that delegates the peer's requests to the [`SnapRequestHandler`] and
+/// yields the responses, and sends the requests of the local node to the peer.
+#[must_use = "Connection does nothing unless polled."]
+pub struct SnapConnection {
+    /// The peer of this connection.
+    peer_id: PeerId,
+    /// The raw `snap` messages of the peer.
+    conn: ProtocolConnection,
+    /// Sender half of the [`SnapRequestHandler`]'s channel, if the state is served to peers.
+    to_request_handler: Option<mpsc::Sender<IncomingSnapRequest>>,
+    /// Responses to the requests that are currently being served.
+    pending_responses: FuturesUnordered<BoxFuture<'static, Option<SnapProtocolMessage>>>,
+    /// The peers with an active `snap/1` connection, this connection is removed on drop.
+    peers: Arc<SnapPeers>,
+    /// Sender half of the `requests` channel, identifies this connection in `peers`.
+    to_peer: UnboundedSender<SnapPeerRequest>,
+    /// Requests of the local node to send to the peer.
+    requests: UnboundedReceiver<SnapPeerRequest>,
+    /// Requests sent to the peer that await a response, by request ID.
+    inflight_requests: HashMap<u64, oneshot::Sender<SnapResponse>>,
+}
+
+impl SnapConnection {
+    /// Forwards the request to the [`SnapRequestHandler`].
+    fn on_request(&self, request: SnapProtocolMessage) {
+        if self.pending_responses.len() >= MAX_PENDING_SNAP_REQUESTS_PER_PEER {
+            trace!(target: "net::snap", peer_id=%self.peer_id, "Too many pending requests, dropping request");
+            return
+        }
+
+        let Some(to_request_handler) = &self.to_request_handler else {
+            // this node doesn't serve the state, which is signaled with empty responses
+            if let Some(response) = empty_response(&request) {
+                self.pending_responses.push(future::ready(Some(response)).boxed());
+            }
+            return
+        };
+
+        let peer_id = self.peer_id;
+        let (incoming, response) = match request {
+            SnapProtocolMessage::GetAccountRange(request) => {
+                let (tx, rx) = oneshot::channel();
+                (
+                    IncomingSnapRequest::GetAccountRange { peer_id, request, response: tx },
+                    rx.map(|res| res.ok().map(SnapProtocolMessage::AccountRange)).boxed(),
+                )
+            }
+            SnapProtocolMessage::GetStorageRanges(request) => {
+                let (tx, rx) = oneshot::channel();
+                (
+                    IncomingSnapRequest::GetStorageRanges { peer_id, request, response: tx },
+                    rx.map(|res| res.ok().map(SnapProtocolMessage::StorageRanges)).boxed(),
+                )
+            }
+            SnapProtocolMessage::GetByteCodes(request) => {
+                let (tx, rx) = oneshot::channel();
+                (
+                    IncomingSnapRequest::GetByteCodes { peer_id, request, response: tx },
+                    rx.map(|res| res.ok().map(SnapProtocolMessage::ByteCodes)).boxed(),
+                )
+            }
+            SnapProtocolMessage::GetTrieNodes(request) => {
+                let (tx, rx) = oneshot::channel();
+                (
+                    IncomingSnapRequest::GetTrieNodes { peer_id, request, response: tx },
+                    rx.map(|res| res.ok().map(SnapProtocolMessage::TrieNodes)).boxed(),
+                )
+            }
+            // responses are handled by `on_response`
+            SnapProtocolMessage::AccountRange(_) |
+            SnapProtocolMessage::StorageRanges(_) |
+            SnapProtocolMessage::ByteCodes(_) |
+            SnapProtocolMessage::TrieNodes(_) => return,
+        };
+
+        if to_request_handler.try_send(incoming).is_ok() {
+            self.pending_responses.push(response);
+        } else {
+            trace!(target: "net::snap", peer_id=%self.peer_id, "Snap request handler is busy, dropping request");
+        }
+    }
+
+    /// Delivers the response of the peer to the request of the local node.
+    fn on_response(&mut self, response: SnapResponse) {
+        if let Some(tx) = self.inflight_requests.remove(&response.request_id()) {
+            let _ = tx.send(response);
+        } else {
+            trace!(target: "net::snap", peer_id=%self.peer_id, request_id=response.request_id(), "Received unsolicited snap response");
+        }
+    }
+}
+
+impl Drop for SnapConnection {
+    fn drop(&mut self) {
+        self.peers.remove(&self.to_peer);
+    }
+}
+
+impl std::fmt::Debug for SnapConnection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SnapConnection")
+            .field("peer_id", &self.peer_id)
+            .field("pending_responses", &self.pending_responses.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Stream for SnapConnection {
+    type Item = BytesMut;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        loop {
+            if let Poll::Ready(Some(Some(response))) = this.pending_responses.poll_next_unpin(cx) {
+                return Poll::Ready(Some(BytesMut::from(&response.encode()[..])))
+            }
+
+            if let Poll::Ready(Some(SnapPeerRequest { request_id, request, response })) =
+                this.requests.poll_recv(cx)
+            {
+                // forget the requests whose callers have given up
+                this.inflight_requests.retain(|_, tx| !tx.is_closed());
+                this.inflight_requests.insert(request_id, response);
+                return Poll::Ready(Some(BytesMut::from(&request.encode()[..])))
+            }
+
+            let Some(msg) = ready!(this.conn.poll_next_unpin(cx)) else { return Poll::Ready(None) };
+
+            let Some((&message_id, mut payload)) = msg.split_first() else {
+                return Poll::Ready(None)
+            };
+
+            match SnapProtocolMessage::decode(message_id, &mut payload) {
+                Ok(SnapProtocolMessage::AccountRange(msg)) => {
+                    this.on_response(SnapResponse::AccountRange(msg))
+                }
+                Ok(SnapProtocolMessage::StorageRanges(msg)) => {
+                    this.on_response(SnapResponse::StorageRanges(msg))
+                }
+                Ok(SnapProtocolMessage::ByteCodes(msg)) => {
+                    this.on_response(SnapResponse::ByteCodes(msg))
+                }
+                Ok(SnapProtocolMessage::TrieNodes(msg)) => {
+                    this.on_response(SnapResponse::TrieNodes(msg))
+                }
+                Ok(request) => this.on_request(request),
+                Err(err) => {
+                    debug!(target: "net::snap", peer_id=%this.peer_id, %err, "Failed to decode snap message, closing connection");
+                    return Poll::Ready(None)
+                }
+            }
+        }
+    }
+}
+
+/// The account body in the slim format of the `snap` protocol.
+///
+/// Unlike the trie account, an empty storage root and an empty code hash are encoded as empty
+/// strings.
+#[derive(Debug, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+struct SlimAccount {
+    nonce: u64,
+    balance: U256,
+    storage_root: Bytes,
+    code_hash: Bytes,
+}
+
+impl SlimAccount {
+    fn new(account: &Account, storage_root: B256) -> Self {
+        let storage_root = if storage_root == EMPTY_ROOT_HASH {
+            Bytes::new()
+        } else {
+            Bytes::copy_from_slice(storage_root.as_slice())
+        };
+        let code_hash = match account.bytecode_hash {
+            Some(code_hash) if code_hash != KECCAK_EMPTY => {
+                Bytes::copy_from_slice(code_hash.as_slice())
+            }
+            _ => Bytes::new(),
+        };
+        Self { nonce: account.nonce, balance: account.balance, storage_root, code_hash }
+    }
+
+    fn rlp(&self) -> Bytes {
+        alloy_rlp::encode(self).into()
+    }
+}
+
+/// Returns the empty response to the request, or `None` if the message is not a request.
+const fn empty_response(request: &SnapProtocolMessage) -> Option<SnapProtocolMessage> {
+    let response = match request {
+        SnapProtocolMessage::GetAccountRange(request) => {
+            SnapProtocolMessage::AccountRange(AccountRangeMessage {
+                request_id: request.request_id,
+                accounts: Vec::new(),
+                proof: Vec::new(),
+            })
+        }
+        SnapProtocolMessage::GetStorageRanges(request) => {
+            SnapProtocolMessage::StorageRanges(StorageRangesMessage {
+                request_id: request.request_id,
+                slots: Vec::new(),
+                proof: Vec::new(),
+            })
+        }
+        SnapProtocolMessage::GetByteCodes(request) => {
+            SnapProtocolMessage::ByteCodes(ByteCodesMessage {
+                request_id: request.request_id,
+                codes: Vec::new(),
+            })
+        }
+        SnapProtocolMessage::GetTrieNodes(request) => {
+            SnapProtocolMessage::TrieNodes(TrieNodesMessage {
+                request_id: request.request_id,
+                nodes: Vec::new(),
+            })
+        }
+        _ => return None,
+    };
+    Some(response)
+}
+
+/// Caps the requested response size at the [`SOFT_RESPONSE_LIMIT`].
+fn response_limit(response_bytes: u64) -> usize {
+    usize::try_from(response_bytes).unwrap_or(usize::MAX).min(SOFT_RESPONSE_LIMIT)
+}
+
+/// Returns the key to continue reading a range from, if the batch was full.
+fn next_batch_start<T>(batch: &[T], key: impl Fn(&T) -> B256) -> Option<B256> {
+    if batch.len() < STATE_RANGE_BATCH_SIZE {
+        return None
+    }
+    let last = U256::from_be_bytes(key(batch.last()?).0);
+    last.checked_add(U256::from(1)).map(B256::from)
+}
+
+/// Deduplicates the proof nodes and orders them by their path.
+fn proof_nodes(nodes: impl IntoIterator<Item = (Nibbles, Bytes)>) -> Vec<Bytes> {
+    nodes.into_iter().collect::<BTreeMap<_, _>>().into_values().collect()
+}
+
+/// Returns the smallest key that has the given path as prefix.
+fn pad_path_to_key(path: &Nibbles) -> B256 {
+    let mut padded = path.pack();
+    padded.resize(32, 0);
+    B256::from_slice(&padded)
+}
+
+/// Decodes a hex-prefix encoded trie path.
+///
+/// Returns `None` if the encoding is invalid.
+fn compact_to_nibbles(compact: &[u8]) -> Option<Nibbles> {
+    let Some((&first, rest)) = compact.split_first() else { return Some(Nibbles::new()) };
+
+    let flag = first >> 4;
+    if flag > 3 {
+        return None
+    }
+
+    let mut nibbles = Vec::with_capacity(compact.len() * 2);
+    // odd length paths store the first nibble next to the flag
+    if flag & 1 == 1 {
+        nibbles.push(first & 0x0f);
+    }
+    for byte in rest {
+        nibbles.push(byte >> 4);
+        nibbles.push(byte & 0x0f);
+    }
+
+    (nibbles.len() <= 64).then(|| Nibbles::from_nibbles_unchecked(nibbles))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_genesis::{Genesis, GenesisAccount};
+    use alloy_primitives::{keccak256, Address};
+    use alloy_rlp::Decodable;
+    use reth_chainspec::ChainSpec;
+    use reth_db_common::init::init_genesis;
+    use reth_eth_wire_types::snap::TriePath;
+    use reth_provider::{
+        providers::BlockchainProvider,
+        test_utils::{create_test_provider_factory_with_chain_spec, MockNodeTypesWithDB},
+    };
+    use std::sync::Arc;
+
+    const CONTRACT: Address = Address::new([0x11; 20]);
+    const CODE: [u8; 4] = [0x60, 0x00, 0x60, 0x00];
+
+    /// Returns a handler for a chain whose genesis has two accounts and a contract with storage.
+    fn genesis_handler() -> (SnapRequestHandler<BlockchainProvider<MockNodeTypesWithDB>>, B256) {
+        let storage =
+            (1..=3u64).map(|slot| (B256::with_last_byte(slot as u8), B256::with_last_byte(0xff)));
+        let genesis = Genesis::default().extend_accounts([
+            (Address::new([0x01; 20]), GenesisAccount::default().with_balance(U256::from(1))),
+            (Address::new([0x02; 20]), GenesisAccount::default().with_nonce(Some(2))),
+            (
+                CONTRACT,
+                GenesisAccount::default()
+                    .with_code(Some(Bytes::from_static(&CODE)))
+                    .with_storage(Some(storage.collect())),
+            ),
+        ]);
+        let factory =
+            create_test_provider_factory_with_chain_spec(Arc::new(ChainSpec::from(genesis)));
+        init_genesis(&factory).unwrap();
+        let provider = BlockchainProvider::new(factory).unwrap();
+        let root = provider.header_by_number(0).unwrap().unwrap().state_root;
+
+        let (_, rx) = mpsc::channel(1);
+        (SnapRequestHandler::new(provider, rx), root)
+    }
+
+    #[test]
+    fn serve_account_range() {
+        let (mut handler, root) = genesis_handler();
+
+        let request = GetAccountRangeMessage {
+            request_id: 1,
+            root_hash: root,
+            starting_hash: B256::ZERO,
+            limit_hash: B256::repeat_byte(0xff),
+            response_bytes: 1024 * 1024,
+        };
+        let response = handler.get_account_range_response(request.clone()).unwrap();
+        assert_eq!(response.request_id, 1);
+        assert_eq!(response.accounts.len(), 3);
+        assert!(response.accounts.windows(2).all(|pair| pair[0].hash < pair[1].hash));
+        assert!(response.proof.iter().any(|node| keccak256(node) == root));
+
+        // only the contract has a storage root and code hash
+        for account in &response.accounts {
+            let body = SlimAccount::decode(&mut &account.body[..]).unwrap();
+            if account.hash == keccak256(CONTRACT) {
+                assert_eq!(body.storage_root.len(), 32);
+                assert_eq!(body.code_hash[..], keccak256(CODE)[..]);
+            } else {
+                assert!(body.storage_root.is_empty() && body.code_hash.is_empty());
+            }
+        }
+
+        // the response stops after the first account that exceeds the requested size
+        let response = handler
+            .get_account_range_response(GetAccountRangeMessage { response_bytes: 1, ..request })
+            .unwrap();
+        assert_eq!(response.accounts.len(), 1);
+
+        // unknown state roots are answered with an empty response
+        let response = handler
+            .get_account_range_response(GetAccountRangeMessage { root_hash: B256::ZERO, ..request })
+            .unwrap();
+        assert!(response.accounts.is_empty() && response.proof.is_empty());
+    }
+
+    #[test]
+    fn serve_storage_ranges() {
+        let (mut handler, root) = genesis_handler();
+
+        let request = GetStorageRangesMessage {
+            request_id: 1,
+            root_hash: root,
+            account_hashes: vec![keccak256(CONTRACT), keccak256(Address::new([0x01; 20]))],
+            starting_hash: B256::ZERO,
+            limit_hash: B256::repeat_byte(0xff),
+            response_bytes: 1024 * 1024,
+        };
+        let response = handler.get_storage_ranges_response(request.clone()).unwrap();
+        // the account without storage is omitted and complete ranges need no proof
+        assert_eq!(response.slots.len(), 1);
+        assert_eq!(response.slots[0].len(), 3);
+        assert!(response.slots[0]
+            .iter()
+            .all(|slot| slot.data == alloy_rlp::encode(U256::from(0xff))));
+        assert!(response.proof.is_empty());
+
+        // a range that starts in the middle of the trie is proven and ends the response
+        let starting_hash = response.slots[0][1].hash;
+        let response = handler
+            .get_storage_ranges_response(GetStorageRangesMessage { starting_hash, ..request })
+            .unwrap();
+        assert_eq!(response.slots.len(), 1);
+        assert_eq!(response.slots[0].len(), 2);
+        assert!(!response.proof.is_empty());
+    }
+
+    #[test]
+    fn serve_byte_codes_and_trie_nodes() {
+        let (mut handler, root) = genesis_handler();
+
+        let response = handler
+            .get_byte_codes_response(GetByteCodesMessage {
+                request_id: 1,
+                hashes: vec![keccak256(CODE), B256::ZERO, KECCAK_EMPTY],
+                response_bytes: 1024,
+            })
+            .unwrap();
+        assert_eq!(response.codes, vec![Bytes::from_static(&CODE), Bytes::new()]);
+
+        let contract = handler
+            .get_account_range_response(GetAccountRangeMessage {
+                request_id: 1,
+                root_hash: root,
+                starting_hash: keccak256(CONTRACT),
+                limit_hash: keccak256(CONTRACT),
+                response_bytes: 1024,
+            })
+            .unwrap()
+            .accounts
+            .remove(0);
+        let storage_root = SlimAccount::decode(&mut &contract.body[..]).unwrap().storage_root;
+
+        let response = handler
+            .get_trie_nodes_response(GetTrieNodesMessage {
+                request_id: 1,
+                root_hash: root,
+                paths: vec![
+                    TriePath { account_path: Bytes::new(), slot_paths: Vec::new() },
+                    TriePath {
+                        account_path: keccak256(CONTRACT).into(),
+                        slot_paths: vec![Bytes::new()],
+                    },
+                ],
+                response_bytes: 1024,
+            })
+            .unwrap();
+        assert_eq!(response.nodes.len(), 2);
+        assert_eq!(keccak256(&response.nodes[0]), root);
+        assert_eq!(keccak256(&response.nodes[1])[..], storage_root[..]);
+    }
+
+    #[test]
+    fn decode_compact_paths() {
+        assert_eq!(compact_to_nibbles(&[]), Some(Nibbles::new()));
+        assert_eq!(compact_to_nibbles(&[0x00]), Some(Nibbles::new()));
+        assert_eq!(compact_to_nibbles(&[0x1a]), Some(Nibbles::from_nibbles([0xa])));
+        assert_eq!(compact_to_nibbles(&[0x00, 0xab]), Some(Nibbles::from_nibbles([0xa, 0xb])));
+        assert_eq!(compact_to_nibbles(&[0x3a, 0xbc]), Some(Nibbles::from_nibbles([0xa, 0xb, 0xc])));
+        assert_eq!(compact_to_nibbles(&[0x40]), None);
+        assert_eq!(compact_to_nibbles(&[0x00; 34]), None);
+    }
+}
@@ -19,7 +19,7 @@ use reth_eth_wire::{
 use reth_ethereum_forks::ForkId;
 use reth_network_api::{DiscoveredEvent, DiscoveryEvent, PeerRequest, PeerRequestSender};
 use reth_network_peers::PeerId;
-use reth_network_types::{PeerAddr, PeerKind};
+use reth_network_types::{PeerAddr, PeerKind, PersistedPeer};
 use reth_primitives_traits::Block;
 use std::{
     collections::{HashMap, VecDeque},
@@ -157,12 +157,16 @@ impl<N: NetworkPrimitives> NetworkState<N> {
         // find the corresponding block number
         let block_number =
             self.client.block_number(status.blockhash).ok().flatten().unwrap_or_default();
+        // remember the fork id the peer announced in its status message
+        self.peers_manager.set_discovered_fork_id(peer, status.forkid);
+        let request_stats = self.peers_manager.peer_request_stats(&peer).unwrap_or_default();
         self.state_fetcher.new_active_peer(
             peer,
             status.blockhash,
             block_number,
             timeout,
             range_info,
+            request_stats,
         );
 
         self.active_peers.insert(
@@ -182,7 +186,20 @@ impl<N: NetworkPrimitives> NetworkState<N> {
     /// This will remove the peer from the available set of peers and close all inflight requests.
     pub(crate) fn on_session_closed(&mut self, peer: PeerId) {
         self.active_peers.remove(&peer);
-        self.state_fetcher.on_session_closed(&peer);
+        if let Some(stats) = self.state_fetcher.on_session_closed(&peer) {
+            self.peers_manager.set_peer_request_stats(&peer, stats);
+        }
+    }
+
+    /// Returns the state of all known peers that should survive a restart, including the request
+    /// statistics of the currently active sessions.
+    pub(crate) fn persisted_peers(&self) -> impl Iterator<Item = PersistedPeer> + '_ {
+        self.peers_manager.persisted_peers().map(|mut peer| {
+            if let Some(stats) = self.state_fetcher.request_stats(&peer.record.id) {
+                peer.request_stats = stats;
+            }
+            peer
+        })
     }
 
     /// Starts propagating the new block to peers that haven't reported the block yet.
@@ -16,6 +16,7 @@ use reth_cli_util::get_secret_key;
 use reth_db_api::{database::Database, database_metrics::DatabaseMetrics};
 use reth_exex::ExExContext;
 use reth_network::{
+    snap_client::SnapFetchClient,
     transactions::{TransactionPropagationPolicy, TransactionsManagerConfig},
     NetworkBuilder, NetworkConfig, NetworkConfigBuilder, NetworkHandle, NetworkManager,
     NetworkPrimitives,
@@ -37,7 +38,10 @@ use reth_provider::{
 use reth_tasks::TaskExecutor;
 use reth_transaction_pool::{PoolConfig, PoolTransaction, TransactionPool};
 use secp256k1::SecretKey;
-use std::{fmt::Debug, sync::Arc};
+use std::{
+    fmt::Debug,
+    sync::{Arc, OnceLock},
+};
 use tracing::{info, trace, warn};
 
 pub mod add_ons;
@@ -703,6 +707,8 @@ pub struct BuilderContext<Node: FullNodeTypes> {
     pub(crate) executor: TaskExecutor,
     /// Config container
     pub(crate) config_container: WithConfigs<<Node::Types as NodeTypes>::ChainSpec>,
+    /// The client to snap sync the state, set when the network is started with `--snap-sync`.
+    pub(crate) snap_client: OnceLock<SnapFetchClient>,
 }
 
 impl<Node: FullNodeTypes> BuilderContext<Node> {
@@ -713,7 +719,7 @@ impl<Node: FullNodeTypes> BuilderContext<Node> {
         executor: TaskExecutor,
         config_container: WithConfigs<<Node::Types as NodeTypes>::ChainSpec>,
     ) -> Self {
-        Self { head, provider, executor, config_container }
+        Self { head, provider, executor, config_container, snap_client: OnceLock::new() }
     }
 
     /// Returns the configured provider to interact with the blockchain.
@@ -821,13 +827,32 @@ impl<Node: FullNodeTypes> BuilderContext<Node> {
         Node::Provider: BlockReaderFor<N>,
         Policy: TransactionPropagationPolicy + Debug,
     {
-        let (handle, network, txpool, eth) = builder
+        let mut builder = builder
             .transactions_with_policy(pool, tx_config, propagation_policy)
-            .request_handler(self.provider().clone())
-            .split_with_handle();
+            .request_handler(self.provider().clone());
+        let network_args = &self.config().network;
+        let snap = match (network_args.enable_snap_server, network_args.snap_sync) {
+            (true, true) => {
+                let (snap, client) =
+                    builder.snap_request_handler_with_client(self.provider().clone());
+                let _ = self.snap_client.set(client);
+                Some(snap)
+            }
+            (true, false) => Some(builder.snap_request_handler(self.provider().clone())),
+            (false, true) => {
+                let _ = self.snap_client.set(builder.snap_client());
+                None
+            }
+            (false, false) => None,
+        };
+        let (handle, network, txpool, eth) = builder.split_with_handle();
 
         self.executor.spawn_critical("p2p txpool", txpool);
         self.executor.spawn_critical("p2p eth request handler", eth);
+        if let Some(snap) = snap {
+            // serving state ranges and proofs is expensive, so it gets its own thread
+            self.executor.spawn_critical_blocking("p2p snap request handler", snap);
+        }
 
         let default_peers_path = self.config().datadir().known_peers();
         let known_peers_file = self.config().network.persistent_peers_file(default_peers_path);
@@ -925,6 +950,7 @@ impl<Node: FullNodeTypes> std::fmt::Debug for BuilderContext<Node> {
             .field("provider", &std::any::type_name::<Node::Provider>())
             .field("executor", &self.executor)
             .field("config", &self.config())
+            .field("snap_client", &self.snap_client)
             .finish()
     }
 }
@@ -51,6 +51,7 @@ use reth_evm::{noop::NoopEvmConfig, ConfigureEvm};
 use reth_exex::ExExManagerHandle;
 use reth_fs_util as fs;
 use reth_invalid_block_hooks::InvalidBlockWitnessHook;
+use reth_network::snap_client::SnapFetchClient;
 use reth_network_p2p::headers::client::HeadersClient;
 use reth_node_api::{FullNodeTypes, NodeTypes, NodeTypesWithDB, NodeTypesWithDBAdapter};
 use reth_node_core::{
@@ -333,9 +334,10 @@ impl<R, ChainSpec: EthChainSpec> LaunchContextWith<Attached<WithConfigs<ChainSpe
     ///
     /// This includes:
     /// - Making sure the ETL dir is set to the datadir
+    /// - Making sure the S3 stage download dir is set to the datadir
     /// - RPC settings are adjusted to the correct port
     pub fn with_adjusted_configs(self) -> Self {
-        self.ensure_etl_datadir().with_adjusted_instance_ports()
+        self.ensure_etl_datadir().ensure_s3_datadir().with_adjusted_instance_ports()
     }
 
     /// Make sure ETL doesn't default to /tmp/, but to whatever datadir is set to
@@ -354,6 +356,18 @@ impl<R, ChainSpec: EthChainSpec> LaunchContextWith<Attached<WithConfigs<ChainSpe
         self
     }
 
+    /// Make sure the S3 stage doesn't default to /tmp/, but to whatever datadir is set to.
+    ///
+    /// Unlike the ETL dir, it is kept on launch so interrupted downloads can be resumed.
+    pub fn ensure_s3_datadir(mut self) -> Self {
+        if self.toml_config_mut().stages.s3.folder.is_none() {
+            let s3 = self.toml_config().stages.s3.clone().with_datadir(self.data_dir().data_dir());
+            self.toml_config_mut().stages.s3 = s3;
+        }
+
+        self
+    }
+
     /// Change rpc port numbers based on the instance number.
     pub fn with_adjusted_instance_ports(mut self) -> Self {
         self.node_config_mut().adjust_instance_ports();
@@ -789,6 +803,7 @@ where
             },
             node_adapter,
             head,
+            snap_client: builder_ctx.snap_client.into_inner(),
         };
 
         let ctx = LaunchContextWith {
@@ -846,6 +861,11 @@ where
         &self.right().node_adapter
     }
 
+    /// Returns the client to snap sync the state, if the network was started with `--snap-sync`.
+    pub const fn snap_client(&self) -> Option<&SnapFetchClient> {
+        self.right().snap_client.as_ref()
+    }
+
     /// Returns mutable reference to the configured `NodeAdapter`.
     pub const fn node_adapter_mut(&mut self) -> &mut NodeAdapter<T, CB::Components> {
         &mut self.right_mut().node_adapter
@@ -1239,6 +1259,7 @@ where
     db_provider_container: WithMeteredProvider<NodeTypesWithDBAdapter<T::Types, T::DB>>,
     node_adapter: NodeAdapter<T, CB::Components>,
     head: Head,
+    snap_client: Option<SnapFetchClient>,
 }
 
 #[cfg(test)]
@@ -1284,6 +1305,14 @@ mod tests {
                     bodies_distance: None,
                     receipts_log_filter: None,
                     bodies_before: None,
+                    changesets_distance: None,
+                    changesets_before: None,
+                    block_meta_pre_merge: false,
+                    block_meta_distance: None,
+                    block_meta_before: None,
+                    bytecodes_distance: None,
+                    bytecodes_before: None,
+                    disk_budget: None,
                 },
                 ..NodeConfig::test()
             };
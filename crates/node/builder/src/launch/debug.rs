@@ -179,17 +179,21 @@ where
             let pool = handle.node.pool.clone();
             let payload_builder_handle = handle.node.payload_builder_handle.clone();
 
+            let local_miner = handle.node.add_ons_handle.local_miner.clone();
+
             let dev_mining_mode = handle.node.config.dev_mining_mode(pool);
             handle.node.task_executor.spawn_critical("local engine", async move {
-                LocalMiner::new(
+                let mut miner = LocalMiner::new(
                     blockchain_db,
                     N::Types::local_payload_attributes_builder(&chain_spec),
                     beacon_engine_handle,
                     dev_mining_mode,
                     payload_builder_handle,
-                )
-                .run()
-                .await
+                );
+                if let Some(local_miner) = &local_miner {
+                    miner = miner.with_handle(local_miner);
+                }
+                miner.run().await
             });
         }
 
@@ -147,6 +147,12 @@ where
 
         let consensus = Arc::new(ctx.components().consensus().clone());
 
+        // the receipts of the blocks before the snap sync pivot are never downloaded
+        let snap_client = ctx.snap_client().cloned();
+        if snap_client.is_some() && !ctx.prune_modes().has_receipts_pruning() {
+            eyre::bail!("--snap-sync requires receipts pruning, e.g. with --full");
+        }
+
         let pipeline = build_networked_pipeline(
             &ctx.toml_config().stages,
             network_client.clone(),
@@ -160,6 +166,7 @@ where
             ctx.components().evm_config().clone(),
             maybe_exex_manager_handle.clone().unwrap_or_else(ExExManagerHandle::empty),
             ctx.era_import_source(),
+            snap_client,
         )?;
 
         // The new engine writes directly to static files. This ensures that they're up to the tip.
@@ -244,8 +251,13 @@ where
             ),
         );
 
-        let RpcHandle { rpc_server_handles, rpc_registry, engine_events, beacon_engine_handle } =
-            add_ons.launch_add_ons(add_ons_ctx).await?;
+        let RpcHandle {
+            rpc_server_handles,
+            rpc_registry,
+            engine_events,
+            beacon_engine_handle,
+            local_miner,
+        } = add_ons.launch_add_ons(add_ons_ctx).await?;
 
         // Run consensus engine to completion
         let initial_target = ctx.initial_backfill_target()?;
@@ -343,6 +355,7 @@ where
                 rpc_registry,
                 engine_events,
                 beacon_engine_handle,
+                local_miner,
             },
         };
         // Notify on node started
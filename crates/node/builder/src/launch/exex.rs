@@ -76,6 +76,7 @@ impl<Node: FullNodeComponents + Clone> ExExLauncher<Node> {
 
             // create the launch context for the exex
             let context = ExExContext {
+                id: id.clone(),
                 head,
                 config: config_container.config.clone(),
                 reth_config: config_container.toml_config.clone(),
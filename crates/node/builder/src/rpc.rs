@@ -9,6 +9,7 @@ use alloy_rpc_types_engine::ExecutionData;
 use jsonrpsee::{core::middleware::layer::Either, RpcModule};
 use reth_chain_state::CanonStateSubscriptions;
 use reth_chainspec::{ChainSpecProvider, EthereumHardforks};
+use reth_engine_local::LocalMinerHandle;
 use reth_engine_tree::tree::EngineValidator;
 use reth_node_api::{
     AddOnsContext, BlockTy, EngineTypes, FullNodeComponents, FullNodeTypes, NodeAddOns, NodeTypes,
@@ -19,12 +20,15 @@ use reth_node_core::{
     version::{CARGO_PKG_VERSION, CLIENT_CODE, NAME_CLIENT, VERGEN_GIT_SHA},
 };
 use reth_payload_builder::{PayloadBuilderHandle, PayloadStore};
-use reth_rpc::eth::{core::EthRpcConverterFor, EthApiTypes, FullEthApiServer};
-use reth_rpc_api::{eth::helpers::AddDevSigners, IntoEngineApiRpcModule};
+use reth_rpc::{
+    eth::{core::EthRpcConverterFor, EthApiTypes, FullEthApiServer},
+    AnvilApi,
+};
+use reth_rpc_api::{eth::helpers::AddDevSigners, AnvilApiServer, IntoEngineApiRpcModule};
 use reth_rpc_builder::{
     auth::{AuthRpcModule, AuthServerHandle},
     config::RethRpcServerConfig,
-    RpcModuleBuilder, RpcRegistryInner, RpcServerConfig, RpcServerHandle, Stack,
+    RethRpcModule, RpcModuleBuilder, RpcRegistryInner, RpcServerConfig, RpcServerHandle, Stack,
     TransportRpcModules,
 };
 use reth_rpc_engine_api::{capabilities::EngineCapabilities, EngineApi};
@@ -327,6 +331,8 @@ pub struct RpcHandle<Node: FullNodeComponents, EthApi: EthApiTypes> {
         EventSender<BeaconConsensusEngineEvent<<Node::Types as NodeTypes>::Primitives>>,
     /// Handle to the beacon consensus engine.
     pub beacon_engine_handle: BeaconConsensusEngineHandle<<Node::Types as NodeTypes>::Payload>,
+    /// Handle to the local miner served by the `anvil_` namespace, only set in dev mode.
+    pub local_miner: Option<LocalMinerHandle>,
 }
 
 impl<Node: FullNodeComponents, EthApi: EthApiTypes> Clone for RpcHandle<Node, EthApi> {
@@ -336,6 +342,7 @@ impl<Node: FullNodeComponents, EthApi: EthApiTypes> Clone for RpcHandle<Node, Et
             rpc_registry: self.rpc_registry.clone(),
             engine_events: self.engine_events.clone(),
             beacon_engine_handle: self.beacon_engine_handle.clone(),
+            local_miner: self.local_miner.clone(),
         }
     }
 }
@@ -376,6 +383,8 @@ pub struct RpcServerOnlyHandle<Node: FullNodeComponents, EthApi: EthApiTypes> {
         EventSender<BeaconConsensusEngineEvent<<Node::Types as NodeTypes>::Primitives>>,
     /// Handle to the consensus engine.
     pub engine_handle: BeaconConsensusEngineHandle<<Node::Types as NodeTypes>::Payload>,
+    /// Handle to the local miner served by the `anvil_` namespace, only set in dev mode.
+    pub local_miner: Option<LocalMinerHandle>,
 }
 
 /// Handle returned when only the authenticated Engine API server is launched.
@@ -407,6 +416,7 @@ struct RpcSetupContext<'a, Node: FullNodeComponents, EthApi: EthApiTypes> {
     on_rpc_started: Box<dyn OnRpcStarted<Node, EthApi>>,
     engine_events: EventSender<BeaconConsensusEngineEvent<<Node::Types as NodeTypes>::Primitives>>,
     engine_handle: BeaconConsensusEngineHandle<<Node::Types as NodeTypes>::Payload>,
+    local_miner: Option<LocalMinerHandle>,
 }
 
 /// Node add-ons containing RPC server configuration, with customizable eth API handler.
@@ -658,6 +668,7 @@ where
             on_rpc_started,
             engine_events,
             engine_handle,
+            local_miner,
         } = setup_ctx;
 
         let server_config = config.rpc.rpc_server_config().set_rpc_middleware(rpc_middleware);
@@ -680,6 +691,7 @@ where
             rpc_registry: registry,
             engine_events,
             engine_handle,
+            local_miner,
         })
     }
 
@@ -724,6 +736,7 @@ where
             on_rpc_started,
             engine_events,
             engine_handle,
+            local_miner,
         } = setup_ctx;
 
         let server_config = config.rpc.rpc_server_config().set_rpc_middleware(rpc_middleware);
@@ -760,6 +773,7 @@ where
             rpc_registry: registry,
             engine_events,
             beacon_engine_handle: engine_handle,
+            local_miner,
         })
     }
 
@@ -798,7 +812,11 @@ where
         let eth_api = eth_api_builder.build_eth_api(ctx).await?;
 
         let auth_config = config.rpc.auth_server_config(jwt_secret)?;
-        let module_config = config.rpc.transport_rpc_module_config();
+        let mut module_config = config.rpc.transport_rpc_module_config();
+        if let Some(rpc_module_config) = module_config.config_mut() {
+            *rpc_module_config.debug_mut() =
+                config.rpc.debug_config(config.datadir().debug_traces());
+        }
         debug!(target: "reth::cli", http=?module_config.http(), ws=?module_config.ws(), "Using RPC module config");
 
         let (mut modules, mut auth_module, registry) = RpcModuleBuilder::default()
@@ -810,10 +828,18 @@ where
             .with_consensus(node.consensus().clone())
             .build_with_auth_server(module_config, engine_api, eth_api);
 
-        // in dev mode we generate 20 random dev-signer accounts
-        if config.dev.dev {
+        // in dev mode we generate 20 random dev-signer accounts and serve the `anvil_` namespace
+        // for the local miner
+        let local_miner = if config.dev.dev {
             registry.eth_api().with_dev_accounts();
-        }
+
+            let miner = LocalMinerHandle::new();
+            let anvil = AnvilApi::new(registry.eth_api().clone(), miner.clone());
+            modules.merge_if_module_configured(RethRpcModule::Anvil, anvil.into_rpc())?;
+            Some(miner)
+        } else {
+            None
+        };
 
         let mut registry = RpcRegistry { registry };
         let ctx = RpcContext {
@@ -843,6 +869,7 @@ where
             on_rpc_started,
             engine_events,
             engine_handle: beacon_engine_handle,
+            local_miner,
         })
     }
 
@@ -12,6 +12,7 @@ use reth_downloaders::{
 };
 use reth_evm::ConfigureEvm;
 use reth_exex::ExExManagerHandle;
+use reth_network::snap_client::SnapFetchClient;
 use reth_network_p2p::{
     bodies::downloader::BodyDownloader, headers::downloader::HeaderDownloader, BlockClient,
 };
@@ -19,8 +20,9 @@ use reth_node_api::HeaderTy;
 use reth_provider::{providers::ProviderNodeTypes, ProviderFactory};
 use reth_stages::{
     prelude::DefaultStages,
-    stages::{EraImportSource, ExecutionStage},
-    Pipeline, StageSet,
+    sets::SnapSyncStages,
+    stages::{EraImportSource, ExecutionStage, SnapSyncStage},
+    Pipeline, StageSet, StageSetBuilder,
 };
 use reth_static_file::StaticFileProducer;
 use reth_tasks::TaskExecutor;
@@ -42,6 +44,7 @@ pub fn build_networked_pipeline<N, Client, Evm>(
     evm_config: Evm,
     exex_manager_handle: ExExManagerHandle<N::Primitives>,
     era_import_source: Option<EraImportSource>,
+    snap_client: Option<SnapFetchClient>,
 ) -> eyre::Result<Pipeline<N>>
 where
     N: ProviderNodeTypes,
@@ -70,12 +73,15 @@ where
         evm_config,
         exex_manager_handle,
         era_import_source,
+        snap_client,
     )?;
 
     Ok(pipeline)
 }
 
 /// Builds the [Pipeline] with the given [`ProviderFactory`] and downloaders.
+///
+/// If a snap client is given, the state is snap synced before the execution stage.
 #[expect(clippy::too_many_arguments)]
 pub fn build_pipeline<N, H, B, Evm>(
     provider_factory: ProviderFactory<N>,
@@ -90,6 +96,7 @@ pub fn build_pipeline<N, H, B, Evm>(
     evm_config: Evm,
     exex_manager_handle: ExExManagerHandle<N::Primitives>,
     era_import_source: Option<EraImportSource>,
+    snap_client: Option<SnapFetchClient>,
 ) -> eyre::Result<Pipeline<N>>
 where
     N: ProviderNodeTypes,
@@ -108,29 +115,34 @@ where
 
     let prune_modes = prune_config.map(|prune| prune.segments).unwrap_or_default();
 
+    let default_stages = DefaultStages::new(
+        provider_factory.clone(),
+        tip_rx,
+        Arc::clone(&consensus),
+        header_downloader,
+        body_downloader,
+        evm_config.clone(),
+        stage_config.clone(),
+        prune_modes,
+        era_import_source,
+    );
+    let stages: StageSetBuilder<_> = match snap_client {
+        Some(snap_client) => {
+            SnapSyncStages::new(default_stages, SnapSyncStage::new(snap_client)).builder()
+        }
+        None => default_stages.builder(),
+    };
+
     let pipeline = builder
         .with_tip_sender(tip_tx)
         .with_metrics_tx(metrics_tx)
-        .add_stages(
-            DefaultStages::new(
-                provider_factory.clone(),
-                tip_rx,
-                Arc::clone(&consensus),
-                header_downloader,
-                body_downloader,
-                evm_config.clone(),
-                stage_config.clone(),
-                prune_modes,
-                era_import_source,
-            )
-            .set(ExecutionStage::new(
-                evm_config,
-                consensus,
-                stage_config.execution.into(),
-                stage_config.execution_external_clean_threshold(),
-                exex_manager_handle,
-            )),
-        )
+        .add_stages(stages.set(ExecutionStage::new(
+            evm_config,
+            consensus,
+            stage_config.execution.into(),
+            stage_config.execution_external_clean_threshold(),
+            exex_manager_handle,
+        )))
         .build(provider_factory, static_file_producer);
 
     Ok(pipeline)
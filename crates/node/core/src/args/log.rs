@@ -8,6 +8,8 @@ use reth_tracing::{
 };
 use std::{fmt, fmt::Display};
 use tracing::{level_filters::LevelFilter, Level};
+#[cfg(feature = "otlp")]
+use url::Url;
 /// Constant to convert megabytes to bytes
 const MB_TO_BYTES: u64 = 1024 * 1024;
 
@@ -66,6 +68,41 @@ pub struct LogArgs {
         default_value_t = ColorMode::Always
     )]
     pub color: ColorMode,
+
+    /// Export spans to an `OpenTelemetry` collector at the given OTLP/HTTP endpoint.
+    ///
+    /// If no endpoint is given, spans are exported to a collector on the local machine.
+    #[cfg(feature = "otlp")]
+    #[arg(
+        long = "tracing.otlp",
+        value_name = "URL",
+        global = true,
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = reth_tracing::DEFAULT_OTLP_ENDPOINT
+    )]
+    pub otlp: Option<Url>,
+
+    /// The filter to use for spans exported to the `OpenTelemetry` collector.
+    #[cfg(feature = "otlp")]
+    #[arg(
+        long = "tracing.otlp.filter",
+        value_name = "FILTER",
+        global = true,
+        default_value = "debug"
+    )]
+    pub otlp_filter: String,
+
+    /// The service name that identifies this node in the exported spans.
+    #[cfg(feature = "otlp")]
+    #[arg(
+        long = "tracing.otlp.service-name",
+        value_name = "NAME",
+        global = true,
+        default_value = "reth"
+    )]
+    pub otlp_service_name: String,
+
     /// The verbosity settings for the tracer.
     #[command(flatten)]
     pub verbosity: Verbosity,
@@ -123,6 +160,15 @@ impl LogArgs {
             tracer = tracer.with_file(file, info);
         }
 
+        #[cfg(feature = "otlp")]
+        if let Some(endpoint) = &self.otlp {
+            tracer = tracer.with_otlp(reth_tracing::OtlpInfo::new(
+                self.otlp_service_name.clone(),
+                endpoint.clone(),
+                self.otlp_filter.clone(),
+            ));
+        }
+
         let guard = tracer.init_with_layers(layers)?;
         Ok(guard)
     }
@@ -187,3 +233,36 @@ impl Verbosity {
         }
     }
 }
+
+#[cfg(all(test, feature = "otlp"))]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    /// A helper type to parse Args more easily
+    #[derive(Parser)]
+    struct CommandParser<T: Args> {
+        #[command(flatten)]
+        args: T,
+    }
+
+    #[test]
+    fn parse_otlp_args() {
+        let args = CommandParser::<LogArgs>::parse_from(["reth"]).args;
+        assert_eq!(args.otlp, None);
+
+        let args = CommandParser::<LogArgs>::parse_from(["reth", "--tracing.otlp"]).args;
+        assert_eq!(args.otlp, Some(reth_tracing::DEFAULT_OTLP_ENDPOINT.parse().unwrap()));
+
+        let args = CommandParser::<LogArgs>::parse_from([
+            "reth",
+            "--tracing.otlp=http://collector:4318/v1/traces",
+            "--tracing.otlp.filter",
+            "engine=trace",
+        ])
+        .args;
+        assert_eq!(args.otlp, Some("http://collector:4318/v1/traces".parse().unwrap()));
+        assert_eq!(args.otlp_filter, "engine=trace");
+        assert_eq!(args.otlp_service_name, "reth");
+    }
+}
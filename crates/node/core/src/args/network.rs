@@ -88,7 +88,7 @@ pub struct NetworkArgs {
     #[arg(long, verbatim_doc_comment)]
     pub no_persist_peers: bool,
 
-    /// NAT resolution method (any|none|upnp|publicip|extip:\<IP\>)
+    /// NAT resolution method (any|none|upnp|natpmp|natpmp:\<GATEWAY\>|publicip|extip:\<IP\>)
     #[arg(long, default_value = "any")]
     pub nat: NatResolver,
 
@@ -161,6 +161,21 @@ pub struct NetworkArgs {
     /// The policy determines which peers transactions are gossiped to.
     #[arg(long = "tx-propagation-policy", default_value_t = TransactionPropagationKind::All)]
     pub tx_propagation_policy: TransactionPropagationKind,
+
+    /// Serve the `snap/1` protocol to peers.
+    ///
+    /// This allows other clients to snap sync the state of the most recent blocks from this node.
+    #[arg(long)]
+    pub enable_snap_server: bool,
+
+    /// Download the state of a recent block over the `snap/1` protocol instead of executing all
+    /// blocks from genesis.
+    ///
+    /// This only has an effect on a node that hasn't executed any blocks yet. The receipts of the
+    /// blocks before the synced block are not downloaded, so receipts pruning must be configured,
+    /// e.g. with `--full` or `--prune.receipts.distance`.
+    #[arg(long)]
+    pub snap_sync: bool,
 }
 
 impl NetworkArgs {
@@ -342,7 +357,9 @@ impl Default for NetworkArgs {
             max_seen_tx_history: DEFAULT_MAX_COUNT_TRANSACTIONS_SEEN_BY_PEER,
             max_capacity_cache_txns_pending_fetch: DEFAULT_MAX_CAPACITY_CACHE_PENDING_FETCH,
             net_if: None,
-            tx_propagation_policy: TransactionPropagationKind::default()
+            tx_propagation_policy: TransactionPropagationKind::default(),
+            enable_snap_server: false,
+            snap_sync: false,
         }
     }
 }
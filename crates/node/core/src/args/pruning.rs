@@ -1,11 +1,16 @@
 //! Pruning and full node arguments
 
-use crate::{args::error::ReceiptsLogError, primitives::EthereumHardfork};
+use crate::{
+    args::{database::parse_byte_size, error::ReceiptsLogError},
+    primitives::EthereumHardfork,
+};
 use alloy_primitives::{Address, BlockNumber};
 use clap::{builder::RangedU64ValueParser, Args};
 use reth_chainspec::EthereumHardforks;
 use reth_config::config::PruneConfig;
-use reth_prune_types::{PruneMode, PruneModes, ReceiptsLogPruneConfig, MINIMUM_PRUNING_DISTANCE};
+use reth_prune_types::{
+    PruneBudget, PruneMode, PruneModes, ReceiptsLogPruneConfig, MINIMUM_PRUNING_DISTANCE,
+};
 use std::collections::BTreeMap;
 
 /// Parameters for pruning and full node
@@ -103,6 +108,46 @@ pub struct PruningArgs {
     /// pruned.
     #[arg(long = "prune.bodies.before", value_name = "BLOCK_NUMBER", conflicts_with_all = &["bodies_distance", "bodies_pre_merge"])]
     pub bodies_before: Option<BlockNumber>,
+
+    // Changesets
+    /// Prune account and storage changesets before the `head-N` block number, keeping the history
+    /// indices. In other words, keep last N + 1 blocks.
+    #[arg(long = "prune.changesets.distance", value_name = "BLOCKS", conflicts_with_all = &["changesets_before"])]
+    pub changesets_distance: Option<u64>,
+    /// Prune account and storage changesets before the specified block number, keeping the history
+    /// indices. The specified block number is not pruned.
+    #[arg(long = "prune.changesets.before", value_name = "BLOCK_NUMBER", conflicts_with_all = &["changesets_distance"])]
+    pub changesets_before: Option<BlockNumber>,
+
+    // Block Meta
+    /// Prune block meta static files before the merge block.
+    #[arg(long = "prune.blockmeta.pre-merge", conflicts_with_all = &["block_meta_distance", "block_meta_before"])]
+    pub block_meta_pre_merge: bool,
+    /// Prune block meta static files before the `head-N` block number. In other words, keep last
+    /// N + 1 blocks.
+    #[arg(long = "prune.blockmeta.distance", value_name = "BLOCKS", conflicts_with_all = &["block_meta_pre_merge", "block_meta_before"])]
+    pub block_meta_distance: Option<u64>,
+    /// Prune block meta static files before the specified block number. The specified block
+    /// number is not pruned.
+    #[arg(long = "prune.blockmeta.before", value_name = "BLOCK_NUMBER", conflicts_with_all = &["block_meta_pre_merge", "block_meta_distance"])]
+    pub block_meta_before: Option<BlockNumber>,
+
+    // Bytecodes
+    /// Prune bytecodes that are not referenced by the state or the changesets after the `head-N`
+    /// block number. In other words, keep the bytecodes of the last N + 1 blocks.
+    #[arg(long = "prune.bytecodes.distance", value_name = "BLOCKS", conflicts_with_all = &["bytecodes_before"])]
+    pub bytecodes_distance: Option<u64>,
+    /// Prune bytecodes that are not referenced by the state or the changesets from the specified
+    /// block number onwards.
+    #[arg(long = "prune.bytecodes.before", value_name = "BLOCK_NUMBER", conflicts_with_all = &["bytecodes_distance"])]
+    pub bytecodes_before: Option<BlockNumber>,
+
+    // Disk budget
+    /// Maximum size of the data directory (e.g., 800GB, 2TB). When exceeded, the segments
+    /// configured with a distance are pruned beyond it, one segment at a time, down to their
+    /// minimum distance.
+    #[arg(long = "prune.disk-budget", value_name = "SIZE", value_parser = parse_byte_size)]
+    pub disk_budget: Option<usize>,
 }
 
 impl PruningArgs {
@@ -126,8 +171,12 @@ impl PruningArgs {
                     storage_history: Some(PruneMode::Distance(MINIMUM_PRUNING_DISTANCE)),
                     // TODO: set default to pre-merge block if available
                     bodies_history: None,
+                    changesets: None,
+                    block_meta: None,
+                    bytecodes: None,
                     receipts_log_filter: Default::default(),
                 },
+                budget: None,
             }
         }
 
@@ -153,6 +202,18 @@ impl PruningArgs {
         if let Some(mode) = self.storage_history_prune_mode() {
             config.segments.storage_history = Some(mode);
         }
+        if let Some(mode) = self.changesets_prune_mode() {
+            config.segments.changesets = Some(mode);
+        }
+        if let Some(mode) = self.block_meta_prune_mode(chain_spec) {
+            config.segments.block_meta = Some(mode);
+        }
+        if let Some(mode) = self.bytecodes_prune_mode() {
+            config.segments.bytecodes = Some(mode);
+        }
+        if let Some(max_size) = self.disk_budget {
+            config.budget = Some(PruneBudget::new(max_size as u64));
+        }
         if let Some(receipt_logs) =
             self.receipts_log_filter.as_ref().filter(|c| !c.is_empty()).cloned()
         {
@@ -246,6 +307,42 @@ impl PruningArgs {
             None
         }
     }
+
+    const fn changesets_prune_mode(&self) -> Option<PruneMode> {
+        if let Some(distance) = self.changesets_distance {
+            Some(PruneMode::Distance(distance))
+        } else if let Some(block_number) = self.changesets_before {
+            Some(PruneMode::Before(block_number))
+        } else {
+            None
+        }
+    }
+
+    fn block_meta_prune_mode<ChainSpec>(&self, chain_spec: &ChainSpec) -> Option<PruneMode>
+    where
+        ChainSpec: EthereumHardforks,
+    {
+        if self.block_meta_pre_merge {
+            chain_spec
+                .ethereum_fork_activation(EthereumHardfork::Paris)
+                .block_number()
+                .map(PruneMode::Before)
+        } else if let Some(distance) = self.block_meta_distance {
+            Some(PruneMode::Distance(distance))
+        } else {
+            self.block_meta_before.map(PruneMode::Before)
+        }
+    }
+
+    const fn bytecodes_prune_mode(&self) -> Option<PruneMode> {
+        if let Some(distance) = self.bytecodes_distance {
+            Some(PruneMode::Distance(distance))
+        } else if let Some(block_number) = self.bytecodes_before {
+            Some(PruneMode::Before(block_number))
+        } else {
+            None
+        }
+    }
 }
 
 /// Parses `,` separated pruning info into [`ReceiptsLogPruneConfig`].
@@ -320,6 +417,42 @@ mod tests {
         assert_eq!(args.receipts_log_filter, Some(config));
     }
 
+    #[test]
+    fn parse_changesets_block_meta_bytecodes() {
+        let args = CommandParser::<PruningArgs>::parse_from([
+            "reth",
+            "--prune.changesets.distance",
+            "20000",
+            "--prune.blockmeta.before",
+            "100",
+            "--prune.bytecodes.distance",
+            "30000",
+        ])
+        .args;
+        let config = args.prune_config(&**reth_chainspec::MAINNET).unwrap();
+        assert_eq!(config.segments.changesets, Some(PruneMode::Distance(20000)));
+        assert_eq!(config.segments.block_meta, Some(PruneMode::Before(100)));
+        assert_eq!(config.segments.bytecodes, Some(PruneMode::Distance(30000)));
+
+        let args =
+            CommandParser::<PruningArgs>::parse_from(["reth", "--prune.blockmeta.pre-merge"]).args;
+        let config = args.prune_config(&**reth_chainspec::MAINNET).unwrap();
+        assert_eq!(config.segments.block_meta, Some(PruneMode::Before(15537394)));
+    }
+
+    #[test]
+    fn parse_disk_budget() {
+        let args = CommandParser::<PruningArgs>::parse_from([
+            "reth",
+            "--full",
+            "--prune.disk-budget",
+            "2TB",
+        ])
+        .args;
+        let config = args.prune_config(&**reth_chainspec::MAINNET).unwrap();
+        assert_eq!(config.budget, Some(PruneBudget::new(2 * 1024 * 1024 * 1024 * 1024)));
+    }
+
     #[test]
     fn parse_receiptslogfilter() {
         let default_args = PruningArgs::default();
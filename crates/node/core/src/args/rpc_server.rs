@@ -15,7 +15,9 @@ use clap::{
 };
 use rand::Rng;
 use reth_cli_util::parse_ether_value;
-use reth_rpc_server_types::{constants, RethRpcModule, RpcModuleSelection};
+use reth_rpc_server_types::{
+    constants, RethRpcModule, RpcModuleSelection, RpcRateLimitConfig, RpcRateLimitRule,
+};
 
 use crate::args::{
     types::{MaxU32, ZeroAsNoneU64},
@@ -218,6 +220,36 @@ pub struct RpcServerArgs {
     #[arg(long = "builder.disallow", value_name = "PATH", value_parser = reth_cli_util::parsers::read_json_from_file::<HashSet<Address>>)]
     pub builder_disallow: Option<HashSet<Address>>,
 
+    /// Directory the `debug_standardTraceBlockToFile` and `debug_standardTraceBadBlockToFile`
+    /// methods write their trace files to.
+    ///
+    /// Defaults to `<DIR>/<CHAIN_ID>/debug_traces`.
+    #[arg(long = "rpc.debug-trace-dir", value_name = "PATH")]
+    pub rpc_debug_trace_dir: Option<PathBuf>,
+
+    /// Per client rate limit for HTTP and WS RPC calls, as
+    /// `<methods>=<requests_per_second>[/<burst>]`.
+    ///
+    /// `<methods>` is a method name (`eth_call`), a namespace (`debug`) or `*` for all methods.
+    /// Only the most specific rule applies to a call. Clients are identified by the `sub` claim of
+    /// their JWT, by the `--rpc.rate-limit.ip-header` header or by the IP address of the
+    /// connection.
+    ///
+    /// Example: `--rpc.rate-limit eth_call=10/20,debug=1,*=100`
+    #[arg(long = "rpc.rate-limit", value_name = "RULE", value_delimiter = ',')]
+    pub rpc_rate_limit: Vec<RpcRateLimitRule>,
+
+    /// Path to a TOML file with rate limit rules, see `--rpc.rate-limit`.
+    ///
+    /// Rules passed on the command line are added to the rules of the file.
+    #[arg(long = "rpc.rate-limit.config", value_name = "PATH", value_parser = read_rate_limit_config)]
+    pub rpc_rate_limit_config: Option<RpcRateLimitConfig>,
+
+    /// HTTP header that carries the client IP address for rate limiting, e.g. `x-forwarded-for`
+    /// when running behind a reverse proxy.
+    #[arg(long = "rpc.rate-limit.ip-header", value_name = "HEADER")]
+    pub rpc_rate_limit_ip_header: Option<String>,
+
     /// State cache configuration.
     #[command(flatten)]
     pub rpc_state_cache: RpcStateCacheArgs,
@@ -246,6 +278,12 @@ impl RpcServerArgs {
         self
     }
 
+    /// Configures modules for the WS-RPC server.
+    pub fn with_ws_api(mut self, ws_api: RpcModuleSelection) -> Self {
+        self.ws_api = Some(ws_api);
+        self
+    }
+
     /// Enables the Auth IPC
     pub const fn with_auth_ipc(mut self) -> Self {
         self.auth_ipc = true;
@@ -360,10 +398,21 @@ impl Default for RpcServerArgs {
             rpc_state_cache: RpcStateCacheArgs::default(),
             rpc_proof_permits: constants::DEFAULT_PROOF_PERMITS,
             builder_disallow: Default::default(),
+            rpc_debug_trace_dir: None,
+            rpc_rate_limit: Vec::new(),
+            rpc_rate_limit_config: None,
+            rpc_rate_limit_ip_header: None,
         }
     }
 }
 
+/// clap value parser that reads a [`RpcRateLimitConfig`] from a TOML file.
+fn read_rate_limit_config(path: &str) -> eyre::Result<RpcRateLimitConfig> {
+    let content =
+        std::fs::read_to_string(path).map_err(|err| eyre::eyre!("failed to read {path}: {err}"))?;
+    toml::from_str(&content).map_err(|err| eyre::eyre!("failed to parse {path}: {err}"))
+}
+
 /// clap value parser for [`RpcModuleSelection`].
 #[derive(Clone, Debug, Default)]
 #[non_exhaustive]
@@ -121,6 +121,11 @@ pub struct TxPoolArgs {
     #[arg(long = "txpool.lifetime", value_parser = parse_duration_from_secs_or_ms, default_value = "10800", value_name = "DURATION")]
     pub max_queued_lifetime: Duration,
 
+    /// Maximum number of bundles submitted via `eth_sendBundle` and `mev_sendBundle` that are
+    /// kept for local block building. Bundles are disabled if `0`.
+    #[arg(long = "txpool.max-bundles", value_name = "COUNT", default_value_t = 0)]
+    pub max_bundles: usize,
+
     /// Path to store the local transaction backup at, to survive node restarts.
     #[arg(long = "txpool.transactions-backup", alias = "txpool.journal", value_name = "PATH")]
     pub transactions_backup_path: Option<std::path::PathBuf>,
@@ -163,6 +168,7 @@ impl Default for TxPoolArgs {
             new_tx_listener_buffer_size: NEW_TX_LISTENER_BUFFER_SIZE,
             max_new_pending_txs_notifications: MAX_NEW_PENDING_TXS_NOTIFICATIONS,
             max_queued_lifetime: MAX_QUEUED_TRANSACTION_LIFETIME,
+            max_bundles: 0,
             transactions_backup_path: None,
             disable_transactions_backup: false,
         }
@@ -207,6 +213,7 @@ impl RethTransactionPoolConfig for TxPoolArgs {
             new_tx_listener_buffer_size: self.new_tx_listener_buffer_size,
             max_new_pending_txs_notifications: self.max_new_pending_txs_notifications,
             max_queued_lifetime: self.max_queued_lifetime,
+            max_bundles: self.max_bundles,
         }
     }
 }
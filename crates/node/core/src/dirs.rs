@@ -351,10 +351,23 @@ impl<D> ChainPath<D> {
         self.data_dir().join("invalid_block_hooks")
     }
 
+    /// Returns the path to the directory the `debug_standardTrace*ToFile` RPC methods write their
+    /// trace files to.
+    ///
+    /// `<DIR>/<CHAIN_ID>/debug_traces`
+    pub fn debug_traces(&self) -> PathBuf {
+        self.data_dir().join("debug_traces")
+    }
+
     /// Returns the path to the ExEx WAL directory for this chain.
     pub fn exex_wal(&self) -> PathBuf {
         self.data_dir().join("exex/wal")
     }
+
+    /// Returns the path to the ExEx backfill checkpoints directory for this chain.
+    pub fn exex_backfill(&self) -> PathBuf {
+        self.data_dir().join("exex/backfill")
+    }
 }
 
 impl<D> AsRef<Path> for ChainPath<D> {
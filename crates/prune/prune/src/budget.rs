@@ -0,0 +1,271 @@
+//! Disk budget of the pruner.
+
+use crate::segments::Segment;
+use alloy_primitives::BlockNumber;
+use reth_provider::{ProviderResult, PruneCheckpointReader};
+use reth_prune_types::{PruneBudget, PruneMode, PrunePurpose, PruneSegment};
+use tracing::{debug, warn};
+
+/// Percentage of the budget that the disk usage has to fall below before the distances are
+/// relaxed again, so that they don't flap around the budget.
+const RELAX_THRESHOLD_PERCENT: u64 = 80;
+
+/// Distances of the segments that the pruner chose to keep the data directory within the
+/// [`PruneBudget`].
+#[derive(Debug)]
+pub(crate) struct DiskBudget {
+    /// Maximum size of the data directory in bytes.
+    pub(crate) max_size: u64,
+    /// Segments under the budget in priority order.
+    segments: Vec<BudgetSegment>,
+    /// Whether the distances were restored from the prune checkpoints.
+    restored: bool,
+}
+
+/// A segment under the [`DiskBudget`].
+#[derive(Debug)]
+struct BudgetSegment {
+    segment: PruneSegment,
+    /// Distance the segment is configured with, which is never exceeded.
+    configured: u64,
+    /// Distance the segment is currently pruned with.
+    distance: u64,
+}
+
+impl DiskBudget {
+    /// Creates a new [`DiskBudget`] for the budget segments that are configured with a
+    /// [`PruneMode::Distance`].
+    pub(crate) fn new<Provider>(
+        budget: PruneBudget,
+        segments: &[Box<dyn Segment<Provider>>],
+    ) -> Self {
+        let segments = budget
+            .segments
+            .iter()
+            .filter_map(|budget_segment| {
+                segments.iter().find_map(|segment| {
+                    match (segment.segment(), segment.purpose(), segment.mode()) {
+                        (segment, PrunePurpose::User, Some(PruneMode::Distance(distance)))
+                            if segment == *budget_segment =>
+                        {
+                            Some(BudgetSegment { segment, configured: distance, distance })
+                        }
+                        _ => None,
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        if segments.is_empty() {
+            warn!(target: "pruner", segments = ?budget.segments, "None of the disk budget segments is configured with a distance, the budget can't be enforced");
+        }
+
+        Self { max_size: budget.max_size, segments, restored: false }
+    }
+
+    /// Returns the prune mode chosen for the segment, if it's under the budget.
+    pub(crate) fn prune_mode(&self, segment: PruneSegment) -> Option<PruneMode> {
+        self.segments
+            .iter()
+            .find(|budget_segment| budget_segment.segment == segment)
+            .map(|budget_segment| PruneMode::Distance(budget_segment.distance))
+    }
+
+    /// Returns the segments under the budget with their current distances.
+    pub(crate) fn distances(&self) -> impl Iterator<Item = (PruneSegment, u64)> + '_ {
+        self.segments.iter().map(|budget_segment| (budget_segment.segment, budget_segment.distance))
+    }
+
+    /// Returns `true` if the disk usage is far enough below the budget for the distances to be
+    /// relaxed.
+    pub(crate) const fn is_well_under(&self, disk_usage: u64) -> bool {
+        disk_usage < self.max_size / 100 * RELAX_THRESHOLD_PERCENT
+    }
+
+    /// Restores the distances chosen before the restart of the node from the prune checkpoints,
+    /// if they're lower than the configured ones.
+    pub(crate) fn restore<Provider: PruneCheckpointReader>(
+        &mut self,
+        provider: &Provider,
+    ) -> ProviderResult<()> {
+        if self.restored {
+            return Ok(())
+        }
+
+        for BudgetSegment { segment, distance, .. } in &mut self.segments {
+            if let Some(PruneMode::Distance(checkpoint_distance)) =
+                provider.get_prune_checkpoint(*segment)?.map(|checkpoint| checkpoint.prune_mode)
+            {
+                *distance = checkpoint_distance.min(*distance);
+            }
+        }
+        self.restored = true;
+
+        Ok(())
+    }
+
+    /// Halves the distance of the first segment in priority order that isn't at its minimum
+    /// distance yet. The distance is never higher than the tip, so that the first reduction
+    /// takes effect immediately.
+    ///
+    /// Returns the segment and its new distance, or `None` if all segments are at their minimum
+    /// distance.
+    pub(crate) fn tighten(&mut self, tip_block_number: BlockNumber) -> Option<(PruneSegment, u64)> {
+        for BudgetSegment { segment, distance, .. } in &mut self.segments {
+            let min_distance = segment.min_blocks(PrunePurpose::User);
+            let new_distance = ((*distance).min(tip_block_number) / 2).max(min_distance);
+            if new_distance < *distance {
+                debug!(target: "pruner", ?segment, %distance, %new_distance, "Tightening the distance of the segment to fit the disk budget");
+                *distance = new_distance;
+                return Some((*segment, new_distance))
+            }
+        }
+
+        None
+    }
+
+    /// Doubles the distance of the last segment in priority order that is below its configured
+    /// distance, so that the segments are relaxed in the reverse order they're tightened in. The
+    /// distance never exceeds the configured one.
+    ///
+    /// Returns the segment and its new distance, or `None` if all segments are at their
+    /// configured distance.
+    pub(crate) fn relax(&mut self) -> Option<(PruneSegment, u64)> {
+        for BudgetSegment { segment, configured, distance } in self.segments.iter_mut().rev() {
+            if *distance < *configured {
+                let new_distance = distance.saturating_mul(2).max(1).min(*configured);
+                debug!(target: "pruner", ?segment, %distance, %new_distance, "Relaxing the distance of the segment under the disk budget");
+                *distance = new_distance;
+                return Some((*segment, new_distance))
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DiskBudget;
+    use crate::segments::{AccountHistory, Segment, SenderRecovery, UserReceipts};
+    use reth_provider::{
+        test_utils::{create_test_provider_factory, MockNodeTypesWithDB},
+        DatabaseProviderFactory, ProviderFactory, PruneCheckpointWriter,
+    };
+    use reth_prune_types::{
+        PruneBudget, PruneCheckpoint, PruneMode, PruneSegment, MINIMUM_PRUNING_DISTANCE,
+    };
+
+    #[test]
+    fn tighten() {
+        let factory = create_test_provider_factory();
+        let segments: Vec<
+            Box<
+                dyn Segment<
+                    <ProviderFactory<MockNodeTypesWithDB> as DatabaseProviderFactory>::ProviderRW,
+                >,
+            >,
+        > = vec![
+            Box::new(SenderRecovery::new(PruneMode::Distance(100))),
+            Box::new(UserReceipts::new(PruneMode::Full)),
+            Box::new(AccountHistory::new(PruneMode::Distance(20_000))),
+        ];
+        let mut budget = DiskBudget::new(
+            PruneBudget {
+                max_size: 0,
+                segments: vec![
+                    PruneSegment::AccountHistory,
+                    PruneSegment::Receipts,
+                    PruneSegment::SenderRecovery,
+                ],
+            },
+            &segments,
+        );
+
+        // Receipts aren't configured with a distance, so they are not under the budget.
+        assert_eq!(
+            budget.distances().collect::<Vec<_>>(),
+            [(PruneSegment::AccountHistory, 20_000), (PruneSegment::SenderRecovery, 100)]
+        );
+        assert_eq!(budget.prune_mode(PruneSegment::Receipts), None);
+
+        // The distance is capped by the tip and never goes below the minimum distance of the
+        // segment. Once it's reached, the next segment is tightened.
+        assert_eq!(
+            budget.tighten(15_000),
+            Some((PruneSegment::AccountHistory, MINIMUM_PRUNING_DISTANCE))
+        );
+        assert_eq!(budget.tighten(15_000), Some((PruneSegment::SenderRecovery, 50)));
+        for _ in 0..6 {
+            budget.tighten(15_000);
+        }
+        assert_eq!(budget.prune_mode(PruneSegment::SenderRecovery), Some(PruneMode::Distance(0)));
+        assert_eq!(budget.tighten(15_000), None);
+
+        // Distances chosen before a restart are restored from the checkpoints.
+        let provider = factory.database_provider_rw().unwrap();
+        provider
+            .save_prune_checkpoint(
+                PruneSegment::AccountHistory,
+                PruneCheckpoint {
+                    block_number: Some(1),
+                    tx_number: None,
+                    prune_mode: PruneMode::Distance(15_000),
+                },
+            )
+            .unwrap();
+        let mut budget = DiskBudget::new(PruneBudget::new(0), &segments);
+        budget.restore(&provider).unwrap();
+        assert_eq!(
+            budget.distances().collect::<Vec<_>>(),
+            [(PruneSegment::SenderRecovery, 100), (PruneSegment::AccountHistory, 15_000)]
+        );
+    }
+
+    #[test]
+    fn relax() {
+        let segments: Vec<
+            Box<
+                dyn Segment<
+                    <ProviderFactory<MockNodeTypesWithDB> as DatabaseProviderFactory>::ProviderRW,
+                >,
+            >,
+        > = vec![
+            Box::new(SenderRecovery::new(PruneMode::Distance(100))),
+            Box::new(AccountHistory::new(PruneMode::Distance(20_000))),
+        ];
+        let mut budget = DiskBudget::new(
+            PruneBudget {
+                max_size: 1_000,
+                segments: vec![PruneSegment::AccountHistory, PruneSegment::SenderRecovery],
+            },
+            &segments,
+        );
+
+        // Only usage well under the budget relaxes the distances.
+        assert!(!budget.is_well_under(900));
+        assert!(budget.is_well_under(700));
+
+        // Nothing to relax before the distances are tightened.
+        assert_eq!(budget.relax(), None);
+
+        budget.tighten(15_000);
+        budget.tighten(15_000);
+        assert_eq!(
+            budget.distances().collect::<Vec<_>>(),
+            [
+                (PruneSegment::AccountHistory, MINIMUM_PRUNING_DISTANCE),
+                (PruneSegment::SenderRecovery, 50)
+            ]
+        );
+
+        // The last tightened segment is relaxed first, and the distances never exceed the
+        // configured ones.
+        assert_eq!(budget.relax(), Some((PruneSegment::SenderRecovery, 100)));
+        assert_eq!(budget.relax(), Some((PruneSegment::AccountHistory, 20_000)));
+        assert_eq!(budget.relax(), None);
+        assert_eq!(
+            budget.distances().collect::<Vec<_>>(),
+            [(PruneSegment::AccountHistory, 20_000), (PruneSegment::SenderRecovery, 100)]
+        );
+    }
+}
@@ -9,7 +9,7 @@ use reth_provider::{
     providers::StaticFileProvider, BlockReader, DBProvider, DatabaseProviderFactory,
     NodePrimitivesProvider, PruneCheckpointWriter, StaticFileProviderFactory,
 };
-use reth_prune_types::PruneModes;
+use reth_prune_types::{PruneBudget, PruneModes};
 use std::time::Duration;
 use tokio::sync::watch;
 
@@ -20,6 +20,8 @@ pub struct PrunerBuilder {
     block_interval: usize,
     /// Pruning configuration for every part of the data that can be pruned.
     segments: PruneModes,
+    /// Disk budget that the pruner keeps the data directory within.
+    budget: Option<PruneBudget>,
     /// The delete limit for pruner, per run.
     delete_limit: usize,
     /// Time a pruner job can run before timing out.
@@ -37,6 +39,7 @@ impl PrunerBuilder {
         Self::default()
             .block_interval(pruner_config.block_interval)
             .segments(pruner_config.segments)
+            .budget(pruner_config.budget)
     }
 
     /// Sets the minimum pruning interval measured in blocks.
@@ -51,6 +54,14 @@ impl PrunerBuilder {
         self
     }
 
+    /// Sets the disk budget that the pruner keeps the data directory within.
+    ///
+    /// Only applies to pruners built with [`PrunerBuilder::build_with_provider_factory`].
+    pub fn budget(mut self, budget: Option<PruneBudget>) -> Self {
+        self.budget = budget;
+        self
+    }
+
     /// Sets the delete limit for pruner, per run.
     pub const fn delete_limit(mut self, prune_delete_limit: usize) -> Self {
         self.delete_limit = prune_delete_limit;
@@ -91,14 +102,19 @@ impl PrunerBuilder {
         let segments =
             SegmentSet::from_components(provider_factory.static_file_provider(), self.segments);
 
-        Pruner::new_with_factory(
+        let pruner = Pruner::new_with_factory(
             provider_factory,
             segments.into_vec(),
             self.block_interval,
             self.delete_limit,
             self.timeout,
             self.finished_exex_height,
-        )
+        );
+
+        match self.budget {
+            Some(budget) => pruner.with_disk_budget(budget),
+            None => pruner,
+        }
     }
 
     /// Builds a [Pruner] from the current configuration with the given static file provider.
@@ -130,6 +146,7 @@ impl Default for PrunerBuilder {
         Self {
             block_interval: 5,
             segments: PruneModes::none(),
+            budget: None,
             delete_limit: MAINNET_PRUNE_DELETE_LIMIT,
             timeout: None,
             finished_exex_height: watch::channel(FinishedExExHeight::NoExExs).1,
@@ -9,6 +9,7 @@
 #![allow(missing_docs)]
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 
+mod budget;
 mod builder;
 mod db_ext;
 mod error;
@@ -10,6 +10,10 @@ use std::collections::HashMap;
 pub(crate) struct Metrics {
     /// Pruning duration
     pub(crate) duration_seconds: Histogram,
+    /// Disk usage of the database and the static files, measured when a disk budget is set
+    pub(crate) disk_usage_bytes: Gauge,
+    /// Disk budget of the data directory
+    pub(crate) disk_budget_bytes: Gauge,
     #[metric(skip)]
     prune_segments: HashMap<PruneSegment, PrunerSegmentMetrics>,
 }
@@ -34,4 +38,6 @@ pub(crate) struct PrunerSegmentMetrics {
     pub(crate) duration_seconds: Histogram,
     /// Highest pruned block per segment
     pub(crate) highest_pruned_block: Gauge,
+    /// Distance retained per segment under the disk budget
+    pub(crate) budget_distance: Gauge,
 }
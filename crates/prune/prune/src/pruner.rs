@@ -1,19 +1,21 @@
 //! Support for pruning.
 
 use crate::{
+    budget::DiskBudget,
     segments::{PruneInput, Segment},
     Metrics, PruneLimiter, PrunerError, PrunerEvent,
 };
 use alloy_primitives::BlockNumber;
 use reth_exex_types::FinishedExExHeight;
 use reth_provider::{
-    DBProvider, DatabaseProviderFactory, PruneCheckpointReader, PruneCheckpointWriter,
+    DBProvider, DatabaseProviderFactory, DiskUsageReader, PruneCheckpointReader,
+    PruneCheckpointWriter,
 };
-use reth_prune_types::{PruneProgress, PrunedSegmentInfo, PrunerOutput};
+use reth_prune_types::{PruneBudget, PruneProgress, PrunedSegmentInfo, PrunerOutput};
 use reth_tokio_util::{EventSender, EventStream};
 use std::time::{Duration, Instant};
 use tokio::sync::watch;
-use tracing::debug;
+use tracing::{debug, info, warn};
 
 /// Result of [`Pruner::run`] execution.
 pub type PrunerResult = Result<PrunerOutput, PrunerError>;
@@ -43,6 +45,11 @@ pub struct Pruner<Provider, PF> {
     timeout: Option<Duration>,
     /// The finished height of all `ExEx`'s.
     finished_exex_height: watch::Receiver<FinishedExExHeight>,
+    /// Disk budget, and the distances of the segments chosen to stay within it. Only enforced by
+    /// [`Pruner::run`].
+    budget: Option<DiskBudget>,
+    /// Whether the previous run was interrupted before all segments were pruned to their targets.
+    has_more_data: bool,
     #[doc(hidden)]
     metrics: Metrics,
     event_sender: EventSender<PrunerEvent>,
@@ -65,6 +72,8 @@ impl<Provider> Pruner<Provider, ()> {
             delete_limit,
             timeout,
             finished_exex_height,
+            budget: None,
+            has_more_data: false,
             metrics: Metrics::default(),
             event_sender: Default::default(),
         }
@@ -92,12 +101,26 @@ where
             delete_limit,
             timeout,
             finished_exex_height,
+            budget: None,
+            has_more_data: false,
             metrics: Metrics::default(),
             event_sender: Default::default(),
         }
     }
 }
 
+impl<Provider, S> Pruner<Provider, S> {
+    /// Sets the disk budget that the pruner keeps the data directory within, by reducing the
+    /// distances of the budget segments that are configured with a
+    /// [`PruneMode::Distance`](reth_prune_types::PruneMode::Distance).
+    ///
+    /// The budget is only enforced by [`Pruner::run`], which can measure the disk usage.
+    pub fn with_disk_budget(mut self, budget: PruneBudget) -> Self {
+        self.budget = Some(DiskBudget::new(budget, &self.segments));
+        self
+    }
+}
+
 impl<Provider, S> Pruner<Provider, S>
 where
     Provider: PruneCheckpointReader + PruneCheckpointWriter,
@@ -143,6 +166,7 @@ where
             self.prune_segments(provider, tip_block_number, &mut limiter)?;
 
         self.previous_tip_block_number = Some(tip_block_number);
+        self.has_more_data = !output.progress.is_finished();
 
         let elapsed = start.elapsed();
         self.metrics.duration_seconds.record(elapsed);
@@ -192,8 +216,14 @@ where
                 break
             }
 
-            if let Some((to_block, prune_mode)) = segment
-                .mode()
+            // Segments under the disk budget are pruned with the distance chosen to stay within it.
+            let mode = self
+                .budget
+                .as_ref()
+                .filter(|_| segment.purpose().is_user())
+                .and_then(|budget| budget.prune_mode(segment.segment()))
+                .or_else(|| segment.mode());
+            if let Some((to_block, prune_mode)) = mode
                 .map(|mode| {
                     mode.prune_target_block(tip_block_number, segment.segment(), segment.purpose())
                 })
@@ -318,7 +348,8 @@ where
 
 impl<PF> Pruner<PF::ProviderRW, PF>
 where
-    PF: DatabaseProviderFactory<ProviderRW: PruneCheckpointWriter + PruneCheckpointReader>,
+    PF: DatabaseProviderFactory<ProviderRW: PruneCheckpointWriter + PruneCheckpointReader>
+        + DiskUsageReader,
 {
     /// Run the pruner. This will only prune data up to the highest finished ExEx height, if there
     /// are no ExExes.
@@ -327,10 +358,48 @@ where
     /// to prune.
     pub fn run(&mut self, tip_block_number: BlockNumber) -> PrunerResult {
         let provider = self.provider_factory.database_provider_rw()?;
+        self.update_disk_budget(&provider, tip_block_number)?;
         let result = self.run_with_provider(&provider, tip_block_number);
         provider.commit()?;
         result
     }
+
+    /// Measures the disk usage and, if it exceeds the [`DiskBudget`], reduces the distance of the
+    /// next segment under the budget. If the usage is well under the budget, a reduced distance is
+    /// increased back toward the configured one instead.
+    ///
+    /// Distances are not changed while the previous run still has data to prune, because the disk
+    /// usage doesn't reflect the current distances yet.
+    fn update_disk_budget(
+        &mut self,
+        provider: &PF::ProviderRW,
+        tip_block_number: BlockNumber,
+    ) -> Result<(), PrunerError> {
+        let Some(budget) = &mut self.budget else { return Ok(()) };
+        budget.restore(provider)?;
+
+        let disk_usage = self.provider_factory.disk_usage()?.total();
+        self.metrics.disk_usage_bytes.set(disk_usage as f64);
+        self.metrics.disk_budget_bytes.set(budget.max_size as f64);
+
+        if disk_usage > budget.max_size && !self.has_more_data {
+            if let Some((segment, distance)) = budget.tighten(tip_block_number) {
+                info!(target: "pruner", ?segment, %distance, %disk_usage, max_size = %budget.max_size, "Disk budget exceeded, reducing the distance of the segment");
+            } else {
+                warn!(target: "pruner", %disk_usage, max_size = %budget.max_size, "Disk budget exceeded, but all segments are pruned to their minimum distance");
+            }
+        } else if budget.is_well_under(disk_usage) && !self.has_more_data {
+            if let Some((segment, distance)) = budget.relax() {
+                info!(target: "pruner", ?segment, %distance, %disk_usage, max_size = %budget.max_size, "Disk usage is well under the budget, increasing the distance of the segment");
+            }
+        }
+
+        for (segment, distance) in budget.distances() {
+            self.metrics.get_prune_segment_metrics(segment).budget_distance.set(distance as f64);
+        }
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -1,6 +1,6 @@
 use crate::segments::{
-    AccountHistory, ReceiptsByLogs, Segment, SenderRecovery, StorageHistory, TransactionLookup,
-    UserReceipts,
+    AccountHistory, BlockMeta, Bytecodes, ChangeSets, ReceiptsByLogs, Segment, SenderRecovery,
+    StorageHistory, TransactionLookup, UserReceipts,
 };
 use alloy_eips::eip2718::Encodable2718;
 use reth_db_api::{table::Value, transaction::DbTxMut};
@@ -66,6 +66,9 @@ where
             account_history,
             storage_history,
             bodies_history: _,
+            changesets,
+            block_meta,
+            bytecodes,
             receipts_log_filter,
         } = prune_modes;
 
@@ -80,6 +83,12 @@ where
             .segment_opt(account_history.map(AccountHistory::new))
             // Storage history
             .segment_opt(storage_history.map(StorageHistory::new))
+            // Changesets
+            .segment_opt(changesets.map(ChangeSets::new))
+            // Bytecodes
+            .segment_opt(bytecodes.map(Bytecodes::new))
+            // Block meta static files
+            .segment_opt(block_meta.map(BlockMeta::new))
             // User receipts
             .segment_opt(receipts.map(UserReceipts::new))
             // Receipts by logs
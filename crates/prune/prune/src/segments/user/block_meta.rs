@@ -0,0 +1,152 @@
+use crate::{
+    segments::{PruneInput, Segment},
+    PrunerError,
+};
+use reth_provider::StaticFileProviderFactory;
+use reth_prune_types::{
+    PruneMode, PrunePurpose, PruneSegment, SegmentOutput, SegmentOutputCheckpoint,
+};
+use reth_static_file_types::StaticFileSegment;
+use tracing::{instrument, trace};
+
+/// Deletes the [`StaticFileSegment::BlockMeta`] static files below the prune target.
+///
+/// Static files can only be deleted entirely, so the file that contains the target block is kept.
+#[derive(Debug)]
+pub struct BlockMeta {
+    mode: PruneMode,
+}
+
+impl BlockMeta {
+    pub const fn new(mode: PruneMode) -> Self {
+        Self { mode }
+    }
+}
+
+impl<Provider> Segment<Provider> for BlockMeta
+where
+    Provider: StaticFileProviderFactory,
+{
+    fn segment(&self) -> PruneSegment {
+        PruneSegment::BlockMeta
+    }
+
+    fn mode(&self) -> Option<PruneMode> {
+        Some(self.mode)
+    }
+
+    fn purpose(&self) -> PrunePurpose {
+        PrunePurpose::User
+    }
+
+    #[instrument(level = "trace", target = "pruner", skip(self, provider), ret)]
+    fn prune(&self, provider: &Provider, input: PruneInput) -> Result<SegmentOutput, PrunerError> {
+        let to_block = match input.get_next_block_range() {
+            Some(range) => *range.end(),
+            None => {
+                trace!(target: "pruner", "No block meta static files to prune");
+                return Ok(SegmentOutput::done())
+            }
+        };
+
+        let static_file_provider = provider.static_file_provider();
+        let pruned = static_file_provider
+            .delete_segment_below_block(StaticFileSegment::BlockMeta, to_block + 1)?;
+        let lowest_file_end =
+            static_file_provider.get_lowest_static_file_block(StaticFileSegment::BlockMeta);
+
+        // Blocks before the lowest remaining file are pruned.
+        let last_pruned_block = match lowest_file_end {
+            Some(end) => static_file_provider.find_fixed_range(end).start().checked_sub(1),
+            None => Some(to_block),
+        };
+        trace!(target: "pruner", %pruned, ?last_pruned_block, "Pruned block meta static files");
+
+        Ok(SegmentOutput {
+            progress: input.limiter.progress(true),
+            pruned,
+            checkpoint: Some(SegmentOutputCheckpoint {
+                block_number: last_pruned_block,
+                tx_number: None,
+            }),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::segments::{BlockMeta, PruneInput, PruneLimiter, Segment, SegmentOutput};
+    use assert_matches::assert_matches;
+    use reth_chainspec::MAINNET;
+    use reth_db::test_utils::{create_test_rw_db, create_test_static_files_dir};
+    use reth_db_api::models::{
+        StaticFileBlockWithdrawals, StoredBlockBodyIndices, StoredBlockOmmers,
+    };
+    use reth_provider::{
+        providers::{StaticFileProvider, StaticFileWriter},
+        test_utils::MockNodeTypesWithDB,
+        DatabaseProviderFactory, ProviderFactory, StaticFileProviderFactory,
+    };
+    use reth_prune_types::{PruneMode, PruneProgress};
+    use reth_static_file_types::StaticFileSegment;
+
+    #[test]
+    fn prune() {
+        let (_static_dir, static_dir_path) = create_test_static_files_dir();
+        let factory = ProviderFactory::<MockNodeTypesWithDB>::new(
+            create_test_rw_db(),
+            MAINNET.clone(),
+            StaticFileProvider::read_write(static_dir_path).unwrap().with_custom_blocks_per_file(5),
+        );
+
+        // Blocks 0..=12 in files of 5 blocks each.
+        let static_file_provider = factory.static_file_provider();
+        let mut writer = static_file_provider.latest_writer(StaticFileSegment::BlockMeta).unwrap();
+        for block in 0..=12 {
+            writer
+                .append_block_meta(
+                    &StoredBlockBodyIndices::default(),
+                    &StoredBlockOmmers::<alloy_consensus::Header>::default(),
+                    &StaticFileBlockWithdrawals::default(),
+                    block,
+                )
+                .unwrap();
+        }
+        writer.commit().unwrap();
+        drop(writer);
+        static_file_provider.initialize_index().unwrap();
+
+        let prune = |to_block| {
+            let provider = factory.database_provider_rw().unwrap();
+            let input = PruneInput {
+                previous_checkpoint: None,
+                to_block,
+                limiter: PruneLimiter::default(),
+            };
+            BlockMeta::new(PruneMode::Before(to_block + 1)).prune(&provider, input).unwrap()
+        };
+
+        // The file of blocks 5..=9 contains the target, so only the first file is deleted.
+        let result = prune(8);
+        assert_matches!(
+            result,
+            SegmentOutput { progress: PruneProgress::Finished, pruned: 1, checkpoint: Some(checkpoint) }
+                if checkpoint.block_number == Some(4)
+        );
+        assert_eq!(
+            static_file_provider.get_lowest_static_file_block(StaticFileSegment::BlockMeta),
+            Some(9)
+        );
+
+        let result = prune(9);
+        assert_matches!(
+            result,
+            SegmentOutput { pruned: 1, checkpoint: Some(checkpoint), .. }
+                if checkpoint.block_number == Some(9)
+        );
+        assert_eq!(
+            static_file_provider.get_lowest_static_file_block(StaticFileSegment::BlockMeta),
+            Some(12)
+        );
+    }
+}
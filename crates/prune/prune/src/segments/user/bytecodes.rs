@@ -0,0 +1,187 @@
+use crate::{
+    db_ext::DbTxPruneExt,
+    segments::{PruneInput, Segment},
+    PrunerError,
+};
+use reth_db_api::{
+    cursor::DbCursorRO,
+    tables,
+    transaction::{DbTx, DbTxMut},
+};
+use reth_provider::DBProvider;
+use reth_prune_types::{
+    PruneMode, PrunePurpose, PruneSegment, SegmentOutput, SegmentOutputCheckpoint,
+};
+use rustc_hash::FxHashSet;
+use tracing::{instrument, trace};
+
+/// Minimum number of blocks between two runs of the segment.
+///
+/// Finding orphaned bytecodes requires a scan of the whole account state, so it's only done once
+/// in a while.
+const BYTECODES_PRUNE_INTERVAL: u64 = 100_000;
+
+/// Prunes bytecodes that are neither referenced by an account of the current state, nor by an
+/// account in the changesets after the prune target, which are required to serve the historical
+/// state and to unwind.
+#[derive(Debug)]
+pub struct Bytecodes {
+    mode: PruneMode,
+}
+
+impl Bytecodes {
+    pub const fn new(mode: PruneMode) -> Self {
+        Self { mode }
+    }
+}
+
+impl<Provider> Segment<Provider> for Bytecodes
+where
+    Provider: DBProvider<Tx: DbTxMut>,
+{
+    fn segment(&self) -> PruneSegment {
+        PruneSegment::Bytecodes
+    }
+
+    fn mode(&self) -> Option<PruneMode> {
+        Some(self.mode)
+    }
+
+    fn purpose(&self) -> PrunePurpose {
+        PrunePurpose::User
+    }
+
+    #[instrument(level = "trace", target = "pruner", skip(self, provider), ret)]
+    fn prune(&self, provider: &Provider, input: PruneInput) -> Result<SegmentOutput, PrunerError> {
+        let to_block = match input.get_next_block_range() {
+            Some(range) => *range.end(),
+            None => {
+                trace!(target: "pruner", "No bytecodes to prune");
+                return Ok(SegmentOutput::done())
+            }
+        };
+        let previous_checkpoint =
+            input.previous_checkpoint.map(SegmentOutputCheckpoint::from_prune_checkpoint);
+        if previous_checkpoint
+            .and_then(|checkpoint| checkpoint.block_number)
+            .is_some_and(|previous| to_block - previous < BYTECODES_PRUNE_INTERVAL)
+        {
+            trace!(target: "pruner", "Bytecodes were pruned recently");
+            return Ok(SegmentOutput::done())
+        }
+
+        let mut limiter = input.limiter;
+        if limiter.is_limit_reached() {
+            return Ok(SegmentOutput::not_done(limiter.interrupt_reason(), previous_checkpoint))
+        }
+
+        let tx = provider.tx_ref();
+
+        // The number of distinct bytecodes is much lower than the number of accounts, so the set
+        // stays small compared to the state.
+        let mut referenced = FxHashSet::default();
+        for entry in tx.cursor_read::<tables::PlainAccountState>()?.walk(None)? {
+            let (_, account) = entry?;
+            referenced.extend(account.bytecode_hash);
+        }
+        for entry in tx.cursor_read::<tables::AccountChangeSets>()?.walk_range(to_block + 1..)? {
+            let (_, change) = entry?;
+            referenced.extend(change.info.and_then(|account| account.bytecode_hash));
+        }
+        trace!(target: "pruner", referenced = %referenced.len(), "Collected referenced bytecodes");
+
+        let mut pruned = 0;
+        let (_, done) = tx.prune_table_with_range::<tables::Bytecodes>(
+            ..,
+            &mut limiter,
+            |(hash, _)| referenced.contains(hash),
+            |_| pruned += 1,
+        )?;
+        trace!(target: "pruner", %pruned, %done, "Pruned bytecodes");
+
+        let progress = limiter.progress(done);
+
+        Ok(SegmentOutput {
+            progress,
+            pruned,
+            // If there are more bytecodes to prune, keep the previous checkpoint, so the next run
+            // scans the state again.
+            checkpoint: if done {
+                Some(SegmentOutputCheckpoint { block_number: Some(to_block), tx_number: None })
+            } else {
+                previous_checkpoint
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::segments::{Bytecodes, PruneInput, PruneLimiter, Segment, SegmentOutput};
+    use alloy_primitives::{keccak256, Address, Bytes};
+    use assert_matches::assert_matches;
+    use reth_db_api::{
+        models::AccountBeforeTx,
+        tables,
+        transaction::{DbTx, DbTxMut},
+    };
+    use reth_primitives_traits::{Account, Bytecode};
+    use reth_provider::DatabaseProviderFactory;
+    use reth_prune_types::{PruneMode, PruneProgress};
+    use reth_stages::test_utils::TestStageDB;
+
+    #[test]
+    fn prune() {
+        let db = TestStageDB::default();
+
+        let codes =
+            (0..4u8).map(|i| Bytecode::new_raw(Bytes::from(vec![i; 4]))).collect::<Vec<_>>();
+        let hashes =
+            codes.iter().map(|code| keccak256(code.original_byte_slice())).collect::<Vec<_>>();
+        let account =
+            |code: usize| Account { bytecode_hash: Some(hashes[code]), ..Default::default() };
+
+        let provider = db.factory.database_provider_rw().unwrap();
+        let tx = provider.tx_ref();
+        for (hash, code) in hashes.iter().zip(&codes) {
+            tx.put::<tables::Bytecodes>(*hash, code.clone()).unwrap();
+        }
+        // Code 0 is referenced by the current state.
+        tx.put::<tables::PlainAccountState>(Address::with_last_byte(1), account(0)).unwrap();
+        // Code 1 was replaced before the prune target, code 2 after.
+        tx.put::<tables::AccountChangeSets>(
+            10,
+            AccountBeforeTx { address: Address::with_last_byte(2), info: Some(account(1)) },
+        )
+        .unwrap();
+        tx.put::<tables::AccountChangeSets>(
+            30,
+            AccountBeforeTx { address: Address::with_last_byte(3), info: Some(account(2)) },
+        )
+        .unwrap();
+        // Code 3 is not referenced at all.
+        provider.commit().unwrap();
+
+        let segment = Bytecodes::new(PruneMode::Before(21));
+        let provider = db.factory.database_provider_rw().unwrap();
+        let input = PruneInput {
+            previous_checkpoint: None,
+            to_block: 20,
+            limiter: PruneLimiter::default(),
+        };
+        let result = segment.prune(&provider, input).unwrap();
+        assert_matches!(
+            result,
+            SegmentOutput { progress: PruneProgress::Finished, pruned: 2, checkpoint: Some(checkpoint) }
+                if checkpoint.block_number == Some(20)
+        );
+        provider.commit().unwrap();
+
+        let provider = db.factory.provider().unwrap();
+        let remaining = provider.tx_ref().entries::<tables::Bytecodes>().unwrap();
+        assert_eq!(remaining, 2);
+        for code in [0, 2] {
+            assert!(provider.tx_ref().get::<tables::Bytecodes>(hashes[code]).unwrap().is_some());
+        }
+    }
+}
@@ -0,0 +1,251 @@
+use crate::{
+    db_ext::DbTxPruneExt,
+    segments::{PruneInput, Segment},
+    PrunerError,
+};
+use alloy_primitives::BlockNumber;
+use reth_db_api::{models::BlockNumberAddress, tables, transaction::DbTxMut};
+use reth_provider::DBProvider;
+use reth_prune_types::{
+    PruneMode, PrunePurpose, PruneSegment, SegmentOutput, SegmentOutputCheckpoint,
+};
+use tracing::{instrument, trace};
+
+/// Number of changeset tables to prune in one step.
+///
+/// Changesets consist of two tables: [`tables::AccountChangeSets`] and
+/// [`tables::StorageChangeSets`]. We want to prune them to the same block number.
+const CHANGESET_TABLES_TO_PRUNE: usize = 2;
+
+/// Prunes the changesets without touching the history indices.
+///
+/// The history indices still point to the pruned blocks, but the historical state providers refuse
+/// to serve state at or below the [`PruneSegment::ChangeSets`] checkpoint, so they are never
+/// followed.
+#[derive(Debug)]
+pub struct ChangeSets {
+    mode: PruneMode,
+}
+
+impl ChangeSets {
+    pub const fn new(mode: PruneMode) -> Self {
+        Self { mode }
+    }
+}
+
+impl<Provider> Segment<Provider> for ChangeSets
+where
+    Provider: DBProvider<Tx: DbTxMut>,
+{
+    fn segment(&self) -> PruneSegment {
+        PruneSegment::ChangeSets
+    }
+
+    fn mode(&self) -> Option<PruneMode> {
+        Some(self.mode)
+    }
+
+    fn purpose(&self) -> PrunePurpose {
+        PrunePurpose::User
+    }
+
+    #[instrument(level = "trace", target = "pruner", skip(self, provider), ret)]
+    fn prune(&self, provider: &Provider, input: PruneInput) -> Result<SegmentOutput, PrunerError> {
+        let range = match input.get_next_block_range() {
+            Some(range) => range,
+            None => {
+                trace!(target: "pruner", "No changesets to prune");
+                return Ok(SegmentOutput::done())
+            }
+        };
+        let range_end = *range.end();
+        let previous_block =
+            input.previous_checkpoint.and_then(|checkpoint| checkpoint.block_number);
+
+        let mut limiter = if let Some(limit) = input.limiter.deleted_entries_limit() {
+            input.limiter.set_deleted_entries_limit(limit / CHANGESET_TABLES_TO_PRUNE)
+        } else {
+            input.limiter
+        };
+        if limiter.is_limit_reached() {
+            return Ok(SegmentOutput::not_done(
+                limiter.interrupt_reason(),
+                input.previous_checkpoint.map(SegmentOutputCheckpoint::from_prune_checkpoint),
+            ))
+        }
+
+        let mut last_account_pruned_block = None;
+        let (pruned_accounts, accounts_done) =
+            provider.tx_ref().prune_table_with_range::<tables::AccountChangeSets>(
+                range.clone(),
+                &mut limiter,
+                |_| false,
+                |(block_number, _)| last_account_pruned_block = Some(block_number),
+            )?;
+        trace!(target: "pruner", pruned = %pruned_accounts, done = %accounts_done, "Pruned account changesets");
+
+        let mut last_storage_pruned_block = None;
+        let (pruned_storages, storages_done) =
+            provider.tx_ref().prune_table_with_range::<tables::StorageChangeSets>(
+                BlockNumberAddress::range(range),
+                &mut limiter,
+                |_| false,
+                |(key, _)| last_storage_pruned_block = Some(key.block_number()),
+            )?;
+        trace!(target: "pruner", pruned = %pruned_storages, done = %storages_done, "Pruned storage changesets");
+
+        // Both tables are only pruned up to the lower of the two blocks, so that the changesets of
+        // a block are never partially available.
+        let last_pruned_block =
+            last_pruned_block(last_account_pruned_block, accounts_done, range_end, previous_block)
+                .min(last_pruned_block(
+                    last_storage_pruned_block,
+                    storages_done,
+                    range_end,
+                    previous_block,
+                ));
+
+        let done = accounts_done && storages_done;
+        let progress = limiter.progress(done);
+
+        Ok(SegmentOutput {
+            progress,
+            pruned: pruned_accounts + pruned_storages,
+            checkpoint: Some(SegmentOutputCheckpoint {
+                block_number: last_pruned_block,
+                tx_number: None,
+            }),
+        })
+    }
+}
+
+/// Returns the highest block whose changesets are fully pruned from a table.
+///
+/// If there are more changesets to prune, the last block that was touched may still have entries
+/// left, so the block before it is returned.
+fn last_pruned_block(
+    last_touched_block: Option<BlockNumber>,
+    done: bool,
+    range_end: BlockNumber,
+    previous_block: Option<BlockNumber>,
+) -> Option<BlockNumber> {
+    if done {
+        return Some(range_end)
+    }
+    last_touched_block.and_then(|block| block.checked_sub(1)).max(previous_block)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::segments::{ChangeSets, PruneInput, PruneLimiter, Segment, SegmentOutput};
+    use alloy_primitives::{BlockNumber, B256};
+    use assert_matches::assert_matches;
+    use reth_db_api::{models::BlockNumberAddress, tables};
+    use reth_provider::{DatabaseProviderFactory, PruneCheckpointReader};
+    use reth_prune_types::{PruneMode, PruneProgress, PruneSegment};
+    use reth_stages::test_utils::{StorageKind, TestStageDB};
+    use reth_testing_utils::generators::{
+        self, random_block_range, random_changeset_range, random_eoa_accounts, BlockRangeParams,
+    };
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn prune() {
+        let db = TestStageDB::default();
+        let mut rng = generators::rng();
+
+        let blocks = random_block_range(
+            &mut rng,
+            0..=100,
+            BlockRangeParams { parent: Some(B256::ZERO), tx_count: 0..1, ..Default::default() },
+        );
+        db.insert_blocks(blocks.iter(), StorageKind::Database(None)).expect("insert blocks");
+
+        let accounts = random_eoa_accounts(&mut rng, 2).into_iter().collect::<BTreeMap<_, _>>();
+
+        let (changesets, _) = random_changeset_range(
+            &mut rng,
+            blocks.iter(),
+            accounts.into_iter().map(|(addr, acc)| (addr, (acc, Vec::new()))),
+            1..2,
+            1..2,
+        );
+        db.insert_changesets(changesets.clone(), None).expect("insert changesets");
+        db.insert_history(changesets, None).expect("insert history");
+
+        let history_before = db.table::<tables::AccountsHistory>().unwrap();
+        assert!(!db.table::<tables::StorageChangeSets>().unwrap().is_empty());
+
+        let test_prune = |to_block: BlockNumber, limit: usize| {
+            let prune_mode = PruneMode::Before(to_block + 1);
+            let input = PruneInput {
+                previous_checkpoint: db
+                    .factory
+                    .provider()
+                    .unwrap()
+                    .get_prune_checkpoint(PruneSegment::ChangeSets)
+                    .unwrap(),
+                to_block,
+                limiter: PruneLimiter::default().set_deleted_entries_limit(limit),
+            };
+            let segment = ChangeSets::new(prune_mode);
+
+            let provider = db.factory.database_provider_rw().unwrap();
+            let result = segment.prune(&provider, input).unwrap();
+            segment
+                .save_checkpoint(
+                    &provider,
+                    result.checkpoint.unwrap().as_prune_checkpoint(prune_mode),
+                )
+                .unwrap();
+            provider.commit().expect("commit");
+            result
+        };
+
+        // A limit that is too low to prune all changesets up to the target.
+        let result = test_prune(50, 20);
+        assert_matches!(result, SegmentOutput { progress: PruneProgress::HasMoreData(_), .. });
+        let checkpoint = result.checkpoint.unwrap().block_number;
+        assert!(checkpoint < Some(50));
+
+        // Changesets up to the checkpoint are pruned from both tables, but not all up to the
+        // target.
+        let account_blocks = db
+            .table::<tables::AccountChangeSets>()
+            .unwrap()
+            .into_iter()
+            .map(|(block, _)| block)
+            .collect::<Vec<_>>();
+        let storage_blocks = db
+            .table::<tables::StorageChangeSets>()
+            .unwrap()
+            .into_iter()
+            .map(|(BlockNumberAddress((block, _)), _)| block)
+            .collect::<Vec<_>>();
+        assert!(account_blocks
+            .iter()
+            .chain(&storage_blocks)
+            .all(|block| Some(*block) > checkpoint));
+        assert!(account_blocks.iter().chain(&storage_blocks).any(|block| *block <= 50));
+
+        let result = test_prune(50, usize::MAX);
+        assert_matches!(
+            result,
+            SegmentOutput { progress: PruneProgress::Finished, checkpoint: Some(checkpoint), .. }
+                if checkpoint.block_number == Some(50)
+        );
+
+        assert!(db
+            .table::<tables::AccountChangeSets>()
+            .unwrap()
+            .iter()
+            .all(|(block, _)| *block > 50));
+        assert!(db
+            .table::<tables::StorageChangeSets>()
+            .unwrap()
+            .iter()
+            .all(|(BlockNumberAddress((block, _)), _)| *block > 50));
+        // History indices are kept.
+        assert_eq!(db.table::<tables::AccountsHistory>().unwrap(), history_before);
+    }
+}
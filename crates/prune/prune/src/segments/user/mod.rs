@@ -1,4 +1,7 @@
 mod account_history;
+mod block_meta;
+mod bytecodes;
+mod changesets;
 mod history;
 mod receipts;
 mod receipts_by_logs;
@@ -7,6 +10,9 @@ mod storage_history;
 mod transaction_lookup;
 
 pub use account_history::AccountHistory;
+pub use block_meta::BlockMeta;
+pub use bytecodes::Bytecodes;
+pub use changesets::ChangeSets;
 pub use receipts::Receipts;
 pub use receipts_by_logs::ReceiptsByLogs;
 pub use sender_recovery::SenderRecovery;
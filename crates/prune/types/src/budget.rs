@@ -0,0 +1,62 @@
+use crate::PruneSegment;
+use alloc::vec::Vec;
+
+/// Disk budget of the pruner.
+///
+/// When the data directory grows beyond [`PruneBudget::max_size`], the pruner reduces the
+/// distances of the [`PruneBudget::segments`] one segment at a time, down to the minimum distance
+/// of each segment, until the data directory fits the budget again. Once the usage is well under
+/// the budget, the reduced distances are increased back toward the configured ones.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(any(test, feature = "serde"), derive(serde::Serialize, serde::Deserialize))]
+pub struct PruneBudget {
+    /// Maximum size of the data directory in bytes.
+    pub max_size: u64,
+    /// Segments that may be pruned beyond their configured distance, in priority order.
+    ///
+    /// Only segments configured with a [`PruneMode::Distance`](crate::PruneMode::Distance) are
+    /// taken into account, and the configured distance is the retention kept while the data
+    /// directory fits the budget.
+    #[cfg_attr(any(test, feature = "serde"), serde(default = "PruneBudget::default_segments"))]
+    pub segments: Vec<PruneSegment>,
+}
+
+impl PruneBudget {
+    /// Segments pruned by default when the budget is exceeded, in priority order.
+    pub const DEFAULT_SEGMENTS: [PruneSegment; 6] = [
+        PruneSegment::SenderRecovery,
+        PruneSegment::TransactionLookup,
+        PruneSegment::Receipts,
+        PruneSegment::ChangeSets,
+        PruneSegment::AccountHistory,
+        PruneSegment::StorageHistory,
+    ];
+
+    /// Creates a new [`PruneBudget`] with the given maximum size in bytes and the
+    /// [`PruneBudget::DEFAULT_SEGMENTS`].
+    pub fn new(max_size: u64) -> Self {
+        Self { max_size, segments: Self::default_segments() }
+    }
+
+    fn default_segments() -> Vec<PruneSegment> {
+        Self::DEFAULT_SEGMENTS.to_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserialize_default_segments() {
+        let budget: PruneBudget = toml::from_str("max_size = 1000").unwrap();
+        assert_eq!(budget, PruneBudget::new(1000));
+
+        let budget: PruneBudget = toml::from_str(
+            r#"max_size = 1000
+segments = ["Receipts", "SenderRecovery"]"#,
+        )
+        .unwrap();
+        assert_eq!(budget.segments, [PruneSegment::Receipts, PruneSegment::SenderRecovery]);
+    }
+}
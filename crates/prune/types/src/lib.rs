@@ -11,6 +11,7 @@
 
 extern crate alloc;
 
+mod budget;
 mod checkpoint;
 mod event;
 mod mode;
@@ -22,6 +23,7 @@ use alloc::{collections::BTreeMap, vec::Vec};
 use alloy_primitives::{Address, BlockNumber};
 use core::ops::Deref;
 
+pub use budget::PruneBudget;
 pub use checkpoint::PruneCheckpoint;
 pub use event::PrunerEvent;
 pub use mode::PruneMode;
@@ -26,6 +26,15 @@ pub enum PruneSegment {
     Headers,
     /// Prune segment responsible for the `Transactions` table.
     Transactions,
+    /// Prune segment responsible for the `AccountChangeSets` and `StorageChangeSets` tables,
+    /// without touching the history indices.
+    ChangeSets,
+    /// Prune segment responsible for the `BlockMeta` static files.
+    BlockMeta,
+    /// Prune segment responsible for the `Bytecodes` table entries that are neither referenced by
+    /// the current state nor by the remaining changesets. The state before its checkpoint is no
+    /// longer available.
+    Bytecodes,
 }
 
 impl PruneSegment {
@@ -36,10 +45,13 @@ impl PruneSegment {
                 0
             }
             Self::Receipts if purpose.is_static_file() => 0,
-            Self::ContractLogs | Self::AccountHistory | Self::StorageHistory => {
-                MINIMUM_PRUNING_DISTANCE
-            }
-            Self::Receipts => MINIMUM_PRUNING_DISTANCE,
+            Self::Receipts |
+            Self::ContractLogs |
+            Self::AccountHistory |
+            Self::StorageHistory |
+            Self::ChangeSets |
+            Self::BlockMeta |
+            Self::Bytecodes => MINIMUM_PRUNING_DISTANCE,
         }
     }
 }
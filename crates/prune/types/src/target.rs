@@ -34,6 +34,8 @@ pub enum HistoryType {
     AccountHistory,
     /// Storage history
     StorageHistory,
+    /// Account and storage changesets
+    ChangeSets,
 }
 
 /// Pruning configuration for every segment of the data that can be pruned.
@@ -84,6 +86,34 @@ pub struct PruneModes {
         )
     )]
     pub bodies_history: Option<PruneMode>,
+    /// Changesets pruning configuration. Unlike the history segments, this keeps the history
+    /// indices.
+    #[cfg_attr(
+        any(test, feature = "serde"),
+        serde(
+            skip_serializing_if = "Option::is_none",
+            deserialize_with = "deserialize_opt_prune_mode_with_min_blocks::<MINIMUM_PRUNING_DISTANCE, _>"
+        )
+    )]
+    pub changesets: Option<PruneMode>,
+    /// Block meta static files pruning configuration.
+    #[cfg_attr(
+        any(test, feature = "serde"),
+        serde(
+            skip_serializing_if = "Option::is_none",
+            deserialize_with = "deserialize_opt_prune_mode_with_min_blocks::<MINIMUM_PRUNING_DISTANCE, _>"
+        )
+    )]
+    pub block_meta: Option<PruneMode>,
+    /// Orphaned bytecodes pruning configuration.
+    #[cfg_attr(
+        any(test, feature = "serde"),
+        serde(
+            skip_serializing_if = "Option::is_none",
+            deserialize_with = "deserialize_opt_prune_mode_with_min_blocks::<MINIMUM_PRUNING_DISTANCE, _>"
+        )
+    )]
+    pub bytecodes: Option<PruneMode>,
     /// Receipts pruning configuration by retaining only those receipts that contain logs emitted
     /// by the specified addresses, discarding others. This setting is overridden by `receipts`.
     ///
@@ -107,6 +137,9 @@ impl PruneModes {
             account_history: Some(PruneMode::Full),
             storage_history: Some(PruneMode::Full),
             bodies_history: Some(PruneMode::Full),
+            changesets: Some(PruneMode::Full),
+            block_meta: Some(PruneMode::Full),
+            bytecodes: Some(PruneMode::Full),
             receipts_log_filter: Default::default(),
         }
     }
@@ -131,6 +164,7 @@ impl PruneModes {
         [
             (self.account_history, HistoryType::AccountHistory),
             (self.storage_history, HistoryType::StorageHistory),
+            (self.changesets, HistoryType::ChangeSets),
         ]
         .iter()
         .find_map(|(prune_mode, history_type)| {
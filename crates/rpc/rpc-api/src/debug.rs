@@ -3,17 +3,99 @@ use alloy_genesis::ChainConfig;
 use alloy_json_rpc::RpcObject;
 use alloy_primitives::{Address, Bytes, B256};
 use alloy_rpc_types_debug::ExecutionWitness;
-use alloy_rpc_types_eth::{Block, Bundle, StateContext};
+use alloy_rpc_types_eth::{Bundle, StateContext};
 use alloy_rpc_types_trace::geth::{
-    BlockTraceResult, GethDebugTracingCallOptions, GethDebugTracingOptions, GethTrace, TraceResult,
+    GethDebugTracingCallOptions, GethDebugTracingOptions, GethTrace, TraceResult,
 };
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 use reth_trie_common::{updates::TrieUpdates, HashedPostState};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// A page of the storage of an account, as returned by `debug_storageRangeAt`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageRangeResult {
+    /// The storage slots of the page, keyed by their hashed slot.
+    pub storage: BTreeMap<B256, StorageRangeEntry>,
+    /// The hashed slot the next page starts at, if there are more slots.
+    pub next_key: Option<B256>,
+}
+
+/// A storage slot of a [`StorageRangeResult`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StorageRangeEntry {
+    /// The slot, if the preimage of the hashed slot is known.
+    pub key: Option<B256>,
+    /// The value of the slot.
+    pub value: B256,
+}
+
+/// A page of the accounts of the state, as returned by `debug_accountRange`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountRangeResult {
+    /// The state root of the block.
+    pub root: B256,
+    /// The accounts of the page, keyed by their address if the preimage of the hashed address is
+    /// known, or by `pre(<hashed address>)` otherwise.
+    pub accounts: BTreeMap<String, DumpAccount>,
+    /// The hashed address the next page starts at, if there are more accounts.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next: Option<B256>,
+}
+
+/// An account of an [`AccountRangeResult`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DumpAccount {
+    /// The balance of the account, in decimal.
+    pub balance: String,
+    /// The nonce of the account.
+    pub nonce: u64,
+    /// The storage root of the account.
+    pub root: B256,
+    /// The hash of the code of the account.
+    pub code_hash: B256,
+    /// The code of the account, unless it was excluded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<Bytes>,
+    /// The non-zero storage slots of the account keyed by their hashed slot, unless they were
+    /// excluded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub storage: Option<BTreeMap<B256, B256>>,
+    /// The address of the account, if the preimage of the hashed address is known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<Address>,
+    /// The hashed address of the account.
+    pub key: B256,
+}
+
+/// A block that was rejected because it failed validation, as returned by `debug_getBadBlocks`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BadBlock<B> {
+    /// The hash of the block.
+    pub hash: B256,
+    /// The block in its RPC representation, including all transactions.
+    pub block: B,
+    /// The RLP encoded block.
+    pub rlp: Bytes,
+}
+
+/// Options for `debug_standardTraceBlockToFile` and `debug_standardTraceBadBlockToFile`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct StdTraceConfig {
+    /// Whether to include the memory in each step of the trace.
+    pub enable_memory: bool,
+    /// If set, only the transaction with this hash is traced.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tx_hash: Option<B256>,
+}
 
 /// Debug rpc interface.
 #[cfg_attr(not(feature = "client"), rpc(server, namespace = "debug"))]
 #[cfg_attr(feature = "client", rpc(server, client, namespace = "debug"))]
-pub trait DebugApi<TxReq: RpcObject> {
+pub trait DebugApi<TxReq: RpcObject, B: RpcObject> {
     /// Returns an RLP-encoded header.
     #[method(name = "getRawHeader")]
     async fn raw_header(&self, block_id: BlockId) -> RpcResult<Bytes>;
@@ -38,16 +120,28 @@ pub trait DebugApi<TxReq: RpcObject> {
 
     /// Returns an array of recent bad blocks that the client has seen on the network.
     #[method(name = "getBadBlocks")]
-    async fn bad_blocks(&self) -> RpcResult<Vec<Block>>;
+    async fn bad_blocks(&self) -> RpcResult<Vec<BadBlock<B>>>;
 
     /// Returns the structured logs created during the execution of EVM between two blocks
     /// (excluding start) as a JSON object.
-    #[method(name = "traceChain")]
+    ///
+    /// The blocks are traced with the given tracer, see [`GethDebugTracingOptions`], and the traces
+    /// of each block are streamed as a separate
+    /// [`BlockTraceResult`](alloy_rpc_types_trace::geth::BlockTraceResult) notification, in block
+    /// order.
+    ///
+    /// Note: this is only available over a transport that supports subscriptions.
+    #[subscription(
+        name = "traceChain",
+        unsubscribe = "unsubscribeTraceChain",
+        item = alloy_rpc_types_trace::geth::BlockTraceResult
+    )]
     async fn debug_trace_chain(
         &self,
         start_exclusive: BlockNumberOrTag,
         end_inclusive: BlockNumberOrTag,
-    ) -> RpcResult<Vec<BlockTraceResult>>;
+        opts: Option<GethDebugTracingOptions>,
+    ) -> jsonrpsee::core::SubscriptionResult;
 
     /// The `debug_traceBlock` method will return a full stack trace of all invoked opcodes of all
     /// transaction that were included in this block.
@@ -167,6 +261,10 @@ pub trait DebugApi<TxReq: RpcObject> {
     ///
     /// If incompletes is false, then accounts for which the key preimage (i.e: the address) doesn't
     /// exist in db are skipped. NB: geth by default does not store preimages.
+    ///
+    /// NOTE: reth does not store address preimages, so accounts are keyed by
+    /// `pre(<hashed address>)` and are only returned if `incompletes` is true. The storage of each
+    /// account is capped, and only the state of recent blocks can be enumerated.
     #[method(name = "accountRange")]
     async fn debug_account_range(
         &self,
@@ -176,7 +274,7 @@ pub trait DebugApi<TxReq: RpcObject> {
         nocode: bool,
         nostorage: bool,
         incompletes: bool,
-    ) -> RpcResult<()>;
+    ) -> RpcResult<AccountRangeResult>;
 
     /// Turns on block profiling for the given duration and writes profile data to disk. It uses a
     /// profile rate of 1 for most accurate information. If a different rate is desired, set the
@@ -256,21 +354,26 @@ pub trait DebugApi<TxReq: RpcObject> {
     /// Returns all accounts that have changed between the two blocks specified. A change is defined
     /// as a difference in nonce, balance, code hash, or storage hash. With one parameter, returns
     /// the list of accounts modified in the specified block.
+    ///
+    /// Fails with invalid params if the range spans more than 8192 blocks.
     #[method(name = "getModifiedAccountsByHash")]
     async fn debug_get_modified_accounts_by_hash(
         &self,
         start_hash: B256,
-        end_hash: B256,
-    ) -> RpcResult<()>;
+        end_hash: Option<B256>,
+    ) -> RpcResult<Vec<Address>>;
 
     /// Returns all accounts that have changed between the two blocks specified. A change is defined
-    /// as a difference in nonce, balance, code hash or storage hash.
+    /// as a difference in nonce, balance, code hash or storage hash. With one parameter, returns
+    /// the list of accounts modified in the specified block.
+    ///
+    /// Fails with invalid params if the range spans more than 8192 blocks.
     #[method(name = "getModifiedAccountsByNumber")]
     async fn debug_get_modified_accounts_by_number(
         &self,
         start_number: u64,
-        end_number: u64,
-    ) -> RpcResult<()>;
+        end_number: Option<u64>,
+    ) -> RpcResult<Vec<Address>>;
 
     /// Turns on Go runtime tracing for the given duration and writes trace data to disk.
     #[method(name = "goTrace")]
@@ -282,8 +385,8 @@ pub trait DebugApi<TxReq: RpcObject> {
     async fn debug_intermediate_roots(
         &self,
         block_hash: B256,
-        opts: Option<GethDebugTracingCallOptions>,
-    ) -> RpcResult<()>;
+        opts: Option<GethDebugTracingOptions>,
+    ) -> RpcResult<Vec<B256>>;
 
     /// Returns detailed runtime memory statistics.
     #[method(name = "memStats")]
@@ -336,22 +439,29 @@ pub trait DebugApi<TxReq: RpcObject> {
     #[method(name = "stacks")]
     async fn debug_stacks(&self) -> RpcResult<()>;
 
-    /// Used to obtain info about a block.
+    /// Replays the bad block with the given hash and writes an EIP-3155 trace of each of its
+    /// transactions to a separate file, returning the paths of the files.
+    ///
+    /// This is similar to `debug_standardTraceBlockToFile`, but can be used to obtain info about
+    /// a block which has been rejected as invalid.
     #[method(name = "standardTraceBadBlockToFile")]
     async fn debug_standard_trace_bad_block_to_file(
         &self,
-        block: BlockNumberOrTag,
-        opts: Option<GethDebugTracingCallOptions>,
-    ) -> RpcResult<()>;
+        block_hash: B256,
+        config: Option<StdTraceConfig>,
+    ) -> RpcResult<Vec<String>>;
 
-    /// This method is similar to `debug_standardTraceBlockToFile`, but can be used to obtain info
-    /// about a block which has been rejected as invalid (for some reason).
+    /// Replays the block with the given hash and writes an EIP-3155 trace of each of its
+    /// transactions to a separate file, returning the paths of the files.
+    ///
+    /// Each file contains one JSON object per executed opcode, followed by a summary of the
+    /// transaction.
     #[method(name = "standardTraceBlockToFile")]
     async fn debug_standard_trace_block_to_file(
         &self,
-        block: BlockNumberOrTag,
-        opts: Option<GethDebugTracingCallOptions>,
-    ) -> RpcResult<()>;
+        block_hash: B256,
+        config: Option<StdTraceConfig>,
+    ) -> RpcResult<Vec<String>>;
 
     /// Turns on CPU profiling indefinitely, writing to the given file.
     #[method(name = "startCPUProfile")]
@@ -389,7 +499,7 @@ pub trait DebugApi<TxReq: RpcObject> {
         contract_address: Address,
         key_start: B256,
         max_result: u64,
-    ) -> RpcResult<()>;
+    ) -> RpcResult<StorageRangeResult>;
 
     /// Returns the structured logs created during the execution of EVM against a block pulled
     /// from the pool of bad ones and returns them as a JSON object. For the second parameter see
@@ -398,8 +508,8 @@ pub trait DebugApi<TxReq: RpcObject> {
     async fn debug_trace_bad_block(
         &self,
         block_hash: B256,
-        opts: Option<GethDebugTracingCallOptions>,
-    ) -> RpcResult<()>;
+        opts: Option<GethDebugTracingOptions>,
+    ) -> RpcResult<Vec<TraceResult>>;
 
     /// Sets the logging verbosity ceiling. Log messages with level up to and including the given
     /// level will be printed.
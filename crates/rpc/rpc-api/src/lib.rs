@@ -31,6 +31,12 @@ mod txpool;
 mod validation;
 mod web3;
 
+pub use debug::{
+    AccountRangeResult, BadBlock, DumpAccount, StdTraceConfig, StorageRangeEntry,
+    StorageRangeResult,
+};
+pub use reth::{LowestAvailableBlocks, PruneCheckpointInfo, PruneStatus, StaticFileBlockRange};
+
 /// re-export of all server traits
 pub use servers::*;
 
@@ -38,9 +44,10 @@ pub use servers::*;
 pub mod servers {
     pub use crate::{
         admin::AdminApiServer,
+        anvil::AnvilApiServer,
         debug::{DebugApiServer, DebugExecutionWitnessApiServer},
         engine::{EngineApiServer, EngineEthApiServer, IntoEngineApiRpcModule},
-        mev::{MevFullApiServer, MevSimApiServer},
+        mev::{MevFullApiServer, MevSendBundleApiServer, MevSimApiServer},
         miner::MinerApiServer,
         net::NetApiServer,
         otterscan::OtterscanServer,
@@ -53,7 +60,7 @@ pub mod servers {
     };
     pub use reth_rpc_eth_api::{
         self as eth, EthApiServer, EthBundleApiServer, EthCallBundleApiServer, EthFilterApiServer,
-        EthPubSubApiServer, L2EthApiExtServer,
+        EthPubSubApiServer, EthSendBundleApiServer, L2EthApiExtServer,
     };
 }
 
@@ -71,7 +78,7 @@ pub mod clients {
         engine::{EngineApiClient, EngineEthApiClient},
         ganache::GanacheApiClient,
         hardhat::HardhatApiClient,
-        mev::{MevFullApiClient, MevSimApiClient},
+        mev::{MevFullApiClient, MevSendBundleApiClient, MevSimApiClient},
         miner::MinerApiClient,
         net::NetApiClient,
         otterscan::OtterscanClient,
@@ -84,6 +91,6 @@ pub mod clients {
     };
     pub use reth_rpc_eth_api::{
         EthApiClient, EthBundleApiClient, EthCallBundleApiClient, EthFilterApiClient,
-        L2EthApiExtServer,
+        EthSendBundleApiClient, L2EthApiExtServer,
     };
 }
@@ -15,6 +15,19 @@ pub trait MevSimApi {
     ) -> jsonrpsee::core::RpcResult<SimBundleResponse>;
 }
 
+/// Mev rpc interface that only supports submitting bundles for inclusion.
+#[cfg_attr(not(feature = "client"), rpc(server, namespace = "mev"))]
+#[cfg_attr(feature = "client", rpc(server, client, namespace = "mev"))]
+pub trait MevSendBundleApi {
+    /// Submitting bundles to the relay. It takes in a bundle and provides a bundle hash as a
+    /// return value.
+    #[method(name = "sendBundle")]
+    async fn send_bundle(
+        &self,
+        request: MevSendBundle,
+    ) -> jsonrpsee::core::RpcResult<EthBundleHash>;
+}
+
 /// Mev rpc interface.
 #[cfg_attr(not(feature = "client"), rpc(server, namespace = "mev"))]
 #[cfg_attr(feature = "client", rpc(server, client, namespace = "mev"))]
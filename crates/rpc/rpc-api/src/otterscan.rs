@@ -70,22 +70,44 @@ pub trait Otterscan<T: RpcObject, H: RpcObject> {
     ) -> RpcResult<OtsBlockTransactions<T, H>>;
 
     /// Gets paginated inbound/outbound transaction calls for a certain address.
+    ///
+    /// NOTE: Only transactions that change the state of the address are found. Reth has no index of
+    /// the transactions that touch an address, so candidate blocks are looked up through the
+    /// account and storage history indices. Transactions that call the address without changing
+    /// its balance, nonce, code or storage, e.g. zero-value transfers, read-only calls or reverted
+    /// calls, are not returned.
+    ///
+    /// If the history of the oldest blocks has been pruned, they can't be searched and the last
+    /// page is never reported.
+    ///
+    /// Fails with invalid params if `page_size` is zero or the block tag is not supported.
     #[method(name = "searchTransactionsBefore")]
     async fn search_transactions_before(
         &self,
         address: Address,
         block_number: LenientBlockNumberOrTag,
         page_size: usize,
-    ) -> RpcResult<TransactionsWithReceipts>;
+    ) -> RpcResult<TransactionsWithReceipts<T>>;
 
     /// Gets paginated inbound/outbound transaction calls for a certain address.
+    ///
+    /// NOTE: Only transactions that change the state of the address are found. Reth has no index of
+    /// the transactions that touch an address, so candidate blocks are looked up through the
+    /// account and storage history indices. Transactions that call the address without changing
+    /// its balance, nonce, code or storage, e.g. zero-value transfers, read-only calls or reverted
+    /// calls, are not returned.
+    ///
+    /// If the history of the oldest blocks has been pruned, they can't be searched and the last
+    /// page is never reported.
+    ///
+    /// Fails with invalid params if `page_size` is zero or the block tag is not supported.
     #[method(name = "searchTransactionsAfter")]
     async fn search_transactions_after(
         &self,
         address: Address,
         block_number: LenientBlockNumberOrTag,
         page_size: usize,
-    ) -> RpcResult<TransactionsWithReceipts>;
+    ) -> RpcResult<TransactionsWithReceipts<T>>;
 
     /// Gets the transaction hash for a certain sender address, given its nonce.
     #[method(name = "getTransactionBySenderAndNonce")]
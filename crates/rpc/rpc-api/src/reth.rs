@@ -1,11 +1,65 @@
 use alloy_eips::BlockId;
-use alloy_primitives::{Address, U256};
+use alloy_primitives::{Address, BlockNumber, TxNumber, U256};
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
-use std::collections::HashMap;
+use reth_prune_types::{PruneMode, PruneSegment};
+use reth_static_file_types::StaticFileSegment;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 
 // Required for the subscription attribute below
 use reth_chain_state as _;
 
+/// The pruning progress of a segment, as returned by `reth_getPruneCheckpoints`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PruneCheckpointInfo {
+    /// The highest pruned block, if any.
+    pub block_number: Option<BlockNumber>,
+    /// The highest pruned transaction, if the segment is pruned by transactions.
+    pub tx_number: Option<TxNumber>,
+    /// The prune mode the segment was pruned with.
+    pub prune_mode: PruneMode,
+}
+
+/// The retention the pruner achieved and the disk usage of the node, as returned by
+/// `reth_getPruneStatus`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PruneStatus {
+    /// Number of bytes used by the database.
+    pub database_size: u64,
+    /// Number of bytes used by the static files.
+    pub static_files_size: u64,
+    /// The distances the segments pruned by distance were last pruned with. They are lower than
+    /// the configured ones while the pruner reduces them to fit the disk budget.
+    pub distances: BTreeMap<PruneSegment, u64>,
+}
+
+/// The lowest blocks for which the node holds each kind of data, as returned by
+/// `reth_getLowestAvailableBlocks`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LowestAvailableBlocks {
+    /// The lowest block whose receipts are all available, neither pruned nor expired.
+    pub receipts: BlockNumber,
+    /// The lowest block whose account and storage changes are available.
+    pub history: BlockNumber,
+    /// The lowest block at which the state can be queried.
+    pub state: BlockNumber,
+    /// The lowest block whose body is available.
+    pub bodies: BlockNumber,
+}
+
+/// The range of blocks stored in the static files of a segment, as returned by
+/// `reth_getStaticFileRanges`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StaticFileBlockRange {
+    /// The lowest block.
+    pub start: BlockNumber,
+    /// The highest block.
+    pub end: BlockNumber,
+}
+
 /// Reth API namespace for reth-specific methods
 #[cfg_attr(not(feature = "client"), rpc(server, namespace = "reth"))]
 #[cfg_attr(feature = "client", rpc(server, client, namespace = "reth"))]
@@ -17,6 +71,39 @@ pub trait RethApi {
         block_id: BlockId,
     ) -> RpcResult<HashMap<Address, U256>>;
 
+    /// Returns the prune checkpoints of all pruned segments.
+    #[method(name = "getPruneCheckpoints")]
+    async fn reth_get_prune_checkpoints(
+        &self,
+    ) -> RpcResult<BTreeMap<PruneSegment, PruneCheckpointInfo>>;
+
+    /// Returns the distances the segments are currently pruned with and the disk usage of the
+    /// node.
+    #[method(name = "getPruneStatus")]
+    async fn reth_get_prune_status(&self) -> RpcResult<PruneStatus>;
+
+    /// Returns the lowest blocks for which receipts, history, state and bodies are available.
+    #[method(name = "getLowestAvailableBlocks")]
+    async fn reth_get_lowest_available_blocks(&self) -> RpcResult<LowestAvailableBlocks>;
+
+    /// Returns the block numbers that the pipeline stages have synced to.
+    #[method(name = "getStageCheckpoints")]
+    async fn reth_get_stage_checkpoints(&self) -> RpcResult<BTreeMap<String, BlockNumber>>;
+
+    /// Returns the ranges of blocks stored in the static files of each segment.
+    #[method(name = "getStaticFileRanges")]
+    async fn reth_get_static_file_ranges(
+        &self,
+    ) -> RpcResult<BTreeMap<StaticFileSegment, StaticFileBlockRange>>;
+
+    /// Replaces the log filter of the given output (`stdout`, `file` or `journald`), or of all
+    /// outputs if none is given.
+    ///
+    /// The filter uses the same syntax as `--log.stdout.filter`, e.g.
+    /// `engine::tree=trace,net=debug`.
+    #[method(name = "setLogFilter")]
+    async fn reth_set_log_filter(&self, filter: String, output: Option<String>) -> RpcResult<()>;
+
     /// Subscribe to json `ChainNotifications`
     #[subscription(
         name = "subscribeChainNotifications",
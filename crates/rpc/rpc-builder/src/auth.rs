@@ -1,6 +1,7 @@
 use crate::{
     error::{RpcError, ServerKind},
     middleware::RethRpcMiddleware,
+    trace_context::{RpcRequestTracing, RpcTraceContextLayer},
 };
 use http::header::AUTHORIZATION;
 use jsonrpsee::{
@@ -22,6 +23,7 @@ use tower::layer::util::Identity;
 pub use jsonrpsee::server::ServerBuilder;
 use jsonrpsee::server::{ServerConfig, ServerConfigBuilder};
 pub use reth_ipc::server::Builder as IpcServerBuilder;
+use reth_ipc::server::RpcServiceBuilder as IpcRpcServiceBuilder;
 
 /// Server configuration for the auth server.
 #[derive(Debug)]
@@ -82,10 +84,12 @@ impl<RpcMiddleware> AuthServerConfig<RpcMiddleware> {
         } = self;
 
         // Create auth middleware.
-        let middleware =
-            tower::ServiceBuilder::new().layer(AuthLayer::new(JwtAuthValidator::new(secret)));
+        let middleware = tower::ServiceBuilder::new()
+            .layer(RpcTraceContextLayer)
+            .layer(AuthLayer::new(JwtAuthValidator::new(secret)));
 
-        let rpc_middleware = RpcServiceBuilder::default().layer(rpc_middleware);
+        let rpc_middleware =
+            RpcServiceBuilder::default().layer(RpcRequestTracing).layer(rpc_middleware);
 
         // By default, both http and ws are enabled.
         let server = ServerBuilder::new()
@@ -106,7 +110,9 @@ impl<RpcMiddleware> AuthServerConfig<RpcMiddleware> {
             let ipc_endpoint_str = ipc_endpoint
                 .clone()
                 .unwrap_or_else(|| constants::DEFAULT_ENGINE_API_IPC_ENDPOINT.to_string());
-            let ipc_server = ipc_server_config.build(ipc_endpoint_str);
+            let ipc_server = ipc_server_config
+                .set_rpc_middleware(IpcRpcServiceBuilder::new().layer(RpcRequestTracing))
+                .build(ipc_endpoint_str);
             let res = ipc_server.start(module.inner).await?;
             Some(res)
         } else {
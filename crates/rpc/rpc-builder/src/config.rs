@@ -1,9 +1,9 @@
 use jsonrpsee::server::ServerConfigBuilder;
 use reth_node_core::{args::RpcServerArgs, utils::get_or_create_jwt_secret_from_path};
-use reth_rpc::ValidationApiConfig;
+use reth_rpc::{DebugApiConfig, ValidationApiConfig};
 use reth_rpc_eth_types::{EthConfig, EthStateCacheConfig, GasPriceOracleConfig};
 use reth_rpc_layer::{JwtError, JwtSecret};
-use reth_rpc_server_types::RpcModuleSelection;
+use reth_rpc_server_types::{RpcModuleSelection, RpcRateLimitConfig};
 use std::{net::SocketAddr, path::PathBuf};
 use tower::layer::util::Identity;
 use tracing::{debug, warn};
@@ -30,6 +30,12 @@ pub trait RethRpcServerConfig {
     /// The configured ethereum RPC settings.
     fn flashbots_config(&self) -> ValidationApiConfig;
 
+    /// The configured debug RPC settings.
+    ///
+    /// The `default_trace_dir` is used as the trace directory in case the `rpc_debug_trace_dir`
+    /// argument is not provided.
+    fn debug_config(&self, default_trace_dir: PathBuf) -> DebugApiConfig;
+
     /// Returns state cache configuration.
     fn state_cache_config(&self) -> EthStateCacheConfig;
 
@@ -54,6 +60,11 @@ pub trait RethRpcServerConfig {
     /// Returns the default ipc server builder
     fn ipc_server_builder(&self) -> IpcServerBuilder<Identity, Identity>;
 
+    /// Returns the per client rate limit configuration.
+    ///
+    /// This merges the rules passed on the command line into the configured file.
+    fn rate_limit_config(&self) -> RpcRateLimitConfig;
+
     /// Creates the [`RpcServerConfig`] from cli args.
     fn rpc_server_config(&self) -> RpcServerConfig;
 
@@ -112,6 +123,12 @@ impl RethRpcServerConfig for RpcServerArgs {
         }
     }
 
+    fn debug_config(&self, default_trace_dir: PathBuf) -> DebugApiConfig {
+        DebugApiConfig {
+            trace_dir: Some(self.rpc_debug_trace_dir.clone().unwrap_or(default_trace_dir)),
+        }
+    }
+
     fn state_cache_config(&self) -> EthStateCacheConfig {
         EthStateCacheConfig {
             max_blocks: self.rpc_state_cache.max_blocks,
@@ -176,8 +193,19 @@ impl RethRpcServerConfig for RpcServerArgs {
             .max_connections(self.rpc_max_connections.get())
     }
 
+    fn rate_limit_config(&self) -> RpcRateLimitConfig {
+        let mut config = self.rpc_rate_limit_config.clone().unwrap_or_default();
+        config.rules.extend(self.rpc_rate_limit.iter().cloned());
+        if let Some(header) = &self.rpc_rate_limit_ip_header {
+            config.client_ip_header = Some(header.clone());
+        }
+        config
+    }
+
     fn rpc_server_config(&self) -> RpcServerConfig {
-        let mut config = RpcServerConfig::default().with_jwt_secret(self.rpc_secret_key());
+        let mut config = RpcServerConfig::default()
+            .with_jwt_secret(self.rpc_secret_key())
+            .with_rate_limit(self.rate_limit_config());
 
         if self.http_api.is_some() && !self.http {
             warn!(
@@ -354,6 +382,48 @@ mod tests {
         assert_eq!(config.ipc_endpoint().unwrap(), constants::DEFAULT_IPC_ENDPOINT);
     }
 
+    #[test]
+    fn test_rate_limit_config() {
+        let args = CommandParser::<RpcServerArgs>::parse_from(["reth"]).args;
+        assert!(args.rate_limit_config().is_empty());
+
+        let path = std::env::temp_dir().join("reth-test-rpc-rate-limit.toml");
+        std::fs::write(
+            &path,
+            r#"
+client_ip_header = "x-real-ip"
+
+[[rules]]
+methods = "*"
+requests_per_second = 100
+"#,
+        )
+        .unwrap();
+        let args = CommandParser::<RpcServerArgs>::parse_from([
+            "reth",
+            "--rpc.rate-limit.config",
+            path.to_str().unwrap(),
+            "--rpc.rate-limit",
+            "eth_call=10/20,debug=1",
+            "--rpc.rate-limit.ip-header",
+            "x-forwarded-for",
+        ])
+        .args;
+        std::fs::remove_file(&path).unwrap();
+
+        let config = args.rate_limit_config();
+        let rules = config.rules.iter().map(ToString::to_string).collect::<Vec<_>>();
+        assert_eq!(rules, ["*=100", "eth_call=10/20", "debug=1"]);
+        assert_eq!(config.client_ip_header.as_deref(), Some("x-forwarded-for"));
+
+        let args = CommandParser::<RpcServerArgs>::try_parse_from([
+            "reth",
+            "--rpc.rate-limit",
+            "eth_call=0",
+        ]);
+        assert!(args.is_err());
+    }
+
     #[test]
     fn test_zero_filter_limits() {
         let args = CommandParser::<RpcServerArgs>::parse_from([
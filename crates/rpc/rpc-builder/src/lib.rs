@@ -19,7 +19,12 @@
 #![cfg_attr(not(test), warn(unused_crate_dependencies))]
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 
-use crate::{auth::AuthRpcModule, error::WsHttpSamePortError, metrics::RpcRequestMetrics};
+use crate::{
+    auth::AuthRpcModule,
+    error::WsHttpSamePortError,
+    metrics::RpcRequestMetrics,
+    rate_limiter::{RpcClientIdLayer, RpcClientRateLimiter},
+};
 use alloy_network::Ethereum;
 use alloy_provider::{fillers::RecommendedFillers, Provider, ProviderBuilder};
 use core::marker::PhantomData;
@@ -36,8 +41,9 @@ use reth_evm::ConfigureEvm;
 use reth_network_api::{noop::NoopNetwork, NetworkInfo, Peers};
 use reth_primitives_traits::NodePrimitives;
 use reth_rpc::{
-    AdminApi, DebugApi, EngineEthApi, EthApi, EthApiBuilder, EthBundle, MinerApi, NetApi,
-    OtterscanApi, RPCApi, RethApi, TraceApi, TxPoolApi, ValidationApiConfig, Web3Api,
+    AdminApi, BundlePoolApi, DebugApi, DebugApiConfig, EngineEthApi, EthApi, EthApiBuilder,
+    EthBundle, MinerApi, NetApi, OtterscanApi, RPCApi, RethApi, TraceApi, TxPoolApi,
+    ValidationApiConfig, Web3Api,
 };
 use reth_rpc_api::servers::*;
 use reth_rpc_eth_api::{
@@ -52,8 +58,8 @@ use reth_rpc_eth_api::{
 use reth_rpc_eth_types::{receipt::EthReceiptConverter, EthConfig, EthSubscriptionIdProvider};
 use reth_rpc_layer::{AuthLayer, Claims, CompressionLayer, JwtAuthValidator, JwtSecret};
 use reth_storage_api::{
-    AccountReader, BlockReader, ChangeSetReader, FullRpcProvider, ProviderBlock,
-    StateProviderFactory,
+    AccountReader, BlockReader, ChangeSetReader, DiskUsageReader, FullRpcProvider, ProviderBlock,
+    PruneCheckpointReader, StateProviderFactory, StaticFileRangeReader,
 };
 use reth_tasks::{pool::BlockingTaskGuard, TaskSpawner, TokioTaskExecutor};
 use reth_transaction_pool::{noop::NoopTransactionPool, TransactionPool};
@@ -74,7 +80,9 @@ use jsonrpsee::server::ServerConfigBuilder;
 pub use reth_ipc::server::{
     Builder as IpcServerBuilder, RpcServiceBuilder as IpcRpcServiceBuilder,
 };
-pub use reth_rpc_server_types::{constants, RethRpcModule, RpcModuleSelection};
+pub use reth_rpc_server_types::{
+    constants, RethRpcModule, RpcModuleSelection, RpcRateLimitConfig, RpcRateLimitRule,
+};
 pub use tower::layer::util::{Identity, Stack};
 
 /// Auth server utilities.
@@ -106,6 +114,14 @@ use reth_rpc::eth::sim_bundle::EthSimBundle;
 // Rpc rate limiter
 pub mod rate_limiter;
 
+// Http and ws server
+mod server;
+use server::RpcHttpServer;
+
+// Rpc request tracing
+pub mod trace_context;
+use trace_context::{RpcRequestTracing, RpcTraceContextLayer};
+
 /// A builder type to configure the RPC module: See [`RpcModule`]
 ///
 /// This is the main entrypoint and the easiest way to configure an RPC server.
@@ -309,7 +325,10 @@ where
     Provider: FullRpcProvider<Block = N::Block, Receipt = N::Receipt, Header = N::BlockHeader>
         + CanonStateSubscriptions<Primitives = N>
         + AccountReader
-        + ChangeSetReader,
+        + ChangeSetReader
+        + PruneCheckpointReader
+        + StaticFileRangeReader
+        + DiskUsageReader,
     Pool: TransactionPool + 'static,
     Network: NetworkInfo + Peers + Clone + 'static,
     EvmConfig: ConfigureEvm<Primitives = N> + 'static,
@@ -416,6 +435,8 @@ pub struct RpcModuleConfig {
     eth: EthConfig,
     /// `flashbots` namespace settings
     flashbots: ValidationApiConfig,
+    /// `debug` namespace settings
+    debug: DebugApiConfig,
 }
 
 // === impl RpcModuleConfig ===
@@ -428,7 +449,13 @@ impl RpcModuleConfig {
 
     /// Returns a new RPC module config given the eth namespace config
     pub const fn new(eth: EthConfig, flashbots: ValidationApiConfig) -> Self {
-        Self { eth, flashbots }
+        Self { eth, flashbots, debug: DebugApiConfig { trace_dir: None } }
+    }
+
+    /// Sets the debug namespace config
+    pub fn with_debug(mut self, debug: DebugApiConfig) -> Self {
+        self.debug = debug;
+        self
     }
 
     /// Get a reference to the eth namespace config
@@ -440,6 +467,16 @@ impl RpcModuleConfig {
     pub const fn eth_mut(&mut self) -> &mut EthConfig {
         &mut self.eth
     }
+
+    /// Get a reference to the debug namespace config
+    pub const fn debug(&self) -> &DebugApiConfig {
+        &self.debug
+    }
+
+    /// Get a mutable reference to the debug namespace config
+    pub const fn debug_mut(&mut self) -> &mut DebugApiConfig {
+        &mut self.debug
+    }
 }
 
 /// Configures [`RpcModuleConfig`]
@@ -447,6 +484,7 @@ impl RpcModuleConfig {
 pub struct RpcModuleConfigBuilder {
     eth: Option<EthConfig>,
     flashbots: Option<ValidationApiConfig>,
+    debug: Option<DebugApiConfig>,
 }
 
 // === impl RpcModuleConfigBuilder ===
@@ -464,10 +502,20 @@ impl RpcModuleConfigBuilder {
         self
     }
 
+    /// Configures a custom debug namespace config
+    pub fn debug(mut self, debug: DebugApiConfig) -> Self {
+        self.debug = Some(debug);
+        self
+    }
+
     /// Consumes the type and creates the [`RpcModuleConfig`]
     pub fn build(self) -> RpcModuleConfig {
-        let Self { eth, flashbots } = self;
-        RpcModuleConfig { eth: eth.unwrap_or_default(), flashbots: flashbots.unwrap_or_default() }
+        let Self { eth, flashbots, debug } = self;
+        RpcModuleConfig {
+            eth: eth.unwrap_or_default(),
+            flashbots: flashbots.unwrap_or_default(),
+            debug: debug.unwrap_or_default(),
+        }
     }
 
     /// Get a reference to the eth namespace config, if any
@@ -511,6 +559,8 @@ pub struct RpcRegistryInner<
     modules: HashMap<RethRpcModule, Methods>,
     /// eth config settings
     eth_config: EthConfig,
+    /// debug config settings
+    debug_config: DebugApiConfig,
 }
 
 // === impl RpcRegistryInner ===
@@ -559,6 +609,7 @@ where
             modules: Default::default(),
             blocking_pool_guard,
             eth_config: config.eth,
+            debug_config: config.debug,
             evm_config,
         }
     }
@@ -660,6 +711,9 @@ where
             Transaction = N::SignedTx,
         > + AccountReader
         + ChangeSetReader
+        + PruneCheckpointReader
+        + StaticFileRangeReader
+        + DiskUsageReader
         + CanonStateSubscriptions,
     Network: NetworkInfo + Peers + Clone + 'static,
     EthApi: EthApiServer<
@@ -775,7 +829,10 @@ where
             Transaction = N::SignedTx,
             Receipt = N::Receipt,
         > + AccountReader
-        + ChangeSetReader,
+        + ChangeSetReader
+        + PruneCheckpointReader
+        + StaticFileRangeReader
+        + DiskUsageReader,
     Network: NetworkInfo + Peers + Clone + 'static,
     EthApi: EthApiTypes,
     EvmConfig: ConfigureEvm<Primitives = N>,
@@ -808,7 +865,11 @@ where
     ///
     /// If called outside of the tokio runtime. See also [`Self::eth_api`]
     pub fn debug_api(&self) -> DebugApi<EthApi> {
-        DebugApi::new(self.eth_api().clone(), self.blocking_pool_guard.clone())
+        DebugApi::with_config(
+            self.eth_api().clone(),
+            self.blocking_pool_guard.clone(),
+            self.debug_config.clone(),
+        )
     }
 
     /// Instantiates `NetApi`
@@ -837,7 +898,10 @@ where
     Provider: FullRpcProvider<Block = N::Block>
         + CanonStateSubscriptions<Primitives = N>
         + AccountReader
-        + ChangeSetReader,
+        + ChangeSetReader
+        + PruneCheckpointReader
+        + StaticFileRangeReader
+        + DiskUsageReader,
     Pool: TransactionPool + 'static,
     Network: NetworkInfo + Peers + Clone + 'static,
     EthApi: FullEthApiServer,
@@ -925,11 +989,13 @@ where
                                 .into_rpc()
                                 .into()
                         }
-                        RethRpcModule::Debug => {
-                            DebugApi::new(eth_api.clone(), self.blocking_pool_guard.clone())
-                                .into_rpc()
-                                .into()
-                        }
+                        RethRpcModule::Debug => DebugApi::with_config(
+                            eth_api.clone(),
+                            self.blocking_pool_guard.clone(),
+                            self.debug_config.clone(),
+                        )
+                        .into_rpc()
+                        .into(),
                         RethRpcModule::Eth => {
                             // merge all eth handlers
                             let mut module = eth_api.clone().into_rpc();
@@ -944,6 +1010,15 @@ where
                                     .into_rpc(),
                                 )
                                 .expect("No conflicts");
+                            // bundle submission is only served if the bundle pool is enabled
+                            if eth_api.pool().bundle_pool().is_some() {
+                                module
+                                    .merge(EthSendBundleApiServer::into_rpc(BundlePoolApi::new(
+                                        eth_api.clone(),
+                                        self.blocking_pool_guard.clone(),
+                                    )))
+                                    .expect("No conflicts");
+                            }
 
                             module.into()
                         }
@@ -978,16 +1053,29 @@ where
                                 .into_rpc()
                                 .into()
                         }
-                        // only relevant for Ethereum and configured in `EthereumAddOns`
-                        // implementation
-                        // TODO: can we get rid of this here?
-                        RethRpcModule::Flashbots => Default::default(),
                         RethRpcModule::Miner => MinerApi::default().into_rpc().into(),
                         RethRpcModule::Mev => {
-                            EthSimBundle::new(eth_api.clone(), self.blocking_pool_guard.clone())
-                                .into_rpc()
-                                .into()
+                            let mut module = EthSimBundle::new(
+                                eth_api.clone(),
+                                self.blocking_pool_guard.clone(),
+                            )
+                            .into_rpc();
+                            if eth_api.pool().bundle_pool().is_some() {
+                                module
+                                    .merge(MevSendBundleApiServer::into_rpc(BundlePoolApi::new(
+                                        eth_api.clone(),
+                                        self.blocking_pool_guard.clone(),
+                                    )))
+                                    .expect("No conflicts");
+                            }
+
+                            module.into()
                         }
+                        // only relevant for Ethereum and configured in `EthereumAddOns`
+                        // implementation, anvil is only relevant for dev nodes and configured by
+                        // the node builder once the local miner exists
+                        // TODO: can we get rid of this here?
+                        RethRpcModule::Flashbots | RethRpcModule::Anvil => Default::default(),
                     })
                     .clone()
             })
@@ -1028,6 +1116,8 @@ pub struct RpcServerConfig<RpcMiddleware = Identity> {
     ipc_endpoint: Option<String>,
     /// JWT secret for authentication
     jwt_secret: Option<JwtSecret>,
+    /// Per client rate limiter for the http and ws servers
+    rate_limiter: Option<RpcClientRateLimiter>,
     /// Configurable RPC middleware
     rpc_middleware: RpcMiddleware,
 }
@@ -1048,6 +1138,7 @@ impl Default for RpcServerConfig<Identity> {
             ipc_server_config: None,
             ipc_endpoint: None,
             jwt_secret: None,
+            rate_limiter: None,
             rpc_middleware: Default::default(),
         }
     }
@@ -1112,6 +1203,7 @@ impl<RpcMiddleware> RpcServerConfig<RpcMiddleware> {
             ipc_server_config: self.ipc_server_config,
             ipc_endpoint: self.ipc_endpoint,
             jwt_secret: self.jwt_secret,
+            rate_limiter: self.rate_limiter,
             rpc_middleware,
         }
     }
@@ -1191,6 +1283,14 @@ impl<RpcMiddleware> RpcServerConfig<RpcMiddleware> {
         self
     }
 
+    /// Configures per client rate limiting for the http and ws servers.
+    ///
+    /// No rate limiting is applied if the config doesn't contain any rules.
+    pub fn with_rate_limit(mut self, config: RpcRateLimitConfig) -> Self {
+        self.rate_limiter = (!config.is_empty()).then(|| RpcClientRateLimiter::new(config));
+        self
+    }
+
     /// Returns true if any server is configured.
     ///
     /// If no server is configured, no server will be launched on [`RpcServerConfig::start`].
@@ -1220,6 +1320,14 @@ impl<RpcMiddleware> RpcServerConfig<RpcMiddleware> {
         cors.as_deref().map(cors::create_cors_layer).transpose()
     }
 
+    /// Creates the [`RpcClientIdLayer`] if rate limiting is configured
+    fn maybe_client_id_layer(
+        rate_limiter: Option<&RpcClientRateLimiter>,
+        jwt_secret: Option<JwtSecret>,
+    ) -> Option<RpcClientIdLayer> {
+        rate_limiter.map(|limiter| limiter.client_id_layer(jwt_secret))
+    }
+
     /// Creates the [`AuthLayer`] if any
     fn maybe_jwt_layer(jwt_secret: Option<JwtSecret>) -> Option<AuthLayer<JwtAuthValidator>> {
         jwt_secret.map(|secret| AuthLayer::new(JwtAuthValidator::new(secret)))
@@ -1264,7 +1372,9 @@ impl<RpcMiddleware> RpcServerConfig<RpcMiddleware> {
 
         if let Some(builder) = self.ipc_server_config {
             let ipc = builder
-                .set_rpc_middleware(IpcRpcServiceBuilder::new().layer(metrics))
+                .set_rpc_middleware(
+                    IpcRpcServiceBuilder::new().layer(RpcRequestTracing).layer(metrics),
+                )
                 .build(ipc_path);
             ipc_handle = Some(ipc.start(modules.ipc.clone().expect("ipc server error")).await?);
         }
@@ -1296,14 +1406,21 @@ impl<RpcMiddleware> RpcServerConfig<RpcMiddleware> {
                 let server = ServerBuilder::new()
                     .set_http_middleware(
                         tower::ServiceBuilder::new()
+                            .layer(RpcTraceContextLayer)
                             .option_layer(Self::maybe_cors_layer(cors)?)
                             .option_layer(Self::maybe_jwt_layer(self.jwt_secret))
+                            .option_layer(Self::maybe_client_id_layer(
+                                self.rate_limiter.as_ref(),
+                                self.jwt_secret,
+                            ))
                             .option_layer(Self::maybe_compression_layer(
                                 self.http_disable_compression,
                             )),
                     )
                     .set_rpc_middleware(
                         RpcServiceBuilder::default()
+                            .layer(RpcRequestTracing)
+                            .option_layer(self.rate_limiter.clone())
                             .layer(
                                 modules
                                     .http
@@ -1314,17 +1431,21 @@ impl<RpcMiddleware> RpcServerConfig<RpcMiddleware> {
                             )
                             .layer(self.rpc_middleware.clone()),
                     )
-                    .set_config(config.build())
-                    .build(http_socket_addr)
-                    .await
-                    .map_err(|err| {
-                        RpcError::server_error(err, ServerKind::WsHttp(http_socket_addr))
-                    })?;
+                    .set_config(config.build());
+                let server =
+                    RpcHttpServer::bind(server, http_socket_addr, self.rate_limiter.is_some())
+                        .await
+                        .map_err(|err| {
+                            RpcError::server_error(err, ServerKind::WsHttp(http_socket_addr))
+                        })?;
                 let addr = server.local_addr().map_err(|err| {
                     RpcError::server_error(err, ServerKind::WsHttp(http_socket_addr))
                 })?;
                 if let Some(module) = modules.http.as_ref().or(modules.ws.as_ref()) {
-                    let handle = server.start(module.clone());
+                    let handle = match server {
+                        RpcHttpServer::Jsonrpsee(server) => server.start(module.clone()),
+                        RpcHttpServer::RemoteAddr(server) => server.start(module.clone()),
+                    };
                     http_handle = Some(handle.clone());
                     ws_handle = Some(handle);
                 }
@@ -1350,15 +1471,22 @@ impl<RpcMiddleware> RpcServerConfig<RpcMiddleware> {
                 .set_config(config.ws_only().build())
                 .set_http_middleware(
                     tower::ServiceBuilder::new()
+                        .layer(RpcTraceContextLayer)
                         .option_layer(Self::maybe_cors_layer(self.ws_cors_domains.clone())?)
-                        .option_layer(Self::maybe_jwt_layer(self.jwt_secret)),
+                        .option_layer(Self::maybe_jwt_layer(self.jwt_secret))
+                        .option_layer(Self::maybe_client_id_layer(
+                            self.rate_limiter.as_ref(),
+                            self.jwt_secret,
+                        )),
                 )
                 .set_rpc_middleware(
                     RpcServiceBuilder::default()
+                        .layer(RpcRequestTracing)
+                        .option_layer(self.rate_limiter.clone())
                         .layer(modules.ws.as_ref().map(RpcRequestMetrics::ws).unwrap_or_default())
                         .layer(self.rpc_middleware.clone()),
-                )
-                .build(ws_socket_addr)
+                );
+            let server = RpcHttpServer::bind(server, ws_socket_addr, self.rate_limiter.is_some())
                 .await
                 .map_err(|err| RpcError::server_error(err, ServerKind::WS(ws_socket_addr)))?;
 
@@ -1375,18 +1503,25 @@ impl<RpcMiddleware> RpcServerConfig<RpcMiddleware> {
                 .set_config(config.http_only().build())
                 .set_http_middleware(
                     tower::ServiceBuilder::new()
+                        .layer(RpcTraceContextLayer)
                         .option_layer(Self::maybe_cors_layer(self.http_cors_domains.clone())?)
                         .option_layer(Self::maybe_jwt_layer(self.jwt_secret))
+                        .option_layer(Self::maybe_client_id_layer(
+                            self.rate_limiter.as_ref(),
+                            self.jwt_secret,
+                        ))
                         .option_layer(Self::maybe_compression_layer(self.http_disable_compression)),
                 )
                 .set_rpc_middleware(
                     RpcServiceBuilder::default()
+                        .layer(RpcRequestTracing)
+                        .option_layer(self.rate_limiter.clone())
                         .layer(
                             modules.http.as_ref().map(RpcRequestMetrics::http).unwrap_or_default(),
                         )
                         .layer(self.rpc_middleware.clone()),
-                )
-                .build(http_socket_addr)
+                );
+            let server = RpcHttpServer::bind(server, http_socket_addr, self.rate_limiter.is_some())
                 .await
                 .map_err(|err| RpcError::server_error(err, ServerKind::Http(http_socket_addr)))?;
             let local_addr = server
@@ -1396,10 +1531,20 @@ impl<RpcMiddleware> RpcServerConfig<RpcMiddleware> {
             http_server = Some(server);
         }
 
-        http_handle = http_server
-            .map(|http_server| http_server.start(modules.http.clone().expect("http server error")));
-        ws_handle = ws_server
-            .map(|ws_server| ws_server.start(modules.ws.clone().expect("ws server error")));
+        http_handle = http_server.map(|http_server| {
+            let module = modules.http.clone().expect("http server error");
+            match http_server {
+                RpcHttpServer::Jsonrpsee(server) => server.start(module),
+                RpcHttpServer::RemoteAddr(server) => server.start(module),
+            }
+        });
+        ws_handle = ws_server.map(|ws_server| {
+            let module = modules.ws.clone().expect("ws server error");
+            match ws_server {
+                RpcHttpServer::Jsonrpsee(server) => server.start(module),
+                RpcHttpServer::RemoteAddr(server) => server.start(module),
+            }
+        });
         Ok(RpcServerHandle {
             http_local_addr,
             ws_local_addr,
@@ -1,15 +1,33 @@
-//! [`jsonrpsee`] helper layer for rate limiting certain methods.
+//! [`jsonrpsee`] helper layers for rate limiting RPC calls.
+//!
+//! - [`RpcRequestRateLimiter`] limits the number of concurrent expensive calls.
+//! - [`RpcClientRateLimiter`] limits the number of calls per second of each client.
 
-use jsonrpsee::{server::middleware::rpc::RpcServiceT, types::Request};
+use http::{header, HeaderMap, HeaderName, Request as HttpRequest};
+use jsonrpsee::{
+    core::middleware::{Batch, BatchEntry, BatchEntryErr, Notification, ResponseFuture},
+    server::middleware::rpc::RpcServiceT,
+    types::{ErrorObject, ErrorObjectOwned, Request},
+    MethodResponse,
+};
+use jsonwebtoken::{Algorithm, DecodingKey, Validation};
+use parking_lot::Mutex;
+use reth_metrics::{metrics::Counter, Metrics};
+use reth_rpc_layer::JwtSecret;
+use reth_rpc_server_types::RpcRateLimitConfig;
+use schnellru::{ByLength, LruMap};
+use serde::Deserialize;
 use std::{
     future::Future,
+    net::{IpAddr, SocketAddr},
     pin::Pin,
     sync::Arc,
     task::{ready, Context, Poll},
+    time::{Duration, Instant},
 };
 use tokio::sync::{OwnedSemaphorePermit, Semaphore};
 use tokio_util::sync::PollSemaphore;
-use tower::Layer;
+use tower::{Layer, Service};
 
 /// Rate limiter for the RPC server.
 ///
@@ -86,14 +104,14 @@ where
 
     fn batch<'a>(
         &self,
-        requests: jsonrpsee::core::middleware::Batch<'a>,
+        requests: Batch<'a>,
     ) -> impl Future<Output = Self::BatchResponse> + Send + 'a {
         self.inner.batch(requests)
     }
 
     fn notification<'a>(
         &self,
-        n: jsonrpsee::core::middleware::Notification<'a>,
+        n: Notification<'a>,
     ) -> impl Future<Output = Self::NotificationResponse> + Send + 'a {
         self.inner.notification(n)
     }
@@ -130,3 +148,417 @@ impl<F: Future> Future for RateLimitingRequestFuture<F> {
         res
     }
 }
+
+/// JSON-RPC error code returned for calls rejected by the [`RpcClientRateLimiter`].
+pub const RATE_LIMITED_ERROR_CODE: i32 = -32005;
+
+/// Maximum number of `(rule, client)` buckets tracked by the [`RpcClientRateLimiter`].
+///
+/// The least recently used bucket is evicted first, which resets the limit of that client.
+const MAX_RATE_LIMIT_BUCKETS: u32 = 100_000;
+
+/// Identifies the client of an RPC call for rate limiting purposes.
+///
+/// This is inserted into the request extensions by the [`RpcClientIdLayer`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum RpcClientId {
+    /// The `sub` claim of the client's JWT.
+    Subject(String),
+    /// The client IP address, as reported by the configured header.
+    Ip(IpAddr),
+}
+
+/// Token-bucket rate limiter for RPC calls, keyed by method group and client.
+///
+/// See [`RpcRateLimitConfig`] for how rules are matched and clients are identified.
+#[derive(Clone)]
+pub struct RpcClientRateLimiter {
+    inner: Arc<RpcClientRateLimiterInner>,
+}
+
+impl RpcClientRateLimiter {
+    /// Creates a new rate limiter for the given configuration.
+    pub fn new(config: RpcRateLimitConfig) -> Self {
+        let metrics = config
+            .rules
+            .iter()
+            .map(|rule| RpcRateLimitMetrics::new_with_labels(&[("methods", rule.methods.clone())]))
+            .collect();
+        Self {
+            inner: Arc::new(RpcClientRateLimiterInner {
+                config,
+                metrics,
+                buckets: Mutex::new(LruMap::new(ByLength::new(MAX_RATE_LIMIT_BUCKETS))),
+            }),
+        }
+    }
+
+    /// Returns the configuration of the rate limiter.
+    pub fn config(&self) -> &RpcRateLimitConfig {
+        &self.inner.config
+    }
+
+    /// Returns the HTTP layer that identifies the client of incoming requests.
+    ///
+    /// If a `jwt_secret` is given, clients are identified by the `sub` claim of their JWT.
+    pub fn client_id_layer(&self, jwt_secret: Option<JwtSecret>) -> RpcClientIdLayer {
+        RpcClientIdLayer {
+            jwt_secret,
+            ip_header: self
+                .inner
+                .config
+                .client_ip_header
+                .as_deref()
+                .and_then(|header| HeaderName::try_from(header).ok()),
+        }
+    }
+
+    /// Takes a token for the call, returns the error response if the client is throttled.
+    fn check(&self, method: &str, client: Option<&RpcClientId>) -> Result<(), ErrorObjectOwned> {
+        self.try_acquire(method, client, Instant::now()).map_err(|retry_after| {
+            ErrorObject::owned(
+                RATE_LIMITED_ERROR_CODE,
+                "rate limit exceeded",
+                Some(serde_json::json!({ "retryAfterMs": retry_after.as_millis() as u64 })),
+            )
+        })
+    }
+
+    /// Takes a token from the bucket of the most specific rule for the call.
+    ///
+    /// Returns the time until the next token is available if the bucket is empty.
+    fn try_acquire(
+        &self,
+        method: &str,
+        client: Option<&RpcClientId>,
+        now: Instant,
+    ) -> Result<(), Duration> {
+        let Some(rule_idx) = self.inner.config.rule_index(method) else { return Ok(()) };
+        let rule = &self.inner.config.rules[rule_idx];
+        let rate = rule.requests_per_second.get() as f64;
+        let burst = rule.burst().get() as f64;
+
+        let res = self
+            .inner
+            .buckets
+            .lock()
+            .get_or_insert((rule_idx, client.cloned()), || TokenBucket::new(burst, now))
+            .map_or(Ok(()), |bucket| bucket.try_acquire(rate, burst, now));
+        if res.is_err() {
+            self.inner.metrics[rule_idx].throttled_calls_total.increment(1);
+        }
+        res
+    }
+}
+
+impl std::fmt::Debug for RpcClientRateLimiter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RpcClientRateLimiter").field("config", &self.inner.config).finish()
+    }
+}
+
+impl<S> Layer<S> for RpcClientRateLimiter {
+    type Service = RpcClientRateLimitingService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RpcClientRateLimitingService::new(inner, self.clone())
+    }
+}
+
+/// Shared state of the [`RpcClientRateLimiter`].
+struct RpcClientRateLimiterInner {
+    /// The configured rules
+    config: RpcRateLimitConfig,
+    /// Metrics for each rule, by rule index
+    metrics: Vec<RpcRateLimitMetrics>,
+    /// Token buckets keyed by rule index and client
+    buckets: Mutex<LruMap<(usize, Option<RpcClientId>), TokenBucket>>,
+}
+
+/// A token bucket that refills continuously at the configured rate.
+#[derive(Debug)]
+struct TokenBucket {
+    /// Available tokens, at most the burst size
+    tokens: f64,
+    /// When the bucket was last refilled
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    /// Creates a full bucket.
+    const fn new(burst: f64, now: Instant) -> Self {
+        Self { tokens: burst, last_refill: now }
+    }
+
+    /// Refills the bucket and takes a token, returns the time until the next token is available
+    /// if the bucket is empty.
+    fn try_acquire(&mut self, rate: f64, burst: f64, now: Instant) -> Result<(), Duration> {
+        let elapsed = now.saturating_duration_since(self.last_refill).as_secs_f64();
+        self.tokens = elapsed.mul_add(rate, self.tokens).min(burst);
+        self.last_refill = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - self.tokens) / rate))
+        }
+    }
+}
+
+/// A [`RpcServiceT`] middleware that rejects calls of clients that exceeded their rate limit.
+///
+/// Notifications are never rate limited because they can't be answered with an error.
+#[derive(Debug, Clone)]
+pub struct RpcClientRateLimitingService<S> {
+    /// The rate limiter for RPC calls
+    rate_limiter: RpcClientRateLimiter,
+    /// The inner service being wrapped
+    inner: S,
+}
+
+impl<S> RpcClientRateLimitingService<S> {
+    /// Create a new rate limited service.
+    pub const fn new(service: S, rate_limiter: RpcClientRateLimiter) -> Self {
+        Self { inner: service, rate_limiter }
+    }
+}
+
+impl<S> RpcServiceT for RpcClientRateLimitingService<S>
+where
+    S: RpcServiceT<MethodResponse = MethodResponse> + Send + Sync + Clone + 'static,
+{
+    type MethodResponse = S::MethodResponse;
+    type NotificationResponse = S::NotificationResponse;
+    type BatchResponse = S::BatchResponse;
+
+    fn call<'a>(&self, req: Request<'a>) -> impl Future<Output = Self::MethodResponse> + Send + 'a {
+        match self.rate_limiter.check(req.method_name(), req.extensions().get()) {
+            Ok(()) => ResponseFuture::future(self.inner.call(req)),
+            Err(err) => ResponseFuture::ready(MethodResponse::error(req.id, err)),
+        }
+    }
+
+    fn batch<'a>(
+        &self,
+        mut requests: Batch<'a>,
+    ) -> impl Future<Output = Self::BatchResponse> + Send + 'a {
+        for entry in requests.iter_mut() {
+            let Ok(BatchEntry::Call(req)) = entry else { continue };
+            if let Err(err) = self.rate_limiter.check(req.method_name(), req.extensions().get()) {
+                *entry = Err(BatchEntryErr::new(req.id.clone(), err));
+            }
+        }
+        self.inner.batch(requests)
+    }
+
+    fn notification<'a>(
+        &self,
+        n: Notification<'a>,
+    ) -> impl Future<Output = Self::NotificationResponse> + Send + 'a {
+        self.inner.notification(n)
+    }
+}
+
+/// HTTP layer that inserts the [`RpcClientId`] of a request into its extensions.
+///
+/// This must be installed after the JWT auth layer, because the token is not verified again.
+#[derive(Debug, Clone)]
+pub struct RpcClientIdLayer {
+    /// The secret used to decode the JWT of the client
+    jwt_secret: Option<JwtSecret>,
+    /// The header that carries the client IP address
+    ip_header: Option<HeaderName>,
+}
+
+impl RpcClientIdLayer {
+    /// Returns the id of the client that sent the given request.
+    ///
+    /// The JWT subject takes precedence over the IP address from the configured header, which
+    /// takes precedence over the IP address of the connection.
+    fn client_id<B>(&self, req: &HttpRequest<B>) -> Option<RpcClientId> {
+        let headers = req.headers();
+        if let Some(subject) =
+            self.jwt_secret.as_ref().and_then(|secret| jwt_subject(secret, headers))
+        {
+            return Some(RpcClientId::Subject(subject))
+        }
+
+        self.ip_header
+            .as_ref()
+            .and_then(|ip_header| forwarded_ip(headers, ip_header))
+            .or_else(|| req.extensions().get::<SocketAddr>().map(SocketAddr::ip))
+            .map(RpcClientId::Ip)
+    }
+}
+
+impl<S> Layer<S> for RpcClientIdLayer {
+    type Service = RpcClientIdService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RpcClientIdService { layer: self.clone(), inner }
+    }
+}
+
+/// HTTP service that inserts the [`RpcClientId`] of a request into its extensions.
+#[derive(Debug, Clone)]
+pub struct RpcClientIdService<S> {
+    layer: RpcClientIdLayer,
+    inner: S,
+}
+
+impl<S, B> Service<HttpRequest<B>> for RpcClientIdService<S>
+where
+    S: Service<HttpRequest<B>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: HttpRequest<B>) -> Self::Future {
+        if let Some(client_id) = self.layer.client_id(&req) {
+            req.extensions_mut().insert(client_id);
+        }
+        self.inner.call(req)
+    }
+}
+
+/// Returns the last IP address in the given header.
+///
+/// Proxies append to the header, so the last entry is the one that can be trusted.
+fn forwarded_ip(headers: &HeaderMap, ip_header: &HeaderName) -> Option<IpAddr> {
+    let header = headers.get(ip_header)?.to_str().ok()?;
+    header.rsplit(',').next()?.trim().parse().ok()
+}
+
+/// Returns the `sub` claim of the bearer token in the given headers.
+///
+/// The signature and, if present, the `exp` claim of the token are checked.
+fn jwt_subject(secret: &JwtSecret, headers: &HeaderMap) -> Option<String> {
+    #[derive(Deserialize)]
+    struct SubjectClaim {
+        sub: Option<String>,
+    }
+
+    let token = headers.get(header::AUTHORIZATION)?.to_str().ok()?.strip_prefix("Bearer ")?;
+    let mut validation = Validation::new(Algorithm::HS256);
+    validation.set_required_spec_claims::<&str>(&[]);
+    jsonwebtoken::decode::<SubjectClaim>(
+        token,
+        &DecodingKey::from_secret(secret.as_bytes()),
+        &validation,
+    )
+    .ok()?
+    .claims
+    .sub
+}
+
+/// Metrics for a rate limit rule.
+#[derive(Metrics, Clone)]
+#[metrics(scope = "rpc_server.rate_limit")]
+struct RpcRateLimitMetrics {
+    /// The number of calls rejected because the client exceeded the rate limit
+    throttled_calls_total: Counter,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::Ipv4Addr;
+
+    fn limiter(rules: &[&str]) -> RpcClientRateLimiter {
+        RpcClientRateLimiter::new(
+            RpcRateLimitConfig::default()
+                .with_rules(rules.iter().map(|rule| rule.parse().unwrap()))
+                .with_client_ip_header(Some("x-forwarded-for".to_string())),
+        )
+    }
+
+    #[test]
+    fn token_bucket_refills() {
+        let limiter = limiter(&["eth_call=2/3"]);
+        let now = Instant::now();
+
+        for _ in 0..3 {
+            assert!(limiter.try_acquire("eth_call", None, now).is_ok());
+        }
+        let retry_after = limiter.try_acquire("eth_call", None, now).unwrap_err();
+        assert_eq!(retry_after, Duration::from_millis(500));
+
+        // unrelated methods are not limited
+        assert!(limiter.try_acquire("eth_blockNumber", None, now).is_ok());
+
+        let later = now + Duration::from_millis(500);
+        assert!(limiter.try_acquire("eth_call", None, later).is_ok());
+        assert!(limiter.try_acquire("eth_call", None, later).is_err());
+
+        // the bucket never exceeds the burst size
+        let much_later = now + Duration::from_secs(60);
+        for _ in 0..3 {
+            assert!(limiter.try_acquire("eth_call", None, much_later).is_ok());
+        }
+        assert!(limiter.try_acquire("eth_call", None, much_later).is_err());
+    }
+
+    #[test]
+    fn buckets_per_client_and_rule() {
+        let limiter = limiter(&["eth=1", "eth_call=1"]);
+        let now = Instant::now();
+        let alice = RpcClientId::Subject("alice".to_string());
+        let bob = RpcClientId::Ip(Ipv4Addr::LOCALHOST.into());
+
+        assert!(limiter.try_acquire("eth_call", Some(&alice), now).is_ok());
+        assert!(limiter.try_acquire("eth_call", Some(&alice), now).is_err());
+        assert!(limiter.try_acquire("eth_call", Some(&bob), now).is_ok());
+        assert!(limiter.try_acquire("eth_call", None, now).is_ok());
+        assert!(limiter.try_acquire("eth_getBalance", Some(&alice), now).is_ok());
+        assert!(limiter.try_acquire("eth_getCode", Some(&alice), now).is_err());
+    }
+
+    #[test]
+    fn client_id_from_request() {
+        let secret = JwtSecret::random();
+        let layer = limiter(&[]).client_id_layer(Some(secret));
+
+        let mut req = HttpRequest::new(());
+        assert_eq!(layer.client_id(&req), None);
+
+        req.extensions_mut().insert(SocketAddr::from(([10, 0, 0, 2], 30303)));
+        assert_eq!(layer.client_id(&req), Some(RpcClientId::Ip([10, 0, 0, 2].into())));
+
+        // the configured header takes precedence over the connection
+        req.headers_mut().insert("x-forwarded-for", "10.0.0.1, 192.168.1.7".parse().unwrap());
+        assert_eq!(layer.client_id(&req), Some(RpcClientId::Ip([192, 168, 1, 7].into())));
+
+        #[derive(serde::Serialize)]
+        struct Claims {
+            iat: u64,
+            exp: u64,
+            sub: String,
+        }
+        let token = |exp| {
+            jsonwebtoken::encode(
+                &jsonwebtoken::Header::new(Algorithm::HS256),
+                &Claims { iat: 0, exp, sub: "alice".to_string() },
+                &jsonwebtoken::EncodingKey::from_secret(secret.as_bytes()),
+            )
+            .unwrap()
+        };
+        req.headers_mut()
+            .insert(header::AUTHORIZATION, format!("Bearer {}", token(u64::MAX)).parse().unwrap());
+        assert_eq!(layer.client_id(&req), Some(RpcClientId::Subject("alice".to_string())));
+
+        // tokens signed with another secret are ignored
+        let other = limiter(&[]).client_id_layer(Some(JwtSecret::random()));
+        assert_eq!(other.client_id(&req), Some(RpcClientId::Ip([192, 168, 1, 7].into())));
+
+        // expired tokens are ignored
+        req.headers_mut()
+            .insert(header::AUTHORIZATION, format!("Bearer {}", token(1)).parse().unwrap());
+        assert_eq!(layer.client_id(&req), Some(RpcClientId::Ip([192, 168, 1, 7].into())));
+    }
+}
@@ -0,0 +1,122 @@
+//! HTTP and WS server that can expose the remote address of a connection to the HTTP middleware.
+
+use jsonrpsee::server::{
+    serve_with_graceful_shutdown, stop_channel, HttpBody, HttpRequest, HttpResponse, Methods,
+    Server, ServerBuilder, ServerHandle, TowerService, TowerServiceBuilder,
+};
+use std::{io, net::SocketAddr};
+use tokio::net::{TcpListener, ToSocketAddrs};
+use tower::{BoxError, Service, ServiceBuilder};
+use tracing::debug;
+
+/// A jsonrpsee HTTP and WS server.
+///
+/// The remote address of a connection is only needed to rate limit clients by their IP, so the
+/// regular [`Server`] is used unless it is requested.
+#[derive(Debug)]
+pub(crate) enum RpcHttpServer<HttpMiddleware, RpcMiddleware> {
+    /// The regular jsonrpsee server.
+    Jsonrpsee(Server<HttpMiddleware, RpcMiddleware>),
+    /// A server that exposes the remote address of a connection.
+    RemoteAddr(RemoteAddrServer<HttpMiddleware, RpcMiddleware>),
+}
+
+impl<HttpMiddleware, RpcMiddleware> RpcHttpServer<HttpMiddleware, RpcMiddleware> {
+    /// Binds the server configured by the given builder to the given address.
+    ///
+    /// If `remote_addr` is set, the [`SocketAddr`] of the remote end of a connection is inserted
+    /// into the extensions of every request received on it, see [`RemoteAddrServer`].
+    pub(crate) async fn bind(
+        builder: ServerBuilder<HttpMiddleware, RpcMiddleware>,
+        addr: impl ToSocketAddrs,
+        remote_addr: bool,
+    ) -> io::Result<Self> {
+        if remote_addr {
+            RemoteAddrServer::bind(builder, addr).await.map(Self::RemoteAddr)
+        } else {
+            builder.build(addr).await.map(Self::Jsonrpsee)
+        }
+    }
+
+    /// Returns the local address the server is listening on.
+    pub(crate) fn local_addr(&self) -> io::Result<SocketAddr> {
+        match self {
+            Self::Jsonrpsee(server) => server.local_addr(),
+            Self::RemoteAddr(server) => server.local_addr(),
+        }
+    }
+}
+
+/// A jsonrpsee HTTP and WS server that inserts the [`SocketAddr`] of the remote end of a
+/// connection into the extensions of every request received on it.
+///
+/// Connections are served by the [`TowerService`] of the server's [`TowerServiceBuilder`], so it
+/// otherwise behaves like [`Server`].
+#[derive(Debug)]
+pub(crate) struct RemoteAddrServer<HttpMiddleware, RpcMiddleware> {
+    listener: TcpListener,
+    service_builder: TowerServiceBuilder<RpcMiddleware, HttpMiddleware>,
+}
+
+impl<HttpMiddleware, RpcMiddleware> RemoteAddrServer<HttpMiddleware, RpcMiddleware> {
+    /// Binds the server configured by the given builder to the given address.
+    async fn bind(
+        builder: ServerBuilder<HttpMiddleware, RpcMiddleware>,
+        addr: impl ToSocketAddrs,
+    ) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr).await?;
+        Ok(Self { listener, service_builder: builder.to_service_builder() })
+    }
+
+    /// Returns the local address the server is listening on.
+    fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+}
+
+impl<HttpMiddleware, RpcMiddleware, Body> RemoteAddrServer<HttpMiddleware, RpcMiddleware>
+where
+    HttpMiddleware: Clone + Send + 'static,
+    RpcMiddleware: Clone + Send + 'static,
+    TowerService<RpcMiddleware, HttpMiddleware>:
+        Service<HttpRequest, Response = HttpResponse<Body>, Error = BoxError, Future: Send> + Send,
+    Body: http_body::Body<Data = hyper::body::Bytes> + Send + 'static,
+    <Body as http_body::Body>::Error: Into<BoxError>,
+{
+    /// Starts serving the given methods until the returned handle is stopped or dropped.
+    pub(crate) fn start(self, methods: impl Into<Methods>) -> ServerHandle {
+        let Self { listener, service_builder } = self;
+        let methods = methods.into();
+        let (stop_handle, server_handle) = stop_channel();
+
+        tokio::spawn(async move {
+            loop {
+                let (socket, remote_addr) = tokio::select! {
+                    res = listener.accept() => match res {
+                        Ok(conn) => conn,
+                        Err(err) => {
+                            debug!(target: "rpc", %err, "Failed to accept connection");
+                            continue
+                        }
+                    },
+                    _ = stop_handle.clone().shutdown() => break,
+                };
+
+                let service = ServiceBuilder::new()
+                    .map_request(move |req: HttpRequest<hyper::body::Incoming>| {
+                        let mut req = req.map(HttpBody::new);
+                        req.extensions_mut().insert(remote_addr);
+                        req
+                    })
+                    .service(service_builder.clone().build(methods.clone(), stop_handle.clone()));
+                tokio::spawn(serve_with_graceful_shutdown(
+                    socket,
+                    service,
+                    stop_handle.clone().shutdown(),
+                ));
+            }
+        });
+
+        server_handle
+    }
+}
@@ -0,0 +1,162 @@
+//! [`jsonrpsee`] helper layers for tracing RPC calls.
+//!
+//! - [`RpcTraceContextLayer`] extracts the W3C trace context of incoming HTTP requests.
+//! - [`RpcRequestTracing`] records a span for every RPC call, continuing the trace of the caller if
+//!   known.
+
+use http::{HeaderMap, Request as HttpRequest};
+use jsonrpsee::{
+    core::middleware::{Batch, Notification},
+    server::middleware::rpc::RpcServiceT,
+    types::Request,
+};
+use std::{
+    future::Future,
+    task::{Context, Poll},
+};
+use tower::{Layer, Service};
+use tracing::{debug_span, Instrument};
+
+/// The W3C trace context of the caller of an RPC call.
+///
+/// This is inserted into the request extensions by the [`RpcTraceContextLayer`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceContext {
+    /// The value of the `traceparent` header.
+    pub traceparent: String,
+    /// The value of the `tracestate` header, if any.
+    pub tracestate: Option<String>,
+}
+
+impl TraceContext {
+    /// Returns the trace context carried by the given headers, if any.
+    pub fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let traceparent = headers.get("traceparent")?.to_str().ok()?.to_string();
+        let tracestate =
+            headers.get("tracestate").and_then(|value| value.to_str().ok()).map(String::from);
+        Some(Self { traceparent, tracestate })
+    }
+
+    /// Sets the remote span of this trace context as parent of the given span.
+    fn set_parent_of(&self, span: &tracing::Span) {
+        reth_tracing::set_remote_parent(span, &self.traceparent, self.tracestate.as_deref());
+    }
+}
+
+/// HTTP layer that inserts the [`TraceContext`] of a request into its extensions.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RpcTraceContextLayer;
+
+impl<S> Layer<S> for RpcTraceContextLayer {
+    type Service = RpcTraceContextService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RpcTraceContextService { inner }
+    }
+}
+
+/// HTTP service that inserts the [`TraceContext`] of a request into its extensions.
+#[derive(Debug, Clone)]
+pub struct RpcTraceContextService<S> {
+    inner: S,
+}
+
+impl<S, B> Service<HttpRequest<B>> for RpcTraceContextService<S>
+where
+    S: Service<HttpRequest<B>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: HttpRequest<B>) -> Self::Future {
+        if let Some(trace_context) = TraceContext::from_headers(req.headers()) {
+            req.extensions_mut().insert(trace_context);
+        }
+        self.inner.call(req)
+    }
+}
+
+/// A [`RpcServiceT`] middleware layer that records a span for every RPC call.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RpcRequestTracing;
+
+impl<S> Layer<S> for RpcRequestTracing {
+    type Service = RpcRequestTracingService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RpcRequestTracingService { inner }
+    }
+}
+
+/// A [`RpcServiceT`] middleware that records a span for every RPC call.
+///
+/// The span is a child of the caller's span if the request carried a [`TraceContext`].
+#[derive(Debug, Clone)]
+pub struct RpcRequestTracingService<S> {
+    /// The inner service being wrapped
+    inner: S,
+}
+
+impl<S> RpcServiceT for RpcRequestTracingService<S>
+where
+    S: RpcServiceT + Send + Sync + Clone + 'static,
+{
+    type MethodResponse = S::MethodResponse;
+    type NotificationResponse = S::NotificationResponse;
+    type BatchResponse = S::BatchResponse;
+
+    fn call<'a>(&self, req: Request<'a>) -> impl Future<Output = Self::MethodResponse> + Send + 'a {
+        let span = debug_span!(target: "rpc::server", "rpc_call", method = %req.method_name());
+        if let Some(trace_context) = req.extensions().get::<TraceContext>() {
+            trace_context.set_parent_of(&span);
+        }
+        self.inner.call(req).instrument(span)
+    }
+
+    fn batch<'a>(
+        &self,
+        mut req: Batch<'a>,
+    ) -> impl Future<Output = Self::BatchResponse> + Send + 'a {
+        let span = debug_span!(target: "rpc::server", "rpc_batch", len = req.len());
+        if let Some(trace_context) = req.extensions().get::<TraceContext>() {
+            trace_context.set_parent_of(&span);
+        }
+        self.inner.batch(req).instrument(span)
+    }
+
+    fn notification<'a>(
+        &self,
+        n: Notification<'a>,
+    ) -> impl Future<Output = Self::NotificationResponse> + Send + 'a {
+        self.inner.notification(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trace_context_from_headers() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(TraceContext::from_headers(&headers), None);
+
+        headers.insert("tracestate", "vendor=value".parse().unwrap());
+        assert_eq!(TraceContext::from_headers(&headers), None);
+
+        let traceparent = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+        headers.insert("traceparent", traceparent.parse().unwrap());
+        assert_eq!(
+            TraceContext::from_headers(&headers),
+            Some(TraceContext {
+                traceparent: traceparent.to_string(),
+                tracestate: Some("vendor=value".to_string()),
+            })
+        );
+    }
+}
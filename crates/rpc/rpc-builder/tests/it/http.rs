@@ -22,8 +22,8 @@ use reth_ethereum_primitives::Receipt;
 use reth_network_peers::NodeRecord;
 use reth_rpc_api::{
     clients::{AdminApiClient, EthApiClient},
-    DebugApiClient, EthCallBundleApiClient, EthFilterApiClient, NetApiClient, OtterscanClient,
-    TraceApiClient, Web3ApiClient,
+    DebugApiClient, EthCallBundleApiClient, EthFilterApiClient, LowestAvailableBlocks,
+    NetApiClient, OtterscanClient, PruneStatus, RethApiClient, TraceApiClient, Web3ApiClient,
 };
 use reth_rpc_server_types::RethRpcModule;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
@@ -410,11 +410,70 @@ where
 {
     let block_id = BlockId::Number(BlockNumberOrTag::default());
 
-    DebugApiClient::<TransactionRequest>::raw_header(client, block_id).await.unwrap();
-    DebugApiClient::<TransactionRequest>::raw_block(client, block_id).await.unwrap_err();
-    DebugApiClient::<TransactionRequest>::raw_transaction(client, B256::default()).await.unwrap();
-    DebugApiClient::<TransactionRequest>::raw_receipts(client, block_id).await.unwrap();
-    DebugApiClient::<TransactionRequest>::bad_blocks(client).await.unwrap();
+    DebugApiClient::<TransactionRequest, Block>::raw_header(client, block_id).await.unwrap();
+    DebugApiClient::<TransactionRequest, Block>::raw_block(client, block_id).await.unwrap_err();
+    DebugApiClient::<TransactionRequest, Block>::raw_transaction(client, B256::default())
+        .await
+        .unwrap();
+    DebugApiClient::<TransactionRequest, Block>::raw_receipts(client, block_id).await.unwrap();
+    DebugApiClient::<TransactionRequest, Block>::bad_blocks(client).await.unwrap();
+    DebugApiClient::<TransactionRequest, Block>::debug_verbosity(client, 6).await.unwrap_err();
+    DebugApiClient::<TransactionRequest, Block>::debug_trace_bad_block(
+        client,
+        B256::default(),
+        None,
+    )
+    .await
+    .unwrap_err();
+    DebugApiClient::<TransactionRequest, Block>::debug_intermediate_roots(
+        client,
+        B256::default(),
+        None,
+    )
+    .await
+    .unwrap_err();
+    DebugApiClient::<TransactionRequest, Block>::debug_standard_trace_block_to_file(
+        client,
+        B256::default(),
+        None,
+    )
+    .await
+    .unwrap_err();
+    DebugApiClient::<TransactionRequest, Block>::debug_standard_trace_bad_block_to_file(
+        client,
+        B256::default(),
+        None,
+    )
+    .await
+    .unwrap_err();
+    // an empty range can't be traced
+    DebugApiClient::<TransactionRequest, Block>::debug_trace_chain(
+        client,
+        BlockNumberOrTag::Earliest,
+        BlockNumberOrTag::Earliest,
+        None,
+    )
+    .await
+    .unwrap_err();
+    // the genesis block has no parent to compare against
+    DebugApiClient::<TransactionRequest, Block>::debug_get_modified_accounts_by_number(
+        client, 0, None,
+    )
+    .await
+    .unwrap_err();
+    // ranges longer than the limit are rejected
+    let err = DebugApiClient::<TransactionRequest, Block>::debug_get_modified_accounts_by_number(
+        client,
+        0,
+        Some(u64::MAX),
+    )
+    .await
+    .unwrap_err();
+    assert!(matches!(
+        err,
+        jsonrpsee::core::client::Error::Call(error_obj)
+            if error_obj.code() == ErrorCode::InvalidParams.code()
+    ));
 }
 
 async fn test_basic_net_calls<C>(client: &C)
@@ -484,6 +543,23 @@ where
     Web3ApiClient::sha3(client, Bytes::default()).await.unwrap();
 }
 
+async fn test_basic_reth_calls<C>(client: &C)
+where
+    C: ClientT + SubscriptionClientT + Sync,
+{
+    assert!(RethApiClient::reth_get_prune_checkpoints(client).await.unwrap().is_empty());
+    assert!(RethApiClient::reth_get_stage_checkpoints(client).await.unwrap().is_empty());
+    assert!(RethApiClient::reth_get_static_file_ranges(client).await.unwrap().is_empty());
+    assert_eq!(RethApiClient::reth_get_prune_status(client).await.unwrap(), PruneStatus::default());
+    assert_eq!(
+        RethApiClient::reth_get_lowest_available_blocks(client).await.unwrap(),
+        LowestAvailableBlocks::default()
+    );
+    RethApiClient::reth_set_log_filter(client, "debug".to_string(), Some("stderr".to_string()))
+        .await
+        .unwrap_err();
+}
+
 async fn test_basic_otterscan_calls<C>(client: &C)
 where
     C: ClientT + SubscriptionClientT + Sync,
@@ -541,27 +617,36 @@ where
     .err()
     .unwrap();
 
-    assert!(is_unimplemented(
-        OtterscanClient::<Transaction, Header>::search_transactions_before(
-            client,
-            address,
-            LenientBlockNumberOrTag::new(BlockNumberOrTag::Number(block_number)),
-            page_size,
-        )
-        .await
-        .err()
-        .unwrap()
-    ));
-    assert!(is_unimplemented(
-        OtterscanClient::<Transaction, Header>::search_transactions_after(
-            client,
-            address,
-            LenientBlockNumberOrTag::new(BlockNumberOrTag::Number(block_number)),
-            page_size,
-        )
-        .await
-        .err()
-        .unwrap()
+    let before = OtterscanClient::<Transaction, Header>::search_transactions_before(
+        client,
+        address,
+        LenientBlockNumberOrTag::new(BlockNumberOrTag::Number(block_number)),
+        page_size,
+    )
+    .await
+    .unwrap();
+    assert!(before.txs.is_empty() && before.last_page);
+    let after = OtterscanClient::<Transaction, Header>::search_transactions_after(
+        client,
+        address,
+        LenientBlockNumberOrTag::new(BlockNumberOrTag::Number(block_number)),
+        page_size,
+    )
+    .await
+    .unwrap();
+    assert!(after.txs.is_empty() && after.first_page);
+    let err = OtterscanClient::<Transaction, Header>::search_transactions_before(
+        client,
+        address,
+        LenientBlockNumberOrTag::new(BlockNumberOrTag::Safe),
+        page_size,
+    )
+    .await
+    .unwrap_err();
+    assert!(matches!(
+        err,
+        jsonrpsee::core::client::Error::Call(error_obj)
+            if error_obj.code() == ErrorCode::InvalidParams.code()
     ));
     assert!(OtterscanClient::<Transaction, Header>::get_transaction_by_sender_and_nonce(
         client, sender, nonce
@@ -746,6 +831,24 @@ async fn test_call_web3_functions_http_and_ws() {
     test_basic_web3_calls(&client).await;
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_call_reth_functions_http() {
+    reth_tracing::init_test_tracing();
+
+    let handle = launch_http(vec![RethRpcModule::Reth]).await;
+    let client = handle.http_client().unwrap();
+    test_basic_reth_calls(&client).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_call_reth_functions_ws() {
+    reth_tracing::init_test_tracing();
+
+    let handle = launch_ws(vec![RethRpcModule::Reth]).await;
+    let client = handle.ws_client().await.unwrap();
+    test_basic_reth_calls(&client).await;
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_call_otterscan_functions_http() {
     reth_tracing::init_test_tracing();
@@ -1,11 +1,19 @@
 use crate::utils::{test_address, test_rpc_builder};
 use alloy_rpc_types_eth::{Block, Header, Receipt, Transaction, TransactionRequest};
 use jsonrpsee::{
-    core::middleware::{Batch, Notification},
+    core::{
+        client::{ClientT, Error},
+        middleware::{Batch, Notification},
+        params::BatchRequestBuilder,
+    },
+    rpc_params,
     server::middleware::rpc::RpcServiceT,
     types::Request,
 };
-use reth_rpc_builder::{RpcServerConfig, TransportRpcModuleConfig};
+use reth_rpc_builder::{
+    rate_limiter::RATE_LIMITED_ERROR_CODE, RpcRateLimitConfig, RpcServerConfig,
+    TransportRpcModuleConfig,
+};
 use reth_rpc_eth_api::EthApiClient;
 use reth_rpc_server_types::RpcModuleSelection;
 use std::{
@@ -93,3 +101,35 @@ async fn test_rpc_middleware() {
     let count = mylayer.count.load(Ordering::Relaxed);
     assert_eq!(count, 1);
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_rpc_rate_limit() {
+    let builder = test_rpc_builder();
+    let eth_api = builder.bootstrap_eth_api();
+    let modules =
+        builder.build(TransportRpcModuleConfig::set_http(RpcModuleSelection::All), eth_api);
+
+    let handle = RpcServerConfig::http(Default::default())
+        .with_http_address(test_address())
+        .with_rate_limit(RpcRateLimitConfig::default().with_rules(["web3=1/2".parse().unwrap()]))
+        .start(&modules)
+        .await
+        .unwrap();
+    let client = handle.http_client().unwrap();
+
+    for _ in 0..2 {
+        client.request::<String, _>("web3_clientVersion", rpc_params![]).await.unwrap();
+    }
+    let err = client.request::<String, _>("web3_clientVersion", rpc_params![]).await.unwrap_err();
+    let Error::Call(err) = err else { panic!("expected call error, got {err:?}") };
+    assert_eq!(err.code(), RATE_LIMITED_ERROR_CODE);
+    assert!(err.data().unwrap().get().contains("retryAfterMs"));
+
+    // methods without a rule are not limited, throttled batch entries are rejected individually
+    let mut batch = BatchRequestBuilder::new();
+    batch.insert("web3_clientVersion", rpc_params![]).unwrap();
+    batch.insert("net_version", rpc_params![]).unwrap();
+    let res = client.batch_request::<String>(batch).await.unwrap();
+    assert_eq!(res.num_failed_calls(), 1);
+    assert_eq!(res.num_successful_calls(), 1);
+}
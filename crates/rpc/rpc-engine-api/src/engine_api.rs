@@ -32,7 +32,7 @@ use reth_tasks::TaskSpawner;
 use reth_transaction_pool::TransactionPool;
 use std::{sync::Arc, time::Instant};
 use tokio::sync::oneshot;
-use tracing::{debug, trace, warn};
+use tracing::{debug, instrument, trace, warn};
 
 /// The Engine API response sender.
 pub type EngineApiSender<Ok> = oneshot::Sender<EngineApiResult<Ok>>;
@@ -134,6 +134,13 @@ where
 
     /// See also <https://github.com/ethereum/execution-apis/blob/3d627c95a4d3510a8187dd02e0250ecb4331d27e/src/engine/paris.md#engine_newpayloadv1>
     /// Caution: This should not accept the `withdrawals` field
+    #[instrument(
+        level = "debug",
+        target = "rpc::engine",
+        name = "engine_newPayloadV1",
+        skip_all,
+        fields(block_hash = %payload.block_hash(), block_number = payload.block_number())
+    )]
     pub async fn new_payload_v1(
         &self,
         payload: PayloadT::ExecutionData,
@@ -172,6 +179,13 @@ where
     }
 
     /// See also <https://github.com/ethereum/execution-apis/blob/584905270d8ad665718058060267061ecfd79ca5/src/engine/shanghai.md#engine_newpayloadv2>
+    #[instrument(
+        level = "debug",
+        target = "rpc::engine",
+        name = "engine_newPayloadV2",
+        skip_all,
+        fields(block_hash = %payload.block_hash(), block_number = payload.block_number())
+    )]
     pub async fn new_payload_v2(
         &self,
         payload: PayloadT::ExecutionData,
@@ -208,6 +222,13 @@ where
     }
 
     /// See also <https://github.com/ethereum/execution-apis/blob/fe8e13c288c592ec154ce25c534e26cb7ce0530d/src/engine/cancun.md#engine_newpayloadv3>
+    #[instrument(
+        level = "debug",
+        target = "rpc::engine",
+        name = "engine_newPayloadV3",
+        skip_all,
+        fields(block_hash = %payload.block_hash(), block_number = payload.block_number())
+    )]
     pub async fn new_payload_v3(
         &self,
         payload: PayloadT::ExecutionData,
@@ -245,6 +266,13 @@ where
     }
 
     /// See also <https://github.com/ethereum/execution-apis/blob/7907424db935b93c2fe6a3c0faab943adebe8557/src/engine/prague.md#engine_newpayloadv4>
+    #[instrument(
+        level = "debug",
+        target = "rpc::engine",
+        name = "engine_newPayloadV4",
+        skip_all,
+        fields(block_hash = %payload.block_hash(), block_number = payload.block_number())
+    )]
     pub async fn new_payload_v4(
         &self,
         payload: PayloadT::ExecutionData,
@@ -710,6 +738,19 @@ where
     ///
     /// * If the version above [`EngineApiMessageVersion::V3`], then the payload attributes will be
     ///   validated according to the Cancun rules.
+    #[instrument(
+        level = "debug",
+        target = "rpc::engine",
+        name = "engine_forkchoiceUpdated",
+        skip_all,
+        fields(
+            ?version,
+            head = %state.head_block_hash,
+            safe = %state.safe_block_hash,
+            finalized = %state.finalized_block_hash,
+            has_attributes = payload_attrs.is_some(),
+        )
+    )]
     async fn validate_and_execute_forkchoice(
         &self,
         version: EngineApiMessageVersion,
@@ -8,7 +8,8 @@ use reth_node_api::{FullNodeComponents, NodePrimitives, PrimitivesTy};
 use reth_primitives_traits::{BlockTy, HeaderTy, ReceiptTy, TxTy};
 use reth_rpc_eth_types::EthStateCache;
 use reth_storage_api::{
-    BlockReader, BlockReaderIdExt, StageCheckpointReader, StateProviderFactory,
+    AccountHistoryReader, BadBlockReader, BlockReader, BlockReaderIdExt, HashedStateRangeReader,
+    StageCheckpointReader, StateProviderFactory,
 };
 use reth_transaction_pool::{PoolTransaction, TransactionPool};
 
@@ -35,6 +36,9 @@ pub trait RpcNodeCore: Clone + Send + Sync + Unpin + 'static {
         > + StateProviderFactory
         + CanonStateSubscriptions<Primitives = Self::Primitives>
         + StageCheckpointReader
+        + AccountHistoryReader
+        + HashedStateRangeReader
+        + BadBlockReader
         + Send
         + Sync
         + Clone
@@ -126,6 +130,9 @@ where
         > + StateProviderFactory
         + CanonStateSubscriptions<Primitives = Evm::Primitives>
         + StageCheckpointReader
+        + AccountHistoryReader
+        + HashedStateRangeReader
+        + BadBlockReader
         + Send
         + Sync
         + Unpin
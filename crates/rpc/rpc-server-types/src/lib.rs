@@ -10,9 +10,11 @@
 
 /// Common RPC constants.
 pub mod constants;
+pub mod rate_limit;
 pub mod result;
 
 mod module;
 pub use module::{RethRpcModule, RpcModuleSelection};
+pub use rate_limit::{RpcRateLimitConfig, RpcRateLimitRule};
 
 pub use result::ToRpcResult;
@@ -0,0 +1,220 @@
+//! Configuration for token-bucket rate limiting of RPC calls.
+
+use serde::{Deserialize, Serialize};
+use std::{fmt, num::NonZeroU32, str::FromStr};
+
+/// Wildcard method group that matches every RPC method.
+pub const RATE_LIMIT_ALL_METHODS: &str = "*";
+
+/// Rate limiting configuration for the HTTP and WS RPC servers.
+///
+/// Every rule describes a token bucket that is kept per client, a client is identified by the
+/// `sub` claim of its JWT or by its IP address. The IP address is taken from
+/// [`RpcRateLimitConfig::client_ip_header`] if configured and present, otherwise from the
+/// connection. Clients that can't be identified share a single bucket per rule.
+///
+/// Only the most specific rule is applied to a call: an exact method name takes precedence over a
+/// namespace, which takes precedence over the [`RATE_LIMIT_ALL_METHODS`] wildcard.
+///
+/// # Example
+///
+/// ```toml
+/// client_ip_header = "x-forwarded-for"
+///
+/// [[rules]]
+/// methods = "eth_call"
+/// requests_per_second = 10
+/// burst = 20
+///
+/// [[rules]]
+/// methods = "*"
+/// requests_per_second = 100
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RpcRateLimitConfig {
+    /// The configured rate limit rules.
+    pub rules: Vec<RpcRateLimitRule>,
+    /// HTTP header that carries the client IP address, e.g. `x-forwarded-for` when running behind
+    /// a reverse proxy.
+    ///
+    /// If the header contains a list of addresses, the last one is used because that is the one
+    /// appended by the proxy in front of the node.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_ip_header: Option<String>,
+}
+
+impl RpcRateLimitConfig {
+    /// Returns true if no rules are configured.
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Adds the given rules.
+    pub fn with_rules(mut self, rules: impl IntoIterator<Item = RpcRateLimitRule>) -> Self {
+        self.rules.extend(rules);
+        self
+    }
+
+    /// Sets the header that carries the client IP address.
+    pub fn with_client_ip_header(mut self, header: Option<String>) -> Self {
+        self.client_ip_header = header;
+        self
+    }
+
+    /// Returns the index of the most specific rule that applies to the given method, if any.
+    ///
+    /// If multiple rules are configured for the same method group, the last one wins.
+    pub fn rule_index(&self, method: &str) -> Option<usize> {
+        self.rules
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, rule)| {
+                rule.specificity(method).map(|specificity| (specificity, idx))
+            })
+            .max()
+            .map(|(_, idx)| idx)
+    }
+}
+
+/// A single rate limit rule, see [`RpcRateLimitConfig`].
+///
+/// Can be parsed from a `<methods>=<requests_per_second>[/<burst>]` string, e.g. `eth_call=10/20`,
+/// `debug=1` or `*=100`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RpcRateLimitRule {
+    /// The methods this rule applies to: an exact method name (`eth_call`), a namespace (`eth`)
+    /// or [`RATE_LIMIT_ALL_METHODS`].
+    pub methods: String,
+    /// The number of calls a client can make per second.
+    pub requests_per_second: NonZeroU32,
+    /// The number of calls a client can make at once, defaults to `requests_per_second`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub burst: Option<NonZeroU32>,
+}
+
+impl RpcRateLimitRule {
+    /// Creates a new rule for the given method group.
+    pub fn new(methods: impl Into<String>, requests_per_second: NonZeroU32) -> Self {
+        Self { methods: methods.into(), requests_per_second, burst: None }
+    }
+
+    /// Sets the burst size of the rule.
+    pub const fn with_burst(mut self, burst: NonZeroU32) -> Self {
+        self.burst = Some(burst);
+        self
+    }
+
+    /// Returns the number of calls a client can make at once.
+    pub fn burst(&self) -> NonZeroU32 {
+        self.burst.unwrap_or(self.requests_per_second)
+    }
+
+    /// Returns how specific the rule is for the given method, or `None` if it doesn't apply.
+    ///
+    /// Method names always contain a `_`, so a rule without one names a namespace.
+    fn specificity(&self, method: &str) -> Option<u8> {
+        if self.methods == RATE_LIMIT_ALL_METHODS {
+            Some(0)
+        } else if self.methods == method {
+            Some(2)
+        } else if !self.methods.contains('_') &&
+            method.strip_prefix(self.methods.as_str()).is_some_and(|rest| rest.starts_with('_'))
+        {
+            Some(1)
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Display for RpcRateLimitRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}={}", self.methods, self.requests_per_second)?;
+        if let Some(burst) = self.burst {
+            write!(f, "/{burst}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for RpcRateLimitRule {
+    type Err = RpcRateLimitRuleParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (methods, limit) =
+            s.split_once('=').ok_or_else(|| RpcRateLimitRuleParseError(s.to_string()))?;
+        let methods = methods.trim();
+        if methods.is_empty() {
+            return Err(RpcRateLimitRuleParseError(s.to_string()))
+        }
+        let (rate, burst) = match limit.split_once('/') {
+            Some((rate, burst)) => (rate, Some(burst)),
+            None => (limit, None),
+        };
+        let parse = |val: &str| {
+            val.trim().parse::<NonZeroU32>().map_err(|_| RpcRateLimitRuleParseError(s.to_string()))
+        };
+
+        Ok(Self {
+            methods: methods.to_string(),
+            requests_per_second: parse(rate)?,
+            burst: burst.map(parse).transpose()?,
+        })
+    }
+}
+
+/// Error returned when a [`RpcRateLimitRule`] can't be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RpcRateLimitRuleParseError(String);
+
+impl fmt::Display for RpcRateLimitRuleParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid rate limit rule '{}', expected <methods>=<requests_per_second>[/<burst>]",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for RpcRateLimitRuleParseError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_rule() {
+        let rule: RpcRateLimitRule = "eth_call=10/20".parse().unwrap();
+        assert_eq!(
+            rule,
+            RpcRateLimitRule::new("eth_call", NonZeroU32::new(10).unwrap())
+                .with_burst(NonZeroU32::new(20).unwrap())
+        );
+        assert_eq!(rule.to_string(), "eth_call=10/20");
+
+        let rule: RpcRateLimitRule = "*=100".parse().unwrap();
+        assert_eq!(rule.burst().get(), 100);
+
+        assert!("eth_call".parse::<RpcRateLimitRule>().is_err());
+        assert!("=10".parse::<RpcRateLimitRule>().is_err());
+        assert!("eth=0".parse::<RpcRateLimitRule>().is_err());
+        assert!("eth=10/".parse::<RpcRateLimitRule>().is_err());
+    }
+
+    #[test]
+    fn most_specific_rule() {
+        let config = RpcRateLimitConfig::default().with_rules(
+            ["*=100", "eth=50", "eth_call=10", "debug=1"].map(|rule| rule.parse().unwrap()),
+        );
+        assert_eq!(config.rule_index("eth_call"), Some(2));
+        assert_eq!(config.rule_index("eth_getBalance"), Some(1));
+        assert_eq!(config.rule_index("debug_traceTransaction"), Some(3));
+        assert_eq!(config.rule_index("net_version"), Some(0));
+        assert_eq!(config.rule_index("ethereum_foo"), Some(0));
+
+        let config = RpcRateLimitConfig::default().with_rules(["eth=50".parse().unwrap()]);
+        assert_eq!(config.rule_index("net_version"), None);
+    }
+}
@@ -0,0 +1,399 @@
+use std::{sync::Arc, time::Duration};
+
+use alloy_consensus::BlockHeader;
+use alloy_primitives::{Address, Bytes, B256, U256};
+use alloy_rpc_types_anvil::{
+    Forking, Metadata, MineOptions, NodeEnvironment, NodeForkConfig, NodeInfo,
+};
+use async_trait::async_trait;
+use jsonrpsee::core::RpcResult;
+use jsonrpsee_types::ErrorObject;
+use reth_chainspec::{ChainSpecProvider, EthChainSpec, EthereumHardfork, EthereumHardforks};
+use reth_engine_local::{LocalMinerError, LocalMinerHandle, MiningMode};
+use reth_network_api::NetworkInfo;
+use reth_rpc_api::AnvilApiServer;
+use reth_rpc_eth_api::{
+    helpers::{EthBlocks, EthFees, FullEthApi},
+    RpcBlock,
+};
+use reth_rpc_eth_types::EthApiError;
+use reth_rpc_server_types::{
+    result::{internal_rpc_err, invalid_params_rpc_err},
+    ToRpcResult,
+};
+use reth_storage_api::BlockReaderIdExt;
+use reth_transaction_pool::TransactionPool;
+
+/// Maximum number of blocks that can be mined with a single `anvil_mine` or `anvil_mine_detailed`
+/// call.
+pub const MAX_ANVIL_MINE_BLOCKS: u64 = 1_000;
+
+/// `anvil` API implementation for dev nodes.
+///
+/// Mining and block timestamps are controlled through the [`LocalMinerHandle`] of the node's
+/// [`LocalMiner`](reth_engine_local::LocalMiner).
+///
+/// Dev blocks go through the same execution and validation as blocks of a live network, so state
+/// can only change through signed transactions in mined blocks. The methods that change state,
+/// chain configuration or node settings outside of mined blocks, e.g. impersonation, balance and
+/// code overrides, snapshots or state dumps, fail with an unsupported error.
+pub struct AnvilApi<Eth> {
+    inner: Arc<AnvilApiInner<Eth>>,
+}
+
+// === impl AnvilApi ===
+
+impl<Eth> AnvilApi<Eth> {
+    /// Creates a new instance of the [`AnvilApi`].
+    pub fn new(eth_api: Eth, miner: LocalMinerHandle) -> Self {
+        let inner = Arc::new(AnvilApiInner { eth_api, miner, instance_id: B256::random() });
+        Self { inner }
+    }
+
+    /// Access the underlying `Eth` API.
+    pub fn eth_api(&self) -> &Eth {
+        &self.inner.eth_api
+    }
+
+    /// Access the handle of the local miner.
+    pub fn miner(&self) -> &LocalMinerHandle {
+        &self.inner.miner
+    }
+}
+
+impl<Eth> AnvilApi<Eth>
+where
+    Eth: FullEthApi + 'static,
+{
+    /// Mines `blocks` blocks, `interval` seconds apart, and returns their hashes.
+    ///
+    /// Returns an error if more than [`MAX_ANVIL_MINE_BLOCKS`] blocks are requested.
+    async fn mine_blocks(&self, blocks: u64, interval: Option<u64>) -> RpcResult<Vec<B256>> {
+        if blocks > MAX_ANVIL_MINE_BLOCKS {
+            return Err(invalid_params_rpc_err(format!(
+                "can't mine more than {MAX_ANVIL_MINE_BLOCKS} blocks at once, got {blocks}"
+            )))
+        }
+        self.miner().mine(blocks, interval).await.map_err(miner_err)
+    }
+
+    /// Returns the currently active ethereum hardfork at the given block.
+    fn active_hardfork(&self, number: u64, timestamp: u64) -> Option<EthereumHardfork> {
+        let chain_spec = self.eth_api().provider().chain_spec();
+        EthereumHardfork::VARIANTS.iter().rev().copied().find(|fork| {
+            chain_spec
+                .ethereum_fork_activation(*fork)
+                .active_at_timestamp_or_number(timestamp, number)
+        })
+    }
+}
+
+#[async_trait]
+impl<Eth> AnvilApiServer<RpcBlock<Eth::NetworkTypes>> for AnvilApi<Eth>
+where
+    Eth: FullEthApi + 'static,
+{
+    /// Handler for `anvil_impersonateAccount`
+    async fn anvil_impersonate_account(&self, _address: Address) -> RpcResult<()> {
+        Err(unsupported())
+    }
+
+    /// Handler for `anvil_stopImpersonatingAccount`
+    async fn anvil_stop_impersonating_account(&self, _address: Address) -> RpcResult<()> {
+        Err(unsupported())
+    }
+
+    /// Handler for `anvil_autoImpersonateAccount`
+    async fn anvil_auto_impersonate_account(&self, _enabled: bool) -> RpcResult<()> {
+        Err(unsupported())
+    }
+
+    /// Handler for `anvil_getAutomine`
+    async fn anvil_get_automine(&self) -> RpcResult<bool> {
+        self.miner().is_automine().await.map_err(miner_err)
+    }
+
+    /// Handler for `anvil_mine`
+    async fn anvil_mine(&self, blocks: Option<U256>, interval: Option<U256>) -> RpcResult<()> {
+        let blocks = blocks.map(to_u64).transpose()?.unwrap_or(1);
+        let interval = interval.map(to_u64).transpose()?;
+        self.mine_blocks(blocks, interval).await?;
+        Ok(())
+    }
+
+    /// Handler for `anvil_setAutomine`
+    async fn anvil_set_automine(&self, enabled: bool) -> RpcResult<()> {
+        if enabled {
+            if self.miner().is_automine().await.map_err(miner_err)? {
+                return Ok(())
+            }
+            let pool = self.eth_api().pool();
+            self.miner().set_mining_mode(MiningMode::instant(pool.clone())).map_err(miner_err)?;
+            // the instant mode only reacts to new transactions, so the ones that are already
+            // pending are mined right away
+            if pool.pool_size().pending > 0 {
+                self.mine_blocks(1, None).await?;
+            }
+        } else if self.miner().is_automine().await.map_err(miner_err)? {
+            self.miner().set_mining_mode(MiningMode::Manual).map_err(miner_err)?;
+        }
+        Ok(())
+    }
+
+    /// Handler for `anvil_setIntervalMining`
+    async fn anvil_set_interval_mining(&self, interval: u64) -> RpcResult<()> {
+        let mode = if interval == 0 {
+            MiningMode::Manual
+        } else {
+            MiningMode::interval(Duration::from_secs(interval))
+        };
+        self.miner().set_mining_mode(mode).map_err(miner_err)
+    }
+
+    /// Handler for `anvil_dropTransaction`
+    async fn anvil_drop_transaction(&self, tx_hash: B256) -> RpcResult<Option<B256>> {
+        let removed = self.eth_api().pool().remove_transactions(vec![tx_hash]);
+        Ok((!removed.is_empty()).then_some(tx_hash))
+    }
+
+    /// Handler for `anvil_reset`
+    async fn anvil_reset(&self, _fork: Option<Forking>) -> RpcResult<()> {
+        Err(unsupported())
+    }
+
+    /// Handler for `anvil_setRpcUrl`
+    async fn anvil_set_rpc_url(&self, _url: String) -> RpcResult<()> {
+        Err(unsupported())
+    }
+
+    /// Handler for `anvil_setBalance`
+    async fn anvil_set_balance(&self, _address: Address, _balance: U256) -> RpcResult<()> {
+        Err(unsupported())
+    }
+
+    /// Handler for `anvil_setCode`
+    async fn anvil_set_code(&self, _address: Address, _code: Bytes) -> RpcResult<()> {
+        Err(unsupported())
+    }
+
+    /// Handler for `anvil_setNonce`
+    async fn anvil_set_nonce(&self, _address: Address, _nonce: U256) -> RpcResult<()> {
+        Err(unsupported())
+    }
+
+    /// Handler for `anvil_setStorageAt`
+    async fn anvil_set_storage_at(
+        &self,
+        _address: Address,
+        _slot: U256,
+        _value: B256,
+    ) -> RpcResult<bool> {
+        Err(unsupported())
+    }
+
+    /// Handler for `anvil_setCoinbase`
+    async fn anvil_set_coinbase(&self, _address: Address) -> RpcResult<()> {
+        Err(unsupported())
+    }
+
+    /// Handler for `anvil_setChainId`
+    async fn anvil_set_chain_id(&self, _chain_id: u64) -> RpcResult<()> {
+        Err(unsupported())
+    }
+
+    /// Handler for `anvil_setLoggingEnabled`
+    async fn anvil_set_logging_enabled(&self, _enabled: bool) -> RpcResult<()> {
+        Err(unsupported())
+    }
+
+    /// Handler for `anvil_setMinGasPrice`
+    async fn anvil_set_min_gas_price(&self, _gas_price: U256) -> RpcResult<()> {
+        Err(unsupported())
+    }
+
+    /// Handler for `anvil_setNextBlockBaseFeePerGas`
+    async fn anvil_set_next_block_base_fee_per_gas(&self, _base_fee: U256) -> RpcResult<()> {
+        Err(unsupported())
+    }
+
+    /// Handler for `anvil_setTime`
+    async fn anvil_set_time(&self, timestamp: u64) -> RpcResult<u64> {
+        self.miner().set_time(timestamp).await.map_err(miner_err)
+    }
+
+    /// Handler for `anvil_dumpState`
+    async fn anvil_dump_state(&self) -> RpcResult<Bytes> {
+        Err(unsupported())
+    }
+
+    /// Handler for `anvil_loadState`
+    async fn anvil_load_state(&self, _state: Bytes) -> RpcResult<bool> {
+        Err(unsupported())
+    }
+
+    /// Handler for `anvil_nodeInfo`
+    async fn anvil_node_info(&self) -> RpcResult<NodeInfo> {
+        let header = self
+            .eth_api()
+            .provider()
+            .latest_header()
+            .to_rpc_result()?
+            .ok_or_else(|| internal_rpc_err("latest header not found"))?;
+        let gas_price = EthFees::gas_price(self.eth_api()).await.map_err(Into::into)?;
+        let hard_fork = self
+            .active_hardfork(header.number(), header.timestamp())
+            .map(|fork| fork.name().to_lowercase())
+            .unwrap_or_default();
+
+        Ok(NodeInfo {
+            current_block_number: header.number(),
+            current_block_timestamp: header.timestamp(),
+            current_block_hash: header.hash(),
+            hard_fork,
+            transaction_order: "fees".to_string(),
+            environment: NodeEnvironment {
+                base_fee: header.base_fee_per_gas().unwrap_or_default() as u128,
+                chain_id: self.eth_api().provider().chain_spec().chain().id(),
+                gas_limit: header.gas_limit(),
+                gas_price: gas_price.saturating_to(),
+            },
+            fork_config: NodeForkConfig {
+                fork_url: None,
+                fork_block_number: None,
+                fork_retry_backoff: None,
+            },
+        })
+    }
+
+    /// Handler for `anvil_metadata`
+    async fn anvil_metadata(&self) -> RpcResult<Metadata> {
+        let header = self
+            .eth_api()
+            .provider()
+            .latest_header()
+            .to_rpc_result()?
+            .ok_or_else(|| internal_rpc_err("latest header not found"))?;
+        let status = self.eth_api().network().network_status().await.to_rpc_result()?;
+
+        Ok(Metadata {
+            client_version: status.client_version,
+            chain_id: self.eth_api().provider().chain_spec().chain().id(),
+            instance_id: self.inner.instance_id,
+            latest_block_number: header.number(),
+            latest_block_hash: header.hash(),
+            forked_network: None,
+            snapshots: Default::default(),
+        })
+    }
+
+    /// Handler for `anvil_snapshot`
+    async fn anvil_snapshot(&self) -> RpcResult<U256> {
+        Err(unsupported())
+    }
+
+    /// Handler for `anvil_revert`
+    async fn anvil_revert(&self, _id: U256) -> RpcResult<bool> {
+        Err(unsupported())
+    }
+
+    /// Handler for `anvil_increaseTime`
+    async fn anvil_increase_time(&self, seconds: U256) -> RpcResult<i64> {
+        self.miner().increase_time(to_u64(seconds)?).await.map_err(miner_err)
+    }
+
+    /// Handler for `anvil_setNextBlockTimestamp`
+    async fn anvil_set_next_block_timestamp(&self, seconds: u64) -> RpcResult<()> {
+        self.miner().set_next_block_timestamp(seconds).await.map_err(miner_err)
+    }
+
+    /// Handler for `anvil_setBlockGasLimit`
+    async fn anvil_set_block_gas_limit(&self, _gas_limit: U256) -> RpcResult<bool> {
+        Err(unsupported())
+    }
+
+    /// Handler for `anvil_setBlockTimestampInterval`
+    async fn anvil_set_block_timestamp_interval(&self, seconds: u64) -> RpcResult<()> {
+        self.miner().set_block_timestamp_interval(Some(seconds)).await.map_err(miner_err)?;
+        Ok(())
+    }
+
+    /// Handler for `anvil_removeBlockTimestampInterval`
+    async fn anvil_remove_block_timestamp_interval(&self) -> RpcResult<bool> {
+        self.miner().set_block_timestamp_interval(None).await.map_err(miner_err)
+    }
+
+    /// Handler for `anvil_mine_detailed`
+    async fn anvil_mine_detailed(
+        &self,
+        opts: Option<MineOptions>,
+    ) -> RpcResult<Vec<RpcBlock<Eth::NetworkTypes>>> {
+        let (timestamp, blocks) = match opts.unwrap_or_default() {
+            MineOptions::Options { timestamp, blocks } => (timestamp, blocks),
+            MineOptions::Timestamp(timestamp) => (timestamp, None),
+        };
+        if let Some(timestamp) = timestamp {
+            self.miner().set_next_block_timestamp(timestamp).await.map_err(miner_err)?;
+        }
+
+        let hashes = self.mine_blocks(blocks.unwrap_or(1), None).await?;
+        let mut mined = Vec::with_capacity(hashes.len());
+        for hash in hashes {
+            let block = EthBlocks::rpc_block(self.eth_api(), hash.into(), true)
+                .await
+                .map_err(Into::into)?
+                .ok_or_else(|| internal_rpc_err(format!("mined block {hash} not found")))?;
+            mined.push(block);
+        }
+        Ok(mined)
+    }
+
+    /// Handler for `anvil_enableTraces`
+    async fn anvil_enable_traces(&self) -> RpcResult<()> {
+        Err(unsupported())
+    }
+
+    /// Handler for `anvil_removePoolTransactions`
+    async fn anvil_remove_pool_transactions(&self, address: Address) -> RpcResult<()> {
+        self.eth_api().pool().remove_transactions_by_sender(address);
+        Ok(())
+    }
+}
+
+impl<Eth> std::fmt::Debug for AnvilApi<Eth> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AnvilApi").finish_non_exhaustive()
+    }
+}
+
+impl<Eth> Clone for AnvilApi<Eth> {
+    fn clone(&self) -> Self {
+        Self { inner: Arc::clone(&self.inner) }
+    }
+}
+
+struct AnvilApiInner<Eth> {
+    /// The implementation of `eth` API
+    eth_api: Eth,
+    /// Handle to the local miner driving the dev chain
+    miner: LocalMinerHandle,
+    /// Unique id of this node instance, reported by `anvil_metadata`
+    instance_id: B256,
+}
+
+/// Returns the error for the methods that would change the node outside of mined blocks.
+fn unsupported() -> ErrorObject<'static> {
+    EthApiError::Unsupported("changing the node outside of mined blocks is not supported").into()
+}
+
+/// Converts a quantity argument into a `u64`.
+fn to_u64(value: U256) -> RpcResult<u64> {
+    value.try_into().map_err(|_| invalid_params_rpc_err(format!("{value} exceeds u64")))
+}
+
+/// Maps a [`LocalMinerError`] to an RPC error.
+fn miner_err(err: LocalMinerError) -> ErrorObject<'static> {
+    match err {
+        LocalMinerError::TimestampTooLow { .. } => invalid_params_rpc_err(err.to_string()),
+        _ => internal_rpc_err(err.to_string()),
+    }
+}
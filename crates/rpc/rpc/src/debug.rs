@@ -1,11 +1,11 @@
-use alloy_consensus::{transaction::SignerRecoverable, BlockHeader};
+use alloy_consensus::{constants::KECCAK_EMPTY, transaction::SignerRecoverable, BlockHeader};
 use alloy_eips::{eip2718::Encodable2718, BlockId, BlockNumberOrTag};
 use alloy_genesis::ChainConfig;
-use alloy_primitives::{uint, Address, Bytes, B256};
+use alloy_primitives::{hex, keccak256, uint, Address, Bytes, B256, U256};
 use alloy_rlp::{Decodable, Encodable};
 use alloy_rpc_types_debug::ExecutionWitness;
 use alloy_rpc_types_eth::{
-    state::EvmOverrides, Block as RpcBlock, BlockError, Bundle, StateContext, TransactionInfo,
+    state::EvmOverrides, BlockError, BlockTransactionsKind, Bundle, StateContext, TransactionInfo,
 };
 use alloy_rpc_types_trace::geth::{
     call::FlatCallFrame, BlockTraceResult, FourByteFrame, GethDebugBuiltInTracerType,
@@ -13,37 +13,100 @@ use alloy_rpc_types_trace::geth::{
     NoopFrame, TraceResult,
 };
 use async_trait::async_trait;
-use jsonrpsee::core::RpcResult;
+use futures::{Stream, StreamExt};
+use jsonrpsee::{
+    core::{RpcResult, SubscriptionError},
+    types::ErrorObject,
+    PendingSubscriptionSink, SubscriptionMessage, SubscriptionSink,
+};
 use reth_chainspec::{ChainSpecProvider, EthChainSpec, EthereumHardforks};
-use reth_evm::{execute::Executor, ConfigureEvm, EvmEnvFor, TxEnvFor};
+use reth_errors::RethError;
+use reth_evm::{execute::Executor, ConfigureEvm, Evm, EvmEnvFor, TxEnvFor};
 use reth_primitives_traits::{
     Block as _, BlockBody, ReceiptWithBloom, RecoveredBlock, SignedTransaction,
 };
 use reth_revm::{
     database::StateProviderDatabase,
-    db::{CacheDB, State},
+    db::{AccountState, CacheDB, State},
     witness::ExecutionWitnessRecord,
 };
-use reth_rpc_api::DebugApiServer;
-use reth_rpc_convert::RpcTxReq;
+use reth_rpc_api::{
+    AccountRangeResult, BadBlock, DebugApiServer, DumpAccount, StdTraceConfig, StorageRangeEntry,
+    StorageRangeResult,
+};
+use reth_rpc_convert::{RpcBlock, RpcConvert, RpcTxReq};
 use reth_rpc_eth_api::{
     helpers::{EthTransactions, TraceExt},
-    EthApiTypes, FromEthApiError, RpcNodeCore,
+    EthApiTypes, FromEthApiError, FromEvmError, RpcNodeCore,
 };
 use reth_rpc_eth_types::{EthApiError, StateCacheDb};
-use reth_rpc_server_types::{result::internal_rpc_err, ToRpcResult};
+use reth_rpc_server_types::{
+    result::{internal_rpc_err, invalid_params_rpc_err},
+    ToRpcResult,
+};
 use reth_storage_api::{
-    BlockIdReader, BlockReaderIdExt, HeaderProvider, ProviderBlock, ReceiptProviderIdExt,
-    StateProofProvider, StateProviderFactory, StateRootProvider, TransactionVariant,
+    errors::provider::ProviderResult, AccountHistoryReader, AccountReader, AccountStateBefore,
+    BadBlockReader, BlockHashReader, BlockIdReader, BlockNumReader, BlockReaderIdExt,
+    BytecodeReader, HashedAccountDump, HashedStateRangeReader, HeaderProvider, ProviderBlock,
+    ProviderTx, ReceiptProviderIdExt, StateProofProvider, StateProvider, StateProviderFactory,
+    StateRootProvider, TransactionVariant,
 };
 use reth_tasks::pool::BlockingTaskGuard;
-use reth_trie_common::{updates::TrieUpdates, HashedPostState};
-use revm::{context_interface::Transaction, state::EvmState, DatabaseCommit};
+use reth_tracing::LogFilterHandle;
+use reth_trie_common::{updates::TrieUpdates, HashedPostState, HashedStorage};
+use revm::{
+    context_interface::Transaction, inspector::inspectors::TracerEip3155, state::EvmState,
+    DatabaseCommit,
+};
 use revm_inspectors::tracing::{
     FourByteInspector, MuxInspector, TracingInspector, TracingInspectorConfig, TransactionContext,
 };
-use std::sync::Arc;
+use serde::{Deserialize, Serialize};
+use std::{
+    cell::RefCell,
+    collections::BTreeMap,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    rc::Rc,
+    sync::Arc,
+};
 use tokio::sync::{AcquireError, OwnedSemaphorePermit};
+use tracing::{debug, level_filters::LevelFilter};
+
+/// The maximum number of blocks that are traced concurrently by `debug_traceChain`.
+const TRACE_CHAIN_CONCURRENCY: usize = 4;
+
+/// The maximum number of accounts returned by a single `debug_accountRange` request.
+const ACCOUNT_RANGE_MAX_RESULTS: usize = 256;
+
+/// The maximum number of storage slots returned per account by `debug_accountRange`.
+const ACCOUNT_RANGE_MAX_STORAGE: usize = 1024;
+
+/// The maximum number of plain storage entries of a contract that `debug_storageRangeAt` scans to
+/// resolve the preimages of hashed slots.
+const STORAGE_RANGE_MAX_PREIMAGE_SCAN: usize = 1 << 16;
+
+/// The maximum distance from the tip of the blocks whose state `debug_accountRange` and
+/// `debug_storageRangeAt` iterate.
+///
+/// The state of an older block is iterated with the changes of all later blocks reverted in
+/// memory, so the distance bounds the memory used by a request.
+const STATE_RANGE_MAX_DEPTH: u64 = 8192;
+
+/// The maximum number of blocks whose modified accounts `debug_getModifiedAccountsByNumber` and
+/// `debug_getModifiedAccountsByHash` collect in a single request.
+const MODIFIED_ACCOUNTS_MAX_BLOCKS: u64 = 8192;
+
+/// Configuration for the `debug` API.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DebugApiConfig {
+    /// The directory the `debug_standardTraceBlockToFile` and `debug_standardTraceBadBlockToFile`
+    /// methods write their trace files to.
+    ///
+    /// If not set, these methods are unavailable.
+    pub trace_dir: Option<PathBuf>,
+}
 
 /// `debug` API implementation.
 ///
@@ -57,7 +120,16 @@ pub struct DebugApi<Eth> {
 impl<Eth> DebugApi<Eth> {
     /// Create a new instance of the [`DebugApi`]
     pub fn new(eth_api: Eth, blocking_task_guard: BlockingTaskGuard) -> Self {
-        let inner = Arc::new(DebugApiInner { eth_api, blocking_task_guard });
+        Self::with_config(eth_api, blocking_task_guard, DebugApiConfig::default())
+    }
+
+    /// Create a new instance of the [`DebugApi`] with the given [`DebugApiConfig`]
+    pub fn with_config(
+        eth_api: Eth,
+        blocking_task_guard: BlockingTaskGuard,
+        config: DebugApiConfig,
+    ) -> Self {
+        let inner = Arc::new(DebugApiInner { eth_api, blocking_task_guard, config });
         Self { inner }
     }
 
@@ -151,30 +223,203 @@ where
             .map_err(Eth::Error::from_eth_err)?;
 
         let evm_env = self.eth_api().evm_config().evm_env(block.header());
+        let senders = self.recover_senders(block.header().number(), block.body().transactions())?;
+
+        self.trace_block(Arc::new(block.into_recovered_with_signers(senders)), evm_env, opts).await
+    }
 
+    /// Recovers the senders of the given transactions of the block with the given number.
+    fn recover_senders(
+        &self,
+        block_number: u64,
+        transactions: &[ProviderTx<Eth::Provider>],
+    ) -> Result<Vec<Address>, Eth::Error> {
         // Depending on EIP-2 we need to recover the transactions differently
-        let senders =
-            if self.provider().chain_spec().is_homestead_active_at_block(block.header().number()) {
-                block
-                    .body()
-                    .transactions()
-                    .iter()
-                    .map(|tx| tx.recover_signer().map_err(Eth::Error::from_eth_err))
-                    .collect::<Result<Vec<_>, _>>()?
-                    .into_iter()
-                    .collect()
-            } else {
-                block
-                    .body()
-                    .transactions()
-                    .iter()
-                    .map(|tx| tx.recover_signer_unchecked().map_err(Eth::Error::from_eth_err))
-                    .collect::<Result<Vec<_>, _>>()?
-                    .into_iter()
-                    .collect()
-            };
+        let homestead = self.provider().chain_spec().is_homestead_active_at_block(block_number);
+        transactions
+            .iter()
+            .map(|tx| {
+                if homestead { tx.recover_signer() } else { tx.recover_signer_unchecked() }
+                    .map_err(Eth::Error::from_eth_err)
+            })
+            .collect()
+    }
 
-        self.trace_block(Arc::new(block.into_recovered_with_signers(senders)), evm_env, opts).await
+    /// Returns the recorded bad block with the given hash, with its senders recovered.
+    fn recovered_bad_block(
+        &self,
+        block_hash: B256,
+    ) -> Result<RecoveredBlock<ProviderBlock<Eth::Provider>>, Eth::Error> {
+        let block = self
+            .provider()
+            .bad_block_by_hash(block_hash)
+            .map_err(Eth::Error::from_eth_err)?
+            .ok_or_else(|| {
+                EthApiError::InvalidParams(format!("bad block {block_hash} not found"))
+            })?;
+        let senders = self.recover_senders(block.number(), block.body().transactions())?;
+        Ok(block.with_senders(senders))
+    }
+
+    /// Replays the bad block with the given hash and returns the trace of each transaction.
+    ///
+    /// Note, the parent of the bad block must be present, or it will fail.
+    pub async fn debug_trace_bad_block(
+        &self,
+        block_hash: B256,
+        opts: GethDebugTracingOptions,
+    ) -> Result<Vec<TraceResult>, Eth::Error> {
+        let block = self.recovered_bad_block(block_hash)?;
+        let evm_env = self.eth_api().evm_config().evm_env(block.header());
+
+        self.trace_block(Arc::new(block), evm_env, opts).await
+    }
+
+    /// Replays the block with the given hash and returns the state root after each of its
+    /// transactions.
+    ///
+    /// The block is looked up among the known blocks first, and among the recorded bad blocks
+    /// otherwise.
+    pub async fn debug_intermediate_roots(
+        &self,
+        block_hash: B256,
+    ) -> Result<Vec<B256>, Eth::Error> {
+        let block = match self.eth_api().recovered_block(block_hash.into()).await? {
+            Some(block) => block,
+            None => Arc::new(self.recovered_bad_block(block_hash)?),
+        };
+        let evm_env = self.eth_api().evm_config().evm_env(block.header());
+        // empty accounts are removed from the state since EIP-161
+        let delete_empty =
+            self.provider().chain_spec().is_spurious_dragon_active_at_block(block.number());
+
+        let this = self.clone();
+        self.eth_api()
+            .spawn_with_state_at_block(block.parent_hash().into(), move |state| {
+                let mut db = CacheDB::new(StateProviderDatabase::new(state));
+                this.eth_api().apply_pre_execution_changes(&block, &mut db, &evm_env)?;
+
+                let mut roots = Vec::with_capacity(block.body().transactions().len());
+                let mut evm = this.eth_api().evm_config().evm_with_env(&mut db, evm_env);
+                for tx in block.transactions_recovered() {
+                    let tx_env = this.eth_api().evm_config().tx_env(tx);
+                    evm.transact_commit(tx_env).map_err(Eth::Error::from_evm_err)?;
+
+                    let db = evm.db_mut();
+                    let hashed_state = cache_db_hashed_state(db, delete_empty);
+                    roots.push(db.db.state_root(hashed_state).map_err(Eth::Error::from_eth_err)?);
+                }
+
+                Ok(roots)
+            })
+            .await
+    }
+
+    /// Replays the given block and writes an EIP-3155 trace of each of its transactions to a
+    /// separate file in the configured trace directory, returning the paths of the files.
+    ///
+    /// If [`StdTraceConfig::tx_hash`] is set, only that transaction is traced.
+    async fn standard_trace_block_to_file(
+        &self,
+        block: Arc<RecoveredBlock<ProviderBlock<Eth::Provider>>>,
+        config: StdTraceConfig,
+    ) -> Result<Vec<String>, Eth::Error> {
+        if let Some(tx_hash) = config.tx_hash {
+            if !block.body().transactions().iter().any(|tx| *tx.tx_hash() == tx_hash) {
+                return Err(EthApiError::InvalidParams(format!(
+                    "transaction {tx_hash} not found in block {}",
+                    block.hash()
+                ))
+                .into())
+            }
+        }
+
+        let Some(trace_dir) = self.inner.config.trace_dir.clone() else {
+            return Err(EthApiError::Unsupported("no trace directory configured").into())
+        };
+
+        let evm_env = self.eth_api().evm_config().evm_env(block.header());
+        let this = self.clone();
+        self.eth_api()
+            .spawn_with_state_at_block(block.parent_hash().into(), move |state| {
+                let trace_dir = &trace_dir;
+                std::fs::create_dir_all(trace_dir)
+                    .map_err(|err| trace_file_error(trace_dir, err))?;
+
+                let mut db = CacheDB::new(StateProviderDatabase::new(state));
+                this.eth_api().apply_pre_execution_changes(&block, &mut db, &evm_env)?;
+
+                let mut paths = Vec::new();
+                for (index, tx) in block.transactions_recovered().enumerate() {
+                    let tx_hash = *tx.tx_hash();
+                    let tx_env = this.eth_api().evm_config().tx_env(tx);
+
+                    if config.tx_hash.is_some_and(|hash| hash != tx_hash) {
+                        // only the state changes of this transaction are needed
+                        let mut evm =
+                            this.eth_api().evm_config().evm_with_env(&mut db, evm_env.clone());
+                        evm.transact_commit(tx_env).map_err(Eth::Error::from_evm_err)?;
+                        continue
+                    }
+
+                    // named like the files written by geth
+                    let path = trace_dir.join(format!(
+                        "block_0x{}-{index}-0x{}.jsonl",
+                        hex::encode(&block.hash()[..4]),
+                        hex::encode(&tx_hash[..4])
+                    ));
+                    let mut file =
+                        TraceFile::create(&path).map_err(|err| trace_file_error(&path, err))?;
+
+                    let mut tracer = TracerEip3155::new(Box::new(file.clone()));
+                    if config.enable_memory {
+                        tracer = tracer.with_memory();
+                    }
+                    let mut evm = this.eth_api().evm_config().evm_with_env_and_inspector(
+                        &mut db,
+                        evm_env.clone(),
+                        &mut tracer,
+                    );
+                    evm.transact_commit(tx_env).map_err(Eth::Error::from_evm_err)?;
+                    file.flush().map_err(|err| trace_file_error(&path, err))?;
+
+                    paths.push(path.display().to_string());
+                    if config.tx_hash.is_some() {
+                        break
+                    }
+                }
+
+                Ok(paths)
+            })
+            .await
+    }
+
+    /// Replays the block with the given hash and writes an EIP-3155 trace of each of its
+    /// transactions to a separate file, see [`DebugApiConfig::trace_dir`].
+    pub async fn debug_standard_trace_block_to_file(
+        &self,
+        block_hash: B256,
+        config: StdTraceConfig,
+    ) -> Result<Vec<String>, Eth::Error> {
+        let block = self
+            .eth_api()
+            .recovered_block(block_hash.into())
+            .await?
+            .ok_or(EthApiError::HeaderNotFound(block_hash.into()))?;
+
+        self.standard_trace_block_to_file(block, config).await
+    }
+
+    /// Replays the bad block with the given hash and writes an EIP-3155 trace of each of its
+    /// transactions to a separate file, see [`DebugApiConfig::trace_dir`].
+    pub async fn debug_standard_trace_bad_block_to_file(
+        &self,
+        block_hash: B256,
+        config: StdTraceConfig,
+    ) -> Result<Vec<String>, Eth::Error> {
+        let block = self.recovered_bad_block(block_hash)?;
+
+        self.standard_trace_block_to_file(Arc::new(block), config).await
     }
 
     /// Replays a block and returns the trace of each transaction.
@@ -199,6 +444,396 @@ where
         self.trace_block(block, evm_env, opts).await
     }
 
+    /// Replays all blocks in the `(start_exclusive, end_inclusive]` range and returns a stream of
+    /// the traces of each block, in block order.
+    ///
+    /// Up to [`TRACE_CHAIN_CONCURRENCY`] blocks are traced concurrently, each of them holding a
+    /// trace permit while it is being traced.
+    pub fn debug_trace_chain_stream(
+        &self,
+        start_exclusive: u64,
+        end_inclusive: u64,
+        opts: GethDebugTracingOptions,
+    ) -> impl Stream<Item = Result<BlockTraceResult, Eth::Error>> + Send + 'static {
+        let this = self.clone();
+        futures::stream::iter(start_exclusive.saturating_add(1)..=end_inclusive)
+            .map(move |number| {
+                let this = this.clone();
+                let opts = opts.clone();
+                async move {
+                    let _permit = this.acquire_trace_permit().await;
+                    let hash = this
+                        .provider()
+                        .block_hash(number)
+                        .map_err(Eth::Error::from_eth_err)?
+                        .ok_or(EthApiError::HeaderNotFound(number.into()))?;
+                    let traces = this.debug_trace_block(hash.into(), opts).await?;
+                    Ok(BlockTraceResult { block: U256::from(number), hash, traces })
+                }
+            })
+            .buffered(TRACE_CHAIN_CONCURRENCY)
+    }
+
+    /// Resolves the block range of a `debug_traceChain` request.
+    fn trace_chain_range(
+        &self,
+        start_exclusive: BlockNumberOrTag,
+        end_inclusive: BlockNumberOrTag,
+    ) -> Result<(u64, u64), Eth::Error> {
+        let resolve = |block: BlockNumberOrTag| -> Result<u64, Eth::Error> {
+            self.provider()
+                .convert_block_number(block)
+                .map_err(Eth::Error::from_eth_err)?
+                .ok_or_else(|| EthApiError::HeaderNotFound(block.into()).into())
+        };
+        let start = resolve(start_exclusive)?;
+        let end = resolve(end_inclusive)?;
+
+        let best = self.provider().best_block_number().map_err(Eth::Error::from_eth_err)?;
+        if end > best {
+            return Err(EthApiError::HeaderNotFound(end.into()).into())
+        }
+        if start >= end {
+            return Err(EthApiError::InvalidParams(format!(
+                "end block (#{end}) needs to come after start block (#{start})"
+            ))
+            .into())
+        }
+
+        Ok((start, end))
+    }
+
+    /// Returns a page of the storage of the account in the state before the transaction at the
+    /// given index of the block.
+    ///
+    /// The storage is taken from the hashed state of the parent block, with the changes made by the
+    /// preceding transactions of the block applied on top. Preimages of the hashed slots are
+    /// included if they can be resolved.
+    pub async fn debug_storage_range_at(
+        &self,
+        block_hash: B256,
+        tx_idx: usize,
+        contract_address: Address,
+        key_start: B256,
+        max_result: usize,
+    ) -> Result<StorageRangeResult, Eth::Error> {
+        let block = self
+            .eth_api()
+            .recovered_block(block_hash.into())
+            .await?
+            .ok_or(EthApiError::HeaderNotFound(block_hash.into()))?;
+        if block.number() == 0 {
+            return Err(EthApiError::InvalidParams("genesis is not traceable".to_string()).into())
+        }
+        let target_tx_hash = match block.body().transactions().get(tx_idx) {
+            Some(tx) => Some(*tx.tx_hash()),
+            None if tx_idx == 0 => None,
+            None => {
+                return Err(EthApiError::InvalidParams(format!(
+                    "transaction index {tx_idx} out of range for block {block_hash}"
+                ))
+                .into())
+            }
+        };
+        // the storage of the parent state is looked up by block number
+        if self.provider().block_hash(block.number() - 1).map_err(Eth::Error::from_eth_err)? !=
+            Some(block.parent_hash())
+        {
+            return Err(
+                EthApiError::InvalidParams(format!("block {block_hash} is not canonical")).into()
+            )
+        }
+        ensure_state_range_depth(
+            self.provider().best_block_number().map_err(Eth::Error::from_eth_err)?,
+            block.number() - 1,
+        )?;
+        let (evm_env, _) = self.eth_api().evm_env_at(block_hash.into()).await?;
+
+        let this = self.clone();
+        self.eth_api()
+            .spawn_with_state_at_block(block.parent_hash().into(), move |state| {
+                let mut db = CacheDB::new(StateProviderDatabase::new(state));
+
+                // replay all transactions prior to the targeted transaction
+                this.eth_api().apply_pre_execution_changes(&block, &mut db, &evm_env)?;
+                if let Some(target_tx_hash) = target_tx_hash {
+                    this.eth_api().replay_transactions_until(
+                        &mut db,
+                        evm_env,
+                        block.transactions_recovered(),
+                        target_tx_hash,
+                    )?;
+                }
+
+                // the slots that were changed by the replayed transactions, which take priority
+                // over the slots of the parent state
+                let account = db.cache.accounts.get(&contract_address);
+                let wiped = account.is_some_and(|account| {
+                    matches!(
+                        account.account_state,
+                        AccountState::NotExisting | AccountState::StorageCleared
+                    )
+                });
+                let changed = account
+                    .into_iter()
+                    .flat_map(|account| &account.storage)
+                    .map(|(slot, value)| {
+                        let slot = B256::from(*slot);
+                        (keccak256(slot), (slot, *value))
+                    })
+                    .filter(|(hashed_slot, _)| *hashed_slot >= key_start)
+                    .collect::<BTreeMap<_, _>>();
+
+                // every changed slot can hide at most one slot of the parent state, so fetching
+                // that many additional slots is enough to fill the page
+                let mut slots = BTreeMap::new();
+                if wiped {
+                    slots.extend(
+                        changed
+                            .into_iter()
+                            .filter(|(_, (_, value))| !value.is_zero())
+                            .map(|(hashed_slot, (slot, value))| (hashed_slot, (Some(slot), value))),
+                    );
+                } else {
+                    let limit = max_result.saturating_add(changed.len()).saturating_add(1);
+                    let parent_slots = this
+                        .provider()
+                        .hashed_storage_range(
+                            block.number() - 1,
+                            keccak256(contract_address),
+                            key_start,
+                            limit,
+                        )
+                        .map_err(Eth::Error::from_eth_err)?;
+                    let last_slot = (parent_slots.len() == limit)
+                        .then(|| parent_slots.last().map(|(hashed_slot, _)| *hashed_slot))
+                        .flatten();
+                    slots.extend(
+                        parent_slots
+                            .into_iter()
+                            .map(|(hashed_slot, value)| (hashed_slot, (None, value))),
+                    );
+                    for (hashed_slot, (slot, value)) in changed {
+                        if value.is_zero() {
+                            slots.remove(&hashed_slot);
+                        } else if last_slot.is_none_or(|last_slot| hashed_slot <= last_slot) {
+                            slots.insert(hashed_slot, (Some(slot), value));
+                        }
+                    }
+                }
+
+                let next_key = slots.keys().nth(max_result).copied();
+                let mut slots = slots.into_iter().take(max_result).collect::<Vec<_>>();
+
+                let unresolved = slots
+                    .iter()
+                    .filter(|(_, (slot, _))| slot.is_none())
+                    .map(|(hashed_slot, _)| *hashed_slot)
+                    .collect::<Vec<_>>();
+                if !unresolved.is_empty() {
+                    let preimages = this
+                        .provider()
+                        .storage_preimages(
+                            contract_address,
+                            &unresolved,
+                            STORAGE_RANGE_MAX_PREIMAGE_SCAN,
+                        )
+                        .map_err(Eth::Error::from_eth_err)?;
+                    for (hashed_slot, (slot, _)) in &mut slots {
+                        if slot.is_none() {
+                            *slot = preimages.get(hashed_slot).copied();
+                        }
+                    }
+                }
+
+                let storage = slots
+                    .into_iter()
+                    .map(|(hashed_slot, (key, value))| {
+                        (hashed_slot, StorageRangeEntry { key, value: value.into() })
+                    })
+                    .collect();
+                Ok(StorageRangeResult { storage, next_key })
+            })
+            .await
+    }
+
+    /// Returns a page of the accounts in the state after the given block, ordered by their hashed
+    /// address.
+    ///
+    /// Preimages of hashed addresses are not stored, so every account is keyed by its hashed
+    /// address. Accounts without a preimage are only included if `incompletes` is set, otherwise
+    /// they are skipped and only the key of the next page is returned. At most
+    /// [`ACCOUNT_RANGE_MAX_STORAGE`] storage slots are returned per account, and the block can be
+    /// at most [`STATE_RANGE_MAX_DEPTH`] blocks behind the tip.
+    pub async fn debug_account_range(
+        &self,
+        block_number: BlockNumberOrTag,
+        start: Bytes,
+        max_results: usize,
+        nocode: bool,
+        nostorage: bool,
+        incompletes: bool,
+    ) -> Result<AccountRangeResult, Eth::Error> {
+        if start.len() > 32 {
+            return Err(EthApiError::InvalidParams(format!(
+                "start key is too long, expected at most 32 bytes, got {}",
+                start.len()
+            ))
+            .into())
+        }
+        let start = B256::right_padding_from(&start);
+        let max_results = if max_results == 0 || max_results > ACCOUNT_RANGE_MAX_RESULTS {
+            ACCOUNT_RANGE_MAX_RESULTS
+        } else {
+            max_results
+        };
+
+        self.eth_api()
+            .spawn_blocking_io(move |this| {
+                let provider = this.provider();
+                let header = provider
+                    .sealed_header_by_number_or_tag(block_number)
+                    .map_err(Eth::Error::from_eth_err)?
+                    .ok_or(EthApiError::HeaderNotFound(block_number.into()))?;
+                ensure_state_range_depth(
+                    provider.best_block_number().map_err(Eth::Error::from_eth_err)?,
+                    header.number(),
+                )?;
+                let mut result =
+                    AccountRangeResult { root: header.state_root(), ..Default::default() };
+
+                let mut accounts = provider
+                    .hashed_accounts_dump(
+                        header.number(),
+                        start,
+                        max_results + 1,
+                        (!nostorage).then_some(ACCOUNT_RANGE_MAX_STORAGE),
+                    )
+                    .map_err(Eth::Error::from_eth_err)?;
+                if accounts.len() > max_results {
+                    result.next = accounts.pop().map(|dump| dump.hashed_address);
+                }
+
+                let state =
+                    if nocode { None } else { Some(this.state_at_block_id(header.hash().into())?) };
+                for HashedAccountDump { hashed_address, account, storage_root, storage } in accounts
+                {
+                    let code_hash = account.bytecode_hash.unwrap_or(KECCAK_EMPTY);
+                    let code = match &state {
+                        Some(state) if code_hash != KECCAK_EMPTY => state
+                            .bytecode_by_hash(&code_hash)
+                            .map_err(Eth::Error::from_eth_err)?
+                            .map(|code| code.original_bytes()),
+                        _ => None,
+                    };
+
+                    let dump = DumpAccount {
+                        balance: account.balance.to_string(),
+                        nonce: account.nonce,
+                        root: storage_root,
+                        code_hash,
+                        code,
+                        storage: storage.map(|slots| {
+                            slots
+                                .into_iter()
+                                .map(|(hashed_slot, value)| (hashed_slot, value.into()))
+                                .collect()
+                        }),
+                        address: None,
+                        key: hashed_address,
+                    };
+                    if dump.address.is_none() && !incompletes {
+                        continue
+                    }
+                    result.accounts.insert(format!("pre({hashed_address})"), dump);
+                }
+
+                Ok(result)
+            })
+            .await
+    }
+
+    /// Returns the addresses of all accounts that were modified after the `start` block, up to and
+    /// including the `end` block.
+    ///
+    /// Like geth, this compares the state after the two blocks: accounts that were changed and
+    /// restored to their original info and storage within the range are not returned. The state
+    /// after the `start` block is taken from the changesets of the range, so only the account info
+    /// and the storage slots that were changed are compared.
+    ///
+    /// If no `end` block is given, the accounts modified by the `start` block itself are returned.
+    /// At most [`MODIFIED_ACCOUNTS_MAX_BLOCKS`] blocks are supported.
+    pub async fn debug_get_modified_accounts(
+        &self,
+        start: u64,
+        end: Option<u64>,
+    ) -> Result<Vec<Address>, Eth::Error> {
+        let (start, end) = match end {
+            Some(end) => (start, end),
+            None => {
+                if start == 0 {
+                    return Err(EthApiError::InvalidParams(
+                        "genesis block has no parent".to_string(),
+                    )
+                    .into())
+                }
+                (start - 1, start)
+            }
+        };
+        if start >= end {
+            return Err(EthApiError::InvalidParams(format!(
+                "start block height ({start}) must be less than end block height ({end})"
+            ))
+            .into())
+        }
+        if end - start > MODIFIED_ACCOUNTS_MAX_BLOCKS {
+            return Err(EthApiError::InvalidParams(format!(
+                "requested range of {} blocks exceeds the limit of {MODIFIED_ACCOUNTS_MAX_BLOCKS}",
+                end - start
+            ))
+            .into())
+        }
+
+        self.eth_api()
+            .spawn_blocking_io(move |this| {
+                let provider = this.provider();
+                let best = provider.best_block_number().map_err(Eth::Error::from_eth_err)?;
+                if end > best {
+                    return Err(EthApiError::HeaderNotFound(end.into()).into())
+                }
+
+                let accounts = provider
+                    .modified_accounts(start + 1..=end)
+                    .map_err(Eth::Error::from_eth_err)?;
+
+                let after = this.state_at_block_id(end.into())?;
+                // the account info and the storage make up the account in the state trie, and only
+                // the parts that were changed within the range can differ
+                let is_modified =
+                    |address: Address, before: AccountStateBefore| -> ProviderResult<bool> {
+                        if let Some(info) = before.info {
+                            if info != after.basic_account(&address)? {
+                                return Ok(true)
+                            }
+                        }
+                        for (slot, value) in before.storage {
+                            if after.storage(address, slot)?.unwrap_or_default() != value {
+                                return Ok(true)
+                            }
+                        }
+                        Ok(false)
+                    };
+                let mut modified = Vec::with_capacity(accounts.len());
+                for (address, before) in accounts {
+                    if is_modified(address, before).map_err(Eth::Error::from_eth_err)? {
+                        modified.push(address);
+                    }
+                }
+                Ok(modified)
+            })
+            .await
+    }
+
     /// Trace the transaction according to the provided options.
     ///
     /// Ref: <https://geth.ethereum.org/docs/developers/evm-tracing/built-in-tracers>
@@ -896,7 +1531,19 @@ where
 }
 
 #[async_trait]
-impl<Eth> DebugApiServer<RpcTxReq<Eth::NetworkTypes>> for DebugApi<Eth>
+/// Fails if the state after the given block is more than [`STATE_RANGE_MAX_DEPTH`] blocks behind
+/// the best block.
+fn ensure_state_range_depth(best_block: u64, block_number: u64) -> Result<(), EthApiError> {
+    let depth = best_block.saturating_sub(block_number);
+    if depth > STATE_RANGE_MAX_DEPTH {
+        return Err(EthApiError::InvalidParams(format!(
+            "block #{block_number} is {depth} blocks behind the tip, at most {STATE_RANGE_MAX_DEPTH} are supported"
+        )))
+    }
+    Ok(())
+}
+
+impl<Eth> DebugApiServer<RpcTxReq<Eth::NetworkTypes>, RpcBlock<Eth::NetworkTypes>> for DebugApi<Eth>
 where
     Eth: EthApiTypes + EthTransactions + TraceExt + 'static,
 {
@@ -969,17 +1616,56 @@ where
     }
 
     /// Handler for `debug_getBadBlocks`
-    async fn bad_blocks(&self) -> RpcResult<Vec<RpcBlock>> {
-        Ok(vec![])
+    ///
+    /// Senders that can't be recovered are reported as the zero address.
+    async fn bad_blocks(&self) -> RpcResult<Vec<BadBlock<RpcBlock<Eth::NetworkTypes>>>> {
+        let blocks = self.provider().bad_blocks().to_rpc_result()?;
+        blocks
+            .into_iter()
+            .map(|block| {
+                let hash = block.hash();
+                let rlp = alloy_rlp::encode(block.clone_block()).into();
+                let senders = block
+                    .body()
+                    .transactions()
+                    .iter()
+                    .map(|tx| tx.recover_signer_unchecked().unwrap_or_default())
+                    .collect();
+                let block = block
+                    .with_senders(senders)
+                    .into_rpc_block(
+                        BlockTransactionsKind::Full,
+                        |tx, tx_info| self.eth_api().tx_resp_builder().fill(tx, tx_info),
+                        |header, size| {
+                            self.eth_api().tx_resp_builder().convert_header(header, size)
+                        },
+                    )
+                    .map_err(Into::into)?;
+                Ok(BadBlock { hash, block, rlp })
+            })
+            .collect()
     }
 
     /// Handler for `debug_traceChain`
     async fn debug_trace_chain(
         &self,
-        _start_exclusive: BlockNumberOrTag,
-        _end_inclusive: BlockNumberOrTag,
-    ) -> RpcResult<Vec<BlockTraceResult>> {
-        Err(internal_rpc_err("unimplemented"))
+        pending: PendingSubscriptionSink,
+        start_exclusive: BlockNumberOrTag,
+        end_inclusive: BlockNumberOrTag,
+        opts: Option<GethDebugTracingOptions>,
+    ) -> jsonrpsee::core::SubscriptionResult {
+        let (start, end) = match self.trace_chain_range(start_exclusive, end_inclusive) {
+            Ok(range) => range,
+            Err(err) => {
+                pending.reject(err.into()).await;
+                return Ok(())
+            }
+        };
+
+        let sink = pending.accept().await?;
+        let stream = self.debug_trace_chain_stream(start, end, opts.unwrap_or_default());
+        // an error is sent to the subscriber as the final notification of the subscription
+        pipe_trace_chain(sink, stream).await
     }
 
     /// Handler for `debug_traceBlock`
@@ -1075,16 +1761,27 @@ where
         Ok(())
     }
 
+    /// Handler for `debug_accountRange`
     async fn debug_account_range(
         &self,
-        _block_number: BlockNumberOrTag,
-        _start: Bytes,
-        _max_results: u64,
-        _nocode: bool,
-        _nostorage: bool,
-        _incompletes: bool,
-    ) -> RpcResult<()> {
-        Ok(())
+        block_number: BlockNumberOrTag,
+        start: Bytes,
+        max_results: u64,
+        nocode: bool,
+        nostorage: bool,
+        incompletes: bool,
+    ) -> RpcResult<AccountRangeResult> {
+        Self::debug_account_range(
+            self,
+            block_number,
+            start,
+            max_results.try_into().unwrap_or(usize::MAX),
+            nocode,
+            nostorage,
+            incompletes,
+        )
+        .await
+        .map_err(Into::into)
     }
 
     async fn debug_block_profile(&self, _file: String, _seconds: u64) -> RpcResult<()> {
@@ -1153,18 +1850,26 @@ where
 
     async fn debug_get_modified_accounts_by_hash(
         &self,
-        _start_hash: B256,
-        _end_hash: B256,
-    ) -> RpcResult<()> {
-        Ok(())
+        start_hash: B256,
+        end_hash: Option<B256>,
+    ) -> RpcResult<Vec<Address>> {
+        let block_number = |hash: B256| -> RpcResult<u64> {
+            self.provider()
+                .block_number(hash)
+                .map_err(|err| Eth::Error::from_eth_err(err).into())?
+                .ok_or_else(|| EthApiError::HeaderNotFound(hash.into()).into())
+        };
+        let start = block_number(start_hash)?;
+        let end = end_hash.map(block_number).transpose()?;
+        Self::debug_get_modified_accounts(self, start, end).await.map_err(Into::into)
     }
 
     async fn debug_get_modified_accounts_by_number(
         &self,
-        _start_number: u64,
-        _end_number: u64,
-    ) -> RpcResult<()> {
-        Ok(())
+        start_number: u64,
+        end_number: Option<u64>,
+    ) -> RpcResult<Vec<Address>> {
+        Self::debug_get_modified_accounts(self, start_number, end_number).await.map_err(Into::into)
     }
 
     async fn debug_go_trace(&self, _file: String, _seconds: u64) -> RpcResult<()> {
@@ -1173,10 +1878,11 @@ where
 
     async fn debug_intermediate_roots(
         &self,
-        _block_hash: B256,
-        _opts: Option<GethDebugTracingCallOptions>,
-    ) -> RpcResult<()> {
-        Ok(())
+        block_hash: B256,
+        _opts: Option<GethDebugTracingOptions>,
+    ) -> RpcResult<Vec<B256>> {
+        let _permit = self.acquire_trace_permit().await;
+        Self::debug_intermediate_roots(self, block_hash).await.map_err(Into::into)
     }
 
     async fn debug_mem_stats(&self) -> RpcResult<()> {
@@ -1225,18 +1931,24 @@ where
 
     async fn debug_standard_trace_bad_block_to_file(
         &self,
-        _block: BlockNumberOrTag,
-        _opts: Option<GethDebugTracingCallOptions>,
-    ) -> RpcResult<()> {
-        Ok(())
+        block_hash: B256,
+        config: Option<StdTraceConfig>,
+    ) -> RpcResult<Vec<String>> {
+        let _permit = self.acquire_trace_permit().await;
+        Self::debug_standard_trace_bad_block_to_file(self, block_hash, config.unwrap_or_default())
+            .await
+            .map_err(Into::into)
     }
 
     async fn debug_standard_trace_block_to_file(
         &self,
-        _block: BlockNumberOrTag,
-        _opts: Option<GethDebugTracingCallOptions>,
-    ) -> RpcResult<()> {
-        Ok(())
+        block_hash: B256,
+        config: Option<StdTraceConfig>,
+    ) -> RpcResult<Vec<String>> {
+        let _permit = self.acquire_trace_permit().await;
+        Self::debug_standard_trace_block_to_file(self, block_hash, config.unwrap_or_default())
+            .await
+            .map_err(Into::into)
     }
 
     async fn debug_start_cpu_profile(&self, _file: String) -> RpcResult<()> {
@@ -1263,31 +1975,60 @@ where
         Ok(())
     }
 
+    /// Handler for `debug_storageRangeAt`
     async fn debug_storage_range_at(
         &self,
-        _block_hash: B256,
-        _tx_idx: usize,
-        _contract_address: Address,
-        _key_start: B256,
-        _max_result: u64,
-    ) -> RpcResult<()> {
-        Ok(())
+        block_hash: B256,
+        tx_idx: usize,
+        contract_address: Address,
+        key_start: B256,
+        max_result: u64,
+    ) -> RpcResult<StorageRangeResult> {
+        let _permit = self.acquire_trace_permit().await;
+        Self::debug_storage_range_at(
+            self,
+            block_hash,
+            tx_idx,
+            contract_address,
+            key_start,
+            max_result.try_into().unwrap_or(usize::MAX),
+        )
+        .await
+        .map_err(Into::into)
     }
 
     async fn debug_trace_bad_block(
         &self,
-        _block_hash: B256,
-        _opts: Option<GethDebugTracingCallOptions>,
-    ) -> RpcResult<()> {
-        Ok(())
+        block_hash: B256,
+        opts: Option<GethDebugTracingOptions>,
+    ) -> RpcResult<Vec<TraceResult>> {
+        let _permit = self.acquire_trace_permit().await;
+        Self::debug_trace_bad_block(self, block_hash, opts.unwrap_or_default())
+            .await
+            .map_err(Into::into)
     }
 
-    async fn debug_verbosity(&self, _level: usize) -> RpcResult<()> {
-        Ok(())
+    /// Handler for `debug_verbosity`
+    ///
+    /// The levels are the same as geth's: 0 is off, 1 is error, and so on up to 5, which is
+    /// trace.
+    async fn debug_verbosity(&self, level: usize) -> RpcResult<()> {
+        let level = level
+            .to_string()
+            .parse::<LevelFilter>()
+            .map_err(|_| invalid_params_rpc_err(format!("invalid verbosity level {level}")))?;
+        log_filter_handle()?.set_verbosity(level).map_err(|err| internal_rpc_err(err.to_string()))
     }
 
-    async fn debug_vmodule(&self, _pattern: String) -> RpcResult<()> {
-        Ok(())
+    /// Handler for `debug_vmodule`
+    ///
+    /// The pattern is a comma-separated list of `target=level` directives that are added to the
+    /// filters of all log outputs, e.g. `engine::tree=5,net=debug`. Geth's file patterns, e.g.
+    /// `eth/downloader/*=5`, are translated to the target of the module they name.
+    async fn debug_vmodule(&self, pattern: String) -> RpcResult<()> {
+        log_filter_handle()?
+            .set_vmodule(&pattern)
+            .map_err(|err| invalid_params_rpc_err(err.to_string()))
     }
 
     async fn debug_write_block_profile(&self, _file: String) -> RpcResult<()> {
@@ -1303,6 +2044,119 @@ where
     }
 }
 
+/// A buffered trace file that can still be flushed after it has been handed to a tracer.
+#[derive(Clone)]
+struct TraceFile(Rc<RefCell<BufWriter<File>>>);
+
+impl TraceFile {
+    /// Creates the file at the given path, truncating it if it already exists.
+    fn create(path: &Path) -> io::Result<Self> {
+        Ok(Self(Rc::new(RefCell::new(BufWriter::new(File::create(path)?)))))
+    }
+}
+
+impl Write for TraceFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.borrow_mut().flush()
+    }
+}
+
+/// Converts an error that occurred while writing a trace file to the given path.
+fn trace_file_error(path: &Path, err: io::Error) -> EthApiError {
+    EthApiError::Internal(RethError::msg(format!(
+        "failed to write trace file {}: {err}",
+        path.display()
+    )))
+}
+
+/// Returns the state of the [`CacheDB`] on top of its underlying database as a
+/// [`HashedPostState`].
+///
+/// If `delete_empty` is set, touched accounts that are empty are treated as removed, as mandated
+/// by EIP-161.
+fn cache_db_hashed_state<DB>(db: &CacheDB<DB>, delete_empty: bool) -> HashedPostState {
+    let mut hashed_state = HashedPostState::default();
+    for (address, account) in &db.cache.accounts {
+        let removed = match account.account_state {
+            AccountState::NotExisting => true,
+            AccountState::None => false,
+            AccountState::Touched | AccountState::StorageCleared => {
+                delete_empty && account.info.is_empty()
+            }
+        };
+        let wiped = removed || account.account_state == AccountState::StorageCleared;
+
+        let hashed_address = keccak256(address);
+        hashed_state.accounts.insert(hashed_address, (!removed).then(|| (&account.info).into()));
+        hashed_state.storages.insert(
+            hashed_address,
+            HashedStorage::from_iter(
+                wiped,
+                account
+                    .storage
+                    .iter()
+                    .filter(|_| !removed)
+                    .map(|(slot, value)| (keccak256(B256::from(*slot)), *value)),
+            ),
+        );
+    }
+    hashed_state
+}
+
+/// Pipes the block traces of a `debug_traceChain` request to the subscription sink.
+///
+/// The subscription ends once all blocks have been traced, the connection is dropped, or a block
+/// fails to be traced, in which case the error is returned so it's sent to the subscriber.
+async fn pipe_trace_chain<E>(
+    sink: SubscriptionSink,
+    stream: impl Stream<Item = Result<BlockTraceResult, E>>,
+) -> Result<(), SubscriptionError>
+where
+    E: Into<ErrorObject<'static>> + std::fmt::Debug,
+{
+    let mut stream = std::pin::pin!(stream);
+    loop {
+        tokio::select! {
+            _ = sink.closed() => {
+                // connection dropped
+                return Ok(())
+            }
+            maybe_item = stream.next() => {
+                let item = match maybe_item {
+                    Some(Ok(item)) => item,
+                    Some(Err(err)) => {
+                        debug!(target: "rpc::debug", ?err, "failed to trace block, ending traceChain subscription");
+                        let err: ErrorObject<'static> = err.into();
+                        return Err(serde_json::value::to_raw_value(&err)
+                            .map(SubscriptionError::from_json)
+                            .unwrap_or_else(|_| err.message().into()))
+                    }
+                    None => {
+                        // all blocks traced
+                        return Ok(())
+                    }
+                };
+                let msg = SubscriptionMessage::new(sink.method_name(), sink.subscription_id(), &item)
+                    .map_err(|err| SubscriptionError::from(format!("failed to serialize block trace: {err}")))?;
+
+                if sink.send(msg).await.is_err() {
+                    return Ok(())
+                }
+            }
+        }
+    }
+}
+
+/// Returns the handle to change the log filters of the node at runtime.
+pub(crate) fn log_filter_handle() -> RpcResult<&'static LogFilterHandle> {
+    reth_tracing::log_filter_handle()
+        .ok_or_else(|| internal_rpc_err("log filters can't be changed at runtime"))
+}
+
 impl<Eth> std::fmt::Debug for DebugApi<Eth> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("DebugApi").finish_non_exhaustive()
@@ -1320,4 +2174,6 @@ struct DebugApiInner<Eth> {
     eth_api: Eth,
     // restrict the number of concurrent calls to blocking calls
     blocking_task_guard: BlockingTaskGuard,
+    /// The configuration of the `debug` API
+    config: DebugApiConfig,
 }
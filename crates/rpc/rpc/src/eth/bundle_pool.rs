@@ -0,0 +1,195 @@
+//! `eth_sendBundle` and `mev_sendBundle` implementation backed by the local bundle pool.
+
+use alloy_eips::Typed2718;
+use alloy_primitives::B256;
+use alloy_rpc_types_mev::{EthBundleHash, EthCancelBundle, EthSendBundle, MevSendBundle};
+use jsonrpsee::core::RpcResult;
+use reth_primitives_traits::SignedTransaction;
+use reth_rpc_api::MevSendBundleApiServer;
+use reth_rpc_eth_api::{
+    helpers::{block::LoadBlock, Call, EthTransactions},
+    EthSendBundleApiServer,
+};
+use reth_rpc_eth_types::{utils::recover_raw_transaction, EthApiError};
+use reth_tasks::pool::BlockingTaskGuard;
+use reth_transaction_pool::{
+    bundle::{Bundle, BundlePool, BundleTransaction},
+    PoolConsensusTx, PoolPooledTx, PoolTransaction, TransactionPool,
+};
+
+use crate::eth::{bundle::EthBundleError, sim_bundle::EthSimBundle};
+
+/// Bundle submission API that feeds the bundle pool of the transaction pool.
+///
+/// Bundles submitted via `eth_sendBundle` or `mev_sendBundle` are tried by the payload builder at
+/// the top of the blocks they target.
+pub struct BundlePoolApi<Eth> {
+    /// Used to parse and flatten `mev_sendBundle` requests.
+    sim: EthSimBundle<Eth>,
+}
+
+impl<Eth> BundlePoolApi<Eth> {
+    /// Create a new `BundlePoolApi` instance.
+    pub fn new(eth_api: Eth, blocking_task_guard: BlockingTaskGuard) -> Self {
+        Self { sim: EthSimBundle::new(eth_api, blocking_task_guard) }
+    }
+
+    /// Access the underlying `Eth` API.
+    pub fn eth_api(&self) -> &Eth {
+        self.sim.eth_api()
+    }
+}
+
+impl<Eth> BundlePoolApi<Eth>
+where
+    Eth: EthTransactions + LoadBlock + Call + 'static,
+{
+    /// Returns the bundle pool of the transaction pool.
+    fn bundle_pool(&self) -> Result<&BundlePool<PoolConsensusTx<Eth::Pool>>, EthApiError> {
+        self.eth_api()
+            .pool()
+            .bundle_pool()
+            .ok_or(EthApiError::Unsupported("the bundle pool is disabled"))
+    }
+
+    /// Adds the bundle to the bundle pool and returns its hash.
+    fn add_bundle(
+        &self,
+        bundle: Bundle<PoolConsensusTx<Eth::Pool>>,
+    ) -> Result<EthBundleHash, EthApiError> {
+        if bundle.transactions.iter().any(|tx| tx.tx.is_eip4844()) {
+            return Err(EthApiError::InvalidParams(BundlePoolApiError::BlobTransaction.to_string()))
+        }
+
+        let bundle_hash = self
+            .bundle_pool()?
+            .add_bundle(bundle)
+            .map_err(|err| EthApiError::InvalidParams(err.to_string()))?;
+
+        Ok(EthBundleHash { bundle_hash })
+    }
+
+    /// Submits an `eth_sendBundle` bundle.
+    fn send_eth_bundle(&self, bundle: EthSendBundle) -> Result<EthBundleHash, EthApiError> {
+        let EthSendBundle {
+            txs,
+            block_number,
+            min_timestamp,
+            max_timestamp,
+            reverting_tx_hashes,
+            replacement_uuid,
+            dropping_tx_hashes,
+            ..
+        } = bundle;
+
+        if txs.is_empty() {
+            return Err(EthApiError::InvalidParams(
+                EthBundleError::EmptyBundleTransactions.to_string(),
+            ))
+        }
+        if block_number == 0 {
+            return Err(EthApiError::InvalidParams(
+                EthBundleError::BundleMissingBlockNumber.to_string(),
+            ))
+        }
+
+        let transactions = txs
+            .iter()
+            .map(|tx| {
+                let tx = recover_raw_transaction::<PoolPooledTx<Eth::Pool>>(tx)?
+                    .map(<Eth::Pool as TransactionPool>::Transaction::pooled_into_consensus);
+                let hash: &B256 = tx.tx_hash();
+                Ok(BundleTransaction {
+                    can_revert: reverting_tx_hashes.contains(hash),
+                    can_drop: dropping_tx_hashes.contains(hash),
+                    tx,
+                })
+            })
+            .collect::<Result<Vec<_>, EthApiError>>()?;
+
+        self.add_bundle(Bundle {
+            transactions,
+            block_number,
+            max_block_number: block_number,
+            min_timestamp,
+            max_timestamp,
+            replacement_uuid,
+        })
+    }
+
+    /// Submits a `mev_sendBundle` bundle.
+    fn send_mev_bundle(&self, request: MevSendBundle) -> Result<EthBundleHash, EthApiError> {
+        let items = self.sim.parse_and_flatten_bundle(&request)?;
+        if items.iter().any(|item| item.refund_percent.is_some()) {
+            return Err(EthApiError::InvalidParams(BundlePoolApiError::Refund.to_string()))
+        }
+
+        let block_number = request.inclusion.block_number();
+        let max_block_number = request.inclusion.max_block_number().unwrap_or(block_number);
+        let transactions = items
+            .into_iter()
+            .map(|item| BundleTransaction {
+                tx: item.tx,
+                can_revert: item.can_revert,
+                can_drop: false,
+            })
+            .collect();
+
+        self.add_bundle(Bundle {
+            transactions,
+            block_number,
+            max_block_number,
+            min_timestamp: None,
+            max_timestamp: None,
+            replacement_uuid: None,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl<Eth> EthSendBundleApiServer for BundlePoolApi<Eth>
+where
+    Eth: EthTransactions + LoadBlock + Call + 'static,
+{
+    async fn send_bundle(&self, bundle: EthSendBundle) -> RpcResult<EthBundleHash> {
+        Ok(self.send_eth_bundle(bundle)?)
+    }
+
+    async fn cancel_bundle(&self, request: EthCancelBundle) -> RpcResult<()> {
+        self.bundle_pool()?.cancel_bundle(&request.replacement_uuid);
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl<Eth> MevSendBundleApiServer for BundlePoolApi<Eth>
+where
+    Eth: EthTransactions + LoadBlock + Call + 'static,
+{
+    async fn send_bundle(&self, request: MevSendBundle) -> RpcResult<EthBundleHash> {
+        Ok(self.send_mev_bundle(request)?)
+    }
+}
+
+impl<Eth> std::fmt::Debug for BundlePoolApi<Eth> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BundlePoolApi").finish_non_exhaustive()
+    }
+}
+
+impl<Eth> Clone for BundlePoolApi<Eth> {
+    fn clone(&self) -> Self {
+        Self { sim: self.sim.clone() }
+    }
+}
+
+/// [`BundlePoolApi`] specific errors.
+#[derive(Debug, thiserror::Error)]
+pub enum BundlePoolApiError {
+    /// Thrown if the bundle contains a blob transaction.
+    #[error("blob transactions are not supported in bundles")]
+    BlobTransaction,
+    /// Thrown if a `mev_sendBundle` bundle requests a refund.
+    #[error("bundle refunds are not supported")]
+    Refund,
+}
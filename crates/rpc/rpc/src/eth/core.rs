@@ -490,7 +490,7 @@ mod tests {
     use reth_network_api::noop::NoopNetwork;
     use reth_provider::{
         test_utils::{MockEthProvider, NoopProvider},
-        StageCheckpointReader,
+        AccountHistoryReader, BadBlockReader, HashedStateRangeReader, StageCheckpointReader,
     };
     use reth_rpc_eth_api::{node::RpcNodeCoreAdapter, EthApiServer};
     use reth_storage_api::{BlockReader, BlockReaderIdExt, StateProviderFactory};
@@ -513,6 +513,9 @@ mod tests {
             + StateProviderFactory
             + CanonStateSubscriptions<Primitives = reth_ethereum_primitives::EthPrimitives>
             + StageCheckpointReader
+            + AccountHistoryReader
+            + HashedStateRangeReader
+            + BadBlockReader
             + Unpin
             + Clone
             + 'static,
@@ -86,7 +86,7 @@ where
     /// `FlattenedBundleItem` with their associated metadata. This handles recursive bundle
     /// processing up to `MAX_NESTED_BUNDLE_DEPTH` and `MAX_BUNDLE_BODY_SIZE`, preserving
     /// inclusion, validity and privacy settings from parent bundles.
-    fn parse_and_flatten_bundle(
+    pub(crate) fn parse_and_flatten_bundle(
         &self,
         request: &MevSendBundle,
     ) -> Result<Vec<FlattenedBundleItem<ProviderTx<Eth::Provider>>>, EthApiError> {
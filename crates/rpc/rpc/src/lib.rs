@@ -33,6 +33,7 @@ use pin_project as _;
 use tower as _;
 
 mod admin;
+mod anvil;
 mod debug;
 mod engine;
 pub mod eth;
@@ -47,9 +48,12 @@ mod validation;
 mod web3;
 
 pub use admin::AdminApi;
-pub use debug::DebugApi;
+pub use anvil::{AnvilApi, MAX_ANVIL_MINE_BLOCKS};
+pub use debug::{DebugApi, DebugApiConfig};
 pub use engine::{EngineApi, EngineEthApi};
-pub use eth::{helpers::SyncListener, EthApi, EthApiBuilder, EthBundle, EthFilter, EthPubSub};
+pub use eth::{
+    helpers::SyncListener, BundlePoolApi, EthApi, EthApiBuilder, EthBundle, EthFilter, EthPubSub,
+};
 pub use miner::MinerApi;
 pub use net::NetApi;
 pub use otterscan::OtterscanApi;
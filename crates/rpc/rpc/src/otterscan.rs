@@ -1,5 +1,5 @@
 use alloy_consensus::{BlockHeader, Typed2718};
-use alloy_eips::{eip1898::LenientBlockNumberOrTag, BlockId};
+use alloy_eips::{eip1898::LenientBlockNumberOrTag, BlockId, BlockNumberOrTag};
 use alloy_network::{ReceiptResponse, TransactionResponse};
 use alloy_primitives::{Address, Bytes, TxHash, B256, U256};
 use alloy_rpc_types_eth::{BlockTransactions, TransactionReceipt};
@@ -16,18 +16,24 @@ use reth_rpc_api::{EthApiServer, OtterscanServer};
 use reth_rpc_convert::RpcTxReq;
 use reth_rpc_eth_api::{
     helpers::{EthTransactions, TraceExt},
-    FullEthApiTypes, RpcBlock, RpcHeader, RpcReceipt, RpcTransaction,
+    FromEthApiError, FullEthApiTypes, RpcBlock, RpcHeader, RpcReceipt, RpcTransaction,
 };
 use reth_rpc_eth_types::{utils::binary_search, EthApiError};
-use reth_rpc_server_types::result::internal_rpc_err;
+use reth_rpc_server_types::result::{internal_rpc_err, invalid_params_rpc_err};
+use reth_storage_api::{AccountHistoryReader, BlockNumReader};
 use revm::context_interface::result::ExecutionResult;
 use revm_inspectors::{
     tracing::{types::CallTraceNode, TracingInspectorConfig},
     transfer::{TransferInspector, TransferKind},
 };
+use std::{collections::HashSet, ops::RangeInclusive};
 
 const API_LEVEL: u64 = 8;
 
+/// The number of blocks whose history is looked up at once when searching for the transactions of
+/// an address.
+const SEARCH_BLOCK_WINDOW: u64 = 100_000;
+
 /// Otterscan API.
 #[derive(Debug)]
 pub struct OtterscanApi<Eth> {
@@ -63,6 +69,167 @@ where
     }
 }
 
+impl<Eth> OtterscanApi<Eth>
+where
+    Eth: EthApiServer<
+            RpcTxReq<Eth::NetworkTypes>,
+            RpcTransaction<Eth::NetworkTypes>,
+            RpcBlock<Eth::NetworkTypes>,
+            RpcReceipt<Eth::NetworkTypes>,
+            RpcHeader<Eth::NetworkTypes>,
+        > + EthTransactions
+        + TraceExt
+        + 'static,
+{
+    /// Searches the given block range for transactions involving the address.
+    ///
+    /// The range is walked from its highest block down if `descending` is set, and from its lowest
+    /// block up otherwise. Candidate blocks are taken from the account and storage history indices,
+    /// and blocks are always included in full, so the search stops after the first block that
+    /// brings the number of found transactions to at least `page_size`.
+    ///
+    /// NOTE: Only blocks in which the address' account or storage changed are candidates. The
+    /// sender of a transaction always changes, but transactions that only call the address without
+    /// changing its state, e.g. zero-value transfers, calls that don't write storage or reverted
+    /// calls, are not found. Blocks whose history has been pruned are not candidates either.
+    ///
+    /// Returns the found transactions and their receipts, ordered from newest to oldest, and
+    /// whether the whole range was searched.
+    async fn search_transactions(
+        &self,
+        address: Address,
+        range: RangeInclusive<u64>,
+        page_size: usize,
+        descending: bool,
+    ) -> RpcResult<(Vec<RpcTransaction<Eth::NetworkTypes>>, Vec<OtsTransactionReceipt>, bool)> {
+        let (start, end) = range.into_inner();
+        let mut txs = Vec::new();
+        let mut receipts = Vec::new();
+
+        let mut window = if descending {
+            end.saturating_sub(SEARCH_BLOCK_WINDOW - 1).max(start)..=end
+        } else {
+            start..=start.saturating_add(SEARCH_BLOCK_WINDOW - 1).min(end)
+        };
+
+        let mut exhausted = start > end;
+        while !exhausted {
+            let lookup = window.clone();
+            let mut blocks = self
+                .eth
+                .spawn_blocking_io(move |this| {
+                    this.provider()
+                        .account_history_blocks(address, lookup)
+                        .map_err(Eth::Error::from_eth_err)
+                })
+                .await
+                .map_err(Into::into)?;
+            if descending {
+                blocks.reverse();
+            }
+
+            for block in blocks {
+                let (mut block_txs, mut block_receipts) =
+                    self.address_transactions_in_block(address, block).await?;
+                if descending {
+                    block_txs.reverse();
+                    block_receipts.reverse();
+                }
+                txs.extend(block_txs);
+                receipts.extend(block_receipts);
+
+                if txs.len() >= page_size {
+                    break
+                }
+            }
+
+            if txs.len() >= page_size {
+                break
+            }
+
+            // move on to the next window, until the range has been searched entirely
+            if descending {
+                exhausted = *window.start() == start;
+                let next_end = window.start().saturating_sub(1);
+                window = next_end.saturating_sub(SEARCH_BLOCK_WINDOW - 1).max(start)..=next_end;
+            } else {
+                exhausted = *window.end() == end;
+                let next_start = window.end().saturating_add(1);
+                window = next_start..=next_start.saturating_add(SEARCH_BLOCK_WINDOW - 1).min(end);
+            }
+        }
+
+        // results are always returned from newest to oldest
+        if !descending {
+            txs.reverse();
+            receipts.reverse();
+        }
+
+        Ok((txs, receipts, exhausted))
+    }
+
+    /// Returns the transactions of the block that involve the address, along with their receipts,
+    /// in the order they appear in the block.
+    ///
+    /// A transaction involves the address if the address is the caller or the callee of any of the
+    /// transaction's call frames, which covers the sender, the recipient, internal calls and
+    /// contract creations.
+    async fn address_transactions_in_block(
+        &self,
+        address: Address,
+        block_number: u64,
+    ) -> RpcResult<(Vec<RpcTransaction<Eth::NetworkTypes>>, Vec<OtsTransactionReceipt>)> {
+        let block_id = BlockId::from(block_number);
+
+        let involved =
+            self.eth
+                .trace_block_with(
+                    block_id,
+                    None,
+                    TracingInspectorConfig::default_parity(),
+                    move |tx_info, ctx| {
+                        let involved = ctx.inspector.traces().nodes().iter().any(|node| {
+                            node.trace.caller == address || node.trace.address == address
+                        });
+                        Ok(tx_info.index.filter(|_| involved))
+                    },
+                )
+                .await
+                .map_err(Into::into)?
+                .unwrap_or_default()
+                .into_iter()
+                .flatten()
+                .collect::<HashSet<_>>();
+
+        if involved.is_empty() {
+            return Ok(Default::default())
+        }
+
+        let block = self.eth.block_by_number(block_number.into(), true);
+        let receipts = self.eth.block_receipts(block_id);
+        let (block, receipts) = futures::try_join!(block, receipts)?;
+
+        let block = block.ok_or(EthApiError::HeaderNotFound(block_id))?;
+        let receipts = receipts.ok_or(EthApiError::ReceiptsNotFound(block_id))?;
+        let timestamp = Some(block.header.timestamp());
+
+        let BlockTransactions::Full(transactions) = block.transactions else {
+            return Err(internal_rpc_err("block is not full"));
+        };
+
+        Ok(transactions
+            .into_iter()
+            .zip(receipts)
+            .enumerate()
+            .filter(|(index, _)| involved.contains(&(*index as u64)))
+            .map(|(_, (tx, receipt))| {
+                let receipt = ots_receipt(&receipt, tx.ty(), timestamp);
+                (tx, receipt)
+            })
+            .unzip())
+    }
+}
+
 #[async_trait]
 impl<Eth> OtterscanServer<RpcTransaction<Eth::NetworkTypes>, RpcHeader<Eth::NetworkTypes>>
     for OtterscanApi<Eth>
@@ -247,32 +414,7 @@ where
         let receipts = receipts
             .drain(page_start..page_end)
             .zip(transactions.iter().map(Typed2718::ty))
-            .map(|(receipt, tx_ty)| {
-                let inner = OtsReceipt {
-                    status: receipt.status(),
-                    cumulative_gas_used: receipt.cumulative_gas_used(),
-                    logs: None,
-                    logs_bloom: None,
-                    r#type: tx_ty,
-                };
-
-                let receipt = TransactionReceipt {
-                    inner,
-                    transaction_hash: receipt.transaction_hash(),
-                    transaction_index: receipt.transaction_index(),
-                    block_hash: receipt.block_hash(),
-                    block_number: receipt.block_number(),
-                    gas_used: receipt.gas_used(),
-                    effective_gas_price: receipt.effective_gas_price(),
-                    blob_gas_used: receipt.blob_gas_used(),
-                    blob_gas_price: receipt.blob_gas_price(),
-                    from: receipt.from(),
-                    to: receipt.to(),
-                    contract_address: receipt.contract_address(),
-                };
-
-                OtsTransactionReceipt { receipt, timestamp }
-            })
+            .map(|(receipt, tx_ty)| ots_receipt(&receipt, tx_ty, timestamp))
             .collect();
 
         // use `transaction_count` to indicate the paginate information
@@ -284,21 +426,76 @@ where
     /// Handler for `ots_searchTransactionsBefore`
     async fn search_transactions_before(
         &self,
-        _address: Address,
-        _block_number: LenientBlockNumberOrTag,
-        _page_size: usize,
-    ) -> RpcResult<TransactionsWithReceipts> {
-        Err(internal_rpc_err("unimplemented"))
+        address: Address,
+        block_number: LenientBlockNumberOrTag,
+        page_size: usize,
+    ) -> RpcResult<TransactionsWithReceipts<RpcTransaction<Eth::NetworkTypes>>> {
+        if page_size == 0 {
+            return Err(invalid_params_rpc_err("page size must be greater than zero"))
+        }
+        let best_block = self.eth.provider().best_block_number().map_err(EthApiError::from)?;
+
+        // block number 0 means the search starts at the tip, which makes this the first page
+        let (end, first_page) = match block_number.into_inner() {
+            BlockNumberOrTag::Number(0) | BlockNumberOrTag::Latest | BlockNumberOrTag::Pending => {
+                (best_block, true)
+            }
+            BlockNumberOrTag::Number(number) => (number.min(best_block + 1) - 1, false),
+            _ => return Err(invalid_params_rpc_err("unsupported block number")),
+        };
+
+        let (txs, receipts, exhausted) =
+            self.search_transactions(address, 0..=end, page_size, true).await?;
+
+        // blocks with pruned history can't be searched, so the oldest page is never reached
+        let history_pruned =
+            self.eth.provider().lowest_history_block().map_err(EthApiError::from)? > 0;
+
+        Ok(TransactionsWithReceipts {
+            txs,
+            receipts,
+            first_page,
+            last_page: exhausted && !history_pruned,
+        })
     }
 
     /// Handler for `ots_searchTransactionsAfter`
     async fn search_transactions_after(
         &self,
-        _address: Address,
-        _block_number: LenientBlockNumberOrTag,
-        _page_size: usize,
-    ) -> RpcResult<TransactionsWithReceipts> {
-        Err(internal_rpc_err("unimplemented"))
+        address: Address,
+        block_number: LenientBlockNumberOrTag,
+        page_size: usize,
+    ) -> RpcResult<TransactionsWithReceipts<RpcTransaction<Eth::NetworkTypes>>> {
+        if page_size == 0 {
+            return Err(invalid_params_rpc_err("page size must be greater than zero"))
+        }
+        let best_block = self.eth.provider().best_block_number().map_err(EthApiError::from)?;
+
+        // block number 0 means the search starts at genesis, which makes this the last page, unless
+        // the oldest blocks can't be searched because their history was pruned
+        let history_pruned =
+            self.eth.provider().lowest_history_block().map_err(EthApiError::from)? > 0;
+        let (start, last_page) = match block_number.into_inner() {
+            BlockNumberOrTag::Number(0) | BlockNumberOrTag::Earliest => (0, !history_pruned),
+            BlockNumberOrTag::Number(number) => match number.checked_add(1) {
+                Some(start) => (start, false),
+                // there are no blocks after the highest possible block number
+                None => {
+                    return Ok(TransactionsWithReceipts {
+                        txs: Vec::new(),
+                        receipts: Vec::new(),
+                        first_page: true,
+                        last_page: false,
+                    })
+                }
+            },
+            _ => return Err(invalid_params_rpc_err("unsupported block number")),
+        };
+
+        let (txs, receipts, exhausted) =
+            self.search_transactions(address, start..=best_block, page_size, false).await?;
+
+        Ok(TransactionsWithReceipts { txs, receipts, first_page: exhausted, last_page })
     }
 
     /// Handler for `ots_getTransactionBySenderAndNonce`
@@ -382,3 +579,35 @@ where
         Ok(found)
     }
 }
+
+/// Converts a receipt into an [`OtsTransactionReceipt`], dropping the logs and the bloom filter.
+fn ots_receipt<R: ReceiptResponse>(
+    receipt: &R,
+    tx_ty: u8,
+    timestamp: Option<u64>,
+) -> OtsTransactionReceipt {
+    let inner = OtsReceipt {
+        status: receipt.status(),
+        cumulative_gas_used: receipt.cumulative_gas_used(),
+        logs: None,
+        logs_bloom: None,
+        r#type: tx_ty,
+    };
+
+    let receipt = TransactionReceipt {
+        inner,
+        transaction_hash: receipt.transaction_hash(),
+        transaction_index: receipt.transaction_index(),
+        block_hash: receipt.block_hash(),
+        block_number: receipt.block_number(),
+        gas_used: receipt.gas_used(),
+        effective_gas_price: receipt.effective_gas_price(),
+        blob_gas_used: receipt.blob_gas_used(),
+        blob_gas_price: receipt.blob_gas_price(),
+        from: receipt.from(),
+        to: receipt.to(),
+        contract_address: receipt.contract_address(),
+    };
+
+    OtsTransactionReceipt { receipt, timestamp }
+}
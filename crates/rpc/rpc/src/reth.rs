@@ -1,7 +1,11 @@
-use std::{collections::HashMap, future::Future, sync::Arc};
+use std::{
+    collections::{BTreeMap, HashMap},
+    future::Future,
+    sync::Arc,
+};
 
 use alloy_eips::BlockId;
-use alloy_primitives::{Address, U256};
+use alloy_primitives::{Address, BlockNumber, U256};
 use async_trait::async_trait;
 use futures::StreamExt;
 use jsonrpsee::{core::RpcResult, PendingSubscriptionSink, SubscriptionMessage, SubscriptionSink};
@@ -9,13 +13,23 @@ use jsonrpsee_types::ErrorObject;
 use reth_chain_state::{CanonStateNotificationStream, CanonStateSubscriptions};
 use reth_errors::RethResult;
 use reth_primitives_traits::NodePrimitives;
-use reth_rpc_api::RethApiServer;
+use reth_prune_types::{PruneMode, PruneSegment};
+use reth_rpc_api::{
+    LowestAvailableBlocks, PruneCheckpointInfo, PruneStatus, RethApiServer, StaticFileBlockRange,
+};
 use reth_rpc_eth_types::{EthApiError, EthResult};
-use reth_rpc_server_types::result::internal_rpc_err;
-use reth_storage_api::{BlockReaderIdExt, ChangeSetReader, StateProviderFactory};
+use reth_rpc_server_types::result::{internal_rpc_err, invalid_params_rpc_err};
+use reth_static_file_types::StaticFileSegment;
+use reth_storage_api::{
+    BlockReaderIdExt, ChangeSetReader, DiskUsageReader, PruneCheckpointReader,
+    StageCheckpointReader, StateProviderFactory, StaticFileRangeReader,
+};
 use reth_tasks::TaskSpawner;
+use reth_tracing::LogOutput;
 use tokio::sync::oneshot;
 
+use crate::debug::log_filter_handle;
+
 /// `reth` API implementation.
 ///
 /// This type provides the functionality for handling `reth` prototype RPC requests.
@@ -40,7 +54,14 @@ impl<Provider> RethApi<Provider> {
 
 impl<Provider> RethApi<Provider>
 where
-    Provider: BlockReaderIdExt + ChangeSetReader + StateProviderFactory + 'static,
+    Provider: BlockReaderIdExt
+        + ChangeSetReader
+        + StateProviderFactory
+        + PruneCheckpointReader
+        + StageCheckpointReader
+        + StaticFileRangeReader
+        + DiskUsageReader
+        + 'static,
 {
     /// Executes the future on a new blocking task.
     async fn on_blocking_task<C, F, R>(&self, c: C) -> EthResult<R>
@@ -88,6 +109,138 @@ where
         )?;
         Ok(hash_map)
     }
+
+    /// Returns the prune checkpoints of all pruned segments.
+    pub async fn prune_checkpoints(
+        &self,
+    ) -> EthResult<BTreeMap<PruneSegment, PruneCheckpointInfo>> {
+        self.on_blocking_task(|this| async move {
+            Ok(this
+                .provider()
+                .get_prune_checkpoints()?
+                .into_iter()
+                .map(|(segment, checkpoint)| {
+                    let info = PruneCheckpointInfo {
+                        block_number: checkpoint.block_number,
+                        tx_number: checkpoint.tx_number,
+                        prune_mode: checkpoint.prune_mode,
+                    };
+                    (segment, info)
+                })
+                .collect())
+        })
+        .await
+    }
+
+    /// Returns the distances the segments are currently pruned with and the disk usage of the
+    /// node.
+    ///
+    /// The distances are taken from the prune checkpoints, which record the distance chosen to
+    /// fit the disk budget.
+    pub async fn prune_status(&self) -> EthResult<PruneStatus> {
+        self.on_blocking_task(|this| async move {
+            let disk_usage = this.provider().disk_usage()?;
+            let distances = this
+                .provider()
+                .get_prune_checkpoints()?
+                .into_iter()
+                .filter_map(|(segment, checkpoint)| match checkpoint.prune_mode {
+                    PruneMode::Distance(distance) => Some((segment, distance)),
+                    _ => None,
+                })
+                .collect();
+            Ok(PruneStatus {
+                database_size: disk_usage.database,
+                static_files_size: disk_usage.static_files,
+                distances,
+            })
+        })
+        .await
+    }
+
+    /// Returns the lowest blocks for which receipts, history, state and bodies are available.
+    pub async fn lowest_available_blocks(&self) -> EthResult<LowestAvailableBlocks> {
+        self.on_blocking_task(|this| async move { this.try_lowest_available_blocks() }).await
+    }
+
+    fn try_lowest_available_blocks(&self) -> EthResult<LowestAvailableBlocks> {
+        let pruned_block = |segment| -> EthResult<Option<BlockNumber>> {
+            Ok(self
+                .provider()
+                .get_prune_checkpoint(segment)?
+                .and_then(|checkpoint| checkpoint.block_number))
+        };
+
+        // Contract logs pruning keeps only the receipts of the configured addresses, so all
+        // receipts of a block are only available after both receipts checkpoints.
+        let receipts = [PruneSegment::Receipts, PruneSegment::ContractLogs]
+            .into_iter()
+            .map(pruned_block)
+            .collect::<EthResult<Vec<_>>>()?
+            .into_iter()
+            .flatten()
+            .max()
+            .map_or(0, |block| block + 1)
+            .max(
+                self.provider()
+                    .static_file_block_range(StaticFileSegment::Receipts)?
+                    .map_or(0, |range| *range.start()),
+            );
+
+        // Reverting the state to a block requires the changesets of all blocks after it, so the
+        // state is available one block earlier than the history.
+        let history_pruned =
+            [PruneSegment::AccountHistory, PruneSegment::StorageHistory, PruneSegment::ChangeSets]
+                .into_iter()
+                .map(pruned_block)
+                .collect::<EthResult<Vec<_>>>()?
+                .into_iter()
+                .flatten()
+                .max();
+        let history = history_pruned.map_or(0, |block| block + 1);
+        // Bytecodes pruning keeps only the code of the state after its checkpoint.
+        let state = history_pruned.max(pruned_block(PruneSegment::Bytecodes)?).unwrap_or_default();
+
+        let bodies = pruned_block(PruneSegment::Transactions)?.map_or(0, |block| block + 1).max(
+            self.provider()
+                .static_file_block_range(StaticFileSegment::Transactions)?
+                .map_or(0, |range| *range.start()),
+        );
+
+        Ok(LowestAvailableBlocks { receipts, history, state, bodies })
+    }
+
+    /// Returns the block numbers that the pipeline stages have synced to.
+    pub async fn stage_checkpoints(&self) -> EthResult<BTreeMap<String, BlockNumber>> {
+        self.on_blocking_task(|this| async move {
+            Ok(this
+                .provider()
+                .get_all_checkpoints()?
+                .into_iter()
+                .map(|(stage, checkpoint)| (stage, checkpoint.block_number))
+                .collect())
+        })
+        .await
+    }
+
+    /// Returns the ranges of blocks stored in the static files of each segment.
+    pub async fn static_file_ranges(
+        &self,
+    ) -> EthResult<BTreeMap<StaticFileSegment, StaticFileBlockRange>> {
+        self.on_blocking_task(|this| async move {
+            let mut ranges = BTreeMap::new();
+            for segment in StaticFileSegment::iter() {
+                if let Some(range) = this.provider().static_file_block_range(segment)? {
+                    ranges.insert(
+                        segment,
+                        StaticFileBlockRange { start: *range.start(), end: *range.end() },
+                    );
+                }
+            }
+            Ok(ranges)
+        })
+        .await
+    }
 }
 
 #[async_trait]
@@ -96,6 +249,10 @@ where
     Provider: BlockReaderIdExt
         + ChangeSetReader
         + StateProviderFactory
+        + PruneCheckpointReader
+        + StageCheckpointReader
+        + StaticFileRangeReader
+        + DiskUsageReader
         + CanonStateSubscriptions
         + 'static,
 {
@@ -107,6 +264,46 @@ where
         Ok(Self::balance_changes_in_block(self, block_id).await?)
     }
 
+    /// Handler for `reth_getPruneCheckpoints`
+    async fn reth_get_prune_checkpoints(
+        &self,
+    ) -> RpcResult<BTreeMap<PruneSegment, PruneCheckpointInfo>> {
+        Ok(Self::prune_checkpoints(self).await?)
+    }
+
+    /// Handler for `reth_getPruneStatus`
+    async fn reth_get_prune_status(&self) -> RpcResult<PruneStatus> {
+        Ok(Self::prune_status(self).await?)
+    }
+
+    /// Handler for `reth_getLowestAvailableBlocks`
+    async fn reth_get_lowest_available_blocks(&self) -> RpcResult<LowestAvailableBlocks> {
+        Ok(Self::lowest_available_blocks(self).await?)
+    }
+
+    /// Handler for `reth_getStageCheckpoints`
+    async fn reth_get_stage_checkpoints(&self) -> RpcResult<BTreeMap<String, BlockNumber>> {
+        Ok(Self::stage_checkpoints(self).await?)
+    }
+
+    /// Handler for `reth_getStaticFileRanges`
+    async fn reth_get_static_file_ranges(
+        &self,
+    ) -> RpcResult<BTreeMap<StaticFileSegment, StaticFileBlockRange>> {
+        Ok(Self::static_file_ranges(self).await?)
+    }
+
+    /// Handler for `reth_setLogFilter`
+    async fn reth_set_log_filter(&self, filter: String, output: Option<String>) -> RpcResult<()> {
+        let output = output
+            .map(|output| output.parse::<LogOutput>())
+            .transpose()
+            .map_err(|err| invalid_params_rpc_err(err.to_string()))?;
+        log_filter_handle()?
+            .set_filter(output, &filter)
+            .map_err(|err| invalid_params_rpc_err(err.to_string()))
+    }
+
     /// Handler for `reth_subscribeChainNotifications`
     async fn reth_subscribe_chain_notifications(
         &self,
@@ -41,7 +41,6 @@ use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use std::{collections::HashSet, sync::Arc};
 use tokio::sync::{oneshot, RwLock};
-use tracing::warn;
 
 /// The type that implements the `validation` rpc namespace trait
 #[derive(Clone, Debug, derive_more::Deref)]
@@ -385,6 +384,46 @@ where
         Ok(versioned_hashes)
     }
 
+    /// Core logic for validating the builder submission v1
+    async fn validate_builder_submission_v1(
+        &self,
+        request: BuilderBlockValidationRequest,
+    ) -> Result<(), ValidationApiError> {
+        // Bellatrix submissions carry neither withdrawals nor a parent beacon block root, the
+        // payload validator rejects them once Shanghai is active.
+        let block = self.payload_validator.ensure_well_formed_payload(ExecutionData {
+            payload: ExecutionPayload::V1(request.request.execution_payload),
+            sidecar: ExecutionPayloadSidecar::none(),
+        })?;
+
+        self.validate_message_against_block(
+            block,
+            request.request.message,
+            request.registered_gas_limit,
+        )
+        .await
+    }
+
+    /// Core logic for validating the builder submission v2
+    async fn validate_builder_submission_v2(
+        &self,
+        request: BuilderBlockValidationRequestV2,
+    ) -> Result<(), ValidationApiError> {
+        // Capella submissions carry withdrawals but no parent beacon block root, the payload
+        // validator rejects them once Cancun is active.
+        let block = self.payload_validator.ensure_well_formed_payload(ExecutionData {
+            payload: ExecutionPayload::V2(request.request.execution_payload),
+            sidecar: ExecutionPayloadSidecar::none(),
+        })?;
+
+        self.validate_message_against_block(
+            block,
+            request.request.message,
+            request.registered_gas_limit,
+        )
+        .await
+    }
+
     /// Core logic for validating the builder submission v3
     async fn validate_builder_submission_v3(
         &self,
@@ -474,20 +513,40 @@ where
         + 'static,
     E: ConfigureEvm + 'static,
 {
+    /// Validates a block submitted to the relay
     async fn validate_builder_submission_v1(
         &self,
-        _request: BuilderBlockValidationRequest,
+        request: BuilderBlockValidationRequest,
     ) -> RpcResult<()> {
-        warn!(target: "rpc::flashbots", "Method `flashbots_validateBuilderSubmissionV1` is not supported");
-        Err(internal_rpc_err("unimplemented"))
+        let this = self.clone();
+        let (tx, rx) = oneshot::channel();
+
+        self.task_spawner.spawn_blocking(Box::pin(async move {
+            let result = Self::validate_builder_submission_v1(&this, request)
+                .await
+                .map_err(ErrorObject::from);
+            let _ = tx.send(result);
+        }));
+
+        rx.await.map_err(|_| internal_rpc_err("Internal blocking task error"))?
     }
 
+    /// Validates a block submitted to the relay
     async fn validate_builder_submission_v2(
         &self,
-        _request: BuilderBlockValidationRequestV2,
+        request: BuilderBlockValidationRequestV2,
     ) -> RpcResult<()> {
-        warn!(target: "rpc::flashbots", "Method `flashbots_validateBuilderSubmissionV2` is not supported");
-        Err(internal_rpc_err("unimplemented"))
+        let this = self.clone();
+        let (tx, rx) = oneshot::channel();
+
+        self.task_spawner.spawn_blocking(Box::pin(async move {
+            let result = Self::validate_builder_submission_v2(&this, request)
+                .await
+                .map_err(ErrorObject::from);
+            let _ = tx.send(result);
+        }));
+
+        rx.await.map_err(|_| internal_rpc_err("Internal blocking task error"))?
     }
 
     /// Validates a block submitted to the relay
@@ -6,6 +6,9 @@
 //! It is also possible to run parts of reth standalone given the required data is present in
 //! the environment, such as [`ExecutionStages`] or [`HashingStages`].
 //!
+//! [`SnapSyncStages`] extends the default stages to download the state of a recent block over
+//! the `snap` protocol instead of executing all blocks from genesis.
+//!
 //!
 //! # Examples
 //!
@@ -40,8 +43,8 @@ use crate::{
     stages::{
         AccountHashingStage, BodyStage, EraImportSource, EraStage, ExecutionStage, FinishStage,
         HeaderStage, IndexAccountHistoryStage, IndexStorageHistoryStage, MerkleStage,
-        PruneSenderRecoveryStage, PruneStage, SenderRecoveryStage, StorageHashingStage,
-        TransactionLookupStage,
+        PruneSenderRecoveryStage, PruneStage, S3Stage, SenderRecoveryStage, SnapSyncStage,
+        StorageHashingStage, TransactionLookupStage,
     },
     StageSet, StageSetBuilder,
 };
@@ -49,11 +52,14 @@ use alloy_primitives::B256;
 use reth_config::config::StageConfig;
 use reth_consensus::{ConsensusError, FullConsensus};
 use reth_evm::ConfigureEvm;
-use reth_network_p2p::{bodies::downloader::BodyDownloader, headers::downloader::HeaderDownloader};
+use reth_network_p2p::{
+    bodies::downloader::BodyDownloader, headers::downloader::HeaderDownloader,
+    snap::client::SnapClient,
+};
 use reth_primitives_traits::{Block, NodePrimitives};
 use reth_provider::HeaderSyncGapProvider;
 use reth_prune_types::PruneModes;
-use reth_stages_api::Stage;
+use reth_stages_api::{Stage, StageId};
 use std::{ops::Not, sync::Arc};
 use tokio::sync::watch;
 
@@ -66,6 +72,8 @@ use tokio::sync::watch;
 /// - [`FinishStage`]
 ///
 /// This expands to the following series of stages:
+/// - [`EraStage`]
+/// - [`S3Stage`]
 /// - [`HeaderStage`]
 /// - [`BodyStage`]
 /// - [`SenderRecoveryStage`]
@@ -179,6 +187,53 @@ where
     }
 }
 
+/// A set containing all stages to run a snap syncing instance of reth.
+///
+/// These are the [`DefaultStages`] with a [`SnapSyncStage`] before the [`ExecutionStage`], which
+/// downloads the state of a recent pivot block on a node that hasn't executed any blocks yet.
+/// The execution and all following stages then resume from the pivot.
+#[derive(Debug)]
+pub struct SnapSyncStages<Provider, H, B, E, C>
+where
+    H: HeaderDownloader,
+    B: BodyDownloader,
+    E: ConfigureEvm,
+{
+    /// The default stages
+    default: DefaultStages<Provider, H, B, E>,
+    /// The snap sync stage
+    snap_sync: SnapSyncStage<C>,
+}
+
+impl<Provider, H, B, E, C> SnapSyncStages<Provider, H, B, E, C>
+where
+    H: HeaderDownloader,
+    B: BodyDownloader,
+    E: ConfigureEvm,
+{
+    /// Create a new set of snap sync stages from the default stages and the snap sync stage.
+    pub const fn new(
+        default: DefaultStages<Provider, H, B, E>,
+        snap_sync: SnapSyncStage<C>,
+    ) -> Self {
+        Self { default, snap_sync }
+    }
+}
+
+impl<P, H, B, E, C, Provider> StageSet<Provider> for SnapSyncStages<P, H, B, E, C>
+where
+    H: HeaderDownloader,
+    B: BodyDownloader,
+    E: ConfigureEvm,
+    C: SnapClient,
+    DefaultStages<P, H, B, E>: StageSet<Provider>,
+    SnapSyncStage<C>: Stage<Provider> + 'static,
+{
+    fn builder(self) -> StageSetBuilder<Provider> {
+        self.default.builder().add_before(self.snap_sync, StageId::Execution)
+    }
+}
+
 /// A set containing all stages that require network access by default.
 ///
 /// These stages *can* be run without network access if the specified downloaders are
@@ -267,10 +322,12 @@ where
     BodyStage<B>: Stage<Provider>,
     EraStage<<B::Block as Block>::Header, <B::Block as Block>::Body, EraImportSource>:
         Stage<Provider>,
+    S3Stage: Stage<Provider>,
 {
     fn builder(self) -> StageSetBuilder<Provider> {
         StageSetBuilder::default()
             .add_stage(EraStage::new(self.era_import_source, self.stages_config.etl.clone()))
+            .add_stage(S3Stage::new(self.stages_config.s3.clone(), self.stages_config.etl.clone()))
             .add_stage(HeaderStage::new(
                 self.provider,
                 self.header_downloader,
@@ -4,7 +4,7 @@ use futures_util::{Stream, StreamExt};
 use reqwest::{Client, Url};
 use reth_config::config::EtlConfig;
 use reth_db_api::{table::Value, transaction::DbTxMut};
-use reth_era::era1_file::Era1Reader;
+use reth_era::{era1_file::Era1Reader, era_file::EraReader};
 use reth_era_downloader::{read_dir, EraClient, EraMeta, EraStream, EraStreamConfig};
 use reth_era_utils as era;
 use reth_etl::Collector;
@@ -33,6 +33,10 @@ type ThreadSafeEraStream<Header, Body> =
 ///
 /// Imports block headers and bodies from genesis up to the last pre-merge block. Receipts are
 /// generated by execution. Execution is not done in this stage.
+///
+/// When importing from a local directory, post-merge blocks are imported from the execution
+/// payloads of [ERA](https://github.com/eth-clients/e2store-format-specs/blob/main/formats/era.md)
+/// files in the directory.
 pub struct EraStage<Header, Body, StreamFactory> {
     /// The `source` creates `stream`.
     source: Option<StreamFactory>,
@@ -87,9 +91,11 @@ impl EraImportSource {
         Ok(Box::new(Box::pin(stream.map(|meta| {
             meta.and_then(|meta| {
                 let file = reth_fs_util::open(meta.path())?;
-                let reader = Era1Reader::new(file);
-                let iter = reader.iter();
-                let iter = iter.map(era::decode);
+                let iter: Item<Header, Body> = if era::is_era_file(meta.path()) {
+                    Box::new(EraReader::new(file).iter().filter_map(era::decode_beacon_block))
+                } else {
+                    Box::new(Era1Reader::new(file).iter().map(era::decode))
+                };
                 let iter = iter.chain(
                     iter::once_with(move || match meta.mark_as_processed() {
                         Ok(..) => None,
@@ -22,6 +22,8 @@ use reth_stages_api::{
     UnwindInput, UnwindOutput,
 };
 use reth_static_file_types::StaticFileSegment;
+use reth_trie::HashedPostState;
+use reth_trie_db::{DatabaseHashedPostState, StateCommitment};
 use std::{
     cmp::Ordering,
     ops::RangeInclusive,
@@ -31,7 +33,7 @@ use std::{
 };
 use tracing::*;
 
-use super::missing_static_data_error;
+use super::{missing_static_data_error, s3::imported_receipts_block};
 
 /// The execution stage executes all transactions and
 /// update history indexes.
@@ -194,6 +196,9 @@ where
             return Ok(())
         }
 
+        // Receipts that were imported ahead of execution by the S3 stage are kept.
+        let checkpoint = checkpoint.max(imported_receipts_block(provider.tx_ref())?.unwrap_or(0));
+
         // Get next expected receipt number
         let next_receipt_num =
             provider.block_body_indices(checkpoint)?.map(|b| b.next_tx_num()).unwrap_or(0);
@@ -454,6 +459,15 @@ where
         // write output
         provider.write_state(&state, OriginalValuesKnown::Yes, StorageLocation::StaticFiles)?;
 
+        // A snap synced node can't rebuild the hashed state from the plain state, so the hashed
+        // state is updated together with the plain state.
+        if provider.is_snap_synced()? {
+            let hashed_state = HashedPostState::from_bundle_state::<
+                <Provider::StateCommitment as StateCommitment>::KeyHasher,
+            >(state.bundle.state());
+            provider.write_hashed_state(&hashed_state.into_sorted())?;
+        }
+
         let db_write_duration = time.elapsed();
         debug!(
             target: "sync::stages::execution",
@@ -501,6 +515,15 @@ where
 
         self.ensure_consistency(provider, input.checkpoint.block_number, Some(unwind_to))?;
 
+        // The hashed state of a snap synced node is updated by the execution, so it's reverted
+        // here as well, before the changesets are removed.
+        if provider.is_snap_synced()? {
+            let revert_state = HashedPostState::from_reverts::<
+                <Provider::StateCommitment as StateCommitment>::KeyHasher,
+            >(provider.tx_ref(), unwind_to + 1)?;
+            provider.write_hashed_state(&revert_state.into_sorted())?;
+        }
+
         // Unwind account and storage changesets, as well as receipts.
         //
         // This also updates `PlainStorageState` and `PlainAccountState`.
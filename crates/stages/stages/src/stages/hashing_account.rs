@@ -153,7 +153,12 @@ where
         // account otherwise take changesets aggregate the sets and apply hashing to
         // AccountHashing table. Also, if we start from genesis, we need to hash from scratch, as
         // genesis accounts are not in changeset.
-        if to_block - from_block > self.clean_threshold || from_block == 1 {
+        //
+        // A snap synced node doesn't have the full plain state, but its hashed state is already
+        // kept up to date by the execution, so it's never rebuilt from scratch.
+        if (to_block - from_block > self.clean_threshold || from_block == 1) &&
+            !provider.is_snap_synced()?
+        {
             let tx = provider.tx_ref();
 
             // clear table, load all accounts and hash it
@@ -16,6 +16,9 @@ pub enum DownloaderError {
     // Empty content length returned from the server.
     #[error("metadata got an empty content length from server")]
     EmptyContentLength,
+    /// File required by a block range is missing from the file list of the server.
+    #[error("file {0} is missing from the file list")]
+    MissingFile(String),
     /// Reqwest error
     #[error(transparent)]
     FsPath(#[from] FsPathError),
@@ -28,4 +31,7 @@ pub enum DownloaderError {
     /// Bincode error
     #[error(transparent)]
     Bincode(#[from] bincode::Error),
+    /// Json error
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
 }
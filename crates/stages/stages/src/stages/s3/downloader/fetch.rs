@@ -21,6 +21,8 @@ use tracing::{debug, error, info};
 ///
 /// ## Details
 ///
+/// 0) If the file already exists in `target_dir` and matches `file_hash`, nothing is downloaded.
+///    Otherwise, it is removed and downloaded again.
 /// 1) A [`Metadata`] file is created or opened in `{target_dir}/download/{filename}.metadata`. It
 ///    tracks the download progress including total file size, downloaded bytes, chunk sizes, and
 ///    ranges that still need downloading. Allows for resumability.
@@ -30,7 +32,8 @@ use tracing::{debug, error, info};
 /// 4) `Orchestrator` manages workers, distributes chunk ranges, and ensures the download progresses
 ///    efficiently by dynamically assigning tasks to workers as they become available.
 /// 5) Once the file is downloaded:
-///     * If `file_hash` is `Some`, verifies its blake3 hash.
+///     * If `file_hash` is `Some`, verifies its blake3 hash. On mismatch, both the file and its
+///       metadata are deleted so the next attempt starts from scratch.
 ///     * Deletes the metadata file
 ///     * Moves downloaded file to target directory.
 pub async fn fetch(
@@ -40,21 +43,31 @@ pub async fn fetch(
     mut concurrent: u64,
     file_hash: Option<B256>,
 ) -> Result<(), DownloaderError> {
+    // The file might have been downloaded by a previous run.
+    let file_directory = target_dir.join(filename);
+    if file_directory.exists() {
+        match file_hash.map(|file_hash| check_file_hash(&file_directory, &file_hash)) {
+            None | Some(Ok(())) => return Ok(()),
+            Some(Err(err)) => {
+                info!(target: "sync::stages::s3::downloader", ?filename, %err, "Removing existing file.");
+                reth_fs_util::remove_file(&file_directory)?;
+            }
+        }
+    }
+
     // Create a temporary directory to download files to, before moving them to target_dir.
     let download_dir = target_dir.join("download");
     reth_fs_util::create_dir_all(&download_dir)?;
 
     let data_file = download_dir.join(filename);
     let mut metadata = metadata(&data_file, url).await?;
-    if metadata.is_done() {
-        return Ok(())
-    }
 
-    // Ensure the file is preallocated so we can download it concurrently
+    // Ensure the file is preallocated so we can download it concurrently. It must not be truncated
+    // since it may contain chunks downloaded by a previous run.
     {
         let file = OpenOptions::new()
             .create(true)
-            .truncate(true)
+            .truncate(false)
             .read(true)
             .write(true)
             .open(&data_file)?;
@@ -114,14 +127,17 @@ pub async fn fetch(
 
     if let Some(file_hash) = file_hash {
         info!(target: "sync::stages::s3::downloader", ?filename, "Checking file integrity.");
-        check_file_hash(&data_file, &file_hash)?;
+        if let Err(err) = check_file_hash(&data_file, &file_hash) {
+            metadata.delete()?;
+            reth_fs_util::remove_file(&data_file)?;
+            return Err(err)
+        }
     }
 
     // No longer need the metadata file.
     metadata.delete()?;
 
     // Move downloaded file to desired directory.
-    let file_directory = target_dir.join(filename);
     reth_fs_util::rename(data_file, &file_directory)?;
     info!(target: "sync::stages::s3::downloader", ?file_directory, "Moved file from temporary to target directory.");
 
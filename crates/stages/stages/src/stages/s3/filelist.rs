@@ -1,21 +1,80 @@
+use super::downloader::DownloaderError;
 use alloy_primitives::B256;
+use reth_static_file_types::{SegmentRangeInclusive, StaticFileSegment};
+use std::collections::BTreeMap;
 
-/// File list to be downloaded with their hashes.
-pub(crate) static DOWNLOAD_FILE_LIST: [[(&str, B256); 3]; 2] = [
-    [
-        ("static_file_transactions_0_499999", B256::ZERO),
-        ("static_file_transactions_0_499999.off", B256::ZERO),
-        ("static_file_transactions_0_499999.conf", B256::ZERO),
-        // ("static_file_blockmeta_0_499999", B256::ZERO),
-        // ("static_file_blockmeta_0_499999.off", B256::ZERO),
-        // ("static_file_blockmeta_0_499999.conf", B256::ZERO),
-    ],
-    [
-        ("static_file_transactions_500000_999999", B256::ZERO),
-        ("static_file_transactions_500000_999999.off", B256::ZERO),
-        ("static_file_transactions_500000_999999.conf", B256::ZERO),
-        // ("static_file_blockmeta_500000_999999", B256::ZERO),
-        // ("static_file_blockmeta_500000_999999.off", B256::ZERO),
-        // ("static_file_blockmeta_500000_999999.conf", B256::ZERO),
-    ],
+/// Name of the file served by the mirror which maps every static file it offers to its blake3
+/// hash.
+pub(crate) const FILE_LIST: &str = "files.json";
+
+/// Static file segments downloaded for every block range.
+const SEGMENTS: [StaticFileSegment; 4] = [
+    StaticFileSegment::Headers,
+    StaticFileSegment::Transactions,
+    StaticFileSegment::BlockMeta,
+    StaticFileSegment::Receipts,
 ];
+
+/// Extensions of the files that make up a static file, besides the data file itself.
+const EXTENSIONS: [&str; 2] = ["off", "conf"];
+
+/// All files of a block range that need to be downloaded, with their hashes.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct BlockRangeFiles {
+    /// Block range covered by the files.
+    pub(crate) block_range: SegmentRangeInclusive,
+    /// Files to be downloaded with their blake3 hashes.
+    pub(crate) files: Vec<(String, B256)>,
+}
+
+/// Downloads the file list from the mirror at `url`.
+pub(crate) async fn fetch_file_list(url: &str) -> Result<BTreeMap<String, B256>, DownloaderError> {
+    let response = reqwest::get(format!("{url}/{FILE_LIST}")).await?.error_for_status()?;
+    Ok(serde_json::from_slice(&response.bytes().await?)?)
+}
+
+/// Returns the files of every block range that ends after `from_block`, sorted by block range.
+///
+/// Static files do not support gaps, so only the block ranges that can be appended one after the
+/// other right after `from_block` are returned.
+pub(crate) fn block_range_files(
+    file_list: &BTreeMap<String, B256>,
+    from_block: u64,
+) -> Result<Vec<BlockRangeFiles>, DownloaderError> {
+    let mut block_ranges = file_list
+        .keys()
+        .filter_map(|filename| StaticFileSegment::parse_filename(filename))
+        .filter(|(segment, block_range)| segment.is_headers() && block_range.end() > from_block)
+        .map(|(_, block_range)| block_range)
+        .collect::<Vec<_>>();
+    block_ranges.sort_unstable_by_key(|block_range| block_range.start());
+
+    let mut next_block = from_block + 1;
+    let mut requests = Vec::new();
+    for block_range in block_ranges {
+        if block_range.start() > next_block {
+            break
+        }
+        next_block = block_range.end() + 1;
+
+        let mut files = Vec::with_capacity(SEGMENTS.len() * (EXTENSIONS.len() + 1));
+        for segment in SEGMENTS {
+            let data_file = segment.filename(&block_range);
+            let filenames = EXTENSIONS
+                .iter()
+                .map(|extension| format!("{data_file}.{extension}"))
+                .chain(std::iter::once(data_file.clone()));
+
+            for filename in filenames {
+                let hash = *file_list
+                    .get(&filename)
+                    .ok_or_else(|| DownloaderError::MissingFile(filename.clone()))?;
+                files.push((filename, hash));
+            }
+        }
+
+        requests.push(BlockRangeFiles { block_range, files });
+    }
+
+    Ok(requests)
+}
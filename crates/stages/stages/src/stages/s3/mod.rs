@@ -3,48 +3,169 @@ pub use downloader::{fetch, Metadata};
 use downloader::{DownloaderError, S3DownloaderResponse};
 
 mod filelist;
-use filelist::DOWNLOAD_FILE_LIST;
-
-use reth_db_api::transaction::DbTxMut;
+use filelist::{block_range_files, fetch_file_list, BlockRangeFiles};
+
+use alloy_consensus::BlockHeader;
+use alloy_primitives::{BlockHash, BlockNumber, Sealable, TxNumber};
+use alloy_rlp::Decodable;
+use reqwest::Url;
+use reth_codecs::Compact;
+use reth_config::config::{EtlConfig, S3Config};
+use reth_db::static_file::{BodyIndicesMask, OmmersMask, WithdrawalsMask};
+use reth_db_api::{
+    table::Value,
+    tables,
+    transaction::{DbTx, DbTxMut},
+};
+use reth_era_utils as era;
+use reth_etl::Collector;
+use reth_primitives_traits::NodePrimitives;
 use reth_provider::{
-    DBProvider, StageCheckpointReader, StageCheckpointWriter, StaticFileProviderFactory,
+    providers::StaticFileProvider, BlockHashReader, BlockWriter, DBProvider, HeaderProvider,
+    ReceiptProvider, StageCheckpointWriter, StaticFileProviderFactory, StaticFileWriter,
+    TransactionsProvider,
 };
 use reth_stages_api::{
     ExecInput, ExecOutput, Stage, StageCheckpoint, StageError, StageId, UnwindInput, UnwindOutput,
 };
-use reth_static_file_types::StaticFileSegment;
+use reth_static_file_types::{SegmentRangeInclusive, StaticFileSegment};
+use reth_storage_errors::{provider::ProviderResult, ProviderError};
 use std::{
-    path::PathBuf,
+    path::{Path, PathBuf},
     task::{ready, Context, Poll},
 };
-use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
-
-/// S3 `StageId`
-const S3_STAGE_ID: StageId = StageId::Other("S3");
+use tokio::sync::mpsc::{channel, Receiver, Sender};
+use tracing::{info, warn};
 
 /// The S3 stage.
-#[derive(Default, Debug)]
-#[non_exhaustive]
+///
+/// Downloads the headers, transactions, block meta and receipts static files of consecutive block
+/// ranges from an HTTP or S3 mirror, and imports them block by block. Execution is not done in
+/// this stage.
+///
+/// Receipts are imported into the receipts static files as long as they can be appended right
+/// after the local ones and receipts aren't pruned. The highest block with imported receipts is
+/// kept as the progress of the stage, see [`imported_receipts_block`]. The
+/// [`ExecutionStage`](crate::stages::ExecutionStage) keeps the receipts up to that block, instead
+/// of discarding them as leftovers of an interrupted run, and doesn't write them again.
+///
+/// The mirror is expected to serve a `files.json` file mapping every static file it offers to its
+/// blake3 hash. Downloads are resumed if they were interrupted, and every file is checked against
+/// its hash before being imported.
+///
+/// The stage does nothing unless a mirror URL is configured. Once it runs out of block ranges to
+/// import, the [`HeaderStage`](crate::stages::HeaderStage) takes over.
+#[derive(Debug)]
 pub struct S3Stage {
-    /// Static file directory.
-    static_file_directory: PathBuf,
     /// Remote server URL.
-    url: String,
+    url: Option<Url>,
+    /// Directory where block ranges are downloaded to before being imported.
+    folder: PathBuf,
     /// Maximum number of connections per download.
     max_concurrent_requests: u64,
+    /// A map of block hash to block height collected when importing headers and inserted into
+    /// database afterward.
+    hash_collector: Collector<BlockHash, BlockNumber>,
     /// Channel to receive the downloaded ranges from the fetch task.
-    fetch_rx: Option<UnboundedReceiver<Result<S3DownloaderResponse, DownloaderError>>>,
+    fetch_rx: Option<Receiver<Result<S3DownloaderResponse, DownloaderError>>>,
+    /// Downloaded block range which is ready to be imported.
+    block_range: Option<SegmentRangeInclusive>,
 }
 
-impl<Provider> Stage<Provider> for S3Stage
+impl S3Stage {
+    /// Creates a new [`S3Stage`].
+    ///
+    /// If no download folder is configured, a temporary directory is used.
+    pub fn new(config: S3Config, etl_config: EtlConfig) -> Self {
+        Self {
+            url: config.url,
+            folder: config.folder.unwrap_or_else(|| std::env::temp_dir().join("reth-s3")),
+            max_concurrent_requests: config.max_concurrent_requests,
+            hash_collector: Collector::new(etl_config.file_size, etl_config.dir),
+            fetch_rx: None,
+            block_range: None,
+        }
+    }
+
+    /// Returns the directory the files of `block_range` are downloaded to.
+    fn block_range_dir(folder: &Path, block_range: &SegmentRangeInclusive) -> PathBuf {
+        folder.join(format!("{}_{}", block_range.start(), block_range.end()))
+    }
+
+    /// Spawns a task that downloads every block range above `from_block` offered by the mirror.
+    ///
+    /// Every time a block range is ready with all the necessary files, it sends a
+    /// [`S3DownloaderResponse::AddedNewRange`] to the returned channel, followed by a
+    /// [`S3DownloaderResponse::Done`] once there are no more block ranges to download. The channel
+    /// is bounded, so the task doesn't download too far ahead of the ranges being imported.
+    fn spawn_fetch(
+        &self,
+        url: Url,
+        from_block: BlockNumber,
+    ) -> Receiver<Result<S3DownloaderResponse, DownloaderError>> {
+        let folder = self.folder.clone();
+        let max_concurrent_requests = self.max_concurrent_requests;
+
+        let (fetch_tx, fetch_rx) = channel(1);
+        tokio::spawn(async move {
+            let result = Self::fetch_block_ranges(
+                url.as_str().trim_end_matches('/'),
+                &folder,
+                max_concurrent_requests,
+                from_block,
+                &fetch_tx,
+            )
+            .await;
+
+            let _ = fetch_tx.send(result.map(|()| S3DownloaderResponse::Done)).await;
+        });
+
+        fetch_rx
+    }
+
+    /// Downloads the files of every block range above `from_block` into their own directory.
+    async fn fetch_block_ranges(
+        url: &str,
+        folder: &Path,
+        max_concurrent_requests: u64,
+        from_block: BlockNumber,
+        fetch_tx: &Sender<Result<S3DownloaderResponse, DownloaderError>>,
+    ) -> Result<(), DownloaderError> {
+        let file_list = fetch_file_list(url).await?;
+
+        for BlockRangeFiles { block_range, files } in block_range_files(&file_list, from_block)? {
+            let block_range_dir = Self::block_range_dir(folder, &block_range);
+            for (filename, file_hash) in files {
+                fetch(
+                    &filename,
+                    &block_range_dir,
+                    &format!("{url}/{filename}"),
+                    max_concurrent_requests,
+                    Some(file_hash),
+                )
+                .await?;
+            }
+
+            if fetch_tx.send(Ok(S3DownloaderResponse::AddedNewRange(block_range))).await.is_err() {
+                // The stage is no longer interested in the downloaded ranges.
+                return Ok(())
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<Provider, N> Stage<Provider> for S3Stage
 where
     Provider: DBProvider<Tx: DbTxMut>
-        + StaticFileProviderFactory
-        + StageCheckpointReader
+        + StaticFileProviderFactory<Primitives = N>
+        + BlockWriter<Block = N::Block>
         + StageCheckpointWriter,
+    N: NodePrimitives<BlockHeader: Value + Compact, SignedTx: Value, Receipt: Value + Compact>,
 {
     fn id(&self) -> StageId {
-        S3_STAGE_ID
+        StageId::S3
     }
 
     fn poll_execute_ready(
@@ -52,244 +173,522 @@ where
         cx: &mut Context<'_>,
         input: ExecInput,
     ) -> Poll<Result<(), StageError>> {
-        loop {
-            // We are currently fetching and may have downloaded ranges that we can process.
-            if let Some(rx) = &mut self.fetch_rx {
-                // Whether we have downloaded all the required files.
-                let mut is_done = false;
-
-                let response = match ready!(rx.poll_recv(cx)) {
-                    Some(Ok(response)) => {
-                        is_done = response.is_done();
-                        Ok(())
-                    }
-                    Some(Err(_)) => todo!(), // TODO: DownloaderError -> StageError
-                    None => Err(StageError::ChannelClosed),
-                };
+        if self.block_range.is_some() {
+            return Poll::Ready(Ok(()))
+        }
+        let Some(url) = self.url.clone() else { return Poll::Ready(Ok(())) };
 
-                if is_done {
-                    self.fetch_rx = None;
-                }
+        // Spawns the fetch task if it's not running already
+        if self.fetch_rx.is_none() {
+            self.fetch_rx = Some(self.spawn_fetch(url, input.checkpoint().block_number));
+        }
+        let Some(fetch_rx) = &mut self.fetch_rx else { return Poll::Ready(Ok(())) };
 
-                return Poll::Ready(response)
+        let result = match ready!(fetch_rx.poll_recv(cx)) {
+            Some(Ok(S3DownloaderResponse::AddedNewRange(block_range))) => {
+                self.block_range = Some(block_range);
+                return Poll::Ready(Ok(()))
             }
+            Some(Ok(S3DownloaderResponse::Done)) => Ok(()),
+            Some(Err(err)) => Err(StageError::Fatal(Box::new(err))),
+            None => Err(StageError::ChannelClosed),
+        };
+        self.fetch_rx = None;
 
-            // Spawns the downloader task if there are any missing files
-            if let Some(fetch_rx) = self.maybe_spawn_fetch(input) {
-                self.fetch_rx = Some(fetch_rx);
-
-                // Polls fetch_rx & registers waker
-                continue
-            }
+        Poll::Ready(result)
+    }
 
-            // No files to be downloaded
-            return Poll::Ready(Ok(()))
+    fn execute(&mut self, provider: &Provider, input: ExecInput) -> Result<ExecOutput, StageError> {
+        if self.url.is_none() {
+            return Ok(ExecOutput::done(StageCheckpoint::new(input.target())))
         }
-    }
 
-    fn execute(&mut self, provider: &Provider, input: ExecInput) -> Result<ExecOutput, StageError>
-    where
-        Provider: DBProvider<Tx: DbTxMut>
-            + StaticFileProviderFactory
-            + StageCheckpointReader
-            + StageCheckpointWriter,
-    {
-        // Re-initializes the provider to detect the new additions
-        provider.static_file_provider().initialize_index()?;
+        let static_file_provider = provider.static_file_provider();
+        let last_header_number = static_file_provider
+            .get_highest_static_file_block(StaticFileSegment::Headers)
+            .unwrap_or_default();
+
+        let Some(block_range) = self.block_range.take() else {
+            return Ok(ExecOutput::done(StageCheckpoint::new(
+                last_header_number.max(input.target()),
+            )))
+        };
+
+        let block_range_dir = Self::block_range_dir(&self.folder, &block_range);
+        if block_range.start() > last_header_number + 1 {
+            // Static files do not support gaps, so we can't import the remaining ranges.
+            warn!(target: "sync::stages::s3", %block_range, last_header_number, "Downloaded block range does not follow the local chain");
+            self.fetch_rx = None;
+            reth_fs_util::remove_dir_all(&block_range_dir)
+                .map_err(|e| StageError::Fatal(e.into()))?;
+            return Ok(ExecOutput::done(StageCheckpoint::new(
+                last_header_number.max(input.target()),
+            )))
+        }
 
-        // TODO logic for appending tx_block
+        // Receipts can only be appended right after the local ones, and are written to the database
+        // by execution if they are pruned.
+        let import_receipts = !provider.prune_modes_ref().has_receipts_pruning() &&
+            static_file_provider.get_highest_static_file_block(StaticFileSegment::Receipts) ==
+                Some(last_header_number);
+
+        info!(target: "sync::stages::s3", %block_range, import_receipts, "Importing block range");
+
+        // Find the latest total difficulty and hash
+        let mut td = static_file_provider
+            .header_td_by_number(last_header_number)?
+            .ok_or(ProviderError::TotalDifficultyNotFound(last_header_number))?;
+        let mut parent_hash = static_file_provider
+            .block_hash(last_header_number)?
+            .ok_or_else(|| ProviderError::HeaderNotFound(last_header_number.into()))?;
+
+        let height = {
+            let downloaded = StaticFileProvider::<N>::read_only(&block_range_dir, false)?;
+            let end = downloaded
+                .get_highest_static_file_block(StaticFileSegment::Headers)
+                .unwrap_or_default()
+                .min(block_range.end());
+
+            let blocks = (last_header_number + 1..=end).map(|number| {
+                let (header, body) = read_block(&downloaded, block_range, number)?;
+                if header.parent_hash() != parent_hash {
+                    eyre::bail!("block {number} does not extend the local chain")
+                }
+                parent_hash = header.hash_slow();
+                Ok((header, body))
+            });
+
+            let mut writer = static_file_provider.latest_writer(StaticFileSegment::Headers)?;
+            let height = era::process_iter(
+                blocks,
+                &mut writer,
+                provider,
+                &mut self.hash_collector,
+                &mut td,
+                last_header_number..=end,
+            )
+            .map_err(|e| StageError::Fatal(e.into()))?;
+
+            if import_receipts {
+                let mut writer = static_file_provider.latest_writer(StaticFileSegment::Receipts)?;
+                for number in last_header_number + 1..=height {
+                    let receipts = read_receipts(&downloaded, block_range, number)
+                        .map_err(|e| StageError::Fatal(e.into()))?;
+                    writer.increment_block(number)?;
+                    writer.append_receipts(receipts.into_iter().map(Ok))?;
+                }
+                provider
+                    .save_stage_checkpoint_progress(StageId::S3, height.to_be_bytes().to_vec())?;
+            }
 
-        // let (_, _to_block) = input.next_block_range().into_inner();
-        // let static_file_provider = provider.static_file_provider();
-        // let mut _tx_block_cursor =
-        // provider.tx_ref().cursor_write::<tables::TransactionBlocks>()?;
+            height
+        };
 
-        // tx_block_cursor.append(indice.last_tx_num(), &block_number)?;
+        if !self.hash_collector.is_empty() {
+            era::build_index(provider, &mut self.hash_collector)
+                .map_err(|e| StageError::Recoverable(e.into()))?;
+            self.hash_collector.clear();
+        }
 
-        // let checkpoint = StageCheckpoint { block_number: highest_block, stage_checkpoint: None };
-        // provider.save_stage_checkpoint(StageId::Bodies, checkpoint)?;
-        // provider.save_stage_checkpoint(S3_STAGE_ID, checkpoint)?;
+        era::save_stage_checkpoints(
+            &provider,
+            input.checkpoint().block_number,
+            height,
+            height,
+            height,
+        )?;
 
-        // // TODO: verify input.target according to s3 stage specifications
-        // let done = highest_block == to_block;
+        reth_fs_util::remove_dir_all(&block_range_dir).map_err(|e| StageError::Fatal(e.into()))?;
 
-        Ok(ExecOutput { checkpoint: StageCheckpoint::new(input.target()), done: true })
+        Ok(ExecOutput { checkpoint: StageCheckpoint::new(height), done: false })
     }
 
     fn unwind(
         &mut self,
-        _provider: &Provider,
+        provider: &Provider,
         input: UnwindInput,
     ) -> Result<UnwindOutput, StageError> {
-        // TODO
-        Ok(UnwindOutput { checkpoint: StageCheckpoint::new(input.unwind_to) })
+        // The receipts above the unwind target are removed by the execution stage.
+        if imported_receipts_block(provider.tx_ref())?.is_some_and(|block| block > input.unwind_to)
+        {
+            provider.save_stage_checkpoint_progress(
+                StageId::S3,
+                input.unwind_to.to_be_bytes().to_vec(),
+            )?;
+        }
+
+        Ok(UnwindOutput { checkpoint: input.checkpoint.with_block_number(input.unwind_to) })
     }
 }
 
-impl S3Stage {
-    /// It will only spawn a task to fetch files from the remote server, it there are any missing
-    /// static files.
-    ///
-    /// Every time a block range is ready with all the necessary files, it sends a
-    /// [`S3DownloaderResponse`] to `self.fetch_rx`. If it's the last requested block range, the
-    /// response will have `is_done` set to true.
-    fn maybe_spawn_fetch(
-        &self,
-        input: ExecInput,
-    ) -> Option<UnboundedReceiver<Result<S3DownloaderResponse, DownloaderError>>> {
-        let checkpoint = input.checkpoint();
-        // TODO: input target can only be certain numbers. eg. 499_999 , 999_999 etc.
-
-        // Create a list of all the missing files per block range that need to be downloaded.
-        let mut requests = vec![];
-        for block_range_files in &DOWNLOAD_FILE_LIST {
-            let (_, block_range) =
-                StaticFileSegment::parse_filename(block_range_files[0].0).expect("qed");
-
-            if block_range.end() <= checkpoint.block_number {
-                continue
-            }
-
-            let mut block_range_requests = vec![];
-            for (filename, file_hash) in block_range_files {
-                // If the file already exists, then we are resuming a previously interrupted stage
-                // run.
-                if self.static_file_directory.join(filename).exists() {
-                    // TODO: check hash if the file already exists
-                    continue
-                }
+/// Returns the highest block whose receipts were imported into the receipts static files by the
+/// [`S3Stage`], which is kept as the progress of the stage.
+pub(crate) fn imported_receipts_block(tx: &impl DbTx) -> ProviderResult<Option<BlockNumber>> {
+    Ok(tx
+        .get::<tables::StageCheckpointProgresses>(StageId::S3.to_string())?
+        .and_then(|progress| progress.try_into().ok())
+        .map(BlockNumber::from_be_bytes))
+}
 
-                block_range_requests.push((filename, file_hash));
-            }
+/// Reads the header and body of block `number` from the downloaded static files.
+///
+/// The body is assembled in its ethereum form and then decoded into the body type of the node.
+///
+/// The downloaded files are looked up by their block range, since it doesn't necessarily match the
+/// number of blocks per static file of the local node. Only one jar provider is held at a time, as
+/// the static file provider can't hand out a new one while another one is alive.
+fn read_block<N>(
+    provider: &StaticFileProvider<N>,
+    block_range: SegmentRangeInclusive,
+    number: BlockNumber,
+) -> eyre::Result<(N::BlockHeader, N::BlockBody)>
+where
+    N: NodePrimitives<BlockHeader: Value + Compact, SignedTx: Value>,
+{
+    let jar_provider = |segment| {
+        provider
+            .get_segment_provider(segment, || Some(block_range), None)?
+            .ok_or(ProviderError::MissingStaticFileBlock(segment, number))
+    };
 
-            requests.push((block_range, block_range_requests));
-        }
+    let header = jar_provider(StaticFileSegment::Headers)?
+        .header_by_number(number)?
+        .ok_or(ProviderError::MissingStaticFileBlock(StaticFileSegment::Headers, number))?;
+
+    let (body_indices, ommers, withdrawals) = {
+        let block_meta = jar_provider(StaticFileSegment::BlockMeta)?;
+        let mut cursor = block_meta.cursor()?;
+        let missing =
+            || ProviderError::MissingStaticFileBlock(StaticFileSegment::BlockMeta, number);
+        (
+            cursor.get_one::<BodyIndicesMask>(number.into())?.ok_or_else(missing)?,
+            cursor.get_one::<OmmersMask<N::BlockHeader>>(number.into())?.ok_or_else(missing)?,
+            cursor.get_one::<WithdrawalsMask>(number.into())?.ok_or_else(missing)?,
+        )
+    };
 
-        // Return None, if we have downloaded all the files that are required.
-        if requests.is_empty() {
-            return None
-        }
+    let transactions = if body_indices.is_empty() {
+        Vec::new()
+    } else {
+        jar_provider(StaticFileSegment::Transactions)?
+            .transactions_by_tx_range(body_indices.tx_num_range())?
+    };
 
-        let static_file_directory = self.static_file_directory.clone();
-        let url = self.url.clone();
-        let max_concurrent_requests = self.max_concurrent_requests;
+    let body = alloy_consensus::BlockBody {
+        transactions,
+        ommers: ommers.ommers,
+        withdrawals: withdrawals.withdrawals,
+    };
+    let body = N::BlockBody::decode(&mut alloy_rlp::encode(body).as_slice())?;
 
-        let (fetch_tx, fetch_rx) = unbounded_channel();
-        tokio::spawn(async move {
-            let mut requests_iter = requests.into_iter().peekable();
-
-            while let Some((_, file_requests)) = requests_iter.next() {
-                for (filename, file_hash) in file_requests {
-                    if let Err(err) = fetch(
-                        filename,
-                        &static_file_directory,
-                        &format!("{url}/{filename}"),
-                        max_concurrent_requests,
-                        Some(*file_hash),
-                    )
-                    .await
-                    {
-                        let _ = fetch_tx.send(Err(err));
-                        return
-                    }
-                }
+    Ok((header, body))
+}
 
-                let response = if requests_iter.peek().is_none() {
-                    S3DownloaderResponse::Done
-                } else {
-                    S3DownloaderResponse::AddedNewRange
-                };
+/// Reads the receipts of block `number` from the downloaded static files, together with their
+/// transaction numbers.
+fn read_receipts<N>(
+    provider: &StaticFileProvider<N>,
+    block_range: SegmentRangeInclusive,
+    number: BlockNumber,
+) -> eyre::Result<Vec<(TxNumber, N::Receipt)>>
+where
+    N: NodePrimitives<SignedTx: Value, Receipt: Value>,
+{
+    let jar_provider = |segment| {
+        provider
+            .get_segment_provider(segment, || Some(block_range), None)?
+            .ok_or(ProviderError::MissingStaticFileBlock(segment, number))
+    };
 
-                let _ = fetch_tx.send(Ok(response));
-            }
-        });
+    let body_indices = jar_provider(StaticFileSegment::BlockMeta)?
+        .cursor()?
+        .get_one::<BodyIndicesMask>(number.into())?
+        .ok_or(ProviderError::MissingStaticFileBlock(StaticFileSegment::BlockMeta, number))?;
+    if body_indices.is_empty() {
+        return Ok(Vec::new())
+    }
 
-        Some(fetch_rx)
+    let receipts = jar_provider(StaticFileSegment::Receipts)?
+        .receipts_by_tx_range(body_indices.tx_num_range())?;
+    if receipts.len() as u64 != body_indices.tx_count {
+        eyre::bail!("missing receipts of block {number}")
     }
+
+    Ok(body_indices.tx_num_range().zip(receipts).collect())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::test_utils::{
-        ExecuteStageTestRunner, StageTestRunner, TestRunnerError, TestStageDB,
-        UnwindStageTestRunner,
+    use crate::test_utils::{StorageKind, TestStageDB};
+    use alloy_primitives::{B256, U256};
+    use reth_db::{test_utils::TempDatabase, Database, DatabaseEnv};
+    use reth_db_api::models::{
+        StaticFileBlockWithdrawals, StoredBlockBodyIndices, StoredBlockOmmers,
+    };
+    use reth_ethereum_primitives::EthPrimitives;
+    use reth_provider::{
+        test_utils::MockNodeTypesWithDB, writer::UnifiedStorageWriter, BlockReader,
+        DatabaseProvider, DatabaseProviderFactory, StageCheckpointReader,
+    };
+    use reth_stages_api::StageExt;
+    use reth_testing_utils::generators::{
+        self, random_block_range, random_receipt, BlockRangeParams,
     };
-    use reth_primitives_traits::SealedHeader;
-    use reth_testing_utils::{
-        generators,
-        generators::{random_header, random_header_range},
+    use std::{collections::HashMap, sync::Arc};
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
     };
 
-    // stage_test_suite_ext!(S3TestRunner, s3);
+    type TestProvider =
+        DatabaseProvider<<TempDatabase<DatabaseEnv> as Database>::TXMut, MockNodeTypesWithDB>;
 
-    #[derive(Default)]
-    #[allow(unused)]
-    struct S3TestRunner {
-        db: TestStageDB,
-    }
+    /// Serves `files` over HTTP, answering `HEAD` and ranged `GET` requests.
+    async fn serve(files: HashMap<String, Vec<u8>>) -> Url {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap()).parse().unwrap();
+        let files = Arc::new(files);
 
-    impl StageTestRunner for S3TestRunner {
-        type S = S3Stage;
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let files = files.clone();
+                tokio::spawn(async move {
+                    let mut request = Vec::new();
+                    let mut buf = [0; 1024];
+                    while !request.ends_with(b"\r\n\r\n") {
+                        let read = stream.read(&mut buf).await.unwrap();
+                        if read == 0 {
+                            return
+                        }
+                        request.extend_from_slice(&buf[..read]);
+                    }
 
-        fn db(&self) -> &TestStageDB {
-            &self.db
-        }
+                    let request = String::from_utf8(request).unwrap();
+                    let mut lines = request.lines();
+                    let mut request_line = lines.next().unwrap().split(' ');
+                    let (method, path) =
+                        (request_line.next().unwrap(), request_line.next().unwrap());
+                    let range = lines
+                        .filter_map(|line| line.split_once(':'))
+                        .find(|(name, _)| name.eq_ignore_ascii_case("range"))
+                        .and_then(|(_, value)| value.trim().strip_prefix("bytes="))
+                        .and_then(|range| range.split_once('-'))
+                        .map(|(start, end)| {
+                            (start.parse::<usize>().unwrap(), end.parse::<usize>().unwrap())
+                        });
+
+                    let response = match files.get(path.trim_start_matches('/')) {
+                        Some(file) => {
+                            let (status, body) = match range {
+                                Some((start, end)) => {
+                                    ("206 Partial Content", &file[start..=end.min(file.len() - 1)])
+                                }
+                                None => ("200 OK", &file[..]),
+                            };
+                            let mut response = format!(
+                                "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                                body.len()
+                            )
+                            .into_bytes();
+                            if method != "HEAD" {
+                                response.extend_from_slice(body);
+                            }
+                            response
+                        }
+                        None => b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                            .to_vec(),
+                    };
+                    let _ = stream.write_all(&response).await;
+                });
+            }
+        });
 
-        fn stage(&self) -> Self::S {
-            S3Stage::default()
-        }
+        url
     }
 
-    impl ExecuteStageTestRunner for S3TestRunner {
-        type Seed = Vec<SealedHeader>;
-
-        fn seed_execution(&mut self, input: ExecInput) -> Result<Self::Seed, TestRunnerError> {
-            let start = input.checkpoint().block_number;
-            let mut rng = generators::rng();
-            let head = random_header(&mut rng, start, None);
-            self.db.insert_headers_with_td(std::iter::once(&head))?;
+    #[test]
+    fn block_range_files_are_consecutive() {
+        let list = |ranges: &[(u64, u64)]| {
+            ranges
+                .iter()
+                .flat_map(|(start, end)| {
+                    [
+                        StaticFileSegment::Headers,
+                        StaticFileSegment::Transactions,
+                        StaticFileSegment::BlockMeta,
+                        StaticFileSegment::Receipts,
+                    ]
+                    .map(|segment| segment.filename(&SegmentRangeInclusive::new(*start, *end)))
+                })
+                .flat_map(|file| [format!("{file}.off"), format!("{file}.conf"), file])
+                .map(|file| (file, B256::ZERO))
+                .collect()
+        };
+
+        let file_list = list(&[(0, 4), (5, 9), (15, 19)]);
+        let ranges = block_range_files(&file_list, 0)
+            .unwrap()
+            .into_iter()
+            .map(|files| {
+                assert_eq!(files.files.len(), 12);
+                files.block_range
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            ranges,
+            vec![SegmentRangeInclusive::new(0, 4), SegmentRangeInclusive::new(5, 9)]
+        );
+
+        let ranges = block_range_files(&file_list, 9).unwrap();
+        assert!(ranges.is_empty());
+
+        let mut file_list = list(&[(0, 4)]);
+        file_list.remove("static_file_receipts_0_4.off");
+        assert!(matches!(
+            block_range_files(&file_list, 0),
+            Err(DownloaderError::MissingFile(file)) if file == "static_file_receipts_0_4.off"
+        ));
+    }
 
-            // use previous progress as seed size
-            let end = input.target.unwrap_or_default() + 1;
+    #[tokio::test]
+    async fn imports_block_ranges_from_mirror() {
+        reth_tracing::init_test_tracing();
+        let mut rng = generators::rng();
+        let blocks = random_block_range(
+            &mut rng,
+            0..=11,
+            BlockRangeParams {
+                parent: Some(B256::ZERO),
+                tx_count: 1..3,
+                withdrawals_count: Some(0..3),
+                ..Default::default()
+            },
+        );
+        let receipts = blocks
+            .iter()
+            .map(|block| {
+                block
+                    .body()
+                    .transactions
+                    .iter()
+                    .map(|tx| random_receipt(&mut rng, tx, Some(1)))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        // Writes the blocks to static files of 5 blocks each, the way a mirror would serve them.
+        let source_dir = tempfile::tempdir().unwrap();
+        {
+            let source = StaticFileProvider::<EthPrimitives>::read_write(source_dir.path())
+                .unwrap()
+                .with_custom_blocks_per_file(5);
+            let mut headers = source.latest_writer(StaticFileSegment::Headers).unwrap();
+            let mut transactions = source.latest_writer(StaticFileSegment::Transactions).unwrap();
+            let mut block_meta = source.latest_writer(StaticFileSegment::BlockMeta).unwrap();
+            let mut receipts_writer = source.latest_writer(StaticFileSegment::Receipts).unwrap();
+            let mut tx_num = 0;
+            for (block, receipts) in blocks.iter().zip(&receipts) {
+                headers.append_header(block.header(), U256::ZERO, &block.hash()).unwrap();
+
+                let body_indices = StoredBlockBodyIndices {
+                    first_tx_num: tx_num,
+                    tx_count: block.body().transactions.len() as u64,
+                };
+                transactions.increment_block(block.number).unwrap();
+                receipts_writer.increment_block(block.number).unwrap();
+                for (tx, receipt) in block.body().transactions.iter().zip(receipts) {
+                    transactions.append_transaction(tx_num, tx).unwrap();
+                    receipts_writer.append_receipt(tx_num, receipt).unwrap();
+                    tx_num += 1;
+                }
 
-            if start + 1 >= end {
-                return Ok(Vec::default())
+                block_meta
+                    .append_block_meta(
+                        &body_indices,
+                        &StoredBlockOmmers { ommers: block.body().ommers.clone() },
+                        &StaticFileBlockWithdrawals {
+                            withdrawals: block.body().withdrawals.clone(),
+                        },
+                        block.number,
+                    )
+                    .unwrap();
             }
-
-            let mut headers = random_header_range(&mut rng, start + 1..end, head.hash());
-            self.db.insert_headers_with_td(headers.iter())?;
-            headers.insert(0, head);
-            Ok(headers)
+            headers.commit().unwrap();
+            transactions.commit().unwrap();
+            block_meta.commit().unwrap();
+            receipts_writer.commit().unwrap();
         }
 
-        fn validate_execution(
-            &self,
-            input: ExecInput,
-            output: Option<ExecOutput>,
-        ) -> Result<(), TestRunnerError> {
-            if let Some(output) = output {
-                assert!(output.done, "stage should always be done");
-                assert_eq!(
-                    output.checkpoint.block_number,
-                    input.target(),
-                    "stage progress should always match progress of previous stage"
-                );
+        let mut files = HashMap::new();
+        for entry in std::fs::read_dir(source_dir.path()).unwrap() {
+            let path = entry.unwrap().path();
+            let filename = path.file_name().unwrap().to_string_lossy().into_owned();
+            if filename.starts_with("static_file") {
+                files.insert(filename, std::fs::read(path).unwrap());
             }
-            Ok(())
         }
-    }
-
-    impl UnwindStageTestRunner for S3TestRunner {
-        fn validate_unwind(&self, _input: UnwindInput) -> Result<(), TestRunnerError> {
-            Ok(())
+        let file_list = files
+            .iter()
+            .map(|(filename, file)| (filename.clone(), B256::from(*blake3::hash(file).as_bytes())))
+            .collect::<HashMap<_, _>>();
+        files.insert(filelist::FILE_LIST.to_string(), serde_json::to_vec(&file_list).unwrap());
+        let url = serve(files).await;
+
+        // The local chain only knows about genesis.
+        let db = TestStageDB::default();
+        db.insert_blocks(blocks.iter().take(1), StorageKind::Static).unwrap();
+        db.insert_receipts_by_block(
+            [(0, receipts[0].iter().cloned().enumerate().map(|(i, r)| (i as u64, r)))],
+            StorageKind::Static,
+        )
+        .unwrap();
+
+        let folder = tempfile::tempdir().unwrap();
+        let mut stage = S3Stage::new(
+            S3Config {
+                url: Some(url),
+                max_concurrent_requests: 2,
+                folder: Some(folder.path().to_path_buf()),
+            },
+            EtlConfig::default(),
+        );
+
+        let mut input = ExecInput { target: Some(0), checkpoint: None };
+        let mut checkpoints = Vec::new();
+        loop {
+            StageExt::<TestProvider>::execute_ready(&mut stage, input).await.unwrap();
+            let provider = db.factory.database_provider_rw().unwrap();
+            let output = Stage::<TestProvider>::execute(&mut stage, &provider, input).unwrap();
+            UnifiedStorageWriter::commit(provider).unwrap();
+
+            checkpoints.push(output.checkpoint.block_number);
+            input.checkpoint = Some(output.checkpoint);
+            if output.done {
+                break
+            }
         }
-    }
-
-    #[test]
-    fn parse_files() {
-        for block_range_files in &DOWNLOAD_FILE_LIST {
-            let (_, _) = StaticFileSegment::parse_filename(block_range_files[0].0).expect("qed");
+        assert_eq!(checkpoints, vec![4, 9, 11, 11]);
+        assert!(!folder.path().join("0_4").exists());
+
+        // The genesis block was inserted by the test, the rest was imported by the stage.
+        let provider = db.factory.provider().unwrap();
+        for (block, receipts) in blocks.iter().zip(&receipts).skip(1) {
+            let header = provider.sealed_header(block.number).unwrap().unwrap();
+            assert_eq!(header.hash(), block.hash());
+            let body = provider.block_by_number(block.number).unwrap().unwrap().into_body();
+            assert_eq!(body.transactions, block.body().transactions);
+            assert_eq!(body.ommers, block.body().ommers);
+            assert_eq!(
+                provider.receipts_by_block(block.number.into()).unwrap().as_ref(),
+                Some(receipts)
+            );
         }
+        assert_eq!(imported_receipts_block(provider.tx_ref()).unwrap(), Some(11));
+        assert_eq!(
+            provider.get_stage_checkpoint(StageId::Bodies).unwrap().map(|c| c.block_number),
+            Some(11)
+        );
     }
 }
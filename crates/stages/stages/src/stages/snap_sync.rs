@@ -0,0 +1,1790 @@
+use alloy_consensus::{constants::KECCAK_EMPTY, BlockHeader};
+use alloy_primitives::{
+    keccak256,
+    map::{B256Map, B256Set},
+    BlockNumber, Bytes, B256, U256,
+};
+use alloy_rlp::{Decodable, RlpDecodable};
+use futures_util::{stream::FuturesUnordered, FutureExt, StreamExt};
+use reth_codecs::Compact;
+use reth_db_api::{
+    cursor::{DbCursorRO, DbCursorRW, DbDupCursorRO, DbDupCursorRW},
+    tables,
+    transaction::{DbTx, DbTxMut},
+};
+use reth_eth_wire_types::snap::{
+    AccountRangeMessage, ByteCodesMessage, GetAccountRangeMessage, GetByteCodesMessage,
+    GetStorageRangesMessage, GetTrieNodesMessage, StorageRangesMessage, TrieNodesMessage, TriePath,
+};
+use reth_network_p2p::{
+    error::PeerRequestResult,
+    snap::client::{SnapClient, SnapResponse},
+};
+use reth_primitives_traits::{Account, Bytecode, GotExpected, StorageEntry};
+use reth_provider::{
+    DBProvider, HeaderProvider, ProviderError, PruneCheckpointReader, PruneCheckpointWriter,
+    StageCheckpointReader, StageCheckpointWriter, TrieWriter,
+};
+use reth_prune_types::{PruneCheckpoint, PruneMode, PruneSegment};
+use reth_stages_api::{
+    ExecInput, ExecOutput, MerkleCheckpoint, Stage, StageCheckpoint, StageError, StageId,
+    UnwindInput, UnwindOutput,
+};
+use reth_trie::{
+    encode_path_leaf, proof::Proof, HashBuilder, IntermediateStateRootState, MultiProofTargets,
+    Nibbles, RlpNode, StateRoot, StateRootProgress, StoredSubNode, TrieAccount, TrieNode,
+    EMPTY_ROOT_HASH,
+};
+use reth_trie_db::{DatabaseProof, DatabaseStateRoot};
+use std::{
+    cmp::Ordering,
+    collections::VecDeque,
+    fmt::Debug,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use thiserror::Error;
+use tracing::*;
+
+/// The default distance of the snap sync pivot from the pipeline target.
+///
+/// Peers only serve the state of their most recent blocks, so the pivot has to be recent, but
+/// also deep enough to not be reorged.
+pub const SNAP_SYNC_PIVOT_DISTANCE: u64 = 64;
+
+/// The default soft limit for the size of a single `snap` response.
+pub const SNAP_SYNC_RESPONSE_BYTES: u64 = 512 * 1024;
+
+/// The default maximum number of `snap` requests in flight.
+pub const SNAP_SYNC_MAX_CONCURRENT_REQUESTS: usize = 16;
+
+/// Maximum number of accounts whose storage is requested at once.
+const MAX_STORAGE_ACCOUNTS_PER_REQUEST: usize = 128;
+
+/// Maximum number of bytecodes requested at once.
+const MAX_CODES_PER_REQUEST: usize = 64;
+
+/// Maximum number of trie nodes requested at once.
+const MAX_TRIE_NODES_PER_REQUEST: usize = 128;
+
+/// Maximum number of trie nodes compared to the local trie in a single execution of the stage.
+const MAX_HEAL_CHECKS_PER_ROUND: usize = 4096;
+
+/// Number of failed or empty responses in a row after which the stage reports an error.
+const MAX_FAILED_RESPONSES: usize = 64;
+
+/// Number of times the state is healed before the state root mismatch is reported as fatal.
+const MAX_HEAL_PASSES: usize = 4;
+
+/// An error of the [`SnapSyncStage`].
+#[derive(Debug, Error)]
+pub enum SnapSyncError {
+    /// The receipts of the blocks before the pivot are not downloaded, so they have to be pruned.
+    #[error("snap sync requires receipts pruning")]
+    ReceiptsPruningRequired,
+    /// The peers didn't serve the state of the pivot block.
+    #[error("no progress downloading the state of block #{pivot}")]
+    NoProgress {
+        /// The pivot block.
+        pivot: BlockNumber,
+    },
+    /// The state root doesn't match the pivot block after healing.
+    #[error("state root mismatch after healing: {0}")]
+    StateRootMismatch(GotExpected<B256>),
+    /// The state before the pivot block isn't available.
+    #[error("cannot unwind to block #{unwind_to} below the snap sync pivot #{pivot}")]
+    UnwindBelowPivot {
+        /// The requested unwind target.
+        unwind_to: BlockNumber,
+        /// The pivot block.
+        pivot: BlockNumber,
+    },
+    /// The stored progress of the stage can't be decoded.
+    #[error("invalid snap sync progress")]
+    InvalidProgress,
+}
+
+/// The snap sync stage downloads the state of a recent pivot block over the `snap/1` protocol,
+/// instead of executing all blocks from genesis.
+///
+/// The stage is run before the [`ExecutionStage`](crate::stages::ExecutionStage), and only on a
+/// node that hasn't executed any blocks yet. It
+///
+/// 1. downloads the account ranges of the pivot state, and the storage slots and bytecodes of the
+///    accounts, into [`tables::HashedAccounts`], [`tables::HashedStorages`] and
+///    [`tables::Bytecodes`],
+/// 2. computes the state root, writing [`tables::AccountsTrie`] and [`tables::StoragesTrie`],
+/// 3. if the root doesn't match the pivot header, heals the state by walking the trie of the pivot
+///    from the root with `GetTrieNodes` requests, and replacing the local leaves of every subtrie
+///    whose hash differs, then computes the root again.
+///
+/// Every downloaded range is verified against the pivot state root with the proof of its edges,
+/// and the peer is reported if the verification fails. The healing repairs what is left
+/// inconsistent, such as the ranges that were downloaded for an earlier pivot.
+///
+/// Once the state root matches, the checkpoints of the execution, hashing, merkle and history
+/// index stages are set to the pivot, so that they resume from it, and the account and storage
+/// history before the pivot is marked as pruned. The receipts before the pivot are not
+/// available either, so the stage requires receipts pruning, and fails with
+/// [`SnapSyncError::ReceiptsPruningRequired`] without it. The node enables the stage with
+/// `--snap-sync`, which is refused at startup unless receipts pruning is configured.
+///
+/// `snap/1` only serves the hashed state, and the addresses and slot keys can't be recovered
+/// from their hashes, so the stage doesn't write [`tables::PlainAccountState`] and
+/// [`tables::PlainStorageState`]. They only contain the accounts and storage slots that were
+/// changed after the pivot. Instead, the saved progress of the stage marks the state as snap
+/// synced, see [`DBProvider::is_snap_synced`], and the state providers fall back to
+/// [`tables::HashedAccounts`] and [`tables::HashedStorages`] for the accounts and slots that are
+/// missing from the plain state. The execution stage keeps the hashed state up to date instead
+/// of the hashing stages rebuilding it.
+///
+/// Peers only serve the state of their most recent blocks, so the pivot is moved closer to the
+/// target if the stage is run with a target that is far ahead of the pivot. The downloaded
+/// ranges are kept, and the difference is repaired by the healing.
+#[derive(Debug)]
+pub struct SnapSyncStage<C> {
+    /// The client for the `snap` requests.
+    client: C,
+    /// The distance of the pivot from the target.
+    pivot_distance: u64,
+    /// The soft limit for the size of a single response.
+    response_bytes: u64,
+    /// The maximum number of requests in flight.
+    max_concurrent_requests: usize,
+    /// The requests in flight.
+    inflight: FuturesUnordered<SnapRequestFut>,
+    /// The responses received since the last execution of the stage.
+    responses: Vec<(SnapRequest, PeerRequestResult<SnapResponse>)>,
+    /// The status of the current account range.
+    account_range: AccountRangeStatus,
+    /// The storage ranges left to download.
+    storage: VecDeque<StorageTask>,
+    /// The bytecodes left to download.
+    codes: VecDeque<B256>,
+    /// The bytecodes that are queued or requested.
+    pending_codes: B256Set,
+    /// The trie nodes left to heal, if the state is being healed.
+    heal: Option<VecDeque<HealTask>>,
+    /// Number of times the state was healed for the current pivot.
+    heal_passes: usize,
+    /// Number of failed or empty responses in a row.
+    failed_responses: usize,
+    /// The pivot of the last execution of the stage.
+    pivot: BlockNumber,
+}
+
+impl<C> SnapSyncStage<C> {
+    /// Creates a new snap sync stage that downloads the state with the given client.
+    pub fn new(client: C) -> Self {
+        Self {
+            client,
+            pivot_distance: SNAP_SYNC_PIVOT_DISTANCE,
+            response_bytes: SNAP_SYNC_RESPONSE_BYTES,
+            max_concurrent_requests: SNAP_SYNC_MAX_CONCURRENT_REQUESTS,
+            inflight: FuturesUnordered::new(),
+            responses: Vec::new(),
+            account_range: AccountRangeStatus::Pending,
+            storage: VecDeque::new(),
+            codes: VecDeque::new(),
+            pending_codes: B256Set::default(),
+            heal: None,
+            heal_passes: 0,
+            failed_responses: 0,
+            pivot: 0,
+        }
+    }
+
+    /// Sets the distance of the pivot from the target.
+    pub const fn with_pivot_distance(mut self, pivot_distance: u64) -> Self {
+        self.pivot_distance = pivot_distance;
+        self
+    }
+
+    /// Sets the soft limit for the size of a single response.
+    pub const fn with_response_bytes(mut self, response_bytes: u64) -> Self {
+        self.response_bytes = response_bytes;
+        self
+    }
+
+    /// Sets the maximum number of requests in flight.
+    pub const fn with_max_concurrent_requests(mut self, max_concurrent_requests: usize) -> Self {
+        self.max_concurrent_requests = max_concurrent_requests;
+        self
+    }
+
+    /// Drops all requests and pending downloads.
+    fn reset(&mut self) {
+        self.inflight = FuturesUnordered::new();
+        self.responses.clear();
+        self.account_range = AccountRangeStatus::Pending;
+        self.storage.clear();
+        self.codes.clear();
+        self.pending_codes.clear();
+        self.heal = None;
+        self.heal_passes = 0;
+        self.failed_responses = 0;
+    }
+
+    /// Returns `true` if more requests can be sent.
+    fn has_capacity(&self) -> bool {
+        self.inflight.len() < self.max_concurrent_requests
+    }
+
+    /// Queues the bytecode for download, if it's not in the database yet.
+    fn queue_code<TX: DbTx>(&mut self, tx: &TX, code_hash: B256) -> Result<(), StageError> {
+        if code_hash != KECCAK_EMPTY &&
+            !self.pending_codes.contains(&code_hash) &&
+            tx.get::<tables::Bytecodes>(code_hash)?.is_none()
+        {
+            self.pending_codes.insert(code_hash);
+            self.codes.push_back(code_hash);
+        }
+        Ok(())
+    }
+}
+
+impl<C> SnapSyncStage<C>
+where
+    C: SnapClient<Output: 'static>,
+{
+    /// Sends the request.
+    fn send(&self, request: SnapRequest, root_hash: B256) {
+        let response_bytes = self.response_bytes;
+        let response = match &request {
+            SnapRequest::AccountRange { start } => {
+                self.client.get_account_range(GetAccountRangeMessage {
+                    request_id: 0,
+                    root_hash,
+                    starting_hash: *start,
+                    limit_hash: B256::repeat_byte(0xff),
+                    response_bytes,
+                })
+            }
+            SnapRequest::StorageRanges { tasks } => {
+                self.client.get_storage_ranges(GetStorageRangesMessage {
+                    request_id: 0,
+                    root_hash,
+                    account_hashes: tasks.iter().map(|task| task.account).collect(),
+                    starting_hash: tasks[0].start,
+                    limit_hash: B256::repeat_byte(0xff),
+                    response_bytes,
+                })
+            }
+            SnapRequest::ByteCodes { hashes } => self.client.get_byte_codes(GetByteCodesMessage {
+                request_id: 0,
+                hashes: hashes.clone(),
+                response_bytes,
+            }),
+            SnapRequest::TrieNodes { tasks } => self.client.get_trie_nodes(GetTrieNodesMessage {
+                request_id: 0,
+                root_hash,
+                paths: trie_paths(tasks),
+                response_bytes,
+            }),
+        };
+        self.inflight.push(Box::pin(response.map(move |response| (request, response))));
+    }
+
+    /// Requests the storage ranges of the queued accounts.
+    fn request_storage(&mut self, root_hash: B256) {
+        while self.has_capacity() {
+            let Some(first) = self.storage.pop_front() else { break };
+
+            // only ranges that start at the beginning of the storage trie can be batched
+            let mut tasks = vec![first];
+            if first.start.is_zero() {
+                while tasks.len() < MAX_STORAGE_ACCOUNTS_PER_REQUEST &&
+                    self.storage.front().is_some_and(|task| task.start.is_zero())
+                {
+                    tasks.extend(self.storage.pop_front());
+                }
+            }
+            self.send(SnapRequest::StorageRanges { tasks }, root_hash);
+        }
+    }
+
+    /// Requests the queued bytecodes.
+    fn request_codes(&mut self, root_hash: B256) {
+        while self.has_capacity() && !self.codes.is_empty() {
+            let count = self.codes.len().min(MAX_CODES_PER_REQUEST);
+            let hashes = self.codes.drain(..count).collect();
+            self.send(SnapRequest::ByteCodes { hashes }, root_hash);
+        }
+    }
+
+    /// Requests the queued trie nodes that differ from the local trie.
+    fn request_trie_nodes<TX: DbTx>(&mut self, tx: &TX, root_hash: B256) -> Result<(), StageError> {
+        let mut checked = 0;
+        while self.has_capacity() && checked < MAX_HEAL_CHECKS_PER_ROUND {
+            let Some(queue) = self.heal.as_mut() else { break };
+            if queue.is_empty() {
+                break
+            }
+
+            let count = queue.len().min(MAX_TRIE_NODES_PER_REQUEST);
+            let tasks = queue.drain(..count).collect::<Vec<_>>();
+            checked += tasks.len();
+
+            let tasks = remove_local_nodes(tx, tasks)?;
+            if !tasks.is_empty() {
+                self.send(SnapRequest::TrieNodes { tasks }, root_hash);
+            }
+        }
+        Ok(())
+    }
+
+    /// Sends the requests of the current phase and advances the phase once its downloads are
+    /// done.
+    ///
+    /// Returns `true` if the state of the pivot is complete.
+    fn advance<Provider>(
+        &mut self,
+        provider: &Provider,
+        progress: &mut SnapSyncProgress,
+        root_hash: B256,
+    ) -> Result<bool, StageError>
+    where
+        Provider: DBProvider<Tx: DbTxMut> + TrieWriter,
+    {
+        let tx = provider.tx_ref();
+
+        if self.heal.is_some() {
+            self.request_codes(root_hash);
+            self.request_trie_nodes(tx, root_hash)?;
+
+            if self.heal.as_ref().is_some_and(|queue| queue.is_empty()) &&
+                self.codes.is_empty() &&
+                self.inflight.is_empty()
+            {
+                info!(target: "sync::stages::snap_sync", pivot = progress.pivot, "Healed state");
+                self.heal = None;
+            }
+            return Ok(false)
+        }
+
+        match &mut progress.phase {
+            SnapSyncPhase::Accounts { next } => {
+                if let AccountRangeStatus::Received(range_next) = self.account_range {
+                    self.request_storage(root_hash);
+                    self.request_codes(root_hash);
+                    if !self.storage.is_empty() ||
+                        !self.codes.is_empty() ||
+                        !self.inflight.is_empty()
+                    {
+                        return Ok(false)
+                    }
+
+                    self.account_range = AccountRangeStatus::Pending;
+                    let Some(range_next) = range_next else {
+                        info!(target: "sync::stages::snap_sync", pivot = progress.pivot, "Downloaded accounts");
+                        progress.phase = SnapSyncPhase::StateRoot(None);
+                        return Ok(false)
+                    };
+                    *next = range_next;
+                    info!(
+                        target: "sync::stages::snap_sync",
+                        pivot = progress.pivot,
+                        progress = %format!("{:.2}%", key_progress(range_next) * 100.0),
+                        "Downloaded account range"
+                    );
+                }
+
+                if matches!(self.account_range, AccountRangeStatus::Pending) {
+                    self.send(SnapRequest::AccountRange { start: *next }, root_hash);
+                    self.account_range = AccountRangeStatus::Requested;
+                }
+                Ok(false)
+            }
+            SnapSyncPhase::StateRoot(checkpoint) => {
+                if checkpoint.is_none() {
+                    tx.clear::<tables::AccountsTrie>()?;
+                    tx.clear::<tables::StoragesTrie>()?;
+                }
+
+                let progress_result = StateRoot::from_tx(tx)
+                    .with_intermediate_state(
+                        checkpoint.take().map(IntermediateStateRootState::from),
+                    )
+                    .root_with_progress()
+                    .map_err(|err| StageError::Fatal(Box::new(err)))?;
+                match progress_result {
+                    StateRootProgress::Progress(state, _, updates) => {
+                        provider.write_trie_updates(&updates)?;
+                        *checkpoint = Some(MerkleCheckpoint::new(
+                            progress.pivot,
+                            state.last_account_key,
+                            state.walker_stack.into_iter().map(StoredSubNode::from).collect(),
+                            state.hash_builder.into(),
+                        ));
+                        Ok(false)
+                    }
+                    StateRootProgress::Complete(root, _, updates) => {
+                        provider.write_trie_updates(&updates)?;
+                        if root == root_hash {
+                            progress.phase = SnapSyncPhase::Done;
+                            return Ok(true)
+                        }
+
+                        if self.heal_passes >= MAX_HEAL_PASSES {
+                            return Err(StageError::Fatal(Box::new(
+                                SnapSyncError::StateRootMismatch(GotExpected::new(root, root_hash)),
+                            )))
+                        }
+                        self.heal_passes += 1;
+
+                        info!(target: "sync::stages::snap_sync", pivot = progress.pivot, ?root, expected = ?root_hash, "Healing state");
+                        self.heal = Some(VecDeque::from([HealTask {
+                            account: None,
+                            path: Nibbles::default(),
+                            hash: root_hash,
+                        }]));
+                        Ok(false)
+                    }
+                }
+            }
+            SnapSyncPhase::Done => Ok(true),
+        }
+    }
+}
+
+impl<C> SnapSyncStage<C> {
+    /// Writes the downloaded data of the response and queues the follow-up downloads.
+    fn on_response<TX: DbTxMut + DbTx>(
+        &mut self,
+        tx: &TX,
+        request: SnapRequest,
+        response: SnapResponse,
+        root_hash: B256,
+    ) -> Result<ResponseOutcome, StageError> {
+        match (request, response) {
+            (SnapRequest::AccountRange { start }, SnapResponse::AccountRange(response)) => {
+                self.on_account_range(tx, start, response, root_hash)
+            }
+            (SnapRequest::StorageRanges { tasks }, SnapResponse::StorageRanges(response)) => {
+                self.on_storage_ranges(tx, tasks, response)
+            }
+            (SnapRequest::ByteCodes { hashes }, SnapResponse::ByteCodes(response)) => {
+                self.on_byte_codes(tx, hashes, response)
+            }
+            (SnapRequest::TrieNodes { tasks }, SnapResponse::TrieNodes(response)) => {
+                self.on_trie_nodes(tx, tasks, response)
+            }
+            (request, _) => {
+                self.on_failed_request(request);
+                Ok(ResponseOutcome::Invalid)
+            }
+        }
+    }
+
+    /// Queues the downloads of the request again.
+    fn on_failed_request(&mut self, request: SnapRequest) {
+        match request {
+            SnapRequest::AccountRange { .. } => self.account_range = AccountRangeStatus::Pending,
+            SnapRequest::StorageRanges { tasks } => {
+                for task in tasks.into_iter().rev() {
+                    self.storage.push_front(task);
+                }
+            }
+            SnapRequest::ByteCodes { hashes } => self.codes.extend(hashes),
+            SnapRequest::TrieNodes { tasks } => {
+                if let Some(queue) = self.heal.as_mut() {
+                    queue.extend(tasks);
+                }
+            }
+        }
+    }
+
+    fn on_account_range<TX: DbTxMut + DbTx>(
+        &mut self,
+        tx: &TX,
+        start: B256,
+        response: AccountRangeMessage,
+        root_hash: B256,
+    ) -> Result<ResponseOutcome, StageError> {
+        let AccountRangeMessage { accounts, proof, .. } = response;
+        if accounts.is_empty() && proof.is_empty() {
+            // the peer doesn't serve the state
+            self.account_range = AccountRangeStatus::Pending;
+            return Ok(ResponseOutcome::Empty)
+        }
+
+        let decoded = accounts
+            .iter()
+            .map(|account| {
+                decode_slim_account(&account.body).map(|decoded| (account.hash, decoded))
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .ok();
+        let leaves = decoded.as_ref().map(|accounts| {
+            accounts
+                .iter()
+                .map(|(hash, (account, storage_root))| {
+                    (*hash, alloy_rlp::encode(account.into_trie_account(*storage_root)))
+                })
+                .collect::<Vec<_>>()
+        });
+        let Some((accounts, has_more)) = decoded.zip(leaves).and_then(|(accounts, leaves)| {
+            verify_range_proof(root_hash, start, &leaves, &proof)
+                .map(|has_more| (accounts, has_more))
+        }) else {
+            self.account_range = AccountRangeStatus::Pending;
+            return Ok(ResponseOutcome::Invalid)
+        };
+
+        // the range proof also proves that there are no accounts after the last one
+        let last = if has_more { accounts[accounts.len() - 1].0 } else { B256::repeat_byte(0xff) };
+        delete_hashed_state(tx, start, last)?;
+
+        let mut cursor = tx.cursor_write::<tables::HashedAccounts>()?;
+        for (hash, (account, storage_root)) in accounts {
+            cursor.upsert(hash, &account)?;
+            if storage_root != EMPTY_ROOT_HASH {
+                self.storage.push_back(StorageTask {
+                    account: hash,
+                    root: storage_root,
+                    start: B256::ZERO,
+                });
+            }
+            if let Some(code_hash) = account.bytecode_hash {
+                self.queue_code(tx, code_hash)?;
+            }
+        }
+
+        self.account_range =
+            AccountRangeStatus::Received(if has_more { increment_key(last) } else { None });
+        Ok(ResponseOutcome::Useful)
+    }
+
+    fn on_storage_ranges<TX: DbTxMut + DbTx>(
+        &mut self,
+        tx: &TX,
+        tasks: Vec<StorageTask>,
+        response: StorageRangesMessage,
+    ) -> Result<ResponseOutcome, StageError> {
+        let StorageRangesMessage { mut slots, proof, .. } = response;
+        if slots.is_empty() {
+            if proof.is_empty() {
+                // the peer doesn't serve the state
+                self.on_failed_request(SnapRequest::StorageRanges { tasks });
+                return Ok(ResponseOutcome::Empty)
+            }
+
+            // the proof has to show that there are no more slots in the range of the first account
+            slots.push(Vec::new());
+        }
+
+        // Only the last range of the response can be cut off, all ranges before it are complete
+        // and are verified without a proof.
+        let received = slots.len();
+        let decoded = slots
+            .iter()
+            .zip(&tasks)
+            .enumerate()
+            .map(|(idx, (slots, task))| {
+                let slots = slots
+                    .iter()
+                    .map(|slot| U256::decode(&mut &slot.data[..]).map(|value| (slot.hash, value)))
+                    .collect::<Result<Vec<_>, _>>()
+                    .ok()?;
+                let leaves = slots
+                    .iter()
+                    .map(|(hash, value)| (*hash, alloy_rlp::encode(value)))
+                    .collect::<Vec<_>>();
+                let proof = if idx == received - 1 { &proof[..] } else { &[] };
+                let has_more = verify_range_proof(task.root, task.start, &leaves, proof)?;
+                Some((slots, has_more))
+            })
+            .collect::<Option<Vec<_>>>();
+        let Some(decoded) = decoded.filter(|decoded| decoded.len() == received) else {
+            self.on_failed_request(SnapRequest::StorageRanges { tasks });
+            return Ok(ResponseOutcome::Invalid)
+        };
+
+        let mut cursor = tx.cursor_dup_write::<tables::HashedStorages>()?;
+        let mut continuation = None;
+        for (task, (slots, has_more)) in tasks.iter().zip(decoded) {
+            let last = slots.last().map(|(key, _)| *key);
+            for (key, value) in slots {
+                cursor.upsert(task.account, &StorageEntry { key, value })?;
+            }
+
+            // a cut off range is continued after its last slot
+            if has_more {
+                continuation =
+                    last.and_then(increment_key).map(|start| StorageTask { start, ..*task });
+            }
+        }
+
+        let mut remaining = tasks[received..].to_vec();
+        remaining.splice(0..0, continuation);
+        self.on_failed_request(SnapRequest::StorageRanges { tasks: remaining });
+        Ok(ResponseOutcome::Useful)
+    }
+
+    fn on_byte_codes<TX: DbTxMut + DbTx>(
+        &mut self,
+        tx: &TX,
+        hashes: Vec<B256>,
+        response: ByteCodesMessage,
+    ) -> Result<ResponseOutcome, StageError> {
+        let mut remaining = hashes.into_iter().collect::<B256Set>();
+        let mut outcome = if response.codes.is_empty() {
+            ResponseOutcome::Empty
+        } else {
+            ResponseOutcome::Useful
+        };
+        for code in response.codes {
+            let hash = keccak256(&code);
+            if remaining.remove(&hash) {
+                tx.put::<tables::Bytecodes>(hash, Bytecode::new_raw(code))?;
+                self.pending_codes.remove(&hash);
+            } else {
+                outcome = ResponseOutcome::Invalid;
+            }
+        }
+
+        self.codes.extend(remaining);
+        Ok(outcome)
+    }
+
+    fn on_trie_nodes<TX: DbTxMut + DbTx>(
+        &mut self,
+        tx: &TX,
+        tasks: Vec<HealTask>,
+        response: TrieNodesMessage,
+    ) -> Result<ResponseOutcome, StageError> {
+        if response.nodes.is_empty() {
+            self.on_failed_request(SnapRequest::TrieNodes { tasks });
+            return Ok(ResponseOutcome::Empty)
+        }
+
+        let mut outcome = ResponseOutcome::Useful;
+        let mut tasks = tasks.into_iter();
+        let mut remaining = Vec::new();
+        for (task, node) in (&mut tasks).zip(response.nodes) {
+            if keccak256(&node) != task.hash {
+                outcome = ResponseOutcome::Invalid;
+                remaining.push(task);
+                continue
+            }
+
+            // the node is part of the pivot state, so it can always be decoded
+            let node =
+                TrieNode::decode(&mut &node[..]).map_err(|err| StageError::Fatal(Box::new(err)))?;
+            self.heal_node(tx, task.account, task.path, node)?;
+        }
+        remaining.extend(tasks);
+        self.on_failed_request(SnapRequest::TrieNodes { tasks: remaining });
+        Ok(outcome)
+    }
+
+    /// Replaces the local state under the path with the state of the node, and queues the
+    /// children of the node for healing.
+    fn heal_node<TX: DbTxMut + DbTx>(
+        &mut self,
+        tx: &TX,
+        account: Option<B256>,
+        path: Nibbles,
+        node: TrieNode,
+    ) -> Result<(), StageError> {
+        match node {
+            TrieNode::EmptyRoot => delete_leaves(tx, account, &path, None)?,
+            TrieNode::Branch(branch) => {
+                for (nibble, child) in branch.as_ref().children() {
+                    let mut child_path = path;
+                    child_path.push(nibble);
+                    match child {
+                        Some(child) => self.heal_child(tx, account, child_path, child)?,
+                        None => delete_leaves(tx, account, &child_path, None)?,
+                    }
+                }
+            }
+            TrieNode::Extension(extension) => {
+                let child_path = path.join(&extension.key);
+                delete_leaves(tx, account, &path, Some(&child_path))?;
+                self.heal_child(tx, account, child_path, &extension.child)?;
+            }
+            TrieNode::Leaf(leaf) => {
+                let key_path = path.join(&leaf.key);
+                delete_leaves(tx, account, &path, Some(&key_path))?;
+                let key = B256::from_slice(&key_path.pack());
+                let decode_error = |err| StageError::Fatal(Box::new(err));
+
+                match account {
+                    None => {
+                        let trie_account =
+                            TrieAccount::decode(&mut &leaf.value[..]).map_err(decode_error)?;
+                        let account = Account {
+                            nonce: trie_account.nonce,
+                            balance: trie_account.balance,
+                            bytecode_hash: (trie_account.code_hash != KECCAK_EMPTY)
+                                .then_some(trie_account.code_hash),
+                        };
+                        tx.put::<tables::HashedAccounts>(key, account)?;
+                        self.queue_code(tx, trie_account.code_hash)?;
+
+                        if trie_account.storage_root == EMPTY_ROOT_HASH {
+                            delete_leaves(tx, Some(key), &Nibbles::default(), None)?;
+                        } else if let Some(queue) = self.heal.as_mut() {
+                            queue.push_back(HealTask {
+                                account: Some(key),
+                                path: Nibbles::default(),
+                                hash: trie_account.storage_root,
+                            });
+                        }
+                    }
+                    Some(hashed_address) => {
+                        let value = U256::decode(&mut &leaf.value[..]).map_err(decode_error)?;
+                        let mut cursor = tx.cursor_dup_write::<tables::HashedStorages>()?;
+                        if cursor
+                            .seek_by_key_subkey(hashed_address, key)?
+                            .is_some_and(|entry| entry.key == key)
+                        {
+                            cursor.delete_current()?;
+                        }
+                        if !value.is_zero() {
+                            cursor.upsert(hashed_address, &StorageEntry { key, value })?;
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Queues the child for healing, or heals it right away if it's embedded in its parent.
+    fn heal_child<TX: DbTxMut + DbTx>(
+        &mut self,
+        tx: &TX,
+        account: Option<B256>,
+        path: Nibbles,
+        child: &RlpNode,
+    ) -> Result<(), StageError> {
+        if let Some(hash) = child.as_hash() {
+            if let Some(queue) = self.heal.as_mut() {
+                queue.push_back(HealTask { account, path, hash });
+            }
+            return Ok(())
+        }
+
+        let node = TrieNode::decode(&mut child.as_slice())
+            .map_err(|err| StageError::Fatal(Box::new(err)))?;
+        self.heal_node(tx, account, path, node)
+    }
+}
+
+impl<C> SnapSyncStage<C> {
+    /// Returns the stored progress of the stage.
+    fn progress(
+        &self,
+        provider: &impl StageCheckpointReader,
+    ) -> Result<Option<SnapSyncProgress>, StageError> {
+        let buf = provider.get_stage_checkpoint_progress(StageId::SnapSync)?.unwrap_or_default();
+        if buf.is_empty() {
+            return Ok(None)
+        }
+        SnapSyncProgress::decode(&buf)
+            .map(Some)
+            .ok_or_else(|| StageError::Fatal(Box::new(SnapSyncError::InvalidProgress)))
+    }
+
+    /// Removes the genesis state that was written when the database was initialized.
+    fn clear_state<TX: DbTxMut>(tx: &TX) -> Result<(), StageError> {
+        tx.clear::<tables::PlainAccountState>()?;
+        tx.clear::<tables::PlainStorageState>()?;
+        tx.clear::<tables::HashedAccounts>()?;
+        tx.clear::<tables::HashedStorages>()?;
+        tx.clear::<tables::AccountsTrie>()?;
+        tx.clear::<tables::StoragesTrie>()?;
+        Ok(())
+    }
+
+    /// Moves the stages that depend on the state to the pivot, and marks the history before the
+    /// pivot as pruned.
+    fn finish<Provider>(provider: &Provider, pivot: BlockNumber) -> Result<(), StageError>
+    where
+        Provider: StageCheckpointReader
+            + StageCheckpointWriter
+            + PruneCheckpointReader
+            + PruneCheckpointWriter,
+    {
+        for stage_id in [
+            StageId::Execution,
+            StageId::MerkleUnwind,
+            StageId::AccountHashing,
+            StageId::StorageHashing,
+            StageId::MerkleExecute,
+            StageId::IndexStorageHistory,
+            StageId::IndexAccountHistory,
+        ] {
+            if provider.get_stage_checkpoint(stage_id)?.unwrap_or_default().block_number < pivot {
+                provider.save_stage_checkpoint(stage_id, StageCheckpoint::new(pivot))?;
+            }
+        }
+        provider.save_stage_checkpoint_progress(StageId::MerkleExecute, Vec::new())?;
+
+        for segment in [PruneSegment::AccountHistory, PruneSegment::StorageHistory] {
+            let pruned = provider
+                .get_prune_checkpoint(segment)?
+                .and_then(|checkpoint| checkpoint.block_number)
+                .is_some_and(|block_number| block_number >= pivot);
+            if !pruned {
+                provider.save_prune_checkpoint(
+                    segment,
+                    PruneCheckpoint {
+                        block_number: Some(pivot),
+                        tx_number: None,
+                        prune_mode: PruneMode::Before(pivot + 1),
+                    },
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<Provider, C> Stage<Provider> for SnapSyncStage<C>
+where
+    Provider: DBProvider<Tx: DbTxMut>
+        + HeaderProvider
+        + TrieWriter
+        + StageCheckpointReader
+        + StageCheckpointWriter
+        + PruneCheckpointReader
+        + PruneCheckpointWriter,
+    C: SnapClient<Output: 'static> + Debug,
+{
+    /// Return the id of the stage
+    fn id(&self) -> StageId {
+        StageId::SnapSync
+    }
+
+    fn poll_execute_ready(
+        &mut self,
+        cx: &mut Context<'_>,
+        _input: ExecInput,
+    ) -> Poll<Result<(), StageError>> {
+        if self.failed_responses >= MAX_FAILED_RESPONSES {
+            self.failed_responses = 0;
+            return Poll::Ready(Err(StageError::Recoverable(Box::new(SnapSyncError::NoProgress {
+                pivot: self.pivot,
+            }))))
+        }
+
+        if !self.responses.is_empty() || self.inflight.is_empty() {
+            return Poll::Ready(Ok(()))
+        }
+
+        // wait for the first response, and take all others that are ready as well
+        while let Poll::Ready(Some(response)) = self.inflight.poll_next_unpin(cx) {
+            self.responses.push(response);
+        }
+        if self.responses.is_empty() {
+            Poll::Pending
+        } else {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    /// Download the state of the pivot block.
+    fn execute(&mut self, provider: &Provider, input: ExecInput) -> Result<ExecOutput, StageError> {
+        if input.target_reached() {
+            return Ok(ExecOutput::done(input.checkpoint()))
+        }
+
+        let target = input.target();
+        let pivot = target.saturating_sub(self.pivot_distance);
+        let mut progress = match self.progress(provider)? {
+            Some(progress) if progress.phase == SnapSyncPhase::Done => {
+                return Ok(ExecOutput::done(StageCheckpoint::new(target)))
+            }
+            Some(mut progress) => {
+                // peers only serve recent states, so a stale pivot is moved closer to the target
+                if pivot > progress.pivot + self.pivot_distance {
+                    info!(target: "sync::stages::snap_sync", from = progress.pivot, to = pivot, "Moving pivot");
+                    self.reset();
+                    progress.pivot = pivot;
+                    if matches!(progress.phase, SnapSyncPhase::StateRoot(_)) {
+                        progress.phase = SnapSyncPhase::StateRoot(None);
+                    }
+                }
+                progress
+            }
+            None => {
+                // only a node that hasn't executed any blocks can be snap synced
+                let executed =
+                    provider.get_stage_checkpoint(StageId::Execution)?.unwrap_or_default();
+                if executed.block_number > 0 || pivot == 0 {
+                    return Ok(ExecOutput::done(StageCheckpoint::new(target)))
+                }
+                if !provider.prune_modes_ref().has_receipts_pruning() {
+                    return Err(StageError::Fatal(Box::new(SnapSyncError::ReceiptsPruningRequired)))
+                }
+
+                info!(target: "sync::stages::snap_sync", pivot, "Starting snap sync");
+                Self::clear_state(provider.tx_ref())?;
+                self.reset();
+                SnapSyncProgress { pivot, phase: SnapSyncPhase::Accounts { next: B256::ZERO } }
+            }
+        };
+
+        self.pivot = progress.pivot;
+        let root_hash = provider
+            .header_by_number(progress.pivot)?
+            .ok_or_else(|| ProviderError::HeaderNotFound(progress.pivot.into()))?
+            .state_root();
+
+        for (request, response) in std::mem::take(&mut self.responses) {
+            let outcome = match response {
+                Ok(response) => {
+                    let (peer_id, response) = response.split();
+                    let outcome =
+                        self.on_response(provider.tx_ref(), request, response, root_hash)?;
+                    if outcome == ResponseOutcome::Invalid {
+                        debug!(target: "sync::stages::snap_sync", %peer_id, "Invalid snap response");
+                        self.client.report_bad_message(peer_id);
+                    }
+                    outcome
+                }
+                Err(error) => {
+                    debug!(target: "sync::stages::snap_sync", %error, "Snap request failed");
+                    self.on_failed_request(request);
+                    ResponseOutcome::Empty
+                }
+            };
+
+            if outcome == ResponseOutcome::Useful {
+                self.failed_responses = 0;
+            } else {
+                self.failed_responses += 1;
+            }
+        }
+
+        let done = self.advance(provider, &mut progress, root_hash)?;
+        provider.save_stage_checkpoint_progress(StageId::SnapSync, progress.encode())?;
+
+        if !done {
+            return Ok(ExecOutput { checkpoint: input.checkpoint(), done: false })
+        }
+
+        info!(target: "sync::stages::snap_sync", pivot = progress.pivot, "Finished snap sync");
+        Self::finish(provider, progress.pivot)?;
+        self.reset();
+        Ok(ExecOutput::done(StageCheckpoint::new(target)))
+    }
+
+    fn unwind(
+        &mut self,
+        provider: &Provider,
+        input: UnwindInput,
+    ) -> Result<UnwindOutput, StageError> {
+        if let Some(progress) = self.progress(provider)? {
+            if progress.phase == SnapSyncPhase::Done && input.unwind_to < progress.pivot {
+                return Err(StageError::Fatal(Box::new(SnapSyncError::UnwindBelowPivot {
+                    unwind_to: input.unwind_to,
+                    pivot: progress.pivot,
+                })))
+            }
+        }
+
+        Ok(UnwindOutput { checkpoint: StageCheckpoint::new(input.unwind_to) })
+    }
+}
+
+/// The future of a request in flight, resolving to the request and its response.
+type SnapRequestFut =
+    Pin<Box<dyn Future<Output = (SnapRequest, PeerRequestResult<SnapResponse>)> + Send + Sync>>;
+
+/// A request of the stage, holding the downloads that are requested.
+#[derive(Debug, Clone)]
+enum SnapRequest {
+    /// The accounts starting at the hash.
+    AccountRange { start: B256 },
+    /// The storage ranges of the accounts, only the first one can start after the first slot.
+    StorageRanges { tasks: Vec<StorageTask> },
+    /// The bytecodes with the hashes.
+    ByteCodes { hashes: Vec<B256> },
+    /// The trie nodes to heal.
+    TrieNodes { tasks: Vec<HealTask> },
+}
+
+/// The outcome of a response.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ResponseOutcome {
+    /// The response contained new data.
+    Useful,
+    /// The response was empty, the peer doesn't serve the state.
+    Empty,
+    /// The response was invalid.
+    Invalid,
+}
+
+/// The status of the account range that is currently downloaded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AccountRangeStatus {
+    /// The account range has to be requested.
+    Pending,
+    /// The account range is requested.
+    Requested,
+    /// The account range was received. Once the storage and bytecodes of its accounts are
+    /// downloaded, the next range starts at the hash, or the accounts are complete if it's
+    /// `None`.
+    Received(Option<B256>),
+}
+
+/// The storage range of an account to download.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct StorageTask {
+    /// The hashed address of the account.
+    account: B256,
+    /// The storage root of the account in the pivot state.
+    root: B256,
+    /// The first hashed slot of the range.
+    start: B256,
+}
+
+/// A trie node to heal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct HealTask {
+    /// The hashed address of the account if the node is part of a storage trie.
+    account: Option<B256>,
+    /// The path of the node in the trie.
+    path: Nibbles,
+    /// The hash of the node in the pivot state.
+    hash: B256,
+}
+
+/// The progress of the snap sync, stored as the stage checkpoint progress.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SnapSyncProgress {
+    /// The block whose state is downloaded.
+    pivot: BlockNumber,
+    /// The current phase.
+    phase: SnapSyncPhase,
+}
+
+/// The phases of the snap sync.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum SnapSyncPhase {
+    /// Downloading the account ranges, continuing at the hash.
+    Accounts { next: B256 },
+    /// Computing the state root, continuing at the checkpoint. The state is healed in this phase
+    /// if the root doesn't match, and the root is computed from scratch afterwards.
+    StateRoot(Option<MerkleCheckpoint>),
+    /// The state of the pivot is complete.
+    Done,
+}
+
+impl SnapSyncProgress {
+    fn encode(&self) -> Vec<u8> {
+        let mut buf = self.pivot.to_be_bytes().to_vec();
+        match &self.phase {
+            SnapSyncPhase::Accounts { next } => {
+                buf.push(0);
+                buf.extend_from_slice(next.as_slice());
+            }
+            SnapSyncPhase::StateRoot(checkpoint) => {
+                buf.push(1);
+                if let Some(checkpoint) = checkpoint {
+                    checkpoint.to_compact(&mut buf);
+                }
+            }
+            SnapSyncPhase::Done => buf.push(2),
+        }
+        buf
+    }
+
+    fn decode(buf: &[u8]) -> Option<Self> {
+        let (pivot, buf) = buf.split_first_chunk::<8>()?;
+        let (phase, buf) = buf.split_first()?;
+        let phase = match phase {
+            0 => SnapSyncPhase::Accounts { next: B256::try_from(buf).ok()? },
+            1 => SnapSyncPhase::StateRoot(
+                (!buf.is_empty()).then(|| MerkleCheckpoint::from_compact(buf, buf.len()).0),
+            ),
+            2 => SnapSyncPhase::Done,
+            _ => return None,
+        };
+        Some(Self { pivot: u64::from_be_bytes(*pivot), phase })
+    }
+}
+
+/// The account body in the slim format of the `snap` protocol.
+///
+/// Unlike the trie account, an empty storage root and an empty code hash are encoded as empty
+/// strings.
+#[derive(Debug, RlpDecodable)]
+struct SlimAccount {
+    nonce: u64,
+    balance: U256,
+    storage_root: Bytes,
+    code_hash: Bytes,
+}
+
+/// Decodes the slim account body, returning the account and its storage root.
+fn decode_slim_account(mut body: &[u8]) -> alloy_rlp::Result<(Account, B256)> {
+    let SlimAccount { nonce, balance, storage_root, code_hash } = SlimAccount::decode(&mut body)?;
+    let decode_hash = |bytes: Bytes| match bytes.len() {
+        0 => Ok(None),
+        32 => Ok(Some(B256::from_slice(&bytes))),
+        _ => Err(alloy_rlp::Error::UnexpectedLength),
+    };
+
+    let storage_root = decode_hash(storage_root)?.unwrap_or(EMPTY_ROOT_HASH);
+    let bytecode_hash = decode_hash(code_hash)?.filter(|code_hash| *code_hash != KECCAK_EMPTY);
+    Ok((Account { nonce, balance, bytecode_hash }, storage_root))
+}
+
+/// Deletes the hashed accounts in the range, together with their storage.
+fn delete_hashed_state<TX: DbTxMut + DbTx>(
+    tx: &TX,
+    start: B256,
+    end: B256,
+) -> Result<(), StageError> {
+    let mut accounts = tx.cursor_write::<tables::HashedAccounts>()?;
+    let mut walker = accounts.walk_range(start..=end)?;
+    while walker.next().transpose()?.is_some() {
+        walker.delete_current()?;
+    }
+
+    let mut storages = tx.cursor_dup_write::<tables::HashedStorages>()?;
+    while storages.seek(start)?.is_some_and(|(key, _)| key <= end) {
+        storages.delete_current_duplicates()?;
+    }
+    Ok(())
+}
+
+/// Deletes the local leaves of the trie under the path, except the ones under `keep`.
+///
+/// The trie is the account trie if `account` is `None`, and the storage trie of the account
+/// otherwise. Deleted accounts are deleted together with their storage.
+fn delete_leaves<TX: DbTxMut + DbTx>(
+    tx: &TX,
+    account: Option<B256>,
+    path: &Nibbles,
+    keep: Option<&Nibbles>,
+) -> Result<(), StageError> {
+    let should_delete = |key: &B256| {
+        let key = Nibbles::unpack(key);
+        key.starts_with(path) && !keep.is_some_and(|keep| key.starts_with(keep))
+    };
+    let start = pad_path_to_key(path);
+
+    match account {
+        None => {
+            let mut deleted = Vec::new();
+            let mut accounts = tx.cursor_write::<tables::HashedAccounts>()?;
+            let mut walker = accounts.walk(Some(start))?;
+            while let Some((key, _)) = walker.next().transpose()? {
+                if !Nibbles::unpack(key).starts_with(path) {
+                    break
+                }
+                if should_delete(&key) {
+                    walker.delete_current()?;
+                    deleted.push(key);
+                }
+            }
+
+            let mut storages = tx.cursor_dup_write::<tables::HashedStorages>()?;
+            for key in deleted {
+                if storages.seek_exact(key)?.is_some() {
+                    storages.delete_current_duplicates()?;
+                }
+            }
+        }
+        Some(hashed_address) => {
+            let mut storages = tx.cursor_dup_write::<tables::HashedStorages>()?;
+            let mut walker = storages.walk_dup(Some(hashed_address), Some(start))?;
+            while let Some((_, entry)) = walker.next().transpose()? {
+                if !Nibbles::unpack(entry.key).starts_with(path) {
+                    break
+                }
+                if should_delete(&entry.key) {
+                    walker.delete_current()?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Removes the tasks whose node is already in the local trie.
+fn remove_local_nodes<TX: DbTx>(
+    tx: &TX,
+    tasks: Vec<HealTask>,
+) -> Result<Vec<HealTask>, StageError> {
+    let mut targets = MultiProofTargets::default();
+    for task in &tasks {
+        match task.account {
+            Some(hashed_address) => {
+                targets.entry(hashed_address).or_default().insert(pad_path_to_key(&task.path));
+            }
+            None => {
+                targets.entry(pad_path_to_key(&task.path)).or_default();
+            }
+        }
+    }
+    let proof =
+        Proof::from_tx(tx).multiproof(targets).map_err(|err| StageError::Fatal(Box::new(err)))?;
+
+    Ok(tasks
+        .into_iter()
+        .filter(|task| {
+            let node = match task.account {
+                Some(hashed_address) => {
+                    proof.storages.get(&hashed_address).and_then(|s| s.subtree.get(&task.path))
+                }
+                None => proof.account_subtree.get(&task.path),
+            };
+            node.is_none_or(|node| keccak256(node) != task.hash)
+        })
+        .collect())
+}
+
+/// Returns the paths of the `GetTrieNodes` request for the tasks.
+///
+/// Account trie nodes are requested with their own path, and storage trie nodes are grouped by
+/// the hashed address of their account.
+fn trie_paths(tasks: &[HealTask]) -> Vec<TriePath> {
+    let mut paths: Vec<TriePath> = Vec::new();
+    for task in tasks {
+        let compact = Bytes::from(encode_path_leaf(&task.path, false).to_vec());
+        match task.account {
+            None => paths.push(TriePath { account_path: compact, slot_paths: Vec::new() }),
+            Some(hashed_address) => match paths.last_mut() {
+                Some(last)
+                    if !last.slot_paths.is_empty() &&
+                        last.account_path[..] == hashed_address[..] =>
+                {
+                    last.slot_paths.push(compact)
+                }
+                _ => paths.push(TriePath {
+                    account_path: hashed_address.into(),
+                    slot_paths: vec![compact],
+                }),
+            },
+        }
+    }
+    paths
+}
+
+/// Verifies that the leaves, sorted by key, are all leaves of the trie with the given root from
+/// `start` up to the last one, with the proof of the first and the last key of the range.
+///
+/// Without a proof, the leaves have to be all leaves of the trie. Returns whether the trie has
+/// more leaves after the range, or `None` if the range doesn't match the root.
+fn verify_range_proof(
+    root: B256,
+    start: B256,
+    leaves: &[(B256, Vec<u8>)],
+    proof: &[Bytes],
+) -> Option<bool> {
+    if leaves.first().is_some_and(|(key, _)| *key < start) ||
+        !leaves.windows(2).all(|pair| pair[0].0 < pair[1].0)
+    {
+        return None
+    }
+
+    let mut edges = RangeEdges {
+        nodes: proof.iter().map(|node| (keccak256(node), node)).collect(),
+        first: Nibbles::unpack(start),
+        last: leaves.last().map(|(key, _)| Nibbles::unpack(key)),
+        before: Vec::new(),
+        after: Vec::new(),
+    };
+    if !proof.is_empty() {
+        let on_last = edges.last.is_some();
+        edges.walk(&RlpNode::word_rlp(&root), Nibbles::default(), true, on_last)?;
+    }
+    let has_more = !edges.after.is_empty();
+
+    // the whole trie is outside of the range
+    if edges.before.iter().chain(&edges.after).any(|sibling| sibling.path().is_empty()) {
+        return leaves.is_empty().then_some(has_more)
+    }
+
+    let mut hash_builder = HashBuilder::default();
+    for sibling in edges.before {
+        sibling.add_to(&mut hash_builder);
+    }
+    for (key, value) in leaves {
+        hash_builder.add_leaf(Nibbles::unpack(key), value);
+    }
+    for sibling in edges.after {
+        sibling.add_to(&mut hash_builder);
+    }
+    (hash_builder.root() == root).then_some(has_more)
+}
+
+/// The subtries on both sides of a range of leaves, collected from the proof of its edges.
+#[derive(Debug)]
+struct RangeEdges<'a> {
+    /// The nodes of the proof by their hashes.
+    nodes: B256Map<&'a Bytes>,
+    /// The path of the first key of the range.
+    first: Nibbles,
+    /// The path of the last leaf of the range, if the range isn't empty.
+    last: Option<Nibbles>,
+    /// The subtries before the range, in order.
+    before: Vec<RangeSibling>,
+    /// The subtries after the range, in order.
+    after: Vec<RangeSibling>,
+}
+
+impl RangeEdges<'_> {
+    /// Decodes the node, looking it up in the proof if it isn't embedded in its parent.
+    fn node(&self, node: &RlpNode) -> Option<TrieNode> {
+        match node.as_hash() {
+            Some(hash) => TrieNode::decode(&mut &self.nodes.get(&hash)?[..]).ok(),
+            None => TrieNode::decode(&mut node.as_slice()).ok(),
+        }
+    }
+
+    /// Walks the node at the path, which is on the path of the first key of the range if
+    /// `on_first` is set and on the path of the last leaf if `on_last` is set, and collects the
+    /// subtries next to the range.
+    fn walk(&mut self, node: &RlpNode, path: Nibbles, on_first: bool, on_last: bool) -> Option<()> {
+        match self.node(node)? {
+            TrieNode::EmptyRoot => {}
+            TrieNode::Branch(branch) => {
+                let first = if on_first { Some(self.first.get(path.len())?) } else { None };
+                let last = match self.last.filter(|_| on_last) {
+                    Some(last) => Some(last.get(path.len())?),
+                    None => None,
+                };
+                for (nibble, child) in branch.as_ref().children() {
+                    let Some(child) = child else { continue };
+                    let mut child_path = path;
+                    child_path.push(nibble);
+                    if first.is_some_and(|first| nibble < first) {
+                        RangeSibling::collect(child, child_path, &mut self.before)?;
+                    } else if last.is_some_and(|last| nibble > last) {
+                        RangeSibling::collect(child, child_path, &mut self.after)?;
+                    } else if first == Some(nibble) || last == Some(nibble) {
+                        self.walk(child, child_path, first == Some(nibble), last == Some(nibble))?;
+                    }
+                }
+            }
+            TrieNode::Extension(extension) => {
+                let child_path = path.join(&extension.key);
+                if child_path.len() >= self.first.len() {
+                    return None
+                }
+
+                let (mut on_first, mut on_last) = (on_first, on_last);
+                if on_first {
+                    match child_path.cmp(&self.first.slice(..child_path.len())) {
+                        Ordering::Less => {
+                            return RangeSibling::collect(node, path, &mut self.before)
+                        }
+                        Ordering::Greater => on_first = false,
+                        Ordering::Equal => {}
+                    }
+                }
+                if let Some(last) = self.last.filter(|_| on_last) {
+                    match child_path.cmp(&last.slice(..child_path.len())) {
+                        Ordering::Greater => {
+                            return RangeSibling::collect(node, path, &mut self.after)
+                        }
+                        Ordering::Less => on_last = false,
+                        Ordering::Equal => {}
+                    }
+                }
+                if on_first || on_last {
+                    self.walk(&extension.child, child_path, on_first, on_last)?;
+                }
+            }
+            TrieNode::Leaf(leaf) => {
+                let key = path.join(&leaf.key);
+                if on_first && key < self.first {
+                    self.before.push(RangeSibling::Leaf(key, leaf.value));
+                } else if on_last && self.last.is_some_and(|last| key > last) {
+                    self.after.push(RangeSibling::Leaf(key, leaf.value));
+                }
+            }
+        }
+        Some(())
+    }
+}
+
+/// A subtrie next to a range of leaves.
+#[derive(Debug)]
+enum RangeSibling {
+    /// A leaf with its full path and value.
+    Leaf(Nibbles, Vec<u8>),
+    /// A node with its path and hash.
+    Node(Nibbles, B256),
+}
+
+impl RangeSibling {
+    /// Collects the subtrie at the path, decoding it if it's embedded in its parent.
+    fn collect(node: &RlpNode, path: Nibbles, siblings: &mut Vec<Self>) -> Option<()> {
+        if let Some(hash) = node.as_hash() {
+            siblings.push(Self::Node(path, hash));
+            return Some(())
+        }
+
+        match TrieNode::decode(&mut node.as_slice()).ok()? {
+            TrieNode::EmptyRoot => {}
+            TrieNode::Branch(branch) => {
+                for (nibble, child) in branch.as_ref().children() {
+                    if let Some(child) = child {
+                        let mut child_path = path;
+                        child_path.push(nibble);
+                        Self::collect(child, child_path, siblings)?;
+                    }
+                }
+            }
+            TrieNode::Extension(extension) => {
+                Self::collect(&extension.child, path.join(&extension.key), siblings)?;
+            }
+            TrieNode::Leaf(leaf) => siblings.push(Self::Leaf(path.join(&leaf.key), leaf.value)),
+        }
+        Some(())
+    }
+
+    /// Returns the path of the subtrie.
+    const fn path(&self) -> &Nibbles {
+        match self {
+            Self::Leaf(path, _) | Self::Node(path, _) => path,
+        }
+    }
+
+    /// Adds the subtrie to the hash builder.
+    fn add_to(self, hash_builder: &mut HashBuilder) {
+        match self {
+            Self::Leaf(path, value) => hash_builder.add_leaf(path, &value),
+            Self::Node(path, hash) => hash_builder.add_branch(path, hash, false),
+        }
+    }
+}
+
+/// Returns the smallest key that has the given path as prefix.
+fn pad_path_to_key(path: &Nibbles) -> B256 {
+    let mut padded = path.pack();
+    padded.resize(32, 0);
+    B256::from_slice(&padded)
+}
+
+/// Returns the key after the given one, or `None` if it's the last key.
+fn increment_key(key: B256) -> Option<B256> {
+    U256::from_be_bytes(key.0).checked_add(U256::from(1)).map(B256::from)
+}
+
+/// Returns the position of the key in the keyspace, between 0 and 1.
+fn key_progress(key: B256) -> f64 {
+    u64::from_be_bytes(key[..8].try_into().expect("8 bytes")) as f64 / u64::MAX as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::TestStageDB;
+    use alloy_consensus::Header;
+    use alloy_genesis::{Genesis, GenesisAccount};
+    use alloy_primitives::Address;
+    use alloy_trie::proof::ProofRetainer;
+    use reth_chainspec::ChainSpec;
+    use reth_db::{test_utils::TempDatabase, DatabaseEnv};
+    use reth_db_api::{database::Database, table::Table};
+    use reth_db_common::init::init_genesis;
+    use reth_network::{
+        snap_client::SnapFetchClient,
+        snap_requests::{SnapProtocolHandler, SnapRequestHandler},
+        test_utils::Testnet,
+    };
+    use reth_primitives_traits::SealedHeader;
+    use reth_provider::{
+        providers::BlockchainProvider,
+        test_utils::{create_test_provider_factory_with_chain_spec, MockNodeTypesWithDB},
+        DatabaseProvider, DatabaseProviderFactory, ProviderFactory,
+    };
+    use reth_prune_types::PruneModes;
+    use reth_stages_api::StageExt;
+    use reth_storage_api::{AccountReader, StateProvider};
+    use std::{sync::Arc, time::Duration};
+    use tokio::sync::mpsc;
+
+    type TestProvider =
+        DatabaseProvider<<TempDatabase<DatabaseEnv> as Database>::TXMut, MockNodeTypesWithDB>;
+
+    const CONTRACT: Address = Address::new([0x11; 20]);
+    const CODE: [u8; 4] = [0x60, 0x00, 0x60, 0x00];
+
+    /// Returns the factory of a node whose genesis has some accounts and a contract with storage,
+    /// and the state root of the genesis.
+    fn server_factory() -> (ProviderFactory<MockNodeTypesWithDB>, B256) {
+        let storage =
+            (1..=8u64).map(|slot| (B256::with_last_byte(slot as u8), B256::with_last_byte(0xff)));
+        let genesis = Genesis::default().extend_accounts(
+            (1..=32u8)
+                .map(|i| {
+                    (Address::new([i; 20]), GenesisAccount::default().with_balance(U256::from(i)))
+                })
+                .chain([(
+                    CONTRACT,
+                    GenesisAccount::default()
+                        .with_nonce(Some(1))
+                        .with_code(Some(Bytes::from_static(&CODE)))
+                        .with_storage(Some(storage.collect())),
+                )]),
+        );
+        let factory =
+            create_test_provider_factory_with_chain_spec(Arc::new(ChainSpec::from(genesis)));
+        init_genesis(&factory).unwrap();
+        let root = factory.sealed_header(0).unwrap().unwrap().state_root;
+        (factory, root)
+    }
+
+    /// Spawns a network of two peers where the first one serves the state of the factory, and
+    /// returns the client of the second one together with the handle of the network.
+    async fn connect_to_server(
+        factory: ProviderFactory<MockNodeTypesWithDB>,
+    ) -> (SnapFetchClient, impl Send) {
+        let mut net = Testnet::create(2).await;
+
+        let (tx, rx) = mpsc::channel(16);
+        tokio::spawn(SnapRequestHandler::new(BlockchainProvider::new(factory).unwrap(), rx));
+        net.peers_mut()[0].add_rlpx_sub_protocol(SnapProtocolHandler::new(tx));
+
+        let client_protocol = SnapProtocolHandler::client_only();
+        let client = client_protocol.client();
+        net.peers_mut()[1].add_rlpx_sub_protocol(client_protocol);
+
+        let handle = net.spawn();
+        handle.connect_peers().await;
+        (client, handle)
+    }
+
+    /// Returns a database with the headers up to the pivot, whose state root is the given one.
+    fn client_db(root: B256) -> TestStageDB {
+        let mut db = TestStageDB::default();
+        db.factory = db
+            .factory
+            .clone()
+            .with_prune_modes(PruneModes { receipts: Some(PruneMode::Full), ..Default::default() });
+
+        let genesis = SealedHeader::seal_slow(Header::default());
+        let pivot = SealedHeader::seal_slow(Header {
+            number: 1,
+            parent_hash: genesis.hash(),
+            state_root: root,
+            ..Default::default()
+        });
+        db.insert_headers([&genesis, &pivot]).unwrap();
+        db
+    }
+
+    /// Runs the stage until it's done.
+    async fn run_stage(stage: &mut SnapSyncStage<SnapFetchClient>, db: &TestStageDB, target: u64) {
+        let input = ExecInput { target: Some(target), checkpoint: None };
+        let run = async {
+            loop {
+                StageExt::<TestProvider>::execute_ready(stage, input).await.unwrap();
+                let provider = db.factory.database_provider_rw().unwrap();
+                let output = Stage::<TestProvider>::execute(stage, &provider, input).unwrap();
+                provider.commit().unwrap();
+                if output.done {
+                    assert_eq!(output.checkpoint, StageCheckpoint::new(target));
+                    break
+                }
+            }
+        };
+        tokio::time::timeout(Duration::from_secs(60), run).await.expect("snap sync timed out");
+    }
+
+    /// Returns all entries of the table.
+    fn table<T: Table>(factory: &ProviderFactory<MockNodeTypesWithDB>) -> Vec<(T::Key, T::Value)> {
+        let provider = factory.provider().unwrap();
+        let mut cursor = provider.tx_ref().cursor_read::<T>().unwrap();
+        cursor.walk(None).unwrap().collect::<Result<_, _>>().unwrap()
+    }
+
+    /// Asserts that the client downloaded the state of the server, and that the stages after the
+    /// snap sync resume from the pivot.
+    fn assert_synced(server: &ProviderFactory<MockNodeTypesWithDB>, db: &TestStageDB, pivot: u64) {
+        assert_eq!(
+            table::<tables::HashedAccounts>(server),
+            table::<tables::HashedAccounts>(&db.factory)
+        );
+        assert_eq!(
+            table::<tables::HashedStorages>(server),
+            table::<tables::HashedStorages>(&db.factory)
+        );
+        assert_eq!(
+            table::<tables::AccountsTrie>(server),
+            table::<tables::AccountsTrie>(&db.factory)
+        );
+        let code =
+            db.factory.provider().unwrap().tx_ref().get::<tables::Bytecodes>(keccak256(CODE));
+        assert_eq!(
+            code.unwrap().map(|code| code.original_bytes()),
+            Some(Bytes::from_static(&CODE))
+        );
+
+        let provider = db.factory.provider().unwrap();
+        for stage_id in [StageId::Execution, StageId::MerkleExecute, StageId::IndexAccountHistory] {
+            assert_eq!(
+                provider.get_stage_checkpoint(stage_id).unwrap(),
+                Some(StageCheckpoint::new(pivot))
+            );
+        }
+        assert_eq!(
+            provider
+                .get_prune_checkpoint(PruneSegment::AccountHistory)
+                .unwrap()
+                .and_then(|checkpoint| checkpoint.block_number),
+            Some(pivot)
+        );
+
+        // the plain state is read from the hashed state
+        let state = db.factory.latest().unwrap();
+        assert_eq!(
+            state.basic_account(&CONTRACT).unwrap(),
+            server.latest().unwrap().basic_account(&CONTRACT).unwrap()
+        );
+        assert_eq!(
+            state.storage(CONTRACT, B256::with_last_byte(1)).unwrap(),
+            Some(U256::from(0xff))
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn snap_sync_from_peer() {
+        reth_tracing::init_test_tracing();
+        let (server, root) = server_factory();
+        let (client, _handle) = connect_to_server(server.clone()).await;
+        let db = client_db(root);
+
+        // small responses to download the state in multiple ranges
+        let mut stage = SnapSyncStage::new(client).with_pivot_distance(0).with_response_bytes(512);
+        run_stage(&mut stage, &db, 1).await;
+
+        assert_synced(&server, &db, 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn snap_sync_heals_state() {
+        reth_tracing::init_test_tracing();
+        let (server, root) = server_factory();
+        let (client, _handle) = connect_to_server(server.clone()).await;
+        let db = client_db(root);
+
+        // start with a copy of the server state that is missing an account, has a wrong storage
+        // slot and an account that doesn't exist
+        let provider = db.factory.database_provider_rw().unwrap();
+        let tx = provider.tx_ref();
+        for (key, account) in table::<tables::HashedAccounts>(&server) {
+            if key != keccak256(Address::new([0x01; 20])) {
+                tx.put::<tables::HashedAccounts>(key, account).unwrap();
+            }
+        }
+        tx.put::<tables::HashedAccounts>(B256::repeat_byte(0xab), Account::default()).unwrap();
+        for (key, entry) in table::<tables::HashedStorages>(&server) {
+            tx.put::<tables::HashedStorages>(key, StorageEntry { value: U256::from(1), ..entry })
+                .unwrap();
+        }
+        let progress = SnapSyncProgress { pivot: 1, phase: SnapSyncPhase::StateRoot(None) };
+        provider.save_stage_checkpoint_progress(StageId::SnapSync, progress.encode()).unwrap();
+        provider.commit().unwrap();
+
+        let mut stage = SnapSyncStage::new(client).with_pivot_distance(0);
+        run_stage(&mut stage, &db, 1).await;
+
+        assert_synced(&server, &db, 1);
+    }
+
+    #[test]
+    fn verify_range_proofs() {
+        let mut leaves = (0..64u64)
+            .map(|i| (keccak256(B256::from(U256::from(i))), alloy_rlp::encode(U256::from(i + 1))))
+            .collect::<Vec<_>>();
+        leaves.sort_unstable_by_key(|(key, _)| *key);
+        let proof = |targets: &[B256]| {
+            let retainer = ProofRetainer::from_iter(targets.iter().map(Nibbles::unpack));
+            let mut hash_builder = HashBuilder::default().with_proof_retainer(retainer);
+            for (key, value) in &leaves {
+                hash_builder.add_leaf(Nibbles::unpack(key), value);
+            }
+            let root = hash_builder.root();
+            let nodes = hash_builder.take_proof_nodes().into_nodes_sorted();
+            (root, nodes.into_iter().map(|(_, node)| node).collect::<Vec<_>>())
+        };
+        let (root, _) = proof(&[]);
+        let last = leaves[leaves.len() - 1].0;
+
+        // the whole trie doesn't need a proof, but a part of it does
+        assert_eq!(verify_range_proof(root, B256::ZERO, &leaves, &[]), Some(false));
+        assert_eq!(verify_range_proof(root, B256::ZERO, &leaves[..20], &[]), None);
+
+        // a range in the middle of the trie
+        let start = increment_key(leaves[9].0).unwrap();
+        let (_, middle) = proof(&[start, leaves[20].0]);
+        assert_eq!(verify_range_proof(root, start, &leaves[10..=20], &middle), Some(true));
+
+        // a range that ends with the last leaf
+        let start = leaves[50].0;
+        let (_, tail) = proof(&[start, last]);
+        assert_eq!(verify_range_proof(root, start, &leaves[50..], &tail), Some(false));
+
+        // a range with a missing or a wrong leaf
+        let mut missing = leaves[10..=20].to_vec();
+        missing.remove(5);
+        let start = increment_key(leaves[9].0).unwrap();
+        assert_eq!(verify_range_proof(root, start, &missing, &middle), None);
+        let mut wrong = leaves[10..=20].to_vec();
+        wrong[5].1 = alloy_rlp::encode(U256::from(100));
+        assert_eq!(verify_range_proof(root, start, &wrong, &middle), None);
+
+        // an empty range has to prove that there are no leaves after the start
+        let start = increment_key(last).unwrap();
+        let (_, after_last) = proof(&[start]);
+        assert_eq!(verify_range_proof(root, start, &[], &after_last), Some(false));
+        let start = leaves[60].0;
+        let (_, hidden) = proof(&[start]);
+        assert_eq!(verify_range_proof(root, start, &[], &hidden), None);
+    }
+
+    #[test]
+    fn skip_executed_node() {
+        let db = client_db(B256::ZERO);
+        db.commit(|tx| {
+            Ok(tx.put::<tables::StageCheckpoints>(
+                StageId::Execution.to_string(),
+                StageCheckpoint::new(1),
+            )?)
+        })
+        .unwrap();
+
+        let mut stage = SnapSyncStage::new(SnapProtocolHandler::client_only().client());
+        let provider = db.factory.database_provider_rw().unwrap();
+        let input = ExecInput { target: Some(100), checkpoint: None };
+        let output = stage.execute(&provider, input).unwrap();
+        assert_eq!(output, ExecOutput::done(StageCheckpoint::new(100)));
+        assert!(stage.progress(&provider).unwrap().is_none());
+    }
+
+    #[test]
+    fn progress_roundtrip() {
+        let progresses = [
+            SnapSyncProgress { pivot: 1, phase: SnapSyncPhase::Accounts { next: B256::ZERO } },
+            SnapSyncProgress { pivot: 2, phase: SnapSyncPhase::StateRoot(None) },
+            SnapSyncProgress {
+                pivot: 3,
+                phase: SnapSyncPhase::StateRoot(Some(MerkleCheckpoint::new(
+                    3,
+                    B256::repeat_byte(1),
+                    Vec::new(),
+                    Default::default(),
+                ))),
+            },
+            SnapSyncProgress { pivot: 4, phase: SnapSyncPhase::Done },
+        ];
+        for progress in progresses {
+            assert_eq!(SnapSyncProgress::decode(&progress.encode()), Some(progress));
+        }
+        assert_eq!(SnapSyncProgress::decode(&[0; 8]), None);
+    }
+}
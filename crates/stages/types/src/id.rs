@@ -9,6 +9,7 @@ pub enum StageId {
     )]
     StaticFile,
     Era,
+    S3,
     Headers,
     Bodies,
     SenderRecovery,
@@ -23,14 +24,16 @@ pub enum StageId {
     IndexAccountHistory,
     Prune,
     Finish,
+    SnapSync,
     /// Other custom stage with a provided string identifier.
     Other(&'static str),
 }
 
 impl StageId {
     /// All supported Stages
-    pub const ALL: [Self; 15] = [
+    pub const ALL: [Self; 16] = [
         Self::Era,
+        Self::S3,
         Self::Headers,
         Self::Bodies,
         Self::SenderRecovery,
@@ -66,6 +69,7 @@ impl StageId {
             #[expect(deprecated)]
             Self::StaticFile => "StaticFile",
             Self::Era => "Era",
+            Self::S3 => "S3",
             Self::Headers => "Headers",
             Self::Bodies => "Bodies",
             Self::SenderRecovery => "SenderRecovery",
@@ -80,13 +84,15 @@ impl StageId {
             Self::IndexStorageHistory => "IndexStorageHistory",
             Self::Prune => "Prune",
             Self::Finish => "Finish",
+            Self::SnapSync => "SnapSync",
             Self::Other(s) => s,
         }
     }
 
-    /// Returns true if it's a downloading stage [`StageId::Headers`] or [`StageId::Bodies`]
+    /// Returns true if it's a downloading stage [`StageId::Era`], [`StageId::S3`],
+    /// [`StageId::Headers`] or [`StageId::Bodies`]
     pub const fn is_downloading_stage(&self) -> bool {
-        matches!(self, Self::Era | Self::Headers | Self::Bodies)
+        matches!(self, Self::Era | Self::S3 | Self::Headers | Self::Bodies)
     }
 
     /// Returns `true` if it's [`TransactionLookup`](StageId::TransactionLookup) stage.
@@ -113,6 +119,7 @@ mod tests {
     #[test]
     fn stage_id_as_string() {
         assert_eq!(StageId::Era.to_string(), "Era");
+        assert_eq!(StageId::S3.to_string(), "S3");
         assert_eq!(StageId::Headers.to_string(), "Headers");
         assert_eq!(StageId::Bodies.to_string(), "Bodies");
         assert_eq!(StageId::SenderRecovery.to_string(), "SenderRecovery");
@@ -125,6 +132,7 @@ mod tests {
         assert_eq!(StageId::IndexStorageHistory.to_string(), "IndexStorageHistory");
         assert_eq!(StageId::TransactionLookup.to_string(), "TransactionLookup");
         assert_eq!(StageId::Finish.to_string(), "Finish");
+        assert_eq!(StageId::SnapSync.to_string(), "SnapSync");
 
         assert_eq!(StageId::Other("Foo").to_string(), "Foo");
     }
@@ -134,6 +142,7 @@ mod tests {
         assert!(StageId::Headers.is_downloading_stage());
         assert!(StageId::Bodies.is_downloading_stage());
         assert!(StageId::Era.is_downloading_stage());
+        assert!(StageId::S3.is_downloading_stage());
 
         assert!(!StageId::Execution.is_downloading_stage());
     }
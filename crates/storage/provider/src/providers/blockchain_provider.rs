@@ -1,20 +1,24 @@
 #![allow(unused)]
 use crate::{
     providers::{ConsistentProvider, ProviderNodeTypes, StaticFileProvider},
-    AccountReader, BlockHashReader, BlockIdReader, BlockNumReader, BlockReader, BlockReaderIdExt,
-    BlockSource, CanonChainTracker, CanonStateNotifications, CanonStateSubscriptions,
-    ChainSpecProvider, ChainStateBlockReader, ChangeSetReader, DatabaseProvider,
-    DatabaseProviderFactory, FullProvider, HashedPostStateProvider, HeaderProvider, ProviderError,
-    ProviderFactory, PruneCheckpointReader, ReceiptProvider, ReceiptProviderIdExt,
-    StageCheckpointReader, StateProviderBox, StateProviderFactory, StateReader,
-    StaticFileProviderFactory, TransactionVariant, TransactionsProvider,
+    AccountHistoryReader, AccountReader, AccountStateBefore, BadBlockReader, BlockHashReader,
+    BlockIdReader, BlockNumReader, BlockReader, BlockReaderIdExt, BlockSource, CanonChainTracker,
+    CanonStateNotifications, CanonStateSubscriptions, ChainSpecProvider, ChainStateBlockReader,
+    ChangeSetReader, DatabaseProvider, DatabaseProviderFactory, DiskUsage, DiskUsageReader,
+    FullProvider, HashedAccountDump, HashedPostStateProvider, HashedStateRangeReader,
+    HeaderProvider, ProviderError, ProviderFactory, PruneCheckpointReader, ReceiptProvider,
+    ReceiptProviderIdExt, StageCheckpointReader, StateProviderBox, StateProviderFactory,
+    StateReader, StaticFileProviderFactory, StaticFileRangeReader, TransactionVariant,
+    TransactionsProvider,
 };
 use alloy_consensus::{transaction::TransactionMeta, Header};
 use alloy_eips::{
     eip4895::{Withdrawal, Withdrawals},
     BlockHashOrNumber, BlockId, BlockNumHash, BlockNumberOrTag,
 };
-use alloy_primitives::{Address, BlockHash, BlockNumber, Sealable, TxHash, TxNumber, B256, U256};
+use alloy_primitives::{
+    map::B256Map, Address, BlockHash, BlockNumber, Sealable, TxHash, TxNumber, B256, U256,
+};
 use alloy_rpc_types_engine::ForkchoiceState;
 use reth_chain_state::{
     BlockState, CanonicalInMemoryState, ForkChoiceNotifications, ForkChoiceSubscriptions,
@@ -35,15 +39,17 @@ use reth_primitives_traits::{
 };
 use reth_prune_types::{PruneCheckpoint, PruneSegment};
 use reth_stages_types::{StageCheckpoint, StageId};
+use reth_static_file_types::StaticFileSegment;
 use reth_storage_api::{
     BlockBodyIndicesProvider, DBProvider, NodePrimitivesProvider, StateCommitmentProvider,
     StorageChangeSetReader,
 };
 use reth_storage_errors::provider::ProviderResult;
-use reth_trie::HashedPostState;
+use reth_trie::{HashedPostState, MultiProof};
 use reth_trie_db::StateCommitment;
 use revm_database::BundleState;
 use std::{
+    collections::BTreeMap,
     ops::{Add, RangeBounds, RangeInclusive, Sub},
     sync::Arc,
     time::Instant,
@@ -185,6 +191,21 @@ impl<N: ProviderNodeTypes> StaticFileProviderFactory for BlockchainProvider<N> {
     }
 }
 
+impl<N: ProviderNodeTypes> StaticFileRangeReader for BlockchainProvider<N> {
+    fn static_file_block_range(
+        &self,
+        segment: StaticFileSegment,
+    ) -> ProviderResult<Option<RangeInclusive<BlockNumber>>> {
+        self.database.static_file_provider().static_file_block_range(segment)
+    }
+}
+
+impl<N: ProviderNodeTypes> DiskUsageReader for BlockchainProvider<N> {
+    fn disk_usage(&self) -> ProviderResult<DiskUsage> {
+        self.database.disk_usage()
+    }
+}
+
 impl<N: ProviderNodeTypes> HeaderProvider for BlockchainProvider<N> {
     type Header = HeaderTy<N>;
 
@@ -676,6 +697,16 @@ where
     }
 }
 
+impl<N: ProviderNodeTypes> BadBlockReader for BlockchainProvider<N> {
+    fn bad_blocks(&self) -> ProviderResult<Vec<SealedBlock<Self::Block>>> {
+        Ok(self.canonical_in_memory_state.bad_blocks())
+    }
+
+    fn bad_block_by_hash(&self, hash: B256) -> ProviderResult<Option<SealedBlock<Self::Block>>> {
+        Ok(self.canonical_in_memory_state.bad_block_by_hash(hash))
+    }
+}
+
 impl<N: ProviderNodeTypes> CanonStateSubscriptions for BlockchainProvider<N> {
     fn subscribe_to_canonical_state(&self) -> CanonStateNotifications<Self::Primitives> {
         self.canonical_in_memory_state.subscribe_canon_state()
@@ -714,6 +745,76 @@ impl<N: ProviderNodeTypes> ChangeSetReader for BlockchainProvider<N> {
     }
 }
 
+impl<N: ProviderNodeTypes> HashedStateRangeReader for BlockchainProvider<N> {
+    fn hashed_accounts_range(
+        &self,
+        block_number: BlockNumber,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<Vec<(B256, Account)>> {
+        self.consistent_provider()?.hashed_accounts_range(block_number, start, limit)
+    }
+
+    fn hashed_accounts_dump(
+        &self,
+        block_number: BlockNumber,
+        start: B256,
+        limit: usize,
+        storage_limit: Option<usize>,
+    ) -> ProviderResult<Vec<HashedAccountDump>> {
+        self.consistent_provider()?.hashed_accounts_dump(block_number, start, limit, storage_limit)
+    }
+
+    fn hashed_accounts_range_proof(
+        &self,
+        block_number: BlockNumber,
+        start: B256,
+        is_done: &mut dyn FnMut(&HashedAccountDump) -> bool,
+    ) -> ProviderResult<(Vec<HashedAccountDump>, MultiProof)> {
+        self.consistent_provider()?.hashed_accounts_range_proof(block_number, start, is_done)
+    }
+
+    fn hashed_storage_range(
+        &self,
+        block_number: BlockNumber,
+        hashed_address: B256,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<Vec<(B256, U256)>> {
+        self.consistent_provider()?.hashed_storage_range(block_number, hashed_address, start, limit)
+    }
+
+    fn storage_preimages(
+        &self,
+        address: Address,
+        hashed_slots: &[B256],
+        max_entries: usize,
+    ) -> ProviderResult<B256Map<B256>> {
+        self.consistent_provider()?.storage_preimages(address, hashed_slots, max_entries)
+    }
+}
+
+impl<N: ProviderNodeTypes> AccountHistoryReader for BlockchainProvider<N> {
+    fn account_history_blocks(
+        &self,
+        address: Address,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<Vec<BlockNumber>> {
+        self.consistent_provider()?.account_history_blocks(address, range)
+    }
+
+    fn lowest_history_block(&self) -> ProviderResult<BlockNumber> {
+        self.consistent_provider()?.lowest_history_block()
+    }
+
+    fn modified_accounts(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<BTreeMap<Address, AccountStateBefore>> {
+        self.consistent_provider()?.modified_accounts(range)
+    }
+}
+
 impl<N: ProviderNodeTypes> AccountReader for BlockchainProvider<N> {
     /// Get basic account information.
     fn basic_account(&self, address: &Address) -> ProviderResult<Option<Account>> {
@@ -1,7 +1,8 @@
 use super::{DatabaseProviderRO, ProviderFactory, ProviderNodeTypes};
 use crate::{
-    providers::StaticFileProvider, AccountReader, BlockHashReader, BlockIdReader, BlockNumReader,
-    BlockReader, BlockReaderIdExt, BlockSource, ChainSpecProvider, ChangeSetReader, HeaderProvider,
+    providers::StaticFileProvider, AccountHistoryReader, AccountReader, AccountStateBefore,
+    BlockHashReader, BlockIdReader, BlockNumReader, BlockReader, BlockReaderIdExt, BlockSource,
+    ChainSpecProvider, ChangeSetReader, HashedAccountDump, HashedStateRangeReader, HeaderProvider,
     ProviderError, PruneCheckpointReader, ReceiptProvider, ReceiptProviderIdExt,
     StageCheckpointReader, StateReader, StaticFileProviderFactory, TransactionVariant,
     TransactionsProvider,
@@ -12,7 +13,7 @@ use alloy_eips::{
     HashOrNumber,
 };
 use alloy_primitives::{
-    map::{hash_map, HashMap},
+    map::{hash_map, B256Map, HashMap},
     Address, BlockHash, BlockNumber, TxHash, TxNumber, B256, U256,
 };
 use reth_chain_state::{BlockState, CanonicalInMemoryState, MemoryOverlayStateProviderRef};
@@ -28,8 +29,11 @@ use reth_storage_api::{
     StorageChangeSetReader, TryIntoHistoricalStateProvider,
 };
 use reth_storage_errors::provider::ProviderResult;
+use reth_trie::{HashedPostState, KeyHasher, MultiProof};
+use reth_trie_db::StateCommitment;
 use revm_database::states::PlainStorageRevert;
 use std::{
+    collections::BTreeMap,
     ops::{Add, Bound, RangeBounds, RangeInclusive, Sub},
     sync::Arc,
 };
@@ -1349,17 +1353,20 @@ impl<N: ProviderNodeTypes> StorageChangeSetReader for ConsistentProvider<N> {
             // Perform checks on whether or not changesets exist for the block.
 
             // No prune checkpoint means history should exist and we should `unwrap_or(true)`
-            let storage_history_exists = self
-                .storage_provider
-                .get_prune_checkpoint(PruneSegment::StorageHistory)?
-                .and_then(|checkpoint| {
-                    // return true if the block number is ahead of the prune checkpoint.
-                    //
-                    // The checkpoint stores the highest pruned block number, so we should make
-                    // sure the block_number is strictly greater.
-                    checkpoint.block_number.map(|checkpoint| block_number > checkpoint)
-                })
-                .unwrap_or(true);
+            let mut storage_history_exists = true;
+            for segment in [PruneSegment::StorageHistory, PruneSegment::ChangeSets] {
+                storage_history_exists &= self
+                    .storage_provider
+                    .get_prune_checkpoint(segment)?
+                    .and_then(|checkpoint| {
+                        // return true if the block number is ahead of the prune checkpoint.
+                        //
+                        // The checkpoint stores the highest pruned block number, so we should make
+                        // sure the block_number is strictly greater.
+                        checkpoint.block_number.map(|checkpoint| block_number > checkpoint)
+                    })
+                    .unwrap_or(true);
+            }
 
             if !storage_history_exists {
                 return Err(ProviderError::StateAtBlockPruned(block_number))
@@ -1395,17 +1402,20 @@ impl<N: ProviderNodeTypes> ChangeSetReader for ConsistentProvider<N> {
             // Perform checks on whether or not changesets exist for the block.
 
             // No prune checkpoint means history should exist and we should `unwrap_or(true)`
-            let account_history_exists = self
-                .storage_provider
-                .get_prune_checkpoint(PruneSegment::AccountHistory)?
-                .and_then(|checkpoint| {
-                    // return true if the block number is ahead of the prune checkpoint.
-                    //
-                    // The checkpoint stores the highest pruned block number, so we should make
-                    // sure the block_number is strictly greater.
-                    checkpoint.block_number.map(|checkpoint| block_number > checkpoint)
-                })
-                .unwrap_or(true);
+            let mut account_history_exists = true;
+            for segment in [PruneSegment::AccountHistory, PruneSegment::ChangeSets] {
+                account_history_exists &= self
+                    .storage_provider
+                    .get_prune_checkpoint(segment)?
+                    .and_then(|checkpoint| {
+                        // return true if the block number is ahead of the prune checkpoint.
+                        //
+                        // The checkpoint stores the highest pruned block number, so we should make
+                        // sure the block_number is strictly greater.
+                        checkpoint.block_number.map(|checkpoint| block_number > checkpoint)
+                    })
+                    .unwrap_or(true);
+            }
 
             if !account_history_exists {
                 return Err(ProviderError::StateAtBlockPruned(block_number))
@@ -1416,6 +1426,235 @@ impl<N: ProviderNodeTypes> ChangeSetReader for ConsistentProvider<N> {
     }
 }
 
+impl<N: ProviderNodeTypes> AccountHistoryReader for ConsistentProvider<N> {
+    fn account_history_blocks(
+        &self,
+        address: Address,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<Vec<BlockNumber>> {
+        // Blocks at or below the prune checkpoints have no history left, so the lookup starts at
+        // the lowest block with history.
+        let start = (*range.start()).max(self.lowest_history_block()?);
+        let end = *range.end();
+
+        // The last block of the in-memory chain is the lowest block number. Blocks from there on
+        // are not indexed yet, so their execution outcomes are inspected instead. In case of an
+        // overlap, the in-memory blocks take priority.
+        let in_memory_chain =
+            self.head_block.as_ref().map(|b| b.chain().collect::<Vec<_>>()).unwrap_or_default();
+        let lowest_memory_block =
+            in_memory_chain.last().map(|b| b.number()).unwrap_or(BlockNumber::MAX);
+
+        let storage_end = end.min(lowest_memory_block.saturating_sub(1));
+        let mut blocks = if start < lowest_memory_block && start <= storage_end {
+            self.storage_provider.account_history_blocks(address, start..=storage_end)?
+        } else {
+            Vec::new()
+        };
+
+        blocks.extend(
+            in_memory_chain
+                .iter()
+                .rev()
+                .filter(|block_state| (start..=end).contains(&block_state.number()))
+                .filter(|block_state| {
+                    block_state
+                        .block_ref()
+                        .execution_output
+                        .bundle
+                        .reverts
+                        .iter()
+                        .flatten()
+                        .any(|(changed, _)| *changed == address)
+                })
+                .map(|block_state| block_state.number()),
+        );
+
+        Ok(blocks)
+    }
+
+    fn lowest_history_block(&self) -> ProviderResult<BlockNumber> {
+        self.storage_provider.lowest_history_block()
+    }
+
+    fn modified_accounts(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<BTreeMap<Address, AccountStateBefore>> {
+        // Pruning the history segments also prunes the changesets, which would silently leave out
+        // changes from the result.
+        let (start, end) = (*range.start(), *range.end());
+        for segment in
+            [PruneSegment::AccountHistory, PruneSegment::StorageHistory, PruneSegment::ChangeSets]
+        {
+            if self
+                .storage_provider
+                .get_prune_checkpoint(segment)?
+                .and_then(|checkpoint| checkpoint.block_number)
+                .is_some_and(|pruned| start <= pruned)
+            {
+                return Err(ProviderError::StateAtBlockPruned(start))
+            }
+        }
+
+        let in_memory_chain =
+            self.head_block.as_ref().map(|b| b.chain().collect::<Vec<_>>()).unwrap_or_default();
+        let lowest_memory_block =
+            in_memory_chain.last().map(|b| b.number()).unwrap_or(BlockNumber::MAX);
+
+        let storage_end = end.min(lowest_memory_block.saturating_sub(1));
+        let mut accounts = if start < lowest_memory_block && start <= storage_end {
+            self.storage_provider.modified_accounts(start..=storage_end)?
+        } else {
+            BTreeMap::new()
+        };
+
+        // The in-memory blocks are newer than the database ones and are applied starting with the
+        // oldest one, so state that was already recorded is kept.
+        for block_state in
+            in_memory_chain.iter().rev().filter(|block_state| range.contains(&block_state.number()))
+        {
+            let reverts = block_state
+                .block_ref()
+                .execution_output
+                .bundle
+                .reverts
+                .clone()
+                .to_plain_state_reverts();
+            for (address, info) in reverts.accounts.into_iter().flatten() {
+                accounts.entry(address).or_default().record_info(info.map(Into::into));
+            }
+            for revert in reverts.storage.into_iter().flatten() {
+                let account = accounts.entry(revert.address).or_default();
+                for (slot, value) in revert.storage_revert {
+                    account.record_slot(slot.into(), value.to_previous_value());
+                }
+            }
+        }
+
+        Ok(accounts)
+    }
+}
+
+impl<N: ProviderNodeTypes> ConsistentProvider<N> {
+    /// Returns the hashed state that has to be applied on top of the database state to get the
+    /// state after the given block.
+    fn hashed_state_overlay(&self, block_number: BlockNumber) -> ProviderResult<HashedPostState> {
+        let last_database_block = self.storage_provider.last_block_number()?;
+        if block_number <= last_database_block {
+            return self.storage_provider.hashed_state_reverts(block_number)
+        }
+
+        // The database state is behind the requested block, so the in-memory blocks in between are
+        // applied on top of it, starting with the oldest one.
+        let mut state = HashedPostState::default();
+        if let Some(head_block) = &self.head_block {
+            let mut blocks = head_block
+                .chain()
+                .filter(|block_state| {
+                    (last_database_block + 1..=block_number).contains(&block_state.number())
+                })
+                .collect::<Vec<_>>();
+            blocks.reverse();
+            for block_state in blocks {
+                state.extend_ref(&block_state.block_ref().hashed_state);
+            }
+        }
+        Ok(state)
+    }
+}
+
+impl<N: ProviderNodeTypes> HashedStateRangeReader for ConsistentProvider<N> {
+    fn hashed_accounts_range(
+        &self,
+        block_number: BlockNumber,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<Vec<(B256, Account)>> {
+        let overlay = self.hashed_state_overlay(block_number)?.into_sorted();
+        self.storage_provider.hashed_accounts_range_with_overlay(&overlay, start, limit)
+    }
+
+    fn hashed_accounts_dump(
+        &self,
+        block_number: BlockNumber,
+        start: B256,
+        limit: usize,
+        storage_limit: Option<usize>,
+    ) -> ProviderResult<Vec<HashedAccountDump>> {
+        let overlay = self.hashed_state_overlay(block_number)?;
+        self.storage_provider.hashed_accounts_dump_with_overlay(
+            overlay,
+            start,
+            limit,
+            storage_limit,
+            |_| false,
+        )
+    }
+
+    fn hashed_accounts_range_proof(
+        &self,
+        block_number: BlockNumber,
+        start: B256,
+        is_done: &mut dyn FnMut(&HashedAccountDump) -> bool,
+    ) -> ProviderResult<(Vec<HashedAccountDump>, MultiProof)> {
+        let overlay = self.hashed_state_overlay(block_number)?;
+        self.storage_provider.hashed_accounts_range_proof_with_overlay(overlay, start, is_done)
+    }
+
+    fn hashed_storage_range(
+        &self,
+        block_number: BlockNumber,
+        hashed_address: B256,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<Vec<(B256, U256)>> {
+        let overlay = self.hashed_state_overlay(block_number)?.into_sorted();
+        self.storage_provider.hashed_storage_range_with_overlay(
+            &overlay,
+            hashed_address,
+            start,
+            limit,
+        )
+    }
+
+    fn storage_preimages(
+        &self,
+        address: Address,
+        hashed_slots: &[B256],
+        max_entries: usize,
+    ) -> ProviderResult<B256Map<B256>> {
+        let mut preimages =
+            self.storage_provider.storage_preimages(address, hashed_slots, max_entries)?;
+
+        // Slots that were only written by blocks that are not persisted yet are resolved from the
+        // in-memory execution outcomes.
+        if preimages.len() < hashed_slots.len() {
+            let in_memory_slots = self
+                .head_block
+                .iter()
+                .flat_map(|head_block| head_block.chain())
+                .filter_map(|block_state| {
+                    block_state.block_ref().execution_output.bundle.account(&address).map(
+                        |account| {
+                            account.storage.keys().map(|slot| B256::from(*slot)).collect::<Vec<_>>()
+                        },
+                    )
+                })
+                .flatten();
+            for slot in in_memory_slots {
+                let hashed_slot =
+                    <N::StateCommitment as StateCommitment>::KeyHasher::hash_key(slot);
+                if hashed_slots.contains(&hashed_slot) {
+                    preimages.insert(hashed_slot, slot);
+                }
+            }
+        }
+
+        Ok(preimages)
+    }
+}
+
 impl<N: ProviderNodeTypes> AccountReader for ConsistentProvider<N> {
     /// Get basic account information.
     fn basic_account(&self, address: &Address) -> ProviderResult<Option<Account>> {
@@ -13,7 +13,9 @@ use alloy_primitives::{Address, BlockHash, BlockNumber, TxHash, TxNumber, B256,
 use core::fmt;
 use reth_chainspec::ChainInfo;
 use reth_db::{init_db, mdbx::DatabaseArguments, DatabaseEnv};
-use reth_db_api::{database::Database, models::StoredBlockBodyIndices};
+use reth_db_api::{
+    database::Database, database_metrics::DatabaseMetrics, models::StoredBlockBodyIndices,
+};
 use reth_errors::{RethError, RethResult};
 use reth_node_types::{
     BlockTy, HeaderTy, NodeTypes, NodeTypesWithDB, NodeTypesWithDBAdapter, ReceiptTy, TxTy,
@@ -23,8 +25,8 @@ use reth_prune_types::{PruneCheckpoint, PruneModes, PruneSegment};
 use reth_stages_types::{StageCheckpoint, StageId};
 use reth_static_file_types::StaticFileSegment;
 use reth_storage_api::{
-    BlockBodyIndicesProvider, NodePrimitivesProvider, StateCommitmentProvider,
-    TryIntoHistoricalStateProvider,
+    BlockBodyIndicesProvider, DiskUsage, DiskUsageReader, NodePrimitivesProvider,
+    StateCommitmentProvider, TryIntoHistoricalStateProvider,
 };
 use reth_storage_errors::provider::ProviderResult;
 use reth_trie::HashedPostState;
@@ -39,6 +41,7 @@ use std::{
 use tracing::trace;
 
 mod provider;
+use provider::SnapSyncedFlag;
 pub use provider::{DatabaseProvider, DatabaseProviderRO, DatabaseProviderRW};
 
 use super::ProviderNodeTypes;
@@ -65,6 +68,8 @@ pub struct ProviderFactory<N: NodeTypesWithDB> {
     prune_modes: PruneModes,
     /// The node storage handler.
     storage: Arc<N::Storage>,
+    /// Whether the state was snap synced, shared with all providers.
+    snap_synced: SnapSyncedFlag,
 }
 
 impl<N: NodeTypes> ProviderFactory<NodeTypesWithDBAdapter<N, Arc<DatabaseEnv>>> {
@@ -87,6 +92,7 @@ impl<N: NodeTypesWithDB> ProviderFactory<N> {
             static_file_provider,
             prune_modes: PruneModes::none(),
             storage: Default::default(),
+            snap_synced: Default::default(),
         }
     }
 
@@ -129,6 +135,7 @@ impl<N: NodeTypesWithDB<DB = Arc<DatabaseEnv>>> ProviderFactory<N> {
             static_file_provider,
             prune_modes: PruneModes::none(),
             storage: Default::default(),
+            snap_synced: Default::default(),
         })
     }
 }
@@ -148,7 +155,8 @@ impl<N: ProviderNodeTypes> ProviderFactory<N> {
             self.static_file_provider.clone(),
             self.prune_modes.clone(),
             self.storage.clone(),
-        ))
+        )
+        .with_snap_synced(self.snap_synced.clone()))
     }
 
     /// Returns a provider with a created `DbTxMut` inside, which allows fetching and updating
@@ -157,13 +165,16 @@ impl<N: ProviderNodeTypes> ProviderFactory<N> {
     /// open.
     #[track_caller]
     pub fn provider_rw(&self) -> ProviderResult<DatabaseProviderRW<N::DB, N>> {
-        Ok(DatabaseProviderRW(DatabaseProvider::new_rw(
-            self.db.tx_mut()?,
-            self.chain_spec.clone(),
-            self.static_file_provider.clone(),
-            self.prune_modes.clone(),
-            self.storage.clone(),
-        )))
+        Ok(DatabaseProviderRW(
+            DatabaseProvider::new_rw(
+                self.db.tx_mut()?,
+                self.chain_spec.clone(),
+                self.static_file_provider.clone(),
+                self.prune_modes.clone(),
+                self.storage.clone(),
+            )
+            .with_snap_synced(self.snap_synced.clone()),
+        ))
     }
 
     /// State provider for latest block
@@ -600,6 +611,20 @@ impl<N: ProviderNodeTypes> PruneCheckpointReader for ProviderFactory<N> {
     }
 }
 
+impl<N: ProviderNodeTypes> DiskUsageReader for ProviderFactory<N> {
+    fn disk_usage(&self) -> ProviderResult<DiskUsage> {
+        let database = self
+            .db
+            .gauge_metrics()
+            .into_iter()
+            .filter(|(name, _, _)| *name == "db.table_size")
+            .map(|(_, size, _)| size as u64)
+            .sum();
+        let static_files = self.static_file_provider.directory_size()?;
+        Ok(DiskUsage { database, static_files })
+    }
+}
+
 impl<N: ProviderNodeTypes> HashedPostStateProvider for ProviderFactory<N> {
     fn hashed_post_state(&self, bundle_state: &BundleState) -> HashedPostState {
         HashedPostState::from_bundle_state::<<N::StateCommitment as StateCommitment>::KeyHasher>(
@@ -613,13 +638,14 @@ where
     N: NodeTypesWithDB<DB: fmt::Debug, ChainSpec: fmt::Debug, Storage: fmt::Debug>,
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let Self { db, chain_spec, static_file_provider, prune_modes, storage } = self;
+        let Self { db, chain_spec, static_file_provider, prune_modes, storage, snap_synced } = self;
         f.debug_struct("ProviderFactory")
             .field("db", &db)
             .field("chain_spec", &chain_spec)
             .field("static_file_provider", &static_file_provider)
             .field("prune_modes", &prune_modes)
             .field("storage", &storage)
+            .field("snap_synced", &snap_synced)
             .finish()
     }
 }
@@ -632,6 +658,7 @@ impl<N: NodeTypesWithDB> Clone for ProviderFactory<N> {
             static_file_provider: self.static_file_provider.clone(),
             prune_modes: self.prune_modes.clone(),
             storage: self.storage.clone(),
+            snap_synced: self.snap_synced.clone(),
         }
     }
 }
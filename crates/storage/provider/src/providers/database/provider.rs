@@ -2,21 +2,24 @@ use crate::{
     bundle_state::StorageRevertsIter,
     providers::{
         database::{chain::ChainStorage, metrics},
+        state::latest::hashed_account,
         static_file::StaticFileWriter,
         NodeTypesForProvider, StaticFileProvider,
     },
     to_range,
     traits::{
-        AccountExtReader, BlockSource, ChangeSetReader, ReceiptProvider, StageCheckpointWriter,
+        AccountExtReader, AccountHistoryReader, AccountStateBefore, BlockSource, ChangeSetReader,
+        ReceiptProvider, StageCheckpointWriter,
     },
     AccountReader, BlockBodyWriter, BlockExecutionWriter, BlockHashReader, BlockNumReader,
     BlockReader, BlockWriter, BundleStateInit, ChainStateBlockReader, ChainStateBlockWriter,
-    DBProvider, HashingWriter, HeaderProvider, HeaderSyncGapProvider, HistoricalStateProvider,
-    HistoricalStateProviderRef, HistoryWriter, LatestStateProvider, LatestStateProviderRef,
-    OriginalValuesKnown, ProviderError, PruneCheckpointReader, PruneCheckpointWriter, RevertsInit,
-    StageCheckpointReader, StateCommitmentProvider, StateProviderBox, StateWriter,
-    StaticFileProviderFactory, StatsReader, StorageLocation, StorageReader, StorageTrieWriter,
-    TransactionVariant, TransactionsProvider, TransactionsProviderExt, TrieWriter,
+    DBProvider, HashedAccountDump, HashedStateRangeReader, HashingWriter, HeaderProvider,
+    HeaderSyncGapProvider, HistoricalStateProvider, HistoricalStateProviderRef, HistoryWriter,
+    LatestStateProvider, LatestStateProviderRef, OriginalValuesKnown, ProviderError,
+    PruneCheckpointReader, PruneCheckpointWriter, RevertsInit, StageCheckpointReader,
+    StateCommitmentProvider, StateProviderBox, StateWriter, StaticFileProviderFactory, StatsReader,
+    StorageLocation, StorageReader, StorageTrieWriter, TransactionVariant, TransactionsProvider,
+    TransactionsProviderExt, TrieWriter,
 };
 use alloy_consensus::{
     transaction::{SignerRecoverable, TransactionMeta},
@@ -60,11 +63,18 @@ use reth_storage_api::{
 };
 use reth_storage_errors::provider::{ProviderResult, RootMismatch};
 use reth_trie::{
+    hashed_cursor::{HashedCursor, HashedCursorFactory, HashedPostStateCursorFactory},
+    metrics::TrieRootMetrics,
     prefix_set::{PrefixSet, PrefixSetMut, TriePrefixSets},
+    proof::Proof,
     updates::{StorageTrieUpdates, TrieUpdates},
-    HashedPostStateSorted, Nibbles, StateRoot, StoredNibbles,
+    HashedPostState, HashedPostStateSorted, KeyHasher, MultiProof, MultiProofTargets, Nibbles,
+    StateRoot, StorageRoot, StoredNibbles, TrieInput, TrieType,
+};
+use reth_trie_db::{
+    DatabaseHashedCursorFactory, DatabaseHashedPostState, DatabaseProof, DatabaseStateRoot,
+    DatabaseStorageTrieCursor, DatabaseTrieCursorFactory, StateCommitment,
 };
-use reth_trie_db::{DatabaseStateRoot, DatabaseStorageTrieCursor};
 use revm_database::states::{
     PlainStateReverts, PlainStorageChangeset, PlainStorageRevert, StateChangeset,
 };
@@ -73,7 +83,10 @@ use std::{
     collections::{BTreeMap, BTreeSet},
     fmt::Debug,
     ops::{Deref, DerefMut, Range, RangeBounds, RangeInclusive},
-    sync::{mpsc, Arc},
+    sync::{
+        atomic::{AtomicU8, Ordering as AtomicOrdering},
+        mpsc, Arc,
+    },
 };
 use tracing::{debug, trace};
 
@@ -145,6 +158,8 @@ pub struct DatabaseProvider<TX, N: NodeTypes> {
     prune_modes: PruneModes,
     /// Node storage handler.
     storage: Arc<N::Storage>,
+    /// Whether the state was snap synced, shared with the factory.
+    snap_synced: SnapSyncedFlag,
 }
 
 impl<TX, N: NodeTypes> DatabaseProvider<TX, N> {
@@ -152,6 +167,54 @@ impl<TX, N: NodeTypes> DatabaseProvider<TX, N> {
     pub const fn prune_modes_ref(&self) -> &PruneModes {
         &self.prune_modes
     }
+
+    /// Shares the given snap sync flag with the provider.
+    pub(crate) fn with_snap_synced(mut self, snap_synced: SnapSyncedFlag) -> Self {
+        self.snap_synced = snap_synced;
+        self
+    }
+}
+
+/// Caches whether the state was snap synced, see [`DBProvider::is_snap_synced`].
+///
+/// The flag is shared by a [`ProviderFactory`](crate::ProviderFactory) and its providers. It is
+/// loaded from the [`StageId::SnapSync`] checkpoint progress on first use and set once that
+/// progress is saved, so reads of state that is missing from the plain state don't need to look up
+/// the checkpoint.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SnapSyncedFlag(Arc<AtomicU8>);
+
+impl SnapSyncedFlag {
+    const UNKNOWN: u8 = 0;
+    const NOT_SYNCED: u8 = 1;
+    const SYNCED: u8 = 2;
+
+    /// Returns the flag, loading it with the given transaction if it isn't known yet.
+    pub(crate) fn get_or_load<TX: DbTx>(&self, tx: &TX) -> ProviderResult<bool> {
+        match self.0.load(AtomicOrdering::Relaxed) {
+            Self::SYNCED => return Ok(true),
+            Self::NOT_SYNCED => return Ok(false),
+            _ => {}
+        }
+
+        let synced = tx
+            .get::<tables::StageCheckpointProgresses>(StageId::SnapSync.to_string())?
+            .is_some_and(|progress| !progress.is_empty());
+        // a concurrent `set` takes precedence
+        let loaded = if synced { Self::SYNCED } else { Self::NOT_SYNCED };
+        let _ = self.0.compare_exchange(
+            Self::UNKNOWN,
+            loaded,
+            AtomicOrdering::Relaxed,
+            AtomicOrdering::Relaxed,
+        );
+        Ok(synced)
+    }
+
+    /// Marks the state as snap synced.
+    fn set(&self) {
+        self.0.store(Self::SYNCED, AtomicOrdering::Relaxed);
+    }
 }
 
 impl<TX: DbTx + 'static, N: NodeTypes> DatabaseProvider<TX, N> {
@@ -177,25 +240,19 @@ impl<TX: DbTx + 'static, N: NodeTypes> DatabaseProvider<TX, N> {
         // +1 as the changeset that we want is the one that was applied after this block.
         block_number += 1;
 
-        let account_history_prune_checkpoint =
-            self.get_prune_checkpoint(PruneSegment::AccountHistory)?;
-        let storage_history_prune_checkpoint =
-            self.get_prune_checkpoint(PruneSegment::StorageHistory)?;
+        let (account_history_pruned_block, storage_history_pruned_block) =
+            self.highest_pruned_history_blocks()?;
 
         let mut state_provider = HistoricalStateProviderRef::new(self, block_number);
 
         // If we pruned account or storage history, we can't return state on every historical block.
         // Instead, we should cap it at the latest prune checkpoint for corresponding prune segment.
-        if let Some(prune_checkpoint_block_number) =
-            account_history_prune_checkpoint.and_then(|checkpoint| checkpoint.block_number)
-        {
+        if let Some(prune_checkpoint_block_number) = account_history_pruned_block {
             state_provider = state_provider.with_lowest_available_account_history_block_number(
                 prune_checkpoint_block_number + 1,
             );
         }
-        if let Some(prune_checkpoint_block_number) =
-            storage_history_prune_checkpoint.and_then(|checkpoint| checkpoint.block_number)
-        {
+        if let Some(prune_checkpoint_block_number) = storage_history_pruned_block {
             state_provider = state_provider.with_lowest_available_storage_history_block_number(
                 prune_checkpoint_block_number + 1,
             );
@@ -234,14 +291,21 @@ impl<TX: Debug + Send + Sync, N: NodeTypes<ChainSpec: EthChainSpec + 'static>> C
 
 impl<TX: DbTxMut, N: NodeTypes> DatabaseProvider<TX, N> {
     /// Creates a provider with an inner read-write transaction.
-    pub const fn new_rw(
+    pub fn new_rw(
         tx: TX,
         chain_spec: Arc<N::ChainSpec>,
         static_file_provider: StaticFileProvider<N::Primitives>,
         prune_modes: PruneModes,
         storage: Arc<N::Storage>,
     ) -> Self {
-        Self { tx, chain_spec, static_file_provider, prune_modes, storage }
+        Self {
+            tx,
+            chain_spec,
+            static_file_provider,
+            prune_modes,
+            storage,
+            snap_synced: Default::default(),
+        }
     }
 }
 
@@ -382,25 +446,19 @@ impl<TX: DbTx + 'static, N: NodeTypes> TryIntoHistoricalStateProvider for Databa
         // +1 as the changeset that we want is the one that was applied after this block.
         block_number += 1;
 
-        let account_history_prune_checkpoint =
-            self.get_prune_checkpoint(PruneSegment::AccountHistory)?;
-        let storage_history_prune_checkpoint =
-            self.get_prune_checkpoint(PruneSegment::StorageHistory)?;
+        let (account_history_pruned_block, storage_history_pruned_block) =
+            self.highest_pruned_history_blocks()?;
 
         let mut state_provider = HistoricalStateProvider::new(self, block_number);
 
         // If we pruned account or storage history, we can't return state on every historical block.
         // Instead, we should cap it at the latest prune checkpoint for corresponding prune segment.
-        if let Some(prune_checkpoint_block_number) =
-            account_history_prune_checkpoint.and_then(|checkpoint| checkpoint.block_number)
-        {
+        if let Some(prune_checkpoint_block_number) = account_history_pruned_block {
             state_provider = state_provider.with_lowest_available_account_history_block_number(
                 prune_checkpoint_block_number + 1,
             );
         }
-        if let Some(prune_checkpoint_block_number) =
-            storage_history_prune_checkpoint.and_then(|checkpoint| checkpoint.block_number)
-        {
+        if let Some(prune_checkpoint_block_number) = storage_history_pruned_block {
             state_provider = state_provider.with_lowest_available_storage_history_block_number(
                 prune_checkpoint_block_number + 1,
             );
@@ -517,14 +575,21 @@ where
 
 impl<TX: DbTx + 'static, N: NodeTypesForProvider> DatabaseProvider<TX, N> {
     /// Creates a provider with an inner read-only transaction.
-    pub const fn new(
+    pub fn new(
         tx: TX,
         chain_spec: Arc<N::ChainSpec>,
         static_file_provider: StaticFileProvider<N::Primitives>,
         prune_modes: PruneModes,
         storage: Arc<N::Storage>,
     ) -> Self {
-        Self { tx, chain_spec, static_file_provider, prune_modes, storage }
+        Self {
+            tx,
+            chain_spec,
+            static_file_provider,
+            prune_modes,
+            storage,
+            snap_synced: Default::default(),
+        }
     }
 
     /// Consume `DbTx` or `DbTxMut`.
@@ -870,7 +935,11 @@ impl<TX: DbTxMut + DbTx + 'static, N: NodeTypes> DatabaseProvider<TX, N> {
 
 impl<TX: DbTx, N: NodeTypes> AccountReader for DatabaseProvider<TX, N> {
     fn basic_account(&self, address: &Address) -> ProviderResult<Option<Account>> {
-        Ok(self.tx.get_by_encoded_key::<tables::PlainAccountState>(address)?)
+        match self.tx.get_by_encoded_key::<tables::PlainAccountState>(address)? {
+            Some(account) => Ok(Some(account)),
+            None if self.snap_synced.get_or_load(&self.tx)? => hashed_account(&self.tx, address),
+            None => Ok(None),
+        }
     }
 }
 
@@ -950,6 +1019,356 @@ impl<TX: DbTx, N: NodeTypes> ChangeSetReader for DatabaseProvider<TX, N> {
     }
 }
 
+impl<TX: DbTx, N: NodeTypes> AccountHistoryReader for DatabaseProvider<TX, N> {
+    fn account_history_blocks(
+        &self,
+        address: Address,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<Vec<BlockNumber>> {
+        let (start, end) = (*range.start(), *range.end());
+        let mut blocks = BTreeSet::new();
+
+        // Shards are keyed by their highest block number, so the first shard that may overlap the
+        // range is the first one with a key at or above its start.
+        let mut account_cursor = self.tx.cursor_read::<tables::AccountsHistory>()?;
+        let mut entry = account_cursor.seek(ShardedKey::new(address, start))?;
+        while let Some((key, list)) = entry.filter(|(key, _)| key.key == address) {
+            blocks.extend(list.iter().filter(|block| range.contains(block)));
+            if key.highest_block_number >= end {
+                break
+            }
+            entry = account_cursor.next()?;
+        }
+
+        let mut storage_cursor = self.tx.cursor_read::<tables::StoragesHistory>()?;
+        let mut entry = storage_cursor.seek(StorageShardedKey::new(address, B256::ZERO, start))?;
+        while let Some((key, list)) = entry.filter(|(key, _)| key.address == address) {
+            let slot = key.sharded_key.key;
+            if key.sharded_key.highest_block_number < start {
+                // We moved on to a new slot, skip its shards that end before the range.
+                entry = storage_cursor.seek(StorageShardedKey::new(address, slot, start))?;
+                continue
+            }
+
+            blocks.extend(list.iter().filter(|block| range.contains(block)));
+            entry = if key.sharded_key.highest_block_number >= end {
+                // The remaining shards of this slot are past the range, skip to the next slot.
+                match U256::from_be_bytes(slot.0).checked_add(U256::from(1)) {
+                    Some(next) => {
+                        storage_cursor.seek(StorageShardedKey::new(address, next.into(), start))?
+                    }
+                    None => None,
+                }
+            } else {
+                storage_cursor.next()?
+            };
+        }
+
+        Ok(blocks.into_iter().collect())
+    }
+
+    fn lowest_history_block(&self) -> ProviderResult<BlockNumber> {
+        let mut lowest = 0;
+        for segment in [PruneSegment::AccountHistory, PruneSegment::StorageHistory] {
+            if let Some(pruned) = self
+                .tx
+                .get::<tables::PruneCheckpoints>(segment)?
+                .and_then(|checkpoint| checkpoint.block_number)
+            {
+                lowest = lowest.max(pruned + 1);
+            }
+        }
+        Ok(lowest)
+    }
+
+    fn modified_accounts(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<BTreeMap<Address, AccountStateBefore>> {
+        let mut accounts = BTreeMap::<Address, AccountStateBefore>::new();
+
+        // The changesets are walked in ascending block order, so the first entry of an account or
+        // slot holds its state before the range.
+        let mut account_cursor = self.tx.cursor_read::<tables::AccountChangeSets>()?;
+        for entry in account_cursor.walk_range(range.clone())? {
+            let (_, AccountBeforeTx { address, info }) = entry?;
+            accounts.entry(address).or_default().record_info(info);
+        }
+
+        // Accounts that only had storage changes don't show up in the account changesets.
+        let mut storage_cursor = self.tx.cursor_dup_read::<tables::StorageChangeSets>()?;
+        for entry in storage_cursor.walk_range(BlockNumberAddress::range(range))? {
+            let (BlockNumberAddress((_, address)), StorageEntry { key, value }) = entry?;
+            accounts.entry(address).or_default().record_slot(key, value);
+        }
+
+        Ok(accounts)
+    }
+}
+
+impl<TX: DbTx + 'static, N: NodeTypes> DatabaseProvider<TX, N> {
+    /// Returns the highest blocks with pruned account and storage history.
+    ///
+    /// The history is pruned either by the corresponding history segment, or together with the
+    /// changesets by [`PruneSegment::ChangeSets`]. [`PruneSegment::Bytecodes`] removes the code of
+    /// accounts that only exist in the state before its checkpoint, so that state isn't served
+    /// either instead of executing with empty code.
+    fn highest_pruned_history_blocks(
+        &self,
+    ) -> ProviderResult<(Option<BlockNumber>, Option<BlockNumber>)> {
+        let pruned_block = |segment| {
+            self.get_prune_checkpoint(segment)
+                .map(|checkpoint| checkpoint.and_then(|checkpoint| checkpoint.block_number))
+        };
+        let state =
+            pruned_block(PruneSegment::ChangeSets)?.max(pruned_block(PruneSegment::Bytecodes)?);
+        Ok((
+            pruned_block(PruneSegment::AccountHistory)?.max(state),
+            pruned_block(PruneSegment::StorageHistory)?.max(state),
+        ))
+    }
+
+    /// Returns the hashed state that reverts the state of the database to the state after the
+    /// given block.
+    pub(crate) fn hashed_state_reverts(
+        &self,
+        block_number: BlockNumber,
+    ) -> ProviderResult<HashedPostState> {
+        if block_number >= self.last_block_number()? {
+            return Ok(HashedPostState::default())
+        }
+
+        // The changesets of all blocks after the given one are required to revert the state.
+        for segment in
+            [PruneSegment::AccountHistory, PruneSegment::StorageHistory, PruneSegment::ChangeSets]
+        {
+            if self
+                .get_prune_checkpoint(segment)?
+                .and_then(|checkpoint| checkpoint.block_number)
+                .is_some_and(|pruned| pruned > block_number)
+            {
+                return Err(ProviderError::StateAtBlockPruned(block_number))
+            }
+        }
+
+        Ok(HashedPostState::from_reverts::<<N::StateCommitment as StateCommitment>::KeyHasher>(
+            self.tx_ref(),
+            block_number + 1,
+        )?)
+    }
+
+    /// Returns up to `limit` hashed accounts of the database state with the given hashed state on
+    /// top, starting at the given hashed address.
+    pub(crate) fn hashed_accounts_range_with_overlay(
+        &self,
+        overlay: &HashedPostStateSorted,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<Vec<(B256, Account)>> {
+        let factory = HashedPostStateCursorFactory::new(
+            DatabaseHashedCursorFactory::new(self.tx_ref()),
+            overlay,
+        );
+        let mut cursor = factory.hashed_account_cursor()?;
+
+        let mut accounts = Vec::new();
+        let mut entry = cursor.seek(start)?;
+        while let Some(account) = entry.filter(|_| accounts.len() < limit) {
+            accounts.push(account);
+            entry = cursor.next()?;
+        }
+        Ok(accounts)
+    }
+
+    /// Returns up to `limit` hashed accounts of the database state with the given hashed state on
+    /// top, starting at the given hashed address, together with their storage roots and up to
+    /// `storage_limit` of their storage slots.
+    ///
+    /// The storage roots are computed from the storage tries of the database, only the parts of the
+    /// tries that are touched by the given hashed state are recomputed.
+    ///
+    /// No further accounts are read once `is_done` returns `true` for the last read account.
+    pub(crate) fn hashed_accounts_dump_with_overlay(
+        &self,
+        overlay: HashedPostState,
+        start: B256,
+        limit: usize,
+        storage_limit: Option<usize>,
+        mut is_done: impl FnMut(&HashedAccountDump) -> bool,
+    ) -> ProviderResult<Vec<HashedAccountDump>> {
+        let mut storage_prefix_sets = overlay.construct_prefix_sets().storage_prefix_sets;
+        let overlay = overlay.into_sorted();
+        let trie_cursor_factory = DatabaseTrieCursorFactory::new(self.tx_ref());
+        let hashed_cursor_factory = HashedPostStateCursorFactory::new(
+            DatabaseHashedCursorFactory::new(self.tx_ref()),
+            &overlay,
+        );
+        let mut cursor = hashed_cursor_factory.hashed_account_cursor()?;
+
+        let mut accounts = Vec::new();
+        let mut entry = cursor.seek(start)?;
+        while let Some((hashed_address, account)) = entry.filter(|_| accounts.len() < limit) {
+            let prefix_set =
+                storage_prefix_sets.remove(&hashed_address).unwrap_or_default().freeze();
+            let storage_root = StorageRoot::new_hashed(
+                trie_cursor_factory.clone(),
+                hashed_cursor_factory.clone(),
+                hashed_address,
+                prefix_set,
+                TrieRootMetrics::new(TrieType::Storage),
+            )
+            .root()
+            .map_err(|err| ProviderError::Database(err.into()))?;
+
+            let storage = storage_limit
+                .map(|storage_limit| {
+                    let mut cursor = hashed_cursor_factory.hashed_storage_cursor(hashed_address)?;
+                    let mut slots = Vec::new();
+                    let mut entry = cursor.seek(B256::ZERO)?;
+                    while let Some(slot) = entry.filter(|_| slots.len() < storage_limit) {
+                        slots.push(slot);
+                        entry = cursor.next()?;
+                    }
+                    Ok::<_, ProviderError>(slots)
+                })
+                .transpose()?;
+
+            let dump = HashedAccountDump { hashed_address, account, storage_root, storage };
+            let done = is_done(&dump);
+            accounts.push(dump);
+            if done {
+                break
+            }
+            entry = cursor.next()?;
+        }
+        Ok(accounts)
+    }
+
+    /// Returns the hashed accounts of the database state with the given hashed state on top,
+    /// starting at the given hashed address, together with their storage roots and the account
+    /// proofs of the range boundaries.
+    ///
+    /// See [`HashedStateRangeReader::hashed_accounts_range_proof`].
+    pub(crate) fn hashed_accounts_range_proof_with_overlay(
+        &self,
+        overlay: HashedPostState,
+        start: B256,
+        is_done: impl FnMut(&HashedAccountDump) -> bool,
+    ) -> ProviderResult<(Vec<HashedAccountDump>, MultiProof)> {
+        let accounts = self.hashed_accounts_dump_with_overlay(
+            overlay.clone(),
+            start,
+            usize::MAX,
+            None,
+            is_done,
+        )?;
+
+        let mut targets = vec![start];
+        targets.extend(accounts.last().map(|account| account.hashed_address));
+        let proof = Proof::overlay_multiproof(
+            self.tx_ref(),
+            TrieInput::from_state(overlay),
+            MultiProofTargets::accounts(targets),
+        )?;
+
+        Ok((accounts, proof))
+    }
+
+    /// Returns up to `limit` non-zero storage slots of the account with the given hashed address
+    /// in the database state with the given hashed state on top, starting at the given hashed slot.
+    pub(crate) fn hashed_storage_range_with_overlay(
+        &self,
+        overlay: &HashedPostStateSorted,
+        hashed_address: B256,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<Vec<(B256, U256)>> {
+        let factory = HashedPostStateCursorFactory::new(
+            DatabaseHashedCursorFactory::new(self.tx_ref()),
+            overlay,
+        );
+        let mut cursor = factory.hashed_storage_cursor(hashed_address)?;
+
+        let mut slots = Vec::new();
+        let mut entry = cursor.seek(start)?;
+        while let Some(slot) = entry.filter(|_| slots.len() < limit) {
+            slots.push(slot);
+            entry = cursor.next()?;
+        }
+        Ok(slots)
+    }
+}
+
+impl<TX: DbTx + 'static, N: NodeTypes> HashedStateRangeReader for DatabaseProvider<TX, N> {
+    fn hashed_accounts_range(
+        &self,
+        block_number: BlockNumber,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<Vec<(B256, Account)>> {
+        let reverts = self.hashed_state_reverts(block_number)?.into_sorted();
+        self.hashed_accounts_range_with_overlay(&reverts, start, limit)
+    }
+
+    fn hashed_accounts_dump(
+        &self,
+        block_number: BlockNumber,
+        start: B256,
+        limit: usize,
+        storage_limit: Option<usize>,
+    ) -> ProviderResult<Vec<HashedAccountDump>> {
+        let reverts = self.hashed_state_reverts(block_number)?;
+        self.hashed_accounts_dump_with_overlay(reverts, start, limit, storage_limit, |_| false)
+    }
+
+    fn hashed_accounts_range_proof(
+        &self,
+        block_number: BlockNumber,
+        start: B256,
+        is_done: &mut dyn FnMut(&HashedAccountDump) -> bool,
+    ) -> ProviderResult<(Vec<HashedAccountDump>, MultiProof)> {
+        let reverts = self.hashed_state_reverts(block_number)?;
+        self.hashed_accounts_range_proof_with_overlay(reverts, start, is_done)
+    }
+
+    fn hashed_storage_range(
+        &self,
+        block_number: BlockNumber,
+        hashed_address: B256,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<Vec<(B256, U256)>> {
+        let reverts = self.hashed_state_reverts(block_number)?.into_sorted();
+        self.hashed_storage_range_with_overlay(&reverts, hashed_address, start, limit)
+    }
+
+    fn storage_preimages(
+        &self,
+        address: Address,
+        hashed_slots: &[B256],
+        max_entries: usize,
+    ) -> ProviderResult<B256Map<B256>> {
+        let mut remaining = hashed_slots.iter().copied().collect::<HashSet<_>>();
+        let mut preimages = B256Map::default();
+
+        let mut cursor = self.tx.cursor_dup_read::<tables::PlainStorageState>()?;
+        let mut entry = cursor.seek_exact(address)?;
+        let mut scanned = 0;
+        while let Some((_, StorageEntry { key, .. })) =
+            entry.filter(|_| !remaining.is_empty() && scanned < max_entries)
+        {
+            scanned += 1;
+            let hashed_slot = <N::StateCommitment as StateCommitment>::KeyHasher::hash_key(key);
+            if remaining.remove(&hashed_slot) {
+                preimages.insert(hashed_slot, key);
+            }
+            entry = cursor.next_dup()?;
+        }
+
+        Ok(preimages)
+    }
+}
+
 impl<TX: DbTx + 'static, N: NodeTypesForProvider> HeaderSyncGapProvider
     for DatabaseProvider<TX, N>
 {
@@ -1692,6 +2111,9 @@ impl<TX: DbTxMut, N: NodeTypes> StageCheckpointWriter for DatabaseProvider<TX, N
         id: StageId,
         checkpoint: Vec<u8>,
     ) -> ProviderResult<()> {
+        if id == StageId::SnapSync && !checkpoint.is_empty() {
+            self.snap_synced.set();
+        }
         Ok(self.tx.put::<tables::StageCheckpointProgresses>(id.to_string(), checkpoint)?)
     }
 
@@ -1837,6 +2259,15 @@ impl<TX: DbTxMut + DbTx + 'static, N: NodeTypesForProvider> StateWriter
             .then(|| self.static_file_provider.get_writer(first_block, StaticFileSegment::Receipts))
             .transpose()?;
 
+        // Receipts of blocks that are already in the static files were imported ahead of
+        // execution, e.g. from a mirror, and are kept.
+        let highest_static_file_receipts_block = receipts_static_writer
+            .is_some()
+            .then(|| {
+                self.static_file_provider.get_highest_static_file_block(StaticFileSegment::Receipts)
+            })
+            .flatten();
+
         let has_contract_log_filter = !self.prune_modes.receipts_log_filter.is_empty();
         let contract_log_pruner = self.prune_modes.receipts_log_filter.group_by_block(tip, None)?;
 
@@ -1856,8 +2287,12 @@ impl<TX: DbTxMut + DbTx + 'static, N: NodeTypesForProvider> StateWriter
         {
             let block_number = first_block + idx as u64;
 
+            let mut static_file_writer = receipts_static_writer.as_mut().filter(|_| {
+                highest_static_file_receipts_block.is_none_or(|highest| block_number > highest)
+            });
+
             // Increment block number for receipts static file writer
-            if let Some(writer) = receipts_static_writer.as_mut() {
+            if let Some(writer) = static_file_writer.as_mut() {
                 writer.increment_block(block_number)?;
             }
 
@@ -1886,7 +2321,7 @@ impl<TX: DbTxMut + DbTx + 'static, N: NodeTypesForProvider> StateWriter
                     continue
                 }
 
-                if let Some(writer) = &mut receipts_static_writer {
+                if let Some(writer) = static_file_writer.as_mut() {
                     writer.append_receipt(receipt_idx, receipt)?;
                 }
 
@@ -3200,6 +3635,10 @@ impl<TX: DbTx + 'static, N: NodeTypes + 'static> DBProvider for DatabaseProvider
     fn prune_modes_ref(&self) -> &PruneModes {
         self.prune_modes_ref()
     }
+
+    fn is_snap_synced(&self) -> ProviderResult<bool> {
+        self.snap_synced.get_or_load(&self.tx)
+    }
 }
 
 #[cfg(test)]
@@ -3209,7 +3648,10 @@ mod tests {
         test_utils::{blocks::BlockchainTestData, create_test_provider_factory},
         BlockWriter,
     };
+    use alloy_consensus::constants::EMPTY_ROOT_HASH;
+    use assert_matches::assert_matches;
     use reth_testing_utils::generators::{self, random_block, BlockParams};
+    use reth_trie::root::storage_root;
 
     #[test]
     fn test_receipts_by_block_range_empty_range() {
@@ -3454,4 +3896,244 @@ mod tests {
 
         assert_eq!(range_result, individual_results);
     }
+
+    #[test]
+    fn test_account_history_blocks() {
+        let factory = create_test_provider_factory();
+        let address = Address::with_last_byte(1);
+        let other = Address::with_last_byte(2);
+        let slot_a = B256::with_last_byte(1);
+        let slot_b = B256::with_last_byte(2);
+
+        let provider_rw = factory.provider_rw().unwrap();
+        let tx = provider_rw.tx_ref();
+        for (key, blocks) in [
+            (ShardedKey::new(address, 5), vec![1, 3, 5]),
+            (ShardedKey::new(address, u64::MAX), vec![8, 12]),
+            (ShardedKey::new(other, u64::MAX), vec![4]),
+        ] {
+            tx.put::<tables::AccountsHistory>(key, BlockNumberList::new_pre_sorted(blocks))
+                .unwrap();
+        }
+        for (key, blocks) in [
+            (StorageShardedKey::new(address, slot_a, 2), vec![2]),
+            (StorageShardedKey::new(address, slot_a, u64::MAX), vec![6, 12]),
+            (StorageShardedKey::new(address, slot_b, u64::MAX), vec![7]),
+            (StorageShardedKey::new(other, slot_a, u64::MAX), vec![9]),
+        ] {
+            tx.put::<tables::StoragesHistory>(key, BlockNumberList::new_pre_sorted(blocks))
+                .unwrap();
+        }
+        provider_rw.commit().unwrap();
+
+        let provider = factory.provider().unwrap();
+        assert_eq!(
+            provider.account_history_blocks(address, 0..=20).unwrap(),
+            vec![1, 2, 3, 5, 6, 7, 8, 12]
+        );
+        assert_eq!(provider.account_history_blocks(address, 3..=7).unwrap(), vec![3, 5, 6, 7]);
+        assert!(provider.account_history_blocks(address, 13..=20).unwrap().is_empty());
+        assert_eq!(provider.account_history_blocks(other, 0..=20).unwrap(), vec![4, 9]);
+    }
+
+    #[test]
+    fn test_modified_accounts() {
+        let factory = create_test_provider_factory();
+        let accounts = (1..=4).map(Address::with_last_byte).collect::<Vec<_>>();
+
+        let info = |nonce| Some(Account { nonce, ..Default::default() });
+        let slot = B256::with_last_byte(1);
+
+        let provider_rw = factory.provider_rw().unwrap();
+        let tx = provider_rw.tx_ref();
+        for (block, address, info) in
+            [(1, accounts[0], None), (2, accounts[1], info(1)), (3, accounts[0], info(2))]
+        {
+            tx.put::<tables::AccountChangeSets>(block, AccountBeforeTx { address, info }).unwrap();
+        }
+        for (block, address, value) in
+            [(2, accounts[2], 1), (3, accounts[2], 2), (4, accounts[3], 3)]
+        {
+            tx.put::<tables::StorageChangeSets>(
+                BlockNumberAddress((block, address)),
+                StorageEntry { key: slot, value: U256::from(value) },
+            )
+            .unwrap();
+        }
+        provider_rw.commit().unwrap();
+
+        let provider = factory.provider().unwrap();
+        let before = |info: Option<Option<Account>>, slots: &[(B256, u64)]| AccountStateBefore {
+            info,
+            storage: slots.iter().map(|(slot, value)| (*slot, U256::from(*value))).collect(),
+        };
+        assert_eq!(
+            provider.modified_accounts(1..=4).unwrap(),
+            BTreeMap::from([
+                (accounts[0], before(Some(None), &[])),
+                (accounts[1], before(Some(info(1)), &[])),
+                (accounts[2], before(None, &[(slot, 1)])),
+                (accounts[3], before(None, &[(slot, 3)])),
+            ])
+        );
+        // the state before the range is recorded by the first changeset of the range
+        assert_eq!(
+            provider.modified_accounts(3..=3).unwrap(),
+            BTreeMap::from([
+                (accounts[0], before(Some(info(2)), &[])),
+                (accounts[2], before(None, &[(slot, 2)])),
+            ])
+        );
+        assert!(provider.modified_accounts(5..=10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_history_below_bytecodes_checkpoint() {
+        let factory = create_test_provider_factory();
+        let data = BlockchainTestData::default();
+
+        let provider_rw = factory.provider_rw().unwrap();
+        provider_rw
+            .insert_block(
+                data.genesis.clone().try_recover().unwrap(),
+                crate::StorageLocation::Database,
+            )
+            .unwrap();
+        for i in 0..3 {
+            provider_rw
+                .insert_block(data.blocks[i].0.clone(), crate::StorageLocation::Database)
+                .unwrap();
+            provider_rw
+                .write_state(
+                    &data.blocks[i].1,
+                    crate::OriginalValuesKnown::No,
+                    crate::StorageLocation::Database,
+                )
+                .unwrap();
+        }
+        provider_rw
+            .save_prune_checkpoint(
+                PruneSegment::Bytecodes,
+                PruneCheckpoint {
+                    block_number: Some(1),
+                    tx_number: None,
+                    prune_mode: PruneMode::Before(2),
+                },
+            )
+            .unwrap();
+        provider_rw.commit().unwrap();
+
+        let provider = factory.provider().unwrap();
+        let address = Address::random();
+
+        // the code of accounts in the state after the genesis may have been pruned
+        let state = provider.history_by_block_hash(data.genesis.hash()).unwrap();
+        assert_matches!(state.basic_account(&address), Err(ProviderError::StateAtBlockPruned(1)));
+        assert_matches!(
+            state.storage(address, B256::ZERO),
+            Err(ProviderError::StateAtBlockPruned(1))
+        );
+
+        // the state after the checkpoint is still available
+        let state = provider.history_by_block_hash(data.blocks[0].0.hash()).unwrap();
+        assert_matches!(state.basic_account(&address), Ok(None));
+    }
+
+    #[test]
+    fn test_hashed_state_range() {
+        let factory = create_test_provider_factory();
+        let (address_a, address_b, address_c) =
+            (Address::with_last_byte(1), Address::with_last_byte(2), Address::with_last_byte(3));
+        let (slot_a, slot_b) = (B256::with_last_byte(1), B256::with_last_byte(2));
+        let account = |nonce| Account { nonce, ..Default::default() };
+
+        let provider_rw = factory.provider_rw().unwrap();
+        let tx = provider_rw.tx_ref();
+        for number in 0..=2 {
+            tx.put::<tables::CanonicalHeaders>(number, B256::with_last_byte(number as u8)).unwrap();
+        }
+        // latest state, after block 2
+        for (address, nonce) in [(address_a, 2), (address_b, 1), (address_c, 1)] {
+            tx.put::<tables::HashedAccounts>(keccak256(address), account(nonce)).unwrap();
+        }
+        tx.put::<tables::HashedStorages>(
+            keccak256(address_a),
+            StorageEntry { key: keccak256(slot_a), value: U256::from(5) },
+        )
+        .unwrap();
+        tx.put::<tables::PlainStorageState>(
+            address_a,
+            StorageEntry { key: slot_a, value: U256::from(5) },
+        )
+        .unwrap();
+        // changes of block 2
+        for (address, info) in [(address_a, Some(account(1))), (address_c, None)] {
+            tx.put::<tables::AccountChangeSets>(2, AccountBeforeTx { address, info }).unwrap();
+        }
+        for (slot, value) in [(slot_a, 3), (slot_b, 7)] {
+            tx.put::<tables::StorageChangeSets>(
+                BlockNumberAddress((2, address_a)),
+                StorageEntry { key: slot, value: U256::from(value) },
+            )
+            .unwrap();
+        }
+        provider_rw.commit().unwrap();
+
+        let provider = factory.provider().unwrap();
+        fn sorted<T>(mut entries: Vec<(B256, T)>) -> Vec<(B256, T)> {
+            entries.sort_unstable_by_key(|(key, _)| *key);
+            entries
+        }
+
+        assert_eq!(
+            provider.hashed_accounts_range(2, B256::ZERO, 10).unwrap(),
+            sorted(vec![
+                (keccak256(address_a), account(2)),
+                (keccak256(address_b), account(1)),
+                (keccak256(address_c), account(1)),
+            ])
+        );
+        let accounts_before =
+            sorted(vec![(keccak256(address_a), account(1)), (keccak256(address_b), account(1))]);
+        assert_eq!(provider.hashed_accounts_range(1, B256::ZERO, 10).unwrap(), accounts_before);
+        assert_eq!(
+            provider.hashed_accounts_range(1, accounts_before[1].0, 10).unwrap(),
+            accounts_before[1..]
+        );
+        assert_eq!(provider.hashed_accounts_range(1, B256::ZERO, 1).unwrap(), accounts_before[..1]);
+
+        assert_eq!(
+            provider.hashed_storage_range(2, keccak256(address_a), B256::ZERO, 10).unwrap(),
+            vec![(keccak256(slot_a), U256::from(5))]
+        );
+        assert_eq!(
+            provider.hashed_storage_range(1, keccak256(address_a), B256::ZERO, 10).unwrap(),
+            sorted(vec![(keccak256(slot_a), U256::from(3)), (keccak256(slot_b), U256::from(7))])
+        );
+
+        let storage_before =
+            sorted(vec![(keccak256(slot_a), U256::from(3)), (keccak256(slot_b), U256::from(7))]);
+        let dump = provider.hashed_accounts_dump(1, B256::ZERO, 10, Some(1)).unwrap();
+        assert_eq!(
+            dump.iter().map(|dump| (dump.hashed_address, dump.account)).collect::<Vec<_>>(),
+            accounts_before
+        );
+        let dump_a = dump.iter().find(|dump| dump.hashed_address == keccak256(address_a)).unwrap();
+        assert_eq!(dump_a.storage_root, storage_root(storage_before.iter().copied()));
+        assert_eq!(dump_a.storage.as_deref(), Some(&storage_before[..1]));
+        let dump_b = dump.iter().find(|dump| dump.hashed_address == keccak256(address_b)).unwrap();
+        assert_eq!(dump_b.storage_root, EMPTY_ROOT_HASH);
+        assert_eq!(dump_b.storage, Some(Vec::new()));
+        assert!(provider
+            .hashed_accounts_dump(1, B256::ZERO, 10, None)
+            .unwrap()
+            .iter()
+            .all(|dump| dump.storage.is_none()));
+
+        let hashed_slots = [keccak256(slot_a), keccak256(slot_b)];
+        let preimages = provider.storage_preimages(address_a, &hashed_slots, usize::MAX).unwrap();
+        assert_eq!(preimages.len(), 1);
+        assert_eq!(preimages.get(&keccak256(slot_a)), Some(&slot_a));
+        assert!(provider.storage_preimages(address_a, &hashed_slots, 0).unwrap().is_empty());
+    }
 }
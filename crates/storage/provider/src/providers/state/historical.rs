@@ -1,6 +1,10 @@
 use crate::{
-    providers::state::macros::delegate_provider_impls, AccountReader, BlockHashReader,
-    HashedPostStateProvider, ProviderError, StateProvider, StateRootProvider,
+    providers::state::{
+        latest::{hashed_account, hashed_storage},
+        macros::delegate_provider_impls,
+    },
+    AccountReader, BlockHashReader, HashedPostStateProvider, ProviderError, StateProvider,
+    StateRootProvider,
 };
 use alloy_eips::merge::EPOCH_SLOTS;
 use alloy_primitives::{Address, BlockNumber, Bytes, StorageKey, StorageValue, B256};
@@ -263,7 +267,11 @@ impl<Provider: DBProvider + BlockNumReader + StateCommitmentProvider> AccountRea
                 })?
                 .info),
             HistoryInfo::InPlainState | HistoryInfo::MaybeInPlainState => {
-                Ok(self.tx().get_by_encoded_key::<tables::PlainAccountState>(address)?)
+                match self.tx().get_by_encoded_key::<tables::PlainAccountState>(address)? {
+                    Some(account) => Ok(Some(account)),
+                    None if self.provider.is_snap_synced()? => hashed_account(self.tx(), address),
+                    None => Ok(None),
+                }
             }
         }
     }
@@ -425,13 +433,21 @@ impl<Provider: DBProvider + BlockNumReader + BlockHashReader + StateCommitmentPr
                     })?
                     .value,
             )),
-            HistoryInfo::InPlainState | HistoryInfo::MaybeInPlainState => Ok(self
-                .tx()
-                .cursor_dup_read::<tables::PlainStorageState>()?
-                .seek_by_key_subkey(address, storage_key)?
-                .filter(|entry| entry.key == storage_key)
-                .map(|entry| entry.value)
-                .or(Some(StorageValue::ZERO))),
+            HistoryInfo::InPlainState | HistoryInfo::MaybeInPlainState => {
+                let value = match self
+                    .tx()
+                    .cursor_dup_read::<tables::PlainStorageState>()?
+                    .seek_by_key_subkey(address, storage_key)?
+                    .filter(|entry| entry.key == storage_key)
+                {
+                    Some(entry) => Some(entry.value),
+                    None if self.provider.is_snap_synced()? => {
+                        hashed_storage(self.tx(), address, storage_key)?
+                    }
+                    None => None,
+                };
+                Ok(value.or(Some(StorageValue::ZERO)))
+            }
         }
     }
 }
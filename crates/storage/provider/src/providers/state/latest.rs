@@ -2,7 +2,7 @@ use crate::{
     providers::state::macros::delegate_provider_impls, AccountReader, BlockHashReader,
     HashedPostStateProvider, StateProvider, StateRootProvider,
 };
-use alloy_primitives::{Address, BlockNumber, Bytes, StorageKey, StorageValue, B256};
+use alloy_primitives::{keccak256, Address, BlockNumber, Bytes, StorageKey, StorageValue, B256};
 use reth_db_api::{cursor::DbDupCursorRO, tables, transaction::DbTx};
 use reth_primitives_traits::{Account, Bytecode};
 use reth_storage_api::{
@@ -41,7 +41,11 @@ impl<'b, Provider: DBProvider> LatestStateProviderRef<'b, Provider> {
 impl<Provider: DBProvider> AccountReader for LatestStateProviderRef<'_, Provider> {
     /// Get basic account information.
     fn basic_account(&self, address: &Address) -> ProviderResult<Option<Account>> {
-        self.tx().get_by_encoded_key::<tables::PlainAccountState>(address).map_err(Into::into)
+        match self.tx().get_by_encoded_key::<tables::PlainAccountState>(address)? {
+            Some(account) => Ok(Some(account)),
+            None if self.0.is_snap_synced()? => hashed_account(self.tx(), address),
+            None => Ok(None),
+        }
     }
 }
 
@@ -175,6 +179,9 @@ impl<Provider: DBProvider + BlockHashReader + StateCommitmentProvider> StateProv
                 return Ok(Some(entry.value))
             }
         }
+        if self.0.is_snap_synced()? {
+            return hashed_storage(self.tx(), account, storage_key)
+        }
         Ok(None)
     }
 }
@@ -194,6 +201,34 @@ impl<Provider: StateCommitmentProvider> StateCommitmentProvider
     type StateCommitment = Provider::StateCommitment;
 }
 
+/// Reads the account from the hashed state.
+///
+/// The plain state of a snap synced node is incomplete, see [`DBProvider::is_snap_synced`], so
+/// accounts that are missing from it are read from the hashed state instead.
+pub(crate) fn hashed_account<TX: DbTx>(
+    tx: &TX,
+    address: &Address,
+) -> ProviderResult<Option<Account>> {
+    Ok(tx.get::<tables::HashedAccounts>(keccak256(address))?)
+}
+
+/// Reads the storage slot from the hashed state.
+///
+/// The plain state of a snap synced node is incomplete, see [`DBProvider::is_snap_synced`], so
+/// storage slots that are missing from it are read from the hashed state instead.
+pub(crate) fn hashed_storage<TX: DbTx>(
+    tx: &TX,
+    address: Address,
+    storage_key: StorageKey,
+) -> ProviderResult<Option<StorageValue>> {
+    let hashed_key = keccak256(storage_key);
+    Ok(tx
+        .cursor_dup_read::<tables::HashedStorages>()?
+        .seek_by_key_subkey(keccak256(address), hashed_key)?
+        .filter(|entry| entry.key == hashed_key)
+        .map(|entry| entry.value))
+}
+
 /// State provider for the latest state.
 #[derive(Debug)]
 pub struct LatestStateProvider<Provider>(Provider);
@@ -221,6 +256,16 @@ delegate_provider_impls!(LatestStateProvider<Provider> where [Provider: DBProvid
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_utils::create_test_provider_factory;
+    use alloy_primitives::{address, b256, U256};
+    use reth_db_api::transaction::DbTxMut;
+    use reth_primitives_traits::StorageEntry;
+    use reth_stages_types::StageId;
+    use reth_storage_api::{DatabaseProviderFactory, StageCheckpointWriter};
+
+    const ADDRESS: Address = address!("0x0000000000000000000000000000000000000001");
+    const STORAGE: B256 =
+        b256!("0x0000000000000000000000000000000000000000000000000000000000000001");
 
     const fn assert_state_provider<T: StateProvider>() {}
     #[expect(dead_code)]
@@ -229,4 +274,45 @@ mod tests {
     >() {
         assert_state_provider::<LatestStateProvider<T>>();
     }
+
+    #[test]
+    fn snap_synced_state_falls_back_to_hashed_state() {
+        let factory = create_test_provider_factory();
+        let provider = factory.database_provider_rw().unwrap();
+
+        let account = Account { nonce: 1, balance: U256::from(10), bytecode_hash: None };
+        provider.tx_ref().put::<tables::HashedAccounts>(keccak256(ADDRESS), account).unwrap();
+        provider
+            .tx_ref()
+            .put::<tables::HashedStorages>(
+                keccak256(ADDRESS),
+                StorageEntry { key: keccak256(STORAGE), value: U256::from(5) },
+            )
+            .unwrap();
+
+        // without snap sync, the plain state is complete
+        let state = LatestStateProviderRef::new(&provider);
+        assert_eq!(state.basic_account(&ADDRESS).unwrap(), None);
+        assert_eq!(state.storage(ADDRESS, STORAGE).unwrap(), None);
+
+        // the snap synced state is read from the hashed state
+        provider.save_stage_checkpoint_progress(StageId::SnapSync, vec![1]).unwrap();
+        let state = LatestStateProviderRef::new(&provider);
+        assert_eq!(state.basic_account(&ADDRESS).unwrap(), Some(account));
+        assert_eq!(state.storage(ADDRESS, STORAGE).unwrap(), Some(U256::from(5)));
+        assert_eq!(state.storage(ADDRESS, B256::ZERO).unwrap(), None);
+
+        // the plain state takes precedence over the hashed state
+        let changed = Account { nonce: 2, ..account };
+        provider.tx_ref().put::<tables::PlainAccountState>(ADDRESS, changed).unwrap();
+        provider
+            .tx_ref()
+            .put::<tables::PlainStorageState>(
+                ADDRESS,
+                StorageEntry { key: STORAGE, value: U256::from(6) },
+            )
+            .unwrap();
+        assert_eq!(state.basic_account(&ADDRESS).unwrap(), Some(changed));
+        assert_eq!(state.storage(ADDRESS, STORAGE).unwrap(), Some(U256::from(6)));
+    }
 }
@@ -4,8 +4,8 @@ use super::{
 };
 use crate::{
     to_range, BlockHashReader, BlockNumReader, BlockReader, BlockSource, HeaderProvider,
-    ReceiptProvider, StageCheckpointReader, StatsReader, TransactionVariant, TransactionsProvider,
-    TransactionsProviderExt,
+    ReceiptProvider, StageCheckpointReader, StaticFileRangeReader, StatsReader, TransactionVariant,
+    TransactionsProvider, TransactionsProviderExt,
 };
 use alloy_consensus::{
     transaction::{SignerRecoverable, TransactionMeta},
@@ -453,35 +453,51 @@ impl<N: NodePrimitives> StaticFileProvider<N> {
     /// This will not delete the file that contains the block itself, because files can only be
     /// removed entirely.
     pub fn delete_transactions_below(&self, block: BlockNumber) -> ProviderResult<()> {
+        self.delete_segment_below_block(StaticFileSegment::Transactions, block)?;
+        Ok(())
+    }
+
+    /// Deletes all static files of the segment that end below the given block and returns the
+    /// number of deleted files.
+    ///
+    /// This will not delete the file that contains the block itself, because files can only be
+    /// removed entirely.
+    pub fn delete_segment_below_block(
+        &self,
+        segment: StaticFileSegment,
+        block: BlockNumber,
+    ) -> ProviderResult<usize> {
+        let mut deleted = 0;
+
         // Nothing to delete if block is 0.
         if block == 0 {
-            return Ok(())
+            return Ok(deleted)
         }
 
         loop {
-            let Some(block_height) =
-                self.get_lowest_static_file_block(StaticFileSegment::Transactions)
-            else {
-                return Ok(())
+            let Some(block_height) = self.get_lowest_static_file_block(segment) else {
+                return Ok(deleted)
             };
 
             if block_height >= block {
-                return Ok(())
+                return Ok(deleted)
             }
 
             debug!(
                 target: "provider::static_file",
+                ?segment,
                 ?block_height,
-                "Deleting transaction static file below block"
+                "Deleting static file below block"
             );
 
             // now we need to wipe the static file, this will take care of updating the index and
-            // advance the lowest tracked block height for the transactions segment.
-            self.delete_jar(StaticFileSegment::Transactions, block_height)
+            // advance the lowest tracked block height for the segment.
+            self.delete_jar(segment, block_height)
                 .inspect_err(|err| {
-                    warn!( target: "provider::static_file", %block_height, ?err, "Failed to delete transaction static file below block")
+                    warn!( target: "provider::static_file", ?segment, %block_height, ?err, "Failed to delete static file below block")
                 })
                 ?;
+            deleted += 1;
         }
     }
 
@@ -1218,6 +1234,19 @@ impl<N: NodePrimitives> StaticFileProvider<N> {
         &self.path
     }
 
+    /// Returns the total size in bytes of the files in the static files directory.
+    pub fn directory_size(&self) -> ProviderResult<u64> {
+        let mut size = 0;
+        for entry in reth_fs_util::read_dir(&self.path).map_err(ProviderError::other)? {
+            let metadata =
+                entry.and_then(|entry| entry.metadata()).map_err(ProviderError::other)?;
+            if metadata.is_file() {
+                size += metadata.len();
+            }
+        }
+        Ok(size)
+    }
+
     /// Retrieves data from the database or static file, wherever it's available.
     ///
     /// # Arguments
@@ -1829,6 +1858,18 @@ impl<N: NodePrimitives> BlockBodyIndicesProvider for StaticFileProvider<N> {
     }
 }
 
+impl<N: NodePrimitives> StaticFileRangeReader for StaticFileProvider<N> {
+    fn static_file_block_range(
+        &self,
+        segment: StaticFileSegment,
+    ) -> ProviderResult<Option<RangeInclusive<BlockNumber>>> {
+        let lowest = self.static_files_min_block.read().get(&segment).map(|range| range.start());
+        Ok(lowest
+            .zip(self.get_highest_static_file_block(segment))
+            .map(|(lowest, highest)| lowest..=highest))
+    }
+}
+
 impl<N: NodePrimitives> StatsReader for StaticFileProvider<N> {
     fn count_entries<T: Table>(&self) -> ProviderResult<usize> {
         match T::NAME {
@@ -1,15 +1,17 @@
 use crate::{
     traits::{BlockSource, ReceiptProvider},
-    AccountReader, BlockHashReader, BlockIdReader, BlockNumReader, BlockReader, BlockReaderIdExt,
-    ChainSpecProvider, ChangeSetReader, EthStorage, HeaderProvider, ReceiptProviderIdExt,
-    StateProvider, StateProviderBox, StateProviderFactory, StateReader, StateRootProvider,
-    TransactionVariant, TransactionsProvider,
+    AccountHistoryReader, AccountReader, AccountStateBefore, BadBlockReader, BlockHashReader,
+    BlockIdReader, BlockNumReader, BlockReader, BlockReaderIdExt, ChainSpecProvider,
+    ChangeSetReader, EthStorage, HashedAccountDump, HashedStateRangeReader, HeaderProvider,
+    ReceiptProviderIdExt, StateProvider, StateProviderBox, StateProviderFactory, StateReader,
+    StateRootProvider, TransactionVariant, TransactionsProvider,
 };
 use alloy_consensus::{constants::EMPTY_ROOT_HASH, transaction::TransactionMeta, Header};
 use alloy_eips::{BlockHashOrNumber, BlockId, BlockNumberOrTag};
 use alloy_primitives::{
-    keccak256, map::HashMap, Address, BlockHash, BlockNumber, Bytes, StorageKey, StorageValue,
-    TxHash, TxNumber, B256, U256,
+    keccak256,
+    map::{B256Map, HashMap},
+    Address, BlockHash, BlockNumber, Bytes, StorageKey, StorageValue, TxHash, TxNumber, B256, U256,
 };
 use parking_lot::Mutex;
 use reth_chain_state::{CanonStateNotifications, CanonStateSubscriptions};
@@ -23,7 +25,8 @@ use reth_ethereum_primitives::{EthPrimitives, Receipt};
 use reth_execution_types::ExecutionOutcome;
 use reth_node_types::NodeTypes;
 use reth_primitives_traits::{
-    Account, Bytecode, GotExpected, NodePrimitives, RecoveredBlock, SealedHeader, SignerRecoverable,
+    Account, Bytecode, GotExpected, NodePrimitives, RecoveredBlock, SealedBlock, SealedHeader,
+    SignerRecoverable,
 };
 use reth_prune_types::PruneModes;
 use reth_stages_types::{StageCheckpoint, StageId};
@@ -757,6 +760,16 @@ where
     }
 }
 
+impl<ChainSpec> BadBlockReader
+    for MockEthProvider<reth_ethereum_primitives::EthPrimitives, ChainSpec>
+where
+    ChainSpec: EthChainSpec + Send + Sync + 'static,
+{
+    fn bad_blocks(&self) -> ProviderResult<Vec<SealedBlock<reth_ethereum_primitives::Block>>> {
+        Ok(Vec::default())
+    }
+}
+
 impl<T: NodePrimitives, ChainSpec: Send + Sync> AccountReader for MockEthProvider<T, ChainSpec> {
     fn basic_account(&self, address: &Address) -> ProviderResult<Option<Account>> {
         Ok(self.accounts.lock().get(address).cloned().map(|a| a.account))
@@ -988,6 +1001,80 @@ impl<T: NodePrimitives, ChainSpec: Send + Sync> ChangeSetReader for MockEthProvi
     }
 }
 
+impl<T: NodePrimitives, ChainSpec: Send + Sync> AccountHistoryReader
+    for MockEthProvider<T, ChainSpec>
+{
+    fn account_history_blocks(
+        &self,
+        _address: Address,
+        _range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<Vec<BlockNumber>> {
+        Ok(Vec::default())
+    }
+
+    fn lowest_history_block(&self) -> ProviderResult<BlockNumber> {
+        Ok(0)
+    }
+
+    fn modified_accounts(
+        &self,
+        _range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<BTreeMap<Address, AccountStateBefore>> {
+        Ok(BTreeMap::default())
+    }
+}
+
+impl<T: NodePrimitives, ChainSpec: Send + Sync> HashedStateRangeReader
+    for MockEthProvider<T, ChainSpec>
+{
+    fn hashed_accounts_range(
+        &self,
+        _block_number: BlockNumber,
+        _start: B256,
+        _limit: usize,
+    ) -> ProviderResult<Vec<(B256, Account)>> {
+        Ok(Vec::default())
+    }
+
+    fn hashed_accounts_dump(
+        &self,
+        _block_number: BlockNumber,
+        _start: B256,
+        _limit: usize,
+        _storage_limit: Option<usize>,
+    ) -> ProviderResult<Vec<HashedAccountDump>> {
+        Ok(Vec::default())
+    }
+
+    fn hashed_accounts_range_proof(
+        &self,
+        _block_number: BlockNumber,
+        _start: B256,
+        _is_done: &mut dyn FnMut(&HashedAccountDump) -> bool,
+    ) -> ProviderResult<(Vec<HashedAccountDump>, MultiProof)> {
+        Ok(Default::default())
+    }
+
+    fn hashed_storage_range(
+        &self,
+        _block_number: BlockNumber,
+        _hashed_address: B256,
+        _start: B256,
+        _limit: usize,
+    ) -> ProviderResult<Vec<(B256, U256)>> {
+        Ok(Vec::default())
+    }
+
+    fn storage_preimages(
+        &self,
+        _address: Address,
+        _hashed_slots: &[B256],
+        _max_entries: usize,
+    ) -> ProviderResult<B256Map<B256>> {
+        Ok(B256Map::default())
+    }
+}
+
 impl<T: NodePrimitives, ChainSpec: Send + Sync> StateReader for MockEthProvider<T, ChainSpec> {
     type Receipt = Receipt;
 
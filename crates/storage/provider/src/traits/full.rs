@@ -1,8 +1,10 @@
 //! Helper provider traits to encapsulate all provider traits for simplicity.
 
 use crate::{
-    AccountReader, BlockReaderIdExt, ChainSpecProvider, ChangeSetReader, DatabaseProviderFactory,
-    StageCheckpointReader, StateProviderFactory, StaticFileProviderFactory,
+    AccountHistoryReader, AccountReader, BadBlockReader, BlockReaderIdExt, ChainSpecProvider,
+    ChangeSetReader, DatabaseProviderFactory, DiskUsageReader, HashedStateRangeReader,
+    PruneCheckpointReader, StageCheckpointReader, StateProviderFactory, StaticFileProviderFactory,
+    StaticFileRangeReader,
 };
 use reth_chain_state::{CanonStateSubscriptions, ForkChoiceSubscriptions};
 use reth_node_types::{BlockTy, HeaderTy, NodeTypesWithDB, ReceiptTy, TxTy};
@@ -20,12 +22,18 @@ pub trait FullProvider<N: NodeTypesWithDB>:
         Receipt = ReceiptTy<N>,
         Header = HeaderTy<N>,
     > + AccountReader
+    + BadBlockReader
     + StateProviderFactory
     + ChainSpecProvider<ChainSpec = N::ChainSpec>
     + ChangeSetReader
+    + AccountHistoryReader
+    + HashedStateRangeReader
     + CanonStateSubscriptions
     + ForkChoiceSubscriptions<Header = HeaderTy<N>>
     + StageCheckpointReader
+    + PruneCheckpointReader
+    + StaticFileRangeReader
+    + DiskUsageReader
     + Clone
     + Debug
     + Unpin
@@ -43,12 +51,18 @@ impl<T, N: NodeTypesWithDB> FullProvider<N> for T where
             Receipt = ReceiptTy<N>,
             Header = HeaderTy<N>,
         > + AccountReader
+        + BadBlockReader
         + StateProviderFactory
         + ChainSpecProvider<ChainSpec = N::ChainSpec>
         + ChangeSetReader
+        + AccountHistoryReader
+        + HashedStateRangeReader
         + CanonStateSubscriptions
         + ForkChoiceSubscriptions<Header = HeaderTy<N>>
         + StageCheckpointReader
+        + PruneCheckpointReader
+        + StaticFileRangeReader
+        + DiskUsageReader
         + Clone
         + Debug
         + Unpin
@@ -2,7 +2,7 @@ use alloc::{
     collections::{BTreeMap, BTreeSet},
     vec::Vec,
 };
-use alloy_primitives::{Address, BlockNumber};
+use alloy_primitives::{Address, BlockNumber, B256, U256};
 use auto_impl::auto_impl;
 use core::ops::{RangeBounds, RangeInclusive};
 use reth_db_models::AccountBeforeTx;
@@ -55,3 +55,53 @@ pub trait ChangeSetReader {
         block_number: BlockNumber,
     ) -> ProviderResult<Vec<AccountBeforeTx>>;
 }
+
+/// Account history reader
+#[auto_impl(&, Arc, Box)]
+pub trait AccountHistoryReader {
+    /// Returns the numbers of the blocks in the given range in which the account's info or any of
+    /// its storage slots changed, in ascending order.
+    ///
+    /// NOTE: Blocks for which history has been pruned are not included.
+    fn account_history_blocks(
+        &self,
+        address: Address,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<Vec<BlockNumber>>;
+
+    /// Returns the lowest block whose account and storage history is still available, i.e. the
+    /// block after the highest pruned block of either history segment, or zero if history has not
+    /// been pruned.
+    fn lowest_history_block(&self) -> ProviderResult<BlockNumber>;
+
+    /// Returns all accounts whose info or any of whose storage slots were changed by the blocks in
+    /// the given range, together with their state before the first block of the range.
+    fn modified_accounts(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<BTreeMap<Address, AccountStateBefore>>;
+}
+
+/// The state of an account before a range of blocks that changed it, as recorded by the
+/// changesets of the range.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AccountStateBefore {
+    /// The account info before the range, if the range changed it. The inner `None` means that
+    /// the account didn't exist.
+    pub info: Option<Option<Account>>,
+    /// The values of the storage slots changed by the range, before the range.
+    pub storage: BTreeMap<B256, U256>,
+}
+
+impl AccountStateBefore {
+    /// Records the account info before a block, unless an earlier block already recorded it.
+    pub fn record_info(&mut self, info: Option<Account>) {
+        self.info.get_or_insert(info);
+    }
+
+    /// Records the value of a storage slot before a block, unless an earlier block already
+    /// recorded it.
+    pub fn record_slot(&mut self, slot: B256, value: U256) {
+        self.storage.entry(slot).or_insert(value);
+    }
+}
@@ -6,7 +6,7 @@ use alloc::{sync::Arc, vec::Vec};
 use alloy_eips::{BlockHashOrNumber, BlockId, BlockNumberOrTag};
 use alloy_primitives::{BlockNumber, B256};
 use core::ops::RangeInclusive;
-use reth_primitives_traits::{RecoveredBlock, SealedHeader};
+use reth_primitives_traits::{RecoveredBlock, SealedBlock, SealedHeader};
 use reth_storage_errors::provider::ProviderResult;
 
 /// A helper enum that represents the origin of the requested block.
@@ -372,6 +372,19 @@ pub trait BlockReaderIdExt: BlockReader + ReceiptProviderIdExt {
     fn header_by_id(&self, id: BlockId) -> ProviderResult<Option<Self::Header>>;
 }
 
+/// Api trait for fetching blocks that were rejected because they failed validation.
+pub trait BadBlockReader: BlockReader {
+    /// Returns the recently rejected blocks, most recent first.
+    fn bad_blocks(&self) -> ProviderResult<Vec<SealedBlock<Self::Block>>>;
+
+    /// Returns the rejected block with the given hash.
+    ///
+    /// Returns `None` if no such block was recorded.
+    fn bad_block_by_hash(&self, hash: B256) -> ProviderResult<Option<SealedBlock<Self::Block>>> {
+        Ok(self.bad_blocks()?.into_iter().find(|block| block.hash() == hash))
+    }
+}
+
 /// Functionality to read the last known chain blocks from the database.
 pub trait ChainStateBlockReader: Send + Sync {
     /// Returns the last finalized block number.
@@ -44,6 +44,14 @@ pub trait DBProvider: Sized {
     /// Returns a reference to prune modes.
     fn prune_modes_ref(&self) -> &PruneModes;
 
+    /// Returns `true` if the state was downloaded by snap sync.
+    ///
+    /// Snap sync only downloads the hashed state, so the plain state only contains the accounts
+    /// and storage slots that were changed by the blocks executed after the snap sync pivot.
+    fn is_snap_synced(&self) -> ProviderResult<bool> {
+        Ok(false)
+    }
+
     /// Return full table as Vec
     fn table<T: Table>(&self) -> Result<Vec<KeyValue<T>>, DatabaseError>
     where
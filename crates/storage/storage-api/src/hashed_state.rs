@@ -0,0 +1,83 @@
+use alloc::vec::Vec;
+use alloy_primitives::{map::B256Map, Address, BlockNumber, B256, U256};
+use auto_impl::auto_impl;
+use reth_primitives_traits::Account;
+use reth_storage_errors::provider::ProviderResult;
+use reth_trie_common::MultiProof;
+
+/// An account of the hashed state together with its storage root and a page of its storage.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HashedAccountDump {
+    /// The hashed address of the account.
+    pub hashed_address: B256,
+    /// The account.
+    pub account: Account,
+    /// The root of the account's storage trie.
+    pub storage_root: B256,
+    /// The first non-zero storage slots of the account, ordered by their hashed slot, if storage
+    /// was requested.
+    pub storage: Option<Vec<(B256, U256)>>,
+}
+
+/// Reader for ordered ranges of the hashed state.
+#[auto_impl(&, Arc, Box)]
+pub trait HashedStateRangeReader {
+    /// Returns up to `limit` accounts of the state after the given block, ordered by their hashed
+    /// address, starting at the given hashed address.
+    fn hashed_accounts_range(
+        &self,
+        block_number: BlockNumber,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<Vec<(B256, Account)>>;
+
+    /// Returns up to `limit` accounts of the state after the given block, ordered by their hashed
+    /// address, starting at the given hashed address, together with their storage roots.
+    ///
+    /// If `storage_limit` is set, up to that many storage slots are returned for every account,
+    /// otherwise no storage is read.
+    fn hashed_accounts_dump(
+        &self,
+        block_number: BlockNumber,
+        start: B256,
+        limit: usize,
+        storage_limit: Option<usize>,
+    ) -> ProviderResult<Vec<HashedAccountDump>>;
+
+    /// Returns the accounts of the state after the given block, ordered by their hashed address,
+    /// starting at the given hashed address, together with their storage roots and the account
+    /// proofs of the range boundaries.
+    ///
+    /// Accounts are read until `is_done` returns `true` for the last read account or there are no
+    /// more accounts. The proofs are generated for the starting hashed address and the last
+    /// returned account. The state of the block is only reconstructed once for both.
+    fn hashed_accounts_range_proof(
+        &self,
+        block_number: BlockNumber,
+        start: B256,
+        is_done: &mut dyn FnMut(&HashedAccountDump) -> bool,
+    ) -> ProviderResult<(Vec<HashedAccountDump>, MultiProof)>;
+
+    /// Returns up to `limit` non-zero storage slots of the account with the given hashed address in
+    /// the state after the given block, ordered by their hashed slot, starting at the given hashed
+    /// slot.
+    fn hashed_storage_range(
+        &self,
+        block_number: BlockNumber,
+        hashed_address: B256,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<Vec<(B256, U256)>>;
+
+    /// Resolves the preimages of the given hashed storage slots of the account from the plain
+    /// storage state.
+    ///
+    /// At most `max_entries` entries of the plain storage state of the account are scanned. Slots
+    /// that are not found among them are omitted from the result.
+    fn storage_preimages(
+        &self,
+        address: Address,
+        hashed_slots: &[B256],
+        max_entries: usize,
+    ) -> ProviderResult<B256Map<B256>>;
+}
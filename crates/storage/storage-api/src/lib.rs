@@ -42,6 +42,9 @@ pub use receipts::*;
 mod stage_checkpoint;
 pub use stage_checkpoint::*;
 
+mod static_file;
+pub use static_file::*;
+
 mod state;
 pub use state::*;
 
@@ -54,6 +57,9 @@ pub use transactions::*;
 mod trie;
 pub use trie::*;
 
+mod hashed_state;
+pub use hashed_state::*;
+
 mod chain_info;
 pub use chain_info::*;
 
@@ -1,21 +1,23 @@
 //! Various noop implementations for traits.
 
 use crate::{
-    AccountReader, BlockBodyIndicesProvider, BlockHashReader, BlockIdReader, BlockNumReader,
-    BlockReader, BlockReaderIdExt, BlockSource, BytecodeReader, ChangeSetReader,
-    HashedPostStateProvider, HeaderProvider, NodePrimitivesProvider, PruneCheckpointReader,
-    ReceiptProvider, ReceiptProviderIdExt, StageCheckpointReader, StateProofProvider,
-    StateProvider, StateProviderBox, StateProviderFactory, StateRootProvider, StorageRootProvider,
-    TransactionVariant, TransactionsProvider,
+    AccountHistoryReader, AccountReader, AccountStateBefore, BadBlockReader,
+    BlockBodyIndicesProvider, BlockHashReader, BlockIdReader, BlockNumReader, BlockReader,
+    BlockReaderIdExt, BlockSource, BytecodeReader, ChangeSetReader, HashedAccountDump,
+    HashedPostStateProvider, HashedStateRangeReader, HeaderProvider, NodePrimitivesProvider,
+    PruneCheckpointReader, ReceiptProvider, ReceiptProviderIdExt, StageCheckpointReader,
+    StateProofProvider, StateProvider, StateProviderBox, StateProviderFactory, StateRootProvider,
+    StaticFileRangeReader, StorageRootProvider, TransactionVariant, TransactionsProvider,
 };
 
 #[cfg(feature = "db-api")]
-use crate::{DBProvider, DatabaseProviderFactory};
-use alloc::{boxed::Box, string::String, sync::Arc, vec::Vec};
+use crate::{DBProvider, DatabaseProviderFactory, DiskUsage, DiskUsageReader};
+use alloc::{boxed::Box, collections::BTreeMap, string::String, sync::Arc, vec::Vec};
 use alloy_consensus::transaction::TransactionMeta;
 use alloy_eips::{BlockHashOrNumber, BlockId, BlockNumberOrTag};
 use alloy_primitives::{
-    Address, BlockHash, BlockNumber, Bytes, StorageKey, StorageValue, TxHash, TxNumber, B256, U256,
+    map::B256Map, Address, BlockHash, BlockNumber, Bytes, StorageKey, StorageValue, TxHash,
+    TxNumber, B256, U256,
 };
 use core::{
     fmt::Debug,
@@ -27,11 +29,14 @@ use reth_chainspec::{ChainInfo, ChainSpecProvider, EthChainSpec, MAINNET};
 use reth_db_api::mock::{DatabaseMock, TxMock};
 use reth_db_models::{AccountBeforeTx, StoredBlockBodyIndices};
 use reth_ethereum_primitives::EthPrimitives;
-use reth_primitives_traits::{Account, Bytecode, NodePrimitives, RecoveredBlock, SealedHeader};
+use reth_primitives_traits::{
+    Account, Bytecode, NodePrimitives, RecoveredBlock, SealedBlock, SealedHeader,
+};
 #[cfg(feature = "db-api")]
 use reth_prune_types::PruneModes;
 use reth_prune_types::{PruneCheckpoint, PruneSegment};
 use reth_stages_types::{StageCheckpoint, StageId};
+use reth_static_file_types::StaticFileSegment;
 use reth_storage_errors::provider::{ProviderError, ProviderResult};
 use reth_trie_common::{
     updates::TrieUpdates, AccountProof, HashedPostState, HashedStorage, MultiProof,
@@ -178,6 +183,12 @@ impl<C: Send + Sync, N: NodePrimitives> BlockReaderIdExt for NoopProvider<C, N>
     }
 }
 
+impl<C: Send + Sync, N: NodePrimitives> BadBlockReader for NoopProvider<C, N> {
+    fn bad_blocks(&self) -> ProviderResult<Vec<SealedBlock<Self::Block>>> {
+        Ok(Vec::new())
+    }
+}
+
 impl<C: Send + Sync, N: NodePrimitives> BlockReader for NoopProvider<C, N> {
     type Block = N::Block;
 
@@ -396,6 +407,76 @@ impl<C: Send + Sync, N: NodePrimitives> ChangeSetReader for NoopProvider<C, N> {
     }
 }
 
+impl<C: Send + Sync, N: NodePrimitives> AccountHistoryReader for NoopProvider<C, N> {
+    fn account_history_blocks(
+        &self,
+        _address: Address,
+        _range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<Vec<BlockNumber>> {
+        Ok(Vec::default())
+    }
+
+    fn lowest_history_block(&self) -> ProviderResult<BlockNumber> {
+        Ok(0)
+    }
+
+    fn modified_accounts(
+        &self,
+        _range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<BTreeMap<Address, AccountStateBefore>> {
+        Ok(BTreeMap::default())
+    }
+}
+
+impl<C: Send + Sync, N: NodePrimitives> HashedStateRangeReader for NoopProvider<C, N> {
+    fn hashed_accounts_range(
+        &self,
+        _block_number: BlockNumber,
+        _start: B256,
+        _limit: usize,
+    ) -> ProviderResult<Vec<(B256, Account)>> {
+        Ok(Vec::default())
+    }
+
+    fn hashed_accounts_dump(
+        &self,
+        _block_number: BlockNumber,
+        _start: B256,
+        _limit: usize,
+        _storage_limit: Option<usize>,
+    ) -> ProviderResult<Vec<HashedAccountDump>> {
+        Ok(Vec::default())
+    }
+
+    fn hashed_accounts_range_proof(
+        &self,
+        _block_number: BlockNumber,
+        _start: B256,
+        _is_done: &mut dyn FnMut(&HashedAccountDump) -> bool,
+    ) -> ProviderResult<(Vec<HashedAccountDump>, MultiProof)> {
+        Ok(Default::default())
+    }
+
+    fn hashed_storage_range(
+        &self,
+        _block_number: BlockNumber,
+        _hashed_address: B256,
+        _start: B256,
+        _limit: usize,
+    ) -> ProviderResult<Vec<(B256, U256)>> {
+        Ok(Vec::default())
+    }
+
+    fn storage_preimages(
+        &self,
+        _address: Address,
+        _hashed_slots: &[B256],
+        _max_entries: usize,
+    ) -> ProviderResult<B256Map<B256>> {
+        Ok(B256Map::default())
+    }
+}
+
 impl<C: Send + Sync, N: NodePrimitives> StateRootProvider for NoopProvider<C, N> {
     fn state_root(&self, _state: HashedPostState) -> ProviderResult<B256> {
         Ok(B256::default())
@@ -574,6 +655,15 @@ impl<C: Send + Sync, N: NodePrimitives> PruneCheckpointReader for NoopProvider<C
     }
 }
 
+impl<C: Send + Sync, N: Send + Sync> StaticFileRangeReader for NoopProvider<C, N> {
+    fn static_file_block_range(
+        &self,
+        _segment: StaticFileSegment,
+    ) -> ProviderResult<Option<RangeInclusive<BlockNumber>>> {
+        Ok(None)
+    }
+}
+
 impl<C: Send + Sync, N: NodePrimitives> NodePrimitivesProvider for NoopProvider<C, N> {
     type Primitives = N;
 }
@@ -612,6 +702,13 @@ impl<ChainSpec: Send + Sync, N: NodePrimitives> DBProvider for NoopProvider<Chai
     }
 }
 
+#[cfg(feature = "db-api")]
+impl<C: Send + Sync, N: Send + Sync> DiskUsageReader for NoopProvider<C, N> {
+    fn disk_usage(&self) -> ProviderResult<DiskUsage> {
+        Ok(DiskUsage::default())
+    }
+}
+
 #[cfg(feature = "db-api")]
 impl<ChainSpec: Send + Sync, N: NodePrimitives> DatabaseProviderFactory
     for NoopProvider<ChainSpec, N>
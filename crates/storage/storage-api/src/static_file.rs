@@ -0,0 +1,15 @@
+use alloy_primitives::BlockNumber;
+use core::ops::RangeInclusive;
+use reth_static_file_types::StaticFileSegment;
+use reth_storage_errors::provider::ProviderResult;
+
+/// The trait for fetching the block ranges stored in static files.
+#[auto_impl::auto_impl(&, Arc)]
+pub trait StaticFileRangeReader: Send + Sync {
+    /// Returns the range of blocks stored in the static files of the segment, or `None` if there
+    /// are no static files for the segment.
+    fn static_file_block_range(
+        &self,
+        segment: StaticFileSegment,
+    ) -> ProviderResult<Option<RangeInclusive<BlockNumber>>>;
+}
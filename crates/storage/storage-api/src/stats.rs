@@ -7,3 +7,29 @@ pub trait StatsReader: Send + Sync {
     /// route to different data sources other than [Table].
     fn count_entries<T: Table>(&self) -> reth_storage_errors::provider::ProviderResult<usize>;
 }
+
+/// The trait for fetching the disk usage of the node data.
+#[auto_impl::auto_impl(&, Arc)]
+pub trait DiskUsageReader: Send + Sync {
+    /// Returns the number of bytes used by the database and the static files.
+    fn disk_usage(&self) -> reth_storage_errors::provider::ProviderResult<DiskUsage>;
+}
+
+/// Disk usage of the node data, in bytes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DiskUsage {
+    /// Size of the database pages in use.
+    ///
+    /// Pages freed by deletions are reused by the database, but never returned to the file
+    /// system, so this can be lower than the size of the database file.
+    pub database: u64,
+    /// Size of the static files.
+    pub static_files: u64,
+}
+
+impl DiskUsage {
+    /// Returns the total number of bytes used.
+    pub const fn total(&self) -> u64 {
+        self.database + self.static_files
+    }
+}
@@ -4,16 +4,26 @@
 //! applications. It allows for easily capturing and exporting distributed traces to compatible
 //! backends like Jaeger, Zipkin, or any other OpenTelemetry-compatible tracing system.
 
-use opentelemetry::{trace::TracerProvider, KeyValue, Value};
-use opentelemetry_otlp::SpanExporter;
+use opentelemetry::{
+    global,
+    propagation::{Extractor, TextMapPropagator},
+    trace::TracerProvider,
+    KeyValue, Value,
+};
+use opentelemetry_otlp::{SpanExporter, WithExportConfig};
 use opentelemetry_sdk::{
+    propagation::TraceContextPropagator,
     trace::{SdkTracer, SdkTracerProvider},
     Resource,
 };
 use opentelemetry_semantic_conventions::{attribute::SERVICE_VERSION, SCHEMA_URL};
 use tracing::Subscriber;
-use tracing_opentelemetry::OpenTelemetryLayer;
+use tracing_opentelemetry::{OpenTelemetryLayer, OpenTelemetrySpanExt};
 use tracing_subscriber::registry::LookupSpan;
+use url::Url;
+
+/// The default OTLP/HTTP endpoint of a collector running on the local machine.
+pub const DEFAULT_OTLP_ENDPOINT: &str = "http://localhost:4318/v1/traces";
 
 /// Creates a tracing [`OpenTelemetryLayer`] that exports spans to an OTLP endpoint.
 ///
@@ -36,3 +46,169 @@ where
     let tracer = provider.tracer("reth-otlp");
     tracing_opentelemetry::layer().with_tracer(tracer)
 }
+
+/// Creates a tracing [`OpenTelemetryLayer`] that exports spans to the given OTLP/HTTP endpoint.
+///
+/// The tracer provider and the W3C trace context propagator are also installed globally, so that
+/// [`set_parent`] can continue traces of remote callers.
+pub fn span_layer<S>(
+    service_name: impl Into<Value>,
+    endpoint: &Url,
+) -> eyre::Result<OpenTelemetryLayer<S, SdkTracer>>
+where
+    for<'span> S: Subscriber + LookupSpan<'span>,
+{
+    let provider = tracer_provider(service_name, endpoint)?;
+    global::set_tracer_provider(provider.clone());
+    global::set_text_map_propagator(TraceContextPropagator::new());
+
+    let tracer = provider.tracer("reth-otlp");
+    Ok(tracing_opentelemetry::layer().with_tracer(tracer))
+}
+
+/// Creates a tracer provider that exports batches of spans to the given OTLP/HTTP endpoint.
+pub fn tracer_provider(
+    service_name: impl Into<Value>,
+    endpoint: &Url,
+) -> eyre::Result<SdkTracerProvider> {
+    let exporter = SpanExporter::builder().with_http().with_endpoint(endpoint.as_str()).build()?;
+
+    let resource = Resource::builder()
+        .with_service_name(service_name)
+        .with_schema_url([KeyValue::new(SERVICE_VERSION, env!("CARGO_PKG_VERSION"))], SCHEMA_URL)
+        .build();
+
+    Ok(SdkTracerProvider::builder().with_resource(resource).with_batch_exporter(exporter).build())
+}
+
+/// Sets the parent of the span to the remote span of a W3C trace context.
+///
+/// `traceparent` and `tracestate` are the values of the equally named headers of an incoming
+/// request. Invalid trace contexts are ignored.
+pub fn set_parent(span: &tracing::Span, traceparent: &str, tracestate: Option<&str>) {
+    let carrier = TraceContextCarrier { traceparent, tracestate };
+    let cx = TraceContextPropagator::new().extract(&carrier);
+    span.set_parent(cx);
+}
+
+/// An [`Extractor`] for the W3C trace context headers.
+#[derive(Debug)]
+struct TraceContextCarrier<'a> {
+    traceparent: &'a str,
+    tracestate: Option<&'a str>,
+}
+
+impl Extractor for TraceContextCarrier<'_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        match key {
+            "traceparent" => Some(self.traceparent),
+            "tracestate" => self.tracestate,
+            _ => None,
+        }
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        let mut keys = vec!["traceparent"];
+        keys.extend(self.tracestate.map(|_| "tracestate"));
+        keys
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opentelemetry::trace::TraceContextExt;
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        sync::mpsc,
+        thread,
+    };
+    use tracing_subscriber::layer::SubscriberExt;
+
+    /// Spawns a stand-in for a collector that accepts a single OTLP/HTTP export and sends its
+    /// request path and body to the returned channel.
+    fn spawn_collector() -> (Url, mpsc::Receiver<(String, Vec<u8>)>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let endpoint =
+            format!("http://{}/v1/traces", listener.local_addr().unwrap()).parse().unwrap();
+
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            let path = request_line.split_whitespace().nth(1).unwrap().to_string();
+
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line.trim().is_empty() {
+                    break
+                }
+                if let Some((name, value)) = line.split_once(':') {
+                    if name.eq_ignore_ascii_case("content-length") {
+                        content_length = value.trim().parse().unwrap();
+                    }
+                }
+            }
+
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).unwrap();
+            reader
+                .get_mut()
+                .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\nconnection: close\r\n\r\n")
+                .unwrap();
+            tx.send((path, body)).unwrap();
+        });
+
+        (endpoint, rx)
+    }
+
+    #[test]
+    fn exports_spans_to_collector() {
+        let (endpoint, exports) = spawn_collector();
+        let provider = tracer_provider("reth-test", &endpoint).unwrap();
+        let subscriber = tracing_subscriber::registry()
+            .with(tracing_opentelemetry::layer().with_tracer(provider.tracer("reth-otlp")));
+
+        tracing::subscriber::with_default(subscriber, || {
+            let _span = tracing::info_span!("engine_newPayloadV4").entered();
+        });
+        provider.force_flush().unwrap();
+
+        let (path, body) = exports.recv().unwrap();
+        assert_eq!(path, "/v1/traces");
+        // the body is an encoded protobuf message that contains the span and service names
+        let contains = |needle: &[u8]| body.windows(needle.len()).any(|window| window == needle);
+        assert!(contains(b"engine_newPayloadV4"));
+        assert!(contains(b"reth-test"));
+    }
+
+    #[test]
+    fn continues_remote_trace() {
+        let provider = SdkTracerProvider::builder().build();
+        let subscriber = tracing_subscriber::registry()
+            .with(tracing_opentelemetry::layer().with_tracer(provider.tracer("reth-otlp")));
+
+        tracing::subscriber::with_default(subscriber, || {
+            let span = tracing::info_span!("rpc_call");
+            set_parent(&span, "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01", None);
+            let cx = span.context();
+            let span_context = cx.span().span_context().clone();
+            assert_eq!(span_context.trace_id().to_string(), "4bf92f3577b34da6a3ce929d0e0e4736");
+
+            // invalid trace contexts start a new trace
+            let span = tracing::info_span!("rpc_call");
+            set_parent(&span, "invalid", None);
+            let cx = span.context();
+            assert_ne!(
+                cx.span().span_context().trace_id().to_string(),
+                "4bf92f3577b34da6a3ce929d0e0e4736"
+            );
+        });
+    }
+}
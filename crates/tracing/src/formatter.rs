@@ -2,7 +2,7 @@ use crate::layers::BoxedLayer;
 use clap::ValueEnum;
 use std::{fmt, fmt::Display};
 use tracing_appender::non_blocking::NonBlocking;
-use tracing_subscriber::{EnvFilter, Layer, Registry};
+use tracing_subscriber::{reload, EnvFilter, Layer, Registry};
 
 /// Represents the logging format.
 ///
@@ -36,13 +36,14 @@ impl LogFormat {
     /// * `file_writer` - An optional `NonBlocking` writer for directing logs to a file.
     ///
     /// # Returns
-    /// A `BoxedLayer<Registry>` that can be added to a tracing subscriber.
+    /// A `BoxedLayer<Registry>` that can be added to a tracing subscriber, and a handle to replace
+    /// its filter at runtime.
     pub fn apply(
         &self,
         filter: EnvFilter,
         color: Option<String>,
         file_writer: Option<NonBlocking>,
-    ) -> BoxedLayer<Registry> {
+    ) -> (BoxedLayer<Registry>, reload::Handle<EnvFilter, Registry>) {
         let ansi = if let Some(color) = color {
             std::env::var("RUST_LOG_STYLE").map(|val| val != "never").unwrap_or(color != "never")
         } else {
@@ -56,7 +57,8 @@ impl LogFormat {
                 // level is higher than INFO (DEBUG, TRACE)
                 filter.max_level_hint().is_none_or(|max_level| max_level > tracing::Level::INFO));
 
-        match self {
+        let (filter, handle) = reload::Layer::new(filter);
+        let layer = match self {
             Self::Json => {
                 let layer =
                     tracing_subscriber::fmt::layer().json().with_ansi(ansi).with_target(target);
@@ -77,7 +79,8 @@ impl LogFormat {
                     layer.with_filter(filter).boxed()
                 }
             }
-        }
+        };
+        (layer, handle)
     }
 }
 
@@ -5,9 +5,12 @@ use std::{
 
 use rolling_file::{RollingConditionBasic, RollingFileAppender};
 use tracing_appender::non_blocking::WorkerGuard;
-use tracing_subscriber::{filter::Directive, EnvFilter, Layer, Registry};
+use tracing_subscriber::{filter::Directive, reload, EnvFilter, Layer, Registry};
 
-use crate::formatter::LogFormat;
+use crate::{
+    formatter::LogFormat,
+    log_filter::{LogFilterHandle, LogOutput},
+};
 
 /// A worker guard returned by the file layer.
 ///
@@ -37,6 +40,7 @@ const DEFAULT_ENV_FILTER_DIRECTIVES: [&str; 5] = [
 #[derive(Default)]
 pub struct Layers {
     inner: Vec<BoxedLayer<Registry>>,
+    filter_handle: LogFilterHandle,
 }
 
 impl fmt::Debug for Layers {
@@ -59,9 +63,10 @@ impl Layers {
         self.inner.push(layer.boxed());
     }
 
-    /// Consumes the `Layers` instance, returning the inner vector of layers.
-    pub(crate) fn into_inner(self) -> Vec<BoxedLayer<Registry>> {
-        self.inner
+    /// Consumes the `Layers` instance, returning the inner vector of layers and the handle to
+    /// change the filters of the stdout, file and journald layers.
+    pub(crate) fn into_inner(self) -> (Vec<BoxedLayer<Registry>>, LogFilterHandle) {
+        (self.inner, self.filter_handle)
     }
 
     /// Adds a journald layer to the layers collection.
@@ -73,8 +78,10 @@ impl Layers {
     /// An `eyre::Result<()>` indicating the success or failure of the operation.
     pub(crate) fn journald(&mut self, filter: &str) -> eyre::Result<()> {
         let journald_filter = build_env_filter(None, filter)?;
+        let (journald_filter, handle) = reload::Layer::new(journald_filter);
         let layer = tracing_journald::layer()?.with_filter(journald_filter);
         self.add_layer(layer);
+        self.filter_handle.register(LogOutput::Journald, handle, None, filter);
         Ok(())
     }
 
@@ -98,9 +105,10 @@ impl Layers {
         filters: &str,
         color: Option<String>,
     ) -> eyre::Result<()> {
-        let filter = build_env_filter(Some(default_directive), filters)?;
-        let layer = format.apply(filter, color, None);
+        let filter = build_env_filter(Some(default_directive.clone()), filters)?;
+        let (layer, handle) = format.apply(filter, color, None);
         self.add_layer(layer);
+        self.filter_handle.register(LogOutput::Stdout, handle, Some(default_directive), filters);
         Ok(())
     }
 
@@ -121,10 +129,27 @@ impl Layers {
     ) -> eyre::Result<FileWorkerGuard> {
         let (writer, guard) = file_info.create_log_writer();
         let file_filter = build_env_filter(None, filter)?;
-        let layer = format.apply(file_filter, None, Some(writer));
+        let (layer, handle) = format.apply(file_filter, None, Some(writer));
         self.add_layer(layer);
+        self.filter_handle.register(LogOutput::File, handle, None, filter);
         Ok(guard)
     }
+
+    /// Adds a layer that exports spans to an OTLP collector.
+    ///
+    /// # Arguments
+    /// * `otlp_info` - Information about the collector, and the filter for the exported spans.
+    ///
+    /// # Returns
+    /// An `eyre::Result<()>` indicating the success or failure of the operation.
+    #[cfg(feature = "otlp")]
+    pub(crate) fn otlp(&mut self, otlp_info: OtlpInfo) -> eyre::Result<()> {
+        let otlp_filter = build_env_filter(None, &otlp_info.filter)?;
+        let layer = reth_tracing_otlp::span_layer(otlp_info.service_name, &otlp_info.endpoint)?
+            .with_filter(otlp_filter);
+        self.add_layer(layer);
+        Ok(())
+    }
 }
 
 /// Holds configuration information for file logging.
@@ -174,6 +199,26 @@ impl FileInfo {
     }
 }
 
+/// Holds configuration information for exporting spans to an OTLP collector.
+///
+/// Contains the service name that identifies this node, the OTLP/HTTP endpoint of the collector,
+/// and the filter for the exported spans.
+#[cfg(feature = "otlp")]
+#[derive(Debug, Clone)]
+pub struct OtlpInfo {
+    service_name: String,
+    endpoint: url::Url,
+    filter: String,
+}
+
+#[cfg(feature = "otlp")]
+impl OtlpInfo {
+    /// Creates a new `OtlpInfo` instance.
+    pub const fn new(service_name: String, endpoint: url::Url, filter: String) -> Self {
+        Self { service_name, endpoint, filter }
+    }
+}
+
 /// Builds an environment filter for logging.
 ///
 /// The events are filtered by `default_directive`, unless overridden by `RUST_LOG`.
@@ -184,7 +229,7 @@ impl FileInfo {
 ///
 /// # Returns
 /// An `eyre::Result<EnvFilter>` that can be used to configure a tracing subscriber.
-fn build_env_filter(
+pub(crate) fn build_env_filter(
     default_directive: Option<Directive>,
     directives: &str,
 ) -> eyre::Result<EnvFilter> {
@@ -50,11 +50,17 @@ pub use tracing_subscriber;
 
 // Re-export our types
 pub use formatter::LogFormat;
+#[cfg(feature = "otlp")]
+pub use layers::OtlpInfo;
 pub use layers::{FileInfo, FileWorkerGuard, Layers};
+pub use log_filter::{log_filter_handle, LogFilterHandle, LogOutput};
+#[cfg(feature = "otlp")]
+pub use reth_tracing_otlp::DEFAULT_OTLP_ENDPOINT;
 pub use test_tracer::TestTracer;
 
 mod formatter;
 mod layers;
+mod log_filter;
 mod test_tracer;
 
 use tracing::level_filters::LevelFilter;
@@ -64,12 +70,15 @@ use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 ///  Tracer for application logging.
 ///
 ///  Manages the configuration and initialization of logging layers,
-/// including standard output, optional journald, and optional file logging.
+/// including standard output, optional journald, optional file logging, and optional export of
+/// spans to an OTLP collector.
 #[derive(Debug, Clone)]
 pub struct RethTracer {
     stdout: LayerInfo,
     journald: Option<String>,
     file: Option<(LayerInfo, FileInfo)>,
+    #[cfg(feature = "otlp")]
+    otlp: Option<OtlpInfo>,
 }
 
 impl RethTracer {
@@ -78,7 +87,13 @@ impl RethTracer {
     ///  Initializes with default stdout layer configuration.
     ///  Journald and file layers are not set by default.
     pub fn new() -> Self {
-        Self { stdout: LayerInfo::default(), journald: None, file: None }
+        Self {
+            stdout: LayerInfo::default(),
+            journald: None,
+            file: None,
+            #[cfg(feature = "otlp")]
+            otlp: None,
+        }
     }
 
     ///  Sets a custom configuration for the stdout layer.
@@ -108,6 +123,16 @@ impl RethTracer {
         self.file = Some((config, file_info));
         self
     }
+
+    ///  Sets the configuration for exporting spans to an OTLP collector.
+    ///
+    ///  # Arguments
+    ///  * `otlp_info` - The `OtlpInfo` containing the collector endpoint and span filter.
+    #[cfg(feature = "otlp")]
+    pub fn with_otlp(mut self, otlp_info: OtlpInfo) -> Self {
+        self.otlp = Some(otlp_info);
+        self
+    }
 }
 
 impl Default for RethTracer {
@@ -218,6 +243,11 @@ impl Tracer for RethTracer {
             layers.journald(&config)?;
         }
 
+        #[cfg(feature = "otlp")]
+        if let Some(otlp_info) = self.otlp {
+            layers.otlp(otlp_info)?;
+        }
+
         let file_guard = if let Some((config, file_info)) = self.file {
             Some(layers.file(config.format, &config.filters, file_info)?)
         } else {
@@ -226,11 +256,26 @@ impl Tracer for RethTracer {
 
         // The error is returned if the global default subscriber is already set,
         // so it's safe to ignore it
-        let _ = tracing_subscriber::registry().with(layers.into_inner()).try_init();
+        let (layers, filter_handle) = layers.into_inner();
+        if tracing_subscriber::registry().with(layers).try_init().is_ok() {
+            log_filter::set_log_filter_handle(filter_handle);
+        }
         Ok(file_guard)
     }
 }
 
+/// Continues the trace of a remote caller in the given span.
+///
+/// `traceparent` and `tracestate` are the W3C trace context headers of the caller's request.
+/// This is a no-op unless the `otlp` feature is enabled.
+#[cfg_attr(not(feature = "otlp"), expect(clippy::missing_const_for_fn))]
+pub fn set_remote_parent(span: &tracing::Span, traceparent: &str, tracestate: Option<&str>) {
+    #[cfg(feature = "otlp")]
+    reth_tracing_otlp::set_parent(span, traceparent, tracestate);
+    #[cfg(not(feature = "otlp"))]
+    let _ = (span, traceparent, tracestate);
+}
+
 ///  Initializes a tracing subscriber for tests.
 ///
 ///  The filter is configurable via `RUST_LOG`.
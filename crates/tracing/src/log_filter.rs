@@ -0,0 +1,302 @@
+use std::{
+    fmt,
+    str::FromStr,
+    sync::{Arc, Mutex, MutexGuard, OnceLock, PoisonError},
+};
+
+use tracing::level_filters::LevelFilter;
+use tracing_subscriber::{filter::Directive, reload, EnvFilter, Registry};
+
+use crate::layers::build_env_filter;
+
+/// The log filter handle of the global subscriber, set by [`RethTracer`](crate::RethTracer).
+static LOG_FILTER_HANDLE: OnceLock<LogFilterHandle> = OnceLock::new();
+
+/// Returns the handle to change the log filters of the global subscriber at runtime.
+///
+/// Returns `None` if the global subscriber wasn't installed by [`RethTracer`](crate::RethTracer).
+pub fn log_filter_handle() -> Option<&'static LogFilterHandle> {
+    LOG_FILTER_HANDLE.get()
+}
+
+/// Installs the handle returned by [`log_filter_handle`].
+pub(crate) fn set_log_filter_handle(handle: LogFilterHandle) {
+    let _ = LOG_FILTER_HANDLE.set(handle);
+}
+
+/// A log output whose filter can be changed at runtime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LogOutput {
+    /// The stdout layer.
+    Stdout,
+    /// The file layer.
+    File,
+    /// The journald layer.
+    Journald,
+}
+
+impl fmt::Display for LogOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Stdout => f.write_str("stdout"),
+            Self::File => f.write_str("file"),
+            Self::Journald => f.write_str("journald"),
+        }
+    }
+}
+
+impl FromStr for LogOutput {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "stdout" => Ok(Self::Stdout),
+            "file" => Ok(Self::File),
+            "journald" => Ok(Self::Journald),
+            _ => eyre::bail!("unknown log output {s}, expected stdout, file or journald"),
+        }
+    }
+}
+
+/// Handle to change the filters of the log outputs at runtime.
+///
+/// The filter of each output is built from three parts, in increasing order of precedence:
+/// 1. The default level, which can be replaced with [`LogFilterHandle::set_verbosity`].
+/// 2. The filter directives of the output, as passed to `--log.<output>.filter`, which can be
+///    replaced with [`LogFilterHandle::set_filter`].
+/// 3. Additional directives for all outputs, which can be set with
+///    [`LogFilterHandle::set_vmodule`].
+#[derive(Debug, Clone, Default)]
+pub struct LogFilterHandle {
+    inner: Arc<Mutex<LogFilterState>>,
+}
+
+impl LogFilterHandle {
+    /// Registers the reload handle of an output's filter.
+    pub(crate) fn register(
+        &self,
+        output: LogOutput,
+        handle: reload::Handle<EnvFilter, Registry>,
+        default_directive: Option<Directive>,
+        filters: &str,
+    ) {
+        self.lock().outputs.push(OutputFilter {
+            output,
+            handle,
+            default_directive,
+            filters: filters.to_string(),
+        });
+    }
+
+    /// Returns the outputs whose filters can be changed.
+    pub fn outputs(&self) -> Vec<LogOutput> {
+        self.lock().outputs.iter().map(|filter| filter.output).collect()
+    }
+
+    /// Replaces the filter directives of the given output, or of all outputs if `None`.
+    ///
+    /// The directives use the same syntax as `--log.stdout.filter`.
+    pub fn set_filter(&self, output: Option<LogOutput>, filters: &str) -> eyre::Result<()> {
+        let mut state = self.lock();
+        if let Some(output) = output {
+            if !state.outputs.iter().any(|filter| filter.output == output) {
+                eyre::bail!("log output {output} is not enabled")
+            }
+        }
+        state.update(|filter| {
+            if output.is_none_or(|output| output == filter.output) {
+                filter.filters = filters.to_string();
+            }
+        })
+    }
+
+    /// Sets the maximum level of all outputs, replacing their default level.
+    ///
+    /// Directives for specific targets still take precedence.
+    pub fn set_verbosity(&self, level: LevelFilter) -> eyre::Result<()> {
+        let mut state = self.lock();
+        let verbosity = state.verbosity.replace(level);
+        state.update(|_| {}).inspect_err(|_| state.verbosity = verbosity)
+    }
+
+    /// Sets additional filter directives for all outputs, replacing previously set ones.
+    ///
+    /// Besides the syntax of `--log.stdout.filter`, the directives can use geth's `vmodule` syntax
+    /// of file patterns, e.g. `eth/downloader/*=5` or `p2p/server.go=4`. Since log targets are
+    /// module paths rather than file names, a file pattern is translated to the target of the
+    /// module it names, `eth::downloader` and `p2p::server` in the examples above.
+    pub fn set_vmodule(&self, directives: &str) -> eyre::Result<()> {
+        let directives = vmodule_directives(directives)?;
+        let mut state = self.lock();
+        let vmodule = std::mem::replace(&mut state.vmodule, directives);
+        state.update(|_| {}).inspect_err(|_| state.vmodule = vmodule)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, LogFilterState> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Translates the file patterns of geth's `vmodule` syntax in the directives to log targets, see
+/// [`LogFilterHandle::set_vmodule`].
+///
+/// Directives without a path separator or `.go` suffix are kept as they are. Wildcards are only
+/// supported at the end of a pattern, since targets are matched by prefix.
+fn vmodule_directives(directives: &str) -> eyre::Result<String> {
+    directives
+        .split(',')
+        .map(|directive| {
+            let Some((pattern, level)) = directive.split_once('=') else {
+                return Ok(directive.to_string())
+            };
+            // span filters can contain path separators in field values
+            if pattern.contains('[') || (!pattern.contains('/') && !pattern.ends_with(".go")) {
+                return Ok(directive.to_string())
+            }
+
+            let target = pattern
+                .trim_end_matches(".go")
+                .trim_end_matches('*')
+                .trim_matches('/')
+                .replace('/', "::");
+            if target.is_empty() || target.contains('*') {
+                eyre::bail!("unsupported vmodule pattern {pattern}")
+            }
+            Ok(format!("{target}={level}"))
+        })
+        .collect::<eyre::Result<Vec<_>>>()
+        .map(|directives| directives.join(","))
+}
+
+/// The filter configuration of all outputs.
+#[derive(Debug, Default)]
+struct LogFilterState {
+    outputs: Vec<OutputFilter>,
+    /// The level that replaces the default level of all outputs
+    verbosity: Option<LevelFilter>,
+    /// Directives that are added to the filters of all outputs
+    vmodule: String,
+}
+
+impl LogFilterState {
+    /// Applies the change to the outputs and reloads their filters.
+    ///
+    /// The filters are only reloaded if all of them are valid, otherwise the change is discarded.
+    fn update(&mut self, change: impl Fn(&mut OutputFilter)) -> eyre::Result<()> {
+        let mut outputs = self.outputs.clone();
+        outputs.iter_mut().for_each(change);
+
+        let filters = outputs
+            .iter()
+            .map(|output| {
+                let default_directive = self
+                    .verbosity
+                    .map(Directive::from)
+                    .or_else(|| output.default_directive.clone());
+                let directives = [output.filters.as_str(), self.vmodule.as_str()].join(",");
+                build_env_filter(default_directive, &directives)
+            })
+            .collect::<eyre::Result<Vec<_>>>()?;
+
+        for (output, filter) in outputs.iter().zip(filters) {
+            output.handle.reload(filter)?;
+        }
+        self.outputs = outputs;
+        Ok(())
+    }
+}
+
+/// The filter configuration of an output.
+#[derive(Debug, Clone)]
+struct OutputFilter {
+    output: LogOutput,
+    handle: reload::Handle<EnvFilter, Registry>,
+    /// The default level the output was configured with
+    default_directive: Option<Directive>,
+    /// The current filter directives of the output
+    filters: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing::Level;
+    use tracing_subscriber::{layer::SubscriberExt, Layer};
+
+    /// Returns a subscriber that records whether an event was logged, and the handle to change its
+    /// filter.
+    fn subscriber(
+        filters: &str,
+    ) -> (impl tracing::Subscriber, LogFilterHandle, Arc<Mutex<Vec<Level>>>) {
+        let handle = LogFilterHandle::default();
+        let filter = build_env_filter(Some(LevelFilter::INFO.into()), filters).unwrap();
+        let (filter, reload_handle) = reload::Layer::new(filter);
+        handle.register(LogOutput::Stdout, reload_handle, Some(LevelFilter::INFO.into()), filters);
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let recorder = EventRecorder(events.clone()).with_filter(filter);
+        (tracing_subscriber::registry().with(recorder), handle, events)
+    }
+
+    struct EventRecorder(Arc<Mutex<Vec<Level>>>);
+
+    impl<S: tracing::Subscriber> tracing_subscriber::Layer<S> for EventRecorder {
+        fn on_event(
+            &self,
+            event: &tracing::Event<'_>,
+            _ctx: tracing_subscriber::layer::Context<'_, S>,
+        ) {
+            self.0.lock().unwrap().push(*event.metadata().level());
+        }
+    }
+
+    #[test]
+    fn reload_filters() {
+        let (subscriber, handle, events) = subscriber("");
+        let log = || {
+            events.lock().unwrap().clear();
+            tracing::debug!(target: "engine::tree", "debug");
+            tracing::info!(target: "net", "info");
+            std::mem::take(&mut *events.lock().unwrap())
+        };
+
+        tracing::subscriber::with_default(subscriber, || {
+            assert_eq!(log(), vec![Level::INFO]);
+
+            handle.set_filter(Some(LogOutput::Stdout), "engine=debug").unwrap();
+            assert_eq!(log(), vec![Level::DEBUG, Level::INFO]);
+
+            // vmodule directives take precedence over the output's filter
+            handle.set_vmodule("engine=info,net=off").unwrap();
+            assert!(log().is_empty());
+
+            handle.set_vmodule("").unwrap();
+            handle.set_verbosity(LevelFilter::WARN).unwrap();
+            assert_eq!(log(), vec![Level::DEBUG]);
+
+            // invalid filters are rejected and leave the current filter in place
+            assert!(handle.set_filter(None, "engine=loud").is_err());
+            assert!(handle.set_filter(Some(LogOutput::File), "engine=trace").is_err());
+            assert_eq!(log(), vec![Level::DEBUG]);
+        });
+    }
+
+    #[test]
+    fn translate_geth_vmodule() {
+        assert_eq!(
+            vmodule_directives("eth/downloader/*=5,p2p/server.go=4,engine::tree=debug").unwrap(),
+            "eth::downloader=5,p2p::server=4,engine::tree=debug"
+        );
+        assert_eq!(vmodule_directives("").unwrap(), "");
+        assert!(vmodule_directives("eth/*/queue.go=5").is_err());
+        assert!(vmodule_directives("/*=5").is_err());
+    }
+
+    #[test]
+    fn parse_log_output() {
+        for output in [LogOutput::Stdout, LogOutput::File, LogOutput::Journald] {
+            assert_eq!(output.to_string().parse::<LogOutput>().unwrap(), output);
+        }
+        assert!("stderr".parse::<LogOutput>().is_err());
+    }
+}
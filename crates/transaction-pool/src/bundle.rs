@@ -0,0 +1,376 @@
+//! Pool of bundles submitted for inclusion in locally built blocks.
+//!
+//! Bundles are submitted via `eth_sendBundle` or `mev_sendBundle` and are tried by the payload
+//! builder at the top of the block, see [`BundlePool::best_bundles`]. A bundle is only included if
+//! none of its transactions revert unless they are allowed to.
+//!
+//! Bundles are removed from the pool once they can no longer be included because their block
+//! range or time window has passed, see [`BundlePool::on_canonical_block`], or because they
+//! reverted while building a payload, see [`BundlePool::remove_bundles`].
+
+use crate::metrics::BundlePoolMetrics;
+use alloy_primitives::{keccak256, BlockNumber, B256};
+use parking_lot::RwLock;
+use reth_primitives_traits::{Recovered, SignedTransaction};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+    sync::Arc,
+};
+
+/// A bundle of transactions that must be included in order at the top of a block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bundle<T> {
+    /// The transactions of the bundle, in execution order.
+    pub transactions: Vec<BundleTransaction<T>>,
+    /// The first block the bundle can be included in.
+    pub block_number: BlockNumber,
+    /// The last block the bundle can be included in.
+    pub max_block_number: BlockNumber,
+    /// The minimum timestamp of the block the bundle can be included in.
+    pub min_timestamp: Option<u64>,
+    /// The maximum timestamp of the block the bundle can be included in.
+    pub max_timestamp: Option<u64>,
+    /// The identifier that can be used to replace or cancel the bundle.
+    pub replacement_uuid: Option<String>,
+}
+
+impl<T: SignedTransaction> Bundle<T> {
+    /// Returns the hash of the bundle.
+    ///
+    /// This is the keccak256 hash of the concatenated transaction hashes.
+    pub fn hash(&self) -> B256 {
+        let mut hashes = Vec::with_capacity(self.transactions.len() * 32);
+        for tx in &self.transactions {
+            hashes.extend_from_slice(tx.tx.tx_hash().as_slice());
+        }
+        keccak256(hashes)
+    }
+
+    /// Returns true if the bundle can be included in a block with the given number and timestamp.
+    pub fn is_includable(&self, block_number: BlockNumber, timestamp: u64) -> bool {
+        (self.block_number..=self.max_block_number).contains(&block_number) &&
+            self.min_timestamp.is_none_or(|min| timestamp >= min) &&
+            self.max_timestamp.is_none_or(|max| timestamp <= max)
+    }
+
+    /// Returns true if the bundle can't be included in any block after the given one.
+    pub fn is_expired(&self, block_number: BlockNumber, timestamp: u64) -> bool {
+        self.max_block_number <= block_number ||
+            self.max_timestamp.is_some_and(|max| max <= timestamp)
+    }
+}
+
+/// A transaction of a [`Bundle`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BundleTransaction<T> {
+    /// The signed transaction.
+    pub tx: Recovered<T>,
+    /// Whether the transaction is allowed to revert without invalidating the bundle.
+    pub can_revert: bool,
+    /// Whether the transaction can be left out if it is invalid, e.g. because it has already
+    /// been included.
+    pub can_drop: bool,
+}
+
+impl<T> BundleTransaction<T> {
+    /// Creates a new bundle transaction that must succeed.
+    pub const fn new(tx: Recovered<T>) -> Self {
+        Self { tx, can_revert: false, can_drop: false }
+    }
+}
+
+/// Errors returned when adding a bundle to the [`BundlePool`].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum BundlePoolError {
+    /// The bundle doesn't contain any transactions.
+    #[error("bundle contains no transactions")]
+    EmptyBundle,
+    /// The block range of the bundle is invalid.
+    #[error("invalid bundle block range {0}..={1}")]
+    InvalidBlockRange(BlockNumber, BlockNumber),
+    /// The bundle can't be included after the current tip anymore.
+    #[error("bundle expired at block {0}")]
+    Expired(BlockNumber),
+    /// The pool holds the maximum number of bundles.
+    #[error("bundle pool is full")]
+    PoolFull,
+}
+
+/// A shareable pool of [`Bundle`]s, keyed by bundle hash.
+pub struct BundlePool<T> {
+    inner: Arc<RwLock<BundlePoolInner<T>>>,
+    /// The maximum number of bundles in the pool.
+    max_bundles: usize,
+    metrics: BundlePoolMetrics,
+}
+
+impl<T: SignedTransaction> BundlePool<T> {
+    /// Creates a new pool that holds at most `max_bundles` bundles.
+    pub fn new(max_bundles: usize) -> Self {
+        Self { inner: Default::default(), max_bundles, metrics: Default::default() }
+    }
+
+    /// Adds a bundle to the pool and returns its hash.
+    ///
+    /// A bundle with the same replacement uuid as an existing bundle replaces it.
+    pub fn add_bundle(&self, bundle: Bundle<T>) -> Result<B256, BundlePoolError> {
+        if bundle.transactions.is_empty() {
+            return Err(BundlePoolError::EmptyBundle)
+        }
+        if bundle.max_block_number < bundle.block_number {
+            return Err(BundlePoolError::InvalidBlockRange(
+                bundle.block_number,
+                bundle.max_block_number,
+            ))
+        }
+
+        let hash = bundle.hash();
+        let mut inner = self.inner.write();
+        if let Some((tip_number, tip_timestamp)) = inner.tip {
+            if bundle.is_expired(tip_number, tip_timestamp) {
+                return Err(BundlePoolError::Expired(tip_number))
+            }
+        }
+
+        if let Some(uuid) = &bundle.replacement_uuid {
+            if let Some(replaced) = inner.by_uuid.get(uuid).copied() {
+                inner.remove(&replaced);
+            }
+        }
+        if !inner.by_hash.contains_key(&hash) && inner.by_hash.len() >= self.max_bundles {
+            return Err(BundlePoolError::PoolFull)
+        }
+        inner.insert(hash, bundle);
+        self.metrics.bundles.set(inner.by_hash.len() as f64);
+
+        Ok(hash)
+    }
+
+    /// Removes the bundle with the given replacement uuid, returns its hash if it was in the pool.
+    pub fn cancel_bundle(&self, replacement_uuid: &str) -> Option<B256> {
+        let mut inner = self.inner.write();
+        let hash = inner.by_uuid.get(replacement_uuid).copied()?;
+        inner.remove(&hash);
+        self.metrics.bundles.set(inner.by_hash.len() as f64);
+        Some(hash)
+    }
+
+    /// Removes the given bundles, e.g. because they reverted.
+    pub fn remove_bundles(&self, hashes: impl IntoIterator<Item = B256>) {
+        let mut inner = self.inner.write();
+        for hash in hashes {
+            if inner.remove(&hash).is_some() {
+                self.metrics.removed_bundles.increment(1);
+            }
+        }
+        self.metrics.bundles.set(inner.by_hash.len() as f64);
+    }
+
+    /// Returns the bundle with the given hash.
+    pub fn get(&self, hash: &B256) -> Option<Arc<Bundle<T>>> {
+        self.inner.read().by_hash.get(hash).map(|(_, bundle)| bundle.clone())
+    }
+
+    /// Returns the bundles that can be included in a block with the given number and timestamp,
+    /// in the order they were submitted.
+    pub fn best_bundles(
+        &self,
+        block_number: BlockNumber,
+        timestamp: u64,
+    ) -> Vec<(B256, Arc<Bundle<T>>)> {
+        let inner = self.inner.read();
+        inner
+            .by_id
+            .values()
+            .filter_map(|hash| {
+                let (_, bundle) = &inner.by_hash[hash];
+                bundle.is_includable(block_number, timestamp).then(|| (*hash, bundle.clone()))
+            })
+            .collect()
+    }
+
+    /// Removes all bundles that can't be included after the given canonical block.
+    pub fn on_canonical_block(&self, block_number: BlockNumber, timestamp: u64) {
+        let mut inner = self.inner.write();
+        inner.tip = Some((block_number, timestamp));
+
+        let expired = inner
+            .by_hash
+            .iter()
+            .filter(|(_, (_, bundle))| bundle.is_expired(block_number, timestamp))
+            .map(|(hash, _)| *hash)
+            .collect::<Vec<_>>();
+        for hash in &expired {
+            inner.remove(hash);
+        }
+        self.metrics.expired_bundles.increment(expired.len() as u64);
+        self.metrics.bundles.set(inner.by_hash.len() as f64);
+    }
+
+    /// Returns the number of bundles in the pool.
+    pub fn len(&self) -> usize {
+        self.inner.read().by_hash.len()
+    }
+
+    /// Returns true if the pool contains no bundles.
+    pub fn is_empty(&self) -> bool {
+        self.inner.read().by_hash.is_empty()
+    }
+}
+
+impl<T> Clone for BundlePool<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: Arc::clone(&self.inner),
+            max_bundles: self.max_bundles,
+            metrics: self.metrics.clone(),
+        }
+    }
+}
+
+impl<T> fmt::Debug for BundlePool<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BundlePool")
+            .field("bundles", &self.inner.read().by_hash.len())
+            .field("max_bundles", &self.max_bundles)
+            .finish()
+    }
+}
+
+/// The bundles of the [`BundlePool`].
+struct BundlePoolInner<T> {
+    /// Bundles by hash, with their submission id
+    by_hash: HashMap<B256, (u64, Arc<Bundle<T>>)>,
+    /// Bundle hashes by submission id
+    by_id: BTreeMap<u64, B256>,
+    /// Bundle hashes by replacement uuid
+    by_uuid: HashMap<String, B256>,
+    /// The next submission id
+    next_id: u64,
+    /// Number and timestamp of the current canonical tip
+    tip: Option<(BlockNumber, u64)>,
+}
+
+impl<T> BundlePoolInner<T> {
+    /// Inserts the bundle, replacing an existing bundle with the same hash.
+    fn insert(&mut self, hash: B256, bundle: Bundle<T>) {
+        self.remove(&hash);
+
+        let id = self.next_id;
+        self.next_id += 1;
+        if let Some(uuid) = &bundle.replacement_uuid {
+            self.by_uuid.insert(uuid.clone(), hash);
+        }
+        self.by_id.insert(id, hash);
+        self.by_hash.insert(hash, (id, Arc::new(bundle)));
+    }
+
+    /// Removes the bundle with the given hash.
+    fn remove(&mut self, hash: &B256) -> Option<Arc<Bundle<T>>> {
+        let (id, bundle) = self.by_hash.remove(hash)?;
+        self.by_id.remove(&id);
+        if let Some(uuid) = &bundle.replacement_uuid {
+            if self.by_uuid.get(uuid) == Some(hash) {
+                self.by_uuid.remove(uuid);
+            }
+        }
+        Some(bundle)
+    }
+}
+
+impl<T> Default for BundlePoolInner<T> {
+    fn default() -> Self {
+        Self {
+            by_hash: Default::default(),
+            by_id: Default::default(),
+            by_uuid: Default::default(),
+            next_id: 0,
+            tip: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_consensus::{Signed, TxLegacy};
+    use alloy_primitives::{Address, Signature};
+    use reth_ethereum_primitives::TransactionSigned;
+
+    fn bundle(
+        nonce: u64,
+        block_number: BlockNumber,
+        max_block_number: BlockNumber,
+    ) -> Bundle<TransactionSigned> {
+        let tx = Signed::new_unhashed(
+            TxLegacy { nonce, ..Default::default() },
+            Signature::test_signature(),
+        );
+        Bundle {
+            transactions: vec![BundleTransaction::new(Recovered::new_unchecked(
+                tx.into(),
+                Address::ZERO,
+            ))],
+            block_number,
+            max_block_number,
+            min_timestamp: None,
+            max_timestamp: None,
+            replacement_uuid: None,
+        }
+    }
+
+    #[test]
+    fn best_bundles_by_block() {
+        let pool = BundlePool::new(10);
+        let first = pool.add_bundle(bundle(0, 1, 1)).unwrap();
+        let second = pool.add_bundle(bundle(1, 1, 3)).unwrap();
+        let third = pool.add_bundle(bundle(2, 2, 2)).unwrap();
+
+        let best = |number| {
+            pool.best_bundles(number, 0).into_iter().map(|(hash, _)| hash).collect::<Vec<_>>()
+        };
+        assert_eq!(best(1), vec![first, second]);
+        assert_eq!(best(2), vec![second, third]);
+        assert!(best(4).is_empty());
+
+        pool.on_canonical_block(1, 0);
+        assert_eq!(pool.len(), 2);
+        assert!(pool.get(&first).is_none());
+
+        // bundles that can't be included anymore are rejected
+        assert_eq!(pool.add_bundle(bundle(3, 1, 1)), Err(BundlePoolError::Expired(1)));
+
+        pool.remove_bundles([second]);
+        assert_eq!(best(2), vec![third]);
+    }
+
+    #[test]
+    fn replace_and_cancel_bundles() {
+        let pool = BundlePool::new(2);
+        let mut first = bundle(0, 1, 1);
+        first.replacement_uuid = Some("uuid".to_string());
+        pool.add_bundle(first).unwrap();
+
+        let mut replacement = bundle(1, 1, 1);
+        replacement.replacement_uuid = Some("uuid".to_string());
+        let replacement = pool.add_bundle(replacement).unwrap();
+        assert_eq!(pool.len(), 1);
+
+        pool.add_bundle(bundle(2, 1, 1)).unwrap();
+        assert_eq!(pool.add_bundle(bundle(3, 1, 1)), Err(BundlePoolError::PoolFull));
+
+        assert_eq!(pool.cancel_bundle("uuid"), Some(replacement));
+        assert_eq!(pool.cancel_bundle("uuid"), None);
+        assert_eq!(pool.len(), 1);
+    }
+
+    #[test]
+    fn reject_invalid_bundles() {
+        let pool = BundlePool::new(2);
+        let mut empty = bundle(0, 1, 1);
+        empty.transactions.clear();
+        assert_eq!(pool.add_bundle(empty), Err(BundlePoolError::EmptyBundle));
+        assert_eq!(pool.add_bundle(bundle(0, 2, 1)), Err(BundlePoolError::InvalidBlockRange(2, 1)));
+    }
+}
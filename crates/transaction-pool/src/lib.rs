@@ -291,7 +291,7 @@ pub use crate::{
         TransactionValidator, ValidPoolTransaction,
     },
 };
-use crate::{identifier::TransactionId, pool::PoolInner};
+use crate::{bundle::BundlePool, identifier::TransactionId, pool::PoolInner};
 use alloy_eips::{
     eip4844::{BlobAndProofV1, BlobAndProofV2},
     eip7594::BlobTransactionSidecarVariant,
@@ -315,6 +315,7 @@ pub mod pool;
 pub mod validate;
 
 pub mod blobstore;
+pub mod bundle;
 mod config;
 pub mod identifier;
 mod ordering;
@@ -715,6 +716,10 @@ where
         self.pool.unique_senders()
     }
 
+    fn bundle_pool(&self) -> Option<&BundlePool<PoolConsensusTx<Self>>> {
+        self.pool.bundle_pool()
+    }
+
     fn get_blob(
         &self,
         tx_hash: TxHash,
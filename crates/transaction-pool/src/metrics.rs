@@ -76,6 +76,18 @@ pub struct BlobStoreMetrics {
     pub(crate) blobstore_entries: Gauge,
 }
 
+/// Transaction pool bundle metrics
+#[derive(Metrics, Clone)]
+#[metrics(scope = "transaction_pool")]
+pub struct BundlePoolMetrics {
+    /// How many bundles are currently in the bundle pool
+    pub(crate) bundles: Gauge,
+    /// Number of bundles removed because they can no longer be included
+    pub(crate) expired_bundles: Counter,
+    /// Number of bundles removed because they reverted
+    pub(crate) removed_bundles: Counter,
+}
+
 /// Transaction pool maintenance metrics
 #[derive(Metrics)]
 #[metrics(scope = "transaction_pool")]
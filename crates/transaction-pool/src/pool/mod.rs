@@ -67,6 +67,7 @@
 
 use crate::{
     blobstore::BlobStore,
+    bundle::BundlePool,
     error::{PoolError, PoolErrorKind, PoolResult},
     identifier::{SenderId, SenderIdentifiers, TransactionId},
     metrics::BlobStoreMetrics,
@@ -151,6 +152,8 @@ where
     blob_transaction_sidecar_listener: Mutex<Vec<BlobTransactionSidecarListener>>,
     /// Metrics for the blob store
     blob_store_metrics: BlobStoreMetrics,
+    /// Bundles submitted for local block building, if enabled.
+    bundle_pool: Option<BundlePool<<T::Transaction as PoolTransaction>::Consensus>>,
 }
 
 // === impl PoolInner ===
@@ -171,6 +174,7 @@ where
             pending_transaction_listener: Default::default(),
             transaction_listener: Default::default(),
             blob_transaction_sidecar_listener: Default::default(),
+            bundle_pool: (config.max_bundles > 0).then(|| BundlePool::new(config.max_bundles)),
             config,
             blob_store,
             blob_store_metrics: Default::default(),
@@ -182,6 +186,13 @@ where
         &self.blob_store
     }
 
+    /// Returns the bundle pool, if enabled.
+    pub const fn bundle_pool(
+        &self,
+    ) -> Option<&BundlePool<<T::Transaction as PoolTransaction>::Consensus>> {
+        self.bundle_pool.as_ref()
+    }
+
     /// Returns stats about the size of the pool.
     pub fn size(&self) -> PoolSize {
         self.get_pool_data().size()
@@ -389,6 +400,9 @@ where
         trace!(target: "txpool", ?update, "updating pool on canonical state change");
 
         let block_info = update.block_info();
+        if let Some(bundle_pool) = &self.bundle_pool {
+            bundle_pool.on_canonical_block(update.number(), update.timestamp());
+        }
         let CanonicalStateUpdate {
             new_tip, changed_accounts, mined_transactions, update_kind, ..
         } = update;
@@ -52,6 +52,7 @@
 
 use crate::{
     blobstore::BlobStoreError,
+    bundle::BundlePool,
     error::{InvalidPoolTransactionError, PoolError, PoolResult},
     pool::{
         state::SubPool, BestTransactionFilter, NewTransactionEvent, TransactionEvents,
@@ -559,6 +560,11 @@ pub trait TransactionPool: Clone + Debug + Send + Sync {
     /// Returns a set of all senders of transactions in the pool
     fn unique_senders(&self) -> HashSet<Address>;
 
+    /// Returns the pool of bundles submitted for local block building, if enabled.
+    fn bundle_pool(&self) -> Option<&BundlePool<PoolConsensusTx<Self>>> {
+        None
+    }
+
     /// Returns the [`BlobTransactionSidecarVariant`] for the given transaction hash if it exists in
     /// the blob store.
     fn get_blob(
@@ -383,6 +383,10 @@ export const sidebar: SidebarItem[] = [
                             {
                                 text: "reth db path",
                                 link: "/cli/reth/db/path"
+                            },
+                            {
+                                text: "reth db verify",
+                                link: "/cli/reth/db/verify"
                             }
                         ]
                     },
@@ -390,6 +394,17 @@ export const sidebar: SidebarItem[] = [
                         text: "reth download",
                         link: "/cli/reth/download"
                     },
+                    {
+                        text: "reth snapshot",
+                        link: "/cli/reth/snapshot",
+                        collapsed: true,
+                        items: [
+                            {
+                                text: "reth snapshot create",
+                                link: "/cli/reth/snapshot/create"
+                            }
+                        ]
+                    },
                     {
                         text: "reth stage",
                         link: "/cli/reth/stage",